@@ -2,6 +2,7 @@ use std::fs;
 use std::path::PathBuf;
 
 use hydra_core::adapter::{AdapterTier, CapabilityConfidence, DetectStatus};
+use hydra_core::diagnostics::Severity;
 
 fn main() {
     if let Err(err) = generate_frontend_types() {
@@ -9,6 +10,7 @@ fn main() {
     }
 
     println!("cargo:rerun-if-changed=../hydra-core/src/adapter/types.rs");
+    println!("cargo:rerun-if-changed=../hydra-core/src/diagnostics.rs");
     println!("cargo:rerun-if-changed=src/ipc_types.rs");
     tauri_build::build();
 }
@@ -40,6 +42,11 @@ fn generate_frontend_types() -> Result<(), Box<dyn std::error::Error>> {
     .map(enum_literal)
     .collect::<Vec<_>>();
 
+    let severities = [Severity::Info, Severity::Warning, Severity::Error]
+        .iter()
+        .map(enum_literal)
+        .collect::<Vec<_>>();
+
     let check_statuses = ["passed", "failed", "warning", "running"]
         .iter()
         .map(|v| format!("\"{v}\""))
@@ -59,11 +66,15 @@ export const CAPABILITY_CONFIDENCE_VALUES = [{confidence_levels}] as const;\n\
 export type CapabilityConfidence = typeof CAPABILITY_CONFIDENCE_VALUES[number];\n\
 \n\
 export const CHECK_STATUS_VALUES = [{check_statuses}] as const;\n\
-export type CheckStatus = typeof CHECK_STATUS_VALUES[number];\n",
+export type CheckStatus = typeof CHECK_STATUS_VALUES[number];\n\
+\n\
+export const SEVERITY_VALUES = [{severities}] as const;\n\
+export type Severity = typeof SEVERITY_VALUES[number];\n",
         adapter_tiers = adapter_tiers.join(", "),
         detect_statuses = detect_statuses.join(", "),
         confidence_levels = confidence_levels.join(", "),
         check_statuses = check_statuses.join(", "),
+        severities = severities.join(", "),
     );
 
     write_if_changed(out_dir.join("rust-enums.ts"), &generated)?;
@@ -5,6 +5,7 @@ import { CockpitCenter } from './components/CockpitCenter';
 import { LeaderboardRail } from './components/LeaderboardRail';
 import { PreflightDashboard } from './components/PreflightDashboard';
 import { ExperimentalAdapterModal } from './components/ExperimentalAdapterModal';
+import { ApprovalRequestModal, pendingApprovals } from './components/ApprovalRequestModal';
 import { ResultsScoreboard } from './components/ResultsScoreboard';
 import { CandidateDiffReview } from './components/CandidateDiffReview';
 import {
@@ -13,7 +14,15 @@ import {
 } from './components/InteractiveWorkspace';
 import { FileExplorer } from './components/FileExplorer';
 import { Card } from './components/design-system';
-import { getRaceResult, listAdapters, pollRaceEvents, startRace } from './ipc';
+import {
+  cancelRace,
+  getRaceResult,
+  listAdapters,
+  pollRaceEvents,
+  resolveRaceApproval,
+  startRace,
+} from './ipc';
+import { confirmWorkspaceTrust, withWorkspaceTrust } from './workspaceTrust';
 import type { AdapterInfo, RaceResult } from './types';
 import { isExperimental, isTier1 } from './types';
 import { useEventBuffer, useAgentStatuses } from './hooks';
@@ -216,6 +225,13 @@ export default function App() {
   const [orchestrationSelectionRequestId, setOrchestrationSelectionRequestId] = useState<string | null>(null);
 
   const agentStatuses = useAgentStatuses(events, raceAgents, runStatus);
+  // Answered requests stay hidden until the resolution event arrives.
+  const [answeredApprovals, setAnsweredApprovals] = useState<ReadonlySet<string>>(new Set());
+  const approvals = useMemo(
+    () => pendingApprovals(events).filter((a) => !answeredApprovals.has(a.approvalId)),
+    [events, answeredApprovals],
+  );
+  const [approvalBusy, setApprovalBusy] = useState(false);
 
   useEffect(() => {
     let cancelled = false;
@@ -329,12 +345,16 @@ export default function App() {
     clear();
 
     try {
-      const started = await startRace({
-        taskPrompt,
-        agents: selectedAdapters,
-        allowExperimental: selectedExperimentalCount > 0,
-        cwd: workspaceCwd,
-      });
+      const started = await withWorkspaceTrust(
+        workspaceCwd,
+        () => startRace({
+          taskPrompt,
+          agents: selectedAdapters,
+          allowExperimental: selectedExperimentalCount > 0,
+          cwd: workspaceCwd,
+        }),
+        confirmWorkspaceTrust,
+      );
       setActiveRunId(started.runId);
       setRaceAgents(started.agents);
       setSelectedAgent(started.agents[0] ?? null);
@@ -346,6 +366,30 @@ export default function App() {
     }
   }, [clear, selectedAdapters, selectedExperimentalCount, taskPrompt, workspaceCwd]);
 
+  const handleCancelRace = useCallback(async () => {
+    if (!activeRunId) return;
+    try {
+      const result = await cancelRace(activeRunId);
+      setRunStatus(result.status);
+    } catch (err) {
+      setRaceError(err instanceof Error ? err.message : String(err));
+    }
+  }, [activeRunId]);
+
+  const handleApprovalDecision = useCallback(async (approve: boolean) => {
+    const approval = approvals[0];
+    if (!activeRunId || !approval) return;
+    setApprovalBusy(true);
+    try {
+      await resolveRaceApproval(activeRunId, approval.approvalId, approve);
+      setAnsweredApprovals((prev) => new Set(prev).add(approval.approvalId));
+    } catch (err) {
+      setRaceError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setApprovalBusy(false);
+    }
+  }, [activeRunId, approvals]);
+
   const handleWinnerSelect = useCallback((agentKey: string) => {
     setSelectedWinner(agentKey);
   }, []);
@@ -410,7 +454,7 @@ export default function App() {
     async function poll() {
       if (cancelled) return;
       try {
-        const batch = await pollRaceEvents(runId, cursor);
+        const batch = await pollRaceEvents(runId, cursor, { coalesce: true, gzip: true });
         cursor = batch.nextCursor;
 
         for (const evt of batch.events) {
@@ -421,7 +465,8 @@ export default function App() {
           setRaceError(batch.error);
         }
 
-        setRunStatus(batch.status);
+        // Keep the local "cancelling" state until the backend reports the stop.
+        setRunStatus((prev) => (prev === 'cancelling' && batch.status === 'running' ? prev : batch.status));
 
         if (batch.done) {
           const result = await getRaceResult(runId);
@@ -568,6 +613,7 @@ export default function App() {
             onRaceWorkspaceChange={handleRaceWorkspacePathChange}
             onRaceWorkspaceReset={handleRaceWorkspaceReset}
             onStartRace={handleStartRace}
+            onCancelRace={handleCancelRace}
             runStatus={runStatus}
             raceError={raceError}
             events={events}
@@ -686,6 +732,11 @@ export default function App() {
         onConfirm={handleExperimentalConfirm}
         adapter={experimentalModal.adapter}
       />
+      <ApprovalRequestModal
+        approval={approvals[0] ?? null}
+        busy={approvalBusy}
+        onDecide={handleApprovalDecision}
+      />
     </>
   );
 }
@@ -99,13 +99,14 @@ const MOCK_PREFLIGHT: PreflightResult = {
   totalCount: 4,
   healthScore: 100,
   checks: [
-    { name: 'Git Repository', description: 'Working inside a valid git repository', status: 'passed', evidence: null },
-    { name: 'Environment Variables Check', description: 'Found system configuration', status: 'passed', evidence: null },
-    { name: 'Validating Adapters', description: '2/2 tier-1 adapters ready', status: 'passed', evidence: 'Connected to 2 adapter(s)' },
-    { name: 'Working Tree Cleanliness', description: 'Working tree is clean', status: 'passed', evidence: null },
+    { id: 'git.repo', name: 'Git Repository', description: 'Working inside a valid git repository', severity: 'error', status: 'passed', evidence: null, remediation: null },
+    { id: 'env.home', name: 'Environment Variables Check', description: 'Found system configuration', severity: 'warning', status: 'passed', evidence: null, remediation: null },
+    { id: 'adapters.tier1', name: 'Validating Adapters', description: '2/2 tier-1 adapters ready', severity: 'error', status: 'passed', evidence: 'Connected to 2 adapter(s)', remediation: null },
+    { id: 'git.clean_tree', name: 'Working Tree Cleanliness', description: 'Working tree is clean', severity: 'info', status: 'passed', evidence: null, remediation: null },
   ],
   adapters: MOCK_ADAPTERS,
   warnings: [],
+  pings: [],
 };
 
 const MOCK_RACE_RESULT: RaceResult = {
@@ -155,6 +156,8 @@ const MOCK_DIFF: CandidateDiffPayload = {
   diffAvailable: true,
   source: 'artifact',
   warning: null,
+  notes: null,
+  commits: null,
 };
 
 const MOCK_CODEX_DIFF: CandidateDiffPayload = {
@@ -446,6 +449,21 @@ describe('Smoke Test 2: Preflight refresh triggers IPC and updates state', () =>
       expect(ipc.runPreflight).toHaveBeenCalledTimes(2);
     });
   });
+
+  it('deep check action requests a deep preflight', async () => {
+    const user = userEvent.setup();
+    render(<App />);
+    await user.click(screen.getByTestId('nav-preflight'));
+    await waitFor(() => {
+      expect(ipc.runPreflight).toHaveBeenCalledTimes(1);
+    });
+
+    fireEvent.click(await screen.findByText(/deep check/i));
+
+    await waitFor(() => {
+      expect(ipc.runPreflight).toHaveBeenLastCalledWith(true);
+    });
+  });
 });
 
 describe('Smoke Test 3: Experimental adapter modal blocks confirm until acknowledgment', () => {
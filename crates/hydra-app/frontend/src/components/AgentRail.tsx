@@ -132,7 +132,13 @@ function AgentRailItem({
           {isRunning && <PulsingDot />}
           <span style={nameStyle}>{agent.agentKey}</span>
         </div>
-        <div style={metaStyle}>{agent.eventCount} events</div>
+        <div style={metaStyle}>
+          {isRunning && agent.progress
+            ? `${agent.progress.percent}% · ${agent.progress.phase} · `
+            : ''}
+          {agent.filesEdited > 0 ? `${agent.filesEdited} files · ` : ''}
+          {agent.eventCount} events
+        </div>
       </div>
       <Badge variant={lifecycleBadgeVariant[agent.lifecycle]} dot>
         {lifecycleLabel[agent.lifecycle]}
@@ -0,0 +1,85 @@
+import { Modal, Button, Badge } from './design-system';
+import type { AgentStreamEvent, DangerousAction, PendingApproval } from '../types';
+
+interface ApprovalRequestModalProps {
+  approval: PendingApproval | null;
+  busy: boolean;
+  onDecide: (approve: boolean) => void;
+}
+
+/**
+ * Approval requests still waiting for an answer, oldest first. A request
+ * drops out once it is resolved or its agent finishes.
+ */
+export function pendingApprovals(events: AgentStreamEvent[]): PendingApproval[] {
+  const pending = new Map<string, PendingApproval>();
+  for (const evt of events) {
+    const data = (evt.data ?? {}) as { approval_id?: string; action?: DangerousAction };
+    if (evt.eventType === 'approval_requested' && data.approval_id && data.action) {
+      pending.set(data.approval_id, {
+        agentKey: evt.agentKey,
+        approvalId: data.approval_id,
+        action: data.action,
+      });
+    } else if (evt.eventType === 'approval_resolved' && data.approval_id) {
+      pending.delete(data.approval_id);
+    } else if (evt.eventType === 'agent_completed' || evt.eventType === 'agent_failed') {
+      for (const [id, approval] of pending) {
+        if (approval.agentKey === evt.agentKey) pending.delete(id);
+      }
+    }
+  }
+  return Array.from(pending.values());
+}
+
+function describe(action: DangerousAction): { label: string; detail: string } {
+  return action.kind === 'shell_command'
+    ? { label: 'Run shell command', detail: action.command }
+    : { label: 'Delete files', detail: action.path };
+}
+
+export function ApprovalRequestModal({ approval, busy, onDecide }: ApprovalRequestModalProps) {
+  if (!approval) return null;
+  const { label, detail } = describe(approval.action);
+
+  return (
+    <Modal
+      open
+      onClose={() => onDecide(false)}
+      width="520px"
+      title="Approval required"
+      footer={
+        <>
+          <Button variant="secondary" disabled={busy} onClick={() => onDecide(false)}>
+            Deny and stop agent
+          </Button>
+          <Button variant="primary" disabled={busy} onClick={() => onDecide(true)}>
+            Approve
+          </Button>
+        </>
+      }
+    >
+      <div style={{ display: 'flex', gap: 'var(--space-2)', marginBottom: 'var(--space-3)' }}>
+        <Badge variant="warning">Paused</Badge>
+        <Badge variant="neutral">{approval.agentKey}</Badge>
+      </div>
+      <p style={{ color: 'var(--color-text-muted)', fontSize: 'var(--text-sm)', marginBottom: 'var(--space-3)' }}>
+        {label}:
+      </p>
+      <pre
+        style={{
+          fontFamily: 'var(--font-mono)',
+          fontSize: 'var(--text-sm)',
+          padding: 'var(--space-3)',
+          borderRadius: 'var(--radius-md)',
+          border: '1px solid var(--color-border-700)',
+          whiteSpace: 'pre-wrap',
+          wordBreak: 'break-all',
+          margin: 0,
+        }}
+      >
+        {detail}
+      </pre>
+    </Modal>
+  );
+}
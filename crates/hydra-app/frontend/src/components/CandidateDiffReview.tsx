@@ -2,6 +2,7 @@ import { useState, useEffect, useCallback, useMemo } from 'react';
 import type { CSSProperties } from 'react';
 import type {
   AgentResult,
+  CandidateCommits,
   CandidateDiffPayload,
   MergePreviewPayload,
   MergeExecutionPayload,
@@ -271,6 +272,10 @@ export function CandidateDiffReview({ runId, agents, selectedWinner, workspaceCw
                 {diffPayload.warning}
               </div>
             )}
+            {diffPayload.notes && <AgentNotesPanel notes={diffPayload.notes} />}
+            {diffPayload.commits && diffPayload.commits.commits.length > 0 && (
+              <AgentCommitsPanel commits={diffPayload.commits} />
+            )}
             <DiffViewerPane diffText={diffPayload.diffText} />
           </div>
           <div style={{ width: 280, flexShrink: 0, display: 'flex', flexDirection: 'column', gap: 'var(--space-4)' }}>
@@ -718,6 +723,52 @@ function placeholderCellStyle(base: CSSProperties, isPlaceholder: boolean): CSSP
   };
 }
 
+function AgentNotesPanel({ notes }: { notes: string }) {
+  return (
+    <Panel title="Agent Notes">
+      <div
+        style={{
+          whiteSpace: 'pre-wrap',
+          fontSize: 'var(--text-sm)',
+          color: 'var(--color-text-secondary)',
+          maxHeight: 240,
+          overflowY: 'auto',
+        }}
+        data-testid="agent-notes"
+      >
+        {notes}
+      </div>
+    </Panel>
+  );
+}
+
+function AgentCommitsPanel({ commits }: { commits: CandidateCommits }) {
+  return (
+    <Panel
+      title="Agent Commits"
+      headerRight={
+        commits.strategy === 'mixed' ? (
+          <Badge variant="warning">plus uncommitted changes</Badge>
+        ) : (
+          <Badge variant="neutral">{commits.commits.length}</Badge>
+        )
+      }
+    >
+      <div
+        style={{ display: 'flex', flexDirection: 'column', gap: 'var(--space-1)', fontSize: 'var(--text-sm)' }}
+        data-testid="agent-commits"
+      >
+        {commits.commits.map((commit) => (
+          <div key={commit.sha} style={{ display: 'flex', gap: 'var(--space-2)' }}>
+            <code style={{ color: 'var(--color-text-muted)' }}>{commit.sha.slice(0, 7)}</code>
+            <span style={{ color: 'var(--color-text-secondary)' }}>{commit.subject}</span>
+          </div>
+        ))}
+      </div>
+    </Panel>
+  );
+}
+
 function ModifiedFilesList({ files }: { files: DiffFile[] }) {
   return (
     <Panel title="Modified Files" headerRight={<Badge variant="neutral">{files.length}</Badge>}>
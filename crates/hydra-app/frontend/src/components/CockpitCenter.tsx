@@ -18,6 +18,7 @@ interface CockpitCenterProps {
   onRaceWorkspaceChange: (v: string) => void;
   onRaceWorkspaceReset: () => void;
   onStartRace: () => void;
+  onCancelRace: () => void;
   runStatus: string;
   raceError: string | null;
   events: AgentStreamEvent[];
@@ -44,6 +45,7 @@ export function CockpitCenter({
   onRaceWorkspaceChange,
   onRaceWorkspaceReset,
   onStartRace,
+  onCancelRace,
   runStatus,
   raceError,
   events,
@@ -60,14 +62,15 @@ export function CockpitCenter({
   const isIdle = runStatus === 'idle';
   const isRunning = runStatus === 'running';
   const isStarting = runStatus === 'starting';
-  const isFailed = runStatus === 'failed';
+  const isCancelling = runStatus === 'cancelling';
+  const isFailed = runStatus === 'failed' || runStatus === 'cancelled';
   const isCompleted = runStatus === 'completed';
 
   const selectedAgentStatus = agentStatuses.find((a) => a.agentKey === selectedAgent) ?? null;
   const selectedAgentLifecycle = selectedAgentStatus?.lifecycle ?? null;
 
   const showConfig = isIdle || isFailed;
-  const showTerminal = isRunning || isStarting;
+  const showTerminal = isRunning || isStarting || isCancelling;
   const showCompletion = isCompleted && raceResult !== null;
 
   const containerStyle: CSSProperties = {
@@ -108,6 +111,28 @@ export function CockpitCenter({
             />
           </div>
 
+          {(isRunning || isCancelling) && (
+            <div
+              style={{
+                padding: 'var(--space-2) var(--space-4)',
+                backgroundColor: 'var(--color-bg-900)',
+                borderTop: '1px solid var(--color-border-700)',
+                display: 'flex',
+                justifyContent: 'flex-end',
+              }}
+            >
+              <Button
+                variant="secondary"
+                size="sm"
+                onClick={onCancelRace}
+                disabled={isCancelling}
+                data-testid="cockpit-cancel-race"
+              >
+                {isCancelling ? 'Cancelling…' : 'Cancel Race'}
+              </Button>
+            </div>
+          )}
+
           {isRunning && selectedAgent && selectedAgentLifecycle === 'running' && (
             <div
               style={{
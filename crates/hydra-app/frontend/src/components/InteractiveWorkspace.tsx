@@ -6,6 +6,8 @@ import type { XTermRendererHandle } from './XTermRenderer';
 import { Button, Badge } from './design-system';
 import {
   startInteractiveSession,
+  getUnsafeConsent,
+  grantUnsafeConsent,
   pollInteractiveEvents,
   listenInteractiveEvents,
   getInteractiveTransportDiagnostics,
@@ -17,6 +19,7 @@ import {
   listAdapters,
   type InteractivePushAttachReason,
 } from '../ipc';
+import { confirmWorkspaceTrust, withWorkspaceTrust } from '../workspaceTrust';
 import type {
   InteractiveSessionSummary,
   InteractiveStreamEvent,
@@ -880,15 +883,28 @@ export function InteractiveWorkspace({
       const targetThreadRoot = threadRootInput.trim().length > 0
         ? threadRootInput.trim()
         : (workspaceCwd ?? null);
-      const result = await startInteractiveSession({
-        agentKey,
-        taskPrompt: initialPrompt,
-        allowExperimental: allowExperimental && experimentalAcknowledged,
-        unsafeMode,
-        cwd: targetThreadRoot,
-        cols: null,
-        rows: null,
-      });
+      // Ticking "unsafe" is the user's consent; reuse a live grant or mint one.
+      let unsafeConsent: string | null = null;
+      if (unsafeMode) {
+        const current = await getUnsafeConsent(targetThreadRoot);
+        unsafeConsent = current.active
+          ? current.token
+          : (await grantUnsafeConsent(null, targetThreadRoot)).token;
+      }
+      const result = await withWorkspaceTrust(
+        targetThreadRoot,
+        () => startInteractiveSession({
+          agentKey,
+          taskPrompt: initialPrompt,
+          allowExperimental: allowExperimental && experimentalAcknowledged,
+          unsafeMode,
+          unsafeConsent,
+          cwd: targetThreadRoot,
+          cols: null,
+          rows: null,
+        }),
+        confirmWorkspaceTrust,
+      );
 
       const newSession: InteractiveSessionSummary = {
         sessionId: result.sessionId,
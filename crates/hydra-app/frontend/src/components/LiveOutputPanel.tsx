@@ -239,7 +239,14 @@ function eventTypeColor(eventType: string): string {
   if (eventType.includes('started')) return 'var(--color-green-400)';
   if (eventType.includes('completed')) return 'var(--color-green-500)';
   if (eventType.includes('failed') || eventType.includes('error')) return 'var(--color-danger-400)';
-  if (eventType.includes('timeout') || eventType.includes('timed_out')) return 'var(--color-warning-400)';
+  if (
+    eventType.includes('timeout') ||
+    eventType.includes('timed_out') ||
+    eventType.includes('injection') ||
+    eventType.includes('warning')
+  ) {
+    return 'var(--color-warning-400)';
+  }
   if (eventType.includes('stdout') || eventType.includes('output')) return 'var(--color-text-secondary)';
   return 'var(--color-marine-400)';
 }
@@ -278,6 +285,17 @@ function extractHumanReadableText(event: AgentStreamEvent): string {
     case 'agent_timed_out':
     case 'agent_timeout':
       return 'Agent timed out.';
+    case 'agent_progress':
+    case 'file_edited':
+      // Shown in the agent rail instead.
+      return '';
+    case 'prompt_injection_detected': {
+      const data = (event.data ?? {}) as { findings?: Array<{ source: string }>; stripped?: boolean };
+      const findings = data.findings ?? [];
+      const sources = Array.from(new Set(findings.map((f) => f.source))).join(', ');
+      const action = data.stripped ? ' Matches were removed from the prompt.' : '';
+      return `Possible prompt injection in ${sources} (${findings.length} match(es)).${action}`;
+    }
     default:
       break;
   }
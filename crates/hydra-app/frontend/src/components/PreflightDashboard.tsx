@@ -56,6 +56,14 @@ function DiagnosticRow({ check }: { check: DiagnosticCheck }) {
         <div style={{ fontSize: 'var(--text-xs)', color: 'var(--color-text-muted)', marginTop: 2 }}>
           {check.description}
         </div>
+        {check.remediation && (
+          <div style={{ fontSize: 'var(--text-xs)', color: 'var(--color-text-secondary)', marginTop: 2 }}>
+            {check.remediation.description}
+            {check.remediation.command && (
+              <code style={{ marginLeft: 'var(--space-2)' }}>{check.remediation.command}</code>
+            )}
+          </div>
+        )}
       </div>
       <Badge variant={statusToVariant(check.status)}>
         {check.status.toUpperCase()}
@@ -92,7 +100,7 @@ export function PreflightDashboard() {
         <div style={{ color: 'var(--color-danger-400)', marginBottom: 'var(--space-4)' }}>
           Failed to load diagnostics: {error}
         </div>
-        <Button variant="secondary" onClick={refresh}>
+        <Button variant="secondary" onClick={() => refresh()}>
           Retry
         </Button>
       </div>
@@ -128,7 +136,10 @@ export function PreflightDashboard() {
           <Button variant="secondary" size="sm">
             View Logs
           </Button>
-          <Button variant="secondary" size="sm" onClick={refresh} loading={loading}>
+          <Button variant="secondary" size="sm" onClick={() => refresh(true)} loading={loading}>
+            Deep Check
+          </Button>
+          <Button variant="secondary" size="sm" onClick={() => refresh()} loading={loading}>
             Re-run Diagnostics
           </Button>
         </div>
@@ -191,8 +202,8 @@ export function PreflightDashboard() {
             Diagnostic Checks
           </h2>
           <Card padding="none">
-            {result.checks.map((check, i) => (
-              <DiagnosticRow key={i} check={check} />
+            {result.checks.map((check) => (
+              <DiagnosticRow key={check.id} check={check} />
             ))}
           </Card>
         </div>
@@ -12,3 +12,6 @@ export type CapabilityConfidence = typeof CAPABILITY_CONFIDENCE_VALUES[number];
 
 export const CHECK_STATUS_VALUES = ["passed", "failed", "warning", "running"] as const;
 export type CheckStatus = typeof CHECK_STATUS_VALUES[number];
+
+export const SEVERITY_VALUES = ["info", "warning", "error"] as const;
+export type Severity = typeof SEVERITY_VALUES[number];
@@ -3,11 +3,20 @@ import type { AgentStreamEvent } from '../types';
 
 export type AgentLifecycle = 'running' | 'completed' | 'failed' | 'timed_out';
 
+export interface AgentProgress {
+  phase: string;
+  percent: number;
+}
+
 export interface AgentStatus {
   agentKey: string;
   lifecycle: AgentLifecycle;
   eventCount: number;
   lastEventTime: string | null;
+  /** Latest estimate from `agent_progress` events, if any arrived. */
+  progress: AgentProgress | null;
+  /** Files touched so far, from the latest `file_edited` event. */
+  filesEdited: number;
 }
 
 const TERMINAL_EVENT_MAP: Record<string, AgentLifecycle> = {
@@ -23,6 +32,17 @@ function inferTerminalLifecycle(evt: AgentStreamEvent): AgentLifecycle | undefin
   return TERMINAL_EVENT_MAP[evt.eventType];
 }
 
+function parseProgress(evt: AgentStreamEvent): AgentProgress | null {
+  if (evt.eventType !== 'agent_progress' || typeof evt.data !== 'object' || evt.data === null) {
+    return null;
+  }
+  const data = evt.data as Record<string, unknown>;
+  if (typeof data.phase !== 'string' || typeof data.percent !== 'number') {
+    return null;
+  }
+  return { phase: data.phase, percent: data.percent };
+}
+
 function looksLikeTimeoutFailure(evt: AgentStreamEvent): boolean {
   if (typeof evt.data !== 'object' || evt.data === null) {
     return false;
@@ -59,6 +79,8 @@ export function useAgentStatuses(
         lifecycle: defaultLifecycle,
         eventCount: 0,
         lastEventTime: null,
+        progress: null,
+        filesEdited: 0,
       });
     }
 
@@ -72,10 +94,24 @@ export function useAgentStatuses(
           lifecycle: defaultLifecycle,
           eventCount: 0,
           lastEventTime: null,
+          progress: null,
+          filesEdited: 0,
         };
         statusMap.set(evt.agentKey, entry);
       }
 
+      // Progress estimates are bookkeeping, not agent output.
+      const progress = parseProgress(evt);
+      if (progress) {
+        entry.progress = progress;
+        continue;
+      }
+      if (evt.eventType === 'file_edited') {
+        const data = (evt.data ?? {}) as { files?: unknown };
+        if (typeof data.files === 'number') entry.filesEdited = data.files;
+        continue;
+      }
+
       entry.eventCount += 1;
       entry.lastEventTime = evt.timestamp;
 
@@ -6,7 +6,8 @@ interface UsePreflightState {
   result: PreflightResult | null;
   loading: boolean;
   error: string | null;
-  refresh: () => Promise<void>;
+  /** Pass `deep` to also send each adapter a live prompt (slower). */
+  refresh: (deep?: boolean) => Promise<void>;
 }
 
 export function usePreflight(): UsePreflightState {
@@ -15,14 +16,14 @@ export function usePreflight(): UsePreflightState {
   const [error, setError] = useState<string | null>(null);
   const inflight = useRef(false);
 
-  const refresh = useCallback(async () => {
+  const refresh = useCallback(async (deep = false) => {
     if (inflight.current) return;
     inflight.current = true;
     setLoading(true);
     setError(null);
 
     try {
-      const data = await runPreflight();
+      const data = await runPreflight(deep);
       setResult(data);
     } catch (e) {
       setError(e instanceof Error ? e.message : String(e));
@@ -11,27 +11,55 @@ import type {
   AgentStreamEvent,
   RaceRequest,
   RaceStarted,
+  RaceCancelResult,
+  RaceApprovalResult,
   RaceResult,
   RaceEventBatch,
+  RacePollOptions,
+  RunSummary,
+  RunDetail,
+  RunDeleted,
+  RunBundle,
+  RunEventSearch,
+  RunEventSearchFilters,
+  CostPeriod,
+  CostSummary,
+  EffectiveConfig,
+  ConfigUpdateResult,
+  ConfigValidation,
+  UnsafeConsentStatus,
+  WorkspaceTrustStatus,
+  NotificationSettings,
+  AppSettings,
+  DeepPartial,
+  RepoSummary,
   WorkingTreeStatus,
   CandidateDiffPayload,
   MergePreviewPayload,
   MergeExecutionPayload,
+  AgentReviewPayload,
+  FileReviewState,
   InteractiveSessionRequest,
   InteractiveSessionStarted,
   InteractiveEventBatch,
+  InteractiveSessionSnapshot,
   InteractiveStreamEvent,
   InteractiveWriteAck,
   InteractiveResizeAck,
   InteractiveStopResult,
   InteractiveRemoveResult,
+  PromotedCandidate,
   InteractiveSessionSummary,
   InteractiveTransportDiagnostics,
+  SessionTranscriptChunk,
+  SessionTranscriptSummary,
   DirectoryListing,
   FilePreview,
   FileWatcherStarted,
   FileWatchEventBatch,
   FileWatcherStopped,
+  IpcErrorCode,
+  IpcErrorPayload,
 } from './types';
 
 type InvokeFn = <T>(cmd: string, args?: Record<string, unknown>) => Promise<T>;
@@ -50,6 +78,45 @@ export interface InteractivePushAttachResult {
   detail: string | null;
 }
 
+/**
+ * Error thrown for a rejected command. The message keeps the
+ * `[code] message` form that callers already parse.
+ */
+export class IpcCommandError extends Error {
+  readonly code: IpcErrorCode;
+  readonly details: unknown;
+  readonly retryable: boolean;
+  readonly errorCode: string | null;
+
+  constructor(payload: IpcErrorPayload) {
+    super(`[${payload.code}] ${payload.message}`);
+    this.name = 'IpcCommandError';
+    this.code = payload.code;
+    this.details = payload.details ?? null;
+    this.retryable = payload.retryable;
+    this.errorCode = payload.errorCode ?? null;
+  }
+}
+
+function isIpcErrorPayload(value: unknown): value is IpcErrorPayload {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as IpcErrorPayload).code === 'string' &&
+    typeof (value as IpcErrorPayload).message === 'string'
+  );
+}
+
+function withTypedErrors(invoke: InvokeFn): InvokeFn {
+  return async <T>(cmd: string, args?: Record<string, unknown>): Promise<T> => {
+    try {
+      return await invoke<T>(cmd, args);
+    } catch (err) {
+      throw isIpcErrorPayload(err) ? new IpcCommandError(err) : err;
+    }
+  };
+}
+
 let _invoke: InvokeFn | null = null;
 
 async function getInvoke(): Promise<InvokeFn> {
@@ -57,7 +124,7 @@ async function getInvoke(): Promise<InvokeFn> {
 
   try {
     const mod = await import('@tauri-apps/api/core');
-    _invoke = mod.invoke as InvokeFn;
+    _invoke = withTypedErrors(mod.invoke as InvokeFn);
   } catch {
     if (import.meta.env.VITE_ALLOW_MOCK_IPC === 'true') {
       _invoke = mockInvoke;
@@ -79,9 +146,9 @@ export async function healthCheck(): Promise<{ status: string; version: string }
   return invoke('health_check');
 }
 
-export async function runPreflight(): Promise<PreflightResult> {
+export async function runPreflight(deep = false): Promise<PreflightResult> {
   const invoke = await getInvoke();
-  return invoke('run_preflight');
+  return invoke('run_preflight', { deep });
 }
 
 export async function listAdapters(): Promise<AdapterInfo[]> {
@@ -94,14 +161,191 @@ export async function startRace(request: RaceRequest): Promise<RaceStarted> {
   return invoke('start_race', { request });
 }
 
+export async function cancelRace(runId: string): Promise<RaceCancelResult> {
+  const invoke = await getInvoke();
+  return invoke('cancel_race', { runId });
+}
+
+export async function resolveRaceApproval(
+  runId: string,
+  approvalId: string,
+  approve: boolean,
+): Promise<RaceApprovalResult> {
+  const invoke = await getInvoke();
+  return invoke('resolve_race_approval', { runId, approvalId, approve });
+}
+
 export async function getRaceResult(runId: string): Promise<RaceResult | null> {
   const invoke = await getInvoke();
   return invoke('get_race_result', { runId });
 }
 
-export async function pollRaceEvents(runId: string, cursor: number): Promise<RaceEventBatch> {
+/**
+ * Poll race events. With `coalesce`/`gzip` the backend sends fewer, larger
+ * payloads; the batch is unpacked here so callers always get one event per
+ * output line.
+ */
+export async function pollRaceEvents(
+  runId: string,
+  cursor: number,
+  options?: RacePollOptions,
+): Promise<RaceEventBatch> {
+  const invoke = await getInvoke();
+  const batch = await invoke<RaceEventBatch>('poll_race_events', { runId, cursor, options });
+  const events = batch.encoding === 'gzip' && batch.compressedEvents
+    ? await gunzipEvents(batch.compressedEvents)
+    : batch.events;
+  return {
+    ...batch,
+    events: events.flatMap(expandChunk),
+    encoding: null,
+    compressedEvents: null,
+  };
+}
+
+async function gunzipEvents(encoded: string): Promise<AgentStreamEvent[]> {
+  const bytes = Uint8Array.from(atob(encoded), (c) => c.charCodeAt(0));
+  const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream('gzip'));
+  return JSON.parse(await new Response(stream).text()) as AgentStreamEvent[];
+}
+
+function expandChunk(event: AgentStreamEvent): AgentStreamEvent[] {
+  const lines = (event.data as { lines?: unknown } | null)?.lines;
+  if (!Array.isArray(lines)) return [event];
+  return lines.map((line) => ({ ...event, data: { line } }));
+}
+
+export async function listRuns(cwd?: string | null, tags?: string[]): Promise<RunSummary[]> {
+  const invoke = await getInvoke();
+  return invoke('list_runs', { cwd: cwd ?? null, tags: tags ?? null });
+}
+
+export async function getRunDetail(runId: string, cwd?: string | null): Promise<RunDetail> {
+  const invoke = await getInvoke();
+  return invoke('get_run_detail', { runId, cwd: cwd ?? null });
+}
+
+export async function deleteRun(runId: string, cwd?: string | null): Promise<RunDeleted> {
+  const invoke = await getInvoke();
+  return invoke('delete_run', { runId, cwd: cwd ?? null });
+}
+
+export async function exportRunBundle(
+  runId: string,
+  destDir?: string | null,
+  cwd?: string | null,
+): Promise<RunBundle> {
+  const invoke = await getInvoke();
+  return invoke('export_run_bundle', { runId, destDir: destDir ?? null, cwd: cwd ?? null });
+}
+
+export async function searchRunEvents(
+  runId: string,
+  query: string,
+  filters?: RunEventSearchFilters | null,
+  cwd?: string | null,
+): Promise<RunEventSearch> {
+  const invoke = await getInvoke();
+  return invoke('search_run_events', { runId, query, filters: filters ?? null, cwd: cwd ?? null });
+}
+
+export async function getCostSummary(
+  period?: CostPeriod | null,
+  cwd?: string | null,
+): Promise<CostSummary> {
+  const invoke = await getInvoke();
+  return invoke('get_cost_summary', { period: period ?? null, cwd: cwd ?? null });
+}
+
+export async function getEffectiveConfig(cwd?: string | null): Promise<EffectiveConfig> {
+  const invoke = await getInvoke();
+  return invoke('get_effective_config', { cwd: cwd ?? null });
+}
+
+export async function updateConfig(
+  section: string,
+  values: Record<string, unknown>,
+  cwd?: string | null,
+): Promise<ConfigUpdateResult> {
+  const invoke = await getInvoke();
+  return invoke('update_config', { section, values, cwd: cwd ?? null });
+}
+
+export async function validateConfigDraft(draft: string): Promise<ConfigValidation> {
+  const invoke = await getInvoke();
+  return invoke('validate_config_draft', { draft });
+}
+
+export async function getUnsafeConsent(cwd?: string | null): Promise<UnsafeConsentStatus> {
+  const invoke = await getInvoke();
+  return invoke('get_unsafe_consent', { cwd: cwd ?? null });
+}
+
+/** Grant unsafe mode for `minutes` (defaults to `security.unsafe_consent_minutes`). */
+export async function grantUnsafeConsent(
+  minutes?: number | null,
+  cwd?: string | null,
+): Promise<UnsafeConsentStatus> {
+  const invoke = await getInvoke();
+  return invoke('grant_unsafe_consent', { minutes: minutes ?? null, cwd: cwd ?? null });
+}
+
+export async function revokeUnsafeConsent(cwd?: string | null): Promise<UnsafeConsentStatus> {
+  const invoke = await getInvoke();
+  return invoke('revoke_unsafe_consent', { cwd: cwd ?? null });
+}
+
+export async function getWorkspaceTrust(cwd?: string | null): Promise<WorkspaceTrustStatus> {
+  const invoke = await getInvoke();
+  return invoke('get_workspace_trust', { cwd: cwd ?? null });
+}
+
+export async function trustWorkspace(cwd?: string | null): Promise<WorkspaceTrustStatus> {
+  const invoke = await getInvoke();
+  return invoke('trust_workspace', { cwd: cwd ?? null });
+}
+
+export async function revokeWorkspaceTrust(cwd?: string | null): Promise<WorkspaceTrustStatus> {
+  const invoke = await getInvoke();
+  return invoke('revoke_workspace_trust', { cwd: cwd ?? null });
+}
+
+export async function getNotificationSettings(): Promise<NotificationSettings> {
   const invoke = await getInvoke();
-  return invoke('poll_race_events', { runId, cursor });
+  return invoke('get_notification_settings');
+}
+
+export async function updateNotificationSettings(
+  settings: NotificationSettings,
+): Promise<NotificationSettings> {
+  const invoke = await getInvoke();
+  return invoke('update_notification_settings', { settings });
+}
+
+export async function getSettings(): Promise<AppSettings> {
+  const invoke = await getInvoke();
+  return invoke('get_settings');
+}
+
+/** Merge `patch` into the saved settings; `null` resets a key to its default. */
+export async function updateSettings(patch: DeepPartial<AppSettings>): Promise<AppSettings> {
+  const invoke = await getInvoke();
+  return invoke('update_settings', { patch });
+}
+
+export async function listRepos(): Promise<RepoSummary[]> {
+  const invoke = await getInvoke();
+  return invoke('list_repos');
+}
+
+export async function addRepo(path: string): Promise<RepoSummary> {
+  const invoke = await getInvoke();
+  return invoke('add_repo', { path });
+}
+
+export async function setActiveRepo(path: string): Promise<RepoSummary> {
+  const invoke = await getInvoke();
+  return invoke('set_active_repo', { path });
 }
 
 export async function getCandidateDiff(
@@ -133,9 +377,38 @@ export async function executeMerge(
   agentKey: string,
   force: boolean,
   cwd?: string | null,
+  approvedOnly = false,
 ): Promise<MergeExecutionPayload> {
   const invoke = await getInvoke();
-  return invoke('execute_merge', { runId, agentKey, force, cwd: cwd ?? null });
+  return invoke('execute_merge', { runId, agentKey, force, approvedOnly, cwd: cwd ?? null });
+}
+
+export async function setFileReviewState(
+  runId: string,
+  agentKey: string,
+  path: string,
+  state: FileReviewState,
+  note?: string | null,
+  cwd?: string | null,
+): Promise<AgentReviewPayload> {
+  const invoke = await getInvoke();
+  return invoke('set_file_review_state', {
+    runId,
+    agentKey,
+    path,
+    state,
+    note: note ?? null,
+    cwd: cwd ?? null,
+  });
+}
+
+export async function getFileReviewState(
+  runId: string,
+  agentKey: string,
+  cwd?: string | null,
+): Promise<AgentReviewPayload> {
+  const invoke = await getInvoke();
+  return invoke('get_file_review_state', { runId, agentKey, cwd: cwd ?? null });
 }
 
 // ---------------------------------------------------------------------------
@@ -157,6 +430,13 @@ export async function pollInteractiveEvents(
   return invoke('poll_interactive_events', { sessionId, cursor });
 }
 
+export async function getInteractiveSessionSnapshot(
+  sessionId: string,
+): Promise<InteractiveSessionSnapshot> {
+  const invoke = await getInvoke();
+  return invoke('get_interactive_session_snapshot', { sessionId });
+}
+
 export async function writeInteractiveInput(
   sessionId: string,
   input: string,
@@ -188,6 +468,13 @@ export async function removeInteractiveSession(
   return invoke('remove_interactive_session', { sessionId });
 }
 
+export async function promoteInteractiveSession(
+  sessionId: string,
+): Promise<PromotedCandidate> {
+  const invoke = await getInvoke();
+  return invoke('promote_interactive_session', { sessionId });
+}
+
 export async function listInteractiveSessions(): Promise<InteractiveSessionSummary[]> {
   const invoke = await getInvoke();
   return invoke('list_interactive_sessions');
@@ -200,6 +487,24 @@ export async function getInteractiveTransportDiagnostics(
   return invoke('get_interactive_transport_diagnostics', { sessionId });
 }
 
+export async function getSessionTranscript(
+  sessionId: string,
+  offset = 0,
+  limit?: number,
+  cwd?: string,
+): Promise<SessionTranscriptChunk> {
+  const invoke = await getInvoke();
+  return invoke('get_session_transcript', { sessionId, offset, limit, cwd });
+}
+
+export async function getSessionSummary(
+  sessionId: string,
+  cwd?: string,
+): Promise<SessionTranscriptSummary> {
+  const invoke = await getInvoke();
+  return invoke('get_session_summary', { sessionId, cwd });
+}
+
 /**
  * Prefer push-stream interactive events when running inside Tauri.
  * Returns attach diagnostics so callers can decide whether to retry or
@@ -373,16 +678,59 @@ const MOCK_PREFLIGHT: PreflightResult = {
   totalCount: 4,
   healthScore: 100,
   checks: [
-    { name: 'Environment Variables Check', description: 'Found system configuration', status: 'passed', evidence: null },
-    { name: 'Checking Git Repository', description: 'Clean working tree on current branch', status: 'passed', evidence: null },
-    { name: 'Validating Adapters', description: 'Connected to Anthropic and OpenAI endpoints', status: 'passed', evidence: 'Connected to 2 adapter(s)' },
-    { name: 'Running Startup Smoke Tests', description: 'Latency checks: OK. Token estimation: OK.', status: 'passed', evidence: null },
+    { id: 'git.repo', name: 'Git Repository', description: 'Working inside a valid git repository', severity: 'error', status: 'passed', evidence: null, remediation: null },
+    { id: 'env.home', name: 'Environment Variables Check', description: 'Found system configuration', severity: 'warning', status: 'passed', evidence: null, remediation: null },
+    { id: 'adapters.tier1', name: 'Validating Adapters', description: '2/2 tier-1 adapters ready', severity: 'error', status: 'passed', evidence: 'Connected to 2 adapter(s)', remediation: null },
+    { id: 'git.clean_tree', name: 'Working Tree Cleanliness', description: 'Working tree is clean', severity: 'info', status: 'passed', evidence: null, remediation: null },
   ],
   adapters: MOCK_ADAPTERS,
   warnings: [],
+  pings: [],
+};
+
+const MOCK_RUN_SUMMARY: RunSummary = {
+  runId: 'mock-run',
+  status: 'completed',
+  baseRef: 'main',
+  taskPrompt: 'Add input validation to the signup form',
+  startedAt: '2026-01-01T12:00:00Z',
+  completedAt: '2026-01-01T12:00:04.700Z',
+  durationMs: 4700,
+  agents: ['claude', 'codex'],
+  winner: 'claude',
+  totalCost: 0.42,
+  tags: ['frontend'],
+};
+
+const MOCK_CONFIG: Record<string, unknown> = {
+  race: { default_agents: ['claude', 'codex'] },
+  supervisor: { hard_timeout_seconds: 1800, idle_timeout_seconds: 300 },
+};
+
+let mockSettings: AppSettings = {
+  window: { width: null, height: null, maximized: false, sidebarWidth: null, activeView: null },
+  lastUsedAgents: [],
+  experimentalAdapters: [],
+  telemetry: { enabled: false },
+  notifications: { enabled: true },
+  repos: ['/mock/repo'],
+  activeRepo: '/mock/repo',
+  locale: null,
+};
+
+const NO_UNSAFE_CONSENT: UnsafeConsentStatus = {
+  active: false,
+  token: null,
+  grantedAt: null,
+  expiresAt: null,
 };
+let mockUnsafeConsent: UnsafeConsentStatus | null = null;
 
+let mockRepos: RepoSummary[] = [
+  { path: '/mock/repo', name: 'repo', active: true, runIds: [], probed: true },
+];
 let mockCursor = 0;
+let mockRaceCancelled = false;
 let mockStartTime = Date.now();
 
 function mockTs(offsetMs: number): string {
@@ -391,7 +739,7 @@ function mockTs(offsetMs: number): string {
 
 function buildMockEventStream(): AgentStreamEvent[] {
   return [
-    { runId: 'mock-run', agentKey: 'system', eventType: 'race_process_started', data: {}, timestamp: mockTs(0) },
+    { runId: 'mock-run', agentKey: 'system', eventType: 'race_started', data: {}, timestamp: mockTs(0) },
     { runId: 'mock-run', agentKey: 'claude', eventType: 'agent_started', data: {}, timestamp: mockTs(100) },
     { runId: 'mock-run', agentKey: 'codex', eventType: 'agent_started', data: {}, timestamp: mockTs(150) },
     { runId: 'mock-run', agentKey: 'claude', eventType: 'agent_stdout', data: { line: 'Analyzing repository structure...' }, timestamp: mockTs(800) },
@@ -540,15 +888,36 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
     case 'health_check':
       return { status: 'ok', version: '0.1.0' } as T;
     case 'run_preflight':
+      if (_args?.deep) {
+        return {
+          ...MOCK_PREFLIGHT,
+          pings: MOCK_ADAPTERS.map((a) => ({ key: a.key, status: 'ok', durationMs: 1800, detail: null })),
+        } as T;
+      }
       return MOCK_PREFLIGHT as T;
     case 'list_adapters':
       return MOCK_ADAPTERS as T;
     case 'start_race':
       mockCursor = 0;
+      mockRaceCancelled = false;
       mockStartTime = Date.now();
       mockEventStream = buildMockEventStream();
       return { runId: 'mock-run', agents: ['claude', 'codex'] } as T;
+    case 'cancel_race':
+      mockRaceCancelled = true;
+      return { runId: 'mock-run', status: 'cancelling' } as T;
+    case 'resolve_race_approval': {
+      const args = _args as { runId: string; approvalId: string; approve: boolean };
+      return {
+        runId: args.runId,
+        approvalId: args.approvalId,
+        decision: args.approve ? 'approve' : 'deny',
+      } as T;
+    }
     case 'get_race_result':
+      if (mockRaceCancelled) {
+        return { runId: 'mock-run', status: 'cancelled', durationMs: null, totalCost: null, agents: [] } as T;
+      }
       return {
         runId: 'mock-run',
         status: 'completed',
@@ -587,7 +956,167 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
           },
         ],
       } as T;
+    case 'list_runs': {
+      const tags = (_args?.tags as string[] | null) ?? [];
+      return (tags.every((tag) => MOCK_RUN_SUMMARY.tags.includes(tag)) ? [MOCK_RUN_SUMMARY] : []) as T;
+    }
+    case 'get_run_detail':
+      return {
+        summary: MOCK_RUN_SUMMARY,
+        result: await mockInvoke<RaceResult>('get_race_result'),
+      } as T;
+    case 'delete_run':
+      return { runId: String(_args?.runId ?? 'mock-run') } as T;
+    case 'export_run_bundle': {
+      const runId = String(_args?.runId ?? 'mock-run');
+      const destDir = (_args?.destDir as string | null) ?? '/mock/repo/.hydra/exports';
+      return { runId, path: `${destDir}/hydra-run-${runId}.tar.gz`, sizeBytes: 20480 } as T;
+    }
+    case 'search_run_events': {
+      const query = String(_args?.query ?? '');
+      const lines = [
+        { agentKey: 'claude', kind: 'agent_stdout', snippet: 'Reading src/lib.rs' },
+        { agentKey: 'claude', kind: 'agent_stdout', snippet: 'error[E0425]: cannot find value `cfg`' },
+        { agentKey: 'codex', kind: 'agent_stderr', snippet: 'warning: unused import in src/main.rs' },
+      ];
+      const matches = lines
+        .filter((l) => l.snippet.toLowerCase().includes(query.toLowerCase()))
+        .map((l, i) => ({
+          ...l,
+          source: l.agentKey,
+          lineNumber: i + 1,
+          timestamp: new Date(Date.now() - (lines.length - i) * 1000).toISOString(),
+        }));
+      return {
+        runId: String(_args?.runId ?? 'mock-run'),
+        query,
+        matches,
+        linesScanned: lines.length,
+        truncated: false,
+      } as T;
+    }
+    case 'get_cost_summary': {
+      const byAgent = [
+        { key: 'claude', costUsd: 1.25, tokens: 84000, runs: 3 },
+        { key: 'codex', costUsd: 0.6, tokens: 52000, runs: 2 },
+      ];
+      return {
+        period: (_args?.period as string | null) ?? 'month',
+        since: null,
+        runs: 3,
+        totalCostUsd: 1.85,
+        totalTokens: 136000,
+        byAgent,
+        byProvider: [
+          { ...byAgent[0], key: 'anthropic' },
+          { ...byAgent[1], key: 'openai' },
+        ],
+        byDay: [{ key: new Date().toISOString().slice(0, 10), costUsd: 1.85, tokens: 136000, runs: 3 }],
+        budget: {
+          maxCostUsdPerRun: 1,
+          maxTokensPerRun: null,
+          runsNearLimit: 1,
+          runsOverLimit: 0,
+          maxCostUsdPerDay: 10,
+          maxCostUsdPerMonth: null,
+          spentTodayUsd: 1.85,
+          spentThisMonthUsd: 1.85,
+        },
+      } as T;
+    }
+    case 'get_effective_config':
+      return { path: '/mock/repo/hydra.toml', exists: false, config: MOCK_CONFIG } as T;
+    case 'update_config':
+      return {
+        path: '/mock/repo/hydra.toml',
+        saved: true,
+        issues: [],
+        config: { ...MOCK_CONFIG, [String(_args?.section)]: _args?.values },
+      } as T;
+    case 'validate_config_draft':
+      return { valid: true, issues: [], config: MOCK_CONFIG } as T;
+    case 'get_unsafe_consent':
+      return (mockUnsafeConsent ?? NO_UNSAFE_CONSENT) as T;
+    case 'grant_unsafe_consent': {
+      const minutes = (_args?.minutes as number | null) ?? 60;
+      const grantedAt = new Date();
+      mockUnsafeConsent = {
+        active: true,
+        token: `unsafe-mock-${grantedAt.getTime()}`,
+        grantedAt: grantedAt.toISOString(),
+        expiresAt: new Date(grantedAt.getTime() + minutes * 60_000).toISOString(),
+      };
+      return mockUnsafeConsent as T;
+    }
+    case 'revoke_unsafe_consent':
+      mockUnsafeConsent = null;
+      return NO_UNSAFE_CONSENT as T;
+    case 'get_workspace_trust':
+    case 'trust_workspace':
+      return {
+        repoRoot: String(_args?.cwd ?? '/mock/repo'),
+        trusted: true,
+        trustedAt: new Date().toISOString(),
+      } as T;
+    case 'revoke_workspace_trust':
+      return { repoRoot: String(_args?.cwd ?? '/mock/repo'), trusted: false, trustedAt: null } as T;
+    case 'get_notification_settings':
+      return mockSettings.notifications as T;
+    case 'update_notification_settings':
+      mockSettings = { ...mockSettings, notifications: _args?.settings as NotificationSettings };
+      return mockSettings.notifications as T;
+    case 'get_settings':
+      return mockSettings as T;
+    case 'list_repos':
+      return mockRepos as T;
+    case 'add_repo': {
+      const path = String(_args?.path ?? '').replace(/\/+$/, '');
+      let repo = mockRepos.find((r) => r.path === path);
+      if (!repo) {
+        repo = {
+          path,
+          name: path.split('/').pop() || path,
+          active: mockRepos.length === 0,
+          runIds: [],
+          probed: false,
+        };
+        mockRepos = [...mockRepos, repo];
+      }
+      return repo as T;
+    }
+    case 'set_active_repo': {
+      const path = String(_args?.path ?? '');
+      if (!mockRepos.some((r) => r.path === path)) {
+        throw new Error(`[not_found] repository '${path}' has not been added`);
+      }
+      mockRepos = mockRepos.map((r) => ({ ...r, active: r.path === path }));
+      mockSettings = { ...mockSettings, activeRepo: path };
+      return mockRepos.find((r) => r.path === path) as T;
+    }
+    case 'update_settings': {
+      const patch = (_args?.patch ?? {}) as Record<string, unknown>;
+      const merged: Record<string, unknown> = { ...mockSettings };
+      for (const [key, value] of Object.entries(patch)) {
+        const current = merged[key];
+        merged[key] =
+          value && typeof value === 'object' && !Array.isArray(value) && current && typeof current === 'object'
+            ? { ...current, ...value }
+            : value;
+      }
+      mockSettings = merged as unknown as AppSettings;
+      return mockSettings as T;
+    }
     case 'poll_race_events': {
+      if (mockRaceCancelled) {
+        return {
+          runId: 'mock-run',
+          events: [],
+          nextCursor: mockCursor,
+          done: true,
+          status: 'cancelled',
+          error: 'race cancelled by user',
+        } as T;
+      }
       const elapsed = Date.now() - mockStartTime;
       const available = mockEventStream.filter((e) => {
         const offset = new Date(e.timestamp).getTime() - mockStartTime;
@@ -633,6 +1162,13 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
         diffAvailable: true,
         source: 'artifact' as const,
         warning: null,
+        notes: isClaude ? 'Kept the change to the parser and added an integration test.' : null,
+        commits: isClaude
+          ? {
+              strategy: 'committed' as const,
+              commits: [{ sha: '4f2a9c1e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a39', subject: 'Fix parser edge case' }],
+            }
+          : null,
       } as T;
     }
     case 'get_working_tree_status':
@@ -665,6 +1201,24 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
         stderr: null,
       } as T;
     }
+    case 'set_file_review_state':
+    case 'get_file_review_state': {
+      const args = _args as Record<string, unknown> | undefined;
+      const files = cmd === 'set_file_review_state'
+        ? [{
+            path: String(args?.path ?? ''),
+            state: String(args?.state ?? 'pending'),
+            note: (args?.note as string | null) ?? null,
+            updatedAt: new Date().toISOString(),
+          }]
+        : [];
+      return {
+        runId: String(args?.runId ?? 'mock-run'),
+        agentKey: String(args?.agentKey ?? 'claude'),
+        files,
+        approvedPaths: files.filter((f) => f.state === 'approved').map((f) => f.path),
+      } as T;
+    }
     case 'start_interactive_session': {
       const args = _args as Record<string, unknown> | undefined;
       const request = (args?.request ?? {}) as Record<string, unknown>;
@@ -751,6 +1305,26 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
         error: null,
       } as T;
     }
+    case 'get_interactive_session_snapshot': {
+      const args = _args as Record<string, unknown> | undefined;
+      const sessionId = args?.sessionId as string;
+      const session = mockInteractiveSessions.get(sessionId);
+      if (!session) {
+        throw new Error(`[not_found] session '${sessionId}' not found`);
+      }
+      const scrollback = session.events
+        .map((event) => (event.data as { text?: string }).text ?? '')
+        .join('');
+      return {
+        sessionId,
+        agentKey: session.agentKey,
+        status: session.status,
+        error: null,
+        startedAt: session.startedAt,
+        scrollback,
+        nextCursor: session.events.length,
+      } as T;
+    }
     case 'write_interactive_input': {
       const args = _args as Record<string, unknown> | undefined;
       const sessionId = args?.sessionId as string;
@@ -824,6 +1398,36 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
         removed: true,
       } as T;
     }
+    case 'promote_interactive_session': {
+      const args = _args as Record<string, unknown> | undefined;
+      const sessionId = args?.sessionId as string;
+      const session = mockInteractiveSessions.get(sessionId);
+      if (!session) {
+        throw new Error(`[not_found] session '${sessionId}' not found`);
+      }
+      if (session.status === 'running') {
+        throw new Error('[validation_error] session is running; stop it before promoting');
+      }
+      const runId = `mock-run-${Date.now()}`;
+      return {
+        sessionId,
+        runId,
+        branch: `hydra/${runId}/agent/${session.agentKey}`,
+        baseRef: 'a1b2c3d4e5f6',
+        candidate: {
+          agentKey: session.agentKey,
+          status: 'completed',
+          durationMs: null,
+          score: 88.5,
+          mergeable: true,
+          gateFailures: [],
+          dimensions: [
+            { name: 'build', score: 100, evidence: {} },
+            { name: 'diff_scope', score: 77, evidence: {} },
+          ],
+        },
+      } as T;
+    }
     case 'list_interactive_sessions': {
       const summaries = Array.from(mockInteractiveSessions.values()).map((s) => ({
         sessionId: s.sessionId,
@@ -848,6 +1452,39 @@ async function mockInvoke<T>(cmd: string, _args?: Record<string, unknown>): Prom
       } as T;
     }
 
+    case 'get_session_transcript': {
+      const sessionId = (_args?.sessionId as string) ?? 'mock-session';
+      const content = 'mock> hello\r\nAgent ready.\r\n';
+      const offset = Math.min((_args?.offset as number) ?? 0, content.length);
+      return {
+        sessionId,
+        offset,
+        nextOffset: content.length,
+        totalBytes: content.length,
+        content: content.slice(offset),
+        eof: true,
+      } as T;
+    }
+
+    case 'get_session_summary': {
+      const sessionId = (_args?.sessionId as string) ?? 'mock-session';
+      return {
+        sessionId,
+        agentKey: 'claude',
+        status: 'completed',
+        startedAt: '2026-01-01T12:00:00Z',
+        endedAt: '2026-01-01T12:05:00Z',
+        cwd: '/mock/repo',
+        unsafeMode: false,
+        experimental: false,
+        durationMs: 300000,
+        eventCount: 42,
+        outputBytes: 27,
+        userInputCount: 1,
+        transcriptBytes: 27,
+      } as T;
+    }
+
     // File Explorer mock (P4.9.2)
     case 'list_directory': {
       const dirPath = (_args?.path as string) ?? '/workspace';
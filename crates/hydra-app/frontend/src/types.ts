@@ -8,9 +8,10 @@ import type {
   CapabilityConfidence,
   CheckStatus,
   DetectStatus,
+  Severity,
 } from './generated/rust-enums';
 
-export type { AdapterTier, CapabilityConfidence, CheckStatus, DetectStatus };
+export type { AdapterTier, CapabilityConfidence, CheckStatus, DetectStatus, Severity };
 
 // ---------------------------------------------------------------------------
 // Adapter types
@@ -44,11 +45,23 @@ export interface AdapterInfo {
 // Preflight / Doctor
 // ---------------------------------------------------------------------------
 
+/** A way out of a failed check; IDs are shared with `hydra doctor`. */
+export interface Remediation {
+  id: string;
+  description: string;
+  command: string | null;
+  automatic: boolean;
+}
+
 export interface DiagnosticCheck {
+  /** Stable ID shared with `hydra doctor`; key off this, not `name`. */
+  id: string;
   name: string;
   description: string;
+  severity: Severity;
   status: CheckStatus;
   evidence: string | null;
+  remediation: Remediation | null;
 }
 
 export interface PreflightResult {
@@ -61,6 +74,17 @@ export interface PreflightResult {
   checks: DiagnosticCheck[];
   adapters: AdapterInfo[];
   warnings: string[];
+  /** Empty unless preflight ran in deep mode. */
+  pings: AdapterPing[];
+}
+
+export type PingStatus = 'ok' | 'auth_failed' | 'quota_exceeded' | 'timed_out' | 'failed' | 'skipped';
+
+export interface AdapterPing {
+  key: string;
+  status: PingStatus;
+  durationMs: number;
+  detail: string | null;
 }
 
 // ---------------------------------------------------------------------------
@@ -72,6 +96,7 @@ export interface RaceRequest {
   agents: string[];
   allowExperimental: boolean;
   cwd: string | null;
+  tags?: string[];
 }
 
 export interface RaceStarted {
@@ -79,6 +104,28 @@ export interface RaceStarted {
   agents: string[];
 }
 
+export interface RaceCancelResult {
+  runId: string;
+  status: string;
+}
+
+export interface RaceApprovalResult {
+  runId: string;
+  approvalId: string;
+  decision: 'approve' | 'deny';
+}
+
+/** Action an agent is paused on, from an `approval_requested` event. */
+export type DangerousAction =
+  | { kind: 'shell_command'; command: string }
+  | { kind: 'file_deletion'; path: string };
+
+export interface PendingApproval {
+  agentKey: string;
+  approvalId: string;
+  action: DangerousAction;
+}
+
 export interface AgentStreamEvent {
   runId: string;
   agentKey: string;
@@ -111,13 +158,199 @@ export interface RaceResult {
   totalCost: number | null;
 }
 
+export interface RunSummary {
+  runId: string;
+  status: string;
+  baseRef: string;
+  taskPrompt: string | null;
+  startedAt: string;
+  completedAt: string | null;
+  durationMs: number | null;
+  agents: string[];
+  winner: string | null;
+  totalCost: number | null;
+  tags: string[];
+}
+
+export interface RunDetail {
+  summary: RunSummary;
+  result: RaceResult;
+}
+
+export interface RunDeleted {
+  runId: string;
+}
+
+export interface RunBundle {
+  runId: string;
+  path: string;
+  sizeBytes: number;
+}
+
+export interface RunEventSearchFilters {
+  agents?: string[];
+  /** Event kinds in snake_case, e.g. `agent_stdout`. */
+  kinds?: string[];
+  /** RFC 3339 bounds, inclusive. */
+  since?: string | null;
+  until?: string | null;
+  caseSensitive?: boolean;
+  limit?: number | null;
+}
+
+export interface RunEventMatch {
+  /** `run` for the run log, otherwise the agent whose log matched. */
+  source: string;
+  lineNumber: number;
+  timestamp: string;
+  kind: string;
+  agentKey: string | null;
+  snippet: string;
+}
+
+export interface RunEventSearch {
+  runId: string;
+  query: string;
+  matches: RunEventMatch[];
+  linesScanned: number;
+  truncated: boolean;
+}
+
+export type CostPeriod = 'day' | 'week' | 'month' | 'all';
+
+export interface CostBucket {
+  key: string;
+  costUsd: number | null;
+  tokens: number;
+  runs: number;
+}
+
+export interface CostSummary {
+  period: CostPeriod;
+  since: string | null;
+  runs: number;
+  totalCostUsd: number | null;
+  totalTokens: number;
+  byAgent: CostBucket[];
+  byProvider: CostBucket[];
+  /** Keyed by UTC date (`YYYY-MM-DD`) of the run start. */
+  byDay: CostBucket[];
+  budget: {
+    maxCostUsdPerRun: number | null;
+    maxTokensPerRun: number | null;
+    runsNearLimit: number;
+    runsOverLimit: number;
+    maxCostUsdPerDay: number | null;
+    maxCostUsdPerMonth: number | null;
+    /** Cost-ledger spend in the current UTC day and month. */
+    spentTodayUsd: number;
+    spentThisMonthUsd: number;
+  };
+}
+
+/** Parsed `hydra.toml`, keyed by TOML section names (`scoring`, `race`, ...). */
+export type HydraConfig = Record<string, unknown>;
+
+export interface EffectiveConfig {
+  path: string;
+  exists: boolean;
+  config: HydraConfig;
+}
+
+export interface ConfigIssue {
+  field: string | null;
+  message: string;
+  line: number | null;
+  column: number | null;
+}
+
+export interface ConfigValidation {
+  valid: boolean;
+  issues: ConfigIssue[];
+  config: HydraConfig | null;
+}
+
+export interface UnsafeConsentStatus {
+  active: boolean;
+  token: string | null;
+  grantedAt: string | null;
+  expiresAt: string | null;
+}
+
+export interface WorkspaceTrustStatus {
+  repoRoot: string;
+  trusted: boolean;
+  trustedAt: string | null;
+}
+
+export interface ConfigUpdateResult {
+  path: string;
+  saved: boolean;
+  issues: ConfigIssue[];
+  config: HydraConfig;
+}
+
+export interface NotificationSettings {
+  enabled: boolean;
+}
+
+export interface WindowLayout {
+  width: number | null;
+  height: number | null;
+  maximized: boolean;
+  sidebarWidth: number | null;
+  activeView: string | null;
+}
+
+/** Desktop preferences saved under the app config dir. */
+export interface AppSettings {
+  window: WindowLayout;
+  lastUsedAgents: string[];
+  experimentalAdapters: string[];
+  telemetry: { enabled: boolean };
+  notifications: NotificationSettings;
+  repos: string[];
+  activeRepo: string | null;
+  /** Language for error messages, e.g. `de`; null follows the system. */
+  locale: string | null;
+}
+
+export interface RepoSummary {
+  path: string;
+  name: string;
+  active: boolean;
+  /** Races started for this repository since the app launched. */
+  runIds: string[];
+  probed: boolean;
+}
+
+export type DeepPartial<T> = {
+  [K in keyof T]?: T[K] extends unknown[]
+    ? T[K] | null
+    : T[K] extends object
+      ? DeepPartial<T[K]> | null
+      : T[K] | null;
+};
+
 export interface RaceEventBatch {
   runId: string;
+  /** Empty when `encoding` is set; see `compressedEvents`. */
   events: AgentStreamEvent[];
   nextCursor: number;
   done: boolean;
   status: string;
   error: string | null;
+  maxEvents?: number;
+  encoding?: 'gzip' | null;
+  /** Base64 gzip of the events array. */
+  compressedEvents?: string | null;
+}
+
+export interface RacePollOptions {
+  maxEvents?: number;
+  /** Merge consecutive output lines per agent into `{ lines: [...] }` events. */
+  coalesce?: boolean;
+  gzip?: boolean;
 }
 
 export interface WorkingTreeStatus {
@@ -135,6 +368,19 @@ export interface DiffFile {
   removed: number;
 }
 
+export type CommitStrategy = 'unchanged' | 'uncommitted' | 'committed' | 'mixed';
+
+export interface AgentCommit {
+  sha: string;
+  subject: string;
+}
+
+/** Commits the agent made itself, and whether it left work uncommitted. */
+export interface CandidateCommits {
+  strategy: CommitStrategy;
+  commits: AgentCommit[];
+}
+
 export interface CandidateDiffPayload {
   runId: string;
   agentKey: string;
@@ -147,6 +393,8 @@ export interface CandidateDiffPayload {
   diffAvailable: boolean;
   source: 'artifact' | 'git' | 'none';
   warning: string | null;
+  notes: string | null;
+  commits: CandidateCommits | null;
 }
 
 export interface MergePreviewPayload {
@@ -168,14 +416,48 @@ export interface MergeExecutionPayload {
   stderr: string | null;
 }
 
+export type FileReviewState = 'pending' | 'approved' | 'rejected';
+
+export interface FileReviewEntry {
+  path: string;
+  state: FileReviewState;
+  note: string | null;
+  updatedAt: string;
+}
+
+/** Per-file review of one agent's diff. Files not listed are pending. */
+export interface AgentReviewPayload {
+  runId: string;
+  agentKey: string;
+  files: FileReviewEntry[];
+  approvedPaths: string[];
+}
+
 // ---------------------------------------------------------------------------
 // IPC Error
 // ---------------------------------------------------------------------------
 
-export interface IpcError {
-  code: string;
+export type IpcErrorCode =
+  | 'validation_error'
+  | 'not_found'
+  | 'internal_error'
+  | 'adapter_error'
+  | 'safety_gate'
+  | 'experimental_blocked'
+  | 'dirty_worktree'
+  | 'unsafe_blocked'
+  | 'binary_missing'
+  | 'launch_error'
+  | 'untrusted_workspace';
+
+/** Rejection value of every Tauri command. */
+export interface IpcErrorPayload {
+  code: IpcErrorCode;
   message: string;
-  details: string | null;
+  details: unknown;
+  retryable: boolean;
+  /** Stable Hydra code such as `HY-ADAPTER-003`, when known. */
+  errorCode?: string | null;
 }
 
 // ---------------------------------------------------------------------------
@@ -187,6 +469,8 @@ export interface InteractiveSessionRequest {
   taskPrompt: string;
   allowExperimental: boolean;
   unsafeMode: boolean;
+  /** Token from `grantUnsafeConsent`; required when `unsafeMode` is set. */
+  unsafeConsent?: string | null;
   cwd: string | null;
   cols: number | null;
   rows: number | null;
@@ -220,6 +504,24 @@ export interface InteractiveEventBatch {
   error: string | null;
 }
 
+export interface PromotedCandidate {
+  sessionId: string;
+  runId: string;
+  branch: string;
+  baseRef: string;
+  candidate: AgentResult;
+}
+
+export interface InteractiveSessionSnapshot {
+  sessionId: string;
+  agentKey: string;
+  status: string;
+  error: string | null;
+  startedAt: string;
+  scrollback: string;
+  nextCursor: number;
+}
+
 export interface InteractiveWriteAck {
   sessionId: string;
   success: boolean;
@@ -265,6 +567,34 @@ export interface InteractiveTransportDiagnostics {
   lastPushEmitAt: string | null;
 }
 
+/** A page of a session's recorded terminal output, by byte offset. */
+export interface SessionTranscriptChunk {
+  sessionId: string;
+  offset: number;
+  nextOffset: number;
+  totalBytes: number;
+  /** Raw terminal output, ANSI escapes included. */
+  content: string;
+  eof: boolean;
+}
+
+/** Persisted session details; counters are null if the session never finalized. */
+export interface SessionTranscriptSummary {
+  sessionId: string;
+  agentKey: string;
+  status: string;
+  startedAt: string;
+  endedAt: string | null;
+  cwd: string;
+  unsafeMode: boolean;
+  experimental: boolean;
+  durationMs: number | null;
+  eventCount: number | null;
+  outputBytes: number | null;
+  userInputCount: number | null;
+  transcriptBytes: number;
+}
+
 // ---------------------------------------------------------------------------
 // File Explorer (P4.9.2)
 // ---------------------------------------------------------------------------
@@ -0,0 +1,29 @@
+import { trustWorkspace } from './ipc';
+
+/**
+ * Run `start`; if the repository has not been trusted yet, ask `confirm`
+ * and, once the user agrees, trust it and try again.
+ */
+export async function withWorkspaceTrust<T>(
+  cwd: string | null | undefined,
+  start: () => Promise<T>,
+  confirm: (repoRoot: string) => boolean,
+): Promise<T> {
+  try {
+    return await start();
+  } catch (err) {
+    const { code, details } = (err ?? {}) as { code?: string; details?: { repoRoot?: string } | null };
+    if (code !== 'untrusted_workspace') throw err;
+    if (!confirm(details?.repoRoot ?? cwd ?? 'this repository')) throw err;
+    await trustWorkspace(cwd);
+    return start();
+  }
+}
+
+export function confirmWorkspaceTrust(repoRoot: string): boolean {
+  return window.confirm(
+    `Hydra has not been trusted in ${repoRoot}.\n\n`
+      + "Running agents or scoring executes this repository's build and test commands. "
+      + 'Only trust code you have reviewed.\n\nTrust this repository?',
+  );
+}
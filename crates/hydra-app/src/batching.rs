@@ -0,0 +1,173 @@
+//! Shrinking race event batches for the poll loop. Chatty agents can emit
+//! thousands of output lines a second, and one IPC event per line mostly
+//! repeats the same run id, agent key and event type.
+
+use std::io::Write;
+
+use base64::Engine as _;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::ipc_types::{AgentStreamEvent, IpcErrorPayload};
+
+/// Batches whose JSON is smaller than this are sent uncompressed even when
+/// the client asks for gzip; the base64 overhead would outweigh the gain.
+pub(crate) const GZIP_MIN_BYTES: usize = 16 * 1024;
+
+/// Lines merged into one chunk at most, so a single event stays small
+/// enough to render incrementally.
+const MAX_LINES_PER_CHUNK: usize = 256;
+
+/// Merge runs of consecutive plain output lines (`{"line": ...}`) from the
+/// same agent and stream into one event carrying `{"lines": [...]}`. The
+/// chunk keeps the first line's timestamp. Structured events are left as
+/// they are.
+pub(crate) fn coalesce_output(events: Vec<AgentStreamEvent>) -> Vec<AgentStreamEvent> {
+    let mut out: Vec<AgentStreamEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        let Some(line) = plain_line(&event) else {
+            out.push(event);
+            continue;
+        };
+        if let Some(last) = out.last_mut() {
+            if last.agent_key == event.agent_key && last.event_type == event.event_type {
+                if let Some(lines) = chunk_lines(last) {
+                    if lines.len() < MAX_LINES_PER_CHUNK {
+                        lines.push(line.into());
+                        continue;
+                    }
+                } else if let Some(first) = plain_line(last).map(str::to_string) {
+                    last.data = serde_json::json!({ "lines": [first, line] });
+                    continue;
+                }
+            }
+        }
+        out.push(event);
+    }
+    out
+}
+
+/// Gzip a serialized event array and base64-encode it for transport.
+pub(crate) fn gzip_events(json: &[u8]) -> Result<String, IpcErrorPayload> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(json)
+        .and_then(|_| encoder.finish())
+        .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to compress event batch: {e}")))
+}
+
+fn is_output(event: &AgentStreamEvent) -> bool {
+    matches!(event.event_type.as_str(), "agent_stdout" | "agent_stderr")
+}
+
+fn plain_line(event: &AgentStreamEvent) -> Option<&str> {
+    let data = event.data.as_object().filter(|_| is_output(event))?;
+    if data.len() != 1 {
+        return None;
+    }
+    data.get("line")?.as_str()
+}
+
+fn chunk_lines(event: &mut AgentStreamEvent) -> Option<&mut Vec<serde_json::Value>> {
+    if !is_output(event) {
+        return None;
+    }
+    let data = event.data.as_object_mut()?;
+    if data.len() != 1 {
+        return None;
+    }
+    data.get_mut("lines")?.as_array_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn event(agent: &str, event_type: &str, data: serde_json::Value) -> AgentStreamEvent {
+        AgentStreamEvent {
+            run_id: "run".to_string(),
+            agent_key: agent.to_string(),
+            event_type: event_type.to_string(),
+            data,
+            timestamp: "2026-02-24T00:00:00Z".to_string(),
+        }
+    }
+
+    fn line(agent: &str, event_type: &str, text: &str) -> AgentStreamEvent {
+        event(agent, event_type, serde_json::json!({ "line": text }))
+    }
+
+    #[test]
+    fn merges_consecutive_lines_per_agent_and_stream() {
+        let events = vec![
+            line("claude", "agent_stdout", "a"),
+            line("claude", "agent_stdout", "b"),
+            line("claude", "agent_stdout", "c"),
+            line("claude", "agent_stderr", "warn"),
+            line("codex", "agent_stdout", "x"),
+            event(
+                "codex",
+                "agent_stdout",
+                serde_json::json!({ "type": "message", "text": "hi" }),
+            ),
+            line("codex", "agent_stdout", "y"),
+            event("claude", "agent_completed", serde_json::json!({})),
+        ];
+
+        let out = coalesce_output(events);
+        let shapes: Vec<_> = out
+            .iter()
+            .map(|e| (e.agent_key.as_str(), e.event_type.as_str(), e.data.clone()))
+            .collect();
+        assert_eq!(
+            shapes,
+            vec![
+                (
+                    "claude",
+                    "agent_stdout",
+                    serde_json::json!({ "lines": ["a", "b", "c"] })
+                ),
+                (
+                    "claude",
+                    "agent_stderr",
+                    serde_json::json!({ "line": "warn" })
+                ),
+                ("codex", "agent_stdout", serde_json::json!({ "line": "x" })),
+                (
+                    "codex",
+                    "agent_stdout",
+                    serde_json::json!({ "type": "message", "text": "hi" })
+                ),
+                ("codex", "agent_stdout", serde_json::json!({ "line": "y" })),
+                ("claude", "agent_completed", serde_json::json!({})),
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_are_capped() {
+        let events = (0..MAX_LINES_PER_CHUNK + 1)
+            .map(|i| line("claude", "agent_stdout", &i.to_string()))
+            .collect();
+        let out = coalesce_output(events);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[1].data, serde_json::json!({ "line": "256" }));
+    }
+
+    #[test]
+    fn gzip_round_trips() {
+        let json = br#"[{"line":"hello"}]"#;
+        let encoded = gzip_events(json).unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        let mut decoded = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, json);
+    }
+}
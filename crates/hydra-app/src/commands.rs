@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::{
@@ -8,16 +7,34 @@ use std::sync::{
 
 use tauri::{Emitter, State};
 use tokio::io::AsyncReadExt;
-use tokio::process::Command as TokioCommand;
-use tokio::time::{sleep, Duration};
 
-use hydra_core::artifact::{EventKind, RunEvent};
+use hydra_core::adapter::interactive::{
+    build_interactive_args, resolve_interactive_binary, supports_interactive_unsafe_mode,
+    unsafe_mode_requirement_hint,
+};
+use hydra_core::adapter::PingStatus;
+use hydra_core::artifact::{EventSinks, LockMode, RunStatus};
+use hydra_core::config::HydraConfig;
+use hydra_core::diagnostics::{check, remediation, Remediation, Severity};
+use hydra_core::orchestrator::{
+    Orchestrator, OutputStream, PromoteOptions, RaceEvent, RaceOptions,
+};
+use hydra_core::security::{ApprovalDecision, ConsentStore, SecretRedactor, TrustStore};
 use hydra_core::worktree::WorktreeService;
 
+use crate::batching;
+use crate::config_editor;
+use crate::costs;
+use crate::history;
 use crate::ipc_types::*;
+use crate::notifications;
+use crate::search;
+use crate::sessions;
 use crate::state::{AppState, AppStateHandle, InteractiveManagedWorktree, InteractiveSessionPaths};
 
 const MAX_EVENTS_PER_POLL: usize = 512;
+/// Upper bound for a client-negotiated `poll_race_events` cap.
+const MAX_NEGOTIATED_EVENTS_PER_POLL: usize = 8192;
 const INTERACTIVE_STREAM_EVENT: &str = "hydra://interactive-event";
 
 // ---------------------------------------------------------------------------
@@ -25,7 +42,7 @@ const INTERACTIVE_STREAM_EVENT: &str = "hydra://interactive-event";
 // ---------------------------------------------------------------------------
 
 #[tauri::command]
-pub async fn health_check() -> Result<serde_json::Value, String> {
+pub async fn health_check() -> Result<serde_json::Value, IpcErrorPayload> {
     Ok(serde_json::json!({
         "status": "ok",
         "version": env!("CARGO_PKG_VERSION"),
@@ -37,7 +54,10 @@ pub async fn health_check() -> Result<serde_json::Value, String> {
 // ---------------------------------------------------------------------------
 
 #[tauri::command]
-pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult, String> {
+pub async fn run_preflight(
+    state: State<'_, AppState>,
+    deep: Option<bool>,
+) -> Result<PreflightResult, IpcErrorPayload> {
     let report = state.run_probes().await;
 
     let adapters: Vec<AdapterInfo> = report.results.iter().map(AdapterInfo::from).collect();
@@ -53,31 +73,48 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
         .unwrap_or(false);
 
     checks.push(DiagnosticCheck {
+        id: check::GIT_REPO.to_string(),
         name: "Git Repository".to_string(),
         description: if git_repo_ok {
             "Working inside a valid git repository".to_string()
         } else {
             "Not inside a git repository".to_string()
         },
+        severity: Severity::Error,
         status: if git_repo_ok {
             CheckStatus::Passed
         } else {
             CheckStatus::Failed
         },
         evidence: None,
+        remediation: (!git_repo_ok).then(|| {
+            Remediation::manual(
+                remediation::INIT_GIT_REPO,
+                "Open a folder inside a git repository, or initialize one",
+            )
+            .with_command("git init")
+        }),
     });
 
     // Check: Environment variables
     let has_env = std::env::var("HOME").is_ok() || std::env::var("USERPROFILE").is_ok();
     checks.push(DiagnosticCheck {
+        id: check::ENV_HOME.to_string(),
         name: "Environment Variables Check".to_string(),
         description: "Found system configuration".to_string(),
+        severity: Severity::Warning,
         status: if has_env {
             CheckStatus::Passed
         } else {
             CheckStatus::Warning
         },
         evidence: None,
+        remediation: (!has_env).then(|| {
+            Remediation::manual(
+                remediation::SET_HOME,
+                "Set HOME (USERPROFILE on Windows) so adapters can find their config",
+            )
+        }),
     });
 
     // Check: Adapter validation
@@ -91,8 +128,10 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
         .count();
 
     checks.push(DiagnosticCheck {
+        id: check::ADAPTERS_TIER1.to_string(),
         name: "Validating Adapters".to_string(),
         description: format!("{}/{} tier-1 adapters ready", tier1_ready, tier1_count),
+        severity: Severity::Error,
         status: if tier1_ready == tier1_count {
             CheckStatus::Passed
         } else if tier1_ready > 0 {
@@ -104,6 +143,12 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
             "Connected to {} adapter(s)",
             adapters.iter().filter(|a| a.status.is_available()).count()
         )),
+        remediation: (tier1_ready < tier1_count).then(|| {
+            Remediation::manual(
+                remediation::INSTALL_ADAPTER,
+                "Install the missing tier-1 adapters; `hydra doctor` lists the commands",
+            )
+        }),
     });
 
     // Check: working tree cleanliness
@@ -116,6 +161,7 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
     };
 
     checks.push(DiagnosticCheck {
+        id: check::GIT_CLEAN_TREE.to_string(),
         name: "Working Tree Cleanliness".to_string(),
         description: if !git_status_ok {
             "Unable to inspect working tree status".to_string()
@@ -124,6 +170,7 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
         } else {
             "Working tree has uncommitted changes".to_string()
         },
+        severity: Severity::Info,
         status: if !git_status_ok {
             CheckStatus::Warning
         } else if worktree_clean {
@@ -132,8 +179,28 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
             CheckStatus::Warning
         },
         evidence: None,
+        remediation: (git_status_ok && !worktree_clean).then(|| {
+            Remediation::manual(
+                remediation::COMMIT_OR_STASH,
+                "Commit or stash changes before racing; agents start from HEAD",
+            )
+        }),
     });
 
+    // Check: live ping per adapter (deep mode only). A `--version` probe
+    // passing says nothing about auth or quota.
+    let pings: Vec<AdapterPing> = if deep.unwrap_or(false) {
+        state
+            .ping_adapters(&report, hydra_core::adapter::DEFAULT_PING_TIMEOUT)
+            .await
+            .into_iter()
+            .map(AdapterPing::from)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    checks.extend(pings.iter().map(ping_check));
+
     // Warnings for experimental adapters
     for adapter in &adapters {
         if adapter.tier == hydra_core::adapter::AdapterTier::Experimental
@@ -171,26 +238,58 @@ pub async fn run_preflight(state: State<'_, AppState>) -> Result<PreflightResult
         checks,
         adapters,
         warnings,
+        pings,
     })
 }
 
+fn ping_check(ping: &AdapterPing) -> DiagnosticCheck {
+    DiagnosticCheck {
+        id: check::adapter_live(&ping.key),
+        name: format!("Live Check: {}", ping.key),
+        description: match ping.status {
+            PingStatus::Ok => format!("{} answered in {} ms", ping.key, ping.duration_ms),
+            PingStatus::AuthFailed => format!("{} is not authenticated", ping.key),
+            PingStatus::QuotaExceeded => format!("{} hit a rate or usage limit", ping.key),
+            PingStatus::TimedOut => format!("{} did not answer in time", ping.key),
+            PingStatus::Failed => format!("{} failed to answer a prompt", ping.key),
+            PingStatus::Skipped => format!("{} has no headless mode to check", ping.key),
+        },
+        severity: Severity::Warning,
+        status: match ping.status {
+            PingStatus::Ok => CheckStatus::Passed,
+            PingStatus::TimedOut | PingStatus::Skipped => CheckStatus::Warning,
+            _ => CheckStatus::Failed,
+        },
+        evidence: ping.detail.clone(),
+        remediation: (ping.status == PingStatus::AuthFailed).then(|| {
+            Remediation::manual(
+                remediation::LOGIN_ADAPTER,
+                format!("Log in to {}", ping.key),
+            )
+        }),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // List adapters (runtime-driven, not hardcoded)
 // ---------------------------------------------------------------------------
 
 #[tauri::command]
-pub async fn list_adapters(state: State<'_, AppState>) -> Result<Vec<AdapterInfo>, String> {
+pub async fn list_adapters(
+    state: State<'_, AppState>,
+) -> Result<Vec<AdapterInfo>, IpcErrorPayload> {
     let report = state.run_probes().await;
     Ok(report.results.iter().map(AdapterInfo::from).collect())
 }
 
 #[tauri::command]
-pub async fn get_working_tree_status(cwd: Option<String>) -> Result<WorkingTreeStatus, String> {
+pub async fn get_working_tree_status(
+    cwd: Option<String>,
+) -> Result<WorkingTreeStatus, IpcErrorPayload> {
     let repo_root = resolve_repo_root(
         cwd.as_deref(),
         "Not inside a git repository; cannot inspect working tree",
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
     let scope = resolve_working_tree_scope(&repo_root, cwd.as_deref());
     Ok(read_working_tree_status(&repo_root, scope.as_deref()))
 }
@@ -201,24 +300,53 @@ pub async fn get_working_tree_status(cwd: Option<String>) -> Result<WorkingTreeS
 
 #[tauri::command]
 pub async fn start_race(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     request: RaceRequest,
-) -> Result<RaceStarted, String> {
+) -> Result<RaceStarted, IpcErrorPayload> {
     if request.task_prompt.trim().is_empty() {
-        return Err(IpcError::validation("Task prompt cannot be empty").to_string());
+        return Err(IpcErrorPayload::validation("Task prompt cannot be empty"));
     }
     if request.agents.is_empty() {
-        return Err(IpcError::validation("At least one agent must be selected").to_string());
+        return Err(IpcErrorPayload::validation(
+            "At least one agent must be selected",
+        ));
     }
+    for tag in &request.tags {
+        hydra_core::artifact::parse_tag(tag).map_err(IpcErrorPayload::validation)?;
+    }
+
+    let repo_root = resolve_repo_root_with_auto_init(
+        request.cwd.as_deref(),
+        "Not inside a git repository; cannot start race",
+    )?;
+    TrustStore::user_default()?.require_trusted(&repo_root)?;
 
     let run_id = uuid::Uuid::new_v4().to_string();
     let agents = request.agents.clone();
+    if let Err(e) = state
+        .settings
+        .lock()
+        .await
+        .update(serde_json::json!({ "lastUsedAgents": agents }))
+    {
+        tracing::warn!(error = %e, "failed to remember last-used agents");
+    }
     let state_handle = state.handle();
     state_handle.register_race(&run_id).await;
+    if let Some(repo) = state
+        .repos
+        .lock()
+        .await
+        .context_for_mut(request.cwd.as_deref().map(Path::new))
+    {
+        repo.run_ids.push(run_id.clone());
+    }
+    let config = state.config.lock().await.clone();
 
     let run_id_for_task = run_id.clone();
     tokio::spawn(async move {
-        execute_race(state_handle, request, run_id_for_task).await;
+        execute_race(app, state_handle, request, run_id_for_task, config).await;
     });
 
     Ok(RaceStarted { run_id, agents })
@@ -229,18 +357,37 @@ pub async fn poll_race_events(
     state: State<'_, AppState>,
     run_id: String,
     cursor: u64,
-) -> Result<RaceEventBatch, String> {
-    let cursor = usize::try_from(cursor)
-        .map_err(|_| IpcError::validation("Invalid event cursor").to_string())?;
+    options: Option<RacePollOptions>,
+) -> Result<RaceEventBatch, IpcErrorPayload> {
+    let cursor =
+        usize::try_from(cursor).map_err(|_| IpcErrorPayload::validation("Invalid event cursor"))?;
+    let options = options.unwrap_or_default();
+    let max_events = options
+        .max_events
+        .unwrap_or(MAX_EVENTS_PER_POLL)
+        .clamp(1, MAX_NEGOTIATED_EVENTS_PER_POLL);
 
     let state_handle = state.handle();
-    let Some((events, next_cursor, done, status, error)) = state_handle
-        .poll_events(&run_id, cursor, MAX_EVENTS_PER_POLL)
-        .await
+    let Some((mut events, next_cursor, done, status, error)) =
+        state_handle.poll_events(&run_id, cursor, max_events).await
     else {
-        return Err(IpcError::validation("Unknown run ID").to_string());
+        return Err(IpcErrorPayload::validation("Unknown run ID"));
     };
 
+    if options.coalesce {
+        events = batching::coalesce_output(events);
+    }
+    let mut compressed_events = None;
+    if options.gzip {
+        let json = serde_json::to_vec(&events).map_err(|e| {
+            IpcErrorPayload::internal(format!("failed to serialize event batch: {e}"))
+        })?;
+        if json.len() >= batching::GZIP_MIN_BYTES {
+            compressed_events = Some(batching::gzip_events(&json)?);
+            events.clear();
+        }
+    }
+
     Ok(RaceEventBatch {
         run_id,
         events,
@@ -248,6 +395,9 @@ pub async fn poll_race_events(
         done,
         status,
         error,
+        max_events: max_events as u64,
+        encoding: compressed_events.as_ref().map(|_| "gzip".to_string()),
+        compressed_events,
     })
 }
 
@@ -255,12 +405,67 @@ pub async fn poll_race_events(
 pub async fn get_race_result(
     state: State<'_, AppState>,
     run_id: String,
-) -> Result<Option<RaceResult>, String> {
+) -> Result<Option<RaceResult>, IpcErrorPayload> {
     let state_handle = state.handle();
     Ok(state_handle.race_result(&run_id).await)
 }
 
-async fn execute_race(state: AppStateHandle, request: RaceRequest, run_id: String) {
+#[tauri::command]
+pub async fn cancel_race(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<RaceCancelResult, IpcErrorPayload> {
+    let state_handle = state.handle();
+    state_handle.request_cancel(&run_id).await?;
+
+    emit_orchestrator_event(
+        &state_handle,
+        &run_id,
+        "race_cancel_requested",
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(RaceCancelResult {
+        run_id,
+        status: "cancelling".to_string(),
+    })
+}
+
+/// Approve or deny an action an agent is paused on (see
+/// `security.approval.policy = "ask"`).
+#[tauri::command]
+pub async fn resolve_race_approval(
+    state: State<'_, AppState>,
+    run_id: String,
+    approval_id: String,
+    approve: bool,
+) -> Result<RaceApprovalResult, IpcErrorPayload> {
+    let approval_uuid = uuid::Uuid::parse_str(&approval_id)
+        .map_err(|e| IpcErrorPayload::validation(format!("invalid approval_id: {e}")))?;
+    let decision = if approve {
+        ApprovalDecision::Approve
+    } else {
+        ApprovalDecision::Deny
+    };
+    state
+        .handle()
+        .resolve_approval(&run_id, approval_uuid, decision)
+        .await?;
+    Ok(RaceApprovalResult {
+        run_id,
+        approval_id,
+        decision: decision.as_str().to_string(),
+    })
+}
+
+async fn execute_race(
+    app: tauri::AppHandle,
+    state: AppStateHandle,
+    request: RaceRequest,
+    run_id: String,
+    app_config: HydraConfig,
+) {
     let repo_root = match resolve_repo_root_with_auto_init(
         request.cwd.as_deref(),
         "Not inside a git repository; cannot start race",
@@ -271,262 +476,299 @@ async fn execute_race(state: AppStateHandle, request: RaceRequest, run_id: Strin
             return;
         }
     };
+    let (Some(cancel), Some(approvals)) = (
+        state.race_cancel_token(&run_id).await,
+        state.race_approvals(&run_id).await,
+    ) else {
+        return;
+    };
+    let run_uuid = match uuid::Uuid::parse_str(&run_id) {
+        Ok(id) => id,
+        Err(e) => {
+            state
+                .mark_failed(&run_id, format!("invalid run ID '{run_id}': {e}"))
+                .await;
+            return;
+        }
+    };
+    let config = match race_config(&repo_root, app_config) {
+        Ok(config) => config,
+        Err(err) => {
+            state.mark_failed(&run_id, err.message).await;
+            return;
+        }
+    };
 
-    let run_dir = repo_root.join(".hydra").join("runs").join(&run_id);
-    let events_path = run_dir.join("events.jsonl");
-    let agents_dir = run_dir.join("agents");
-
-    let mut cmd = build_race_command(&repo_root, &run_id, &request);
-    let stop_tail = Arc::new(AtomicBool::new(false));
-    let tail_handle = tokio::spawn(tail_run_events_file(
-        state.clone(),
-        run_id.clone(),
-        events_path,
-        agents_dir,
-        Arc::clone(&stop_tail),
-    ));
+    // Forward orchestrator events in order; the sink itself must not block.
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<RaceEvent>();
+    let forwarder = tokio::spawn({
+        let app = app.clone();
+        let state = state.clone();
+        let run_id = run_id.clone();
+        async move {
+            while let Some(event) = event_rx.recv().await {
+                if let RaceEvent::BudgetWarning { agent_key, warning } = &event {
+                    let notice = notifications::budget_warning(agent_key, warning);
+                    notifications::notify(&app, &state, notice).await;
+                }
+                state
+                    .append_event(&run_id, race_stream_event(&run_id, event))
+                    .await;
+            }
+        }
+    });
 
     emit_orchestrator_event(
         &state,
         &run_id,
-        "race_process_started",
+        "race_started",
         serde_json::json!({ "agents": request.agents }),
     )
     .await;
 
-    let output = cmd.output().await;
-
-    stop_tail.store(true, Ordering::Relaxed);
-    let _ = tail_handle.await;
+    let orchestrator = Orchestrator::new(repo_root, config);
+    let result = orchestrator
+        .race(
+            RaceOptions {
+                agents: request.agents,
+                prompt: request.task_prompt,
+                prompt_source: None,
+                tags: hydra_core::artifact::dedup_tags(
+                    request.tags.iter().map(|tag| tag.trim().to_string()),
+                ),
+                task: None,
+                variants: Vec::new(),
+                base_ref: "HEAD".to_string(),
+                unsafe_mode: false,
+                unsafe_consent: None,
+                allow_experimental_adapters: request.allow_experimental,
+                run_id: Some(run_uuid),
+                deterministic: false,
+                lock: LockMode::Wait,
+                stop_on_first_success: false,
+                skip_baseline: false,
+                cancel: cancel.clone(),
+                approvals,
+                event_sinks: EventSinks::new(),
+            },
+            Arc::new(move |event| {
+                let _ = event_tx.send(event);
+            }),
+        )
+        .await;
+    // The sink, and with it the sender, is dropped once the race returns.
+    let _ = forwarder.await;
 
-    match output {
-        Ok(output) if output.status.success() => match parse_cli_race_summary(&output.stdout) {
-            Ok(result) => {
-                state.mark_completed(&run_id, result).await;
-                emit_orchestrator_event(
-                    &state,
-                    &run_id,
-                    "race_process_completed",
-                    serde_json::json!({}),
-                )
-                .await;
-            }
-            Err(err) => {
-                state.mark_failed(&run_id, err.message.clone()).await;
-                emit_orchestrator_event(
-                    &state,
-                    &run_id,
-                    "race_process_failed",
-                    serde_json::json!({ "error": err.message }),
-                )
+    match result {
+        _ if cancel.is_cancelled() => {
+            state.mark_cancelled(&run_id).await;
+            emit_orchestrator_event(&state, &run_id, "race_cancelled", serde_json::json!({})).await;
+        }
+        Ok(outcome) if outcome.status == RunStatus::Completed => {
+            state
+                .mark_completed(&run_id, RaceResult::from(&outcome))
                 .await;
+            emit_orchestrator_event(&state, &run_id, "race_completed", serde_json::json!({})).await;
+            for notice in notifications::race_finished(&outcome) {
+                notifications::notify(&app, &state, notice).await;
             }
-        },
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-            let message = if stderr.is_empty() {
-                format!("race command exited with status {}", output.status)
-            } else {
-                format!("race command failed: {}", stderr)
-            };
+        }
+        Ok(outcome) => {
+            let message = format!(
+                "race finished with status {}",
+                run_status_label(&outcome.status)
+            );
             state.mark_failed(&run_id, message.clone()).await;
             emit_orchestrator_event(
                 &state,
                 &run_id,
-                "race_process_failed",
+                "race_failed",
                 serde_json::json!({ "error": message }),
             )
             .await;
+            for notice in notifications::race_finished(&outcome) {
+                notifications::notify(&app, &state, notice).await;
+            }
         }
         Err(err) => {
-            let message = format!("failed to execute race command: {err}");
+            let message = format!("race failed: {err}");
             state.mark_failed(&run_id, message.clone()).await;
             emit_orchestrator_event(
                 &state,
                 &run_id,
-                "race_process_failed",
+                "race_failed",
                 serde_json::json!({ "error": message }),
             )
             .await;
+            notifications::notify(&app, &state, notifications::race_failed(&message)).await;
         }
     }
 }
 
-fn parse_cli_race_summary(stdout: &[u8]) -> Result<RaceResult, IpcError> {
-    let json: serde_json::Value = serde_json::from_slice(stdout)
-        .map_err(|e| IpcError::internal(format!("failed to parse race JSON output: {e}")))?;
-
-    let run_id = json
-        .get("run_id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| IpcError::internal("race JSON output missing run_id"))?
-        .to_string();
-
-    let status = json
-        .get("status")
-        .and_then(|v| v.as_str())
-        .map(normalize_status)
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let race_duration_ms = json
-        .get("duration_ms")
-        .and_then(|v| v.as_u64())
-        .or_else(|| {
-            json.get("health")
-                .and_then(|h| h.get("duration_ms"))
-                .and_then(|v| v.as_u64())
-        });
-
-    let total_cost = json.get("total_cost").and_then(|v| v.as_f64()).or_else(|| {
-        json.get("cost")
-            .and_then(|c| c.get("estimated_cost_usd"))
-            .and_then(|v| v.as_f64())
-    });
-
-    let agents = json
-        .get("agents")
-        .and_then(|v| v.as_array())
-        .map(|items| {
-            items
-                .iter()
-                .map(|item| {
-                    let score_obj = item.get("score");
-
-                    let dimensions = score_obj
-                        .and_then(|s| s.get("dimensions"))
-                        .and_then(|d| d.as_array())
-                        .map(|dims| {
-                            dims.iter()
-                                .filter_map(|dim| {
-                                    let name = dim.get("name")?.as_str()?.to_string();
-                                    let score = dim.get("score")?.as_f64()?;
-                                    let evidence = dim
-                                        .get("evidence")
-                                        .cloned()
-                                        .unwrap_or(serde_json::json!({}));
-                                    Some(DimensionScoreIpc {
-                                        name,
-                                        score,
-                                        evidence,
-                                    })
-                                })
-                                .collect::<Vec<_>>()
-                        })
-                        .unwrap_or_default();
-
-                    let gate_failures = score_obj
-                        .and_then(|s| s.get("gate_failures"))
-                        .and_then(|g| g.as_array())
-                        .map(|arr| {
-                            arr.iter()
-                                .filter_map(|v| v.as_str().map(String::from))
-                                .collect::<Vec<_>>()
-                        })
-                        .unwrap_or_default();
-
-                    AgentResult {
-                        agent_key: item
-                            .get("agent")
-                            .or_else(|| item.get("agent_key"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("unknown")
-                            .to_string(),
-                        status: item
-                            .get("status")
-                            .and_then(|v| v.as_str())
-                            .map(normalize_status)
-                            .unwrap_or_else(|| "unknown".to_string()),
-                        duration_ms: item.get("duration_ms").and_then(|v| v.as_u64()),
-                        score: score_obj
-                            .and_then(|v| v.get("composite"))
-                            .and_then(|v| v.as_f64()),
-                        mergeable: score_obj
-                            .and_then(|v| v.get("mergeable"))
-                            .and_then(|v| v.as_bool()),
-                        gate_failures,
-                        dimensions,
-                    }
-                })
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default();
-
-    Ok(RaceResult {
-        run_id,
-        status,
-        agents,
-        duration_ms: race_duration_ms,
-        total_cost,
-    })
-}
-
-fn normalize_status(raw: &str) -> String {
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        return "unknown".to_string();
-    }
-
-    let mut out = String::with_capacity(trimmed.len() + 4);
-    let mut prev_was_lower_or_digit = false;
-
-    for ch in trimmed.chars() {
-        if ch.is_ascii_alphanumeric() {
-            if ch.is_ascii_uppercase() && prev_was_lower_or_digit && !out.ends_with('_') {
-                out.push('_');
-            }
-            out.push(ch.to_ascii_lowercase());
-            prev_was_lower_or_digit = ch.is_ascii_lowercase() || ch.is_ascii_digit();
-        } else {
-            if !out.is_empty() && !out.ends_with('_') {
-                out.push('_');
-            }
-            prev_was_lower_or_digit = false;
-        }
-    }
-
-    while out.ends_with('_') {
-        out.pop();
-    }
-
-    if out.is_empty() {
-        "unknown".to_string()
-    } else {
-        out
+/// `hydra.toml` found from the repository root, as the CLI would load it;
+/// the app's own config, with scoring commands detected from the project,
+/// when there is none.
+fn race_config(repo_root: &Path, app_config: HydraConfig) -> Result<HydraConfig, IpcErrorPayload> {
+    match hydra_core::config::discover_config(repo_root) {
+        Some(path) => hydra_core::config::load_config(&path).map_err(|e| {
+            IpcErrorPayload::validation(format!("failed to load {}: {e}", path.display()))
+        }),
+        None => Ok(app_config.autodetect(repo_root)),
     }
 }
 
-fn build_race_command(repo_root: &Path, run_id: &str, request: &RaceRequest) -> TokioCommand {
-    let mut cmd = if let Ok(bin) = std::env::var("HYDRA_CLI_BIN") {
-        TokioCommand::new(bin)
-    } else if binary_available("hydra") {
-        TokioCommand::new("hydra")
-    } else {
-        let mut cargo = TokioCommand::new("cargo");
-        cargo.args(cargo_hydra_cli_parts_without_binary());
-        cargo
+/// Map an orchestrator event to the stream event the frontend renders,
+/// using the same event types and payloads as the run's `events.jsonl`.
+fn race_stream_event(run_id: &str, event: RaceEvent) -> AgentStreamEvent {
+    let (agent_key, event_type, data) = match event {
+        RaceEvent::Queued { holder } => {
+            (None, "run_queued", serde_json::json!({ "holder": holder }))
+        }
+        RaceEvent::RunStarted { run_id, agents } => (
+            None,
+            "run_started",
+            serde_json::json!({ "run_id": run_id.to_string(), "agents": agents }),
+        ),
+        RaceEvent::StaleBase { freshness } => (
+            None,
+            "stale_base",
+            serde_json::json!({ "freshness": freshness }),
+        ),
+        RaceEvent::BaselineSkipped { reused_from } => (
+            None,
+            "baseline_skipped",
+            serde_json::json!({ "reused_from": reused_from.map(|id| id.to_string()) }),
+        ),
+        RaceEvent::PromptInjectionDetected { findings, stripped } => (
+            None,
+            "prompt_injection_detected",
+            serde_json::json!({ "findings": findings, "stripped": stripped }),
+        ),
+        RaceEvent::AgentStarted { agent_key } => {
+            (Some(agent_key), "agent_started", serde_json::json!({}))
+        }
+        RaceEvent::AgentOutput {
+            agent_key,
+            stream,
+            line,
+        } => (
+            Some(agent_key),
+            match stream {
+                OutputStream::Stdout => "agent_stdout",
+                OutputStream::Stderr => "agent_stderr",
+            },
+            serde_json::json!({ "line": line }),
+        ),
+        RaceEvent::AgentEvent {
+            agent_key, event, ..
+        } => (
+            Some(agent_key),
+            "agent_stdout",
+            serde_json::to_value(&event).unwrap_or_default(),
+        ),
+        RaceEvent::AgentProgress {
+            agent_key,
+            progress,
+        } => (
+            Some(agent_key),
+            "agent_progress",
+            serde_json::to_value(&progress).unwrap_or_default(),
+        ),
+        RaceEvent::FileEdited {
+            agent_key,
+            path,
+            operation,
+            files,
+            churn,
+            scope,
+        } => (
+            Some(agent_key),
+            "file_edited",
+            serde_json::json!({
+                "path": path,
+                "operation": operation,
+                "files": files,
+                "churn": churn,
+                "scope": scope,
+            }),
+        ),
+        RaceEvent::ApprovalRequested {
+            agent_key,
+            approval_id,
+            action,
+        } => (
+            Some(agent_key),
+            "approval_requested",
+            serde_json::json!({ "approval_id": approval_id.to_string(), "action": action }),
+        ),
+        RaceEvent::ApprovalResolved {
+            agent_key,
+            approval_id,
+            decision,
+        } => (
+            Some(agent_key),
+            "approval_resolved",
+            serde_json::json!({ "approval_id": approval_id.to_string(), "decision": decision }),
+        ),
+        RaceEvent::AgentFinished {
+            agent_key,
+            status,
+            error,
+            duration_ms,
+            total_tokens,
+        } => (
+            Some(agent_key),
+            if status == RunStatus::Completed {
+                "agent_completed"
+            } else {
+                "agent_failed"
+            },
+            serde_json::json!({
+                "status": format!("{status:?}"),
+                "error": error,
+                "duration_ms": duration_ms,
+                "total_tokens": total_tokens,
+            }),
+        ),
+        RaceEvent::BudgetWarning { agent_key, warning } => {
+            let mut data = serde_json::to_value(&warning).unwrap_or_default();
+            data["message"] = serde_json::Value::String(warning.to_string());
+            (Some(agent_key), "budget_warning", data)
+        }
+        RaceEvent::StoppingEarly {
+            agent_key,
+            composite,
+        } => (
+            Some(agent_key),
+            "race_stopped_early",
+            serde_json::json!({ "composite": composite }),
+        ),
+        RaceEvent::ScoringStarted => (None, "score_started", serde_json::json!({})),
+        RaceEvent::ScoringFinished {
+            ranked_agents,
+            error,
+        } => (
+            None,
+            "score_finished",
+            serde_json::json!({ "ranked_agents": ranked_agents, "error": error }),
+        ),
+        RaceEvent::RunFinished { status } => (
+            None,
+            if status == RunStatus::Completed {
+                "run_completed"
+            } else {
+                "run_failed"
+            },
+            serde_json::json!({ "status": format!("{status:?}") }),
+        ),
     };
-
-    let mut args = vec![
-        "race".to_string(),
-        "--json".to_string(),
-        "--prompt".to_string(),
-        request.task_prompt.clone(),
-        "--base-ref".to_string(),
-        "HEAD".to_string(),
-        "--agents".to_string(),
-        request.agents.join(","),
-        "--run-id".to_string(),
-        run_id.to_string(),
-    ];
-    if request.allow_experimental {
-        args.push("--allow-experimental-adapters".to_string());
+    AgentStreamEvent {
+        run_id: run_id.to_string(),
+        agent_key: agent_key.unwrap_or_else(|| "system".to_string()),
+        event_type: event_type.to_string(),
+        data,
+        timestamp: chrono::Utc::now().to_rfc3339(),
     }
-
-    cmd.args(args);
-    cmd.current_dir(repo_root);
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
-    cmd
 }
 
 fn binary_available(program: &str) -> bool {
@@ -562,7 +804,7 @@ fn cargo_hydra_cli_parts_without_binary() -> Vec<String> {
 fn resolve_existing_directory(
     cwd: Option<&str>,
     context_message: &str,
-) -> Result<PathBuf, IpcError> {
+) -> Result<PathBuf, IpcErrorPayload> {
     let selected_cwd = cwd.and_then(|raw| {
         let trimmed = raw.trim();
         if trimmed.is_empty() {
@@ -579,17 +821,18 @@ fn resolve_existing_directory(
     let target_dir = if let Some(path) = selected_cwd {
         PathBuf::from(path)
     } else {
-        std::env::current_dir()
-            .map_err(|e| IpcError::internal(format!("failed to resolve current directory: {e}")))?
+        std::env::current_dir().map_err(|e| {
+            IpcErrorPayload::internal(format!("failed to resolve current directory: {e}"))
+        })?
     };
 
     if !target_dir.exists() {
-        return Err(IpcError::validation(format!(
+        return Err(IpcErrorPayload::validation(format!(
             "{context_message}{detail}. Path does not exist."
         )));
     }
     if !target_dir.is_dir() {
-        return Err(IpcError::validation(format!(
+        return Err(IpcErrorPayload::validation(format!(
             "{context_message}{detail}. Path is not a directory."
         )));
     }
@@ -597,23 +840,26 @@ fn resolve_existing_directory(
     Ok(target_dir)
 }
 
-fn canonicalize_existing_directory(path: &Path) -> Result<PathBuf, IpcError> {
+fn canonicalize_existing_directory(path: &Path) -> Result<PathBuf, IpcErrorPayload> {
     path.canonicalize().map_err(|e| {
-        IpcError::validation(format!(
+        IpcErrorPayload::validation(format!(
             "Unable to resolve workspace directory '{}' to an absolute path: {e}",
             path.display()
         ))
     })
 }
 
-fn resolve_repo_root(cwd: Option<&str>, not_repo_message: &str) -> Result<PathBuf, IpcError> {
+fn resolve_repo_root(
+    cwd: Option<&str>,
+    not_repo_message: &str,
+) -> Result<PathBuf, IpcErrorPayload> {
     resolve_repo_root_internal(cwd, not_repo_message, false)
 }
 
 fn resolve_repo_root_with_auto_init(
     cwd: Option<&str>,
     not_repo_message: &str,
-) -> Result<PathBuf, IpcError> {
+) -> Result<PathBuf, IpcErrorPayload> {
     resolve_repo_root_internal(cwd, not_repo_message, true)
 }
 
@@ -621,7 +867,7 @@ fn resolve_repo_root_internal(
     cwd: Option<&str>,
     not_repo_message: &str,
     allow_auto_init: bool,
-) -> Result<PathBuf, IpcError> {
+) -> Result<PathBuf, IpcErrorPayload> {
     let selected_cwd = cwd.and_then(|raw| {
         let trimmed = raw.trim();
         if trimmed.is_empty() {
@@ -642,7 +888,7 @@ fn resolve_repo_root_internal(
     // Only auto-init when the user explicitly selected a workspace path.
     if allow_auto_init && selected_cwd.is_some() {
         initialize_git_repository(&target_dir).map_err(|init_err| {
-            IpcError::validation(format!(
+            IpcErrorPayload::validation(format!(
                 "{not_repo_message}{detail}. Auto-initialize failed: {init_err}"
             ))
         })?;
@@ -651,28 +897,32 @@ fn resolve_repo_root_internal(
             return Ok(root);
         }
 
-        return Err(IpcError::validation(format!(
+        return Err(IpcErrorPayload::validation(format!(
             "{not_repo_message}{detail}. Repository was initialized but root discovery still failed."
         )));
     }
 
-    Err(IpcError::validation(format!("{not_repo_message}{detail}")))
+    Err(IpcErrorPayload::validation(format!(
+        "{not_repo_message}{detail}"
+    )))
 }
 
-fn try_repo_root_from_dir(target_dir: &Path) -> Result<PathBuf, IpcError> {
+fn try_repo_root_from_dir(target_dir: &Path) -> Result<PathBuf, IpcErrorPayload> {
     let output = std::process::Command::new("git")
         .args(["rev-parse", "--show-toplevel"])
         .current_dir(target_dir)
         .output()
-        .map_err(|e| IpcError::internal(format!("failed to execute git: {e}")))?;
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to execute git: {e}")))?;
 
     if !output.status.success() {
-        return Err(IpcError::validation("not inside a git repository"));
+        return Err(IpcErrorPayload::validation("not inside a git repository"));
     }
 
     let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
     if root.is_empty() {
-        return Err(IpcError::internal("git returned empty repository root"));
+        return Err(IpcErrorPayload::internal(
+            "git returned empty repository root",
+        ));
     }
 
     Ok(PathBuf::from(root))
@@ -790,115 +1040,6 @@ fn ensure_hydra_gitignore(target_dir: &Path) -> Result<(), String> {
     std::fs::write(&gitignore_path, content).map_err(|e| format!("failed to write .gitignore: {e}"))
 }
 
-async fn tail_run_events_file(
-    state: AppStateHandle,
-    run_id: String,
-    events_path: PathBuf,
-    agents_dir: PathBuf,
-    stop: Arc<AtomicBool>,
-) {
-    let mut consumed_lines = 0usize;
-    let mut consumed_agent_lines: HashMap<PathBuf, usize> = HashMap::new();
-
-    loop {
-        consumed_lines =
-            emit_new_events_from_file(&state, &run_id, &events_path, consumed_lines, false).await;
-        emit_new_agent_output_events_from_dir(
-            &state,
-            &run_id,
-            &agents_dir,
-            &mut consumed_agent_lines,
-        )
-        .await;
-
-        if stop.load(Ordering::Relaxed) {
-            break;
-        }
-
-        sleep(Duration::from_millis(120)).await;
-    }
-
-    let _ = emit_new_events_from_file(&state, &run_id, &events_path, consumed_lines, false).await;
-    emit_new_agent_output_events_from_dir(&state, &run_id, &agents_dir, &mut consumed_agent_lines)
-        .await;
-}
-
-async fn emit_new_events_from_file(
-    state: &AppStateHandle,
-    run_id: &str,
-    events_path: &Path,
-    consumed_lines: usize,
-    output_only: bool,
-) -> usize {
-    let Ok(content) = tokio::fs::read_to_string(events_path).await else {
-        return consumed_lines;
-    };
-
-    let lines: Vec<&str> = content.lines().collect();
-    if lines.len() <= consumed_lines {
-        return lines.len();
-    }
-
-    for line in lines.iter().skip(consumed_lines) {
-        if let Some(event) = parse_run_event_line(run_id, line, output_only) {
-            state.append_event(run_id, event).await;
-        }
-    }
-
-    lines.len()
-}
-
-async fn emit_new_agent_output_events_from_dir(
-    state: &AppStateHandle,
-    run_id: &str,
-    agents_dir: &Path,
-    consumed_lines_by_file: &mut HashMap<PathBuf, usize>,
-) {
-    let Ok(mut entries) = tokio::fs::read_dir(agents_dir).await else {
-        return;
-    };
-
-    let mut event_paths = Vec::new();
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        let path = entry.path().join("events.jsonl");
-        if tokio::fs::metadata(&path).await.is_ok() {
-            event_paths.push(path);
-        }
-    }
-    event_paths.sort();
-
-    for path in &event_paths {
-        let consumed = consumed_lines_by_file.get(path).copied().unwrap_or(0);
-        let next = emit_new_events_from_file(state, run_id, path, consumed, true).await;
-        consumed_lines_by_file.insert(path.clone(), next);
-    }
-
-    consumed_lines_by_file.retain(|path, _| event_paths.contains(path));
-}
-
-fn parse_run_event_line(run_id: &str, line: &str, output_only: bool) -> Option<AgentStreamEvent> {
-    if line.trim().is_empty() {
-        return None;
-    }
-
-    let event: RunEvent = serde_json::from_str(line).ok()?;
-    if output_only && !matches!(event.kind, EventKind::AgentStdout | EventKind::AgentStderr) {
-        return None;
-    }
-    let event_type = serde_json::to_value(&event.kind)
-        .ok()
-        .and_then(|v| v.as_str().map(ToOwned::to_owned))
-        .unwrap_or_else(|| "unknown".to_string());
-
-    Some(AgentStreamEvent {
-        run_id: run_id.to_string(),
-        agent_key: event.agent_key.unwrap_or_else(|| "system".to_string()),
-        event_type,
-        data: event.data,
-        timestamp: event.timestamp.to_rfc3339(),
-    })
-}
-
 async fn emit_orchestrator_event(
     state: &AppStateHandle,
     run_id: &str,
@@ -946,20 +1087,18 @@ async fn resolve_interactive_launch_paths(
     requested_cwd: Option<&str>,
     session_id: &str,
     agent_key: &str,
-) -> Result<InteractiveLaunchPaths, String> {
+) -> Result<InteractiveLaunchPaths, IpcErrorPayload> {
     let source_root = resolve_existing_directory(
         requested_cwd,
         "Not inside a git repository; cannot start interactive session",
-    )
-    .map_err(|e| e.to_string())?;
-    let source_root = canonicalize_existing_directory(&source_root).map_err(|e| e.to_string())?;
+    )?;
+    let source_root = canonicalize_existing_directory(&source_root)?;
 
     let repo_root = resolve_repo_root_with_auto_init(
         requested_cwd,
         "Not inside a git repository; cannot start interactive session",
-    )
-    .map_err(|e| e.to_string())?;
-    let repo_root = canonicalize_existing_directory(&repo_root).map_err(|e| e.to_string())?;
+    )?;
+    let repo_root = canonicalize_existing_directory(&repo_root)?;
 
     let source_root_display = source_root.to_string_lossy().to_string();
     let repo_root_display = repo_root.to_string_lossy().to_string();
@@ -974,7 +1113,7 @@ async fn resolve_interactive_launch_paths(
 
     if has_same_source_running {
         let run_id = uuid::Uuid::parse_str(session_id).map_err(|e| {
-            IpcError::internal(format!("invalid session_id for run id: {e}")).to_string()
+            IpcErrorPayload::internal(format!("invalid session_id for run id: {e}"))
         })?;
         let wt_base = repo_root
             .join(".hydra")
@@ -985,11 +1124,10 @@ async fn resolve_interactive_launch_paths(
             .create(run_id, agent_key, "HEAD")
             .await
             .map_err(|e| {
-                IpcError::launch_error(format!(
+                IpcErrorPayload::launch_error(format!(
                     "Failed to create interactive worktree for '{}': {}",
                     agent_key, e
                 ))
-                .to_string()
             })?;
 
         worktree_path = Some(wt_info.path.to_string_lossy().to_string());
@@ -1036,9 +1174,9 @@ pub async fn start_interactive_session(
     state: State<'_, AppState>,
     app: tauri::AppHandle,
     request: InteractiveSessionRequest,
-) -> Result<InteractiveSessionStarted, String> {
+) -> Result<InteractiveSessionStarted, IpcErrorPayload> {
     if request.agent_key.trim().is_empty() {
-        return Err(IpcError::validation("agent_key cannot be empty").to_string());
+        return Err(IpcErrorPayload::validation("agent_key cannot be empty"));
     }
 
     let session_id = uuid::Uuid::new_v4().to_string();
@@ -1053,14 +1191,13 @@ pub async fn start_interactive_session(
     let adapter = match registry.resolve(&request.agent_key, request.allow_experimental) {
         Ok(a) => a,
         Err(hydra_core::adapter::RegistryError::ExperimentalBlocked { key }) => {
-            return Err(IpcError::experimental_blocked(format!(
+            return Err(IpcErrorPayload::experimental_blocked(format!(
                 "Adapter '{}' is experimental. Enable 'Allow Experimental' and confirm the risk acknowledgment to use it in interactive mode.",
                 key
-            ))
-            .to_string());
+            )));
         }
         Err(e) => {
-            return Err(IpcError::adapter_error(e.to_string()).to_string());
+            return Err(IpcErrorPayload::adapter_error(e.to_string()));
         }
     };
 
@@ -1070,57 +1207,32 @@ pub async fn start_interactive_session(
         let reason = detect
             .error
             .unwrap_or_else(|| format!("status is {:?}", detect.status));
-        return Err(IpcError::safety_gate(format!(
+        return Err(IpcErrorPayload::safety_gate(format!(
             "Adapter '{}' is not available for interactive sessions: {}. Run 'hydra doctor' to diagnose.",
             request.agent_key, reason
-        ))
-        .to_string());
+        )));
     }
 
     let supported_flags = detect.supported_flags.clone();
 
     // M4.5: Unsafe mode policy — block unless explicitly opted in
-    if request.unsafe_mode
-        && !adapter_supports_interactive_unsafe_mode(adapter.key(), &supported_flags)
-    {
-        return Err(IpcError::unsafe_blocked(format!(
+    if request.unsafe_mode && !supports_interactive_unsafe_mode(adapter.key(), &supported_flags) {
+        return Err(IpcErrorPayload::unsafe_blocked(format!(
             "Adapter '{}' does not support interactive unsafe mode. {}",
             request.agent_key,
             unsafe_mode_requirement_hint(adapter.key())
-        ))
-        .to_string());
+        )));
     }
 
     // P4.9.4: Direct external CLI invocation -- resolve binary and build
     // minimal interactive args without race-mode flags.
-    let configured_path = {
-        let adapter_key = adapter.key();
-        match adapter_key {
-            "claude" => config.adapters.claude.clone(),
-            "codex" => config.adapters.codex.clone(),
-            "cursor-agent" => config.adapters.cursor.clone(),
-            _ => None,
-        }
-    };
-
-    let binary_candidates: &[&str] = match adapter.key() {
-        "claude" => &["claude"],
-        "codex" => &["codex"],
-        "cursor-agent" => &["cursor-agent", "cursor"],
-        _ => &[],
-    };
-
-    let binary_path = hydra_core::adapter::resolve_binary(
-        configured_path.as_deref(),
-        binary_candidates,
-    )
-    .ok_or_else(|| {
-        IpcError::binary_missing(format!(
-            "Adapter '{}' binary not found in PATH. Install it or configure the path in hydra.toml.",
-            request.agent_key
-        ))
-        .to_string()
-    })?;
+    let binary_path =
+        resolve_interactive_binary(adapter.key(), &config.adapters).ok_or_else(|| {
+            IpcErrorPayload::binary_missing(format!(
+                "Adapter '{}' binary not found in PATH. Install it or configure the path in hydra.toml.",
+                request.agent_key
+            ))
+        })?;
 
     let interactive_args = build_interactive_args(
         adapter.key(),
@@ -1140,6 +1252,26 @@ pub async fn start_interactive_session(
     )
     .await?;
 
+    let trusted =
+        TrustStore::user_default().and_then(|t| t.require_trusted(&launch_paths.repo_root));
+    if let Err(e) = trusted {
+        if let Some(worktree) = &launch_paths.managed_worktree {
+            cleanup_managed_worktree_on_launch_failure(worktree).await;
+        }
+        return Err(e.into());
+    }
+
+    if request.unsafe_mode {
+        let store = ConsentStore::new(&launch_paths.repo_root.join(".hydra"));
+        let used_for = format!("interactive {} {session_id}", request.agent_key);
+        if let Err(e) = store.authorize(request.unsafe_consent.as_deref(), &used_for) {
+            if let Some(worktree) = &launch_paths.managed_worktree {
+                cleanup_managed_worktree_on_launch_failure(worktree).await;
+            }
+            return Err(e.into());
+        }
+    }
+
     let pty_config = hydra_core::supervisor::pty::PtySessionConfig {
         program: binary_path.to_string_lossy().to_string(),
         args: interactive_args,
@@ -1156,13 +1288,19 @@ pub async fn start_interactive_session(
             if let Some(worktree) = &launch_paths.managed_worktree {
                 cleanup_managed_worktree_on_launch_failure(worktree).await;
             }
-            return Err(IpcError::launch_error(format!("PTY spawn failed: {e}")).to_string());
+            return Err(IpcErrorPayload::launch_error(format!(
+                "PTY spawn failed: {e}"
+            )));
         }
     };
 
     // M4.6: Initialize session artifact writer
     let hydra_root = launch_paths.repo_root.join(".hydra");
     let is_experimental = adapter.tier() == hydra_core::adapter::AdapterTier::Experimental;
+    let app_config = state.config.lock().await.clone();
+    let redactor = race_config(&launch_paths.repo_root, app_config)
+        .map(|config| SecretRedactor::from_config(&config.security.redaction))
+        .unwrap_or_default();
     let artifact_writer = match hydra_core::artifact::SessionArtifactWriter::init(
         &hydra_root,
         &session_id,
@@ -1172,7 +1310,7 @@ pub async fn start_interactive_session(
         request.unsafe_mode,
         is_experimental,
     ) {
-        Ok(w) => Some(w),
+        Ok(w) => Some(w.with_redactor(redactor)),
         Err(e) => {
             tracing::warn!(error = %e, "failed to initialize session artifact writer — session will proceed without artifact persistence");
             None
@@ -1236,13 +1374,15 @@ pub async fn poll_interactive_events(
     state: State<'_, AppState>,
     session_id: String,
     cursor: u64,
-) -> Result<InteractiveEventBatch, String> {
+) -> Result<InteractiveEventBatch, IpcErrorPayload> {
     let interactive = state.interactive.clone();
     let Some((events, next_cursor, done, status, error)) = interactive
         .poll_events(&session_id, cursor, MAX_INTERACTIVE_EVENTS_PER_POLL)
         .await
     else {
-        return Err(IpcError::not_found(format!("session '{session_id}' not found")).to_string());
+        return Err(IpcErrorPayload::not_found(format!(
+            "session '{session_id}' not found"
+        )));
     };
 
     Ok(InteractiveEventBatch {
@@ -1255,12 +1395,26 @@ pub async fn poll_interactive_events(
     })
 }
 
+/// Buffered terminal output and status for a running session, so a reloaded
+/// frontend can redraw the terminal and resume polling from `next_cursor`.
+#[tauri::command]
+pub async fn get_interactive_session_snapshot(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<InteractiveSessionSnapshot, IpcErrorPayload> {
+    state
+        .interactive
+        .snapshot(&session_id)
+        .await
+        .ok_or_else(|| IpcErrorPayload::not_found(format!("session '{session_id}' not found")))
+}
+
 #[tauri::command]
 pub async fn write_interactive_input(
     state: State<'_, AppState>,
     session_id: String,
     input: String,
-) -> Result<InteractiveWriteAck, String> {
+) -> Result<InteractiveWriteAck, IpcErrorPayload> {
     let interactive = state.interactive.clone();
     match interactive.write_input(&session_id, input.as_bytes()).await {
         Ok(()) => Ok(InteractiveWriteAck {
@@ -1282,7 +1436,7 @@ pub async fn resize_interactive_terminal(
     session_id: String,
     cols: u16,
     rows: u16,
-) -> Result<InteractiveResizeAck, String> {
+) -> Result<InteractiveResizeAck, IpcErrorPayload> {
     let interactive = state.interactive.clone();
     match interactive.resize(&session_id, cols, rows).await {
         Ok(()) => Ok(InteractiveResizeAck {
@@ -1303,95 +1457,529 @@ pub async fn resize_interactive_terminal(
 }
 
 #[tauri::command]
-pub async fn stop_interactive_session(
+pub async fn stop_interactive_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<InteractiveStopResult, IpcErrorPayload> {
+    let interactive = state.interactive.clone();
+    let (was_running, status) = interactive.stop_session(&session_id).await?;
+    Ok(InteractiveStopResult {
+        session_id,
+        status,
+        was_running,
+    })
+}
+
+#[tauri::command]
+pub async fn remove_interactive_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<InteractiveRemoveResult, IpcErrorPayload> {
+    let interactive = state.interactive.clone();
+    let status = interactive.remove_session(&session_id).await?;
+    Ok(InteractiveRemoveResult {
+        session_id,
+        status,
+        removed: true,
+    })
+}
+
+/// Score a finished session's changes and register them as a one-agent run,
+/// so they can be diffed, previewed and merged like a race candidate.
+#[tauri::command]
+pub async fn promote_interactive_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<PromotedCandidate, IpcErrorPayload> {
+    let source = state.interactive.promotion_source(&session_id).await?;
+
+    let repo_root = PathBuf::from(&source.repo_root);
+    let app_config = state.config.lock().await.clone();
+    let config = race_config(&repo_root, app_config)?;
+    let base_ref = hydra_core::git_exec::run_git(&["rev-parse", "HEAD"], &repo_root)
+        .await
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to resolve HEAD: {e}")))?
+        .stdout
+        .trim()
+        .to_string();
+
+    let outcome = Orchestrator::new(repo_root, config)
+        .promote(PromoteOptions {
+            agent_key: source.agent_key,
+            source_path: PathBuf::from(source.source_path),
+            base_ref,
+            description: format!("Promoted from interactive session {session_id}"),
+            run_id: None,
+        })
+        .await?;
+
+    Ok(PromotedCandidate::new(session_id, &outcome))
+}
+
+#[tauri::command]
+pub async fn list_interactive_sessions(
+    state: State<'_, AppState>,
+) -> Result<Vec<InteractiveSessionSummary>, IpcErrorPayload> {
+    let interactive = state.interactive.clone();
+    let entries = interactive.list_sessions().await;
+    Ok(entries
+        .into_iter()
+        .map(
+            |(
+                sid,
+                agent_key,
+                status,
+                started_at,
+                event_count,
+                source_root,
+                repo_root,
+                effective_cwd,
+                worktree_path,
+            )| InteractiveSessionSummary {
+                session_id: sid,
+                agent_key,
+                status,
+                started_at,
+                event_count,
+                source_root,
+                repo_root,
+                effective_cwd,
+                worktree_path,
+            },
+        )
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_interactive_transport_diagnostics(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<InteractiveTransportDiagnostics, IpcErrorPayload> {
+    let interactive = state.interactive.clone();
+    let Some((push_emit_error_count, last_push_emit_error, last_push_emit_at)) =
+        interactive.push_transport_diagnostics(&session_id).await
+    else {
+        return Err(IpcErrorPayload::not_found(format!(
+            "session '{session_id}' not found"
+        )));
+    };
+
+    Ok(InteractiveTransportDiagnostics {
+        session_id,
+        push_emit_error_count,
+        last_push_emit_error,
+        last_push_emit_at,
+    })
+}
+
+/// Page through a session's recorded terminal output. Works for sessions
+/// from earlier app launches, since it reads `.hydra/sessions` directly.
+#[tauri::command]
+pub async fn get_session_transcript(
+    session_id: String,
+    offset: Option<u64>,
+    limit: Option<usize>,
+    cwd: Option<String>,
+) -> Result<SessionTranscriptChunk, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot load session transcript",
+    )?;
+    sessions::session_transcript(
+        &repo_root.join(".hydra"),
+        &session_id,
+        offset.unwrap_or(0),
+        limit,
+    )
+}
+
+#[tauri::command]
+pub async fn get_session_summary(
+    session_id: String,
+    cwd: Option<String>,
+) -> Result<SessionTranscriptSummary, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot load session summary",
+    )?;
+    sessions::session_summary(&repo_root.join(".hydra"), &session_id)
+}
+
+// ---------------------------------------------------------------------------
+// Run history commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn list_runs(
+    cwd: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<Vec<RunSummary>, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot list runs",
+    )?;
+    history::list_runs(&repo_root.join(".hydra"), &tags.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_run_detail(
+    run_id: String,
+    cwd: Option<String>,
+) -> Result<RunDetail, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot load run",
+    )?;
+    history::run_detail(&repo_root.join(".hydra"), &run_id)
+}
+
+#[tauri::command]
+pub async fn delete_run(
+    state: State<'_, AppState>,
+    run_id: String,
+    cwd: Option<String>,
+) -> Result<RunDeleted, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot delete run",
+    )?;
+    let state_handle = state.handle();
+    if state_handle.race_is_running(&run_id).await {
+        return Err(IpcErrorPayload::validation(format!(
+            "run {run_id} is still running; cancel it before deleting"
+        )));
+    }
+    history::delete_run(&repo_root.join(".hydra"), &run_id)?;
+    state_handle.forget_race(&run_id).await;
+    Ok(RunDeleted { run_id })
+}
+
+/// Write a run's artifacts to a `.tar.gz` bundle for sharing. Defaults to
+/// `.hydra/exports` when `dest_dir` is not given.
+#[tauri::command]
+pub async fn export_run_bundle(
+    run_id: String,
+    dest_dir: Option<String>,
+    cwd: Option<String>,
+) -> Result<RunBundle, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot export run",
+    )?;
+    history::export_run(
+        &repo_root.join(".hydra"),
+        &run_id,
+        dest_dir.as_deref().map(Path::new),
+    )
+}
+
+/// Find `query` in a run's event logs, optionally narrowed by agent, event
+/// kind and time range.
+#[tauri::command]
+pub async fn search_run_events(
+    run_id: String,
+    query: String,
+    filters: Option<RunEventSearchFilters>,
+    cwd: Option<String>,
+) -> Result<RunEventSearch, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot search run",
+    )?;
+    let filters = filters.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        search::search_run_events(&repo_root.join(".hydra"), &run_id, &query, &filters)
+    })
+    .await
+    .map_err(|e| IpcErrorPayload::internal(format!("search task failed: {e}")))?
+}
+
+// ---------------------------------------------------------------------------
+// Cost commands
+// ---------------------------------------------------------------------------
+
+/// Spend across finished runs for `period` (`day`, `week`, `month` or
+/// `all`; default `month`), checked against the configured per-run budget.
+#[tauri::command]
+pub async fn get_cost_summary(
+    state: State<'_, AppState>,
+    period: Option<String>,
+    cwd: Option<String>,
+) -> Result<CostSummary, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot summarize costs",
+    )?;
+    let budget = state.config.lock().await.scoring.budget.clone();
+    costs::cost_summary(
+        &repo_root.join(".hydra"),
+        period.as_deref().unwrap_or("month"),
+        &budget,
+        chrono::Utc::now(),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Config editor commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn get_effective_config(cwd: Option<String>) -> Result<EffectiveConfig, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot load hydra.toml",
+    )?;
+    config_editor::effective_config(&repo_root)
+}
+
+#[tauri::command]
+pub async fn update_config(
+    state: State<'_, AppState>,
+    section: String,
+    values: serde_json::Value,
+    cwd: Option<String>,
+) -> Result<ConfigUpdateResult, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot update hydra.toml",
+    )?;
+    let result = config_editor::update_section(&repo_root, &section, values)?;
+    if result.saved {
+        // Adapter paths and defaults apply to the next probe or race.
+        *state.config.lock().await = result.config.clone();
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_unsafe_consent(
+    cwd: Option<String>,
+) -> Result<UnsafeConsentStatus, IpcErrorPayload> {
+    let store = unsafe_consent_store(cwd.as_deref())?;
+    Ok(UnsafeConsentStatus::from_consent(store.load()?.as_ref()))
+}
+
+/// Grant unsafe mode for `minutes`, or `security.unsafe_consent_minutes`
+/// from the repository's config.
+#[tauri::command]
+pub async fn grant_unsafe_consent(
+    state: State<'_, AppState>,
+    minutes: Option<u64>,
+    cwd: Option<String>,
+) -> Result<UnsafeConsentStatus, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot grant unsafe mode",
+    )?;
+    let minutes = match minutes {
+        Some(0) => return Err(IpcErrorPayload::validation("minutes must be > 0")),
+        Some(minutes) => minutes,
+        None => {
+            let app_config = state.config.lock().await.clone();
+            race_config(&repo_root, app_config)?
+                .security
+                .unsafe_consent_minutes
+        }
+    };
+    let consent = ConsentStore::new(&repo_root.join(".hydra"))
+        .grant(chrono::Duration::minutes(minutes as i64))?;
+    Ok(UnsafeConsentStatus::from_consent(Some(&consent)))
+}
+
+#[tauri::command]
+pub async fn revoke_unsafe_consent(
+    cwd: Option<String>,
+) -> Result<UnsafeConsentStatus, IpcErrorPayload> {
+    let store = unsafe_consent_store(cwd.as_deref())?;
+    store.revoke()?;
+    Ok(UnsafeConsentStatus::from_consent(None))
+}
+
+#[tauri::command]
+pub async fn get_workspace_trust(
+    cwd: Option<String>,
+) -> Result<WorkspaceTrustStatus, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(cwd.as_deref(), "Not inside a git repository")?;
+    let entry = TrustStore::user_default()?.entry(&repo_root)?;
+    Ok(WorkspaceTrustStatus::new(&repo_root, entry.as_ref()))
+}
+
+/// Trust the repository, after the user has confirmed it in the UI.
+#[tauri::command]
+pub async fn trust_workspace(cwd: Option<String>) -> Result<WorkspaceTrustStatus, IpcErrorPayload> {
+    let repo_root = resolve_repo_root_with_auto_init(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot trust it",
+    )?;
+    let entry = TrustStore::user_default()?.trust(&repo_root)?;
+    Ok(WorkspaceTrustStatus::new(&repo_root, Some(&entry)))
+}
+
+#[tauri::command]
+pub async fn revoke_workspace_trust(
+    cwd: Option<String>,
+) -> Result<WorkspaceTrustStatus, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(cwd.as_deref(), "Not inside a git repository")?;
+    TrustStore::user_default()?.revoke(&repo_root)?;
+    Ok(WorkspaceTrustStatus::new(&repo_root, None))
+}
+
+fn unsafe_consent_store(cwd: Option<&str>) -> Result<ConsentStore, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(cwd, "Not inside a git repository; no unsafe mode grant")?;
+    Ok(ConsentStore::new(&repo_root.join(".hydra")))
+}
+
+#[tauri::command]
+pub async fn validate_config_draft(draft: String) -> Result<ConfigValidation, IpcErrorPayload> {
+    Ok(config_editor::validate_draft(&draft))
+}
+
+// ---------------------------------------------------------------------------
+// Notification settings commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn get_notification_settings(
     state: State<'_, AppState>,
-    session_id: String,
-) -> Result<InteractiveStopResult, String> {
-    let interactive = state.interactive.clone();
-    match interactive.stop_session(&session_id).await {
-        Ok((was_running, status)) => Ok(InteractiveStopResult {
-            session_id,
-            status,
-            was_running,
-        }),
-        Err(e) => Err(IpcError::not_found(e).to_string()),
-    }
+) -> Result<NotificationSettings, IpcErrorPayload> {
+    Ok(state.settings.lock().await.settings().notifications.clone())
 }
 
 #[tauri::command]
-pub async fn remove_interactive_session(
+pub async fn update_notification_settings(
     state: State<'_, AppState>,
-    session_id: String,
-) -> Result<InteractiveRemoveResult, String> {
-    let interactive = state.interactive.clone();
-    match interactive.remove_session(&session_id).await {
-        Ok(status) => Ok(InteractiveRemoveResult {
-            session_id,
-            status,
-            removed: true,
-        }),
-        Err(e) => {
-            if e.contains("not found") {
-                Err(IpcError::not_found(e).to_string())
-            } else {
-                Err(IpcError::validation(e).to_string())
-            }
-        }
-    }
+    settings: NotificationSettings,
+) -> Result<NotificationSettings, IpcErrorPayload> {
+    let patch = serde_json::json!({ "notifications": settings });
+    let updated = state.settings.lock().await.update(patch)?;
+    Ok(updated.notifications)
+}
+
+// ---------------------------------------------------------------------------
+// App settings commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, IpcErrorPayload> {
+    Ok(state.settings.lock().await.settings().clone())
 }
 
+/// Merge `patch` into the saved settings (JSON merge patch: `null` resets a
+/// key to its default) and return the result.
 #[tauri::command]
-pub async fn list_interactive_sessions(
+pub async fn update_settings(
     state: State<'_, AppState>,
-) -> Result<Vec<InteractiveSessionSummary>, String> {
-    let interactive = state.interactive.clone();
-    let entries = interactive.list_sessions().await;
-    Ok(entries
-        .into_iter()
-        .map(
-            |(
-                sid,
-                agent_key,
-                status,
-                started_at,
-                event_count,
-                source_root,
-                repo_root,
-                effective_cwd,
-                worktree_path,
-            )| InteractiveSessionSummary {
-                session_id: sid,
-                agent_key,
-                status,
-                started_at,
-                event_count,
-                source_root,
-                repo_root,
-                effective_cwd,
-                worktree_path,
-            },
-        )
-        .collect())
+    patch: serde_json::Value,
+) -> Result<AppSettings, IpcErrorPayload> {
+    state.settings.lock().await.update(patch)
 }
 
+// ---------------------------------------------------------------------------
+// Repository commands
+// ---------------------------------------------------------------------------
+
 #[tauri::command]
-pub async fn get_interactive_transport_diagnostics(
+pub async fn list_repos(state: State<'_, AppState>) -> Result<Vec<RepoSummary>, IpcErrorPayload> {
+    Ok(state.repos.lock().await.summaries())
+}
+
+/// Register a repository. The first one added becomes the active repository.
+#[tauri::command]
+pub async fn add_repo(
     state: State<'_, AppState>,
-    session_id: String,
-) -> Result<InteractiveTransportDiagnostics, String> {
-    let interactive = state.interactive.clone();
-    let Some((push_emit_error_count, last_push_emit_error, last_push_emit_at)) =
-        interactive.push_transport_diagnostics(&session_id).await
-    else {
-        return Err(IpcError::not_found(format!("session '{session_id}' not found")).to_string());
+    path: String,
+) -> Result<RepoSummary, IpcErrorPayload> {
+    let root = resolve_repo_root(Some(&path), "Not a git repository; cannot add it")
+        .and_then(|root| canonicalize_existing_directory(&root))?;
+
+    let summary = state.repos.lock().await.add(root.clone());
+    if summary.active {
+        activate_repo(&state, &root).await?;
+    }
+    persist_repos(&state).await;
+    Ok(summary)
+}
+
+/// Make a registered repository the one commands act on when no `cwd` is
+/// given, and load its `hydra.toml`.
+#[tauri::command]
+pub async fn set_active_repo(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<RepoSummary, IpcErrorPayload> {
+    let root = canonicalize_existing_directory(Path::new(path.trim()))?;
+    let summary = activate_repo(&state, &root).await?;
+    persist_repos(&state).await;
+    Ok(summary)
+}
+
+/// Re-register the repositories saved in settings, falling back to the
+/// repository the app was launched from. Called once at app setup, after
+/// settings are loaded.
+pub async fn restore_repos(state: &AppState) {
+    let (saved, active) = {
+        let store = state.settings.lock().await;
+        let settings = store.settings();
+        (settings.repos.clone(), settings.active_repo.clone())
     };
 
-    Ok(InteractiveTransportDiagnostics {
-        session_id,
-        push_emit_error_count,
-        last_push_emit_error,
-        last_push_emit_at,
-    })
+    let mut roots: Vec<PathBuf> = saved
+        .iter()
+        .filter_map(|path| Path::new(path).canonicalize().ok())
+        .collect();
+    if roots.is_empty() {
+        if let Ok(root) = resolve_repo_root(None, "launch directory is not a git repository")
+            .and_then(|root| canonicalize_existing_directory(&root))
+        {
+            roots.push(root);
+        }
+    }
+
+    {
+        let mut repos = state.repos.lock().await;
+        for root in &roots {
+            repos.add(root.clone());
+        }
+    }
+
+    let active = active
+        .and_then(|path| Path::new(&path).canonicalize().ok())
+        .filter(|root| roots.contains(root))
+        .or_else(|| roots.first().cloned());
+    if let Some(root) = active {
+        if let Err(e) = activate_repo(state, &root).await {
+            tracing::warn!(repo = %root.display(), error = %e, "failed to restore active repository");
+        }
+    }
+}
+
+async fn activate_repo(state: &AppState, root: &Path) -> Result<RepoSummary, IpcErrorPayload> {
+    let config = race_config(root, HydraConfig::default())?;
+    let summary = state.repos.lock().await.set_active(root)?;
+    // Commands without an explicit `cwd` resolve the repository from the
+    // process working directory.
+    std::env::set_current_dir(root).map_err(|e| {
+        IpcErrorPayload::internal(format!(
+            "failed to switch to repository '{}': {e}",
+            root.display()
+        ))
+    })?;
+    *state.config.lock().await = config;
+    Ok(summary)
+}
+
+async fn persist_repos(state: &AppState) {
+    let (paths, active) = {
+        let repos = state.repos.lock().await;
+        (
+            repos.paths(),
+            repos.active().map(|repo| repo.root.display().to_string()),
+        )
+    };
+    let patch = serde_json::json!({ "repos": paths, "activeRepo": active });
+    if let Err(e) = state.settings.lock().await.update(patch) {
+        tracing::warn!(error = %e, "failed to save repository list");
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1403,44 +1991,55 @@ pub async fn get_candidate_diff(
     run_id: String,
     agent_key: String,
     cwd: Option<String>,
-) -> Result<CandidateDiffPayload, String> {
+) -> Result<CandidateDiffPayload, IpcErrorPayload> {
     let repo_root = resolve_repo_root(
         cwd.as_deref(),
         "Not inside a git repository; cannot load candidate diff",
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
     let hydra_root = repo_root.join(".hydra");
     let run_uuid = uuid::Uuid::parse_str(&run_id)
-        .map_err(|e| format!("[validation_error] invalid run_id: {e}"))?;
+        .map_err(|e| IpcErrorPayload::validation(format!("invalid run_id: {e}")))?;
     let layout = hydra_core::artifact::RunLayout::new(&hydra_root, run_uuid);
 
     if !layout.base_dir().exists() {
-        return Err(format!("[validation_error] run {} not found", run_id));
+        return Err(IpcErrorPayload::validation(format!(
+            "run {} not found",
+            run_id
+        )));
     }
 
     let manifest = hydra_core::artifact::RunManifest::read_from(&layout.manifest_path())
-        .map_err(|e| format!("[internal_error] failed to read manifest: {e}"))?;
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to read manifest: {e}")))?;
 
     let entry = manifest
         .agents
         .iter()
         .find(|a| a.agent_key == agent_key)
         .ok_or_else(|| {
-            format!(
-                "[validation_error] agent '{}' not found in run {}",
+            IpcErrorPayload::validation(format!(
+                "agent '{}' not found in run {}",
                 agent_key, run_id
-            )
+            ))
         })?;
 
     let base_ref = manifest.base_ref.clone();
     let branch = Some(entry.branch.clone());
+    let commits = manifest.candidate_commits.get(&agent_key).cloned();
+    // Agents that commit move the worktree's `HEAD`, so live diffs start
+    // from the commit the run began at rather than the ref it named.
+    let diff_base = manifest
+        .environment
+        .as_ref()
+        .and_then(|env| env.base_commit.clone())
+        .unwrap_or_else(|| base_ref.clone());
 
     let (mergeable, gate_failures) = load_agent_mergeability(&layout, &agent_key);
+    let notes = std::fs::read_to_string(layout.agent_notes(&agent_key)).ok();
 
     let diff_artifact = layout.agent_diff(&agent_key);
     if diff_artifact.exists() {
         let diff_text = std::fs::read_to_string(&diff_artifact)
-            .map_err(|e| format!("[internal_error] failed to read diff artifact: {e}"))?;
+            .map_err(|e| IpcErrorPayload::internal(format!("failed to read diff artifact: {e}")))?;
         let files = parse_diff_numstat_from_patch(&diff_text);
         return Ok(CandidateDiffPayload {
             run_id,
@@ -1454,12 +2053,14 @@ pub async fn get_candidate_diff(
             diff_available: true,
             source: "artifact".to_string(),
             warning: None,
+            notes,
+            commits,
         });
     }
 
     if let Some(branch_name) = &branch {
         if let Some(worktree_path) = find_worktree_path_for_branch(&repo_root, branch_name) {
-            match generate_worktree_diff(&worktree_path, &base_ref) {
+            match generate_worktree_diff(&worktree_path, &diff_base) {
                 Ok(diff_text) => {
                     let files = parse_diff_numstat_from_patch(&diff_text);
                     return Ok(CandidateDiffPayload {
@@ -1477,6 +2078,8 @@ pub async fn get_candidate_diff(
                             "Diff generated live from retained worktree (artifact was not persisted)"
                                 .to_string(),
                         ),
+                        notes: notes.clone(),
+                        commits: commits.clone(),
                     });
                 }
                 Err(e) => {
@@ -1486,7 +2089,7 @@ pub async fn get_candidate_diff(
         }
 
         if branch_exists(&repo_root, branch_name) {
-            match generate_branch_diff(&repo_root, branch_name, &base_ref) {
+            match generate_branch_diff(&repo_root, branch_name, &diff_base) {
                 Ok(diff_text) => {
                     let files = parse_diff_numstat_from_patch(&diff_text);
                     return Ok(CandidateDiffPayload {
@@ -1504,6 +2107,8 @@ pub async fn get_candidate_diff(
                             "Diff generated live from branch (artifact was not persisted)"
                                 .to_string(),
                         ),
+                        notes: notes.clone(),
+                        commits: commits.clone(),
                     });
                 }
                 Err(e) => {
@@ -1527,16 +2132,20 @@ pub async fn get_candidate_diff(
         warning: Some(
             "Diff unavailable: artifact not persisted and branch no longer exists".to_string(),
         ),
+        notes,
+        commits,
     })
 }
 
 #[tauri::command]
 pub async fn preview_merge(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
     run_id: String,
     agent_key: String,
     force: bool,
     cwd: Option<String>,
-) -> Result<MergePreviewPayload, String> {
+) -> Result<MergePreviewPayload, IpcErrorPayload> {
     let (cli_parts, repo_root) = resolve_cli_and_repo(cwd.as_deref())?;
 
     let mut args: Vec<String> = cli_parts[1..].to_vec();
@@ -1557,23 +2166,84 @@ pub async fn preview_merge(
         .args(&args)
         .current_dir(&repo_root)
         .output()
-        .map_err(|e| format!("[internal_error] failed to execute merge command: {e}"))?;
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to execute merge command: {e}")))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-    parse_merge_preview_payload(output.status.success(), &agent_key, &stdout, &stderr)
+    let payload =
+        parse_merge_preview_payload(output.status.success(), &agent_key, &stdout, &stderr)?;
+    if payload.has_conflicts {
+        notifications::notify(
+            &app,
+            &state.handle(),
+            notifications::merge_conflicts(&agent_key),
+        )
+        .await;
+    }
+    Ok(payload)
+}
+
+#[tauri::command]
+pub async fn set_file_review_state(
+    run_id: String,
+    agent_key: String,
+    path: String,
+    state: String,
+    note: Option<String>,
+    cwd: Option<String>,
+) -> Result<AgentReviewPayload, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot save review",
+    )?;
+    history::set_file_review(
+        &repo_root.join(".hydra"),
+        &run_id,
+        &agent_key,
+        &path,
+        &state,
+        note,
+    )
+}
+
+#[tauri::command]
+pub async fn get_file_review_state(
+    run_id: String,
+    agent_key: String,
+    cwd: Option<String>,
+) -> Result<AgentReviewPayload, IpcErrorPayload> {
+    let repo_root = resolve_repo_root(
+        cwd.as_deref(),
+        "Not inside a git repository; cannot load review",
+    )?;
+    history::file_review(&repo_root.join(".hydra"), &run_id, &agent_key)
 }
 
+/// Merge an agent's changes. With `approved_only`, only files approved via
+/// `set_file_review_state` are taken, committed on top of the current branch.
 #[tauri::command]
 pub async fn execute_merge(
     run_id: String,
     agent_key: String,
     force: bool,
+    approved_only: Option<bool>,
     cwd: Option<String>,
-) -> Result<MergeExecutionPayload, String> {
+) -> Result<MergeExecutionPayload, IpcErrorPayload> {
     let (cli_parts, repo_root) = resolve_cli_and_repo(cwd.as_deref())?;
 
+    let approved_paths = if approved_only.unwrap_or(false) {
+        let review = history::file_review(&repo_root.join(".hydra"), &run_id, &agent_key)?;
+        if review.approved_paths.is_empty() {
+            return Err(IpcErrorPayload::validation(format!(
+                "no files of '{agent_key}' have been approved for merge"
+            )));
+        }
+        review.approved_paths
+    } else {
+        Vec::new()
+    };
+
     let mut args: Vec<String> = cli_parts[1..].to_vec();
     args.extend([
         "merge".to_string(),
@@ -1587,12 +2257,15 @@ pub async fn execute_merge(
     if force {
         args.push("--force".to_string());
     }
+    for path in approved_paths {
+        args.extend(["--only".to_string(), path]);
+    }
 
     let output = std::process::Command::new(&cli_parts[0])
         .args(&args)
         .current_dir(&repo_root)
         .output()
-        .map_err(|e| format!("[internal_error] failed to execute merge command: {e}"))?;
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to execute merge command: {e}")))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -1673,9 +2346,8 @@ pub async fn execute_merge(
     }
 }
 
-fn resolve_cli_and_repo(cwd: Option<&str>) -> Result<(Vec<String>, PathBuf), String> {
-    let repo_root = resolve_repo_root(cwd, "Not inside a git repository; cannot run merge")
-        .map_err(|e| e.to_string())?;
+fn resolve_cli_and_repo(cwd: Option<&str>) -> Result<(Vec<String>, PathBuf), IpcErrorPayload> {
+    let repo_root = resolve_repo_root(cwd, "Not inside a git repository; cannot run merge")?;
 
     if let Ok(bin) = std::env::var("HYDRA_CLI_BIN") {
         Ok((vec![bin], repo_root))
@@ -1693,7 +2365,7 @@ fn parse_merge_preview_payload(
     agent_key: &str,
     stdout: &str,
     stderr: &str,
-) -> Result<MergePreviewPayload, String> {
+) -> Result<MergePreviewPayload, IpcErrorPayload> {
     if let Some(payload) = try_parse_merge_preview_payload(agent_key, stdout, stderr) {
         if status_success || payload.has_conflicts {
             return Ok(payload);
@@ -1701,30 +2373,28 @@ fn parse_merge_preview_payload(
 
         let payload_stderr = payload.stderr.trim();
         if !payload_stderr.is_empty() {
-            return Err(format!("[validation_error] {payload_stderr}"));
+            return Err(IpcErrorPayload::validation(payload_stderr));
         }
 
         let payload_stdout = payload.stdout.trim();
         if !payload_stdout.is_empty() {
-            return Err(format!("[validation_error] {payload_stdout}"));
+            return Err(IpcErrorPayload::validation(payload_stdout));
         }
 
-        return Err(format!(
-            "[validation_error] {}",
-            best_merge_error_message(stderr, stdout)
-        ));
+        return Err(IpcErrorPayload::validation(best_merge_error_message(
+            stderr, stdout,
+        )));
     }
 
     if status_success {
-        return Err(
-            "[internal_error] merge preview did not return expected JSON payload".to_string(),
-        );
+        return Err(IpcErrorPayload::internal(
+            "merge preview did not return expected JSON payload",
+        ));
     }
 
-    Err(format!(
-        "[validation_error] {}",
-        best_merge_error_message(stderr, stdout)
-    ))
+    Err(IpcErrorPayload::validation(best_merge_error_message(
+        stderr, stdout,
+    )))
 }
 
 fn resolve_working_tree_scope(repo_root: &Path, cwd: Option<&str>) -> Option<PathBuf> {
@@ -1930,27 +2600,14 @@ fn load_agent_mergeability(
     layout: &hydra_core::artifact::RunLayout,
     agent_key: &str,
 ) -> (Option<bool>, Vec<String>) {
-    let score_path = layout.agent_score(agent_key);
-    if !score_path.exists() {
-        return (None, Vec::new());
+    match hydra_core::artifact::read_agent_score(layout, agent_key) {
+        Ok(Some(score)) => (Some(score.mergeable), score.gate_failures),
+        Ok(None) => (None, Vec::new()),
+        Err(e) => {
+            tracing::warn!(agent = agent_key, error = %e, "failed to read score artifact");
+            (None, Vec::new())
+        }
     }
-    let Ok(data) = std::fs::read_to_string(&score_path) else {
-        return (None, Vec::new());
-    };
-    let Ok(score) = serde_json::from_str::<serde_json::Value>(&data) else {
-        return (None, Vec::new());
-    };
-    let mergeable = score.get("mergeable").and_then(|v| v.as_bool());
-    let gate_failures = score
-        .get("gate_failures")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect()
-        })
-        .unwrap_or_default();
-    (mergeable, gate_failures)
 }
 
 fn parse_diff_numstat_from_patch(patch: &str) -> Vec<DiffFile> {
@@ -2130,26 +2787,6 @@ fn parse_worktree_path_for_branch(porcelain: &str, branch: &str) -> Option<PathB
     None
 }
 
-fn adapter_supports_interactive_unsafe_mode(adapter_key: &str, supported_flags: &[String]) -> bool {
-    match adapter_key {
-        // Codex uses explicit dangerous bypass flag for unsafe mode.
-        "codex" => supported_flags
-            .iter()
-            .any(|f| f == "--dangerously-bypass-approvals-and-sandbox"),
-        // Claude uses permission-mode bypass in force-edit flows.
-        "claude" => supported_flags.iter().any(|f| f == "--permission-mode"),
-        _ => false,
-    }
-}
-
-fn unsafe_mode_requirement_hint(adapter_key: &str) -> &'static str {
-    match adapter_key {
-        "codex" => "Expected flag: --dangerously-bypass-approvals-and-sandbox.",
-        "claude" => "Expected flag: --permission-mode.",
-        _ => "Unsafe mode is only supported for adapters with explicit sandbox-bypass controls.",
-    }
-}
-
 // ---------------------------------------------------------------------------
 // File Explorer (P4.9.2)
 // ---------------------------------------------------------------------------
@@ -2192,7 +2829,7 @@ fn is_probably_binary(bytes: &[u8]) -> bool {
 }
 
 #[tauri::command]
-pub async fn list_directory(path: String) -> Result<DirectoryListing, String> {
+pub async fn list_directory(path: String) -> Result<DirectoryListing, IpcErrorPayload> {
     let dir = PathBuf::from(&path);
     if !dir.is_dir() {
         return Ok(DirectoryListing {
@@ -2205,7 +2842,7 @@ pub async fn list_directory(path: String) -> Result<DirectoryListing, String> {
     let mut entries = Vec::new();
     let mut read_dir = tokio::fs::read_dir(&dir)
         .await
-        .map_err(|e| format!("Failed to read directory {path}: {e}"))?;
+        .map_err(|e| IpcErrorPayload::internal(format!("Failed to read directory {path}: {e}")))?;
 
     while let Ok(Some(entry)) = read_dir.next_entry().await {
         let name = entry.file_name().to_string_lossy().to_string();
@@ -2269,7 +2906,7 @@ pub async fn list_directory(path: String) -> Result<DirectoryListing, String> {
 pub async fn read_file_preview(
     path: String,
     max_bytes: Option<usize>,
-) -> Result<FilePreview, String> {
+) -> Result<FilePreview, IpcErrorPayload> {
     let file_path = PathBuf::from(&path);
     if !file_path.is_file() {
         return Ok(FilePreview {
@@ -2286,21 +2923,26 @@ pub async fn read_file_preview(
         .unwrap_or(DEFAULT_FILE_PREVIEW_BYTES)
         .clamp(1024, MAX_FILE_PREVIEW_BYTES);
 
-    let metadata = tokio::fs::metadata(&file_path)
-        .await
-        .map_err(|e| format!("Failed to read metadata for {}: {e}", file_path.display()))?;
+    let metadata = tokio::fs::metadata(&file_path).await.map_err(|e| {
+        IpcErrorPayload::internal(format!(
+            "Failed to read metadata for {}: {e}",
+            file_path.display()
+        ))
+    })?;
     let size = Some(metadata.len());
 
-    let file = tokio::fs::File::open(&file_path)
-        .await
-        .map_err(|e| format!("Failed to open file {}: {e}", file_path.display()))?;
+    let file = tokio::fs::File::open(&file_path).await.map_err(|e| {
+        IpcErrorPayload::internal(format!("Failed to open file {}: {e}", file_path.display()))
+    })?;
 
     let mut buf = Vec::with_capacity(preview_limit + 1);
     let bytes_read = file
         .take((preview_limit + 1) as u64)
         .read_to_end(&mut buf)
         .await
-        .map_err(|e| format!("Failed to read file {}: {e}", file_path.display()))?;
+        .map_err(|e| {
+            IpcErrorPayload::internal(format!("Failed to read file {}: {e}", file_path.display()))
+        })?;
 
     let truncated = bytes_read > preview_limit;
     if truncated {
@@ -2335,10 +2977,12 @@ pub async fn read_file_preview(
 pub async fn start_file_watcher(
     state: State<'_, AppState>,
     root: String,
-) -> Result<FileWatcherStarted, String> {
+) -> Result<FileWatcherStarted, IpcErrorPayload> {
     let root_path = PathBuf::from(&root);
     if !root_path.is_dir() {
-        return Err(IpcError::validation(format!("Path is not a directory: {root}")).to_string());
+        return Err(IpcErrorPayload::validation(format!(
+            "Path is not a directory: {root}"
+        )));
     }
 
     let watcher_id = uuid::Uuid::new_v4().to_string();
@@ -2443,7 +3087,7 @@ pub async fn poll_file_watch_events(
     state: State<'_, AppState>,
     watcher_id: String,
     cursor: u64,
-) -> Result<FileWatchEventBatch, String> {
+) -> Result<FileWatchEventBatch, IpcErrorPayload> {
     let result = state
         .file_watcher
         .poll_events(&watcher_id, cursor, MAX_FILE_WATCH_EVENTS_PER_POLL)
@@ -2457,7 +3101,9 @@ pub async fn poll_file_watch_events(
             active,
             error,
         }),
-        None => Err(IpcError::not_found(format!("Watcher not found: {watcher_id}")).to_string()),
+        None => Err(IpcErrorPayload::not_found(format!(
+            "Watcher not found: {watcher_id}"
+        ))),
     }
 }
 
@@ -2465,13 +3111,15 @@ pub async fn poll_file_watch_events(
 pub async fn stop_file_watcher(
     state: State<'_, AppState>,
     watcher_id: String,
-) -> Result<FileWatcherStopped, String> {
+) -> Result<FileWatcherStopped, IpcErrorPayload> {
     match state.file_watcher.stop_watcher(&watcher_id).await {
         Some(was_active) => Ok(FileWatcherStopped {
             watcher_id,
             was_active,
         }),
-        None => Err(IpcError::not_found(format!("Watcher not found: {watcher_id}")).to_string()),
+        None => Err(IpcErrorPayload::not_found(format!(
+            "Watcher not found: {watcher_id}"
+        ))),
     }
 }
 
@@ -2502,67 +3150,13 @@ async fn cleanup_managed_worktree_on_launch_failure(worktree: &InteractiveManage
     }
 }
 
-/// P4.9.4: Build minimal interactive CLI args for direct external tool invocation.
-/// No race-mode flags (--output-format json, --verbose, --stream-json, etc.).
-fn build_interactive_args(
-    adapter_key: &str,
-    task_prompt: &str,
-    unsafe_mode: bool,
-    supported_flags: &[String],
-) -> Vec<String> {
-    match adapter_key {
-        "claude" => {
-            // Keep interactive launches tool-native: no print/headless flags.
-            let mut args = Vec::new();
-            if unsafe_mode && supported_flags.iter().any(|f| f == "--permission-mode") {
-                args.extend([
-                    "--permission-mode".to_string(),
-                    "bypassPermissions".to_string(),
-                ]);
-            }
-            if !task_prompt.trim().is_empty() {
-                args.push(task_prompt.to_string());
-            }
-            args
-        }
-        "codex" => {
-            // Empty prompt launches native interactive mode; non-empty prompt
-            // keeps one-shot bootstrap behavior via `exec`.
-            let mut args = if task_prompt.trim().is_empty() {
-                Vec::new()
-            } else {
-                vec!["exec".to_string(), task_prompt.to_string()]
-            };
-            if unsafe_mode
-                && supported_flags
-                    .iter()
-                    .any(|f| f == "--dangerously-bypass-approvals-and-sandbox")
-            {
-                args.push("--dangerously-bypass-approvals-and-sandbox".to_string());
-            }
-            args
-        }
-        "cursor-agent" => {
-            if task_prompt.trim().is_empty() {
-                Vec::new()
-            } else {
-                vec![task_prompt.to_string()]
-            }
-        }
-        _ => {
-            if task_prompt.trim().is_empty() {
-                Vec::new()
-            } else {
-                vec![task_prompt.to_string()]
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::state::{InteractiveSessionRuntime, InteractiveStateHandle};
+    use hydra_core::orchestrator::OrchestratorError;
+    use hydra_core::supervisor::pty::Scrollback;
+    use std::collections::HashMap;
     use std::sync::Arc;
     use tokio::sync::Mutex;
 
@@ -2616,13 +3210,13 @@ mod tests {
 
     #[test]
     fn ipc_error_display_format() {
-        let err = IpcError::validation("bad input");
+        let err = IpcErrorPayload::validation("bad input");
         assert_eq!(err.to_string(), "[validation_error] bad input");
     }
 
     #[test]
     fn ipc_error_serializes() {
-        let err = IpcError::internal("something broke");
+        let err = IpcErrorPayload::internal("something broke");
         let json = serde_json::to_string(&err).unwrap();
         assert!(json.contains("internal_error"));
         assert!(json.contains("something broke"));
@@ -2683,17 +3277,24 @@ mod tests {
             total_count: 4,
             health_score: 100.0,
             checks: vec![DiagnosticCheck {
+                id: check::GIT_REPO.to_string(),
                 name: "Test".to_string(),
                 description: "Test check".to_string(),
-                status: CheckStatus::Passed,
+                severity: Severity::Error,
+                status: CheckStatus::Failed,
                 evidence: None,
+                remediation: Some(Remediation::manual(remediation::INIT_GIT_REPO, "git init")),
             }],
             adapters: vec![],
             warnings: vec![],
+            pings: vec![],
         };
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("systemReady"));
         assert!(json.contains("healthScore"));
+        assert!(json.contains(r#""id":"git.repo""#));
+        assert!(json.contains(r#""severity":"error""#));
+        assert!(json.contains(r#""remediation":{"id":"init_git_repo""#));
     }
 
     #[test]
@@ -2818,6 +3419,7 @@ mod tests {
                     managed_worktree: None,
                     events: Vec::new(),
                     event_base_cursor: 0,
+                    scrollback: Scrollback::new(1024),
                     error: None,
                     pty_session: None,
                     artifact_writer: None,
@@ -2885,6 +3487,7 @@ mod tests {
                     managed_worktree: None,
                     events: Vec::new(),
                     event_base_cursor: 0,
+                    scrollback: Scrollback::new(1024),
                     error: None,
                     pty_session: None,
                     artifact_writer: None,
@@ -2984,81 +3587,58 @@ mod tests {
     }
 
     #[test]
-    fn parse_cli_summary_extracts_agents() {
-        let payload = serde_json::json!({
-            "run_id": "abc",
-            "status": "Completed",
-            "duration_ms": 5000,
-            "total_cost": 0.42,
-            "agents": [
-                {
-                    "agent": "claude",
-                    "status": "Completed",
-                    "duration_ms": 42,
-                    "score": {
-                        "composite": 95.1,
-                        "mergeable": true,
-                        "gate_failures": [],
-                        "dimensions": [
-                            { "name": "build", "score": 100.0, "evidence": {} },
-                            { "name": "tests", "score": 90.0, "evidence": { "passed": 14, "failed": 0 } }
-                        ]
-                    }
-                }
-            ]
-        });
-
-        let parsed = parse_cli_race_summary(payload.to_string().as_bytes()).unwrap();
-        assert_eq!(parsed.run_id, "abc");
-        assert_eq!(parsed.status, "completed");
-        assert_eq!(parsed.duration_ms, Some(5000));
-        assert_eq!(parsed.total_cost, Some(0.42));
-        assert_eq!(parsed.agents.len(), 1);
-        assert_eq!(parsed.agents[0].agent_key, "claude");
-        assert_eq!(parsed.agents[0].score, Some(95.1));
-        assert_eq!(parsed.agents[0].mergeable, Some(true));
-        assert!(parsed.agents[0].gate_failures.is_empty());
-        assert_eq!(parsed.agents[0].dimensions.len(), 2);
-        assert_eq!(parsed.agents[0].dimensions[0].name, "build");
-        assert!((parsed.agents[0].dimensions[0].score - 100.0).abs() < 0.01);
-    }
-
-    #[test]
-    fn parse_cli_summary_supports_nested_cost_and_camel_statuses() {
-        let payload = serde_json::json!({
-            "run_id": "run-2",
-            "status": "TimedOut",
-            "duration_ms": 2200,
-            "cost": {
-                "estimated_cost_usd": 1.25
+    fn race_stream_events_match_run_event_types() {
+        let output = race_stream_event(
+            "run-1",
+            RaceEvent::AgentOutput {
+                agent_key: "codex".to_string(),
+                stream: OutputStream::Stdout,
+                line: "hello world".to_string(),
             },
-            "agents": [
-                {
-                    "agent": "codex",
-                    "status": "TimedOut",
-                    "duration_ms": 2200,
-                    "score": {
-                        "composite": 0.0,
-                        "mergeable": false,
-                        "gate_failures": ["timed_out"],
-                        "dimensions": []
-                    }
-                }
-            ]
-        });
+        );
+        assert_eq!(output.run_id, "run-1");
+        assert_eq!(output.agent_key, "codex");
+        assert_eq!(output.event_type, "agent_stdout");
+        assert_eq!(output.data["line"], "hello world");
+
+        let finished = race_stream_event(
+            "run-1",
+            RaceEvent::AgentFinished {
+                agent_key: "claude".to_string(),
+                status: RunStatus::TimedOut,
+                error: Some("timed out (hard)".to_string()),
+                duration_ms: 10,
+                total_tokens: 0,
+            },
+        );
+        assert_eq!(finished.event_type, "agent_failed");
+        assert_eq!(finished.data["status"], "TimedOut");
 
-        let parsed = parse_cli_race_summary(payload.to_string().as_bytes()).unwrap();
-        assert_eq!(parsed.status, "timed_out");
-        assert_eq!(parsed.duration_ms, Some(2200));
-        assert_eq!(parsed.total_cost, Some(1.25));
-        assert_eq!(parsed.agents[0].status, "timed_out");
+        let run = race_stream_event(
+            "run-1",
+            RaceEvent::RunFinished {
+                status: RunStatus::Completed,
+            },
+        );
+        assert_eq!(run.agent_key, "system");
+        assert_eq!(run.event_type, "run_completed");
     }
 
     #[test]
-    fn normalize_status_handles_mixed_delimiters_and_camel_case() {
-        assert_eq!(normalize_status("TimedOut"), "timed_out");
-        assert_eq!(normalize_status("timed-out"), "timed_out");
-        assert_eq!(normalize_status("Already Fine"), "already_fine");
+    fn race_config_prefers_repo_hydra_toml() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut app_config = HydraConfig::default();
+        app_config.race.default_agents = vec!["codex".to_string()];
+        let config = race_config(tmp.path(), app_config.clone()).unwrap();
+        assert_eq!(config.race.default_agents, vec!["codex"]);
+
+        std::fs::write(
+            tmp.path().join("hydra.toml"),
+            "[race]\ndefault_agents = [\"claude\"]\n",
+        )
+        .unwrap();
+        let config = race_config(tmp.path(), app_config).unwrap();
+        assert_eq!(config.race.default_agents, vec!["claude"]);
     }
 
     #[test]
@@ -3118,8 +3698,8 @@ index 3333333..4444444 100644
             "working tree has uncommitted changes",
         )
         .unwrap_err();
-        assert!(err.contains("validation_error"));
-        assert!(err.contains("working tree has uncommitted changes"));
+        assert_eq!(err.code, "validation_error");
+        assert!(err.message.contains("working tree has uncommitted changes"));
     }
 
     #[test]
@@ -3142,8 +3722,8 @@ index 3333333..4444444 100644
         )
         .unwrap_err();
 
-        assert!(err.contains("branch missing for this run"));
-        assert!(!err.contains("Finished `dev` profile"));
+        assert!(err.message.contains("branch missing for this run"));
+        assert!(!err.message.contains("Finished `dev` profile"));
     }
 
     #[test]
@@ -3153,39 +3733,6 @@ index 3333333..4444444 100644
         assert_eq!(cleaned, "error: patch failed at src/main.rs");
     }
 
-    #[test]
-    fn parse_run_event_line_output_only_filters_lifecycle_events() {
-        let lifecycle_line = serde_json::json!({
-            "timestamp": "2026-02-23T20:40:59.440503361Z",
-            "kind": "agent_completed",
-            "agent_key": "claude",
-            "data": { "status": "Completed" }
-        })
-        .to_string();
-
-        assert!(parse_run_event_line("run-1", &lifecycle_line, true).is_none());
-        let passthrough = parse_run_event_line("run-1", &lifecycle_line, false).unwrap();
-        assert_eq!(passthrough.event_type, "agent_completed");
-        assert_eq!(passthrough.agent_key, "claude");
-    }
-
-    #[test]
-    fn parse_run_event_line_output_only_keeps_stdout_lines() {
-        let stdout_line = serde_json::json!({
-            "timestamp": "2026-02-23T20:40:20.589821238Z",
-            "kind": "agent_stdout",
-            "agent_key": "codex",
-            "data": { "line": "hello world" }
-        })
-        .to_string();
-
-        let parsed = parse_run_event_line("run-2", &stdout_line, true).unwrap();
-        assert_eq!(parsed.run_id, "run-2");
-        assert_eq!(parsed.agent_key, "codex");
-        assert_eq!(parsed.event_type, "agent_stdout");
-        assert_eq!(parsed.data["line"], "hello world");
-    }
-
     #[test]
     fn parse_porcelain_path_extracts_modified_added_and_renamed_paths() {
         assert_eq!(
@@ -3309,119 +3856,16 @@ branch refs/heads/main
         assert!(parse_worktree_path_for_branch(porcelain, "hydra/run/agent/codex").is_none());
     }
 
-    #[test]
-    fn unsafe_mode_support_for_codex_requires_dangerous_flag() {
-        let ok = adapter_supports_interactive_unsafe_mode(
-            "codex",
-            &[
-                "--json".to_string(),
-                "--dangerously-bypass-approvals-and-sandbox".to_string(),
-            ],
-        );
-        let blocked = adapter_supports_interactive_unsafe_mode(
-            "codex",
-            &["--json".to_string(), "--permission-mode".to_string()],
-        );
-        assert!(ok);
-        assert!(!blocked);
-    }
-
-    #[test]
-    fn unsafe_mode_support_for_claude_requires_permission_mode() {
-        let ok = adapter_supports_interactive_unsafe_mode(
-            "claude",
-            &["--print".to_string(), "--permission-mode".to_string()],
-        );
-        let blocked = adapter_supports_interactive_unsafe_mode("claude", &["--print".to_string()]);
-        assert!(ok);
-        assert!(!blocked);
-    }
-
-    #[test]
-    fn unsafe_mode_support_rejects_unknown_adapters() {
-        let blocked =
-            adapter_supports_interactive_unsafe_mode("cursor-agent", &["--force".to_string()]);
-        assert!(!blocked);
-    }
-
-    // P4.9.4: Direct CLI invocation tests
-    #[test]
-    fn build_interactive_args_claude_basic() {
-        let args = build_interactive_args("claude", "fix the bug", false, &[]);
-        assert_eq!(args, vec!["fix the bug"]);
-    }
-
-    #[test]
-    fn build_interactive_args_claude_unsafe_with_permission_mode() {
-        let flags = vec!["--permission-mode".to_string()];
-        let args = build_interactive_args("claude", "fix it", true, &flags);
-        assert_eq!(
-            args,
-            vec!["--permission-mode", "bypassPermissions", "fix it"]
-        );
-    }
-
-    #[test]
-    fn build_interactive_args_claude_unsafe_without_permission_mode() {
-        let args = build_interactive_args("claude", "fix it", true, &[]);
-        assert_eq!(args, vec!["fix it"]);
-    }
-
-    #[test]
-    fn build_interactive_args_codex_basic() {
-        let args = build_interactive_args("codex", "refactor the module", false, &[]);
-        assert_eq!(args, vec!["exec", "refactor the module"]);
-    }
-
-    #[test]
-    fn build_interactive_args_codex_unsafe_with_dangerous_flag() {
-        let flags = vec!["--dangerously-bypass-approvals-and-sandbox".to_string()];
-        let args = build_interactive_args("codex", "fix", true, &flags);
-        assert_eq!(
-            args,
-            vec!["exec", "fix", "--dangerously-bypass-approvals-and-sandbox"]
-        );
-    }
-
-    #[test]
-    fn build_interactive_args_cursor_agent() {
-        let args = build_interactive_args("cursor-agent", "do it", false, &[]);
-        assert_eq!(args, vec!["do it"]);
-    }
-
-    #[test]
-    fn build_interactive_args_unknown_adapter() {
-        let args = build_interactive_args("unknown", "task", false, &[]);
-        assert_eq!(args, vec!["task"]);
-    }
-
-    #[test]
-    fn build_interactive_args_empty_prompt_launches_interactive_shell() {
-        let claude = build_interactive_args("claude", "", false, &[]);
-        let codex = build_interactive_args("codex", "", false, &[]);
-        let cursor = build_interactive_args("cursor-agent", "", false, &[]);
-        assert!(claude.is_empty());
-        assert!(codex.is_empty());
-        assert!(cursor.is_empty());
-    }
-
-    #[test]
-    fn build_interactive_args_codex_empty_prompt_with_unsafe_flag() {
-        let flags = vec!["--dangerously-bypass-approvals-and-sandbox".to_string()];
-        let codex = build_interactive_args("codex", "", true, &flags);
-        assert_eq!(codex, vec!["--dangerously-bypass-approvals-and-sandbox"]);
-    }
-
     #[test]
     fn ipc_error_binary_missing_variant() {
-        let err = IpcError::binary_missing("claude not found");
+        let err = IpcErrorPayload::binary_missing("claude not found");
         assert_eq!(err.code, "binary_missing");
         assert_eq!(err.to_string(), "[binary_missing] claude not found");
     }
 
     #[test]
     fn ipc_error_launch_error_variant() {
-        let err = IpcError::launch_error("PTY spawn failed");
+        let err = IpcErrorPayload::launch_error("PTY spawn failed");
         assert_eq!(err.code, "launch_error");
         assert_eq!(err.to_string(), "[launch_error] PTY spawn failed");
     }
@@ -3533,7 +3977,7 @@ branch refs/heads/main
 
     #[test]
     fn ipc_error_not_found_variant() {
-        let err = IpcError::not_found("session not found");
+        let err = IpcErrorPayload::not_found("session not found");
         assert_eq!(err.code, "not_found");
         assert_eq!(err.to_string(), "[not_found] session not found");
     }
@@ -3541,29 +3985,50 @@ branch refs/heads/main
     // M4.5: Safety and capability gating error variant tests
     #[test]
     fn ipc_error_safety_gate_variant() {
-        let err = IpcError::safety_gate("adapter not available");
+        let err = IpcErrorPayload::safety_gate("adapter not available");
         assert_eq!(err.code, "safety_gate");
         assert_eq!(err.to_string(), "[safety_gate] adapter not available");
     }
 
     #[test]
     fn ipc_error_experimental_blocked_variant() {
-        let err = IpcError::experimental_blocked("cursor-agent requires confirmation");
+        let err = IpcErrorPayload::experimental_blocked("cursor-agent requires confirmation");
         assert_eq!(err.code, "experimental_blocked");
         assert!(err.to_string().contains("experimental_blocked"));
     }
 
     #[test]
     fn ipc_error_dirty_worktree_variant() {
-        let err = IpcError::dirty_worktree("uncommitted changes found");
+        let err = IpcErrorPayload::dirty_worktree("uncommitted changes found");
         assert_eq!(err.code, "dirty_worktree");
         assert!(err.to_string().contains("dirty_worktree"));
     }
 
     #[test]
     fn ipc_error_unsafe_blocked_variant() {
-        let err = IpcError::unsafe_blocked("adapter lacks dangerous flag");
+        let err = IpcErrorPayload::unsafe_blocked("adapter lacks dangerous flag");
         assert_eq!(err.code, "unsafe_blocked");
         assert!(err.to_string().contains("unsafe_blocked"));
     }
+
+    #[test]
+    fn ipc_error_payload_serializes_as_tagged_object() {
+        let err = IpcErrorPayload::launch_error("spawn failed")
+            .with_details(serde_json::json!({ "agent": "codex" }));
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["code"], "launch_error");
+        assert_eq!(value["message"], "spawn failed");
+        assert_eq!(value["details"]["agent"], "codex");
+        assert_eq!(value["retryable"], true);
+
+        let err = IpcErrorPayload::from(OrchestratorError::NothingToPromote {
+            path: PathBuf::from("/repo"),
+        });
+        assert_eq!(err.code, "validation_error");
+        assert!(!err.retryable);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap()["errorCode"],
+            "HY-RACE-002"
+        );
+    }
 }
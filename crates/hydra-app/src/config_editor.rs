@@ -0,0 +1,206 @@
+//! Reading and editing the repository's `hydra.toml` for the settings screen.
+//!
+//! Edits go through [`hydra_core::config::update_config_file`], so a change
+//! that fails validation never reaches disk.
+
+use std::path::{Path, PathBuf};
+
+use hydra_core::config::{
+    discover_config, load_config, parse_config, update_config_file, ConfigError, CONFIG_FILE_NAME,
+};
+
+use crate::ipc_types::{
+    ConfigIssue, ConfigUpdateResult, ConfigValidation, EffectiveConfig, IpcErrorPayload,
+};
+
+/// The config file races in `repo_root` load, or where it would be created.
+pub(crate) fn config_path(repo_root: &Path) -> PathBuf {
+    discover_config(repo_root).unwrap_or_else(|| repo_root.join(CONFIG_FILE_NAME))
+}
+
+pub(crate) fn effective_config(repo_root: &Path) -> Result<EffectiveConfig, IpcErrorPayload> {
+    let path = config_path(repo_root);
+    let config = load_config(&path).map_err(|e| {
+        IpcErrorPayload::validation(format!("failed to load {}: {e}", path.display()))
+    })?;
+    Ok(EffectiveConfig {
+        path: path.display().to_string(),
+        exists: path.exists(),
+        config,
+    })
+}
+
+/// Set `values` under the dotted `section` (e.g. `scoring.weights`) and save.
+///
+/// Validation failures are reported as issues with `saved: false` rather
+/// than as an error.
+pub(crate) fn update_section(
+    repo_root: &Path,
+    section: &str,
+    values: serde_json::Value,
+) -> Result<ConfigUpdateResult, IpcErrorPayload> {
+    let path = config_path(repo_root);
+    let overrides = section_overrides(section, values)?;
+
+    match update_config_file(&path, overrides) {
+        Ok(config) => Ok(ConfigUpdateResult {
+            path: path.display().to_string(),
+            saved: true,
+            issues: Vec::new(),
+            config,
+        }),
+        Err(err @ (ConfigError::ReadFailed { .. } | ConfigError::WriteFailed { .. })) => {
+            Err(IpcErrorPayload::internal(err.to_string()))
+        }
+        Err(err) => {
+            let current = effective_config(repo_root)?;
+            Ok(ConfigUpdateResult {
+                path: current.path,
+                saved: false,
+                issues: vec![config_issue(&err, None)],
+                config: current.config,
+            })
+        }
+    }
+}
+
+/// Check a full `hydra.toml` draft without writing it.
+pub(crate) fn validate_draft(draft: &str) -> ConfigValidation {
+    match parse_config(draft) {
+        Ok(config) => ConfigValidation {
+            valid: true,
+            issues: Vec::new(),
+            config: Some(config),
+        },
+        Err(err) => ConfigValidation {
+            valid: false,
+            issues: vec![config_issue(&err, Some(draft))],
+            config: None,
+        },
+    }
+}
+
+fn section_overrides(
+    section: &str,
+    values: serde_json::Value,
+) -> Result<toml::Table, IpcErrorPayload> {
+    let keys: Vec<&str> = section.split('.').map(str::trim).collect();
+    if keys.iter().any(|k| k.is_empty()) {
+        return Err(IpcErrorPayload::validation(format!(
+            "invalid config section '{section}'"
+        )));
+    }
+    let toml::Value::Table(mut table) = toml::Value::try_from(values).map_err(|e| {
+        IpcErrorPayload::validation(format!("values for [{section}] are not valid TOML: {e}"))
+    })?
+    else {
+        return Err(IpcErrorPayload::validation(format!(
+            "values for [{section}] must be an object"
+        )));
+    };
+    for key in keys.iter().rev() {
+        let mut parent = toml::Table::new();
+        parent.insert((*key).to_string(), toml::Value::Table(table));
+        table = parent;
+    }
+    Ok(table)
+}
+
+/// Structured form of a config error. `source` is the TOML text the error
+/// came from, used to turn byte spans into line and column.
+fn config_issue(err: &ConfigError, source: Option<&str>) -> ConfigIssue {
+    match err {
+        ConfigError::Validation { field, message } => ConfigIssue {
+            field: Some(field.clone()),
+            message: message.clone(),
+            line: None,
+            column: None,
+        },
+        ConfigError::ParseFailed(parse) => {
+            let position = source
+                .zip(parse.span())
+                .map(|(text, span)| line_column(text, span.start));
+            ConfigIssue {
+                field: None,
+                message: parse.message().to_string(),
+                line: position.map(|(line, _)| line),
+                column: position.map(|(_, column)| column),
+            }
+        }
+        other => ConfigIssue {
+            field: None,
+            message: other.to_string(),
+            line: None,
+            column: None,
+        },
+    }
+}
+
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_section_writes_nested_values() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let result = update_section(
+            tmp.path(),
+            "scoring.weights",
+            serde_json::json!({ "speed": 0, "build": 40 }),
+        )
+        .unwrap();
+
+        assert!(result.saved);
+        assert_eq!(result.config.scoring.weights.build, 40);
+        assert_eq!(result.config.scoring.weights.speed, 0);
+        let written = std::fs::read_to_string(tmp.path().join("hydra.toml")).unwrap();
+        assert!(written.contains("[scoring.weights]"));
+    }
+
+    #[test]
+    fn update_section_reports_validation_issue_without_saving() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let result = update_section(
+            tmp.path(),
+            "supervisor",
+            serde_json::json!({ "hard_timeout_seconds": 0 }),
+        )
+        .unwrap();
+
+        assert!(!result.saved);
+        assert_eq!(
+            result.issues[0].field.as_deref(),
+            Some("supervisor.hard_timeout_seconds")
+        );
+        assert!(!tmp.path().join("hydra.toml").exists());
+
+        let err =
+            update_section(tmp.path(), "scoring..weights", serde_json::json!({})).unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        let err = update_section(tmp.path(), "race", serde_json::json!(["claude"])).unwrap_err();
+        assert_eq!(err.code, "validation_error");
+    }
+
+    #[test]
+    fn validate_draft_locates_syntax_errors() {
+        let ok = validate_draft("[race]\ndefault_agents = [\"claude\"]\n");
+        assert!(ok.valid);
+        assert_eq!(ok.config.unwrap().race.default_agents, vec!["claude"]);
+
+        let bad = validate_draft("[race]\ndefault_agents = = 1\n");
+        assert!(!bad.valid);
+        assert_eq!(bad.issues[0].line, Some(2));
+        assert!(bad.issues[0].column.is_some());
+
+        let unknown = validate_draft("[scoring]\nbogus = 1\n");
+        assert!(!unknown.valid);
+        assert_eq!(unknown.issues[0].line, Some(2));
+    }
+}
@@ -0,0 +1,274 @@
+//! Spend across past runs for the cost dashboard.
+//!
+//! The breakdowns come from each run's terminal agent events, so they cover
+//! the runs still on disk. Daily and monthly totals come from the cost
+//! ledger, which is what `[scoring.budget]` period limits are enforced
+//! against and which outlives cleaned-up runs.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use hydra_core::artifact::{
+    CostLedger, EventReader, RunEvent, RunLayout, RunManifest, RunStatus, SpendPeriod,
+};
+use hydra_core::config::BudgetConfig;
+
+use crate::history::terminal_event;
+use crate::ipc_types::{CostBucket, CostBudgetStatus, CostSummary, IpcErrorPayload};
+
+/// Share of `budget.max_cost_usd` at which a run counts as near the limit.
+const NEAR_BUDGET_RATIO: f64 = 0.8;
+
+/// Reporting window: `day`, `week`, `month` (30 days) or `all`.
+pub(crate) fn period_start(
+    period: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, IpcErrorPayload> {
+    match period {
+        "day" => Ok(Some(now - Duration::days(1))),
+        "week" => Ok(Some(now - Duration::days(7))),
+        "month" => Ok(Some(now - Duration::days(30))),
+        "all" => Ok(None),
+        other => Err(IpcErrorPayload::validation(format!(
+            "unknown cost period '{other}' (expected day, week, month or all)"
+        ))),
+    }
+}
+
+pub(crate) fn cost_summary(
+    hydra_root: &Path,
+    period: &str,
+    budget: &BudgetConfig,
+    now: DateTime<Utc>,
+) -> Result<CostSummary, IpcErrorPayload> {
+    let since = period_start(period, now)?;
+    let run_ids = RunLayout::list_runs(hydra_root)
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to list runs: {e}")))?;
+
+    let mut by_agent = BTreeMap::new();
+    let mut by_provider = BTreeMap::new();
+    let mut by_day = BTreeMap::new();
+    let mut total = Spend::default();
+    let mut runs = 0;
+    let mut runs_near_limit = 0;
+    let mut runs_over_limit = 0;
+
+    for run_id in run_ids {
+        let layout = RunLayout::new(hydra_root, run_id);
+        let Ok(manifest) = RunManifest::read_from(&layout.manifest_path()) else {
+            continue;
+        };
+        if manifest.status == RunStatus::Running
+            || manifest.replay_of.is_some()
+            || since.is_some_and(|since| manifest.started_at < since)
+        {
+            continue;
+        }
+        runs += 1;
+
+        let events = EventReader::read_all(&layout.events_path()).unwrap_or_default();
+        let day = manifest.started_at.format("%Y-%m-%d").to_string();
+        let mut run_spend = Spend::default();
+        for agent in &manifest.agents {
+            let spend = agent_spend(&events, &agent.agent_key);
+            run_spend.add(&spend);
+            by_agent
+                .entry(agent.agent_key.clone())
+                .or_insert_with(Spend::default)
+                .add_run(&spend);
+            by_provider
+                .entry(provider_for(&agent.agent_key).to_string())
+                .or_insert_with(Spend::default)
+                .add_run(&spend);
+        }
+        by_day
+            .entry(day)
+            .or_insert_with(Spend::default)
+            .add_run(&run_spend);
+        total.add(&run_spend);
+
+        if let (Some(limit), Some(cost)) = (budget.max_cost_usd, run_spend.cost_usd) {
+            if cost >= limit {
+                runs_over_limit += 1;
+            } else if cost >= limit * NEAR_BUDGET_RATIO {
+                runs_near_limit += 1;
+            }
+        }
+    }
+
+    let ledger = CostLedger::new(hydra_root);
+    let spent_in = |period: SpendPeriod| {
+        ledger
+            .spend_since(period.start(now))
+            .map_err(|e| IpcErrorPayload::internal(format!("failed to read cost ledger: {e}")))
+    };
+
+    Ok(CostSummary {
+        period: period.to_string(),
+        since: since.map(|since| since.to_rfc3339()),
+        runs,
+        total_cost_usd: total.cost_usd,
+        total_tokens: total.tokens,
+        by_agent: buckets(by_agent),
+        by_provider: buckets(by_provider),
+        by_day: buckets(by_day),
+        budget: CostBudgetStatus {
+            max_cost_usd_per_run: budget.max_cost_usd,
+            max_tokens_per_run: budget.max_tokens_total,
+            runs_near_limit,
+            runs_over_limit,
+            max_cost_usd_per_day: budget.max_cost_usd_per_day,
+            max_cost_usd_per_month: budget.max_cost_usd_per_month,
+            spent_today_usd: spent_in(SpendPeriod::Day)?,
+            spent_this_month_usd: spent_in(SpendPeriod::Month)?,
+        },
+    })
+}
+
+/// Billing provider behind an adapter, for grouping spend.
+fn provider_for(agent_key: &str) -> &str {
+    match agent_key {
+        "claude" => "anthropic",
+        "codex" => "openai",
+        "cursor" => "cursor",
+        other => other,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Spend {
+    cost_usd: Option<f64>,
+    tokens: u64,
+    runs: usize,
+}
+
+impl Spend {
+    fn add(&mut self, other: &Spend) {
+        if let Some(cost) = other.cost_usd {
+            *self.cost_usd.get_or_insert(0.0) += cost;
+        }
+        self.tokens += other.tokens;
+    }
+
+    fn add_run(&mut self, other: &Spend) {
+        self.add(other);
+        self.runs += 1;
+    }
+}
+
+fn agent_spend(events: &[RunEvent], agent_key: &str) -> Spend {
+    let terminal = terminal_event(events, agent_key);
+    Spend {
+        cost_usd: terminal
+            .and_then(|e| e.data.get("estimated_cost_usd"))
+            .and_then(serde_json::Value::as_f64),
+        tokens: terminal
+            .and_then(|e| e.data.get("total_tokens"))
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0),
+        runs: 0,
+    }
+}
+
+fn buckets(map: BTreeMap<String, Spend>) -> Vec<CostBucket> {
+    map.into_iter()
+        .map(|(key, spend)| CostBucket {
+            key,
+            cost_usd: spend.cost_usd,
+            tokens: spend.tokens,
+            runs: spend.runs,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::{EventKind, EventWriter};
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+
+    fn write_run(hydra_root: &Path, age: Duration, costs: &[(&str, Option<f64>, u64)]) {
+        let keys: Vec<&str> = costs.iter().map(|(key, _, _)| *key).collect();
+        let (layout, mut manifest) = new_run(hydra_root, &keys);
+        manifest.started_at = Utc::now() - age;
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let mut writer = EventWriter::create(&layout.events_path()).unwrap();
+        for (key, cost, tokens) in costs {
+            writer
+                .write_event(&RunEvent::new(
+                    EventKind::AgentCompleted,
+                    Some(key.to_string()),
+                    serde_json::json!({ "estimated_cost_usd": cost, "total_tokens": tokens }),
+                ))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn aggregates_by_agent_provider_and_day() {
+        let tmp = TempDir::new().unwrap();
+        write_run(
+            tmp.path(),
+            Duration::hours(1),
+            &[("claude", Some(0.5), 1000), ("codex", None, 400)],
+        );
+        write_run(
+            tmp.path(),
+            Duration::hours(2),
+            &[("claude", Some(0.25), 500)],
+        );
+        write_run(
+            tmp.path(),
+            Duration::days(10),
+            &[("claude", Some(4.0), 9000)],
+        );
+
+        let budget = BudgetConfig {
+            max_tokens_total: None,
+            max_cost_usd: Some(0.6),
+            ..BudgetConfig::default()
+        };
+        let summary = cost_summary(tmp.path(), "week", &budget, Utc::now()).unwrap();
+
+        assert_eq!(summary.runs, 2);
+        assert_eq!(summary.total_cost_usd, Some(0.75));
+        assert_eq!(summary.total_tokens, 1900);
+
+        let claude = &summary.by_agent[0];
+        assert_eq!(
+            (claude.key.as_str(), claude.cost_usd, claude.runs),
+            ("claude", Some(0.75), 2)
+        );
+        let codex = &summary.by_agent[1];
+        assert_eq!((codex.cost_usd, codex.tokens), (None, 400));
+        assert_eq!(summary.by_provider[0].key, "anthropic");
+        assert_eq!(summary.by_provider[1].key, "openai");
+        assert!(!summary.by_day.is_empty());
+        assert_eq!(summary.budget.runs_near_limit, 1);
+        assert_eq!(summary.budget.runs_over_limit, 0);
+
+        let all = cost_summary(tmp.path(), "all", &budget, Utc::now()).unwrap();
+        assert_eq!(all.runs, 3);
+        assert_eq!(all.budget.runs_over_limit, 1);
+        // The ledger is backfilled from the same terminal events, which were
+        // all written just now.
+        assert_eq!(all.budget.spent_today_usd, 4.75);
+    }
+
+    #[test]
+    fn rejects_unknown_period() {
+        let tmp = TempDir::new().unwrap();
+        let err = cost_summary(
+            tmp.path(),
+            "fortnight",
+            &BudgetConfig::default(),
+            Utc::now(),
+        )
+        .unwrap_err();
+        assert_eq!(err.code, "validation_error");
+    }
+}
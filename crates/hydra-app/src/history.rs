@@ -0,0 +1,488 @@
+//! Past runs read back from `.hydra/runs`, so the app can show them after
+//! a restart.
+//!
+//! The manifest gives membership, status and timing; score files give the
+//! scoreboard; terminal agent events give per-agent status, cost and
+//! duration.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use hydra_core::artifact::{
+    export_run_bundle, has_tags, read_agent_score, AgentReview, EventKind, EventReader,
+    FileReviewState, RunEvent, RunLayout, RunManifest, RunStatus,
+};
+use hydra_core::scoring::ranking::AgentScore;
+use uuid::Uuid;
+
+use crate::ipc_types::{
+    run_status_label, AgentResult, AgentReviewPayload, DimensionScoreIpc, FileReviewEntry,
+    IpcErrorPayload, RaceResult, RunBundle, RunDetail, RunSummary,
+};
+
+/// Every run with a readable manifest that carries all of `tags`, newest
+/// first.
+pub(crate) fn list_runs(
+    hydra_root: &Path,
+    tags: &[String],
+) -> Result<Vec<RunSummary>, IpcErrorPayload> {
+    let run_ids = RunLayout::list_runs(hydra_root)
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to list runs: {e}")))?;
+
+    let mut runs: Vec<RunSummary> = run_ids
+        .into_iter()
+        .filter_map(|run_id| match load_run(hydra_root, run_id) {
+            Ok(detail) => has_tags(&detail.summary.tags, tags).then_some(detail.summary),
+            Err(e) => {
+                tracing::debug!(run_id = %run_id, error = %e, "skipping unreadable run");
+                None
+            }
+        })
+        .collect();
+    runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(runs)
+}
+
+pub(crate) fn run_detail(hydra_root: &Path, run_id: &str) -> Result<RunDetail, IpcErrorPayload> {
+    load_run(hydra_root, parse_run_id(run_id)?)
+}
+
+/// Remove a finished run's artifacts. Branches and retained worktrees are
+/// left alone.
+pub(crate) fn delete_run(hydra_root: &Path, run_id: &str) -> Result<(), IpcErrorPayload> {
+    let layout = RunLayout::new(hydra_root, parse_run_id(run_id)?);
+    if !layout.base_dir().exists() {
+        return Err(IpcErrorPayload::not_found(format!(
+            "run {run_id} not found"
+        )));
+    }
+    if let Ok(manifest) = RunManifest::read_from(&layout.manifest_path()) {
+        if manifest.status == RunStatus::Running {
+            return Err(IpcErrorPayload::validation(format!(
+                "run {run_id} is still running; cancel it before deleting"
+            )));
+        }
+    }
+    layout
+        .cleanup()
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to delete run {run_id}: {e}")))
+}
+
+/// Bundle a run's artifacts as a `.tar.gz` in `dest_dir`, or in
+/// `.hydra/exports` when none is given.
+pub(crate) fn export_run(
+    hydra_root: &Path,
+    run_id: &str,
+    dest_dir: Option<&Path>,
+) -> Result<RunBundle, IpcErrorPayload> {
+    let layout = RunLayout::new(hydra_root, parse_run_id(run_id)?);
+    if !layout.base_dir().exists() {
+        return Err(IpcErrorPayload::not_found(format!(
+            "run {run_id} not found"
+        )));
+    }
+    let dest_dir = dest_dir.map_or_else(|| hydra_root.join("exports"), Path::to_path_buf);
+    let path = export_run_bundle(&layout, &dest_dir)
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to export run {run_id}: {e}")))?;
+    let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(RunBundle {
+        run_id: run_id.to_string(),
+        path: path.display().to_string(),
+        size_bytes,
+    })
+}
+
+/// Record a review decision (`pending`, `approved` or `rejected`) for one
+/// file of an agent's diff and return the agent's full review.
+pub(crate) fn set_file_review(
+    hydra_root: &Path,
+    run_id: &str,
+    agent_key: &str,
+    path: &str,
+    state: &str,
+    note: Option<String>,
+) -> Result<AgentReviewPayload, IpcErrorPayload> {
+    let state = match state {
+        "pending" => FileReviewState::Pending,
+        "approved" => FileReviewState::Approved,
+        "rejected" => FileReviewState::Rejected,
+        other => {
+            return Err(IpcErrorPayload::validation(format!(
+                "unknown review state '{other}' (expected pending, approved or rejected)"
+            )))
+        }
+    };
+    if path.trim().is_empty() || Path::new(path).is_absolute() {
+        return Err(IpcErrorPayload::validation(format!(
+            "invalid review path '{path}': expected a repository-relative path"
+        )));
+    }
+
+    let layout = agent_layout(hydra_root, run_id, agent_key)?;
+    let review_path = layout.agent_review(agent_key);
+    let mut review = AgentReview::read_from(&review_path)
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to read review: {e}")))?;
+    review.set(path, state, note);
+    std::fs::create_dir_all(layout.agent_dir(agent_key))
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to create agent directory: {e}")))?;
+    review
+        .write_to(&review_path)
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to save review: {e}")))?;
+    Ok(review_payload(run_id, agent_key, &review))
+}
+
+pub(crate) fn file_review(
+    hydra_root: &Path,
+    run_id: &str,
+    agent_key: &str,
+) -> Result<AgentReviewPayload, IpcErrorPayload> {
+    let layout = agent_layout(hydra_root, run_id, agent_key)?;
+    let review = AgentReview::read_from(&layout.agent_review(agent_key))
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to read review: {e}")))?;
+    Ok(review_payload(run_id, agent_key, &review))
+}
+
+fn agent_layout(
+    hydra_root: &Path,
+    run_id: &str,
+    agent_key: &str,
+) -> Result<RunLayout, IpcErrorPayload> {
+    let layout = RunLayout::new(hydra_root, parse_run_id(run_id)?);
+    let manifest = RunManifest::read_from(&layout.manifest_path())
+        .map_err(|_| IpcErrorPayload::not_found(format!("run {run_id} not found")))?;
+    if !manifest.agents.iter().any(|a| a.agent_key == agent_key) {
+        return Err(IpcErrorPayload::not_found(format!(
+            "agent '{agent_key}' not found in run {run_id}"
+        )));
+    }
+    Ok(layout)
+}
+
+fn review_payload(run_id: &str, agent_key: &str, review: &AgentReview) -> AgentReviewPayload {
+    AgentReviewPayload {
+        run_id: run_id.to_string(),
+        agent_key: agent_key.to_string(),
+        approved_paths: review.approved_paths(),
+        files: review
+            .files
+            .iter()
+            .map(|(path, file)| FileReviewEntry {
+                path: path.clone(),
+                state: match file.state {
+                    FileReviewState::Pending => "pending",
+                    FileReviewState::Approved => "approved",
+                    FileReviewState::Rejected => "rejected",
+                }
+                .to_string(),
+                note: file.note.clone(),
+                updated_at: file.updated_at.to_rfc3339(),
+            })
+            .collect(),
+    }
+}
+
+fn parse_run_id(run_id: &str) -> Result<Uuid, IpcErrorPayload> {
+    Uuid::parse_str(run_id).map_err(|e| IpcErrorPayload::validation(format!("invalid run_id: {e}")))
+}
+
+fn load_run(hydra_root: &Path, run_id: Uuid) -> Result<RunDetail, IpcErrorPayload> {
+    let layout = RunLayout::new(hydra_root, run_id);
+    if !layout.base_dir().exists() {
+        return Err(IpcErrorPayload::not_found(format!(
+            "run {run_id} not found"
+        )));
+    }
+    let manifest = RunManifest::read_from(&layout.manifest_path())
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to read manifest: {e}")))?;
+
+    let run_events = EventReader::read_all(&layout.events_path()).unwrap_or_default();
+    let scores: HashMap<String, AgentScore> = manifest
+        .agents
+        .iter()
+        .filter_map(|agent| {
+            let score = read_agent_score(&layout, &agent.agent_key).ok()??;
+            Some((agent.agent_key.clone(), score))
+        })
+        .collect();
+
+    let mut total_cost: Option<f64> = None;
+    let agents: Vec<AgentResult> = manifest
+        .agents
+        .iter()
+        .map(|agent| {
+            let key = &agent.agent_key;
+            let terminal = terminal_event(&run_events, key);
+            if let Some(cost) = terminal
+                .and_then(|e| e.data.get("estimated_cost_usd"))
+                .and_then(serde_json::Value::as_f64)
+            {
+                *total_cost.get_or_insert(0.0) += cost;
+            }
+            let agent_events = EventReader::read_all(&layout.agent_events(key)).unwrap_or_default();
+            let duration_ms = terminal_event(&agent_events, key)
+                .and_then(|e| e.data.get("duration_ms"))
+                .and_then(serde_json::Value::as_u64);
+            let score = scores.get(key);
+
+            AgentResult {
+                agent_key: key.clone(),
+                status: terminal
+                    .map(agent_status_label)
+                    .unwrap_or_else(|| "unknown".to_string()),
+                duration_ms,
+                score: score.map(|s| s.composite),
+                mergeable: score.map(|s| s.mergeable),
+                gate_failures: score.map(|s| s.gate_failures.clone()).unwrap_or_default(),
+                dimensions: score
+                    .iter()
+                    .flat_map(|s| &s.dimensions)
+                    .map(|dim| DimensionScoreIpc {
+                        name: dim.name.clone(),
+                        score: dim.score,
+                        evidence: dim.evidence.clone(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let winner = scores
+        .values()
+        .filter(|s| s.mergeable)
+        .max_by(|a, b| a.composite.total_cmp(&b.composite))
+        .map(|s| s.agent_key.clone());
+    let duration_ms = manifest
+        .completed_at
+        .map(|done| (done - manifest.started_at).num_milliseconds().max(0) as u64);
+    let status = run_status_label(&manifest.status);
+
+    Ok(RunDetail {
+        summary: RunSummary {
+            run_id: run_id.to_string(),
+            status: status.clone(),
+            base_ref: manifest.base_ref.clone(),
+            task_prompt: manifest.task_prompt.clone(),
+            started_at: manifest.started_at.to_rfc3339(),
+            completed_at: manifest.completed_at.map(|t| t.to_rfc3339()),
+            duration_ms,
+            agents: manifest
+                .agents
+                .iter()
+                .map(|a| a.agent_key.clone())
+                .collect(),
+            winner,
+            total_cost,
+            tags: manifest.tags.clone(),
+        },
+        result: RaceResult {
+            run_id: run_id.to_string(),
+            status,
+            agents,
+            duration_ms,
+            total_cost,
+        },
+    })
+}
+
+/// The agent's last completed/failed event.
+pub(crate) fn terminal_event<'a>(events: &'a [RunEvent], agent_key: &str) -> Option<&'a RunEvent> {
+    events
+        .iter()
+        .rev()
+        .filter(|e| matches!(e.kind, EventKind::AgentCompleted | EventKind::AgentFailed))
+        .find(|e| e.agent_key.as_deref() == Some(agent_key))
+}
+
+/// Run-level agent events record the status in `Debug` form (`TimedOut`).
+fn agent_status_label(event: &RunEvent) -> String {
+    let status = match event.data.get("status").and_then(serde_json::Value::as_str) {
+        Some("Completed") => RunStatus::Completed,
+        Some("TimedOut") => RunStatus::TimedOut,
+        Some("Interrupted") => RunStatus::Interrupted,
+        Some("Running") => RunStatus::Running,
+        Some(_) => RunStatus::Failed,
+        None if event.kind == EventKind::AgentCompleted => RunStatus::Completed,
+        None => RunStatus::Failed,
+    };
+    run_status_label(&status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::EventWriter;
+    use hydra_core::scoring::DimensionScore;
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+
+    fn write_run(hydra_root: &Path, status: RunStatus) -> Uuid {
+        let (layout, manifest) = new_run(hydra_root, &["claude", "codex"]);
+        let run_id = layout.run_id();
+        let mut manifest = manifest.with_task_prompt(
+            "fix the bug",
+            false,
+            &hydra_core::security::SecretRedactor::new(),
+        );
+        if status != RunStatus::Running {
+            manifest.mark_completed(status);
+        }
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let mut writer = EventWriter::create(&layout.events_path()).unwrap();
+        writer
+            .write_event(&RunEvent::new(
+                EventKind::AgentCompleted,
+                Some("claude".to_string()),
+                serde_json::json!({ "status": "Completed", "estimated_cost_usd": 0.25 }),
+            ))
+            .unwrap();
+        writer
+            .write_event(&RunEvent::new(
+                EventKind::AgentFailed,
+                Some("codex".to_string()),
+                serde_json::json!({ "status": "TimedOut", "estimated_cost_usd": 0.5 }),
+            ))
+            .unwrap();
+
+        let mut agent_writer = EventWriter::create(&layout.agent_events("claude")).unwrap();
+        agent_writer
+            .write_event(&RunEvent::new(
+                EventKind::AgentCompleted,
+                Some("claude".to_string()),
+                serde_json::json!({ "exit_code": 0, "duration_ms": 1200 }),
+            ))
+            .unwrap();
+
+        let score = AgentScore {
+            agent_key: "claude".to_string(),
+            dimensions: vec![DimensionScore {
+                name: "build".to_string(),
+                score: 100.0,
+                evidence: serde_json::json!({}),
+            }],
+            composite: 92.5,
+            mergeable: true,
+            gate_failures: Vec::new(),
+            partial: false,
+        };
+        std::fs::write(
+            layout.agent_score("claude"),
+            serde_json::to_string(&score).unwrap(),
+        )
+        .unwrap();
+        run_id
+    }
+
+    #[test]
+    fn run_detail_rebuilds_scoreboard_from_artifacts() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = write_run(tmp.path(), RunStatus::Completed);
+
+        let detail = run_detail(tmp.path(), &run_id.to_string()).unwrap();
+        assert_eq!(detail.summary.status, "completed");
+        assert_eq!(detail.summary.task_prompt.as_deref(), Some("fix the bug"));
+        assert_eq!(detail.summary.winner.as_deref(), Some("claude"));
+        assert_eq!(detail.summary.total_cost, Some(0.75));
+        assert!(detail.summary.duration_ms.is_some());
+
+        let claude = &detail.result.agents[0];
+        assert_eq!(claude.status, "completed");
+        assert_eq!(claude.duration_ms, Some(1200));
+        assert_eq!(claude.score, Some(92.5));
+        assert_eq!(claude.mergeable, Some(true));
+        assert_eq!(claude.dimensions.len(), 1);
+
+        let codex = &detail.result.agents[1];
+        assert_eq!(codex.status, "timed_out");
+        assert_eq!(codex.score, None);
+    }
+
+    #[test]
+    fn file_review_is_persisted_per_agent() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = write_run(tmp.path(), RunStatus::Completed).to_string();
+
+        set_file_review(
+            tmp.path(),
+            &run_id,
+            "claude",
+            "src/lib.rs",
+            "approved",
+            None,
+        )
+        .unwrap();
+        let review = set_file_review(
+            tmp.path(),
+            &run_id,
+            "claude",
+            "src/main.rs",
+            "rejected",
+            Some("keeps the old API".to_string()),
+        )
+        .unwrap();
+        assert_eq!(review.approved_paths, vec!["src/lib.rs"]);
+        assert_eq!(review.files.len(), 2);
+
+        let loaded = file_review(tmp.path(), &run_id, "claude").unwrap();
+        assert_eq!(loaded.files[1].state, "rejected");
+        assert_eq!(loaded.files[1].note.as_deref(), Some("keeps the old API"));
+        assert!(file_review(tmp.path(), &run_id, "codex")
+            .unwrap()
+            .files
+            .is_empty());
+
+        let err =
+            set_file_review(tmp.path(), &run_id, "claude", "a.rs", "maybe", None).unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        let err = file_review(tmp.path(), &run_id, "gemini").unwrap_err();
+        assert_eq!(err.code, "not_found");
+    }
+
+    #[test]
+    fn list_runs_skips_directories_without_manifest() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = write_run(tmp.path(), RunStatus::Failed);
+        std::fs::create_dir_all(tmp.path().join("runs").join(Uuid::new_v4().to_string())).unwrap();
+
+        let runs = list_runs(tmp.path(), &[]).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].run_id, run_id.to_string());
+        assert_eq!(runs[0].status, "failed");
+        assert!(list_runs(tmp.path(), &["backend".to_string()])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn delete_run_refuses_running_runs() {
+        let tmp = TempDir::new().unwrap();
+        let running = write_run(tmp.path(), RunStatus::Running);
+        let err = delete_run(tmp.path(), &running.to_string()).unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        assert!(RunLayout::new(tmp.path(), running).base_dir().exists());
+
+        let done = write_run(tmp.path(), RunStatus::Completed);
+        delete_run(tmp.path(), &done.to_string()).unwrap();
+        assert!(!RunLayout::new(tmp.path(), done).base_dir().exists());
+
+        let err = delete_run(tmp.path(), &done.to_string()).unwrap_err();
+        assert_eq!(err.code, "not_found");
+    }
+
+    #[test]
+    fn export_run_writes_bundle_under_hydra_exports() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = write_run(tmp.path(), RunStatus::Completed);
+
+        let bundle = export_run(tmp.path(), &run_id.to_string(), None).unwrap();
+        let expected = tmp
+            .path()
+            .join("exports")
+            .join(format!("hydra-run-{run_id}.tar.gz"));
+        assert_eq!(bundle.path, expected.display().to_string());
+        assert!(bundle.size_bytes > 0);
+
+        let err = export_run(tmp.path(), &Uuid::new_v4().to_string(), None).unwrap_err();
+        assert_eq!(err.code, "not_found");
+    }
+}
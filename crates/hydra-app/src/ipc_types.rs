@@ -1,8 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 use hydra_core::adapter::{
-    AdapterTier, CapabilityConfidence, CapabilitySet, DetectStatus, ProbeResult,
+    AdapterTier, CapabilityConfidence, CapabilitySet, DetectStatus, PingResult, PingStatus,
+    ProbeResult, RegistryError,
 };
+use hydra_core::artifact::{ArtifactError, CandidateCommits, RunStatus};
+use hydra_core::config::HydraConfig;
+use hydra_core::diagnostics::{Remediation, Severity};
+use hydra_core::error::{ErrorCode, HydraError};
+use hydra_core::orchestrator::{OrchestratorError, PromoteOutcome, RaceOutcome};
+use hydra_core::security::{ConsentError, TrustError, TrustedRepo, UnsafeConsent};
 
 // ---------------------------------------------------------------------------
 // Doctor / Preflight types
@@ -11,10 +18,14 @@ use hydra_core::adapter::{
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticCheck {
+    /// Stable ID shared with `hydra doctor`; key off this, not `name`.
+    pub id: String,
     pub name: String,
     pub description: String,
+    pub severity: Severity,
     pub status: CheckStatus,
     pub evidence: Option<String>,
+    pub remediation: Option<Remediation>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -62,6 +73,29 @@ pub struct PreflightResult {
     pub checks: Vec<DiagnosticCheck>,
     pub adapters: Vec<AdapterInfo>,
     pub warnings: Vec<String>,
+    /// Live-check results; empty unless preflight ran in deep mode.
+    pub pings: Vec<AdapterPing>,
+}
+
+/// Result of sending a trivial prompt to an adapter (deep preflight).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterPing {
+    pub key: String,
+    pub status: PingStatus,
+    pub duration_ms: u64,
+    pub detail: Option<String>,
+}
+
+impl From<PingResult> for AdapterPing {
+    fn from(result: PingResult) -> Self {
+        Self {
+            key: result.adapter_key,
+            status: result.status,
+            duration_ms: result.duration_ms,
+            detail: result.detail,
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -75,6 +109,9 @@ pub struct RaceRequest {
     pub agents: Vec<String>,
     pub allow_experimental: bool,
     pub cwd: Option<String>,
+    /// Labels recorded in the run manifest.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +121,23 @@ pub struct RaceStarted {
     pub agents: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaceCancelResult {
+    pub run_id: String,
+    /// `cancelling` until the race task stops; poll for `cancelled`.
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaceApprovalResult {
+    pub run_id: String,
+    pub approval_id: String,
+    /// `approve` or `deny`.
+    pub decision: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentStreamEvent {
@@ -116,11 +170,31 @@ pub struct RaceResult {
 #[serde(rename_all = "camelCase")]
 pub struct RaceEventBatch {
     pub run_id: String,
+    /// Empty when `encoding` is set; the events are in `compressed_events`.
     pub events: Vec<AgentStreamEvent>,
     pub next_cursor: u64,
     pub done: bool,
     pub status: String,
     pub error: Option<String>,
+    /// Cap actually applied to this poll, after clamping the requested one.
+    pub max_events: u64,
+    /// `"gzip"` when `compressed_events` holds the base64 gzip of the
+    /// events array.
+    pub encoding: Option<String>,
+    pub compressed_events: Option<String>,
+}
+
+/// Client-side knobs for `poll_race_events`. All fields are optional so
+/// older callers keep getting plain per-line batches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RacePollOptions {
+    /// Requested cap on source events per poll.
+    pub max_events: Option<usize>,
+    /// Merge consecutive output lines per agent into `{"lines": [...]}`.
+    pub coalesce: bool,
+    /// Allow a gzip-compressed batch body when it is large enough.
+    pub gzip: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -142,6 +216,390 @@ pub struct AgentResult {
     pub dimensions: Vec<DimensionScoreIpc>,
 }
 
+impl From<&RaceOutcome> for RaceResult {
+    fn from(outcome: &RaceOutcome) -> Self {
+        Self {
+            run_id: outcome.run_id.to_string(),
+            status: run_status_label(&outcome.status),
+            agents: outcome
+                .agents
+                .iter()
+                .map(|agent| AgentResult {
+                    agent_key: agent.agent_key.clone(),
+                    status: run_status_label(&agent.status),
+                    duration_ms: Some(agent.duration_ms),
+                    score: agent.score.as_ref().map(|s| s.composite),
+                    mergeable: agent.score.as_ref().map(|s| s.mergeable),
+                    gate_failures: agent
+                        .score
+                        .as_ref()
+                        .map(|s| s.gate_failures.clone())
+                        .unwrap_or_default(),
+                    dimensions: agent
+                        .score
+                        .iter()
+                        .flat_map(|s| &s.dimensions)
+                        .map(|dim| DimensionScoreIpc {
+                            name: dim.name.clone(),
+                            score: dim.score,
+                            evidence: dim.evidence.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            duration_ms: Some(outcome.duration_ms),
+            total_cost: outcome.usage.estimated_cost_usd,
+        }
+    }
+}
+
+/// An interactive session's work, scored and registered as a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromotedCandidate {
+    pub session_id: String,
+    pub run_id: String,
+    pub branch: String,
+    pub base_ref: String,
+    pub candidate: AgentResult,
+}
+
+impl PromotedCandidate {
+    pub fn new(session_id: String, outcome: &PromoteOutcome) -> Self {
+        let score = &outcome.score;
+        Self {
+            session_id,
+            run_id: outcome.run_id.to_string(),
+            branch: outcome.branch.clone(),
+            base_ref: outcome.base_ref.clone(),
+            candidate: AgentResult {
+                agent_key: outcome.agent_key.clone(),
+                status: run_status_label(&RunStatus::Completed),
+                duration_ms: None,
+                score: Some(score.composite),
+                mergeable: Some(score.mergeable),
+                gate_failures: score.gate_failures.clone(),
+                dimensions: score
+                    .dimensions
+                    .iter()
+                    .map(|dim| DimensionScoreIpc {
+                        name: dim.name.clone(),
+                        score: dim.score,
+                        evidence: dim.evidence.clone(),
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Snake-case status as the frontend expects (`timed_out`, `completed`).
+pub fn run_status_label(status: &RunStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Run history types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub run_id: String,
+    pub status: String,
+    pub base_ref: String,
+    /// Secret-redacted unless full prompt storage was enabled.
+    pub task_prompt: Option<String>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub agents: Vec<String>,
+    /// Highest-scoring mergeable agent.
+    pub winner: Option<String>,
+    pub total_cost: Option<f64>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDetail {
+    pub summary: RunSummary,
+    /// Scoreboard rebuilt from the run's artifacts.
+    pub result: RaceResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDeleted {
+    pub run_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunBundle {
+    pub run_id: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Config editor types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    /// The `hydra.toml` races in this repository use, which may not exist yet.
+    pub path: String,
+    pub exists: bool,
+    pub config: HydraConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    /// Dotted key path such as `supervisor.hard_timeout_seconds`, when known.
+    pub field: Option<String>,
+    pub message: String,
+    /// 1-based position in the submitted draft, for TOML syntax errors.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigValidation {
+    pub valid: bool,
+    pub issues: Vec<ConfigIssue>,
+    /// The parsed config when the draft is valid.
+    pub config: Option<HydraConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigUpdateResult {
+    pub path: String,
+    /// False when validation failed; the file is left unchanged.
+    pub saved: bool,
+    pub issues: Vec<ConfigIssue>,
+    /// The config now in effect.
+    pub config: HydraConfig,
+}
+
+// ---------------------------------------------------------------------------
+// Notification types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    /// Show OS notifications when a race finishes, a budget stop triggers,
+    /// or a merge preview finds conflicts.
+    pub enabled: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Event search types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunEventSearchFilters {
+    /// Only these agents; empty searches every agent.
+    pub agents: Vec<String>,
+    /// Only these event kinds (`agent_stdout`, `agent_failed`, ...).
+    pub kinds: Vec<String>,
+    /// RFC 3339 bounds on the event timestamp, inclusive.
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub case_sensitive: bool,
+    /// Maximum matches returned (default 500, at most 5000).
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunEventMatch {
+    /// `run` for the run's own log, otherwise the agent whose log matched.
+    pub source: String,
+    /// 1-based line in that log.
+    pub line_number: usize,
+    pub timestamp: String,
+    pub kind: String,
+    pub agent_key: Option<String>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunEventSearch {
+    pub run_id: String,
+    pub query: String,
+    /// Ordered by timestamp.
+    pub matches: Vec<RunEventMatch>,
+    pub lines_scanned: usize,
+    /// More matches exist beyond `limit`.
+    pub truncated: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Cost types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostSummary {
+    /// `day`, `week`, `month` or `all`.
+    pub period: String,
+    /// Start of the window (RFC 3339); `None` for `all`.
+    pub since: Option<String>,
+    /// Finished runs started inside the window.
+    pub runs: usize,
+    /// `None` when no agent in the window reported a cost.
+    pub total_cost_usd: Option<f64>,
+    pub total_tokens: u64,
+    pub by_agent: Vec<CostBucket>,
+    pub by_provider: Vec<CostBucket>,
+    /// Keyed by UTC date (`YYYY-MM-DD`) of the run start.
+    pub by_day: Vec<CostBucket>,
+    pub budget: CostBudgetStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostBucket {
+    pub key: String,
+    pub cost_usd: Option<f64>,
+    pub tokens: u64,
+    /// Runs contributing to this bucket.
+    pub runs: usize,
+}
+
+/// Spend against the `[scoring.budget]` limits in `hydra.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostBudgetStatus {
+    pub max_cost_usd_per_run: Option<f64>,
+    pub max_tokens_per_run: Option<u64>,
+    /// Runs that used at least 80% of `max_cost_usd_per_run`.
+    pub runs_near_limit: usize,
+    pub runs_over_limit: usize,
+    pub max_cost_usd_per_day: Option<f64>,
+    pub max_cost_usd_per_month: Option<f64>,
+    /// Ledger spend in the current UTC day and month, which the daily and
+    /// monthly limits are enforced against.
+    pub spent_today_usd: f64,
+    pub spent_this_month_usd: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Repository types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoSummary {
+    pub path: String,
+    pub name: String,
+    pub active: bool,
+    /// Races started for this repository since the app launched.
+    pub run_ids: Vec<String>,
+    /// Whether adapters have been probed with this repository's config.
+    pub probed: bool,
+}
+
+// ---------------------------------------------------------------------------
+// App settings types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub window: WindowLayout,
+    /// Agents selected for the most recent race, preselected next time.
+    pub last_used_agents: Vec<String>,
+    /// Experimental adapters the user has opted into racing.
+    pub experimental_adapters: Vec<String>,
+    pub telemetry: TelemetrySettings,
+    pub notifications: NotificationSettings,
+    /// Repository roots added in the app, restored at startup.
+    pub repos: Vec<String>,
+    pub active_repo: Option<String>,
+    /// Language for error messages, e.g. `de`. Unset follows the system
+    /// locale.
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowLayout {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub maximized: bool,
+    pub sidebar_width: Option<u32>,
+    pub active_view: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelemetrySettings {
+    /// Off unless the user opts in.
+    pub enabled: bool,
+}
+
+/// The repository's unsafe mode grant, if any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeConsentStatus {
+    pub active: bool,
+    pub token: Option<String>,
+    pub granted_at: Option<String>,
+    pub expires_at: Option<String>,
+}
+
+impl UnsafeConsentStatus {
+    pub fn from_consent(consent: Option<&UnsafeConsent>) -> Self {
+        Self {
+            active: consent.is_some_and(|c| !c.is_expired_at(chrono::Utc::now())),
+            token: consent.map(|c| c.token.clone()),
+            granted_at: consent.map(|c| c.granted_at.to_rfc3339()),
+            expires_at: consent.map(|c| c.expires_at.to_rfc3339()),
+        }
+    }
+}
+
+/// Whether the user has trusted a repository to run agents and scoring.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceTrustStatus {
+    pub repo_root: String,
+    pub trusted: bool,
+    pub trusted_at: Option<String>,
+}
+
+impl WorkspaceTrustStatus {
+    pub fn new(repo_root: &std::path::Path, entry: Option<&TrustedRepo>) -> Self {
+        Self {
+            repo_root: entry
+                .map(|e| e.path.as_path())
+                .unwrap_or(repo_root)
+                .display()
+                .to_string(),
+            trusted: entry.is_some(),
+            trusted_at: entry.map(|e| e.trusted_at.to_rfc3339()),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Interactive session types (M4.2)
 // ---------------------------------------------------------------------------
@@ -153,6 +611,9 @@ pub struct InteractiveSessionRequest {
     pub task_prompt: String,
     pub allow_experimental: bool,
     pub unsafe_mode: bool,
+    /// Token from `grant_unsafe_consent`; required when `unsafe_mode` is set.
+    #[serde(default)]
+    pub unsafe_consent: Option<String>,
     pub cwd: Option<String>,
     pub cols: Option<u16>,
     pub rows: Option<u16>,
@@ -192,6 +653,22 @@ pub struct InteractiveEventBatch {
     pub error: Option<String>,
 }
 
+/// Everything a reloaded terminal view needs to reattach to a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InteractiveSessionSnapshot {
+    pub session_id: String,
+    pub agent_key: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub started_at: String,
+    /// Most recent terminal output, oldest first.
+    pub scrollback: String,
+    /// Resume `poll_interactive_events` from here; output before this cursor
+    /// is already in `scrollback`.
+    pub next_cursor: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InteractiveWriteAck {
@@ -249,106 +726,269 @@ pub struct InteractiveTransportDiagnostics {
     pub last_push_emit_at: Option<String>,
 }
 
+/// A page of a session's `transcript.ansi.log`, addressed by byte offset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTranscriptChunk {
+    pub session_id: String,
+    pub offset: u64,
+    pub next_offset: u64,
+    pub total_bytes: u64,
+    /// Raw terminal output, ANSI escapes included.
+    pub content: String,
+    pub eof: bool,
+}
+
+/// Persisted view of an interactive session, from `session.json` and
+/// `summary.json`. Counters are `None` when the session never finalized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTranscriptSummary {
+    pub session_id: String,
+    pub agent_key: String,
+    pub status: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub cwd: String,
+    pub unsafe_mode: bool,
+    pub experimental: bool,
+    pub duration_ms: Option<u64>,
+    pub event_count: Option<u64>,
+    pub output_bytes: Option<u64>,
+    pub user_input_count: Option<u64>,
+    pub transcript_bytes: u64,
+}
+
 // ---------------------------------------------------------------------------
-// IPC Error wrapper
+// IPC error
 // ---------------------------------------------------------------------------
 
+/// Machine-readable error category, serialized as `snake_case`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcErrorCode {
+    ValidationError,
+    NotFound,
+    InternalError,
+    AdapterError,
+    SafetyGate,
+    ExperimentalBlocked,
+    DirtyWorktree,
+    UnsafeBlocked,
+    BinaryMissing,
+    LaunchError,
+    /// The repository has not been trusted to run agents or scoring.
+    UntrustedWorkspace,
+}
+
+impl IpcErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ValidationError => "validation_error",
+            Self::NotFound => "not_found",
+            Self::InternalError => "internal_error",
+            Self::AdapterError => "adapter_error",
+            Self::SafetyGate => "safety_gate",
+            Self::ExperimentalBlocked => "experimental_blocked",
+            Self::DirtyWorktree => "dirty_worktree",
+            Self::UnsafeBlocked => "unsafe_blocked",
+            Self::BinaryMissing => "binary_missing",
+            Self::LaunchError => "launch_error",
+            Self::UntrustedWorkspace => "untrusted_workspace",
+        }
+    }
+
+    /// Whether the same request may succeed if sent again unchanged.
+    /// Everything else needs the input or the environment fixed first.
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::InternalError | Self::AdapterError | Self::LaunchError
+        )
+    }
+}
+
+impl std::fmt::Display for IpcErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<&str> for IpcErrorCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Error returned by every Tauri command. The frontend receives it as a
+/// JSON object rather than a `"[code] message"` string.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct IpcError {
-    pub code: String,
+pub struct IpcErrorPayload {
+    pub code: IpcErrorCode,
     pub message: String,
-    pub details: Option<String>,
+    /// Structured context for the UI, e.g. gate failures.
+    pub details: Option<serde_json::Value>,
+    pub retryable: bool,
+    /// Stable Hydra code (`HY-ADAPTER-003`) when the error came from
+    /// hydra-core; finer-grained than `code`.
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
 }
 
-impl IpcError {
-    pub fn internal(msg: impl Into<String>) -> Self {
+impl IpcErrorPayload {
+    pub fn new(code: IpcErrorCode, msg: impl Into<String>) -> Self {
         Self {
-            code: "internal_error".to_string(),
+            code,
             message: msg.into(),
             details: None,
+            retryable: code.retryable(),
+            error_code: None,
         }
     }
 
-    pub fn validation(msg: impl Into<String>) -> Self {
-        Self {
-            code: "validation_error".to_string(),
-            message: msg.into(),
-            details: None,
+    /// Attach the Hydra error code, which also decides `retryable`. In a
+    /// non-English locale the message is headed by the code's translated
+    /// summary.
+    pub fn with_error_code(mut self, code: ErrorCode) -> Self {
+        self.error_code = Some(code);
+        self.retryable = code.retryable();
+        if let Some(summary) = hydra_core::i18n::catalog().error_summary(code) {
+            self.message = format!("{summary}: {}", self.message);
         }
+        self
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn internal(msg: impl Into<String>) -> Self {
+        Self::new(IpcErrorCode::InternalError, msg)
+    }
+
+    pub fn validation(msg: impl Into<String>) -> Self {
+        Self::new(IpcErrorCode::ValidationError, msg)
     }
 
     pub fn not_found(msg: impl Into<String>) -> Self {
-        Self {
-            code: "not_found".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::NotFound, msg)
     }
 
     pub fn adapter_error(msg: impl Into<String>) -> Self {
-        Self {
-            code: "adapter_error".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::AdapterError, msg)
     }
 
     pub fn safety_gate(msg: impl Into<String>) -> Self {
-        Self {
-            code: "safety_gate".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::SafetyGate, msg)
     }
 
     pub fn experimental_blocked(msg: impl Into<String>) -> Self {
-        Self {
-            code: "experimental_blocked".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::ExperimentalBlocked, msg)
     }
 
     pub fn dirty_worktree(msg: impl Into<String>) -> Self {
-        Self {
-            code: "dirty_worktree".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::DirtyWorktree, msg)
     }
 
     pub fn unsafe_blocked(msg: impl Into<String>) -> Self {
-        Self {
-            code: "unsafe_blocked".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::UnsafeBlocked, msg)
     }
 
     pub fn binary_missing(msg: impl Into<String>) -> Self {
-        Self {
-            code: "binary_missing".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::BinaryMissing, msg)
     }
 
     pub fn launch_error(msg: impl Into<String>) -> Self {
-        Self {
-            code: "launch_error".to_string(),
-            message: msg.into(),
-            details: None,
-        }
+        Self::new(IpcErrorCode::LaunchError, msg)
     }
 }
 
-impl std::fmt::Display for IpcError {
+impl std::fmt::Display for IpcErrorPayload {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{}] {}", self.code, self.message)
     }
 }
 
+impl std::error::Error for IpcErrorPayload {}
+
+impl From<OrchestratorError> for IpcErrorPayload {
+    fn from(e: OrchestratorError) -> Self {
+        if let OrchestratorError::UnsafeConsent(e) = e {
+            return e.into();
+        }
+        let error_code = e.code();
+        let code = match &e {
+            OrchestratorError::NoAgents | OrchestratorError::NothingToPromote { .. } => {
+                IpcErrorCode::ValidationError
+            }
+            OrchestratorError::Registry(RegistryError::UnknownAdapter { .. }) => {
+                IpcErrorCode::NotFound
+            }
+            OrchestratorError::Registry(RegistryError::ExperimentalBlocked { .. }) => {
+                IpcErrorCode::ExperimentalBlocked
+            }
+            OrchestratorError::AdapterNotReady { .. } | OrchestratorError::Command(_) => {
+                IpcErrorCode::AdapterError
+            }
+            OrchestratorError::SandboxBlocked { .. } => IpcErrorCode::SafetyGate,
+            OrchestratorError::Supervisor(_) => IpcErrorCode::LaunchError,
+            _ => IpcErrorCode::InternalError,
+        };
+        Self::new(code, e.to_string()).with_error_code(error_code)
+    }
+}
+
+impl From<ArtifactError> for IpcErrorPayload {
+    fn from(e: ArtifactError) -> Self {
+        let code = match &e {
+            ArtifactError::RunNotFound { .. } | ArtifactError::ManifestNotFound { .. } => {
+                IpcErrorCode::NotFound
+            }
+            _ => IpcErrorCode::InternalError,
+        };
+        Self::new(code, e.to_string()).with_error_code(e.code())
+    }
+}
+
+impl From<ConsentError> for IpcErrorPayload {
+    fn from(e: ConsentError) -> Self {
+        let code = match &e {
+            ConsentError::NotGranted
+            | ConsentError::Expired { .. }
+            | ConsentError::TokenMismatch => IpcErrorCode::UnsafeBlocked,
+            _ => IpcErrorCode::InternalError,
+        };
+        Self::new(code, e.to_string()).with_error_code(e.code())
+    }
+}
+
+impl From<TrustError> for IpcErrorPayload {
+    fn from(e: TrustError) -> Self {
+        let payload = match &e {
+            TrustError::Untrusted { repo_root } => {
+                let details = serde_json::json!({ "repoRoot": repo_root.display().to_string() });
+                Self::new(IpcErrorCode::UntrustedWorkspace, e.to_string()).with_details(details)
+            }
+            _ => Self::new(IpcErrorCode::InternalError, e.to_string()),
+        };
+        payload.with_error_code(e.code())
+    }
+}
+
+impl From<HydraError> for IpcErrorPayload {
+    fn from(e: HydraError) -> Self {
+        match e {
+            HydraError::Orchestrator(e) => e.into(),
+            HydraError::Artifact(e) => e.into(),
+            HydraError::Consent(e) => e.into(),
+            HydraError::Trust(e) => e.into(),
+            e => Self::new(IpcErrorCode::InternalError, e.to_string()).with_error_code(e.code()),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // File Explorer types (P4.9.2)
 // ---------------------------------------------------------------------------
@@ -440,6 +1080,10 @@ pub struct CandidateDiffPayload {
     pub diff_available: bool,
     pub source: String,
     pub warning: Option<String>,
+    /// The agent's explanation of its approach, from its `notes.md`.
+    pub notes: Option<String>,
+    /// Commits the agent made itself, and whether it left work uncommitted.
+    pub commits: Option<CandidateCommits>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -454,6 +1098,27 @@ pub struct MergePreviewPayload {
     pub report_path: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReviewEntry {
+    pub path: String,
+    /// `pending`, `approved` or `rejected`.
+    pub state: String,
+    pub note: Option<String>,
+    pub updated_at: String,
+}
+
+/// Per-file review of one agent's diff. Files not listed are pending.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentReviewPayload {
+    pub run_id: String,
+    pub agent_key: String,
+    pub files: Vec<FileReviewEntry>,
+    /// What `execute_merge` takes when `approvedOnly` is set.
+    pub approved_paths: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MergeExecutionPayload {
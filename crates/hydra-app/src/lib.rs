@@ -1,7 +1,18 @@
+mod batching;
 mod commands;
+mod config_editor;
+mod costs;
+mod history;
 mod ipc_types;
+mod notifications;
+mod repos;
+mod search;
+mod sessions;
+mod settings;
 mod state;
 
 pub use commands::*;
 pub use ipc_types::*;
+pub use repos::{RepoContext, RepoRegistry};
+pub use settings::{SettingsStore, SETTINGS_FILE_NAME};
 pub use state::*;
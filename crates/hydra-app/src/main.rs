@@ -1,6 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use hydra_core::config::HydraConfig;
+use tauri::Manager;
 
 fn main() {
     let config = HydraConfig::default();
@@ -9,7 +10,20 @@ fn main() {
     let file_watcher_handle = app_state.file_watcher.clone();
 
     tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
         .manage(app_state)
+        .setup(|app| {
+            let settings_path = app
+                .path()
+                .app_config_dir()?
+                .join(hydra_app::SETTINGS_FILE_NAME);
+            let state = app.state::<hydra_app::AppState>();
+            tauri::async_runtime::block_on(async {
+                state.load_settings(settings_path).await;
+                hydra_app::restore_repos(&state).await;
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             hydra_app::health_check,
             hydra_app::run_preflight,
@@ -18,17 +32,47 @@ fn main() {
             hydra_app::start_race,
             hydra_app::poll_race_events,
             hydra_app::get_race_result,
+            hydra_app::cancel_race,
+            hydra_app::resolve_race_approval,
+            hydra_app::list_runs,
+            hydra_app::get_run_detail,
+            hydra_app::delete_run,
+            hydra_app::export_run_bundle,
+            hydra_app::search_run_events,
+            hydra_app::get_cost_summary,
+            hydra_app::get_effective_config,
+            hydra_app::update_config,
+            hydra_app::validate_config_draft,
+            hydra_app::get_unsafe_consent,
+            hydra_app::grant_unsafe_consent,
+            hydra_app::revoke_unsafe_consent,
+            hydra_app::get_workspace_trust,
+            hydra_app::trust_workspace,
+            hydra_app::revoke_workspace_trust,
+            hydra_app::get_notification_settings,
+            hydra_app::update_notification_settings,
+            hydra_app::get_settings,
+            hydra_app::update_settings,
+            hydra_app::list_repos,
+            hydra_app::add_repo,
+            hydra_app::set_active_repo,
             hydra_app::get_candidate_diff,
             hydra_app::preview_merge,
             hydra_app::execute_merge,
+            hydra_app::set_file_review_state,
+            hydra_app::get_file_review_state,
             hydra_app::start_interactive_session,
             hydra_app::poll_interactive_events,
+            hydra_app::get_interactive_session_snapshot,
             hydra_app::write_interactive_input,
             hydra_app::resize_interactive_terminal,
             hydra_app::stop_interactive_session,
             hydra_app::remove_interactive_session,
+            hydra_app::promote_interactive_session,
             hydra_app::list_interactive_sessions,
             hydra_app::get_interactive_transport_diagnostics,
+            hydra_app::get_session_transcript,
+            hydra_app::get_session_summary,
             hydra_app::list_directory,
             hydra_app::read_file_preview,
             hydra_app::start_file_watcher,
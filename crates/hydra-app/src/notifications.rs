@@ -0,0 +1,183 @@
+//! OS notifications for events worth pulling the user back to the app:
+//! a race finishing, a budget warning or stop, or a merge preview with
+//! conflicts.
+
+use hydra_core::artifact::RunStatus;
+use hydra_core::orchestrator::{BudgetWarning, RaceOutcome};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::ipc_types::run_status_label;
+use crate::state::AppStateHandle;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Notice {
+    pub title: String,
+    pub body: String,
+}
+
+/// Show `notice` unless notifications are turned off. Failures are logged,
+/// never surfaced to the caller.
+pub(crate) async fn notify(app: &tauri::AppHandle, state: &AppStateHandle, notice: Notice) {
+    if !state.settings.lock().await.settings().notifications.enabled {
+        return;
+    }
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(notice.title)
+        .body(notice.body)
+        .show()
+    {
+        tracing::warn!(error = %e, "failed to show desktop notification");
+    }
+}
+
+pub(crate) fn race_finished(outcome: &RaceOutcome) -> Vec<Notice> {
+    let mut notices = Vec::new();
+    if let Some(reason) = &outcome.budget_stop_reason {
+        notices.push(Notice {
+            title: "Budget stop".to_string(),
+            body: format!("Agents were stopped: {reason}"),
+        });
+    }
+
+    let notice = if outcome.status == RunStatus::Completed {
+        let winner = outcome
+            .winner()
+            .and_then(|key| outcome.agents.iter().find(|a| a.agent_key == key))
+            .and_then(|agent| agent.score.as_ref())
+            .map(|score| format!("Winner: {} ({:.1})", score.agent_key, score.composite));
+        Notice {
+            title: "Race finished".to_string(),
+            body: winner.unwrap_or_else(|| "No mergeable candidate".to_string()),
+        }
+    } else {
+        Notice {
+            title: "Race failed".to_string(),
+            body: format!(
+                "Run finished with status {}",
+                run_status_label(&outcome.status)
+            ),
+        }
+    };
+    notices.push(notice);
+    notices
+}
+
+pub(crate) fn budget_warning(agent_key: &str, warning: &BudgetWarning) -> Notice {
+    Notice {
+        title: "Budget warning".to_string(),
+        body: format!("{warning}; latest usage from {agent_key}"),
+    }
+}
+
+pub(crate) fn race_failed(error: &str) -> Notice {
+    Notice {
+        title: "Race failed".to_string(),
+        body: error.to_string(),
+    }
+}
+
+pub(crate) fn merge_conflicts(agent_key: &str) -> Notice {
+    Notice {
+        title: "Merge conflicts".to_string(),
+        body: format!("Merge preview for {agent_key} found conflicts"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::adapter::AdapterTier;
+    use hydra_core::orchestrator::{AgentOutcome, BudgetLimit, UsageStatus};
+    use hydra_core::scoring::baseline::ResolvedCommands;
+    use hydra_core::scoring::cost::CostEstimate;
+    use hydra_core::scoring::ranking::AgentScore;
+
+    fn outcome(status: RunStatus, mergeable: bool, budget: Option<&str>) -> RaceOutcome {
+        let score = AgentScore {
+            agent_key: "claude".to_string(),
+            dimensions: Vec::new(),
+            composite: 91.5,
+            mergeable,
+            gate_failures: Vec::new(),
+            partial: false,
+        };
+        RaceOutcome {
+            run_id: uuid::Uuid::new_v4(),
+            status: status.clone(),
+            duration_ms: 1000,
+            artifacts_dir: "/tmp/run".into(),
+            baseline_path: "/tmp/run/baseline".into(),
+            commands: ResolvedCommands {
+                build: None,
+                test: None,
+                lint: None,
+            },
+            agents: vec![AgentOutcome {
+                agent_key: "claude".to_string(),
+                tier: AdapterTier::Tier1,
+                status,
+                error: None,
+                duration_ms: 1000,
+                branch: "hydra/run/agent/claude".to_string(),
+                worktree_cleaned: false,
+                usage: None,
+                usage_status: UsageStatus::Missing,
+                score: Some(score.clone()),
+                notes: None,
+                commits: None,
+            }],
+            rankings: vec![score],
+            usage: CostEstimate {
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                estimated_cost_usd: None,
+            },
+            budget_stop_reason: budget.map(str::to_string),
+            stopped_early_by: None,
+            health: None,
+        }
+    }
+
+    #[test]
+    fn completed_race_names_the_winner() {
+        let notices = race_finished(&outcome(RunStatus::Completed, true, None));
+        assert_eq!(
+            notices,
+            vec![Notice {
+                title: "Race finished".to_string(),
+                body: "Winner: claude (91.5)".to_string(),
+            }]
+        );
+
+        let notices = race_finished(&outcome(RunStatus::Completed, false, None));
+        assert_eq!(notices[0].body, "No mergeable candidate");
+    }
+
+    #[test]
+    fn budget_stop_and_failure_are_reported() {
+        let notices = race_finished(&outcome(
+            RunStatus::Failed,
+            false,
+            Some("token budget exceeded"),
+        ));
+        assert_eq!(notices.len(), 2);
+        assert_eq!(notices[0].title, "Budget stop");
+        assert!(notices[0].body.contains("token budget exceeded"));
+        assert_eq!(notices[1].title, "Race failed");
+        assert_eq!(notices[1].body, "Run finished with status failed");
+
+        let warning = BudgetWarning {
+            limit: BudgetLimit::Tokens,
+            percent: 80,
+            used: 8_200.0,
+            max: 10_000.0,
+        };
+        assert_eq!(
+            budget_warning("codex", &warning).body,
+            "80% of the token budget used (8200 of 10000); latest usage from codex"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Repositories the app knows about, and the one commands act on when the
+//! frontend does not pass a `cwd`.
+//!
+//! Each repository keeps its own adapter probe cache and the races started
+//! for it in this app session, so switching projects does not mix them up.
+
+use std::path::{Path, PathBuf};
+
+use hydra_core::adapter::ProbeReport;
+
+use crate::ipc_types::{IpcErrorPayload, RepoSummary};
+
+#[derive(Debug, Clone)]
+pub struct RepoContext {
+    pub root: PathBuf,
+    pub probe_report: Option<ProbeReport>,
+    /// Races started from the app for this repository, oldest first.
+    pub run_ids: Vec<String>,
+}
+
+impl RepoContext {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            probe_report: None,
+            run_ids: Vec::new(),
+        }
+    }
+
+    fn summary(&self, active: bool) -> RepoSummary {
+        RepoSummary {
+            path: self.root.display().to_string(),
+            name: self
+                .root
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.root.display().to_string()),
+            active,
+            run_ids: self.run_ids.clone(),
+            probed: self.probe_report.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RepoRegistry {
+    repos: Vec<RepoContext>,
+    active: Option<usize>,
+}
+
+impl RepoRegistry {
+    /// Register `root` (a canonical repository root). Adding a known
+    /// repository is a no-op. The first repository added becomes active.
+    pub fn add(&mut self, root: PathBuf) -> RepoSummary {
+        let index = match self.position(&root) {
+            Some(index) => index,
+            None => {
+                self.repos.push(RepoContext::new(root));
+                self.repos.len() - 1
+            }
+        };
+        self.active.get_or_insert(index);
+        self.summary_at(index)
+    }
+
+    pub fn set_active(&mut self, root: &Path) -> Result<RepoSummary, IpcErrorPayload> {
+        let index = self.position(root).ok_or_else(|| {
+            IpcErrorPayload::not_found(format!(
+                "repository '{}' has not been added",
+                root.display()
+            ))
+        })?;
+        self.active = Some(index);
+        Ok(self.summary_at(index))
+    }
+
+    pub fn active(&self) -> Option<&RepoContext> {
+        self.active.map(|index| &self.repos[index])
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut RepoContext> {
+        self.active.map(|index| &mut self.repos[index])
+    }
+
+    /// The registered repository containing `path`, or the active one when
+    /// `path` is `None` or outside every registered repository.
+    pub fn context_for_mut(&mut self, path: Option<&Path>) -> Option<&mut RepoContext> {
+        let index = path
+            .and_then(|path| {
+                self.repos
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, repo)| path.starts_with(&repo.root))
+                    .max_by_key(|(_, repo)| repo.root.components().count())
+                    .map(|(index, _)| index)
+            })
+            .or(self.active)?;
+        Some(&mut self.repos[index])
+    }
+
+    pub fn summaries(&self) -> Vec<RepoSummary> {
+        (0..self.repos.len()).map(|i| self.summary_at(i)).collect()
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.repos
+            .iter()
+            .map(|repo| repo.root.display().to_string())
+            .collect()
+    }
+
+    fn position(&self, root: &Path) -> Option<usize> {
+        self.repos.iter().position(|repo| repo.root == root)
+    }
+
+    fn summary_at(&self, index: usize) -> RepoSummary {
+        self.repos[index].summary(self.active == Some(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_repo_becomes_active_and_duplicates_are_ignored() {
+        let mut registry = RepoRegistry::default();
+        assert!(registry.active().is_none());
+
+        let first = registry.add(PathBuf::from("/work/api"));
+        assert!(first.active);
+        assert_eq!(first.name, "api");
+        let second = registry.add(PathBuf::from("/work/web"));
+        assert!(!second.active);
+        registry.add(PathBuf::from("/work/api"));
+
+        assert_eq!(registry.summaries().len(), 2);
+        assert_eq!(registry.active().unwrap().root, Path::new("/work/api"));
+    }
+
+    #[test]
+    fn set_active_requires_a_registered_repo() {
+        let mut registry = RepoRegistry::default();
+        registry.add(PathBuf::from("/work/api"));
+        registry.add(PathBuf::from("/work/web"));
+
+        let active = registry.set_active(Path::new("/work/web")).unwrap();
+        assert!(active.active);
+        assert_eq!(registry.active().unwrap().root, Path::new("/work/web"));
+
+        let err = registry.set_active(Path::new("/work/other")).unwrap_err();
+        assert_eq!(err.code, "not_found");
+    }
+
+    #[test]
+    fn runs_are_recorded_against_the_containing_repo() {
+        let mut registry = RepoRegistry::default();
+        registry.add(PathBuf::from("/work/api"));
+        registry.add(PathBuf::from("/work/web"));
+
+        registry
+            .context_for_mut(Some(Path::new("/work/web/src")))
+            .unwrap()
+            .run_ids
+            .push("run-1".to_string());
+        registry
+            .context_for_mut(None)
+            .unwrap()
+            .run_ids
+            .push("run-2".to_string());
+
+        let summaries = registry.summaries();
+        assert_eq!(summaries[0].run_ids, vec!["run-2"]);
+        assert_eq!(summaries[1].run_ids, vec!["run-1"]);
+    }
+}
@@ -0,0 +1,355 @@
+//! Text search over a run's event logs (`events.jsonl` for the run and for
+//! each agent), for finding where an agent mentioned a file or an error.
+//!
+//! Logs are streamed line by line rather than loaded whole, and for plain
+//! queries a line that does not contain the query verbatim is skipped
+//! without being parsed.
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use hydra_core::artifact::{EventKind, RunEvent, RunLayout, RunManifest};
+use uuid::Uuid;
+
+use crate::ipc_types::{IpcErrorPayload, RunEventMatch, RunEventSearch, RunEventSearchFilters};
+
+const DEFAULT_LIMIT: usize = 500;
+const MAX_LIMIT: usize = 5_000;
+/// Matched text longer than this is cut down around the first hit.
+const MAX_SNIPPET_CHARS: usize = 400;
+
+pub(crate) fn search_run_events(
+    hydra_root: &Path,
+    run_id: &str,
+    query: &str,
+    filters: &RunEventSearchFilters,
+) -> Result<RunEventSearch, IpcErrorPayload> {
+    if query.is_empty() {
+        return Err(IpcErrorPayload::validation(
+            "search query must not be empty",
+        ));
+    }
+    let run_uuid = Uuid::parse_str(run_id)
+        .map_err(|e| IpcErrorPayload::validation(format!("invalid run_id: {e}")))?;
+    let layout = RunLayout::new(hydra_root, run_uuid);
+    let manifest = RunManifest::read_from(&layout.manifest_path())
+        .map_err(|_| IpcErrorPayload::not_found(format!("run {run_id} not found")))?;
+
+    let matcher = Matcher::new(query, filters)?;
+    let limit = filters.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let mut sources = vec![("run".to_string(), layout.events_path())];
+    sources.extend(
+        manifest
+            .agents
+            .iter()
+            .filter(|a| filters.agents.is_empty() || filters.agents.contains(&a.agent_key))
+            .map(|a| (a.agent_key.clone(), layout.agent_events(&a.agent_key))),
+    );
+
+    let mut result = RunEventSearch {
+        run_id: run_id.to_string(),
+        query: query.to_string(),
+        matches: Vec::new(),
+        lines_scanned: 0,
+        truncated: false,
+    };
+    for (source, path) in sources {
+        let Ok(file) = std::fs::File::open(&path) else {
+            continue;
+        };
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let Ok(line) = line else {
+                break;
+            };
+            result.lines_scanned += 1;
+            let Some(event_match) = matcher.check(&source, index + 1, &line) else {
+                continue;
+            };
+            if result.matches.len() == limit {
+                result.truncated = true;
+                break;
+            }
+            result.matches.push(event_match);
+        }
+        if result.truncated {
+            break;
+        }
+    }
+    result.matches.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(result)
+}
+
+struct Matcher<'a> {
+    needle: String,
+    case_sensitive: bool,
+    /// Whether the query survives JSON encoding unchanged, so the raw line
+    /// can be checked before parsing.
+    raw_prefilter: bool,
+    agents: &'a [String],
+    kinds: Vec<EventKind>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(query: &str, filters: &'a RunEventSearchFilters) -> Result<Self, IpcErrorPayload> {
+        let kinds = filters
+            .kinds
+            .iter()
+            .map(|kind| {
+                serde_json::from_value(serde_json::Value::String(kind.clone())).map_err(|_| {
+                    IpcErrorPayload::validation(format!("unknown event kind '{kind}'"))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            needle: if filters.case_sensitive {
+                query.to_string()
+            } else {
+                query.to_lowercase()
+            },
+            case_sensitive: filters.case_sensitive,
+            raw_prefilter: !query
+                .chars()
+                .any(|c| c == '"' || c == '\\' || c.is_control()),
+            agents: &filters.agents,
+            kinds,
+            since: parse_time("since", filters.since.as_deref())?,
+            until: parse_time("until", filters.until.as_deref())?,
+        })
+    }
+
+    fn contains(&self, haystack: &str) -> bool {
+        if self.case_sensitive {
+            haystack.contains(&self.needle)
+        } else {
+            haystack.to_lowercase().contains(&self.needle)
+        }
+    }
+
+    fn check(&self, source: &str, line_number: usize, line: &str) -> Option<RunEventMatch> {
+        if line.trim().is_empty() || (self.raw_prefilter && !self.contains(line)) {
+            return None;
+        }
+        let event: RunEvent = serde_json::from_str(line).ok()?;
+        if !self.agents.is_empty()
+            && !event
+                .agent_key
+                .as_ref()
+                .is_some_and(|key| self.agents.contains(key))
+        {
+            return None;
+        }
+        if !self.kinds.is_empty() && !self.kinds.contains(&event.kind) {
+            return None;
+        }
+        if self.since.is_some_and(|since| event.timestamp < since)
+            || self.until.is_some_and(|until| event.timestamp > until)
+        {
+            return None;
+        }
+
+        let text = match event.data.get("line").and_then(serde_json::Value::as_str) {
+            Some(line) => line.to_string(),
+            None => event.data.to_string(),
+        };
+        if !self.contains(&text) {
+            return None;
+        }
+        Some(RunEventMatch {
+            source: source.to_string(),
+            line_number,
+            timestamp: event.timestamp.to_rfc3339(),
+            kind: serde_json::to_value(&event.kind)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            agent_key: event.agent_key,
+            snippet: self.snippet(&text),
+        })
+    }
+
+    fn snippet(&self, text: &str) -> String {
+        if text.chars().count() <= MAX_SNIPPET_CHARS {
+            return text.to_string();
+        }
+        let haystack = if self.case_sensitive {
+            text.to_string()
+        } else {
+            text.to_lowercase()
+        };
+        // Lowercasing can change byte offsets, so fall back to the start.
+        let hit = haystack
+            .find(&self.needle)
+            .filter(|_| haystack.len() == text.len())
+            .map_or(0, |byte| text[..byte].chars().count());
+        let start = hit.saturating_sub(MAX_SNIPPET_CHARS / 4);
+        let mut snippet: String = text.chars().skip(start).take(MAX_SNIPPET_CHARS).collect();
+        if start > 0 {
+            snippet.insert(0, '…');
+        }
+        if start + MAX_SNIPPET_CHARS < text.chars().count() {
+            snippet.push('…');
+        }
+        snippet
+    }
+}
+
+fn parse_time(field: &str, value: Option<&str>) -> Result<Option<DateTime<Utc>>, IpcErrorPayload> {
+    value
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| IpcErrorPayload::validation(format!("invalid {field} timestamp: {e}")))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use hydra_core::artifact::EventWriter;
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+
+    fn event(kind: EventKind, agent: &str, line: &str, at: DateTime<Utc>) -> RunEvent {
+        RunEvent {
+            timestamp: at,
+            kind,
+            agent_key: Some(agent.to_string()),
+            data: serde_json::json!({ "line": line }),
+        }
+    }
+
+    fn write_run(hydra_root: &Path, start: DateTime<Utc>) -> String {
+        let (layout, manifest) = new_run(hydra_root, &["claude", "codex"]);
+        let run_id = layout.run_id();
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let mut claude = EventWriter::create(&layout.agent_events("claude")).unwrap();
+        for (i, line) in ["reading src/lib.rs", "error: cannot find value", "done"]
+            .iter()
+            .enumerate()
+        {
+            claude
+                .write_event(&event(
+                    EventKind::AgentStdout,
+                    "claude",
+                    line,
+                    start + Duration::seconds(i as i64),
+                ))
+                .unwrap();
+        }
+        let mut codex = EventWriter::create(&layout.agent_events("codex")).unwrap();
+        codex
+            .write_event(&event(
+                EventKind::AgentStderr,
+                "codex",
+                "Error: src/lib.rs is locked",
+                start + Duration::seconds(1),
+            ))
+            .unwrap();
+        run_id.to_string()
+    }
+
+    #[test]
+    fn finds_matches_across_agents_with_filters() {
+        let tmp = TempDir::new().unwrap();
+        let start = Utc::now();
+        let run_id = write_run(tmp.path(), start);
+
+        let all = search_run_events(
+            tmp.path(),
+            &run_id,
+            "src/lib.rs",
+            &RunEventSearchFilters::default(),
+        )
+        .unwrap();
+        assert_eq!(all.matches.len(), 2);
+        assert_eq!(all.matches[0].snippet, "reading src/lib.rs");
+        assert_eq!(all.matches[0].line_number, 1);
+        assert_eq!(all.matches[1].agent_key.as_deref(), Some("codex"));
+        assert_eq!(all.matches[1].kind, "agent_stderr");
+
+        let errors = search_run_events(
+            tmp.path(),
+            &run_id,
+            "error",
+            &RunEventSearchFilters {
+                agents: vec!["claude".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(errors.matches.len(), 1);
+        assert_eq!(errors.matches[0].line_number, 2);
+
+        let stderr_only = search_run_events(
+            tmp.path(),
+            &run_id,
+            "error",
+            &RunEventSearchFilters {
+                kinds: vec!["agent_stderr".to_string()],
+                case_sensitive: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(stderr_only.matches.is_empty());
+
+        let late = search_run_events(
+            tmp.path(),
+            &run_id,
+            "o",
+            &RunEventSearchFilters {
+                since: Some((start + Duration::seconds(2)).to_rfc3339()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(late.matches.len(), 1);
+        assert_eq!(late.matches[0].snippet, "done");
+    }
+
+    #[test]
+    fn limit_truncates_and_bad_filters_are_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = write_run(tmp.path(), Utc::now());
+
+        let limited = search_run_events(
+            tmp.path(),
+            &run_id,
+            "r",
+            &RunEventSearchFilters {
+                limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(limited.matches.len(), 1);
+        assert!(limited.truncated);
+
+        let err = search_run_events(
+            tmp.path(),
+            &run_id,
+            "x",
+            &RunEventSearchFilters {
+                kinds: vec!["agent_chatter".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        let err = search_run_events(
+            tmp.path(),
+            &Uuid::new_v4().to_string(),
+            "x",
+            &Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.code, "not_found");
+    }
+}
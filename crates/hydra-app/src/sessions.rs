@@ -0,0 +1,165 @@
+//! Read-only access to interactive session artifacts under
+//! `.hydra/sessions`, so finished sessions (including ones from earlier app
+//! launches) can be inspected after their PTY is gone.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use hydra_core::artifact::{SessionLayout, SessionMetadata, SessionSummary};
+use uuid::Uuid;
+
+use crate::ipc_types::{IpcErrorPayload, SessionTranscriptChunk, SessionTranscriptSummary};
+
+const DEFAULT_TRANSCRIPT_LIMIT: usize = 64 * 1024;
+const MAX_TRANSCRIPT_LIMIT: usize = 1024 * 1024;
+
+/// Up to `limit` bytes of the session transcript starting at byte
+/// `offset`. A chunk never ends inside a UTF-8 sequence; pass
+/// `next_offset` back to continue.
+pub(crate) fn session_transcript(
+    hydra_root: &Path,
+    session_id: &str,
+    offset: u64,
+    limit: Option<usize>,
+) -> Result<SessionTranscriptChunk, IpcErrorPayload> {
+    let layout = session_layout(hydra_root, session_id)?;
+    let path = layout.transcript_path();
+    let mut file = std::fs::File::open(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            IpcErrorPayload::not_found(format!("session {session_id} has no transcript"))
+        }
+        _ => IpcErrorPayload::internal(format!("failed to open transcript: {e}")),
+    })?;
+    let total_bytes = file
+        .metadata()
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to read transcript: {e}")))?
+        .len();
+
+    let offset = offset.min(total_bytes);
+    let limit = limit
+        .unwrap_or(DEFAULT_TRANSCRIPT_LIMIT)
+        .clamp(1, MAX_TRANSCRIPT_LIMIT);
+    let mut buf = Vec::with_capacity(limit.min((total_bytes - offset) as usize));
+    file.seek(SeekFrom::Start(offset))
+        .and_then(|_| file.take(limit as u64).read_to_end(&mut buf))
+        .map_err(|e| IpcErrorPayload::internal(format!("failed to read transcript: {e}")))?;
+
+    let reached_end = offset + buf.len() as u64 >= total_bytes;
+    if !reached_end {
+        if let Err(e) = std::str::from_utf8(&buf) {
+            if e.error_len().is_none() {
+                buf.truncate(e.valid_up_to());
+            }
+        }
+    }
+    let next_offset = offset + buf.len() as u64;
+    Ok(SessionTranscriptChunk {
+        session_id: session_id.to_string(),
+        offset,
+        next_offset,
+        total_bytes,
+        content: String::from_utf8_lossy(&buf).into_owned(),
+        eof: next_offset >= total_bytes,
+    })
+}
+
+/// Metadata and final counters for a session. Sessions that never
+/// finished cleanly have no `summary.json`; their counters are `None`.
+pub(crate) fn session_summary(
+    hydra_root: &Path,
+    session_id: &str,
+) -> Result<SessionTranscriptSummary, IpcErrorPayload> {
+    let layout = session_layout(hydra_root, session_id)?;
+    let metadata = SessionMetadata::read_from(&layout.session_json_path())
+        .map_err(|_| IpcErrorPayload::not_found(format!("session {session_id} not found")))?;
+    let summary = SessionSummary::read_from(&layout.summary_path()).ok();
+    let transcript_bytes = std::fs::metadata(layout.transcript_path())
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    Ok(SessionTranscriptSummary {
+        session_id: metadata.session_id,
+        agent_key: metadata.agent_key,
+        status: summary
+            .as_ref()
+            .map_or(metadata.status, |s| s.status.clone()),
+        started_at: metadata.started_at,
+        ended_at: summary
+            .as_ref()
+            .map(|s| s.ended_at.clone())
+            .or(metadata.ended_at),
+        cwd: metadata.cwd,
+        unsafe_mode: metadata.unsafe_mode,
+        experimental: metadata.experimental,
+        duration_ms: summary.as_ref().map(|s| s.duration_ms),
+        event_count: summary.as_ref().map(|s| s.event_count),
+        output_bytes: summary.as_ref().map(|s| s.output_bytes),
+        user_input_count: summary.as_ref().map(|s| s.user_input_count),
+        transcript_bytes,
+    })
+}
+
+fn session_layout(hydra_root: &Path, session_id: &str) -> Result<SessionLayout, IpcErrorPayload> {
+    Uuid::parse_str(session_id)
+        .map_err(|e| IpcErrorPayload::validation(format!("invalid session_id: {e}")))?;
+    Ok(SessionLayout::new(hydra_root, session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::SessionArtifactWriter;
+    use tempfile::TempDir;
+
+    fn finished_session(hydra_root: &Path, output: &str) -> String {
+        let session_id = Uuid::new_v4().to_string();
+        let mut writer = SessionArtifactWriter::init(
+            hydra_root,
+            &session_id,
+            "claude",
+            "2026-02-24T00:00:00Z",
+            "/repo",
+            false,
+            false,
+        )
+        .unwrap();
+        writer.record_output(output.as_bytes()).unwrap();
+        writer
+            .finalize("completed", "2026-02-24T00:00:05Z", 5000)
+            .unwrap();
+        session_id
+    }
+
+    #[test]
+    fn transcript_pages_without_splitting_characters() {
+        let tmp = TempDir::new().unwrap();
+        let session_id = finished_session(tmp.path(), "héllo wörld");
+
+        let first = session_transcript(tmp.path(), &session_id, 0, Some(2)).unwrap();
+        assert_eq!(first.content, "h");
+        assert_eq!(first.next_offset, 1);
+        assert!(!first.eof);
+
+        let rest = session_transcript(tmp.path(), &session_id, first.next_offset, None).unwrap();
+        assert_eq!(rest.content, "éllo wörld");
+        assert!(rest.eof);
+        assert_eq!(rest.next_offset, rest.total_bytes);
+    }
+
+    #[test]
+    fn summary_merges_metadata_and_final_counters() {
+        let tmp = TempDir::new().unwrap();
+        let session_id = finished_session(tmp.path(), "output");
+
+        let summary = session_summary(tmp.path(), &session_id).unwrap();
+        assert_eq!(summary.agent_key, "claude");
+        assert_eq!(summary.status, "completed");
+        assert_eq!(summary.duration_ms, Some(5000));
+        assert_eq!(summary.transcript_bytes, 6);
+
+        let err = session_summary(tmp.path(), &Uuid::new_v4().to_string()).unwrap_err();
+        assert_eq!(err.code, "not_found");
+        let err = session_summary(tmp.path(), "../runs").unwrap_err();
+        assert_eq!(err.code, "validation_error");
+    }
+}
@@ -0,0 +1,177 @@
+//! App preferences (window layout, last-used agents, opt-ins) persisted as
+//! JSON in the platform app config dir, so UI choices survive restarts.
+//!
+//! These are desktop-only choices; anything that changes how races run
+//! belongs in `hydra.toml` instead.
+
+use std::path::{Path, PathBuf};
+
+use hydra_core::i18n::Locale;
+
+use crate::ipc_types::{AppSettings, IpcErrorPayload};
+
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// In-memory settings plus the file they are saved to. Without a path
+/// (before app setup, or in tests) updates are kept in memory only.
+#[derive(Debug, Default)]
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: AppSettings,
+}
+
+impl SettingsStore {
+    /// Read settings from `path`. A missing file gives defaults; an
+    /// unreadable one is logged and also gives defaults, and is overwritten
+    /// on the next update.
+    pub fn load(path: PathBuf) -> Self {
+        let settings = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "ignoring unreadable settings file");
+                AppSettings::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => AppSettings::default(),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "failed to read settings file");
+                AppSettings::default()
+            }
+        };
+        apply_locale(&settings);
+        Self {
+            path: Some(path),
+            settings,
+        }
+    }
+
+    pub fn settings(&self) -> &AppSettings {
+        &self.settings
+    }
+
+    /// Apply `patch` as a JSON merge patch (RFC 7386) and save. Keys set to
+    /// `null` go back to their defaults. Nothing changes if the result does
+    /// not parse or cannot be written.
+    pub fn update(&mut self, patch: serde_json::Value) -> Result<AppSettings, IpcErrorPayload> {
+        let mut merged = serde_json::to_value(&self.settings)
+            .map_err(|e| IpcErrorPayload::internal(format!("failed to serialize settings: {e}")))?;
+        merge_patch(&mut merged, patch);
+        let updated: AppSettings = serde_json::from_value(merged)
+            .map_err(|e| IpcErrorPayload::validation(format!("invalid settings: {e}")))?;
+        if let Some(locale) = updated.locale.as_deref() {
+            if Locale::parse(locale).is_none() {
+                return Err(IpcErrorPayload::validation(format!(
+                    "no messages for locale '{locale}'"
+                )));
+            }
+        }
+
+        if let Some(path) = &self.path {
+            write_settings(path, &updated)
+                .map_err(|e| IpcErrorPayload::internal(format!("failed to save settings: {e}")))?;
+        }
+        apply_locale(&updated);
+        self.settings = updated.clone();
+        Ok(updated)
+    }
+}
+
+/// Translate error messages into the chosen locale, or the system's.
+fn apply_locale(settings: &AppSettings) {
+    hydra_core::i18n::set_locale(Locale::resolve(settings.locale.as_deref()));
+}
+
+fn merge_patch(target: &mut serde_json::Value, patch: serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+    let target = target.as_object_mut().expect("target is an object");
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            merge_patch(target.entry(key).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
+
+fn write_settings(path: &Path, settings: &AppSettings) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let text = serde_json::to_string_pretty(settings).map_err(std::io::Error::other)?;
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn update_persists_and_reloads() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config").join(SETTINGS_FILE_NAME);
+
+        let mut store = SettingsStore::load(path.clone());
+        assert_eq!(store.settings(), &AppSettings::default());
+
+        let updated = store
+            .update(serde_json::json!({
+                "lastUsedAgents": ["claude", "codex"],
+                "window": { "width": 1280, "maximized": true },
+                "notifications": { "enabled": false },
+            }))
+            .unwrap();
+        assert_eq!(updated.window.width, Some(1280));
+
+        let reloaded = SettingsStore::load(path);
+        assert_eq!(reloaded.settings(), &updated);
+        assert_eq!(
+            reloaded.settings().last_used_agents,
+            vec!["claude", "codex"]
+        );
+        assert!(reloaded.settings().window.maximized);
+        assert!(!reloaded.settings().notifications.enabled);
+    }
+
+    #[test]
+    fn patch_merges_nested_keys_and_null_resets() {
+        let mut store = SettingsStore::default();
+        store
+            .update(serde_json::json!({ "window": { "width": 1280, "height": 800 } }))
+            .unwrap();
+        let updated = store
+            .update(serde_json::json!({ "window": { "height": null } }))
+            .unwrap();
+        assert_eq!(updated.window.width, Some(1280));
+        assert_eq!(updated.window.height, None);
+
+        let err = store
+            .update(serde_json::json!({ "lastUsedAgents": "claude" }))
+            .unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        assert_eq!(store.settings().window.width, Some(1280));
+
+        let err = store
+            .update(serde_json::json!({ "locale": "tlh" }))
+            .unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        assert_eq!(store.settings().locale, None);
+    }
+
+    #[test]
+    fn unreadable_file_falls_back_to_defaults() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SETTINGS_FILE_NAME);
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(
+            SettingsStore::load(path).settings(),
+            &AppSettings::default()
+        );
+    }
+}
@@ -5,19 +5,27 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, mpsc, Mutex};
 
-use hydra_core::adapter::{AdapterRegistry, ProbeReport, ProbeRunner};
+use hydra_core::adapter::{AdapterRegistry, PingResult, ProbeReport, ProbeRunner};
 use hydra_core::artifact::SessionArtifactWriter;
 use hydra_core::config::HydraConfig;
-use hydra_core::supervisor::pty::{PtyEvent, PtySession};
+use hydra_core::orchestrator::{ApprovalGate, CancelToken};
+use hydra_core::security::ApprovalDecision;
+use hydra_core::supervisor::pty::{PtyEvent, PtySession, Scrollback};
 use hydra_core::worktree::{WorktreeInfo, WorktreeService};
 
-use crate::ipc_types::{AgentStreamEvent, FileWatchEvent, InteractiveStreamEvent, RaceResult};
+use crate::ipc_types::{
+    AgentStreamEvent, FileWatchEvent, InteractiveSessionSnapshot, InteractiveStreamEvent,
+    IpcErrorPayload, RaceResult,
+};
+use crate::repos::RepoRegistry;
+use crate::settings::SettingsStore;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 const EVENT_CHANNEL_CAPACITY: usize = 4096;
 const MAX_STORED_EVENTS_PER_RUN: usize = 10_000;
 const MAX_INTERACTIVE_EVENTS: usize = 50_000;
 const MAX_INTERACTIVE_EVENT_TEXT_BYTES: usize = 64 * 1024;
+const INTERACTIVE_SCROLLBACK_BYTES: usize = 256 * 1024;
 pub const INTERACTIVE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 const MAX_FILE_WATCH_EVENTS: usize = 10_000;
 
@@ -27,6 +35,10 @@ pub struct RaceRuntime {
     pub events: Vec<AgentStreamEvent>,
     pub result: Option<RaceResult>,
     pub error: Option<String>,
+    /// Shared with the orchestrator, which stops the agents when it is set.
+    pub cancel: CancelToken,
+    /// Approval requests of agents paused by `security.approval`.
+    pub approvals: ApprovalGate,
 }
 
 impl RaceRuntime {
@@ -36,6 +48,8 @@ impl RaceRuntime {
             events: Vec::new(),
             result: None,
             error: None,
+            cancel: CancelToken::new(),
+            approvals: ApprovalGate::new(),
         }
     }
 }
@@ -53,6 +67,13 @@ pub struct InteractiveManagedWorktree {
     pub branch: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct InteractivePromotionSource {
+    pub agent_key: String,
+    pub repo_root: String,
+    pub source_path: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct InteractiveSessionPaths {
     pub source_root: String,
@@ -91,6 +112,8 @@ pub struct InteractiveSessionRuntime {
     pub managed_worktree: Option<InteractiveManagedWorktree>,
     pub events: Vec<InteractiveStreamEvent>,
     pub event_base_cursor: u64,
+    /// Raw output kept for reattaching after a frontend reload.
+    pub scrollback: Scrollback,
     pub error: Option<String>,
     pub pty_session: Option<PtySession>,
     pub artifact_writer: Option<Arc<Mutex<SessionArtifactWriter>>>,
@@ -100,6 +123,15 @@ pub struct InteractiveSessionRuntime {
 }
 
 impl InteractiveSessionRuntime {
+    fn push_event(&mut self, event: InteractiveStreamEvent) {
+        self.events.push(event);
+        if self.events.len() > MAX_INTERACTIVE_EVENTS {
+            let overflow = self.events.len() - MAX_INTERACTIVE_EVENTS;
+            self.events.drain(0..overflow);
+            self.event_base_cursor = self.event_base_cursor.saturating_add(overflow as u64);
+        }
+    }
+
     fn new(
         session_id: String,
         agent_key: String,
@@ -120,6 +152,7 @@ impl InteractiveSessionRuntime {
             managed_worktree: paths.managed_worktree,
             events: Vec::new(),
             event_base_cursor: 0,
+            scrollback: Scrollback::new(INTERACTIVE_SCROLLBACK_BYTES),
             error: None,
             pty_session: None,
             artifact_writer: None,
@@ -187,16 +220,39 @@ impl InteractiveStateHandle {
     pub async fn append_event(&self, session_id: &str, event: InteractiveStreamEvent) {
         let mut sessions = self.sessions.lock().await;
         if let Some(session) = sessions.get_mut(session_id) {
-            session.events.push(event);
-            if session.events.len() > MAX_INTERACTIVE_EVENTS {
-                let overflow = session.events.len() - MAX_INTERACTIVE_EVENTS;
-                session.events.drain(0..overflow);
-                session.event_base_cursor =
-                    session.event_base_cursor.saturating_add(overflow as u64);
-            }
+            session.push_event(event);
         }
     }
 
+    /// Record an output event and its raw bytes together, so a snapshot's
+    /// scrollback and cursor always agree.
+    pub async fn append_output(
+        &self,
+        session_id: &str,
+        bytes: &[u8],
+        event: InteractiveStreamEvent,
+    ) {
+        let mut sessions = self.sessions.lock().await;
+        if let Some(session) = sessions.get_mut(session_id) {
+            session.scrollback.push(bytes);
+            session.push_event(event);
+        }
+    }
+
+    pub async fn snapshot(&self, session_id: &str) -> Option<InteractiveSessionSnapshot> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions.get(session_id)?;
+        Some(InteractiveSessionSnapshot {
+            session_id: session.session_id.clone(),
+            agent_key: session.agent_key.clone(),
+            status: session.status.clone(),
+            error: session.error.clone(),
+            started_at: session.started_at.clone(),
+            scrollback: String::from_utf8_lossy(&session.scrollback.snapshot()).into_owned(),
+            next_cursor: session.event_base_cursor + session.events.len() as u64,
+        })
+    }
+
     pub async fn poll_events(
         &self,
         session_id: &str,
@@ -331,12 +387,12 @@ impl InteractiveStateHandle {
     }
 
     /// Stop a session. Returns (was_running, current_status). Idempotent.
-    pub async fn stop_session(&self, session_id: &str) -> Result<(bool, String), String> {
+    pub async fn stop_session(&self, session_id: &str) -> Result<(bool, String), IpcErrorPayload> {
         let (was_running, status, writer, started_at, worktree) = {
             let mut sessions = self.sessions.lock().await;
-            let session = sessions
-                .get_mut(session_id)
-                .ok_or_else(|| "session not found".to_string())?;
+            let session = sessions.get_mut(session_id).ok_or_else(|| {
+                IpcErrorPayload::not_found(format!("session '{session_id}' not found"))
+            })?;
 
             let was_running = session.status == "running";
             if was_running {
@@ -373,14 +429,16 @@ impl InteractiveStateHandle {
     }
 
     /// Remove a non-running session from the registry. Returns final status.
-    pub async fn remove_session(&self, session_id: &str) -> Result<String, String> {
+    pub async fn remove_session(&self, session_id: &str) -> Result<String, IpcErrorPayload> {
         let removed = {
             let mut sessions = self.sessions.lock().await;
-            let session = sessions
-                .get(session_id)
-                .ok_or_else(|| "session not found".to_string())?;
+            let session = sessions.get(session_id).ok_or_else(|| {
+                IpcErrorPayload::not_found(format!("session '{session_id}' not found"))
+            })?;
             if session.status == "running" {
-                return Err("session is running; stop it before removing".to_string());
+                return Err(IpcErrorPayload::validation(
+                    "session is running; stop it before removing",
+                ));
             }
             sessions.remove(session_id).expect("checked existence")
         };
@@ -391,6 +449,32 @@ impl InteractiveStateHandle {
         Ok(removed.status)
     }
 
+    /// Agent and checkout of a finished session, for promoting its work to
+    /// a race candidate. Sessions in the primary checkout promote from the
+    /// repository root.
+    pub async fn promotion_source(
+        &self,
+        session_id: &str,
+    ) -> Result<InteractivePromotionSource, IpcErrorPayload> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions.get(session_id).ok_or_else(|| {
+            IpcErrorPayload::not_found(format!("session '{session_id}' not found"))
+        })?;
+        if session.status == "running" {
+            return Err(IpcErrorPayload::validation(
+                "session is running; stop it before promoting",
+            ));
+        }
+        Ok(InteractivePromotionSource {
+            agent_key: session.agent_key.clone(),
+            repo_root: session.repo_root.clone(),
+            source_path: session
+                .managed_worktree
+                .as_ref()
+                .map_or_else(|| session.repo_root.clone(), |wt| wt.path.clone()),
+        })
+    }
+
     pub async fn list_sessions(
         &self,
     ) -> Vec<(
@@ -714,7 +798,12 @@ pub fn spawn_pty_event_bridge_with_sink(
             if let Some(ref sink) = event_sink {
                 sink(&stream_event);
             }
-            state.append_event(&session_id, stream_event).await;
+            match &evt {
+                PtyEvent::Output(bytes) => {
+                    state.append_output(&session_id, bytes, stream_event).await
+                }
+                _ => state.append_event(&session_id, stream_event).await,
+            }
         }
     });
 }
@@ -723,6 +812,7 @@ pub fn spawn_pty_event_bridge_with_sink(
 pub struct AppStateHandle {
     pub races: Arc<Mutex<HashMap<String, RaceRuntime>>>,
     pub event_tx: broadcast::Sender<AgentStreamEvent>,
+    pub settings: Arc<Mutex<SettingsStore>>,
 }
 
 impl AppStateHandle {
@@ -731,6 +821,68 @@ impl AppStateHandle {
         races.insert(run_id.to_string(), RaceRuntime::running());
     }
 
+    pub async fn race_cancel_token(&self, run_id: &str) -> Option<CancelToken> {
+        let races = self.races.lock().await;
+        races.get(run_id).map(|r| r.cancel.clone())
+    }
+
+    pub async fn race_approvals(&self, run_id: &str) -> Option<ApprovalGate> {
+        let races = self.races.lock().await;
+        races.get(run_id).map(|r| r.approvals.clone())
+    }
+
+    /// Answer an agent's pending approval request.
+    pub async fn resolve_approval(
+        &self,
+        run_id: &str,
+        approval_id: uuid::Uuid,
+        decision: ApprovalDecision,
+    ) -> Result<(), IpcErrorPayload> {
+        let races = self.races.lock().await;
+        let race = races
+            .get(run_id)
+            .ok_or_else(|| IpcErrorPayload::not_found(format!("race '{run_id}' not found")))?;
+        if !race.approvals.resolve(approval_id, decision) {
+            return Err(IpcErrorPayload::not_found(format!(
+                "approval '{approval_id}' is not pending"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Ask a running race to stop its agents.
+    pub async fn request_cancel(&self, run_id: &str) -> Result<(), IpcErrorPayload> {
+        let races = self.races.lock().await;
+        let race = races
+            .get(run_id)
+            .ok_or_else(|| IpcErrorPayload::not_found(format!("race '{run_id}' not found")))?;
+        if race.status != "running" {
+            return Err(IpcErrorPayload::validation(format!(
+                "race '{run_id}' is not running (status: {})",
+                race.status
+            )));
+        }
+        race.cancel.cancel();
+        Ok(())
+    }
+
+    pub async fn mark_cancelled(&self, run_id: &str) {
+        let mut races = self.races.lock().await;
+        if let Some(race) = races.get_mut(run_id) {
+            race.status = "cancelled".to_string();
+            race.error = Some("race cancelled by user".to_string());
+            if race.result.is_none() {
+                race.result = Some(RaceResult {
+                    run_id: run_id.to_string(),
+                    status: "cancelled".to_string(),
+                    agents: Vec::new(),
+                    duration_ms: None,
+                    total_cost: None,
+                });
+            }
+        }
+    }
+
     pub async fn append_event(&self, run_id: &str, event: AgentStreamEvent) {
         let mut races = self.races.lock().await;
         if let Some(race) = races.get_mut(run_id) {
@@ -771,6 +923,19 @@ impl AppStateHandle {
         }
     }
 
+    pub async fn race_is_running(&self, run_id: &str) -> bool {
+        let races = self.races.lock().await;
+        races.get(run_id).is_some_and(|r| r.status == "running")
+    }
+
+    /// Drop a finished race's in-memory events and result.
+    pub async fn forget_race(&self, run_id: &str) {
+        let mut races = self.races.lock().await;
+        if races.get(run_id).is_some_and(|r| r.status != "running") {
+            races.remove(run_id);
+        }
+    }
+
     pub async fn race_result(&self, run_id: &str) -> Option<RaceResult> {
         let races = self.races.lock().await;
         races.get(run_id).and_then(|r| r.result.clone())
@@ -888,9 +1053,10 @@ impl FileWatcherStateHandle {
 
 pub struct AppState {
     pub config: Arc<Mutex<HydraConfig>>,
-    pub last_probe_report: Arc<Mutex<Option<ProbeReport>>>,
+    pub repos: Arc<Mutex<RepoRegistry>>,
     pub races: Arc<Mutex<HashMap<String, RaceRuntime>>>,
     pub event_tx: broadcast::Sender<AgentStreamEvent>,
+    pub settings: Arc<Mutex<SettingsStore>>,
     pub interactive: InteractiveStateHandle,
     pub file_watcher: FileWatcherStateHandle,
 }
@@ -900,9 +1066,10 @@ impl AppState {
         let (event_tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
         Self {
             config: Arc::new(Mutex::new(config)),
-            last_probe_report: Arc::new(Mutex::new(None)),
+            repos: Arc::new(Mutex::new(RepoRegistry::default())),
             races: Arc::new(Mutex::new(HashMap::new())),
             event_tx,
+            settings: Arc::new(Mutex::new(SettingsStore::default())),
             interactive: InteractiveStateHandle {
                 sessions: Arc::new(Mutex::new(HashMap::new())),
             },
@@ -912,10 +1079,16 @@ impl AppState {
         }
     }
 
+    /// Load persisted app settings from `path`; called once at app setup.
+    pub async fn load_settings(&self, path: PathBuf) {
+        *self.settings.lock().await = SettingsStore::load(path);
+    }
+
     pub fn handle(&self) -> AppStateHandle {
         AppStateHandle {
             races: Arc::clone(&self.races),
             event_tx: self.event_tx.clone(),
+            settings: Arc::clone(&self.settings),
         }
     }
 
@@ -938,9 +1111,41 @@ impl AppState {
         let runner = ProbeRunner::new(adapters);
         let report = runner.run();
 
-        *self.last_probe_report.lock().await = Some(report.clone());
+        if let Some(repo) = self.repos.lock().await.active_mut() {
+            repo.probe_report = Some(report.clone());
+        }
         report
     }
+
+    /// Live-check every available adapter in `report` concurrently, in
+    /// report order.
+    pub async fn ping_adapters(&self, report: &ProbeReport, timeout: Duration) -> Vec<PingResult> {
+        let registry = AdapterRegistry::from_config(&self.config.lock().await.adapters);
+        let mut tasks = tokio::task::JoinSet::new();
+        for (index, probe) in report.results.iter().enumerate() {
+            if !probe.detect.status.is_available() {
+                continue;
+            }
+            let Ok(adapter) = registry.resolve(&probe.adapter_key, true) else {
+                continue;
+            };
+            let flags = probe.detect.supported_flags.clone();
+            tasks.spawn(async move {
+                let result =
+                    hydra_core::adapter::ping_adapter(adapter.as_ref(), flags, timeout).await;
+                (index, result)
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok(result) = joined {
+                results.push(result);
+            }
+        }
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
 }
 
 /// Wraps an `Arc<dyn AgentAdapter>` to satisfy `ProbeRunner`'s `Box<dyn AgentAdapter>` requirement.
@@ -1195,7 +1400,8 @@ mod tests {
             .remove_session("rm-running")
             .await
             .expect_err("running sessions must not be removable");
-        assert!(err.contains("running"));
+        assert_eq!(err.code, "validation_error");
+        assert!(err.message.contains("running"));
 
         let _ = state.stop_session("rm-running").await;
     }
@@ -1519,6 +1725,7 @@ mod tests {
         assert_eq!(req.rows, Some(40));
         assert!(!req.allow_experimental);
         assert!(!req.unsafe_mode);
+        assert!(req.unsafe_consent.is_none());
         assert!(req.cwd.is_none());
     }
 
@@ -1616,6 +1823,75 @@ mod tests {
         assert_ne!(status, "running");
     }
 
+    #[tokio::test]
+    async fn interactive_snapshot_returns_scrollback_and_cursor() {
+        let state = new_interactive_state();
+        assert!(state.snapshot("snap").await.is_none());
+
+        let (tx, _rx) = mpsc::channel(16);
+        let session = PtySession::spawn(sleep_pty_config(60), tx).unwrap();
+        state
+            .register_session("snap", "claude", "2026-02-24T00:00:00Z", session, None)
+            .await;
+
+        for chunk in ["hello ", "world"] {
+            state
+                .append_output(
+                    "snap",
+                    chunk.as_bytes(),
+                    InteractiveStreamEvent {
+                        session_id: "snap".to_string(),
+                        agent_key: "claude".to_string(),
+                        event_type: "output".to_string(),
+                        data: serde_json::json!(chunk),
+                        timestamp: "2026-02-24T00:00:00Z".to_string(),
+                    },
+                )
+                .await;
+        }
+
+        let snapshot = state.snapshot("snap").await.unwrap();
+        assert_eq!(snapshot.scrollback, "hello world");
+        assert_eq!(snapshot.status, "running");
+        assert_eq!(snapshot.next_cursor, 2);
+
+        let (events, _, _, _, _) = state
+            .poll_events("snap", snapshot.next_cursor, 10)
+            .await
+            .unwrap();
+        assert!(
+            events.is_empty(),
+            "snapshot cursor should skip replayed output"
+        );
+        state.stop_session("snap").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn interactive_promotion_requires_a_stopped_session() {
+        let state = new_interactive_state();
+        assert_eq!(
+            state.promotion_source("promo").await.unwrap_err().code,
+            "not_found"
+        );
+
+        let (tx, _rx) = mpsc::channel(16);
+        let session = PtySession::spawn(sleep_pty_config(60), tx).unwrap();
+        state
+            .register_session("promo", "codex", "2026-02-24T00:00:00Z", session, None)
+            .await;
+        let err = state.promotion_source("promo").await.unwrap_err();
+        assert_eq!(err.code, "validation_error");
+        assert!(
+            err.message.contains("stop it before promoting"),
+            "got: {err}"
+        );
+
+        state.stop_session("promo").await.unwrap();
+        let source = state.promotion_source("promo").await.unwrap();
+        assert_eq!(source.agent_key, "codex");
+        assert_eq!(source.source_path, source.repo_root);
+    }
+
     #[tokio::test]
     async fn interactive_poll_cursor_survives_event_trimming() {
         let state = new_interactive_state();
@@ -2011,4 +2287,57 @@ mod tests {
         assert!(state.poll_events("w-a", 0, 10).await.is_none());
         assert!(state.poll_events("w-b", 0, 10).await.is_none());
     }
+
+    #[tokio::test]
+    async fn race_cancel_is_only_accepted_while_running() {
+        let state = AppState::new(HydraConfig::default()).handle();
+        assert!(state.request_cancel("missing").await.is_err());
+
+        state.register_race("r1").await;
+        let cancel = state.race_cancel_token("r1").await.unwrap();
+        state.request_cancel("r1").await.unwrap();
+        assert!(cancel.is_cancelled());
+
+        state.mark_cancelled("r1").await;
+        let (_, _, done, status, _) = state.poll_events("r1", 0, 10).await.unwrap();
+        assert!(done);
+        assert_eq!(status, "cancelled");
+        assert_eq!(state.race_result("r1").await.unwrap().status, "cancelled");
+        assert!(state.request_cancel("r1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn approvals_resolve_only_pending_requests() {
+        let state = AppState::new(HydraConfig::default()).handle();
+        let id = uuid::Uuid::new_v4();
+        assert_eq!(
+            state
+                .resolve_approval("missing", id, ApprovalDecision::Approve)
+                .await
+                .unwrap_err()
+                .code,
+            "not_found"
+        );
+
+        state.register_race("r1").await;
+        let gate = state.race_approvals("r1").await.unwrap();
+        assert!(state
+            .resolve_approval("r1", id, ApprovalDecision::Approve)
+            .await
+            .is_err());
+        assert!(gate.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn forget_race_keeps_running_races() {
+        let state = AppState::new(HydraConfig::default()).handle();
+        state.register_race("r1").await;
+        state.forget_race("r1").await;
+        assert!(state.race_is_running("r1").await);
+
+        state.mark_failed("r1", "boom").await;
+        assert!(!state.race_is_running("r1").await);
+        state.forget_race("r1").await;
+        assert!(state.race_result("r1").await.is_none());
+    }
 }
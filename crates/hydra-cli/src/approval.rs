@@ -0,0 +1,105 @@
+//! Terminal prompts for `security.approval.policy = "ask"`.
+//!
+//! The orchestrator pauses an agent and emits an approval request; the
+//! question is asked on stderr from a helper thread so the event sink never
+//! blocks. Without a terminal on stdin nobody can answer, so requests are
+//! denied straight away.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+use indicatif::MultiProgress;
+use uuid::Uuid;
+
+use hydra_core::orchestrator::ApprovalGate;
+use hydra_core::security::{ApprovalDecision, DangerousAction};
+
+pub struct ApprovalPrompter {
+    gate: ApprovalGate,
+    interactive: bool,
+    /// One question on screen at a time when several agents are paused.
+    prompt_lock: Arc<Mutex<()>>,
+}
+
+impl ApprovalPrompter {
+    pub fn new(gate: ApprovalGate) -> Self {
+        Self {
+            gate,
+            interactive: std::io::stdin().is_terminal(),
+            prompt_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Ask about `action` and send the answer to the gate. Spinners, if
+    /// any, are suspended while the question is on screen.
+    pub fn request(
+        &self,
+        agent_key: &str,
+        approval_id: Uuid,
+        action: &DangerousAction,
+        progress: Option<MultiProgress>,
+    ) {
+        let question = question(agent_key, action);
+        if !self.interactive {
+            eprintln!("{question}denied (no terminal to ask on)");
+            self.gate.resolve(approval_id, ApprovalDecision::Deny);
+            return;
+        }
+
+        let gate = self.gate.clone();
+        let prompt_lock = Arc::clone(&self.prompt_lock);
+        std::thread::spawn(move || {
+            let _guard = prompt_lock.lock().unwrap_or_else(|e| e.into_inner());
+            let decision = match progress {
+                Some(progress) => progress.suspend(|| ask(&question)),
+                None => ask(&question),
+            };
+            gate.resolve(approval_id, decision);
+        });
+    }
+}
+
+/// Ask about `action` and wait for the answer, for approvals that are not
+/// answered through a local [`ApprovalGate`].
+pub(crate) fn ask_blocking(agent_key: &str, action: &DangerousAction) -> ApprovalDecision {
+    let question = question(agent_key, action);
+    if !std::io::stdin().is_terminal() {
+        eprintln!("{question}denied (no terminal to ask on)");
+        return ApprovalDecision::Deny;
+    }
+    ask(&question)
+}
+
+fn question(agent_key: &str, action: &DangerousAction) -> String {
+    format!("[{agent_key}] wants to {action}. Allow? [y/N] ")
+}
+
+fn ask(question: &str) -> ApprovalDecision {
+    eprint!("{question}");
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    match std::io::stdin().lock().read_line(&mut answer) {
+        Ok(_) => parse_answer(&answer),
+        Err(_) => ApprovalDecision::Deny,
+    }
+}
+
+fn parse_answer(answer: &str) -> ApprovalDecision {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" => ApprovalDecision::Approve,
+        _ => ApprovalDecision::Deny,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_an_explicit_yes_approves() {
+        assert_eq!(parse_answer("y\n"), ApprovalDecision::Approve);
+        assert_eq!(parse_answer(" YES "), ApprovalDecision::Approve);
+        assert_eq!(parse_answer("\n"), ApprovalDecision::Deny);
+        assert_eq!(parse_answer("nope"), ApprovalDecision::Deny);
+    }
+}
@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use hydra_core::artifact::{dedup_tags, parse_tag, LockMode, RunStatus};
+use hydra_core::config::{apply_overrides, HydraConfig};
+use hydra_core::git_ref::validate_agent_key;
+
+use crate::race::{run_race, RaceOpts, RaceOutcome};
+use crate::{config, output};
+
+pub struct BatchOpts {
+    pub file: PathBuf,
+    pub parallel: usize,
+    pub json: bool,
+    pub unsafe_mode: bool,
+    pub allow_experimental_adapters: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchFile {
+    pub tasks: Vec<BatchTask>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchTask {
+    /// Label for the summary table; defaults to `task-<n>`.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub prompt: String,
+    /// Agents for this task; empty means the race defaults.
+    #[serde(default)]
+    pub agents: Vec<String>,
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    /// Labels recorded on the task's run, as with `hydra race --tag`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Partial `hydra.toml` layered over the repository config for this task.
+    #[serde(default)]
+    pub config: Option<serde_yaml::Value>,
+}
+
+/// A batch task resolved against the repository config, ready to race.
+struct PreparedTask {
+    id: String,
+    agents: Vec<String>,
+    prompt: String,
+    base_ref: String,
+    tags: Vec<String>,
+    config: HydraConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchTaskReport {
+    task_id: String,
+    run_id: Option<Uuid>,
+    status: Option<RunStatus>,
+    duration_ms: Option<u64>,
+    winner: Option<String>,
+    error: Option<String>,
+}
+
+impl BatchTaskReport {
+    fn from_result(task_id: String, result: Result<RaceOutcome>) -> Self {
+        match result {
+            Ok(outcome) => Self {
+                task_id,
+                run_id: Some(outcome.run_id),
+                status: Some(outcome.status.clone()),
+                duration_ms: Some(outcome.duration_ms),
+                winner: outcome.winner().map(str::to_string),
+                error: None,
+            },
+            Err(e) => Self {
+                task_id,
+                run_id: None,
+                status: None,
+                duration_ms: None,
+                winner: None,
+                error: Some(format!("{e:#}")),
+            },
+        }
+    }
+
+    fn completed(&self) -> bool {
+        self.status == Some(RunStatus::Completed)
+    }
+}
+
+impl BatchFile {
+    fn parse(data: &str) -> Result<Self> {
+        let file: BatchFile = serde_yaml::from_str(data).context("invalid batch file")?;
+        if file.tasks.is_empty() {
+            bail!("batch file has no tasks");
+        }
+        Ok(file)
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read batch file {}", path.display()))?;
+        Self::parse(&data)
+    }
+
+    /// Validate every task up front so a typo in task 7 does not surface
+    /// hours into an overnight batch.
+    fn prepare(self, base: &HydraConfig) -> Result<Vec<PreparedTask>> {
+        self.tasks
+            .into_iter()
+            .enumerate()
+            .map(|(idx, task)| {
+                let id = task.id.unwrap_or_else(|| format!("task-{}", idx + 1));
+                if task.prompt.trim().is_empty() {
+                    bail!("batch task '{id}' has an empty prompt");
+                }
+                for agent in &task.agents {
+                    validate_agent_key(agent)
+                        .map_err(|e| anyhow::anyhow!("batch task '{id}': {e}"))?;
+                }
+                let tags = task
+                    .tags
+                    .iter()
+                    .map(|tag| parse_tag(tag))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| anyhow::anyhow!("batch task '{id}': {e}"))?;
+                let config = match task.config {
+                    Some(overrides) => {
+                        let table: toml::Table = serde_yaml::from_value(overrides)
+                            .with_context(|| format!("batch task '{id}': invalid config"))?;
+                        apply_overrides(base, table)
+                            .with_context(|| format!("batch task '{id}': invalid config"))?
+                    }
+                    None => base.clone(),
+                };
+                Ok(PreparedTask {
+                    id,
+                    agents: task.agents,
+                    prompt: task.prompt,
+                    base_ref: task.base_ref.unwrap_or_else(|| "HEAD".to_string()),
+                    tags: dedup_tags(tags),
+                    config,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Run every task in the batch file. Returns `true` when all runs completed.
+pub async fn run_batch(opts: BatchOpts) -> Result<bool> {
+    if opts.parallel == 0 {
+        bail!("--parallel must be at least 1");
+    }
+    let base = config::load()?;
+    let tasks = BatchFile::load(&opts.file)?.prepare(&base)?;
+    let total = tasks.len();
+
+    let semaphore = Arc::new(Semaphore::new(opts.parallel));
+    let mut set = JoinSet::new();
+    for (idx, task) in tasks.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        let race_opts = RaceOpts {
+            agents: task.agents,
+            prompt: task.prompt,
+            prompt_source: None,
+            tags: task.tags,
+            task: None,
+            variants: Vec::new(),
+            base_ref: task.base_ref,
+            json: false,
+            unsafe_mode: opts.unsafe_mode,
+            allow_experimental_adapters: opts.allow_experimental_adapters,
+            run_id: None,
+            deterministic: false,
+            lock: LockMode::Wait,
+            stop_on_first_success: false,
+            skip_baseline: false,
+            quiet: true,
+            config: Some(task.config),
+        };
+        let json = opts.json;
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await?;
+            if !json {
+                println!("[{}/{}] {} started", idx + 1, total, task.id);
+            }
+            let result = run_race(race_opts).await;
+            if let Err(e) = &result {
+                tracing::warn!(task = %task.id, error = %e, "batch task failed");
+            }
+            let report = BatchTaskReport::from_result(task.id, result);
+            if !json {
+                println!(
+                    "[{}/{}] {} finished: {}",
+                    idx + 1,
+                    total,
+                    report.task_id,
+                    status_label(&report)
+                );
+            }
+            anyhow::Ok((idx, report))
+        });
+    }
+
+    let mut reports: Vec<Option<BatchTaskReport>> = (0..total).map(|_| None).collect();
+    while let Some(joined) = set.join_next().await {
+        let (idx, report) = joined.context("batch task panicked")??;
+        reports[idx] = Some(report);
+    }
+    let reports: Vec<BatchTaskReport> = reports.into_iter().flatten().collect();
+    let all_completed = reports.iter().all(BatchTaskReport::completed);
+
+    let result = serde_json::json!({
+        "file": opts.file,
+        "tasks": reports,
+    });
+    output::emit(&result, opts.json)?;
+    if opts.json {
+        return Ok(all_completed);
+    }
+
+    println!();
+    println!("Batch Results");
+    println!("=============");
+    println!(
+        "  {:<20} {:<12} {:>9} {:<14} Run ID",
+        "Task", "Status", "Duration", "Winner"
+    );
+    for report in &reports {
+        println!(
+            "  {:<20} {:<12} {:>9} {:<14} {}",
+            report.task_id,
+            status_label(report),
+            report
+                .duration_ms
+                .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+                .unwrap_or_else(|| "n/a".to_string()),
+            report.winner.as_deref().unwrap_or("none"),
+            report
+                .run_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        );
+    }
+    for report in reports.iter().filter(|r| r.error.is_some()) {
+        println!();
+        println!(
+            "  {} error: {}",
+            report.task_id,
+            report.error.as_deref().unwrap_or_default()
+        );
+    }
+    let completed = reports.iter().filter(|r| r.completed()).count();
+    println!();
+    println!("  {completed}/{total} tasks completed");
+
+    Ok(all_completed)
+}
+
+fn status_label(report: &BatchTaskReport) -> String {
+    match &report.status {
+        Some(status) => format!("{status:?}"),
+        None => "Error".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+tasks:
+  - id: fix-login
+    prompt: Fix the login redirect bug
+    agents: [claude, codex]
+    tags: [auth, auth, sprint-42]
+    config:
+      supervisor:
+        hard_timeout_seconds: 600
+  - prompt: Add a changelog entry
+    base_ref: main
+"#;
+
+    #[test]
+    fn parses_and_prepares_tasks_with_overrides() {
+        let base = HydraConfig::default();
+        let tasks = BatchFile::parse(SAMPLE).unwrap().prepare(&base).unwrap();
+        assert_eq!(tasks.len(), 2);
+
+        assert_eq!(tasks[0].id, "fix-login");
+        assert_eq!(tasks[0].agents, vec!["claude", "codex"]);
+        assert_eq!(tasks[0].base_ref, "HEAD");
+        assert_eq!(tasks[0].tags, vec!["auth", "sprint-42"]);
+        assert_eq!(tasks[0].config.supervisor.hard_timeout_seconds, 600);
+
+        assert_eq!(tasks[1].id, "task-2");
+        assert!(tasks[1].agents.is_empty());
+        assert_eq!(tasks[1].base_ref, "main");
+        assert_eq!(tasks[1].config, base);
+    }
+
+    #[test]
+    fn rejects_invalid_tasks_before_running() {
+        let base = HydraConfig::default();
+        assert!(BatchFile::parse("tasks: []\n").is_err());
+        assert!(BatchFile::parse("tasks:\n  - prompt: x\n    extra: 1\n").is_err());
+
+        let blank = BatchFile::parse("tasks:\n  - prompt: '  '\n").unwrap();
+        assert!(blank.prepare(&base).is_err());
+
+        let bad_agent = BatchFile::parse("tasks:\n  - prompt: x\n    agents: ['../x']\n").unwrap();
+        assert!(bad_agent.prepare(&base).is_err());
+
+        let bad_config = BatchFile::parse(
+            "tasks:\n  - prompt: x\n    config:\n      scoring:\n        nope: 1\n",
+        )
+        .unwrap();
+        assert!(bad_config.prepare(&base).is_err());
+    }
+}
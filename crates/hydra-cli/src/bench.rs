@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use hydra_core::artifact::{LockMode, RunStatus};
+
+use crate::output;
+use crate::race::{run_race, RaceOpts, RaceOutcome};
+
+/// Small, repository-agnostic tasks used when no `--suite` is given.
+const BUILTIN_SUITE: &str = r#"
+name: builtin
+tasks:
+  - id: doc-comment
+    prompt: >-
+      Find one public function or type that has no documentation comment and
+      add a concise, accurate doc comment to it. Do not change behavior.
+  - id: unit-test
+    prompt: >-
+      Find one small pure function that has no unit test and add a focused
+      unit test for it using the project's existing test conventions.
+  - id: typo-sweep
+    prompt: >-
+      Fix up to three spelling mistakes in comments or documentation. Do not
+      modify code identifiers or behavior.
+"#;
+
+pub struct BenchOpts {
+    pub suite: Option<PathBuf>,
+    pub agents: Vec<String>,
+    pub base_ref: String,
+    pub json: bool,
+    pub unsafe_mode: bool,
+    pub allow_experimental_adapters: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchSuite {
+    pub name: String,
+    pub tasks: Vec<BenchTask>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchTask {
+    pub id: String,
+    pub prompt: String,
+}
+
+impl BenchSuite {
+    fn parse(data: &str) -> Result<Self> {
+        let suite: BenchSuite = serde_yaml::from_str(data).context("invalid bench suite")?;
+        if suite.tasks.is_empty() {
+            bail!("bench suite '{}' has no tasks", suite.name);
+        }
+        for task in &suite.tasks {
+            if task.prompt.trim().is_empty() {
+                bail!("bench task '{}' has an empty prompt", task.id);
+            }
+        }
+        Ok(suite)
+    }
+
+    fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => {
+                let data = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read bench suite {}", path.display()))?;
+                Self::parse(&data)
+            }
+            None => Self::parse(BUILTIN_SUITE),
+        }
+    }
+}
+
+/// Per-task record in the bench report.
+#[derive(Debug, Serialize)]
+struct TaskReport {
+    task_id: String,
+    run_id: Option<Uuid>,
+    status: Option<RunStatus>,
+    duration_ms: Option<u64>,
+    winner: Option<String>,
+    error: Option<String>,
+}
+
+/// Aggregated comparison row for one adapter across the suite.
+#[derive(Debug, Default, Serialize, PartialEq)]
+struct AgentBenchSummary {
+    agent_key: String,
+    runs: u32,
+    completed: u32,
+    mergeable: u32,
+    wins: u32,
+    mean_score: Option<f64>,
+    mean_duration_ms: Option<u64>,
+    total_tokens: u64,
+    total_cost_usd: Option<f64>,
+}
+
+#[derive(Default)]
+struct AgentAccumulator {
+    runs: u32,
+    completed: u32,
+    mergeable: u32,
+    wins: u32,
+    score_sum: f64,
+    scored: u32,
+    duration_sum_ms: u64,
+    total_tokens: u64,
+    cost_sum: f64,
+    has_cost: bool,
+}
+
+fn summarize(outcomes: &[RaceOutcome]) -> Vec<AgentBenchSummary> {
+    let mut acc: BTreeMap<String, AgentAccumulator> = BTreeMap::new();
+    for outcome in outcomes {
+        let winner = outcome.winner();
+        for agent in &outcome.agents {
+            let entry = acc.entry(agent.agent_key.clone()).or_default();
+            entry.runs += 1;
+            entry.duration_sum_ms += agent.duration_ms;
+            entry.total_tokens += agent.total_tokens().unwrap_or(0);
+            if agent.status == RunStatus::Completed {
+                entry.completed += 1;
+            }
+            if let Some(cost) = agent.estimated_cost_usd() {
+                entry.cost_sum += cost;
+                entry.has_cost = true;
+            }
+            if let Some(score) = &agent.score {
+                entry.score_sum += score.composite;
+                entry.scored += 1;
+                if score.mergeable {
+                    entry.mergeable += 1;
+                }
+            }
+            if winner == Some(agent.agent_key.as_str()) {
+                entry.wins += 1;
+            }
+        }
+    }
+
+    acc.into_iter()
+        .map(|(agent_key, a)| AgentBenchSummary {
+            agent_key,
+            runs: a.runs,
+            completed: a.completed,
+            mergeable: a.mergeable,
+            wins: a.wins,
+            mean_score: (a.scored > 0).then(|| a.score_sum / a.scored as f64),
+            mean_duration_ms: (a.runs > 0).then(|| a.duration_sum_ms / a.runs as u64),
+            total_tokens: a.total_tokens,
+            total_cost_usd: a.has_cost.then_some(a.cost_sum),
+        })
+        .collect()
+}
+
+pub async fn run_bench(opts: BenchOpts) -> Result<()> {
+    let suite = BenchSuite::load(opts.suite.as_deref())?;
+
+    let mut outcomes = Vec::new();
+    let mut tasks = Vec::new();
+    for (idx, task) in suite.tasks.iter().enumerate() {
+        if !opts.json {
+            println!("[{}/{}] {}", idx + 1, suite.tasks.len(), task.id);
+        }
+        let result = run_race(RaceOpts {
+            agents: opts.agents.clone(),
+            prompt: task.prompt.clone(),
+            prompt_source: None,
+            tags: Vec::new(),
+            task: None,
+            variants: Vec::new(),
+            base_ref: opts.base_ref.clone(),
+            json: false,
+            unsafe_mode: opts.unsafe_mode,
+            allow_experimental_adapters: opts.allow_experimental_adapters,
+            run_id: None,
+            deterministic: false,
+            lock: LockMode::Wait,
+            stop_on_first_success: false,
+            skip_baseline: false,
+            quiet: true,
+            config: None,
+        })
+        .await;
+
+        match result {
+            Ok(outcome) => {
+                tasks.push(TaskReport {
+                    task_id: task.id.clone(),
+                    run_id: Some(outcome.run_id),
+                    status: Some(outcome.status.clone()),
+                    duration_ms: Some(outcome.duration_ms),
+                    winner: outcome.winner().map(str::to_string),
+                    error: None,
+                });
+                outcomes.push(outcome);
+            }
+            Err(e) => {
+                tracing::warn!(task = %task.id, error = %e, "bench task failed");
+                tasks.push(TaskReport {
+                    task_id: task.id.clone(),
+                    run_id: None,
+                    status: None,
+                    duration_ms: None,
+                    winner: None,
+                    error: Some(format!("{e:#}")),
+                });
+            }
+        }
+    }
+
+    let agents = summarize(&outcomes);
+
+    let result = serde_json::json!({
+        "suite": suite.name,
+        "tasks": tasks,
+        "agents": agents,
+    });
+    output::emit(&result, opts.json)?;
+    if opts.json {
+        return Ok(());
+    }
+
+    println!();
+    println!("Bench Results");
+    println!("=============");
+    println!("  Suite:  {} ({} tasks)", suite.name, suite.tasks.len());
+    println!();
+    for task in &tasks {
+        let status = match (&task.status, &task.error) {
+            (Some(status), _) => format!("{status:?}"),
+            (None, Some(err)) => format!("error: {err}"),
+            (None, None) => "unknown".to_string(),
+        };
+        println!(
+            "  {:<20} {:<12} winner: {}",
+            task.task_id,
+            status,
+            task.winner.as_deref().unwrap_or("none")
+        );
+    }
+    println!();
+    println!(
+        "  {:<14} {:>4} {:>9} {:>9} {:>4} {:>10} {:>10} {:>10} {:>10}",
+        "Agent",
+        "Runs",
+        "Completed",
+        "Mergeable",
+        "Wins",
+        "Mean Score",
+        "Mean Time",
+        "Tokens",
+        "Cost"
+    );
+    for a in &agents {
+        println!(
+            "  {:<14} {:>4} {:>9} {:>9} {:>4} {:>10} {:>10} {:>10} {:>10}",
+            a.agent_key,
+            a.runs,
+            a.completed,
+            a.mergeable,
+            a.wins,
+            a.mean_score
+                .map(|s| format!("{s:.1}"))
+                .unwrap_or_else(|| "n/a".to_string()),
+            a.mean_duration_ms
+                .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+                .unwrap_or_else(|| "n/a".to_string()),
+            a.total_tokens,
+            a.total_cost_usd
+                .map(|c| format!("${c:.4}"))
+                .unwrap_or_else(|| "n/a".to_string()),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::adapter::AdapterTier;
+    use hydra_core::orchestrator::{AgentOutcome, UsageStatus};
+    use hydra_core::scoring::baseline::ResolvedCommands;
+    use hydra_core::scoring::cost::CostEstimate;
+    use hydra_core::scoring::ranking::AgentScore;
+
+    fn usage(total_tokens: u64) -> CostEstimate {
+        CostEstimate {
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens,
+            estimated_cost_usd: None,
+        }
+    }
+
+    fn race(agents: Vec<AgentOutcome>) -> RaceOutcome {
+        RaceOutcome {
+            run_id: Uuid::new_v4(),
+            status: RunStatus::Completed,
+            duration_ms: 2000,
+            artifacts_dir: PathBuf::new(),
+            baseline_path: PathBuf::new(),
+            commands: ResolvedCommands {
+                build: None,
+                test: None,
+                lint: None,
+            },
+            agents,
+            rankings: Vec::new(),
+            usage: usage(0),
+            budget_stop_reason: None,
+            stopped_early_by: None,
+            health: None,
+        }
+    }
+
+    fn agent(key: &str, composite: Option<f64>, mergeable: bool) -> AgentOutcome {
+        AgentOutcome {
+            agent_key: key.to_string(),
+            tier: AdapterTier::Tier1,
+            status: RunStatus::Completed,
+            error: None,
+            duration_ms: 1000,
+            branch: format!("hydra/run/agent/{key}"),
+            worktree_cleaned: true,
+            usage: Some(usage(100)),
+            usage_status: UsageStatus::Captured,
+            score: composite.map(|c| AgentScore {
+                agent_key: key.to_string(),
+                dimensions: Vec::new(),
+                composite: c,
+                mergeable,
+                gate_failures: Vec::new(),
+                partial: false,
+            }),
+            notes: None,
+            commits: None,
+        }
+    }
+
+    #[test]
+    fn builtin_suite_parses() {
+        let suite = BenchSuite::load(None).unwrap();
+        assert_eq!(suite.name, "builtin");
+        assert!(suite.tasks.len() >= 3);
+    }
+
+    #[test]
+    fn suite_rejects_empty_tasks_and_unknown_fields() {
+        assert!(BenchSuite::parse("name: x\ntasks: []\n").is_err());
+        assert!(
+            BenchSuite::parse("name: x\ntasks:\n  - id: a\n    prompt: p\n    extra: 1\n").is_err()
+        );
+    }
+
+    #[test]
+    fn summarize_counts_wins_and_means() {
+        let outcomes = vec![
+            race(vec![
+                agent("claude", Some(90.0), true),
+                agent("codex", Some(70.0), true),
+            ]),
+            race(vec![
+                agent("claude", Some(50.0), false),
+                agent("codex", None, false),
+            ]),
+        ];
+
+        let summary = summarize(&outcomes);
+        assert_eq!(summary.len(), 2);
+        let claude = &summary[0];
+        assert_eq!(claude.agent_key, "claude");
+        assert_eq!(claude.runs, 2);
+        assert_eq!(claude.wins, 1);
+        assert_eq!(claude.mergeable, 1);
+        assert_eq!(claude.mean_score, Some(70.0));
+        assert_eq!(claude.total_tokens, 200);
+        assert_eq!(claude.total_cost_usd, None);
+
+        let codex = &summary[1];
+        assert_eq!(codex.wins, 0);
+        assert_eq!(codex.mean_score, Some(70.0));
+    }
+}
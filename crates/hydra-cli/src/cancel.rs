@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use uuid::Uuid;
+
+use hydra_core::artifact::{RunLayout, RunManifest, RunStatus};
+
+use crate::output;
+use crate::run::{discover_repo_root, resolve_run_id};
+
+pub struct CancelOpts {
+    pub run_id: Option<Uuid>,
+    pub latest: bool,
+    pub agent: Option<String>,
+    pub json: bool,
+}
+
+pub fn run_cancel(opts: CancelOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let hydra_root = repo_root.join(".hydra");
+    let run_id = resolve_run_id(&hydra_root, opts.run_id, opts.latest)?;
+    let layout = RunLayout::new(&hydra_root, run_id);
+
+    let marker = request_cancel(&layout, opts.agent.as_deref())?;
+    let target = opts.agent.as_deref().unwrap_or("all agents");
+
+    let result = serde_json::json!({
+        "run_id": run_id,
+        "agent": opts.agent,
+        "marker_path": marker,
+    });
+    output::emit(&result, opts.json)?;
+    if !opts.json {
+        println!("Cancellation requested");
+        println!("  Run ID:  {run_id}");
+        println!("  Target:  {target}");
+        println!("  Marker:  {}", marker.display());
+    }
+
+    Ok(())
+}
+
+/// Ask a live race to cancel one agent, or every agent when `agent` is `None`.
+///
+/// The race polls for the marker file and cancels matching agents gracefully,
+/// so this returns as soon as the request is recorded.
+pub(crate) fn request_cancel(layout: &RunLayout, agent: Option<&str>) -> Result<PathBuf> {
+    let manifest = RunManifest::read_from(&layout.manifest_path()).with_context(|| {
+        format!(
+            "failed to read manifest for run {} at {}",
+            layout.run_id(),
+            layout.manifest_path().display()
+        )
+    })?;
+
+    if manifest.status != RunStatus::Running {
+        bail!(
+            "run {} is not running (status: {:?})",
+            layout.run_id(),
+            manifest.status
+        );
+    }
+
+    let marker = match agent {
+        Some(key) => {
+            if !manifest.agents.iter().any(|a| a.agent_key == key) {
+                bail!("agent '{}' is not part of run {}", key, layout.run_id());
+            }
+            layout.agent_cancel_marker(key)
+        }
+        None => layout.run_cancel_marker(),
+    };
+
+    let body = serde_json::json!({
+        "requested_at": chrono::Utc::now(),
+        "agent": agent,
+    });
+    std::fs::write(&marker, serde_json::to_string(&body)?)
+        .with_context(|| format!("failed to write cancel marker {}", marker.display()))?;
+
+    tracing::info!(run_id = %layout.run_id(), agent = ?agent, "cancellation requested");
+    Ok(marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+
+    fn running_layout(tmp: &TempDir) -> RunLayout {
+        let (layout, manifest) = new_run(&tmp.path().join(".hydra"), &["claude"]);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+        layout
+    }
+
+    #[test]
+    fn request_cancel_writes_agent_and_run_markers() {
+        let tmp = TempDir::new().unwrap();
+        let layout = running_layout(&tmp);
+
+        let agent_marker = request_cancel(&layout, Some("claude")).unwrap();
+        assert_eq!(agent_marker, layout.agent_cancel_marker("claude"));
+        assert!(layout.cancel_requested("claude"));
+        assert!(!layout.run_cancel_marker().exists());
+
+        let run_marker = request_cancel(&layout, None).unwrap();
+        assert_eq!(run_marker, layout.run_cancel_marker());
+        assert!(run_marker.exists());
+    }
+
+    #[test]
+    fn request_cancel_rejects_unknown_agent() {
+        let tmp = TempDir::new().unwrap();
+        let layout = running_layout(&tmp);
+        assert!(request_cancel(&layout, Some("codex")).is_err());
+    }
+
+    #[test]
+    fn request_cancel_rejects_finished_run() {
+        let tmp = TempDir::new().unwrap();
+        let layout = running_layout(&tmp);
+        let mut manifest = RunManifest::read_from(&layout.manifest_path()).unwrap();
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let err = request_cancel(&layout, None).unwrap_err();
+        assert!(err.to_string().contains("not running"));
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+use uuid::Uuid;
+
+use hydra_core::artifact::{RunEvent, RunLayout, RunStatus};
+use hydra_core::error::ErrorCode;
+use hydra_core::orchestrator::ErrorContext;
+
+use crate::output::{self, EXIT_INFRA_FAILURE, EXIT_NOT_MERGEABLE, EXIT_SUCCESS};
+use crate::race::{run_race, RaceOpts, RaceOutcome};
+use crate::run::discover_repo_root;
+use crate::watch::RunEventSources;
+
+/// Summary file written when `--summary-file` is not given.
+pub const DEFAULT_SUMMARY_FILE: &str = "hydra-ci-summary.json";
+
+const PROGRESS_POLL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiVerdict {
+    Mergeable,
+    NotMergeable,
+    InfraFailure,
+}
+
+impl CiVerdict {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            CiVerdict::Mergeable => EXIT_SUCCESS,
+            CiVerdict::NotMergeable => EXIT_NOT_MERGEABLE,
+            CiVerdict::InfraFailure => EXIT_INFRA_FAILURE,
+        }
+    }
+
+    fn classify(outcome: &RaceOutcome) -> Self {
+        if outcome.winner().is_some() {
+            return CiVerdict::Mergeable;
+        }
+        match outcome.status {
+            RunStatus::Completed | RunStatus::Interrupted => CiVerdict::NotMergeable,
+            RunStatus::Failed | RunStatus::TimedOut | RunStatus::Running => CiVerdict::InfraFailure,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CiSummary<'a> {
+    verdict: CiVerdict,
+    exit_code: i32,
+    run_id: Uuid,
+    winner: Option<String>,
+    error: Option<String>,
+    /// Stable code for `error`, when it came from Hydra itself.
+    error_code: Option<ErrorCode>,
+    /// Run, agent and phase `error` was raised in, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_context: Option<ErrorContext>,
+    outcome: Option<&'a RaceOutcome>,
+}
+
+/// Run a race in CI mode: JSON-lines progress on stdout, a summary file,
+/// and an exit code from the documented contract.
+pub async fn run_race_ci(mut opts: RaceOpts, summary_file: Option<PathBuf>) -> Result<i32> {
+    let run_id = *opts.run_id.get_or_insert_with(Uuid::new_v4);
+    opts.quiet = true;
+    opts.json = false;
+
+    let repo_root = discover_repo_root()?;
+    let layout = RunLayout::new(&repo_root.join(".hydra"), run_id);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let progress = tokio::spawn(stream_progress(layout, Arc::clone(&stop)));
+
+    let result = run_race(opts).await;
+
+    stop.store(true, Ordering::SeqCst);
+    if let Err(e) = progress.await {
+        tracing::warn!(error = %e, "ci progress task failed");
+    }
+
+    let (verdict, error, error_code, error_context) = match &result {
+        Ok(outcome) => (CiVerdict::classify(outcome), None, None, None),
+        Err(e) => (
+            CiVerdict::InfraFailure,
+            Some(format!("{e:#}")),
+            ErrorCode::find(e.as_ref()),
+            ErrorContext::find(e.as_ref()).cloned(),
+        ),
+    };
+    let outcome = result.as_ref().ok();
+    let winner = outcome.and_then(|o| o.winner()).map(str::to_string);
+
+    let summary = CiSummary {
+        verdict,
+        exit_code: verdict.exit_code(),
+        run_id,
+        winner,
+        error,
+        error_code,
+        error_context,
+        outcome,
+    };
+
+    let summary_path = summary_file.unwrap_or_else(|| PathBuf::from(DEFAULT_SUMMARY_FILE));
+    output::write_atomic(&summary_path, &serde_json::to_vec_pretty(&summary)?)?;
+    output::emit(&summary, false)?;
+
+    let mut line = serde_json::to_value(&summary)?;
+    line["type"] = serde_json::json!("summary");
+    line["summary_file"] = serde_json::json!(summary_path);
+    println!("{}", serde_json::to_string(&line)?);
+
+    Ok(verdict.exit_code())
+}
+
+async fn stream_progress(layout: RunLayout, stop: Arc<AtomicBool>) {
+    let mut sources = RunEventSources::open(&layout);
+    loop {
+        // Read the flag before polling so the final poll after a stop drains
+        // everything the race wrote.
+        let stopping = stop.load(Ordering::SeqCst);
+        match sources.poll() {
+            Ok(events) => events.iter().for_each(print_event_line),
+            Err(e) => tracing::warn!(error = %e, "failed to read run events"),
+        }
+        if stopping {
+            break;
+        }
+        tokio::time::sleep(PROGRESS_POLL).await;
+    }
+}
+
+fn print_event_line(event: &RunEvent) {
+    let line = serde_json::json!({
+        "type": "event",
+        "timestamp": event.timestamp,
+        "kind": event.kind,
+        "agent_key": event.agent_key,
+        "data": event.data,
+    });
+    println!("{line}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::adapter::AdapterTier;
+    use hydra_core::orchestrator::{AgentOutcome, UsageStatus};
+    use hydra_core::scoring::baseline::ResolvedCommands;
+    use hydra_core::scoring::cost::CostEstimate;
+    use hydra_core::scoring::ranking::AgentScore;
+
+    fn outcome(status: RunStatus, mergeable: Option<bool>) -> RaceOutcome {
+        RaceOutcome {
+            run_id: Uuid::new_v4(),
+            status,
+            duration_ms: 0,
+            artifacts_dir: PathBuf::new(),
+            baseline_path: PathBuf::new(),
+            commands: ResolvedCommands {
+                build: None,
+                test: None,
+                lint: None,
+            },
+            rankings: Vec::new(),
+            usage: CostEstimate {
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                estimated_cost_usd: None,
+            },
+            budget_stop_reason: None,
+            stopped_early_by: None,
+            health: None,
+            agents: vec![AgentOutcome {
+                agent_key: "claude".to_string(),
+                tier: AdapterTier::Tier1,
+                status: RunStatus::Completed,
+                error: None,
+                duration_ms: 0,
+                branch: "hydra/run/agent/claude".to_string(),
+                worktree_cleaned: true,
+                usage: None,
+                usage_status: UsageStatus::Unavailable,
+                score: mergeable.map(|m| AgentScore {
+                    agent_key: "claude".to_string(),
+                    dimensions: Vec::new(),
+                    composite: 80.0,
+                    mergeable: m,
+                    gate_failures: Vec::new(),
+                    partial: false,
+                }),
+                notes: None,
+                commits: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn verdict_maps_to_documented_exit_codes() {
+        assert_eq!(
+            CiVerdict::classify(&outcome(RunStatus::Completed, Some(true))).exit_code(),
+            0
+        );
+        assert_eq!(
+            CiVerdict::classify(&outcome(RunStatus::Completed, Some(false))).exit_code(),
+            2
+        );
+        assert_eq!(
+            CiVerdict::classify(&outcome(RunStatus::Interrupted, None)).exit_code(),
+            2
+        );
+        assert_eq!(
+            CiVerdict::classify(&outcome(RunStatus::Failed, None)).exit_code(),
+            3
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Resolution of the `hydra.toml` every subcommand loads.
+//!
+//! The global `--config <file>` wins; otherwise the nearest `hydra.toml` in
+//! the current directory or its ancestors is used. With neither, commands run
+//! on defaults with scoring commands detected from the project's manifests,
+//! and `doctor --fix` creates the file at the repository root.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+
+use hydra_core::config::{discover_config, HydraConfig, CONFIG_FILE_NAME};
+
+use crate::run::discover_repo_root;
+
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Record the global `--config` path. Called once from `main`.
+pub fn set_config_path(path: PathBuf) -> Result<()> {
+    if !path.is_file() {
+        bail!("config file {} not found", path.display());
+    }
+    if CONFIG_PATH.set(path).is_err() {
+        bail!("config path already set");
+    }
+    Ok(())
+}
+
+/// The config file commands should read, which may not exist yet.
+pub fn path() -> PathBuf {
+    if let Some(path) = CONFIG_PATH.get() {
+        return path.clone();
+    }
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    resolve(&cwd, discover_repo_root().ok().as_deref())
+}
+
+fn resolve(cwd: &Path, repo_root: Option<&Path>) -> PathBuf {
+    discover_config(cwd).unwrap_or_else(|| repo_root.unwrap_or(cwd).join(CONFIG_FILE_NAME))
+}
+
+/// Load and validate the resolved config, falling back to detected defaults
+/// when no file exists.
+pub fn load() -> Result<HydraConfig> {
+    let path = path();
+    let project_root = match discover_repo_root() {
+        Ok(root) => root,
+        Err(_) => std::env::current_dir()?,
+    };
+    HydraConfig::load_or_default(&path, &project_root)
+        .with_context(|| format!("failed to load {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_nearest_file_then_repo_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let nested = tmp.path().join("crates").join("app");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(
+            resolve(&nested, Some(tmp.path())),
+            tmp.path().join("hydra.toml")
+        );
+        std::fs::write(nested.join("hydra.toml"), "").unwrap();
+        assert_eq!(
+            resolve(&nested, Some(tmp.path())),
+            nested.join("hydra.toml")
+        );
+    }
+
+    #[test]
+    fn explicit_config_must_exist() {
+        assert!(set_config_path(PathBuf::from("/nonexistent/hydra.toml")).is_err());
+    }
+}
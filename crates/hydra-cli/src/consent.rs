@@ -0,0 +1,95 @@
+use anyhow::{bail, Result};
+use chrono::Utc;
+use clap::Subcommand;
+
+use hydra_core::security::ConsentStore;
+
+use crate::run::discover_repo_root;
+use crate::{config, output};
+
+#[derive(Subcommand)]
+pub enum UnsafeCommand {
+    /// Allow `--unsafe` races and sessions for a limited time
+    Grant {
+        /// Minutes the grant lasts (defaults to security.unsafe_consent_minutes)
+        #[arg(long)]
+        minutes: Option<u64>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the current grant and when it expires
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Withdraw the current grant before it expires
+    Revoke {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run_command(command: UnsafeCommand) -> Result<()> {
+    let store = ConsentStore::new(&discover_repo_root()?.join(".hydra"));
+
+    match command {
+        UnsafeCommand::Grant { minutes, json } => {
+            let minutes = match minutes {
+                Some(0) => bail!("--minutes must be > 0"),
+                Some(minutes) => minutes,
+                None => config::load()?.security.unsafe_consent_minutes,
+            };
+            let consent = store.grant(chrono::Duration::minutes(minutes as i64))?;
+            output::emit(&consent, json)?;
+            if !json {
+                println!(
+                    "Unsafe mode granted until {} ({minutes} min).",
+                    consent.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+                println!("Token: {}", consent.token);
+            }
+        }
+        UnsafeCommand::Status { json } => {
+            let consent = store.load()?;
+            let active = consent
+                .as_ref()
+                .is_some_and(|consent| !consent.is_expired_at(Utc::now()));
+            output::emit(
+                &serde_json::json!({ "active": active, "consent": consent }),
+                json,
+            )?;
+            if !json {
+                match consent {
+                    Some(consent) if active => println!(
+                        "Unsafe mode granted until {}.",
+                        consent.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    Some(consent) => println!(
+                        "Unsafe mode grant expired at {}.",
+                        consent.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    None => println!("Unsafe mode has not been granted."),
+                }
+            }
+        }
+        UnsafeCommand::Revoke { json } => {
+            let revoked = store.revoke()?;
+            output::emit(&serde_json::json!({ "revoked": revoked }), json)?;
+            if !json {
+                println!(
+                    "{}",
+                    if revoked {
+                        "Unsafe mode grant revoked."
+                    } else {
+                        "No unsafe mode grant to revoke."
+                    }
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use uuid::Uuid;
+
+use hydra_core::artifact::{RunLayout, RunManifest};
+use hydra_core::git_ref::validate_branch_name;
+use hydra_core::scoring::diff_scope::parse_numstat;
+
+use crate::merge::branch_exists;
+use crate::output;
+use crate::run::{discover_repo_root, resolve_run_id};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMode {
+    Patch,
+    Stat,
+    NameOnly,
+}
+
+pub struct DiffOpts {
+    pub run_id: Option<Uuid>,
+    pub latest: bool,
+    pub agent: String,
+    pub mode: DiffMode,
+    pub pager: bool,
+}
+
+/// Where the patch text came from.
+#[derive(Debug, PartialEq, Eq)]
+enum DiffSource {
+    Artifact,
+    Branch(String),
+}
+
+pub fn run_diff(opts: DiffOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let hydra_root = repo_root.join(".hydra");
+    let run_id = resolve_run_id(&hydra_root, opts.run_id, opts.latest)?;
+    let layout = RunLayout::new(&hydra_root, run_id);
+
+    let (patch, source) = load_patch(&repo_root, &layout, &opts.agent)?;
+    if let DiffSource::Branch(branch) = &source {
+        tracing::info!(%branch, "no diff.patch artifact; regenerated diff from branch");
+    }
+
+    if output::requested() {
+        let numstat = git_apply_summary(&repo_root, &patch, "--numstat")?;
+        let (source_label, branch) = match &source {
+            DiffSource::Artifact => ("artifact", None),
+            DiffSource::Branch(branch) => ("branch", Some(branch.as_str())),
+        };
+        let result = serde_json::json!({
+            "run_id": run_id,
+            "agent": opts.agent,
+            "source": source_label,
+            "branch": branch,
+            "stats": parse_numstat(&numstat),
+        });
+        output::emit(&result, false)?;
+    }
+
+    let output = match opts.mode {
+        DiffMode::Patch => patch,
+        DiffMode::Stat => git_apply_summary(&repo_root, &patch, "--stat")?,
+        DiffMode::NameOnly => name_only(&git_apply_summary(&repo_root, &patch, "--numstat")?),
+    };
+
+    if output.is_empty() {
+        eprintln!("agent '{}' made no changes in run {run_id}", opts.agent);
+        return Ok(());
+    }
+
+    if opts.pager && opts.mode == DiffMode::Patch {
+        return page(&output);
+    }
+    std::io::stdout()
+        .write_all(output.as_bytes())
+        .context("failed to write diff to stdout")
+}
+
+/// Read the persisted `diff.patch`, or regenerate it from the agent's
+/// retained branch when the artifact is missing.
+fn load_patch(
+    repo_root: &Path,
+    layout: &RunLayout,
+    agent_key: &str,
+) -> Result<(String, DiffSource)> {
+    let manifest = RunManifest::read_from(&layout.manifest_path()).with_context(|| {
+        format!(
+            "failed to read manifest for run {} at {}",
+            layout.run_id(),
+            layout.manifest_path().display()
+        )
+    })?;
+    let entry = manifest
+        .agents
+        .iter()
+        .find(|a| a.agent_key == agent_key)
+        .ok_or_else(|| {
+            anyhow::anyhow!("agent '{}' not found in run {}", agent_key, layout.run_id())
+        })?;
+
+    let diff_path = layout.agent_diff(agent_key);
+    if diff_path.exists() {
+        let patch = std::fs::read_to_string(&diff_path)
+            .with_context(|| format!("failed to read {}", diff_path.display()))?;
+        return Ok((patch, DiffSource::Artifact));
+    }
+
+    let branch = &entry.branch;
+    validate_branch_name(branch)
+        .map_err(|e| anyhow::anyhow!("invalid branch name '{}': {}", branch, e))?;
+    if !branch_exists(repo_root, branch)? {
+        bail!(
+            "no diff artifact at {} and branch '{}' no longer exists",
+            diff_path.display(),
+            branch
+        );
+    }
+
+    let range = format!("{}...{}", manifest.base_ref, branch);
+    let output = Command::new("git")
+        .args(["diff", "--no-color", "--patch", &range, "--"])
+        .current_dir(repo_root)
+        .output()
+        .context("failed to run git diff")?;
+    if !output.status.success() {
+        bail!(
+            "git diff {range} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok((
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        DiffSource::Branch(branch.clone()),
+    ))
+}
+
+/// Summarize a patch with `git apply --stat` or `--numstat` without touching
+/// the working tree.
+fn git_apply_summary(repo_root: &Path, patch: &str, flag: &str) -> Result<String> {
+    if patch.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let mut child = Command::new("git")
+        .args(["apply", flag, "-"])
+        .current_dir(repo_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run git apply")?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(patch.as_bytes())
+        .context("failed to pass patch to git apply")?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "git apply {flag} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn name_only(numstat: &str) -> String {
+    numstat
+        .lines()
+        .filter_map(|line| line.splitn(3, '\t').nth(2))
+        .map(|path| format!("{path}\n"))
+        .collect()
+}
+
+/// Pipe the patch through `$HYDRA_PAGER`, `delta`, `$PAGER`, or `less -R`,
+/// using the first one that starts.
+fn page(text: &str) -> Result<()> {
+    let mut candidates: Vec<String> = Vec::new();
+    candidates.extend(std::env::var("HYDRA_PAGER").ok());
+    candidates.push("delta".to_string());
+    candidates.extend(std::env::var("PAGER").ok());
+    candidates.push("less -R".to_string());
+
+    for candidate in candidates {
+        let mut parts = candidate.split_whitespace();
+        let Some(program) = parts.next() else {
+            continue;
+        };
+        let Ok(mut child) = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            // The pager may exit before reading everything (e.g. `q` in less).
+            let _ = stdin.write_all(text.as_bytes());
+        }
+        child.wait().context("pager failed")?;
+        return Ok(());
+    }
+
+    std::io::stdout()
+        .write_all(text.as_bytes())
+        .context("failed to write diff to stdout")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::test_support::run_manifest;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "git {args:?} failed");
+    }
+
+    fn init_repo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("a.txt"), "one\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "init"]);
+        tmp
+    }
+
+    fn write_manifest(layout: &RunLayout, branch: &str) {
+        layout.create_dirs(&["claude"]).unwrap();
+        let mut manifest = run_manifest(layout.run_id(), &["claude"]);
+        manifest.base_ref = "main".to_string();
+        manifest.agents[0].branch = branch.to_string();
+        manifest.write_to(&layout.manifest_path()).unwrap();
+    }
+
+    #[test]
+    fn prefers_persisted_artifact_and_summarizes_it() {
+        let repo = init_repo();
+        let layout = RunLayout::new(&repo.path().join(".hydra"), Uuid::new_v4());
+        write_manifest(&layout, "hydra/x/agent/claude");
+        let patch =
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1,2 @@\n one\n+two\n";
+        std::fs::write(layout.agent_diff("claude"), patch).unwrap();
+
+        let (loaded, source) = load_patch(repo.path(), &layout, "claude").unwrap();
+        assert_eq!(source, DiffSource::Artifact);
+        assert_eq!(loaded, patch);
+
+        let numstat = git_apply_summary(repo.path(), &loaded, "--numstat").unwrap();
+        assert_eq!(name_only(&numstat), "a.txt\n");
+        let stat = git_apply_summary(repo.path(), &loaded, "--stat").unwrap();
+        assert!(stat.contains("1 insertion"));
+    }
+
+    #[test]
+    fn regenerates_from_branch_when_artifact_missing() {
+        let repo = init_repo();
+        let branch = "hydra/x/agent/claude";
+        git(repo.path(), &["checkout", "-q", "-b", branch]);
+        std::fs::write(repo.path().join("b.txt"), "new\n").unwrap();
+        git(repo.path(), &["add", "b.txt"]);
+        git(repo.path(), &["commit", "-q", "-m", "agent change"]);
+        git(repo.path(), &["checkout", "-q", "main"]);
+
+        let layout = RunLayout::new(&repo.path().join(".hydra"), Uuid::new_v4());
+        write_manifest(&layout, branch);
+
+        let (patch, source) = load_patch(repo.path(), &layout, "claude").unwrap();
+        assert_eq!(source, DiffSource::Branch(branch.to_string()));
+        assert!(patch.contains("+++ b/b.txt"));
+    }
+
+    #[test]
+    fn errors_for_unknown_agent_or_missing_sources() {
+        let repo = init_repo();
+        let layout = RunLayout::new(&repo.path().join(".hydra"), Uuid::new_v4());
+        write_manifest(&layout, "hydra/x/agent/claude");
+
+        assert!(load_patch(repo.path(), &layout, "codex").is_err());
+        let err = load_patch(repo.path(), &layout, "claude").unwrap_err();
+        assert!(err.to_string().contains("no longer exists"));
+    }
+}
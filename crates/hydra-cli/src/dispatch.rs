@@ -0,0 +1,550 @@
+//! `hydra race --worker`: run the race on a `hydra worker` and keep only
+//! control here.
+//!
+//! The base commit goes to the worker as a git bundle, thin when the worker
+//! already holds an ancestor, together with the local `hydra.toml`. Progress
+//! is polled from the worker's event logs, approval requests are asked here,
+//! and Ctrl-C cancels the remote race. When the run settles its artifacts
+//! are unpacked into the local `.hydra/runs`, so `hydra merge`, `diff` and
+//! `run show` work as if the race had run locally.
+//!
+//! The protocol is plain HTTP; reach a worker on another network through an
+//! SSH tunnel.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+use hydra_core::artifact::{EventKind, RunEvent, RunLayout, RunManifest};
+use hydra_core::security::DangerousAction;
+
+use crate::race::RaceOpts;
+use crate::run::discover_repo_root;
+use crate::watch::WatchState;
+use crate::worker::{valid_repo_name, BUNDLE_REF, DEFAULT_PORT, TOKEN_ENV};
+
+/// How often the worker is asked for new events.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A `hydra worker` and the token it was started with.
+struct WorkerClient {
+    host: String,
+    port: u16,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct RepoInfo {
+    commit: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StartedRace {
+    run_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct EventPage {
+    events: Vec<RunEvent>,
+    next: usize,
+}
+
+impl WorkerClient {
+    /// `url` is `http://host[:port]`; the token comes from `HYDRA_WORKER_TOKEN`.
+    fn new(url: &str) -> Result<Self> {
+        let token = std::env::var(TOKEN_ENV)
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .with_context(|| {
+                format!("set {TOKEN_ENV} to the token the worker printed at startup")
+            })?;
+        let (host, port) = parse_url(url)?;
+        Ok(Self { host, port, token })
+    }
+
+    fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// Send one request and return the body of a 2xx response.
+    async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<(&str, Vec<u8>)>,
+    ) -> Result<Vec<u8>> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .with_context(|| format!("failed to reach worker at {}", self.authority()))?;
+        let (content_type, body) = body.unwrap_or(("application/json", Vec::new()));
+        let head = format!(
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.authority(),
+            self.token,
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&body).await?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .await
+            .context("worker closed the connection")?;
+        let (status, body) = split_response(&response).context("malformed worker response")?;
+        if !(200..300).contains(&status) {
+            let message = serde_json::from_slice::<serde_json::Value>(body)
+                .ok()
+                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string))
+                .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string());
+            bail!("worker answered {method} {path} with {status}: {message}");
+        }
+        Ok(body.to_vec())
+    }
+
+    async fn json<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<T> {
+        let body = body.map(|v| ("application/json", v.to_string().into_bytes()));
+        let response = self.request(method, path, body).await?;
+        serde_json::from_slice(&response)
+            .with_context(|| format!("unexpected worker response to {method} {path}"))
+    }
+}
+
+/// Run `opts` on the worker at `url` and import the finished run. Returns
+/// the imported manifest.
+pub async fn run_remote_race(opts: RaceOpts, url: &str) -> Result<RunManifest> {
+    if opts.unsafe_mode {
+        bail!("--unsafe cannot be used with --worker");
+    }
+    if opts.run_id.is_some() {
+        bail!("--run-id cannot be used with --worker");
+    }
+    let client = WorkerClient::new(url)?;
+    let repo_root = discover_repo_root()?;
+    let name = repo_name(&repo_root);
+    let print = !opts.json && !opts.quiet;
+
+    let commit = git_text(
+        &repo_root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", opts.base_ref),
+        ],
+    )
+    .await
+    .with_context(|| format!("base ref '{}' does not name a commit", opts.base_ref))?;
+    if sync(&client, &repo_root, &name, &commit).await? && print {
+        eprintln!("Pushed {} to {}", short(&commit), client.authority());
+    }
+
+    let config_path = crate::config::path();
+    let config = if config_path.is_file() {
+        std::fs::read(&config_path)
+            .with_context(|| format!("failed to read {}", config_path.display()))?
+    } else {
+        Vec::new()
+    };
+    client
+        .request(
+            "PUT",
+            &format!("/v1/repos/{name}/config"),
+            Some(("application/toml", config)),
+        )
+        .await?;
+
+    let started: StartedRace = client
+        .json(
+            "POST",
+            &format!("/v1/repos/{name}/races"),
+            Some(&serde_json::json!({
+                "prompt": opts.prompt,
+                "agents": opts.agents,
+                "base_ref": commit,
+                "allow_experimental_adapters": opts.allow_experimental_adapters,
+                "prompt_source": opts.prompt_source,
+                "tags": opts.tags,
+                "task": opts.task,
+                "variants": opts.variants,
+            })),
+        )
+        .await?;
+    let run_id = started.run_id;
+    if print {
+        eprintln!("Racing on {} as run {run_id}", client.authority());
+    }
+
+    follow(&client, &name, run_id, print).await?;
+    import(&client, &name, run_id, &repo_root).await
+}
+
+/// Make the worker's copy of `name` hold `commit`. Returns whether a
+/// bundle had to be sent.
+async fn sync(client: &WorkerClient, repo_root: &Path, name: &str, commit: &str) -> Result<bool> {
+    let info: RepoInfo = client
+        .json("GET", &format!("/v1/repos/{name}"), None)
+        .await?;
+    if info.commit.as_deref() == Some(commit) {
+        return Ok(false);
+    }
+
+    // Bundles carry refs, not bare commits, so the commit is named by a
+    // scratch ref for as long as it takes to write one.
+    git_text(repo_root, &["update-ref", BUNDLE_REF, commit]).await?;
+    let mut bundle = Err(anyhow::anyhow!("no bundle written"));
+    if let Some(known) = &info.commit {
+        if git_text(
+            repo_root,
+            &["cat-file", "-e", &format!("{known}^{{commit}}")],
+        )
+        .await
+        .is_ok()
+        {
+            // Empty when `known` already contains `commit`; a full bundle
+            // is the fallback.
+            bundle = git_bytes(
+                repo_root,
+                &[
+                    "bundle",
+                    "create",
+                    "--quiet",
+                    "-",
+                    BUNDLE_REF,
+                    &format!("^{known}"),
+                ],
+            )
+            .await;
+        }
+    }
+    if bundle.is_err() {
+        bundle = git_bytes(repo_root, &["bundle", "create", "--quiet", "-", BUNDLE_REF]).await;
+    }
+    git_text(repo_root, &["update-ref", "-d", BUNDLE_REF]).await?;
+
+    let synced: RepoInfo = serde_json::from_slice(
+        &client
+            .request(
+                "POST",
+                &format!("/v1/repos/{name}/sync"),
+                Some(("application/x-git-bundle", bundle?)),
+            )
+            .await?,
+    )
+    .context("unexpected worker response to sync")?;
+    if synced.commit.as_deref() != Some(commit) {
+        bail!(
+            "worker synced {} instead of {commit}",
+            synced.commit.as_deref().unwrap_or("nothing")
+        );
+    }
+    Ok(true)
+}
+
+/// Print the run's events until its manifest settles. Approval requests
+/// are asked here and Ctrl-C cancels the run on the worker.
+async fn follow(client: &WorkerClient, name: &str, run_id: Uuid, print: bool) -> Result<()> {
+    let base = format!("/v1/repos/{name}/runs/{run_id}");
+    let mut state = WatchState::default();
+    // Position in the run log (`None`) and in each agent's log.
+    let mut cursors: BTreeMap<Option<String>, usize> = BTreeMap::from([(None, 0)]);
+    let mut answered: HashSet<String> = HashSet::new();
+    let mut cancelled = false;
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        // Checked before reading events so the last read sees everything.
+        let run: serde_json::Value = client.json("GET", &base, None).await?;
+        if let Some(error) = run.get("error").and_then(|e| e.as_str()) {
+            bail!("race failed on the worker: {error}");
+        }
+        for agent in run
+            .get("agents")
+            .and_then(|a| a.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(key) = agent.get("agent_key").and_then(|k| k.as_str()) {
+                cursors.entry(Some(key.to_string())).or_insert(0);
+            }
+        }
+        let settled = !matches!(
+            run.get("status").and_then(|s| s.as_str()),
+            Some("running" | "starting")
+        );
+
+        let mut events = Vec::new();
+        for (agent, since) in cursors.iter_mut() {
+            let query = match agent {
+                Some(agent) => format!("?since={since}&agent={agent}"),
+                None => format!("?since={since}"),
+            };
+            let page: EventPage = client
+                .json("GET", &format!("{base}/events{query}"), None)
+                .await?;
+            *since = page.next;
+            events.extend(page.events);
+        }
+        events.sort_by_key(|e| e.timestamp);
+
+        for event in &events {
+            if let Some(line) = state.apply(event) {
+                if print {
+                    eprintln!("{line}");
+                }
+            }
+            if event.kind == EventKind::ApprovalRequested && !settled {
+                answer_approval(client, &base, event, &mut answered).await?;
+            }
+        }
+        if settled {
+            return Ok(());
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = &mut ctrl_c, if !cancelled => {
+                cancelled = true;
+                eprintln!("Cancelling the race on the worker...");
+                client
+                    .request("POST", &format!("{base}/cancel"), None)
+                    .await?;
+            }
+        }
+    }
+}
+
+async fn answer_approval(
+    client: &WorkerClient,
+    base: &str,
+    event: &RunEvent,
+    answered: &mut HashSet<String>,
+) -> Result<()> {
+    let Some(approval_id) = event.data.get("approval_id").and_then(|v| v.as_str()) else {
+        return Ok(());
+    };
+    let Some(action) = event
+        .data
+        .get("action")
+        .and_then(|v| serde_json::from_value::<DangerousAction>(v.clone()).ok())
+    else {
+        return Ok(());
+    };
+    if !answered.insert(approval_id.to_string()) {
+        return Ok(());
+    }
+    let agent = event.agent_key.clone().unwrap_or_else(|| "-".to_string());
+    let decision =
+        tokio::task::spawn_blocking(move || crate::approval::ask_blocking(&agent, &action)).await?;
+    // A request the run already gave up on is gone; nothing to answer.
+    if let Err(e) = client
+        .request(
+            "POST",
+            &format!("{base}/approvals/{approval_id}"),
+            Some((
+                "application/json",
+                serde_json::json!({ "decision": decision })
+                    .to_string()
+                    .into_bytes(),
+            )),
+        )
+        .await
+    {
+        tracing::debug!(error = %e, "approval answer not delivered");
+    }
+    Ok(())
+}
+
+/// Unpack the worker's run directory into the local `.hydra/runs`.
+async fn import(
+    client: &WorkerClient,
+    name: &str,
+    run_id: Uuid,
+    repo_root: &Path,
+) -> Result<RunManifest> {
+    let archive = client
+        .request(
+            "GET",
+            &format!("/v1/repos/{name}/runs/{run_id}/archive"),
+            None,
+        )
+        .await?;
+    let hydra_root = repo_root.join(".hydra");
+    let runs_dir = hydra_root.join("runs");
+    std::fs::create_dir_all(&runs_dir)
+        .with_context(|| format!("failed to create {}", runs_dir.display()))?;
+    tokio::task::spawn_blocking({
+        let runs_dir = runs_dir.clone();
+        move || untar(&runs_dir, &archive)
+    })
+    .await??;
+
+    let layout = RunLayout::new(&hydra_root, run_id);
+    let mut manifest = RunManifest::read_from(&layout.manifest_path())
+        .context("worker archive has no run manifest")?;
+    // Worker paths mean nothing here; retained worktrees stay on the worker.
+    manifest.repo_root = repo_root.display().to_string();
+    for agent in &mut manifest.agents {
+        agent.worktree_path = None;
+    }
+    manifest.write_to(&layout.manifest_path())?;
+    Ok(manifest)
+}
+
+fn untar(dir: &Path, archive: &[u8]) -> Result<()> {
+    let mut child = Command::new("tar")
+        .arg("-C")
+        .arg(dir)
+        .args(["-xf", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run tar")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(archive)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "tar failed to unpack the run: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn parse_url(url: &str) -> Result<(String, u16)> {
+    if url.starts_with("https://") {
+        bail!("workers speak plain HTTP; reach a remote one through an SSH tunnel");
+    }
+    let authority = url
+        .strip_prefix("http://")
+        .unwrap_or(url)
+        .trim_end_matches('/');
+    if authority.is_empty() || authority.contains('/') {
+        bail!("invalid worker URL '{url}'; expected http://host[:port]");
+    }
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(rest) => {
+            let (host, rest) = rest
+                .split_once(']')
+                .with_context(|| format!("invalid worker URL '{url}'"))?;
+            (host, rest.strip_prefix(':'))
+        }
+        None => match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port
+            .parse()
+            .with_context(|| format!("invalid port in worker URL '{url}'"))?,
+        None => DEFAULT_PORT,
+    };
+    Ok((host.to_string(), port))
+}
+
+fn split_response(response: &[u8]) -> Option<(u16, &[u8])> {
+    let head_end = response.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&response[..head_end]).ok()?;
+    let status = head.split_whitespace().nth(1)?.parse().ok()?;
+    Some((status, &response[head_end + 4..]))
+}
+
+/// The worker-side name: the repository's directory name, made safe.
+fn repo_name(repo_root: &Path) -> String {
+    let name: String = repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches('.').to_string();
+    if valid_repo_name(&name) {
+        name
+    } else {
+        "repo".to_string()
+    }
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(12)]
+}
+
+async fn git_bytes(repo_root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let repo_root: PathBuf = repo_root.to_path_buf();
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new("git")
+            .arg("-C")
+            .arg(&repo_root)
+            .args(&args)
+            .output()
+    })
+    .await?
+    .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+async fn git_text(repo_root: &Path, args: &[&str]) -> Result<String> {
+    let stdout = git_bytes(repo_root, args).await?;
+    Ok(String::from_utf8_lossy(&stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url_takes_host_and_optional_port() {
+        assert_eq!(
+            parse_url("http://gpu-box:9000/").unwrap(),
+            ("gpu-box".to_string(), 9000)
+        );
+        assert_eq!(
+            parse_url("10.0.0.5").unwrap(),
+            ("10.0.0.5".to_string(), DEFAULT_PORT)
+        );
+        assert_eq!(
+            parse_url("http://[::1]:7000").unwrap(),
+            ("::1".to_string(), 7000)
+        );
+        assert!(parse_url("https://gpu-box").is_err());
+        assert!(parse_url("http://gpu-box/v1").is_err());
+        assert_eq!(repo_name(Path::new("/src/my repo")), "my-repo");
+        assert_eq!(repo_name(Path::new("/")), "repo");
+    }
+}
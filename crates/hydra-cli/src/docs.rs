@@ -0,0 +1,44 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Command;
+use clap_complete::Shell;
+
+/// Write a completion script for `shell` to `out`.
+pub fn write_completions(shell: Shell, cmd: &mut Command, out: &mut dyn Write) {
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, cmd, name, out);
+}
+
+/// Render the top-level man page to `out`.
+pub fn write_man_page(cmd: Command, out: &mut dyn Write) -> Result<()> {
+    clap_mangen::Man::new(cmd)
+        .render(out)
+        .context("failed to render man page")
+}
+
+/// Write `hydra.1` plus one `hydra-<subcommand>.1` page per subcommand into `dir`.
+pub fn write_man_pages(cmd: Command, dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create man page directory {}", dir.display()))?;
+
+    let mut written = Vec::new();
+    let root_name = cmd.get_name().to_string();
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        let page_name = format!("{root_name}-{}", sub.get_name());
+        let sub = sub.clone().name(page_name.clone());
+        written.push(render_to_file(sub, dir, &page_name)?);
+    }
+    written.insert(0, render_to_file(cmd, dir, &root_name)?);
+    Ok(written)
+}
+
+fn render_to_file(cmd: Command, dir: &Path, name: &str) -> Result<PathBuf> {
+    let path = dir.join(format!("{name}.1"));
+    let mut buf = Vec::new();
+    write_man_page(cmd, &mut buf)?;
+    std::fs::write(&path, buf)
+        .with_context(|| format!("failed to write man page {}", path.display()))?;
+    Ok(path)
+}
@@ -1,158 +1,1177 @@
-use hydra_core::adapter::ProbeReport;
+use hydra_core::adapter::claude::ClaudeAdapter;
+use hydra_core::adapter::codex::CodexAdapter;
+use hydra_core::adapter::cursor::CursorAdapter;
+use hydra_core::adapter::{
+    AdapterTier, AgentAdapter, AuthResult, AuthStatus, DetectStatus, ProbeReport, ProbeRunner,
+};
 use hydra_core::config::{AdaptersConfig, HydraConfig};
+use hydra_core::diagnostics::{check, remediation, Remediation, Severity};
+use hydra_core::scoring::baseline::resolve_commands;
+use hydra_core::scoring::toolchain::{probe_tool, required_tools};
+use hydra_core::tr;
 use serde::Serialize;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::output;
+
+/// Version of the `hydra doctor --json` layout. Bump it when a check or
+/// remediation ID is renamed or a field changes meaning.
+pub const SCHEMA_VERSION: u32 = 1;
 
 #[derive(Debug, Serialize)]
 pub struct DoctorReport {
+    pub schema_version: u32,
+    /// No check of `error` severity failed.
+    pub healthy: bool,
+    pub checks: Vec<DoctorCheck>,
+    /// Raw probe output behind the `adapter.*` checks.
     pub adapters: ProbeReport,
-    pub git: GitChecks,
-    pub all_tier1_ready: bool,
-    pub git_ok: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<DoctorFix>,
 }
 
 impl DoctorReport {
-    pub fn new(adapters: ProbeReport, git: GitChecks) -> Self {
-        let all_tier1_ready = adapters.all_tier1_ready;
-        let git_ok = git.is_repo && git.has_commits;
+    pub fn new(adapters: ProbeReport, checks: Vec<DoctorCheck>) -> Self {
+        let healthy = !checks.iter().any(DoctorCheck::is_blocking);
         Self {
+            schema_version: SCHEMA_VERSION,
+            healthy,
+            checks,
             adapters,
-            git,
-            all_tier1_ready,
-            git_ok,
+            fixes: Vec::new(),
         }
     }
 
     pub fn healthy(&self) -> bool {
-        self.all_tier1_ready && self.git_ok
+        self.healthy
+    }
+}
+
+/// One health check, identified by a stable ID from
+/// [`hydra_core::diagnostics::check`].
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub id: String,
+    /// How much a failure matters; only `error` failures are unhealthy.
+    pub severity: Severity,
+    pub status: CheckStatus,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<Remediation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// The check could not be run or does not apply.
+    Skip,
+}
+
+impl DoctorCheck {
+    fn new(
+        id: impl Into<String>,
+        severity: Severity,
+        status: CheckStatus,
+        summary: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            severity,
+            status,
+            summary: summary.into(),
+            evidence: None,
+            remediation: None,
+        }
+    }
+
+    fn pass(id: impl Into<String>, severity: Severity, summary: impl Into<String>) -> Self {
+        Self::new(id, severity, CheckStatus::Pass, summary)
+    }
+
+    fn fail(
+        id: impl Into<String>,
+        severity: Severity,
+        summary: impl Into<String>,
+        remediation: Remediation,
+    ) -> Self {
+        Self {
+            remediation: Some(remediation),
+            ..Self::new(id, severity, CheckStatus::Fail, summary)
+        }
+    }
+
+    fn skip(id: impl Into<String>, summary: impl Into<String>) -> Self {
+        Self::new(id, Severity::Info, CheckStatus::Skip, summary)
+    }
+
+    fn with_evidence(mut self, evidence: Option<String>) -> Self {
+        self.evidence = evidence;
+        self
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        self.status == CheckStatus::Fail && self.severity == Severity::Error
+    }
+
+    fn label(&self) -> &'static str {
+        match (self.status, self.severity) {
+            (CheckStatus::Pass, _) => "ok",
+            (CheckStatus::Skip, _) => "skip",
+            (CheckStatus::Fail, Severity::Error) => "FAIL",
+            (CheckStatus::Fail, Severity::Warning) => "warn",
+            (CheckStatus::Fail, Severity::Info) => "info",
+        }
+    }
+}
+
+/// Run every doctor check against the current repo, applying safe fixes when
+/// `fix` is set.
+pub fn build_report(fix: bool) -> DoctorReport {
+    let repo_root = crate::run::discover_repo_root().ok();
+    report_for(
+        repo_root.as_deref(),
+        &crate::config::path(),
+        &load_config(),
+        fix,
+    )
+}
+
+pub fn report_for(
+    repo_root: Option<&Path>,
+    config_path: &Path,
+    config: &HydraConfig,
+    fix: bool,
+) -> DoctorReport {
+    let probe = probe_adapters(config.adapters.clone());
+    let mut checks = git_checks(repo_root);
+    checks.extend(adapter_checks(config.adapters.clone(), &probe));
+    checks.extend(environment_checks(repo_root, config_path, config));
+    checks.extend(scoring_checks(repo_root, config));
+
+    let mut report = DoctorReport::new(probe, checks);
+    let fixes = plan_fixes(repo_root, config_path, &report, fix);
+    if fixes.iter().any(|f| f.status == FixStatus::Applied) {
+        // Report the state after the fixes, not before.
+        let mut checks = git_checks(repo_root);
+        checks.extend(adapter_checks(config.adapters.clone(), &report.adapters));
+        checks.extend(environment_checks(repo_root, config_path, config));
+        checks.extend(scoring_checks(repo_root, config));
+        report = DoctorReport::new(report.adapters, checks);
+    }
+    report.fixes = fixes;
+    report
+}
+
+/// How often `--watch` looks for changes that warrant an early re-run.
+const WATCH_POLL: Duration = Duration::from_secs(1);
+
+/// `hydra doctor --watch`: print the full report once, then re-run the checks
+/// every `interval` (or as soon as PATH directories or hydra.toml change) and
+/// print only what changed. Runs until interrupted.
+pub fn watch(interval: Duration, json: bool) -> anyhow::Result<()> {
+    let mut report = build_report(false);
+    output::emit(&report, false)?;
+    if json {
+        println!("{}", serde_json::to_string(&report)?);
+    } else {
+        print_human_report(&report);
+        println!();
+        println!(
+            "Watching for changes every {}s (Ctrl-C to stop)...",
+            interval.as_secs()
+        );
+    }
+
+    let mut summary = summarize(&report);
+    let mut fingerprint = watch_fingerprint();
+    let mut last_run = Instant::now();
+    loop {
+        std::thread::sleep(WATCH_POLL);
+        let current = watch_fingerprint();
+        if current == fingerprint && last_run.elapsed() < interval {
+            continue;
+        }
+        fingerprint = current;
+        last_run = Instant::now();
+
+        report = build_report(false);
+        let next = summarize(&report);
+        let changes = summary_changes(&summary, &next);
+        summary = next;
+        if changes.is_empty() {
+            continue;
+        }
+
+        output::emit(&report, false)?;
+        if json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            let stamp = chrono::Utc::now().format("%H:%M:%SZ");
+            for change in changes {
+                println!("[{stamp}] {change}");
+            }
+        }
+    }
+}
+
+/// Flatten a report into `check id -> outcome`. Summaries are left out so
+/// volatile details such as free-space figures do not count as changes.
+fn summarize(report: &DoctorReport) -> BTreeMap<String, String> {
+    let mut summary: BTreeMap<String, String> = report
+        .checks
+        .iter()
+        .map(|c| (c.id.clone(), c.label().to_string()))
+        .collect();
+    for fix in &report.fixes {
+        summary.insert(format!("fix {}", fix.id), fix.status.label().to_string());
+    }
+    summary.insert(
+        "overall".to_string(),
+        if report.healthy() {
+            "HEALTHY"
+        } else {
+            "UNHEALTHY"
+        }
+        .to_string(),
+    );
+    summary
+}
+
+fn summary_changes(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (label, state) in after {
+        match before.get(label) {
+            Some(old) if old == state => {}
+            Some(old) => changes.push(format!("{label}: {old} -> {state}")),
+            None => changes.push(format!("{label}: {state}")),
+        }
+    }
+    for label in before.keys().filter(|label| !after.contains_key(*label)) {
+        changes.push(format!("{label}: resolved"));
+    }
+    changes
+}
+
+/// Modification times of the places an adapter install or config edit
+/// shows up: every PATH directory, hydra.toml and the repo's .gitignore.
+fn watch_fingerprint() -> Vec<Option<SystemTime>> {
+    let mut paths: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    paths.push(crate::config::path());
+    if let Ok(repo_root) = crate::run::discover_repo_root() {
+        paths.push(repo_root.join(".gitignore"));
     }
+    paths
+        .iter()
+        .map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+        .collect()
 }
 
-pub fn load_adapter_config() -> AdaptersConfig {
-    load_adapter_config_from(Path::new("hydra.toml"))
+/// Load hydra.toml for doctor, falling back to defaults so a broken config
+/// never hides the rest of the report.
+pub fn load_config() -> HydraConfig {
+    load_config_from(&crate::config::path())
 }
 
-fn load_adapter_config_from(path: &Path) -> AdaptersConfig {
+fn load_config_from(path: &Path) -> HydraConfig {
     match hydra_core::config::load_config(path) {
-        Ok(config) => config.adapters,
+        Ok(config) => config,
         Err(err) => {
             tracing::warn!(
                 config = %path.display(),
                 error = %err,
-                "failed to load hydra.toml, using default adapter paths"
+                "failed to load hydra.toml, using defaults"
             );
-            HydraConfig::default().adapters
+            HydraConfig::default()
         }
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct GitChecks {
-    pub is_repo: bool,
-    pub has_commits: bool,
-    pub current_branch: Option<String>,
-    pub clean_working_tree: bool,
-    pub error: Option<String>,
+fn adapters(config: AdaptersConfig) -> Vec<Box<dyn AgentAdapter>> {
+    vec![
+        Box::new(ClaudeAdapter::new(config.claude)),
+        Box::new(CodexAdapter::new(config.codex)),
+        Box::new(CursorAdapter::new(config.cursor)),
+    ]
+}
+
+/// Probe every known adapter using the configured binary paths.
+pub fn probe_adapters(config: AdaptersConfig) -> ProbeReport {
+    ProbeRunner::new(adapters(config)).run()
 }
 
-pub fn check_git_repo() -> GitChecks {
-    let is_repo = Command::new("git")
-        .args(["rev-parse", "--is-inside-work-tree"])
+/// Repository checks. Everything else hydra does assumes a repo with at
+/// least one commit.
+pub fn git_checks(repo_root: Option<&Path>) -> Vec<DoctorCheck> {
+    let Some(repo_root) = repo_root else {
+        return vec![DoctorCheck::fail(
+            check::GIT_REPO,
+            Severity::Error,
+            tr!("doctor.not_a_repo"),
+            Remediation::manual(remediation::INIT_GIT_REPO, tr!("doctor.not_a_repo_fix"))
+                .with_command("git init"),
+        )];
+    };
+
+    let branch = git_stdout(repo_root, &["branch", "--show-current"]);
+    let mut checks = vec![DoctorCheck::pass(
+        check::GIT_REPO,
+        Severity::Error,
+        format!("git repository at {}", repo_root.display()),
+    )
+    .with_evidence(Some(match branch {
+        Some(branch) => format!("on branch {branch}"),
+        None => "detached HEAD".to_string(),
+    }))];
+
+    checks.push(if git_stdout(repo_root, &["rev-parse", "HEAD"]).is_some() {
+        DoctorCheck::pass(
+            check::GIT_COMMITS,
+            Severity::Error,
+            "repository has commits",
+        )
+    } else {
+        DoctorCheck::fail(
+            check::GIT_COMMITS,
+            Severity::Error,
+            tr!("doctor.no_commits"),
+            Remediation::manual(
+                remediation::CREATE_INITIAL_COMMIT,
+                tr!("doctor.no_commits_fix"),
+            )
+            .with_command("git commit --allow-empty -m \"initial commit\""),
+        )
+    });
+
+    let clean = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(repo_root)
         .output()
-        .map(|o| o.status.success())
+        .map(|o| o.status.success() && o.stdout.is_empty())
         .unwrap_or(false);
+    checks.push(if clean {
+        DoctorCheck::pass(
+            check::GIT_CLEAN_TREE,
+            Severity::Info,
+            "working tree is clean",
+        )
+    } else {
+        DoctorCheck::fail(
+            check::GIT_CLEAN_TREE,
+            Severity::Info,
+            tr!("doctor.dirty_tree"),
+            Remediation::manual(remediation::COMMIT_OR_STASH, tr!("doctor.dirty_tree_fix")),
+        )
+    });
+
+    checks
+}
 
-    if !is_repo {
-        return GitChecks {
-            is_repo: false,
-            has_commits: false,
-            current_branch: None,
-            clean_working_tree: false,
-            error: Some("not inside a git repository".to_string()),
+/// Binary and credential checks per adapter, with the login status kept
+/// apart from "binary found". Tier-1 failures are errors; experimental
+/// adapters only produce informational findings.
+pub fn adapter_checks(config: AdaptersConfig, probe: &ProbeReport) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+    for adapter in adapters(config) {
+        let key = adapter.key();
+        let Some(result) = probe.results.iter().find(|r| r.adapter_key == key) else {
+            continue;
         };
+        let severity = match result.tier {
+            AdapterTier::Tier1 => Severity::Error,
+            AdapterTier::Experimental => Severity::Info,
+        };
+        let detect = &result.detect;
+        let id = check::adapter_binary(key);
+        let summary = tr!(
+            "doctor.adapter_status",
+            adapter = key,
+            status = detect.status_label()
+        );
+
+        if !detect.status.is_available() {
+            let fix = if detect.status == DetectStatus::Missing {
+                let fix = Remediation::manual(
+                    remediation::INSTALL_ADAPTER,
+                    tr!("doctor.install_adapter_fix", adapter = key),
+                );
+                match install_hint(key) {
+                    Some(command) => fix.with_command(command),
+                    None => fix,
+                }
+            } else {
+                Remediation::manual(
+                    remediation::UPGRADE_ADAPTER,
+                    tr!("doctor.upgrade_adapter_fix", adapter = key),
+                )
+            };
+            checks.push(
+                DoctorCheck::fail(id, severity, summary, fix).with_evidence(detect.error.clone()),
+            );
+            continue;
+        }
+
+        let evidence = detect
+            .binary_path
+            .as_ref()
+            .map(|path| match &detect.version {
+                Some(version) => format!("{} ({version})", path.display()),
+                None => path.display().to_string(),
+            });
+        checks.push(DoctorCheck::pass(id, severity, summary).with_evidence(evidence));
+        checks.push(auth_check(key, severity, adapter.auth()));
     }
+    checks
+}
 
-    let has_commits = Command::new("git")
-        .args(["rev-parse", "HEAD"])
+fn auth_check(key: &str, severity: Severity, auth: AuthResult) -> DoctorCheck {
+    let id = check::adapter_auth(key);
+    match auth.status {
+        AuthStatus::Authenticated => {
+            DoctorCheck::pass(id, severity, format!("{key} is authenticated"))
+                .with_evidence(auth.source)
+        }
+        AuthStatus::Missing | AuthStatus::Expired => {
+            let description = auth
+                .remediation
+                .unwrap_or_else(|| tr!("doctor.login_fix", adapter = key));
+            DoctorCheck::fail(
+                id,
+                severity,
+                tr!(
+                    "doctor.adapter_status",
+                    adapter = key,
+                    status = auth.status.label()
+                ),
+                Remediation::manual(remediation::LOGIN_ADAPTER, description),
+            )
+            .with_evidence(auth.detail)
+        }
+        AuthStatus::Unknown => DoctorCheck::skip(id, tr!("doctor.login_unknown", adapter = key))
+            .with_evidence(auth.detail),
+    }
+}
+
+/// Below this much free space in the worktree base dir a race is likely to
+/// fail part-way through checking out or building candidates.
+const DISK_FAIL_BYTES: u64 = 1024 * 1024 * 1024;
+const DISK_WARN_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Git lock files untouched for this long belong to a crashed process.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Config, disk, git and `.hydra` checks. Those that need a repository are
+/// left out outside one; `git_checks` already reports that.
+pub fn environment_checks(
+    repo_root: Option<&Path>,
+    config_path: &Path,
+    config: &HydraConfig,
+) -> Vec<DoctorCheck> {
+    let mut checks = vec![check_config_file(config_path)];
+    let Some(repo_root) = repo_root else {
+        return checks;
+    };
+
+    checks.push(check_gitignore(repo_root));
+    checks.push(check_git_worktree(repo_root));
+    checks.push(check_prunable_worktrees(repo_root));
+    checks.push(check_disk_space(&repo_root.join(&config.worktree.base_dir)));
+    if cfg!(windows) {
+        checks.push(check_long_paths(repo_root));
+    }
+    checks.push(check_stale_locks(repo_root));
+    checks.push(check_protected_branch(repo_root));
+    checks
+}
+
+/// Resolve the scoring commands and check that their programs are installed.
+/// A missing command or tool makes that dimension score zero for every
+/// candidate, which is easy to misread as the agents doing badly.
+pub fn scoring_checks(repo_root: Option<&Path>, config: &HydraConfig) -> Vec<DoctorCheck> {
+    let Some(repo_root) = repo_root else {
+        return Vec::new();
+    };
+
+    let commands = resolve_commands(&config.scoring);
+    let dimensions = [
+        ("build", commands.build),
+        ("test", commands.test),
+        ("lint", commands.lint),
+    ];
+    let unresolved: Vec<&str> = dimensions
+        .iter()
+        .filter(|(_, command)| command.is_none())
+        .map(|(name, _)| *name)
+        .collect();
+
+    let source = match config.scoring.profile {
+        Some(profile) => format!("profile '{}'", crate::setup::profile_name(profile)),
+        None => "no profile".to_string(),
+    };
+    let mut checks = vec![if unresolved.len() == dimensions.len() {
+        let fix = Remediation::manual(
+            remediation::SET_SCORING_COMMANDS,
+            "set scoring.profile or scoring.commands in hydra.toml",
+        );
+        let fix = match crate::setup::detect_profile(repo_root) {
+            Some(profile) => fix.with_command(format!(
+                "scoring.profile = \"{}\"",
+                crate::setup::profile_name(profile)
+            )),
+            None => fix,
+        };
+        DoctorCheck::fail(
+            check::SCORING_COMMANDS,
+            Severity::Warning,
+            format!("{source} and no build, test or lint command; every candidate will score 0"),
+            fix,
+        )
+    } else if unresolved.is_empty() {
+        DoctorCheck::pass(
+            check::SCORING_COMMANDS,
+            Severity::Warning,
+            format!("{source}; build, test and lint commands resolved"),
+        )
+    } else {
+        DoctorCheck::pass(
+            check::SCORING_COMMANDS,
+            Severity::Warning,
+            format!("{source}; no {} command", unresolved.join(" or ")),
+        )
+    }];
+
+    for (dimension, command) in &dimensions {
+        let Some(command) = command else {
+            continue;
+        };
+        checks.push(check_scoring_command(repo_root, dimension, command));
+    }
+    checks
+}
+
+fn check_scoring_command(repo_root: &Path, dimension: &str, command: &str) -> DoctorCheck {
+    let id = check::scoring_dimension(dimension);
+    let tools: Vec<_> = required_tools(command)
+        .iter()
+        .map(|tool| probe_tool(tool, repo_root))
+        .collect();
+    let versions: Vec<String> = tools
+        .iter()
+        .filter(|t| t.found())
+        .map(|t| {
+            format!(
+                "{} {}",
+                t.name,
+                t.version.as_deref().unwrap_or("(unknown version)")
+            )
+        })
+        .collect();
+    let evidence = (!versions.is_empty()).then(|| versions.join(", "));
+    let missing: Vec<&str> = tools
+        .iter()
+        .filter(|t| !t.found())
+        .map(|t| t.name.as_str())
+        .collect();
+
+    if missing.is_empty() {
+        let summary = if versions.is_empty() {
+            format!("{dimension}: `{command}`")
+        } else {
+            format!("{dimension}: `{command}` ({})", versions.join(", "))
+        };
+        return DoctorCheck::pass(&id, Severity::Warning, summary);
+    }
+    DoctorCheck::fail(
+        &id,
+        Severity::Warning,
+        format!(
+            "{dimension}: `{command}` needs {} (not on PATH)",
+            missing.join(", ")
+        ),
+        Remediation::manual(
+            remediation::INSTALL_TOOL,
+            format!(
+                "install {} or change scoring.commands.{dimension}",
+                missing.join(", ")
+            ),
+        ),
+    )
+    .with_evidence(evidence)
+}
+
+fn check_config_file(config_path: &Path) -> DoctorCheck {
+    if config_path.exists() {
+        DoctorCheck::pass(
+            check::CONFIG_FILE,
+            Severity::Warning,
+            format!("using {}", config_path.display()),
+        )
+    } else {
+        DoctorCheck::fail(
+            check::CONFIG_FILE,
+            Severity::Warning,
+            format!("{} not found; using defaults", config_path.display()),
+            Remediation::automatic(
+                remediation::CREATE_CONFIG,
+                format!("create default {}", config_path.display()),
+            ),
+        )
+    }
+}
+
+fn check_gitignore(repo_root: &Path) -> DoctorCheck {
+    if gitignore_covers_hydra(&repo_root.join(".gitignore")) {
+        DoctorCheck::pass(
+            check::HYDRA_GITIGNORE,
+            Severity::Warning,
+            ".hydra/ is ignored",
+        )
+    } else {
+        DoctorCheck::fail(
+            check::HYDRA_GITIGNORE,
+            Severity::Warning,
+            ".hydra/ is not in .gitignore",
+            Remediation::automatic(remediation::IGNORE_HYDRA_DIR, "add .hydra/ to .gitignore"),
+        )
+    }
+}
+
+fn check_git_worktree(repo_root: &Path) -> DoctorCheck {
+    let output = Command::new("git")
+        .args(["worktree", "list"])
+        .current_dir(repo_root)
+        .output();
+    let upgrade = || {
+        Remediation::manual(
+            remediation::UPGRADE_GIT,
+            "install git 2.5 or newer; hydra runs every agent in its own worktree",
+        )
+    };
+    match output {
+        Ok(o) if o.status.success() => DoctorCheck::pass(
+            check::GIT_WORKTREE,
+            Severity::Error,
+            "git worktree available",
+        ),
+        Ok(o) => DoctorCheck::fail(
+            check::GIT_WORKTREE,
+            Severity::Error,
+            "git worktree unavailable",
+            upgrade(),
+        )
+        .with_evidence(Some(String::from_utf8_lossy(&o.stderr).trim().to_string())),
+        Err(e) => DoctorCheck::fail(
+            check::GIT_WORKTREE,
+            Severity::Error,
+            "cannot run git",
+            upgrade(),
+        )
+        .with_evidence(Some(e.to_string())),
+    }
+}
+
+fn check_prunable_worktrees(repo_root: &Path) -> DoctorCheck {
+    let prunable = prunable_worktrees(repo_root);
+    if prunable.is_empty() {
+        return DoctorCheck::pass(
+            check::GIT_PRUNABLE_WORKTREES,
+            Severity::Warning,
+            "no stale worktree entries",
+        );
+    }
+    let entries = if prunable.len() == 1 {
+        "entry"
+    } else {
+        "entries"
+    };
+    DoctorCheck::fail(
+        check::GIT_PRUNABLE_WORKTREES,
+        Severity::Warning,
+        format!("{} stale worktree {entries}", prunable.len()),
+        Remediation::automatic(
+            remediation::PRUNE_WORKTREES,
+            format!("prune {} stale worktree {entries}", prunable.len()),
+        )
+        .with_command("git worktree prune"),
+    )
+    .with_evidence(Some(prunable.join(", ")))
+}
+
+fn check_disk_space(base_dir: &Path) -> DoctorCheck {
+    // The base dir is created on the first race; measure the volume it will
+    // live on.
+    let probe = base_dir
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(base_dir);
+    let Some(free) = free_disk_bytes(probe) else {
+        return DoctorCheck::skip(
+            check::DISK_SPACE,
+            format!("could not determine free space for {}", base_dir.display()),
+        );
+    };
+
+    let summary = format!("{} free for {}", format_bytes(free), base_dir.display());
+    let fix = || {
+        Remediation::manual(
+            remediation::FREE_DISK_SPACE,
+            "free up space or point worktree.base_dir in hydra.toml at a larger volume",
+        )
+    };
+    if free < DISK_FAIL_BYTES {
+        DoctorCheck::fail(check::DISK_SPACE, Severity::Error, summary, fix())
+    } else if free < DISK_WARN_BYTES {
+        DoctorCheck::fail(check::DISK_SPACE, Severity::Warning, summary, fix())
+    } else {
+        DoctorCheck::pass(check::DISK_SPACE, Severity::Error, summary)
+    }
+}
+
+#[cfg(unix)]
+fn free_disk_bytes(path: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // POSIX format: header, then "fs blocks used available capacity mount".
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let available_kb: u64 = stdout
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()?;
+    Some(available_kb * 1024)
+}
+
+#[cfg(not(unix))]
+fn free_disk_bytes(path: &Path) -> Option<u64> {
+    let output = Command::new("fsutil")
+        .args(["volume", "diskfree"])
+        .arg(path)
         .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // "Total free bytes        : 123,456 (...)" on the first line.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = stdout.lines().next()?.split(':').nth(1)?;
+    let digits: String = value
+        .split_whitespace()
+        .next()?
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MIB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= GIB {
+        format!("{:.1} GiB", bytes as f64 / GIB)
+    } else {
+        format!("{:.0} MiB", bytes as f64 / MIB)
+    }
+}
+
+/// Worktree paths nest the repo path under the base dir, which overflows
+/// Windows' 260-character limit unless git is told to use long paths.
+fn check_long_paths(repo_root: &Path) -> DoctorCheck {
+    let enabled = git_stdout(repo_root, &["config", "--bool", "core.longpaths"])
+        .is_some_and(|value| value == "true");
+    if enabled {
+        DoctorCheck::pass(
+            check::GIT_LONG_PATHS,
+            Severity::Warning,
+            "core.longpaths enabled",
+        )
+    } else {
+        DoctorCheck::fail(
+            check::GIT_LONG_PATHS,
+            Severity::Warning,
+            "core.longpaths is not enabled",
+            Remediation::manual(
+                remediation::ENABLE_LONG_PATHS,
+                "enable long paths in git and LongPathsEnabled in the Windows registry",
+            )
+            .with_command("git config --global core.longpaths true"),
+        )
+    }
+}
 
-    let current_branch = Command::new("git")
-        .args(["branch", "--show-current"])
+fn check_stale_locks(repo_root: &Path) -> DoctorCheck {
+    let locks = stale_locks(repo_root);
+    if locks.is_empty() {
+        return DoctorCheck::pass(
+            check::HYDRA_STALE_LOCKS,
+            Severity::Error,
+            "no stale lock files",
+        );
+    }
+    let listed: Vec<String> = locks.iter().map(|p| p.display().to_string()).collect();
+    DoctorCheck::fail(
+        check::HYDRA_STALE_LOCKS,
+        Severity::Error,
+        format!("{} stale lock file(s)", locks.len()),
+        Remediation::automatic(
+            remediation::REMOVE_STALE_LOCKS,
+            format!(
+                "remove {} stale lock file(s) once no git or hydra process is running",
+                locks.len()
+            ),
+        ),
+    )
+    .with_evidence(Some(listed.join(", ")))
+}
+
+/// Lock files left behind by crashed processes: old git `index.lock` files
+/// in the repo and its worktrees, and attach sockets of interactive sessions
+/// whose host has gone away.
+fn stale_locks(repo_root: &Path) -> Vec<PathBuf> {
+    let mut locks = Vec::new();
+
+    if let Some(git_dir) = git_common_dir(repo_root) {
+        let mut candidates = vec![git_dir.join("index.lock")];
+        if let Ok(entries) = std::fs::read_dir(git_dir.join("worktrees")) {
+            candidates.extend(entries.flatten().map(|e| e.path().join("index.lock")));
+        }
+        locks.extend(candidates.into_iter().filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= STALE_LOCK_AGE)
+        }));
+    }
+
+    if let Ok(entries) = std::fs::read_dir(repo_root.join(".hydra").join("sessions")) {
+        locks.extend(
+            entries
+                .flatten()
+                .map(|e| e.path().join("attach.sock"))
+                .filter(|sock| is_dead_socket(sock)),
+        );
+    }
+
+    locks
+}
+
+#[cfg(unix)]
+fn is_dead_socket(path: &Path) -> bool {
+    path.exists()
+        && matches!(
+            std::os::unix::net::UnixStream::connect(path),
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused
+        )
+}
+
+#[cfg(not(unix))]
+fn is_dead_socket(_path: &Path) -> bool {
+    false
+}
+
+fn remove_stale_locks(locks: &[PathBuf]) -> std::io::Result<()> {
+    for lock in locks {
+        match std::fs::remove_file(lock) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn git_common_dir(repo_root: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-common-dir"])
+        .current_dir(repo_root)
         .output()
-        .ok()
-        .and_then(|o| {
-            if o.status.success() {
-                let branch = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                if branch.is_empty() {
-                    None
-                } else {
-                    Some(branch)
-                }
-            } else {
-                None
-            }
-        });
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Some(if dir.is_absolute() {
+        dir
+    } else {
+        repo_root.join(dir)
+    })
+}
 
-    let clean_working_tree = Command::new("git")
-        .args(["status", "--porcelain"])
+/// `hydra merge` lands on the checked-out branch, so merging while the
+/// remote's default branch (or main/master without a remote) is checked out
+/// bypasses whatever review that branch is protected by.
+fn check_protected_branch(repo_root: &Path) -> DoctorCheck {
+    let Some(current) = git_stdout(repo_root, &["branch", "--show-current"]) else {
+        return DoctorCheck::skip(
+            check::GIT_PROTECTED_BRANCH,
+            "detached HEAD; merges need a branch",
+        );
+    };
+
+    let protected: Vec<String> = match git_stdout(
+        repo_root,
+        &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+    ) {
+        Some(default) => vec![default
+            .strip_prefix("origin/")
+            .unwrap_or(&default)
+            .to_string()],
+        None => vec!["main".to_string(), "master".to_string()],
+    };
+
+    if protected.contains(&current) {
+        DoctorCheck::fail(
+            check::GIT_PROTECTED_BRANCH,
+            Severity::Warning,
+            format!("merges would land directly on protected branch '{current}'"),
+            Remediation::manual(
+                remediation::SWITCH_BRANCH,
+                "switch to a working branch before `hydra merge`",
+            )
+            .with_command("git switch -c <name>"),
+        )
+    } else {
+        DoctorCheck::pass(
+            check::GIT_PROTECTED_BRANCH,
+            Severity::Warning,
+            format!("merges land on '{current}', not a protected branch"),
+        )
+    }
+}
+
+fn git_stdout(repo_root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
         .output()
-        .map(|o| o.status.success() && o.stdout.is_empty())
-        .unwrap_or(false);
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !stdout.is_empty()).then_some(stdout)
+}
+
+/// Outcome of an automatic remediation. `id` is the remediation ID of the
+/// check it fixes.
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorFix {
+    pub id: String,
+    pub description: String,
+    pub status: FixStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
 
-    GitChecks {
-        is_repo,
-        has_commits,
-        current_branch,
-        clean_working_tree,
-        error: None,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixStatus {
+    /// Would be applied with `--fix`.
+    Pending,
+    Applied,
+    Failed,
+}
+
+impl FixStatus {
+    fn label(self) -> &'static str {
+        match self {
+            FixStatus::Pending => "fixable",
+            FixStatus::Applied => "fixed",
+            FixStatus::Failed => "FAILED",
+        }
     }
 }
 
-pub fn print_human_report(report: &DoctorReport) {
-    println!("Hydra Doctor Report");
-    println!("===================");
-    println!();
+/// Collect the automatic remediations of failed checks, applying them when
+/// `apply` is set. Manual remediations stay on their checks; `--fix` never
+/// installs software or touches user state.
+pub fn plan_fixes(
+    repo_root: Option<&Path>,
+    config_path: &Path,
+    report: &DoctorReport,
+    apply: bool,
+) -> Vec<DoctorFix> {
+    report
+        .checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .filter_map(|c| c.remediation.as_ref().filter(|r| r.automatic))
+        .filter_map(|r| {
+            let fix: Box<dyn FnOnce() -> std::io::Result<()>> = match (r.id.as_str(), repo_root) {
+                (remediation::CREATE_CONFIG, _) => Box::new(|| write_default_config(config_path)),
+                (remediation::IGNORE_HYDRA_DIR, Some(root)) => {
+                    Box::new(move || append_hydra_ignore(&root.join(".gitignore")))
+                }
+                (remediation::PRUNE_WORKTREES, Some(root)) => {
+                    Box::new(move || prune_worktrees(root))
+                }
+                (remediation::REMOVE_STALE_LOCKS, Some(root)) => {
+                    Box::new(move || remove_stale_locks(&stale_locks(root)))
+                }
+                _ => return None,
+            };
+            Some(run_fix(&r.id, r.description.clone(), apply, fix))
+        })
+        .collect()
+}
 
-    println!("Git Repository:");
-    if report.git.is_repo {
-        println!("  Status: OK");
-        if let Some(branch) = &report.git.current_branch {
-            println!("  Branch: {branch}");
+fn run_fix(
+    id: &str,
+    description: String,
+    apply: bool,
+    fix: impl FnOnce() -> std::io::Result<()>,
+) -> DoctorFix {
+    let id = id.to_string();
+    if !apply {
+        return DoctorFix {
+            id,
+            description,
+            status: FixStatus::Pending,
+            detail: None,
+        };
+    }
+
+    match fix() {
+        Ok(()) => DoctorFix {
+            id,
+            description,
+            status: FixStatus::Applied,
+            detail: None,
+        },
+        Err(e) => {
+            tracing::warn!(fix = %id, error = %e, "doctor fix failed");
+            DoctorFix {
+                id,
+                description,
+                status: FixStatus::Failed,
+                detail: Some(e.to_string()),
+            }
         }
-        println!(
-            "  Working tree: {}",
-            if report.git.clean_working_tree {
-                "clean"
-            } else {
-                "dirty"
+    }
+}
+
+fn write_default_config(path: &Path) -> std::io::Result<()> {
+    let rendered = hydra_core::config::render_config(&HydraConfig::default())
+        .map_err(std::io::Error::other)?;
+    std::fs::write(path, rendered)
+}
+
+fn gitignore_covers_hydra(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .any(|line| matches!(line, ".hydra" | ".hydra/" | "/.hydra" | "/.hydra/"))
+        })
+        .unwrap_or(false)
+}
+
+fn append_hydra_ignore(path: &Path) -> std::io::Result<()> {
+    let mut content = std::fs::read_to_string(path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(".hydra/\n");
+    std::fs::write(path, content)
+}
+
+/// Worktrees git reports as prunable (their directory no longer exists).
+fn prunable_worktrees(repo_root: &Path) -> Vec<String> {
+    let Ok(output) = Command::new("git")
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(repo_root)
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    let mut prunable = Vec::new();
+    let mut current: Option<String> = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            current = Some(path.to_string());
+        } else if line.starts_with("prunable") {
+            if let Some(path) = current.take() {
+                prunable.push(path);
             }
-        );
-        println!(
-            "  Has commits: {}",
-            if report.git.has_commits { "yes" } else { "no" }
-        );
+        }
+    }
+    prunable
+}
+
+fn prune_worktrees(repo_root: &Path) -> std::io::Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "prune"])
+        .current_dir(repo_root)
+        .output()?;
+    if output.status.success() {
+        Ok(())
     } else {
-        println!("  Status: NOT A GIT REPO");
-        if let Some(err) = &report.git.error {
-            println!("  Error: {err}");
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+fn install_hint(adapter_key: &str) -> Option<&'static str> {
+    match adapter_key {
+        "claude" => Some("npm install -g @anthropic-ai/claude-code"),
+        "codex" => Some("npm install -g @openai/codex"),
+        _ => None,
+    }
+}
+
+fn print_check(check: &DoctorCheck, indent: &str) {
+    println!("{indent}[{}] {}", check.label(), check.summary);
+    if check.status == CheckStatus::Pass {
+        return;
+    }
+    if let Some(evidence) = &check.evidence {
+        println!("{indent}    {evidence}");
+    }
+    if let Some(fix) = &check.remediation {
+        match &fix.command {
+            Some(command) => println!(
+                "{indent}    {}: {} (`{command}`)",
+                tr!("doctor.fix"),
+                fix.description
+            ),
+            None => println!("{indent}    {}: {}", tr!("doctor.fix"), fix.description),
         }
     }
+}
 
-    println!();
-    println!("Adapter Readiness:");
-    println!(
-        "  All Tier-1 ready: {}",
-        if report.all_tier1_ready { "yes" } else { "NO" }
-    );
+pub fn print_human_report(report: &DoctorReport) {
+    crate::race::title("doctor.title");
     println!();
 
+    println!("{}:", tr!("doctor.git"));
+    for check in report.checks.iter().filter(|c| c.id.starts_with("git.")) {
+        print_check(check, "  ");
+    }
+
+    println!();
+    println!("{}:", tr!("doctor.adapters"));
     for r in &report.adapters.results {
         let tier_label = match r.tier {
-            hydra_core::adapter::AdapterTier::Tier1 => "tier-1",
-            hydra_core::adapter::AdapterTier::Experimental => "experimental",
+            AdapterTier::Tier1 => "tier-1",
+            AdapterTier::Experimental => "experimental",
         };
         let status = r.detect.status_label();
         println!("  [{tier_label}] {key} ({status})", key = r.adapter_key);
@@ -165,21 +1184,51 @@ pub fn print_human_report(report: &DoctorReport) {
         if !r.detect.supported_flags.is_empty() {
             println!("    flags: {}", r.detect.supported_flags.join(", "));
         }
-        if let Some(err) = &r.detect.error {
-            println!("    error: {err}");
+        let prefix = format!("adapter.{}.", r.adapter_key);
+        for check in report.checks.iter().filter(|c| c.id.starts_with(&prefix)) {
+            print_check(check, "    ");
+        }
+    }
+
+    println!();
+    println!("{}:", tr!("doctor.scoring"));
+    for check in report
+        .checks
+        .iter()
+        .filter(|c| c.id.starts_with("scoring."))
+    {
+        print_check(check, "  ");
+    }
+
+    println!();
+    println!("{}:", tr!("doctor.environment"));
+    for check in report.checks.iter().filter(|c| {
+        !c.id.starts_with("git.") && !c.id.starts_with("adapter.") && !c.id.starts_with("scoring.")
+    }) {
+        print_check(check, "  ");
+    }
+
+    if !report.fixes.is_empty() {
+        println!();
+        println!("{}:", tr!("doctor.fixes"));
+        for fix in &report.fixes {
+            println!("  [{}] {}", fix.status.label(), fix.description);
+            if let Some(detail) = &fix.detail {
+                println!("    {detail}");
+            }
+        }
+        if report.fixes.iter().any(|f| f.status == FixStatus::Pending) {
+            println!("  {}", tr!("doctor.run_fix"));
         }
     }
 
     println!();
     if report.healthy() {
-        println!("Overall: HEALTHY");
+        println!("{}", tr!("doctor.healthy"));
     } else {
-        println!("Overall: UNHEALTHY");
-        if !report.all_tier1_ready {
-            println!("  - One or more Tier-1 adapters are not ready");
-        }
-        if !report.git_ok {
-            println!("  - Git repository checks failed");
+        println!("{}", tr!("doctor.unhealthy"));
+        for check in report.checks.iter().filter(|c| c.is_blocking()) {
+            println!("  - {}: {}", check.id, check.summary);
         }
     }
 }
@@ -204,7 +1253,7 @@ cursor = "/opt/cursor-agent"
         )
         .unwrap();
 
-        let adapters = load_adapter_config_from(f.path());
+        let adapters = load_config_from(f.path()).adapters;
         assert_eq!(adapters.claude.as_deref(), Some("/opt/claude"));
         assert_eq!(adapters.codex.as_deref(), Some("/opt/codex"));
         assert_eq!(adapters.cursor.as_deref(), Some("/opt/cursor-agent"));
@@ -212,18 +1261,314 @@ cursor = "/opt/cursor-agent"
 
     #[test]
     fn missing_config_file_returns_default_adapter_paths() {
-        let adapters = load_adapter_config_from(Path::new("/tmp/nonexistent-hydra-test.toml"));
+        let adapters = load_config_from(Path::new("/tmp/nonexistent-hydra-test.toml")).adapters;
         assert!(adapters.claude.is_none());
         assert!(adapters.codex.is_none());
         assert!(adapters.cursor.is_none());
     }
 
+    fn git_repo(dir: &Path) {
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        run(&["init", "-q"]);
+        run(&["config", "user.email", "test@example.com"]);
+        run(&["config", "user.name", "Test"]);
+        run(&["commit", "-q", "--allow-empty", "-m", "init"]);
+    }
+
+    fn empty_probe() -> ProbeReport {
+        ProbeReport {
+            timestamp: chrono::Utc::now(),
+            results: Vec::new(),
+            all_tier1_ready: true,
+        }
+    }
+
+    fn repo_report(repo: &Path, config_path: &Path) -> DoctorReport {
+        DoctorReport::new(
+            empty_probe(),
+            environment_checks(Some(repo), config_path, &HydraConfig::default()),
+        )
+    }
+
+    fn find<'a>(report: &'a DoctorReport, id: &str) -> &'a DoctorCheck {
+        report.checks.iter().find(|c| c.id == id).unwrap()
+    }
+
+    #[test]
+    fn plan_fixes_reports_without_applying() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        git_repo(tmp.path());
+        let config_path = tmp.path().join("hydra.toml");
+
+        let report = repo_report(tmp.path(), &config_path);
+        let fixes = plan_fixes(Some(tmp.path()), &config_path, &report, false);
+        let ids: Vec<&str> = fixes.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![remediation::CREATE_CONFIG, remediation::IGNORE_HYDRA_DIR]
+        );
+        assert!(fixes.iter().all(|f| f.status == FixStatus::Pending));
+        assert!(!config_path.exists());
+    }
+
+    #[test]
+    fn plan_fixes_applies_safe_fixes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        git_repo(tmp.path());
+        std::fs::write(tmp.path().join(".gitignore"), "target/").unwrap();
+        let config_path = tmp.path().join("hydra.toml");
+
+        let stale = tmp.path().join("stale-wt");
+        let status = Command::new("git")
+            .args(["worktree", "add", "-q", "-b", "stale"])
+            .arg(&stale)
+            .current_dir(tmp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::remove_dir_all(&stale).unwrap();
+        assert_eq!(prunable_worktrees(tmp.path()).len(), 1);
+
+        let report = repo_report(tmp.path(), &config_path);
+        let fixes = plan_fixes(Some(tmp.path()), &config_path, &report, true);
+        assert!(fixes.iter().all(|f| f.status == FixStatus::Applied));
+        assert_eq!(fixes.len(), 3);
+
+        hydra_core::config::load_config(&config_path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(".gitignore")).unwrap(),
+            "target/\n.hydra/\n"
+        );
+        assert!(prunable_worktrees(tmp.path()).is_empty());
+
+        let report = repo_report(tmp.path(), &config_path);
+        let again = plan_fixes(Some(tmp.path()), &config_path, &report, false);
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn environment_checks_flag_stale_locks_with_remediation() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        git_repo(tmp.path());
+        let config_path = tmp.path().join("hydra.toml");
+
+        let fresh = tmp.path().join(".git/index.lock");
+        std::fs::write(&fresh, "").unwrap();
+        let report = repo_report(tmp.path(), &config_path);
+        find(&report, check::GIT_WORKTREE);
+        find(&report, check::DISK_SPACE);
+        let locks = find(&report, check::HYDRA_STALE_LOCKS);
+        assert_eq!(
+            locks.status,
+            CheckStatus::Pass,
+            "a fresh lock may be in use"
+        );
+
+        let old = std::time::SystemTime::now() - STALE_LOCK_AGE * 2;
+        std::fs::File::options()
+            .write(true)
+            .open(&fresh)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        let report = repo_report(tmp.path(), &config_path);
+        let locks = find(&report, check::HYDRA_STALE_LOCKS);
+        assert!(locks.is_blocking());
+        let fix = locks.remediation.as_ref().unwrap();
+        assert_eq!(fix.id, remediation::REMOVE_STALE_LOCKS);
+        assert!(fix.automatic);
+        assert!(!report.healthy());
+
+        let fixes = plan_fixes(Some(tmp.path()), &config_path, &report, true);
+        assert!(fixes
+            .iter()
+            .any(|f| f.id == remediation::REMOVE_STALE_LOCKS && f.status == FixStatus::Applied));
+        assert!(!fresh.exists());
+    }
+
+    #[test]
+    fn protected_branch_check_warns_on_default_branch() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        git_repo(tmp.path());
+        let run = |args: &[&str]| {
+            assert!(Command::new("git")
+                .args(args)
+                .current_dir(tmp.path())
+                .status()
+                .unwrap()
+                .success());
+        };
+
+        run(&["switch", "-q", "-C", "main"]);
+        let check = check_protected_branch(tmp.path());
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(check.severity, Severity::Warning);
+        assert_eq!(
+            check.remediation.map(|r| r.id),
+            Some(remediation::SWITCH_BRANCH.to_string())
+        );
+
+        run(&["switch", "-q", "-c", "feature"]);
+        assert_eq!(check_protected_branch(tmp.path()).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn git_checks_outside_repo_fail_with_stable_ids() {
+        let checks = git_checks(None);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].id, check::GIT_REPO);
+        assert!(checks[0].is_blocking());
+        assert_eq!(
+            checks[0].remediation.as_ref().unwrap().id,
+            remediation::INIT_GIT_REPO
+        );
+    }
+
+    #[test]
+    fn auth_failures_block_only_tier1_adapters() {
+        let logged_out = AuthResult {
+            status: AuthStatus::Missing,
+            source: None,
+            detail: None,
+            remediation: Some("run `codex login`".to_string()),
+        };
+
+        let tier1 = auth_check("codex", Severity::Error, logged_out.clone());
+        assert_eq!(tier1.id, "adapter.codex.auth");
+        assert!(tier1.is_blocking());
+        let fix = tier1.remediation.clone().unwrap();
+        assert_eq!(fix.id, remediation::LOGIN_ADAPTER);
+        assert_eq!(fix.description, "run `codex login`");
+        assert!(!fix.automatic);
+        assert!(!DoctorReport::new(empty_probe(), vec![tier1]).healthy());
+
+        let experimental = auth_check("cursor-agent", Severity::Info, logged_out);
+        assert_eq!(experimental.status, CheckStatus::Fail);
+        assert!(DoctorReport::new(empty_probe(), vec![experimental]).healthy());
+
+        let unknown = auth_check("cursor-agent", Severity::Info, AuthResult::unknown("?"));
+        assert_eq!(unknown.status, CheckStatus::Skip);
+        let ok = auth_check("claude", Severity::Error, AuthResult::authenticated("KEY"));
+        assert_eq!(ok.status, CheckStatus::Pass);
+        assert_eq!(ok.evidence.as_deref(), Some("KEY"));
+    }
+
+    #[test]
+    fn scoring_checks_warn_when_nothing_resolves() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
+
+        let checks = scoring_checks(Some(tmp.path()), &HydraConfig::default());
+        assert_eq!(checks.len(), 1);
+        let commands = &checks[0];
+        assert_eq!(commands.id, check::SCORING_COMMANDS);
+        assert_eq!(commands.status, CheckStatus::Fail);
+        assert_eq!(commands.severity, Severity::Warning);
+        let fix = commands.remediation.as_ref().unwrap();
+        assert_eq!(fix.id, remediation::SET_SCORING_COMMANDS);
+        assert_eq!(fix.command.as_deref(), Some("scoring.profile = \"rust\""));
+
+        assert!(scoring_checks(None, &HydraConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn scoring_checks_flag_missing_tools() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = HydraConfig::default();
+        config.scoring.commands.test = Some("git --version".to_string());
+        config.scoring.commands.lint = Some("hydra-no-such-linter --check".to_string());
+
+        let checks = scoring_checks(Some(tmp.path()), &config);
+        let ids: Vec<&str> = checks.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![check::SCORING_COMMANDS, "scoring.test", "scoring.lint"]
+        );
+        assert_eq!(checks[0].status, CheckStatus::Pass);
+        assert!(checks[0].summary.contains("no build command"));
+
+        assert_eq!(checks[1].status, CheckStatus::Pass);
+        assert!(checks[1].summary.contains("git "), "{}", checks[1].summary);
+
+        let lint = &checks[2];
+        assert_eq!(lint.status, CheckStatus::Fail);
+        assert!(lint.summary.contains("hydra-no-such-linter"));
+        assert_eq!(
+            lint.remediation.as_ref().unwrap().id,
+            remediation::INSTALL_TOOL
+        );
+    }
+
+    #[test]
+    fn report_json_uses_stable_ids_and_severities() {
+        let report = DoctorReport::new(
+            empty_probe(),
+            vec![check_config_file(Path::new("/nonexistent/hydra.toml"))],
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["healthy"], true);
+        let check = &json["checks"][0];
+        assert_eq!(check["id"], "config.file");
+        assert_eq!(check["severity"], "warning");
+        assert_eq!(check["status"], "fail");
+        assert_eq!(check["remediation"]["id"], "create_config");
+        assert_eq!(check["remediation"]["automatic"], true);
+    }
+
+    #[test]
+    fn watch_reports_only_changed_outcomes() {
+        let disk = |summary: &str, status: CheckStatus| DoctorCheck {
+            status,
+            ..DoctorCheck::pass(check::DISK_SPACE, Severity::Error, summary)
+        };
+        let before = summarize(&DoctorReport::new(
+            empty_probe(),
+            vec![disk("9.0 GiB free", CheckStatus::Pass)],
+        ));
+
+        let same = DoctorReport::new(empty_probe(), vec![disk("8.5 GiB free", CheckStatus::Pass)]);
+        assert!(summary_changes(&before, &summarize(&same)).is_empty());
+
+        let mut failing =
+            DoctorReport::new(empty_probe(), vec![disk("512 MiB free", CheckStatus::Fail)]);
+        failing.fixes = vec![DoctorFix {
+            id: remediation::REMOVE_STALE_LOCKS.to_string(),
+            description: "remove 1 stale lock file(s)".to_string(),
+            status: FixStatus::Pending,
+            detail: None,
+        }];
+        let after = summarize(&failing);
+        assert_eq!(
+            summary_changes(&before, &after),
+            vec![
+                "disk.space: ok -> FAIL",
+                "fix remove_stale_locks: fixable",
+                "overall: HEALTHY -> UNHEALTHY",
+            ]
+        );
+
+        failing.fixes.clear();
+        assert_eq!(
+            summary_changes(&after, &summarize(&failing)),
+            vec!["fix remove_stale_locks: resolved"]
+        );
+    }
+
     #[test]
     fn malformed_config_file_returns_defaults() {
         let mut f = NamedTempFile::new().unwrap();
         write!(f, "[[[invalid toml structure").unwrap();
 
-        let adapters = load_adapter_config_from(f.path());
+        let adapters = load_config_from(f.path()).adapters;
         assert!(adapters.claude.is_none());
     }
 }
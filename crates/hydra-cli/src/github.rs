@@ -0,0 +1,392 @@
+//! Task prompts composed from GitHub issues and pull requests
+//! (`hydra race --from-issue` / `--from-pr`).
+//!
+//! Everything is fetched with the `gh` CLI, so its login and GitHub
+//! Enterprise host settings apply and no token is handled here.
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+use hydra_core::artifact::{PromptSource, PromptSourceKind};
+
+/// Used when `race.github_template` is unset.
+pub const DEFAULT_TEMPLATE: &str =
+    "{prompt}\n\nGitHub {kind} {repo}#{number}: {title}\n{url}\n\n{body}\n\n{comments}";
+
+/// An issue or pull request as given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// `owner/name`; the current repository's GitHub remote when `None`.
+    pub repo: Option<String>,
+    pub number: u64,
+}
+
+/// Parse `123`, `#123`, `owner/name#123` or a github.com issue/PR URL.
+pub fn parse_reference(raw: &str) -> Result<Reference, String> {
+    let raw = raw.trim();
+    let invalid = || format!("'{raw}' is not an issue number, owner/name#number or GitHub URL");
+
+    if let Some(rest) = raw
+        .strip_prefix("https://")
+        .or_else(|| raw.strip_prefix("http://"))
+    {
+        // host/owner/name/{issues,pull}/number[/...]
+        let parts: Vec<&str> = rest.split(['/', '#', '?']).collect();
+        let [_, owner, name, "issues" | "pull", number, ..] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        return Ok(Reference {
+            repo: Some(format!("{owner}/{name}")),
+            number: number.parse().map_err(|_| invalid())?,
+        });
+    }
+
+    let (repo, number) = match raw.rsplit_once('#') {
+        Some(("", number)) => (None, number),
+        Some((repo, number)) => {
+            if repo.split('/').count() != 2 || repo.split('/').any(str::is_empty) {
+                return Err(invalid());
+            }
+            (Some(repo.to_string()), number)
+        }
+        None => (None, raw),
+    };
+    match number.parse() {
+        Ok(number) if number > 0 => Ok(Reference { repo, number }),
+        _ => Err(invalid()),
+    }
+}
+
+/// The race prompt for `--prompt` plus an optional `--from-issue` or
+/// `--from-pr`, and the source to record in the manifest.
+pub fn resolve_prompt(
+    prompt: Option<String>,
+    issue: Option<Reference>,
+    pr: Option<Reference>,
+) -> Result<(String, Option<PromptSource>)> {
+    let (kind, reference) = match (issue, pr) {
+        (Some(reference), _) => (PromptSourceKind::Issue, reference),
+        (None, Some(reference)) => (PromptSourceKind::PullRequest, reference),
+        (None, None) => return Ok((prompt.unwrap_or_default(), None)),
+    };
+    let config = crate::config::load()?;
+    let thread = fetch(kind, &reference)?;
+    let template = config
+        .race
+        .github_template
+        .as_deref()
+        .unwrap_or(DEFAULT_TEMPLATE);
+    Ok((
+        thread.compose(template, prompt.as_deref()),
+        Some(thread.source()),
+    ))
+}
+
+/// An issue or pull request with its discussion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thread {
+    pub kind: PromptSourceKind,
+    pub repo: String,
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub author: String,
+    pub body: String,
+    /// `path:line` for review comments on a pull request's diff.
+    pub location: Option<String>,
+}
+
+impl Thread {
+    pub fn source(&self) -> PromptSource {
+        PromptSource {
+            kind: self.kind,
+            repo: self.repo.clone(),
+            number: self.number,
+            url: self.url.clone(),
+        }
+    }
+
+    /// Fill in `template`. `prompt` is the `--prompt` text given alongside
+    /// the reference, if any.
+    pub fn compose(&self, template: &str, prompt: Option<&str>) -> String {
+        let kind = match self.kind {
+            PromptSourceKind::Issue => "issue",
+            PromptSourceKind::PullRequest => "pull request",
+        };
+        let number = self.number.to_string();
+        let comments = self.render_comments();
+        let values = [
+            ("kind", kind),
+            ("repo", self.repo.as_str()),
+            ("number", number.as_str()),
+            ("url", self.url.as_str()),
+            ("prompt", prompt.unwrap_or_default().trim()),
+            ("title", self.title.as_str()),
+            ("body", self.body.trim()),
+            ("comments", comments.as_str()),
+        ];
+        let composed = fill_template(template, &values);
+        collapse_blank_lines(&composed)
+    }
+
+    fn render_comments(&self) -> String {
+        if self.comments.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("Comments:");
+        for comment in &self.comments {
+            out.push_str("\n\n@");
+            out.push_str(&comment.author);
+            if let Some(location) = &comment.location {
+                out.push_str(" on ");
+                out.push_str(location);
+            }
+            out.push_str(":\n");
+            out.push_str(comment.body.trim());
+        }
+        out
+    }
+}
+
+/// Fetch an issue or pull request with its comments and, for pull
+/// requests, its reviews and review comments.
+pub fn fetch(kind: PromptSourceKind, reference: &Reference) -> Result<Thread> {
+    let repo = match &reference.repo {
+        Some(repo) => repo.clone(),
+        None => current_repo()?,
+    };
+    let number = reference.number;
+
+    let item = match kind {
+        PromptSourceKind::Issue => gh_api(&format!("repos/{repo}/issues/{number}"))?,
+        PromptSourceKind::PullRequest => gh_api(&format!("repos/{repo}/pulls/{number}"))?,
+    };
+    let mut comments: Vec<Comment> =
+        gh_api_list(&format!("repos/{repo}/issues/{number}/comments"))?
+            .iter()
+            .filter_map(|c| comment(c, None))
+            .collect();
+    if kind == PromptSourceKind::PullRequest {
+        for review in gh_api_list(&format!("repos/{repo}/pulls/{number}/reviews"))? {
+            let state = str_field(&review, "state").to_lowercase().replace('_', " ");
+            comments.extend(comment(&review, None).map(|mut c| {
+                c.author = format!("{} ({state})", c.author);
+                c
+            }));
+        }
+        for review_comment in gh_api_list(&format!("repos/{repo}/pulls/{number}/comments"))? {
+            let line = review_comment
+                .get("line")
+                .or_else(|| review_comment.get("original_line"))
+                .and_then(Value::as_u64);
+            let path = str_field(&review_comment, "path");
+            let location = match line {
+                Some(line) => format!("{path}:{line}"),
+                None => path.to_string(),
+            };
+            comments.extend(comment(&review_comment, Some(location)));
+        }
+    }
+
+    Ok(Thread {
+        kind,
+        repo,
+        number,
+        title: str_field(&item, "title").to_string(),
+        body: str_field(&item, "body").to_string(),
+        url: str_field(&item, "html_url").to_string(),
+        comments,
+    })
+}
+
+fn comment(value: &Value, location: Option<String>) -> Option<Comment> {
+    let body = str_field(value, "body");
+    if body.trim().is_empty() {
+        return None;
+    }
+    Some(Comment {
+        author: value
+            .pointer("/user/login")
+            .and_then(Value::as_str)
+            .unwrap_or("ghost")
+            .to_string(),
+        body: body.to_string(),
+        location,
+    })
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn current_repo() -> Result<String> {
+    let output = gh(&[
+        "repo",
+        "view",
+        "--json",
+        "nameWithOwner",
+        "--jq",
+        ".nameWithOwner",
+    ])?;
+    let repo = String::from_utf8_lossy(&output).trim().to_string();
+    if repo.is_empty() {
+        bail!("could not determine the GitHub repository; pass owner/name#number instead");
+    }
+    Ok(repo)
+}
+
+fn gh_api(endpoint: &str) -> Result<Value> {
+    let output = gh(&["api", endpoint])?;
+    serde_json::from_slice(&output).with_context(|| format!("unexpected response from {endpoint}"))
+}
+
+/// A paginated list endpoint. `gh api --paginate` prints one JSON array
+/// per page.
+fn gh_api_list(endpoint: &str) -> Result<Vec<Value>> {
+    let output = gh(&["api", "--paginate", endpoint])?;
+    let mut items = Vec::new();
+    for page in serde_json::Deserializer::from_slice(&output).into_iter::<Value>() {
+        match page.with_context(|| format!("unexpected response from {endpoint}"))? {
+            Value::Array(page) => items.extend(page),
+            other => bail!("unexpected response from {endpoint}: {other}"),
+        }
+    }
+    Ok(items)
+}
+
+fn gh(args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("gh").args(args).output().map_err(|e| {
+        anyhow::anyhow!(
+            "failed to run gh ({e}); --from-issue and --from-pr need the GitHub CLI, \
+             logged in with `gh auth login`"
+        )
+    })?;
+    if !output.status.success() {
+        bail!(
+            "gh {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Replace `{name}` placeholders in one pass, so braces inside the values
+/// (issue text often has code) are left alone. Unknown placeholders stay.
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.trim().lines() {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_parse_in_every_form() {
+        let local = |number| Reference { repo: None, number };
+        let remote = |number| Reference {
+            repo: Some("octo/hydra".to_string()),
+            number,
+        };
+        assert_eq!(parse_reference("123"), Ok(local(123)));
+        assert_eq!(parse_reference("#123"), Ok(local(123)));
+        assert_eq!(parse_reference("octo/hydra#7"), Ok(remote(7)));
+        assert_eq!(
+            parse_reference("https://github.com/octo/hydra/pull/456/files"),
+            Ok(remote(456))
+        );
+        assert_eq!(
+            parse_reference("https://github.com/octo/hydra/issues/9#issuecomment-1"),
+            Ok(remote(9))
+        );
+        for invalid in ["", "0", "abc", "hydra#1", "https://github.com/octo/hydra"] {
+            assert!(parse_reference(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn compose_fills_the_template_and_drops_empty_sections() {
+        let mut thread = Thread {
+            kind: PromptSourceKind::PullRequest,
+            repo: "octo/hydra".to_string(),
+            number: 456,
+            title: "Retry {prompt} uploads".to_string(),
+            body: "Uploads fail on flaky networks.\n".to_string(),
+            url: "https://github.com/octo/hydra/pull/456".to_string(),
+            comments: vec![Comment {
+                author: "alice".to_string(),
+                body: "Cap the backoff.".to_string(),
+                location: Some("src/upload.rs:42".to_string()),
+            }],
+        };
+        assert_eq!(
+            thread.compose(DEFAULT_TEMPLATE, Some("Address the review.")),
+            "Address the review.\n\n\
+             GitHub pull request octo/hydra#456: Retry {prompt} uploads\n\
+             https://github.com/octo/hydra/pull/456\n\n\
+             Uploads fail on flaky networks.\n\n\
+             Comments:\n\n\
+             @alice on src/upload.rs:42:\n\
+             Cap the backoff."
+        );
+
+        thread.comments.clear();
+        thread.kind = PromptSourceKind::Issue;
+        assert_eq!(
+            thread.compose(DEFAULT_TEMPLATE, None),
+            "GitHub issue octo/hydra#456: Retry {prompt} uploads\n\
+             https://github.com/octo/hydra/pull/456\n\n\
+             Uploads fail on flaky networks."
+        );
+    }
+}
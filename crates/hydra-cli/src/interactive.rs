@@ -0,0 +1,830 @@
+//! `hydra interactive`: PTY agent sessions from the terminal.
+//!
+//! The agent runs under a detached session host (`hydra interactive --host
+//! <id>`) that owns the PTY, records artifacts under `.hydra/sessions/<id>`,
+//! and serves terminal clients on a Unix socket in the session directory.
+//! Detaching (Ctrl-]) only disconnects the client; `--attach <id>` reconnects
+//! and replays recent output. `--handoff <id>` stops a session and starts
+//! another agent in its checkout, seeded with what the first one left behind.
+
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use ratatui::crossterm::terminal;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use hydra_core::adapter::interactive::{
+    build_interactive_args, resolve_interactive_binary, supports_interactive_unsafe_mode,
+    unsafe_mode_requirement_hint,
+};
+use hydra_core::adapter::{AdapterRegistry, AdapterTier};
+use hydra_core::artifact::{
+    has_tags, SessionArtifactWriter, SessionHandoff, SessionLayout, SessionMetadata,
+};
+use hydra_core::security::ConsentStore;
+use hydra_core::supervisor::pty::{PtyEvent, PtySession, PtySessionConfig, Scrollback};
+
+use crate::run::discover_repo_root;
+use crate::{config, output};
+
+/// Ctrl-], as in telnet.
+const DETACH_KEY: u8 = 0x1d;
+/// Output kept by the host for replay when a client attaches.
+const SCROLLBACK_BYTES: usize = 64 * 1024;
+const MAX_FRAME_BYTES: usize = 1024 * 1024;
+const HOST_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+const HANDOFF_STOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+// Attach socket frames: tag byte, big-endian u32 length, payload.
+/// Client: keyboard input. Host: PTY output.
+const FRAME_DATA: u8 = 0;
+/// Client: terminal size as two big-endian u16s (cols, rows).
+const FRAME_RESIZE: u8 = 1;
+/// Host: JSON [`SessionExit`], sent once the agent process ends.
+const FRAME_EXIT: u8 = 2;
+/// Client: stop the agent, as for SIGTERM to the host. Empty payload.
+const FRAME_STOP: u8 = 3;
+
+pub struct InteractiveOpts {
+    pub agent: String,
+    pub prompt: Option<String>,
+    pub unsafe_mode: bool,
+    pub allow_experimental_adapters: bool,
+    pub detach: bool,
+    /// Session to stop and take over.
+    pub handoff: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Everything the session host needs to spawn the agent. Written to the
+/// session directory by the client and removed by the host once read.
+#[derive(Debug, Serialize, Deserialize)]
+struct LaunchSpec {
+    agent_key: String,
+    program: String,
+    args: Vec<String>,
+    cwd: PathBuf,
+    unsafe_mode: bool,
+    experimental: bool,
+    started_at: String,
+    cols: u16,
+    rows: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    handoff_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Kept to rebuild `args` once a handoff prompt is known.
+    #[serde(skip)]
+    supported_flags: Vec<String>,
+}
+
+impl LaunchSpec {
+    fn set_prompt(&mut self, prompt: &str) {
+        self.args = build_interactive_args(
+            &self.agent_key,
+            prompt,
+            self.unsafe_mode,
+            &self.supported_flags,
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SessionExit {
+    status: String,
+    exit_code: Option<u32>,
+    error: Option<String>,
+    duration_ms: u64,
+}
+
+enum AttachOutcome {
+    Detached,
+    Ended(SessionExit),
+    Disconnected,
+}
+
+fn launch_spec_path(layout: &SessionLayout) -> PathBuf {
+    layout.base_dir().join("launch.json")
+}
+
+fn socket_path(layout: &SessionLayout) -> PathBuf {
+    layout.base_dir().join("attach.sock")
+}
+
+fn host_log_path(layout: &SessionLayout) -> PathBuf {
+    layout.base_dir().join("host.log")
+}
+
+fn hydra_root() -> Result<PathBuf> {
+    Ok(discover_repo_root()?.join(".hydra"))
+}
+
+/// Start a session under a detached host, then attach unless `--detach`.
+/// Returns whether the session is running or ended successfully.
+pub async fn run_interactive(opts: InteractiveOpts) -> Result<bool> {
+    let cwd = std::env::current_dir().context("failed to read current directory")?;
+    let hydra_root = hydra_root()?;
+    if !opts.detach && !std::io::stdin().is_terminal() {
+        bail!("hydra interactive needs a terminal; use --detach to start the session in the background");
+    }
+    let mut spec = plan_launch(&opts, cwd)?;
+    if let Some(from) = &opts.handoff {
+        take_over(&hydra_root, from, opts.prompt.as_deref(), &mut spec).await?;
+    }
+
+    let session_id = Uuid::new_v4().to_string();
+    let layout = SessionLayout::new(&hydra_root, &session_id);
+    layout
+        .create_dirs()
+        .context("failed to create session directory")?;
+    std::fs::write(launch_spec_path(&layout), serde_json::to_vec(&spec)?)
+        .context("failed to write session launch spec")?;
+
+    spawn_host(&layout).await?;
+
+    if opts.detach {
+        let result = serde_json::json!({
+            "session_id": session_id,
+            "agent": spec.agent_key,
+            "status": "running",
+            "artifacts_path": layout.base_dir(),
+        });
+        output::emit(&result, false)?;
+        println!("Started session {session_id} ({})", spec.agent_key);
+        println!("  Attach with: hydra interactive --attach {session_id}");
+        return Ok(true);
+    }
+    finish_attach(&layout, attach(&layout).await?)
+}
+
+/// Reattach the terminal to a running session.
+pub async fn run_attach(session_id: &str) -> Result<bool> {
+    let layout = SessionLayout::new(&hydra_root()?, session_id);
+    if !layout.session_json_path().exists() {
+        bail!("session '{session_id}' not found");
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!("hydra interactive --attach needs a terminal");
+    }
+    finish_attach(&layout, attach(&layout).await?)
+}
+
+fn finish_attach(layout: &SessionLayout, outcome: AttachOutcome) -> Result<bool> {
+    let session_id = layout.session_id();
+    let (status, exit) = match &outcome {
+        AttachOutcome::Detached => ("running", None),
+        AttachOutcome::Ended(exit) => (exit.status.as_str(), Some(exit)),
+        AttachOutcome::Disconnected => ("unknown", None),
+    };
+    let result = serde_json::json!({
+        "session_id": session_id,
+        "status": status,
+        "exit": exit,
+        "artifacts_path": layout.base_dir(),
+    });
+    output::emit(&result, false)?;
+
+    match outcome {
+        AttachOutcome::Detached => {
+            eprintln!("[hydra] detached; reattach with: hydra interactive --attach {session_id}");
+            Ok(true)
+        }
+        AttachOutcome::Ended(exit) => {
+            match (&exit.error, exit.exit_code) {
+                (Some(error), _) => eprintln!("[hydra] session {}: {error}", exit.status),
+                (None, Some(code)) => {
+                    eprintln!("[hydra] session {} (exit code {code})", exit.status)
+                }
+                (None, None) => eprintln!("[hydra] session {}", exit.status),
+            }
+            eprintln!("[hydra] artifacts: {}", layout.base_dir().display());
+            Ok(exit.status == "completed")
+        }
+        AttachOutcome::Disconnected => {
+            eprintln!(
+                "[hydra] connection to session {session_id} closed (attached from another terminal?)"
+            );
+            Ok(true)
+        }
+    }
+}
+
+/// Resolve the adapter and build the launch, applying the same tier, readiness
+/// and unsafe-mode gates as the desktop app.
+fn plan_launch(opts: &InteractiveOpts, cwd: PathBuf) -> Result<LaunchSpec> {
+    let config = config::load()?;
+    let registry = AdapterRegistry::from_config(&config.adapters);
+    let adapter = registry
+        .resolve(&opts.agent, opts.allow_experimental_adapters)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    let detect = adapter.detect();
+    if !detect.status.is_available() {
+        let detail = detect
+            .error
+            .clone()
+            .unwrap_or_else(|| "probe failed with no detail".to_string());
+        bail!(
+            "adapter '{}' is not ready ({}): {}",
+            adapter.key(),
+            detect.status_label(),
+            detail
+        );
+    }
+    if opts.unsafe_mode && !supports_interactive_unsafe_mode(adapter.key(), &detect.supported_flags)
+    {
+        bail!(
+            "adapter '{}' does not support interactive unsafe mode. {}",
+            adapter.key(),
+            unsafe_mode_requirement_hint(adapter.key())
+        );
+    }
+
+    if opts.unsafe_mode {
+        ConsentStore::new(&discover_repo_root()?.join(".hydra"))
+            .authorize(None, &format!("interactive {}", adapter.key()))?;
+    }
+
+    let program = resolve_interactive_binary(adapter.key(), &config.adapters)
+        .with_context(|| format!("adapter '{}' binary not found", adapter.key()))?;
+    let args = build_interactive_args(
+        adapter.key(),
+        opts.prompt.as_deref().unwrap_or_default(),
+        opts.unsafe_mode,
+        &detect.supported_flags,
+    );
+    let (cols, rows) = terminal::size().unwrap_or((120, 40));
+
+    Ok(LaunchSpec {
+        agent_key: adapter.key().to_string(),
+        program: program.to_string_lossy().to_string(),
+        args,
+        cwd,
+        unsafe_mode: opts.unsafe_mode,
+        experimental: adapter.tier() == AdapterTier::Experimental,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        cols,
+        rows,
+        handoff_from: None,
+        tags: opts.tags.clone(),
+        supported_flags: detect.supported_flags.clone(),
+    })
+}
+
+/// Stop session `from` if it is still running, then point `spec` at its
+/// checkout with a prompt carrying its transcript tail and diff. Runs after
+/// `plan_launch`, so an unusable target agent never stops the session.
+async fn take_over(
+    hydra_root: &Path,
+    from: &str,
+    task: Option<&str>,
+    spec: &mut LaunchSpec,
+) -> Result<()> {
+    let layout = SessionLayout::new(hydra_root, from);
+    let metadata = SessionMetadata::read_from(&layout.session_json_path())
+        .with_context(|| format!("session '{from}' not found"))?;
+    if socket_path(&layout).exists() {
+        stop_session(&layout).await?;
+        eprintln!("[hydra] stopped session {from} ({})", metadata.agent_key);
+    }
+
+    let cwd = PathBuf::from(&metadata.cwd);
+    let handoff = SessionHandoff::capture(&layout, &cwd)
+        .await
+        .with_context(|| format!("failed to collect handoff context from session '{from}'"))?;
+    spec.set_prompt(&handoff.to_prompt(task));
+    spec.cwd = cwd;
+    spec.handoff_from = Some(from.to_string());
+    if spec.tags.is_empty() {
+        spec.tags = metadata.tags;
+    }
+    Ok(())
+}
+
+/// Ask a session host to stop its agent and wait until it has finalized the
+/// session's artifacts.
+async fn stop_session(layout: &SessionLayout) -> Result<()> {
+    let stream = UnixStream::connect(socket_path(layout))
+        .await
+        .with_context(|| format!("failed to connect to session '{}'", layout.session_id()))?;
+    let (mut reader, mut writer) = stream.into_split();
+    write_frame(&mut writer, FRAME_STOP, &[]).await?;
+
+    let wait_for_exit = async {
+        while let Some((tag, _)) = read_frame(&mut reader).await? {
+            if tag == FRAME_EXIT {
+                break;
+            }
+        }
+        anyhow::Ok(())
+    };
+    tokio::time::timeout(HANDOFF_STOP_TIMEOUT, wait_for_exit)
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "session '{}' did not stop within {}s",
+                layout.session_id(),
+                HANDOFF_STOP_TIMEOUT.as_secs()
+            )
+        })?
+}
+
+/// Launch `hydra interactive --host <id>` in its own process group, so it
+/// outlives this process and ignores terminal job control, and wait for its
+/// attach socket.
+async fn spawn_host(layout: &SessionLayout) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let exe = std::env::current_exe().context("failed to locate the hydra executable")?;
+    let log = std::fs::File::create(host_log_path(layout))
+        .context("failed to create session host log")?;
+    let mut child = std::process::Command::new(exe)
+        .args(["interactive", "--host", layout.session_id()])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(log)
+        .process_group(0)
+        .spawn()
+        .context("failed to start session host")?;
+
+    let socket = socket_path(layout);
+    let deadline = tokio::time::Instant::now() + HOST_STARTUP_TIMEOUT;
+    loop {
+        if socket.exists() {
+            return Ok(());
+        }
+        if let Some(status) = child.try_wait()? {
+            let log = std::fs::read_to_string(host_log_path(layout)).unwrap_or_default();
+            bail!(
+                "session host exited during startup ({status}){}",
+                last_line(&log)
+                    .map(|line| format!(": {line}"))
+                    .unwrap_or_default()
+            );
+        }
+        if tokio::time::Instant::now() >= deadline {
+            bail!(
+                "session host did not start within {}s; see {}",
+                HOST_STARTUP_TIMEOUT.as_secs(),
+                host_log_path(layout).display()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+fn last_line(text: &str) -> Option<&str> {
+    text.lines().rev().map(str::trim).find(|l| !l.is_empty())
+}
+
+/// Restores cooked mode even if the attach loop errors out.
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Result<Self> {
+        terminal::enable_raw_mode().context("failed to put terminal in raw mode")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+async fn attach(layout: &SessionLayout) -> Result<AttachOutcome> {
+    let socket = socket_path(layout);
+    let stream = UnixStream::connect(&socket).await.with_context(|| {
+        format!(
+            "session '{}' is not running (no session host listening)",
+            layout.session_id()
+        )
+    })?;
+    let (mut reader, mut writer) = stream.into_split();
+
+    eprintln!(
+        "[hydra] attached to session {}; press Ctrl-] to detach",
+        layout.session_id()
+    );
+    let _raw = RawModeGuard::enable()?;
+
+    let (frame_tx, mut frame_rx) = mpsc::channel::<(u8, Vec<u8>)>(64);
+    tokio::spawn(async move {
+        while let Ok(Some(frame)) = read_frame(&mut reader).await {
+            if frame_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // Blocking stdin reads get a plain thread; it dies with the process.
+    let (input_tx, mut input_rx) = mpsc::channel::<Vec<u8>>(64);
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin();
+        let mut buf = [0u8; 1024];
+        loop {
+            match stdin.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if input_tx.blocking_send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let mut winch = signal(SignalKind::window_change())?;
+    send_resize(&mut writer).await?;
+
+    let mut stdout = std::io::stdout();
+    loop {
+        tokio::select! {
+            input = input_rx.recv() => {
+                let Some(input) = input else {
+                    return Ok(AttachOutcome::Detached);
+                };
+                let (data, detach) = split_detach(&input);
+                if !data.is_empty() {
+                    write_frame(&mut writer, FRAME_DATA, data).await?;
+                }
+                if detach {
+                    return Ok(AttachOutcome::Detached);
+                }
+            }
+            _ = winch.recv() => {
+                send_resize(&mut writer).await?;
+            }
+            frame = frame_rx.recv() => match frame {
+                Some((FRAME_DATA, data)) => {
+                    stdout.write_all(&data)?;
+                    stdout.flush()?;
+                }
+                Some((FRAME_EXIT, data)) => {
+                    let exit: SessionExit = serde_json::from_slice(&data)
+                        .context("invalid session exit frame")?;
+                    return Ok(AttachOutcome::Ended(exit));
+                }
+                Some(_) => {}
+                None => return Ok(AttachOutcome::Disconnected),
+            },
+        }
+    }
+}
+
+/// Split input at the detach key: bytes to forward, and whether to detach.
+fn split_detach(input: &[u8]) -> (&[u8], bool) {
+    match input.iter().position(|&b| b == DETACH_KEY) {
+        Some(idx) => (&input[..idx], true),
+        None => (input, false),
+    }
+}
+
+async fn send_resize(writer: &mut OwnedWriteHalf) -> Result<()> {
+    let (cols, rows) = terminal::size().unwrap_or((120, 40));
+    write_frame(writer, FRAME_RESIZE, &encode_size(cols, rows)).await?;
+    Ok(())
+}
+
+fn encode_size(cols: u16, rows: u16) -> [u8; 4] {
+    let [c0, c1] = cols.to_be_bytes();
+    let [r0, r1] = rows.to_be_bytes();
+    [c0, c1, r0, r1]
+}
+
+fn decode_size(payload: &[u8]) -> Option<(u16, u16)> {
+    match payload {
+        [c0, c1, r0, r1] => Some((
+            u16::from_be_bytes([*c0, *c1]),
+            u16::from_be_bytes([*r0, *r1]),
+        )),
+        _ => None,
+    }
+}
+
+async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    tag: u8,
+    payload: &[u8],
+) -> std::io::Result<()> {
+    let mut frame = Vec::with_capacity(5 + payload.len());
+    frame.push(tag);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    writer.write_all(&frame).await?;
+    writer.flush().await
+}
+
+/// Read one frame, or `None` at a clean end of stream.
+async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<(u8, Vec<u8>)>> {
+    let mut header = [0u8; 5];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds limit"),
+        ));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).await?;
+    Ok(Some((header[0], payload)))
+}
+
+/// Session host entry point: own the PTY until the agent exits.
+pub async fn run_host(session_id: &str) -> Result<()> {
+    let hydra_root = hydra_root()?;
+    let layout = SessionLayout::new(&hydra_root, session_id);
+    let spec_path = launch_spec_path(&layout);
+    let spec: LaunchSpec = serde_json::from_slice(
+        &std::fs::read(&spec_path).context("failed to read session launch spec")?,
+    )
+    .context("invalid session launch spec")?;
+    let _ = std::fs::remove_file(&spec_path);
+
+    let (event_tx, mut event_rx) = mpsc::channel(1024);
+    let pty = PtySession::spawn(
+        PtySessionConfig {
+            program: spec.program.clone(),
+            args: spec.args.clone(),
+            env: vec![],
+            cwd: spec.cwd.clone(),
+            initial_cols: spec.cols,
+            initial_rows: spec.rows,
+        },
+        event_tx,
+    )
+    .context("PTY spawn failed")?;
+
+    let mut artifacts = match SessionArtifactWriter::init(
+        &hydra_root,
+        session_id,
+        &spec.agent_key,
+        &spec.started_at,
+        &spec.cwd.to_string_lossy(),
+        spec.unsafe_mode,
+        spec.experimental,
+    )
+    .and_then(|writer| writer.with_tags(spec.tags.clone()))
+    {
+        Ok(writer) => Some(writer),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to initialize session artifact writer; session will proceed without artifact persistence");
+            None
+        }
+    };
+    if let (Some(writer), Some(from)) = (artifacts.as_mut(), &spec.handoff_from) {
+        let _ = writer.record_event("handoff", serde_json::json!({ "from_session": from }));
+    }
+
+    let socket = socket_path(&layout);
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket)
+        .with_context(|| format!("failed to listen on {}", socket.display()))?;
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let (client_tx, mut client_rx) = mpsc::channel::<(u64, u8, Vec<u8>)>(256);
+    let mut client: Option<(u64, OwnedWriteHalf)> = None;
+    let mut next_conn = 0u64;
+    let mut scrollback = Scrollback::new(SCROLLBACK_BYTES);
+
+    let exit = loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                let (mut reader, mut writer) = stream.into_split();
+                next_conn += 1;
+                let conn = next_conn;
+                if write_frame(&mut writer, FRAME_DATA, &scrollback.snapshot()).await.is_err() {
+                    continue;
+                }
+                // Replacing the writer closes the previous client's stream.
+                client = Some((conn, writer));
+                let tx = client_tx.clone();
+                tokio::spawn(async move {
+                    while let Ok(Some((tag, payload))) = read_frame(&mut reader).await {
+                        if tx.send((conn, tag, payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+                tracing::info!(session_id, conn, "client attached");
+            }
+            Some((conn, tag, payload)) = client_rx.recv() => {
+                if client.as_ref().map(|(current, _)| *current) != Some(conn) {
+                    continue;
+                }
+                match tag {
+                    FRAME_DATA => {
+                        if let Some(writer) = artifacts.as_mut() {
+                            let _ = writer.record_user_input(&String::from_utf8_lossy(&payload));
+                        }
+                        if let Err(e) = pty.write_input(&payload).await {
+                            tracing::warn!(error = %e, "failed to forward input to PTY");
+                        }
+                    }
+                    FRAME_RESIZE => {
+                        if let Some((cols, rows)) = decode_size(&payload) {
+                            let _ = pty.resize(cols, rows).await;
+                        }
+                    }
+                    FRAME_STOP => {
+                        if let Some(writer) = artifacts.as_mut() {
+                            let _ = writer.record_event("stop_requested", serde_json::json!({}));
+                        }
+                        pty.stop().await;
+                    }
+                    _ => {}
+                }
+            }
+            event = event_rx.recv() => match event {
+                Some(PtyEvent::Started) => {}
+                Some(PtyEvent::Output(bytes)) => {
+                    if let Some(writer) = artifacts.as_mut() {
+                        let _ = writer.record_output(&bytes);
+                    }
+                    scrollback.push(&bytes);
+                    if let Some((_, writer)) = client.as_mut() {
+                        if write_frame(writer, FRAME_DATA, &bytes).await.is_err() {
+                            client = None;
+                        }
+                    }
+                }
+                Some(PtyEvent::Completed { exit_code, duration }) => {
+                    let status = if exit_code.unwrap_or(0) == 0 { "completed" } else { "failed" };
+                    break session_exit(status, exit_code, None, duration);
+                }
+                Some(PtyEvent::Failed { error, duration }) => {
+                    break session_exit("failed", None, Some(error), duration);
+                }
+                Some(PtyEvent::Stopped { duration }) => {
+                    break session_exit("stopped", None, None, duration);
+                }
+                None => break session_exit("failed", None, Some("PTY event stream closed".to_string()), Duration::ZERO),
+            },
+            // The host has no terminal; a hangup from the launching shell must not end the session.
+            _ = hangup.recv() => {}
+            _ = terminate.recv() => pty.stop().await,
+        }
+    };
+
+    if let Some(writer) = artifacts.as_mut() {
+        let ended_at = chrono::Utc::now().to_rfc3339();
+        if let Err(e) = writer.finalize(&exit.status, &ended_at, exit.duration_ms) {
+            tracing::warn!(error = %e, "failed to finalize session artifacts");
+        }
+    }
+    if let Some((_, mut writer)) = client.take() {
+        let _ = write_frame(&mut writer, FRAME_EXIT, &serde_json::to_vec(&exit)?).await;
+    }
+    let _ = std::fs::remove_file(&socket);
+    tracing::info!(session_id, status = %exit.status, "session ended");
+    Ok(())
+}
+
+fn session_exit(
+    status: &str,
+    exit_code: Option<u32>,
+    error: Option<String>,
+    duration: Duration,
+) -> SessionExit {
+    SessionExit {
+        status: status.to_string(),
+        exit_code,
+        error,
+        duration_ms: duration.as_millis() as u64,
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SessionListing {
+    #[serde(flatten)]
+    metadata: SessionMetadata,
+    attachable: bool,
+}
+
+/// List recorded sessions carrying every tag in `tags`, newest first.
+pub fn run_list(tags: &[String], json: bool) -> Result<()> {
+    let hydra_root = hydra_root()?;
+    let mut sessions: Vec<SessionListing> = SessionLayout::list_sessions(&hydra_root)
+        .context("failed to list sessions")?
+        .into_iter()
+        .filter_map(|id| {
+            let layout = SessionLayout::new(&hydra_root, &id);
+            let metadata = SessionMetadata::read_from(&layout.session_json_path()).ok()?;
+            if !has_tags(&metadata.tags, tags) {
+                return None;
+            }
+            let attachable = metadata.status == "running" && socket_path(&layout).exists();
+            Some(SessionListing {
+                metadata,
+                attachable,
+            })
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.metadata.started_at.cmp(&a.metadata.started_at));
+
+    output::emit(&sessions, json)?;
+    if json {
+        return Ok(());
+    }
+    if sessions.is_empty() {
+        println!("No interactive sessions recorded.");
+        return Ok(());
+    }
+    println!(
+        "  {:<36}  {:<12} {:<10} {:<25} Attach",
+        "Session", "Agent", "Status", "Started"
+    );
+    for session in &sessions {
+        println!(
+            "  {:<36}  {:<12} {:<10} {:<25} {}",
+            session.metadata.session_id,
+            session.metadata.agent_key,
+            session.metadata.status,
+            session.metadata.started_at,
+            if session.attachable { "yes" } else { "-" }
+        );
+        if !session.metadata.tags.is_empty() {
+            println!("    tags: {}", session.metadata.tags.join(", "));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detach_key_splits_input() {
+        assert_eq!(split_detach(b"ls\r"), (&b"ls\r"[..], false));
+        assert_eq!(split_detach(b"ab\x1dcd"), (&b"ab"[..], true));
+        assert_eq!(split_detach(b"\x1d"), (&b""[..], true));
+    }
+
+    #[test]
+    fn resize_payload_round_trips() {
+        assert_eq!(decode_size(&encode_size(212, 57)), Some((212, 57)));
+        assert_eq!(decode_size(b"abc"), None);
+    }
+
+    #[tokio::test]
+    async fn frames_round_trip_and_end_cleanly() {
+        let (mut a, mut b) = tokio::io::duplex(64);
+        write_frame(&mut a, FRAME_DATA, b"hello").await.unwrap();
+        write_frame(&mut a, FRAME_RESIZE, &encode_size(80, 24))
+            .await
+            .unwrap();
+        drop(a);
+        assert_eq!(
+            read_frame(&mut b).await.unwrap(),
+            Some((FRAME_DATA, b"hello".to_vec()))
+        );
+        assert_eq!(
+            read_frame(&mut b).await.unwrap(),
+            Some((FRAME_RESIZE, encode_size(80, 24).to_vec()))
+        );
+        assert_eq!(read_frame(&mut b).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn stop_session_sends_stop_and_waits_for_exit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let layout = SessionLayout::new(tmp.path(), "s1");
+        layout.create_dirs().unwrap();
+        let listener = UnixListener::bind(socket_path(&layout)).unwrap();
+
+        let host = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            write_frame(&mut stream, FRAME_DATA, b"scrollback")
+                .await
+                .unwrap();
+            let (tag, _) = read_frame(&mut stream).await.unwrap().unwrap();
+            let exit = session_exit("stopped", None, None, Duration::ZERO);
+            write_frame(&mut stream, FRAME_EXIT, &serde_json::to_vec(&exit).unwrap())
+                .await
+                .unwrap();
+            tag
+        });
+
+        stop_session(&layout).await.unwrap();
+        assert_eq!(host.await.unwrap(), FRAME_STOP);
+    }
+}
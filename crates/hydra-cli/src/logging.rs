@@ -0,0 +1,99 @@
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::otlp::{Endpoint, OtlpLayer};
+
+use anyhow::{Context, Result};
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Default filter for `--log-file` when `RUST_LOG` is unset.
+const LOG_FILE_DIRECTIVE: &str = "info,hydra=debug,hydra_core=debug";
+
+pub struct LogOpts {
+    pub quiet: bool,
+    pub verbose: u8,
+    pub log_file: Option<PathBuf>,
+    pub ansi: bool,
+    /// Export spans to this OTLP/HTTP endpoint.
+    pub otlp: Option<Endpoint>,
+}
+
+/// Console filter for the `-q`/`-v` flags, or `None` to defer to `RUST_LOG`.
+fn console_directive(quiet: bool, verbose: u8) -> Option<&'static str> {
+    match (quiet, verbose) {
+        (true, _) => Some("error"),
+        (false, 0) => None,
+        (false, 1) => Some("info"),
+        (false, 2) => Some("debug"),
+        (false, _) => Some("trace"),
+    }
+}
+
+/// Install the global subscriber: human-readable logs on stderr, plus JSON
+/// logs appended to `--log-file` and spans exported over OTLP when
+/// configured.
+pub fn init(opts: LogOpts) -> Result<()> {
+    let console_filter = match console_directive(opts.quiet, opts.verbose) {
+        Some(directive) => EnvFilter::new(directive),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+    };
+    let console = tracing_subscriber::fmt::layer()
+        .with_ansi(opts.ansi)
+        .with_writer(std::io::stderr)
+        .with_filter(console_filter);
+
+    let file = match &opts.log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("failed to open log file {}", path.display()))?;
+            let filter = EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new(LOG_FILE_DIRECTIVE));
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(file))
+                    .with_filter(filter),
+            )
+        }
+        None => None,
+    };
+
+    // Only hydra's own spans: the race, agent, supervisor and scoring phases.
+    let otlp = opts.otlp.map(|endpoint| {
+        OtlpLayer::new(endpoint).with_filter(
+            Targets::new()
+                .with_target("hydra", Level::INFO)
+                .with_target("hydra_core", Level::INFO),
+        )
+    });
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(file)
+        .with(otlp)
+        .init();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_flags_map_to_levels() {
+        assert_eq!(console_directive(false, 0), None);
+        assert_eq!(console_directive(true, 0), Some("error"));
+        assert_eq!(console_directive(false, 1), Some("info"));
+        assert_eq!(console_directive(false, 2), Some("debug"));
+        assert_eq!(console_directive(false, 5), Some("trace"));
+    }
+}
@@ -1,19 +1,77 @@
-use clap::{Parser, Subcommand};
-use tracing_subscriber::EnvFilter;
-
-use hydra_core::adapter::claude::ClaudeAdapter;
-use hydra_core::adapter::codex::CodexAdapter;
-use hydra_core::adapter::cursor::CursorAdapter;
-use hydra_core::adapter::{AgentAdapter, ProbeRunner};
+use clap::{CommandFactory, Parser, Subcommand};
 
+mod approval;
+mod batch;
+mod bench;
+mod cancel;
+mod ci;
+mod config;
+mod consent;
+mod diff;
+mod dispatch;
+mod docs;
 mod doctor;
+mod github;
+#[cfg(unix)]
+mod interactive;
+mod logging;
 mod merge;
+mod metrics;
+mod otlp;
+mod output;
+mod progress;
 mod race;
+mod replay;
+mod rerun;
+mod rpc;
 mod run;
+mod score;
+mod security;
+mod serve;
+mod setup;
+mod stats;
+mod task;
+mod timeline;
+mod trust;
+mod tui;
+mod variants;
+mod watch;
+mod websocket;
+mod worker;
+mod worktrees;
 
 #[derive(Parser)]
-#[command(name = "hydra", about = "Multi-agent orchestration control center")]
+#[command(
+    name = "hydra",
+    about = "Multi-agent orchestration control center",
+    after_help = output::EXIT_CODES_HELP
+)]
 struct Cli {
+    /// Only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log more detail (-v info, -vv debug, -vvv trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Also write full JSON logs to this file
+    #[arg(long, global = true)]
+    log_file: Option<std::path::PathBuf>,
+
+    /// Export tracing spans to this OTLP/HTTP collector (defaults to
+    /// OTEL_EXPORTER_OTLP_ENDPOINT)
+    #[arg(long, global = true, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+
+    /// Config file to use instead of the nearest hydra.toml in this or a parent directory
+    #[arg(long, global = true)]
+    config: Option<std::path::PathBuf>,
+
+    /// Also write the command's JSON result to this file (written atomically)
+    #[arg(long, global = true)]
+    output: Option<std::path::PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -25,26 +83,87 @@ enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Apply safe fixes (default hydra.toml, .gitignore entry, worktree prune)
+        #[arg(long, conflicts_with = "watch")]
+        fix: bool,
+
+        /// Keep re-running the checks and print what changed until interrupted
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between re-runs in --watch mode (changes to PATH directories
+        /// or hydra.toml trigger one sooner)
+        #[arg(long, default_value_t = 10, requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
     },
     /// Run agents on a task in isolated worktrees
     Race {
-        /// Agents to run (comma-separated, e.g. "claude,codex")
-        #[arg(long, value_delimiter = ',')]
+        /// Agents to run (comma-separated, e.g. "claude,codex"; defaults to race.default_agents, else all Tier-1 adapters)
+        #[arg(long, value_delimiter = ',', value_parser = parse_agent_key)]
         agents: Vec<String>,
 
-        /// Task prompt for the agents
-        #[arg(long, short = 'p')]
-        prompt: String,
+        /// Task prompt for the agents; with --from-issue/--from-pr, extra
+        /// instructions placed before the fetched text
+        #[arg(
+            long,
+            short = 'p',
+            value_parser = parse_prompt,
+            required_unless_present_any = ["from_issue", "from_pr", "task", "variants"]
+        )]
+        prompt: Option<String>,
+
+        /// Read the task from a YAML file with title, description,
+        /// acceptance_criteria, relevant_paths, forbidden_paths and sparse
+        /// keys; each agent gets it in its preferred format
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["prompt", "from_issue", "from_pr"]
+        )]
+        task: Option<std::path::PathBuf>,
+
+        /// Race one agent on several phrasings of the task, read from a YAML
+        /// file with a `variants` list of `name` and `prompt` entries; each
+        /// variant gets its own worktree, branch and score
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["prompt", "task", "from_issue", "from_pr"]
+        )]
+        variants: Option<std::path::PathBuf>,
+
+        /// Build the prompt from a GitHub issue (123, owner/name#123 or a URL;
+        /// needs the gh CLI). The template is race.github_template, with
+        /// {prompt}, {kind}, {repo}, {number}, {title}, {url}, {body} and
+        /// {comments} placeholders
+        #[arg(
+            long,
+            value_name = "ISSUE",
+            value_parser = github::parse_reference,
+            conflicts_with = "from_pr"
+        )]
+        from_issue: Option<github::Reference>,
+
+        /// Build the prompt from a GitHub pull request, including its reviews
+        /// and review comments
+        #[arg(long, value_name = "PR", value_parser = github::parse_reference)]
+        from_pr: Option<github::Reference>,
 
         /// Base git ref to branch from (default: HEAD)
-        #[arg(long, default_value = "HEAD")]
+        #[arg(long, default_value = "HEAD", value_parser = parse_base_ref)]
         base_ref: String,
 
+        /// Label the run (repeatable), for `hydra run list --tag` and
+        /// `hydra stats --tag`
+        #[arg(long = "tag", value_name = "TAG", value_parser = hydra_core::artifact::parse_tag)]
+        tags: Vec<String>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
 
-        /// Allow agent execution outside strict worktree sandbox controls
+        /// Allow agent execution outside strict worktree sandbox controls (requires `hydra unsafe grant`)
         #[arg(long = "unsafe")]
         unsafe_mode: bool,
 
@@ -55,6 +174,133 @@ enum Commands {
         /// Internal override for run ID (used by GUI orchestration).
         #[arg(long, hide = true)]
         run_id: Option<uuid::Uuid>,
+
+        /// Derive the run ID from the prompt, base commit, agents and config.
+        /// Repeating the same race is refused while its run exists; remove
+        /// it with `hydra run rm <id>` first
+        #[arg(long, conflicts_with = "run_id")]
+        deterministic: bool,
+
+        /// If another race is setting up in this repository, queue behind it
+        /// instead of failing
+        #[arg(long, conflicts_with = "force")]
+        wait: bool,
+
+        /// Take the repository lock even if another race holds it
+        #[arg(long)]
+        force: bool,
+
+        /// Score agents as they finish and stop the rest once one passes the
+        /// gates with at least `race.success_threshold`
+        #[arg(long)]
+        stop_on_first_success: bool,
+
+        /// Reuse the latest baseline captured on the same base commit instead
+        /// of running the scoring commands again; without one, score with no
+        /// baseline
+        #[arg(long)]
+        skip_baseline: bool,
+
+        /// CI mode: no ANSI, JSON-lines progress on stdout, and a summary file.
+        /// Exit codes: 0 = a candidate is mergeable, 2 = no candidate is
+        /// mergeable, 3 = infrastructure failure.
+        #[arg(long, conflicts_with = "json")]
+        ci: bool,
+
+        /// Where `--ci` writes the run summary (default: hydra-ci-summary.json)
+        #[arg(long, requires = "ci")]
+        summary_file: Option<std::path::PathBuf>,
+
+        /// Run the agents on a `hydra worker` (http://host[:port], token in
+        /// $HYDRA_WORKER_TOKEN) and import the run's artifacts when it ends
+        #[arg(long, value_name = "URL", conflicts_with_all = ["ci", "unsafe_mode", "run_id", "deterministic", "wait", "force", "stop_on_first_success", "skip_baseline"])]
+        worker: Option<String>,
+    },
+    /// Start a fresh race with the parameters of a previous run
+    Rerun {
+        /// Run ID to repeat
+        run_id: uuid::Uuid,
+
+        /// Agents to run instead of the original set (comma-separated)
+        #[arg(long, value_delimiter = ',', value_parser = parse_agent_key)]
+        agents: Vec<String>,
+
+        /// Branch from the current HEAD instead of the original base ref
+        #[arg(long)]
+        head: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Allow agent execution outside strict worktree sandbox controls (requires `hydra unsafe grant`)
+        #[arg(long = "unsafe")]
+        unsafe_mode: bool,
+
+        /// Allow experimental (non-Tier-1) adapters to participate in the race
+        #[arg(long)]
+        allow_experimental_adapters: bool,
+    },
+    /// Run a queue of race tasks from a YAML file
+    Batch {
+        /// YAML file listing tasks (prompt, agents, base_ref, config overrides)
+        file: std::path::PathBuf,
+
+        /// Maximum number of races to run at once
+        #[arg(long, default_value_t = 1)]
+        parallel: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Allow agent execution outside strict worktree sandbox controls (requires `hydra unsafe grant`)
+        #[arg(long = "unsafe")]
+        unsafe_mode: bool,
+
+        /// Allow experimental (non-Tier-1) adapters to participate in the races
+        #[arg(long)]
+        allow_experimental_adapters: bool,
+    },
+    /// Score a branch or checkout against a base ref without running agents
+    Score {
+        /// Branch/ref to score, or path to an existing checkout
+        #[arg(long)]
+        target: String,
+
+        /// Base git ref to compare against
+        #[arg(long, default_value = "HEAD", value_parser = parse_base_ref)]
+        base_ref: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Benchmark adapters on a suite of small tasks and compare results
+    Bench {
+        /// YAML suite file (defaults to the built-in suite)
+        #[arg(long)]
+        suite: Option<std::path::PathBuf>,
+
+        /// Agents to benchmark (comma-separated; defaults to race.default_agents, else all Tier-1 adapters)
+        #[arg(long, value_delimiter = ',', value_parser = parse_agent_key)]
+        agents: Vec<String>,
+
+        /// Base git ref each task branches from
+        #[arg(long, default_value = "HEAD", value_parser = parse_base_ref)]
+        base_ref: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Allow agent execution outside strict worktree sandbox controls (requires `hydra unsafe grant`)
+        #[arg(long = "unsafe")]
+        unsafe_mode: bool,
+
+        /// Allow experimental (non-Tier-1) adapters to participate
+        #[arg(long)]
+        allow_experimental_adapters: bool,
     },
     /// Merge an agent's branch from a completed race run
     Merge {
@@ -78,66 +324,580 @@ enum Commands {
         #[arg(long)]
         force: bool,
 
+        /// Merge only these paths from the agent's changes (comma-separated or repeated)
+        #[arg(long, value_delimiter = ',', value_name = "PATH")]
+        only: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the changes an agent made in a run
+    Diff {
+        /// Run ID to inspect
+        #[arg(long)]
+        run_id: Option<uuid::Uuid>,
+
+        /// Use the most recently modified run
+        #[arg(long)]
+        latest: bool,
+
+        /// Agent whose changes to show
+        #[arg(long, value_parser = parse_agent_key)]
+        agent: String,
+
+        /// Show a diffstat instead of the patch
+        #[arg(long, conflicts_with = "name_only")]
+        stat: bool,
+
+        /// Show only the names of changed files
+        #[arg(long)]
+        name_only: bool,
+
+        /// Pipe the patch through $HYDRA_PAGER, delta, $PAGER, or less
+        #[arg(long)]
+        pager: bool,
+    },
+    /// Cancel a live run, or a single agent within it
+    Cancel {
+        /// Run ID to cancel
+        #[arg(long)]
+        run_id: Option<uuid::Uuid>,
+
+        /// Cancel the most recently started run
+        #[arg(long)]
+        latest: bool,
+
+        /// Cancel only this agent (defaults to every agent in the run)
+        #[arg(long, value_parser = parse_agent_key)]
+        agent: Option<String>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
+    /// Attach the terminal to an agent in a PTY session (Ctrl-] detaches)
+    #[cfg(unix)]
+    Interactive {
+        /// Agent to launch
+        #[arg(
+            long,
+            value_parser = parse_agent_key,
+            required_unless_present_any = ["attach", "list", "host"]
+        )]
+        agent: Option<String>,
+
+        /// Initial prompt (omit to start at the agent's own prompt)
+        #[arg(long, short = 'p', requires = "agent")]
+        prompt: Option<String>,
+
+        /// Allow agent execution outside strict sandbox controls (requires `hydra unsafe grant`)
+        #[arg(long = "unsafe", requires = "agent")]
+        unsafe_mode: bool,
+
+        /// Allow experimental (non-Tier-1) adapters
+        #[arg(long, requires = "agent")]
+        allow_experimental_adapters: bool,
+
+        /// Start the session in the background without attaching
+        #[arg(long, requires = "agent")]
+        detach: bool,
+
+        /// Stop this session and continue its work with --agent in the same
+        /// checkout, passing along the end of its transcript and its diff
+        #[arg(long, value_name = "SESSION", requires = "agent")]
+        handoff: Option<String>,
+
+        /// Label the session (repeatable); with --list, only sessions
+        /// carrying every given tag
+        #[arg(long = "tag", value_name = "TAG", value_parser = hydra_core::artifact::parse_tag)]
+        tags: Vec<String>,
+
+        /// Reattach to a running session
+        #[arg(long, conflicts_with_all = ["agent", "list"])]
+        attach: Option<String>,
+
+        /// List recorded sessions
+        #[arg(long, conflicts_with = "agent")]
+        list: bool,
+
+        /// Output as JSON (with --list)
+        #[arg(long, requires = "list")]
+        json: bool,
+
+        /// Internal: run the session host for this session ID.
+        #[arg(long, hide = true, conflicts_with_all = ["agent", "attach", "list"])]
+        host: Option<String>,
+    },
     /// Inspect stored run artifacts and scores
     Run {
         #[command(subcommand)]
         command: run::RunCommand,
     },
+    /// Check stored runs against the security policy
+    Security {
+        #[command(subcommand)]
+        command: security::SecurityCommand,
+    },
+    /// Manage the repositories hydra may run agents and scoring in
+    Trust {
+        #[command(subcommand)]
+        command: trust::TrustCommand,
+    },
+    /// Grant, inspect, or revoke time-limited consent for --unsafe
+    Unsafe {
+        #[command(subcommand)]
+        command: consent::UnsafeCommand,
+    },
+    /// Manage agent worktrees
+    Worktrees {
+        #[command(subcommand)]
+        command: worktrees::WorktreesCommand,
+    },
+    /// Follow a run's event stream with live per-agent status
+    Watch {
+        /// Run ID to watch
+        #[arg(long)]
+        run_id: Option<uuid::Uuid>,
+
+        /// Watch the most recently started run
+        #[arg(long)]
+        latest: bool,
+
+        /// Stream raw events as JSON lines
+        #[arg(long)]
+        json: bool,
+
+        /// Poll interval for new events in milliseconds
+        #[arg(long, default_value_t = 250)]
+        poll_interval_ms: u64,
+    },
+    /// Show where a run spent its time as a text timeline
+    Timeline {
+        /// Run ID to show
+        #[arg(long)]
+        run_id: Option<uuid::Uuid>,
+
+        /// Show the most recently started run
+        #[arg(long)]
+        latest: bool,
+
+        /// Print the timeline as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Width of the bars in columns
+        #[arg(long, default_value_t = 60)]
+        width: usize,
+    },
+    /// Re-emit a recorded run's events as a new run, without running agents
+    Replay {
+        /// Run ID to replay
+        run_id: uuid::Uuid,
+
+        /// Playback speed relative to the recording, e.g. 10x
+        #[arg(long, default_value = "1x", value_parser = replay::parse_speed)]
+        speed: f64,
+
+        /// Stream raw events as JSON lines
+        #[arg(long)]
+        json: bool,
+
+        /// Also serve the API, including its WebSocket event stream, on this
+        /// address until Ctrl-C
+        #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = "127.0.0.1:7700")]
+        serve: Option<std::net::SocketAddr>,
+
+        /// Bearer token for --serve (default: $HYDRA_SERVE_TOKEN, else a
+        /// random token printed at startup)
+        #[arg(long, requires = "serve")]
+        token: Option<String>,
+    },
+    /// Guided first-run setup: probe agents, choose defaults, and write hydra.toml
+    Setup {
+        /// Accept every default without prompting
+        #[arg(long, short)]
+        yes: bool,
+
+        /// Overwrite an existing hydra.toml without asking
+        #[arg(long)]
+        force: bool,
+    },
+    /// Summarize win rate, mergeability, scores, cost, and duration per agent
+    Stats {
+        /// Only include runs started within this window (e.g. 30d, 12h, 2w)
+        #[arg(long, value_parser = stats::parse_since)]
+        since: Option<chrono::Duration>,
+
+        /// Only report on this agent
+        #[arg(long, value_parser = parse_agent_key)]
+        agent: Option<String>,
+
+        /// Only include runs carrying this tag (repeatable; runs must have all of them)
+        #[arg(long = "tag", value_name = "TAG", value_parser = hydra_core::artifact::parse_tag)]
+        tags: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Print all-time metrics in the Prometheus text format (for the
+        /// textfile collector or a Pushgateway)
+        #[arg(long, conflicts_with_all = ["since", "agent", "tags", "json"])]
+        prometheus: bool,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Generate man pages (prints hydra.1 to stdout unless --out-dir is set)
+    Man {
+        /// Write hydra.1 and one page per subcommand into this directory
+        #[arg(long)]
+        out_dir: Option<std::path::PathBuf>,
+    },
+    /// Open the terminal dashboard for runs, agent output, and scores
+    Tui {
+        /// Run ID to select on startup (defaults to the newest run)
+        #[arg(long)]
+        run_id: Option<uuid::Uuid>,
+    },
+    /// Serve races, run artifacts, and merges over a local HTTP/WebSocket API
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = serve::DEFAULT_PORT)]
+        port: u16,
+
+        /// Address to bind; anything but loopback exposes the API to the network
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+
+        /// Bearer token clients must send (default: $HYDRA_SERVE_TOKEN, else a
+        /// random token printed at startup)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Speak the serve API as JSON-RPC over stdin/stdout, for editor extensions
+    Rpc,
+    /// Run races sent by `hydra race --worker` from other machines
+    Worker {
+        /// Address to listen on; anything but loopback exposes the worker to the network
+        #[arg(long, default_value_t = std::net::SocketAddr::from(([127, 0, 0, 1], worker::DEFAULT_PORT)))]
+        listen: std::net::SocketAddr,
+
+        /// Bearer token controllers must send (default: $HYDRA_WORKER_TOKEN,
+        /// else a random token printed at startup)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Directory for synced repositories and their runs (default:
+        /// ~/.local/share/hydra/worker)
+        #[arg(long)]
+        dir: Option<std::path::PathBuf>,
+    },
 }
 
-fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+fn parse_agent_key(raw: &str) -> Result<String, String> {
+    let key = raw.trim();
+    hydra_core::git_ref::validate_agent_key(key)
+        .map_err(|e| format!("invalid agent key '{key}': {e}"))?;
+    Ok(key.to_string())
+}
 
-    let cli = Cli::parse();
+fn parse_prompt(raw: &str) -> Result<String, String> {
+    if raw.trim().is_empty() {
+        return Err("prompt cannot be empty".to_string());
+    }
+    Ok(raw.to_string())
+}
 
-    match cli.command {
-        Commands::Doctor { json } => {
-            let adapter_cfg = doctor::load_adapter_config();
-            let adapters: Vec<Box<dyn AgentAdapter>> = vec![
-                Box::new(ClaudeAdapter::new(adapter_cfg.claude)),
-                Box::new(CodexAdapter::new(adapter_cfg.codex)),
-                Box::new(CursorAdapter::new(adapter_cfg.cursor)),
-            ];
-
-            let runner = ProbeRunner::new(adapters);
-            let probe_report = runner.run();
-            let git_checks = doctor::check_git_repo();
-            let report = doctor::DoctorReport::new(probe_report, git_checks);
-
-            if json {
-                println!("{}", serde_json::to_string_pretty(&report)?);
+fn parse_base_ref(raw: &str) -> Result<String, String> {
+    let base_ref = raw.trim();
+    if base_ref.is_empty() {
+        return Err("base ref cannot be empty".to_string());
+    }
+    // Refs are passed straight to git; a leading dash would be read as an option.
+    if base_ref.starts_with('-') {
+        return Err(format!("base ref '{base_ref}' cannot start with '-'"));
+    }
+    if base_ref.chars().any(char::is_whitespace) {
+        return Err(format!("base ref '{base_ref}' cannot contain whitespace"));
+    }
+    Ok(base_ref.to_string())
+}
+
+fn exit_unless_completed(outcome: &race::RaceOutcome, json: bool) {
+    if outcome.status != hydra_core::artifact::RunStatus::Completed {
+        if !json {
+            eprintln!("Error: race did not complete successfully");
+        }
+        std::process::exit(output::EXIT_FAILURE);
+    }
+}
+
+/// Commands that spawn agents or run the repository's build and test
+/// commands, and so only run in trusted repositories.
+fn needs_trust(command: &Commands) -> bool {
+    match command {
+        Commands::Race { .. }
+        | Commands::Rerun { .. }
+        | Commands::Batch { .. }
+        | Commands::Score { .. }
+        | Commands::Bench { .. }
+        | Commands::Serve { .. }
+        | Commands::Rpc => true,
+        Commands::Interactive { agent, host, .. } => agent.is_some() && host.is_none(),
+        Commands::Replay { serve, .. } => serve.is_some(),
+        _ => false,
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        std::process::exit(output::report_error(&e));
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            // Keep clap's default of 0 for --help/--version, but move usage
+            // errors off 2, which means "not mergeable".
+            let code = if e.use_stderr() {
+                output::EXIT_USAGE
             } else {
+                output::EXIT_SUCCESS
+            };
+            let _ = e.print();
+            std::process::exit(code);
+        }
+    };
+
+    let ci_mode = matches!(cli.command, Commands::Race { ci: true, .. });
+    logging::init(logging::LogOpts {
+        quiet: cli.quiet,
+        verbose: cli.verbose,
+        log_file: cli.log_file.clone(),
+        ansi: !ci_mode,
+        otlp: otlp::Endpoint::resolve(cli.otlp_endpoint.as_deref())?,
+    })?;
+
+    if let Some(path) = cli.config.clone() {
+        if let Err(e) = config::set_config_path(path) {
+            eprintln!("Error: {e}");
+            std::process::exit(output::EXIT_USAGE);
+        }
+    }
+    // An invalid config is reported by the command that needs it.
+    let configured_locale = config::load().ok().and_then(|c| c.ui.locale);
+    hydra_core::i18n::set_locale(hydra_core::i18n::Locale::resolve(
+        configured_locale.as_deref(),
+    ));
+
+    if let Some(path) = cli.output.clone() {
+        // These write their artifact to stdout or take over the terminal.
+        if matches!(
+            cli.command,
+            Commands::Completions { .. }
+                | Commands::Man { out_dir: None }
+                | Commands::Tui { .. }
+                | Commands::Serve { .. }
+                | Commands::Rpc
+                | Commands::Worker { .. }
+                | Commands::Replay { .. }
+        ) {
+            eprintln!("Error: --output is not supported by this command");
+            std::process::exit(output::EXIT_USAGE);
+        }
+        output::set_output_path(path)?;
+    }
+
+    if needs_trust(&cli.command) {
+        if let Err(e) = trust::ensure_trusted(!ci_mode) {
+            eprintln!("Error: {e}");
+            std::process::exit(if ci_mode {
+                output::EXIT_INFRA_FAILURE
+            } else {
+                output::EXIT_FAILURE
+            });
+        }
+    }
+
+    match cli.command {
+        Commands::Doctor {
+            json,
+            fix,
+            watch,
+            interval,
+        } => {
+            if watch {
+                return doctor::watch(std::time::Duration::from_secs(interval), json);
+            }
+
+            let report = doctor::build_report(fix);
+            output::emit(&report, json)?;
+            if !json {
                 doctor::print_human_report(&report);
             }
 
             if !report.healthy() {
-                std::process::exit(1);
+                std::process::exit(output::EXIT_FAILURE);
             }
         }
         Commands::Race {
             agents,
             prompt,
+            task,
+            variants,
+            from_issue,
+            from_pr,
             base_ref,
+            tags,
             json,
             unsafe_mode,
             allow_experimental_adapters,
             run_id,
+            deterministic,
+            wait,
+            force,
+            stop_on_first_success,
+            skip_baseline,
+            ci,
+            summary_file,
+            worker,
         } => {
+            let task = task.as_deref().map(task::load).transpose()?;
+            let variants = variants
+                .as_deref()
+                .map(variants::load)
+                .transpose()?
+                .unwrap_or_default();
+            let (prompt, prompt_source) = match &task {
+                Some(spec) => (spec.to_markdown(), None),
+                None if !variants.is_empty() => (variants::combined_prompt(&variants), None),
+                None => github::resolve_prompt(prompt, from_issue, from_pr)?,
+            };
             let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(race::run_race(race::RaceOpts {
+            let opts = race::RaceOpts {
                 agents,
                 prompt,
+                prompt_source,
+                tags: hydra_core::artifact::dedup_tags(tags),
+                task,
+                variants,
                 base_ref,
                 json,
                 unsafe_mode,
                 allow_experimental_adapters,
                 run_id,
+                deterministic,
+                lock: match (wait, force) {
+                    (_, true) => hydra_core::artifact::LockMode::Force,
+                    (true, _) => hydra_core::artifact::LockMode::Wait,
+                    _ => hydra_core::artifact::LockMode::Fail,
+                },
+                stop_on_first_success,
+                skip_baseline,
+                quiet: false,
+                config: None,
+            };
+            if ci {
+                let code = match rt.block_on(ci::run_race_ci(opts, summary_file)) {
+                    Ok(code) => code,
+                    Err(e) => {
+                        eprintln!("Error: {e:#}");
+                        output::EXIT_INFRA_FAILURE
+                    }
+                };
+                std::process::exit(code);
+            }
+            if let Some(url) = worker {
+                let manifest = rt.block_on(dispatch::run_remote_race(opts, &url))?;
+                run::run_show(Some(manifest.run_id), false, json)?;
+                if manifest.status != hydra_core::artifact::RunStatus::Completed {
+                    if !json {
+                        eprintln!("Error: race did not complete successfully");
+                    }
+                    std::process::exit(output::EXIT_FAILURE);
+                }
+                return Ok(());
+            }
+            let outcome = rt.block_on(race::run_race(opts))?;
+            exit_unless_completed(&outcome, json);
+        }
+        Commands::Rerun {
+            run_id,
+            agents,
+            head,
+            json,
+            unsafe_mode,
+            allow_experimental_adapters,
+        } => {
+            let opts = rerun::prepare_rerun(rerun::RerunOpts {
+                run_id,
+                agents,
+                head,
+                json,
+                unsafe_mode,
+                allow_experimental_adapters,
+            })?;
+            let rt = tokio::runtime::Runtime::new()?;
+            let outcome = rt.block_on(race::run_race(opts))?;
+            exit_unless_completed(&outcome, json);
+        }
+        Commands::Batch {
+            file,
+            parallel,
+            json,
+            unsafe_mode,
+            allow_experimental_adapters,
+        } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            let all_completed = rt.block_on(batch::run_batch(batch::BatchOpts {
+                file,
+                parallel,
+                json,
+                unsafe_mode,
+                allow_experimental_adapters,
+            }))?;
+            if !all_completed {
+                std::process::exit(output::EXIT_FAILURE);
+            }
+        }
+        Commands::Score {
+            target,
+            base_ref,
+            json,
+        } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            let mergeable = rt.block_on(score::run_score(score::ScoreOpts {
+                target,
+                base_ref,
+                json,
+            }))?;
+            if !mergeable {
+                std::process::exit(output::EXIT_NOT_MERGEABLE);
+            }
+        }
+        Commands::Bench {
+            suite,
+            agents,
+            base_ref,
+            json,
+            unsafe_mode,
+            allow_experimental_adapters,
+        } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(bench::run_bench(bench::BenchOpts {
+                suite,
+                agents,
+                base_ref,
+                json,
+                unsafe_mode,
+                allow_experimental_adapters,
             }))?;
         }
         Commands::Merge {
@@ -146,6 +906,7 @@ fn main() -> anyhow::Result<()> {
             dry_run,
             confirm,
             force,
+            only,
             json,
         } => {
             merge::run_merge(merge::MergeOpts {
@@ -155,12 +916,466 @@ fn main() -> anyhow::Result<()> {
                 confirm,
                 force,
                 json,
+                only,
             })?;
         }
+        Commands::Diff {
+            run_id,
+            latest,
+            agent,
+            stat,
+            name_only,
+            pager,
+        } => {
+            let mode = if stat {
+                diff::DiffMode::Stat
+            } else if name_only {
+                diff::DiffMode::NameOnly
+            } else {
+                diff::DiffMode::Patch
+            };
+            diff::run_diff(diff::DiffOpts {
+                run_id,
+                latest,
+                agent,
+                mode,
+                pager,
+            })?;
+        }
+        Commands::Cancel {
+            run_id,
+            latest,
+            agent,
+            json,
+        } => {
+            cancel::run_cancel(cancel::CancelOpts {
+                run_id,
+                latest,
+                agent,
+                json,
+            })?;
+        }
+        #[cfg(unix)]
+        Commands::Interactive {
+            agent,
+            prompt,
+            unsafe_mode,
+            allow_experimental_adapters,
+            detach,
+            handoff,
+            tags,
+            attach,
+            list,
+            json,
+            host,
+        } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            let ok = if let Some(session_id) = host {
+                rt.block_on(interactive::run_host(&session_id))?;
+                true
+            } else if let Some(session_id) = attach {
+                rt.block_on(interactive::run_attach(&session_id))?
+            } else if list {
+                interactive::run_list(&tags, json)?;
+                true
+            } else {
+                rt.block_on(interactive::run_interactive(interactive::InteractiveOpts {
+                    agent: agent.unwrap_or_default(),
+                    prompt,
+                    unsafe_mode,
+                    allow_experimental_adapters,
+                    detach,
+                    handoff,
+                    tags: hydra_core::artifact::dedup_tags(tags),
+                }))?
+            };
+            if !ok {
+                std::process::exit(output::EXIT_FAILURE);
+            }
+        }
         Commands::Run { command } => {
             run::run_command(command)?;
         }
+        Commands::Security { command } => security::run_command(command)?,
+        Commands::Trust { command } => trust::run_command(command)?,
+        Commands::Unsafe { command } => consent::run_command(command)?,
+        Commands::Worktrees { command } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            if !rt.block_on(worktrees::run_command(command))? {
+                std::process::exit(output::EXIT_FAILURE);
+            }
+        }
+        Commands::Watch {
+            run_id,
+            latest,
+            json,
+            poll_interval_ms,
+        } => {
+            watch::run_watch(watch::WatchOpts {
+                run_id,
+                latest,
+                json,
+                poll_interval_ms,
+            })?;
+        }
+        Commands::Timeline {
+            run_id,
+            latest,
+            json,
+            width,
+        } => {
+            timeline::run_timeline(timeline::TimelineOpts {
+                run_id,
+                latest,
+                json,
+                width,
+            })?;
+        }
+        Commands::Replay {
+            run_id,
+            speed,
+            json,
+            serve,
+            token,
+        } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(replay::run_replay(replay::ReplayOpts {
+                run_id,
+                speed,
+                json,
+                serve,
+                token,
+            }))?;
+        }
+        Commands::Setup { yes, force } => {
+            if !setup::run_setup(setup::SetupOpts { yes, force })? {
+                std::process::exit(output::EXIT_FAILURE);
+            }
+        }
+        Commands::Stats {
+            since,
+            agent,
+            tags,
+            json,
+            prometheus,
+        } => {
+            if prometheus {
+                let repo_root = run::discover_repo_root()?;
+                let rt = tokio::runtime::Runtime::new()?;
+                print!(
+                    "{}",
+                    rt.block_on(metrics::render(&repo_root, &config::load()?))?
+                );
+            } else {
+                stats::run_stats(stats::StatsOpts {
+                    since,
+                    agent,
+                    tags,
+                    json,
+                })?;
+            }
+        }
+        Commands::Completions { shell } => {
+            docs::write_completions(shell, &mut Cli::command(), &mut std::io::stdout());
+        }
+        Commands::Man { out_dir } => match out_dir {
+            Some(dir) => {
+                let pages = docs::write_man_pages(Cli::command(), &dir)?;
+                for path in &pages {
+                    println!("{}", path.display());
+                }
+                output::emit(&serde_json::json!({ "pages": pages }), false)?;
+            }
+            None => docs::write_man_page(Cli::command(), &mut std::io::stdout())?,
+        },
+        Commands::Tui { run_id } => {
+            tui::run_tui(tui::TuiOpts { run_id })?;
+        }
+        Commands::Serve { port, host, token } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(serve::run_serve(serve::ServeOpts { host, port, token }))?;
+        }
+        Commands::Rpc => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(rpc::run_rpc())?;
+        }
+        Commands::Worker { listen, token, dir } => {
+            let rt = tokio::runtime::Runtime::new()?;
+            rt.block_on(worker::run_worker(worker::WorkerOpts {
+                listen,
+                token,
+                dir,
+            }))?;
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn completions_cover_subcommands() {
+        for shell in [
+            clap_complete::Shell::Bash,
+            clap_complete::Shell::Zsh,
+            clap_complete::Shell::Fish,
+            clap_complete::Shell::PowerShell,
+        ] {
+            let mut out = Vec::new();
+            docs::write_completions(shell, &mut Cli::command(), &mut out);
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("race"), "{shell} completions missing race");
+            assert!(
+                script.contains("doctor"),
+                "{shell} completions missing doctor"
+            );
+        }
+    }
+
+    #[test]
+    fn man_pages_written_per_subcommand() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let pages = docs::write_man_pages(Cli::command(), tmp.path()).unwrap();
+        assert!(pages[0].ends_with("hydra.1"));
+        assert!(tmp.path().join("hydra-race.1").exists());
+        let race_page = std::fs::read_to_string(tmp.path().join("hydra-race.1")).unwrap();
+        assert!(race_page.contains("base\\-ref"));
+    }
+
+    #[test]
+    fn race_accepts_full_option_surface() {
+        let run_id = uuid::Uuid::new_v4();
+        let cli = Cli::try_parse_from([
+            "hydra",
+            "race",
+            "--agents",
+            "claude, codex",
+            "-p",
+            "fix the bug",
+            "--base-ref",
+            "main~1",
+            "--json",
+            "--unsafe",
+            "--allow-experimental-adapters",
+            "--run-id",
+            &run_id.to_string(),
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Race {
+                agents,
+                prompt,
+                base_ref,
+                json,
+                unsafe_mode,
+                allow_experimental_adapters,
+                run_id: parsed_run_id,
+                ..
+            } => {
+                assert_eq!(agents, vec!["claude", "codex"]);
+                assert_eq!(prompt.as_deref(), Some("fix the bug"));
+                assert_eq!(base_ref, "main~1");
+                assert!(json);
+                assert!(unsafe_mode);
+                assert!(allow_experimental_adapters);
+                assert_eq!(parsed_run_id, Some(run_id));
+            }
+            _ => panic!("expected race command"),
+        }
+    }
+
+    #[test]
+    fn race_prompt_can_come_from_github() {
+        let cli = Cli::try_parse_from(["hydra", "race", "--from-pr", "octo/hydra#456"]).unwrap();
+        match cli.command {
+            Commands::Race {
+                prompt, from_pr, ..
+            } => {
+                assert_eq!(prompt, None);
+                assert_eq!(from_pr.unwrap().number, 456);
+            }
+            _ => panic!("expected race command"),
+        }
+
+        assert!(Cli::try_parse_from(["hydra", "race"]).is_err());
+        assert!(
+            Cli::try_parse_from(["hydra", "race", "--from-issue", "1", "--from-pr", "2"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["hydra", "race", "--from-issue", "nope"]).is_err());
+    }
+
+    #[test]
+    fn race_prompt_can_come_from_a_task_or_variants_file() {
+        for flag in ["--task", "--variants"] {
+            assert!(Cli::try_parse_from(["hydra", "race", flag, "file.yaml"]).is_ok());
+            assert!(
+                Cli::try_parse_from(["hydra", "race", flag, "file.yaml", "-p", "task"]).is_err()
+            );
+        }
+        assert!(Cli::try_parse_from([
+            "hydra",
+            "race",
+            "--task",
+            "task.yaml",
+            "--variants",
+            "variants.yaml"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn race_worker_excludes_local_only_modes() {
+        let cli =
+            Cli::try_parse_from(["hydra", "race", "-p", "task", "--worker", "http://gpu:7701"])
+                .unwrap();
+        match cli.command {
+            Commands::Race { worker, .. } => assert_eq!(worker.as_deref(), Some("http://gpu:7701")),
+            _ => panic!("expected race command"),
+        }
+        for flag in ["--ci", "--unsafe"] {
+            assert!(
+                Cli::try_parse_from(["hydra", "race", "-p", "task", "--worker", "gpu", flag])
+                    .is_err()
+            );
+        }
+
+        let cli = Cli::try_parse_from(["hydra", "worker", "--listen", "0.0.0.0:9000"]).unwrap();
+        match cli.command {
+            Commands::Worker { listen, .. } => assert_eq!(listen.port(), 9000),
+            _ => panic!("expected worker command"),
+        }
+    }
+
+    #[test]
+    fn race_ci_flags_parse_and_summary_file_requires_ci() {
+        let cli = Cli::try_parse_from([
+            "hydra",
+            "race",
+            "-p",
+            "task",
+            "--ci",
+            "--summary-file",
+            "out/summary.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Race {
+                ci, summary_file, ..
+            } => {
+                assert!(ci);
+                assert_eq!(
+                    summary_file,
+                    Some(std::path::PathBuf::from("out/summary.json"))
+                );
+            }
+            _ => panic!("expected race command"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["hydra", "race", "-p", "task", "--summary-file", "x"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["hydra", "race", "-p", "task", "--ci", "--json"]).is_err());
+    }
+
+    #[test]
+    fn global_log_flags_parse_after_subcommand() {
+        let cli = Cli::try_parse_from([
+            "hydra",
+            "race",
+            "-p",
+            "task",
+            "-vv",
+            "--log-file",
+            "hydra.log",
+        ])
+        .unwrap();
+        assert_eq!(cli.verbose, 2);
+        assert!(!cli.quiet);
+        assert_eq!(cli.log_file, Some(std::path::PathBuf::from("hydra.log")));
+
+        assert!(Cli::try_parse_from(["hydra", "-q", "-v", "doctor"]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn interactive_modes_are_mutually_exclusive() {
+        let cli =
+            Cli::try_parse_from(["hydra", "interactive", "--agent", "claude", "-p", "fix it"])
+                .unwrap();
+        match cli.command {
+            Commands::Interactive { agent, prompt, .. } => {
+                assert_eq!(agent.as_deref(), Some("claude"));
+                assert_eq!(prompt.as_deref(), Some("fix it"));
+            }
+            _ => panic!("expected interactive command"),
+        }
+
+        assert!(Cli::try_parse_from(["hydra", "interactive", "--attach", "abc"]).is_ok());
+        assert!(Cli::try_parse_from(["hydra", "interactive", "--list", "--json"]).is_ok());
+        assert!(Cli::try_parse_from(["hydra", "interactive"]).is_err());
+        assert!(Cli::try_parse_from(["hydra", "interactive", "-p", "task"]).is_err());
+        assert!(Cli::try_parse_from([
+            "hydra",
+            "interactive",
+            "--agent",
+            "claude",
+            "--attach",
+            "abc"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn race_defaults_base_ref_and_agents() {
+        let cli = Cli::try_parse_from(["hydra", "race", "-p", "task"]).unwrap();
+        match cli.command {
+            Commands::Race {
+                agents, base_ref, ..
+            } => {
+                assert!(agents.is_empty());
+                assert_eq!(base_ref, "HEAD");
+            }
+            _ => panic!("expected race command"),
+        }
+    }
+
+    #[test]
+    fn race_rejects_invalid_agent_keys() {
+        for agents in ["claude,", "claude,../etc", "co dex"] {
+            let result = Cli::try_parse_from(["hydra", "race", "--agents", agents, "-p", "task"]);
+            assert!(result.is_err(), "agents '{agents}' should be rejected");
+        }
+    }
+
+    #[test]
+    fn race_rejects_blank_prompt_and_option_like_base_ref() {
+        assert!(Cli::try_parse_from(["hydra", "race", "-p", "   "]).is_err());
+        assert!(
+            Cli::try_parse_from(["hydra", "race", "-p", "task", "--base-ref=--upload-pack=x"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn only_agent_and_scoring_commands_need_trust() {
+        let needs = |args: &[&str]| needs_trust(&Cli::try_parse_from(args).unwrap().command);
+        assert!(needs(&["hydra", "race", "-p", "task"]));
+        assert!(needs(&["hydra", "score", "--target", "main"]));
+        assert!(needs(&["hydra", "interactive", "--agent", "claude"]));
+        assert!(needs(&["hydra", "serve"]));
+        assert!(needs(&["hydra", "rpc"]));
+        assert!(!needs(&["hydra", "interactive", "--list"]));
+        assert!(!needs(&["hydra", "doctor"]));
+        assert!(!needs(&["hydra", "security", "check", "--latest"]));
+        assert!(!needs(&["hydra", "trust", "add"]));
+    }
+}
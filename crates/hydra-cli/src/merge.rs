@@ -3,9 +3,12 @@ use std::path::{Path, PathBuf};
 use anyhow::{bail, Context, Result};
 use uuid::Uuid;
 
-use hydra_core::artifact::{RunLayout, RunManifest, RunStatus};
+use hydra_core::artifact::{read_agent_score, RunLayout, RunManifest, RunStatus};
 use hydra_core::git_ref::{validate_agent_key, validate_branch_name};
 use hydra_core::scoring::ranking::AgentScore;
+use hydra_core::tr;
+
+use crate::output;
 
 pub struct MergeOpts {
     pub run_id: Uuid,
@@ -14,11 +17,41 @@ pub struct MergeOpts {
     pub confirm: bool,
     pub force: bool,
     pub json: bool,
+    /// Merge only these repository-relative paths from the agent's changes.
+    pub only: Vec<String>,
 }
 
 enum MergeInput {
     Branch { branch: String },
-    DiffPatch { branch: String, patch_path: PathBuf },
+    DiffPatch(PatchInput),
+}
+
+struct PatchInput {
+    branch: String,
+    patch_path: PathBuf,
+    /// Paths to take from the patch; empty applies all of it.
+    include: Vec<String>,
+}
+
+impl PatchInput {
+    fn source(&self) -> &'static str {
+        if self.include.is_empty() {
+            "patch"
+        } else {
+            "selection"
+        }
+    }
+
+    /// `git apply <mode>` arguments for this patch.
+    fn apply_args(&self, mode: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = std::iter::once("apply")
+            .chain(mode.iter().copied())
+            .map(str::to_string)
+            .collect();
+        args.extend(self.include.iter().map(|path| format!("--include={path}")));
+        args.push(self.patch_path.to_string_lossy().to_string());
+        args
+    }
 }
 
 pub fn run_merge(opts: MergeOpts) -> Result<()> {
@@ -27,22 +60,22 @@ pub fn run_merge(opts: MergeOpts) -> Result<()> {
     let layout = RunLayout::new(&hydra_root, opts.run_id);
 
     if !layout.base_dir().exists() {
-        bail!(
-            "run {} not found at {}",
-            opts.run_id,
-            layout.base_dir().display()
-        );
+        bail!(tr!(
+            "merge.run_not_found",
+            run_id = opts.run_id,
+            path = layout.base_dir().display()
+        ));
     }
 
     let manifest =
         RunManifest::read_from(&layout.manifest_path()).context("failed to read manifest")?;
 
     if manifest.status != RunStatus::Completed {
-        bail!(
-            "run {} has status {:?}, not Completed",
-            opts.run_id,
-            manifest.status
-        );
+        bail!(tr!(
+            "merge.not_completed",
+            run_id = opts.run_id,
+            status = format!("{:?}", manifest.status)
+        ));
     }
 
     let agent_key = match &opts.agent {
@@ -55,16 +88,22 @@ pub fn run_merge(opts: MergeOpts) -> Result<()> {
         .agents
         .iter()
         .find(|a| a.agent_key == agent_key)
-        .ok_or_else(|| anyhow::anyhow!("agent '{}' not found in run {}", agent_key, opts.run_id))?;
+        .ok_or_else(|| {
+            anyhow::anyhow!(tr!(
+                "merge.agent_not_in_run",
+                agent = agent_key,
+                run_id = opts.run_id
+            ))
+        })?;
 
     if !opts.force {
         if let Ok(score) = load_agent_score(&layout, &agent_key) {
             if !score.mergeable {
-                bail!(
-                    "agent '{}' is not mergeable (gate failures: {}). Use --force to override",
-                    agent_key,
-                    score.gate_failures.join(", ")
-                );
+                bail!(tr!(
+                    "merge.not_mergeable",
+                    agent = agent_key,
+                    failures = score.gate_failures.join(", ")
+                ));
             }
         }
     }
@@ -75,14 +114,23 @@ pub fn run_merge(opts: MergeOpts) -> Result<()> {
     // Pre-flight safety checks
     check_not_in_merge_state(&repo_root)?;
     check_clean_working_tree(&repo_root)?;
-    let merge_input = resolve_merge_input(&repo_root, &layout, &agent_key, &branch)?;
+    // Manifests from before commit detection say nothing; trust the branch.
+    let branch_is_complete = manifest
+        .candidate_commits
+        .get(&agent_key)
+        .is_none_or(|commits| commits.strategy.branch_is_complete());
+    let mut merge_input =
+        resolve_merge_input(&repo_root, &layout, &agent_key, &branch, branch_is_complete)?;
+    if !opts.only.is_empty() {
+        merge_input = select_paths(&repo_root, &layout, &agent_key, merge_input, &opts.only)?;
+    }
 
     if opts.dry_run {
         return run_dry_merge(&repo_root, &merge_input, &layout, &agent_key, opts.json);
     }
 
     if !opts.confirm {
-        bail!("merge requires --confirm flag (or use --dry-run to preview)");
+        bail!(tr!("merge.needs_confirm"));
     }
 
     run_real_merge(
@@ -106,32 +154,88 @@ fn validate_merge_inputs(agent_key: &str, branch: &str) -> Result<()> {
         .map_err(|e| anyhow::anyhow!("invalid branch name '{}': {}", branch, e))
 }
 
+/// The agent's branch when it exists and holds all of its changes,
+/// otherwise its `diff.patch`, which also covers what it left uncommitted.
 fn resolve_merge_input(
     repo_root: &Path,
     layout: &RunLayout,
     agent_key: &str,
     branch: &str,
+    branch_is_complete: bool,
 ) -> Result<MergeInput> {
-    if branch_exists(repo_root, branch)? {
+    let diff_path = layout.agent_diff(agent_key);
+    if branch_exists(repo_root, branch)? && (branch_is_complete || !diff_path.exists()) {
         return Ok(MergeInput::Branch {
             branch: branch.to_string(),
         });
     }
 
-    let diff_path = layout.agent_diff(agent_key);
     if diff_path.exists() {
-        return Ok(MergeInput::DiffPatch {
+        // Binary and oversized files were left out of the artifact, so
+        // applying it would silently drop them.
+        let omitted = layout.agent_diff_omitted(agent_key);
+        if omitted.exists() {
+            bail!(tr!(
+                "merge.diff_incomplete",
+                branch = branch,
+                path = omitted.display()
+            ));
+        }
+        return Ok(MergeInput::DiffPatch(PatchInput {
             branch: branch.to_string(),
             patch_path: diff_path,
-        });
+            include: Vec::new(),
+        }));
     }
 
-    bail!(
-        "branch '{}' does not exist and no persisted diff artifact found at {}. \
-         The worktree may have been cleaned up already",
-        branch,
-        layout.agent_diff(agent_key).display()
-    );
+    bail!(tr!(
+        "merge.no_source",
+        branch = branch,
+        path = layout.agent_diff(agent_key).display()
+    ));
+}
+
+/// Narrow `input` to `paths`. A branch is turned into a patch of its
+/// changes since it forked from HEAD, so the result is a plain commit of the
+/// selected files rather than a merge of the whole branch.
+fn select_paths(
+    repo_root: &Path,
+    layout: &RunLayout,
+    agent_key: &str,
+    input: MergeInput,
+    paths: &[String],
+) -> Result<MergeInput> {
+    for path in paths {
+        if path.is_empty() || Path::new(path).is_absolute() || path.split('/').any(|c| c == "..") {
+            bail!("invalid merge path '{path}': expected a repository-relative path");
+        }
+    }
+    let include = paths.to_vec();
+
+    match input {
+        MergeInput::Branch { branch } => {
+            let output = std::process::Command::new("git")
+                .args(["diff", "--binary", &format!("HEAD...{branch}")])
+                .current_dir(repo_root)
+                .output()
+                .context("failed to run git diff for selective merge")?;
+            if !output.status.success() {
+                bail!(
+                    "git diff HEAD...{branch} failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            let patch_path = layout.agent_dir(agent_key).join("selection.patch");
+            std::fs::write(&patch_path, &output.stdout)
+                .context("failed to write selective merge patch")?;
+            Ok(MergeInput::DiffPatch(PatchInput {
+                branch,
+                patch_path,
+                include,
+            }))
+        }
+        MergeInput::DiffPatch(patch) => Ok(MergeInput::DiffPatch(PatchInput { include, ..patch })),
+    }
 }
 
 fn run_dry_merge(
@@ -187,20 +291,19 @@ fn run_dry_merge(
                 "branch".to_string(),
             )
         }
-        MergeInput::DiffPatch { branch, patch_path } => {
-            let patch_arg = patch_path.to_string_lossy().to_string();
+        MergeInput::DiffPatch(patch) => {
             let merge_output = std::process::Command::new("git")
-                .args(["apply", "--check", "--3way", &patch_arg])
+                .args(patch.apply_args(&["--check", "--3way"]))
                 .current_dir(repo_root)
                 .output()
                 .context("failed to run git apply --check")?;
 
             (
-                branch.as_str(),
+                patch.branch.as_str(),
                 String::from_utf8_lossy(&merge_output.stdout).to_string(),
                 String::from_utf8_lossy(&merge_output.stderr).to_string(),
                 !merge_output.status.success(),
-                "patch".to_string(),
+                patch.source().to_string(),
             )
         }
     };
@@ -215,25 +318,45 @@ fn run_dry_merge(
         stderr: merge_stderr.clone(),
         source: source.clone(),
     };
-    let (report_path, report_json) = write_merge_report(layout, &report)?;
+    let (report_path, _) = write_merge_report(layout, &report)?;
 
+    output::emit(&report, json)?;
     if json {
-        println!("{report_json}");
-    } else if has_conflicts {
+        return Ok(());
+    }
+    if has_conflicts {
         println!(
-            "Dry-run merge of '{agent_key}' source '{source}' targeting '{branch}': CONFLICTS DETECTED"
+            "{}",
+            tr!(
+                "merge.dry_run_conflicts",
+                agent = agent_key,
+                source = source,
+                branch = branch
+            )
         );
         println!();
         if !merge_stderr.is_empty() {
             println!("{merge_stderr}");
         }
-        println!("Report saved to: {}", report_path.display());
-        std::process::exit(1);
+        println!(
+            "{}",
+            tr!("merge.report_saved", path = report_path.display())
+        );
+        std::process::exit(output::EXIT_FAILURE);
     } else {
         println!(
-            "Dry-run merge of '{agent_key}' source '{source}' targeting '{branch}': clean merge (no conflicts)"
+            "{}",
+            tr!(
+                "merge.dry_run_clean",
+                agent = agent_key,
+                source = source,
+                branch = branch
+            )
+        );
+        println!(
+            "{}",
+            tr!("merge.report_saved", path = report_path.display())
         );
-        println!("Report saved to: {}", report_path.display());
     }
 
     Ok(())
@@ -251,9 +374,9 @@ fn run_real_merge(
         MergeInput::Branch { branch } => {
             run_real_branch_merge(repo_root, branch, layout, run_id, agent_key, json)
         }
-        MergeInput::DiffPatch { branch, patch_path } => run_real_patch_merge(
-            repo_root, branch, patch_path, layout, run_id, agent_key, json,
-        ),
+        MergeInput::DiffPatch(patch) => {
+            run_real_patch_merge(repo_root, patch, layout, run_id, agent_key, json)
+        }
     }
 }
 
@@ -292,26 +415,27 @@ fn run_real_branch_merge(
             stderr: stderr.clone(),
             source: "branch".to_string(),
         };
-        let (_report_path, report_json) = write_merge_report(layout, &report)?;
+        write_merge_report(layout, &report)?;
 
-        if json {
-            println!("{report_json}");
-        } else {
-            eprintln!("Merge failed: {}", stderr.trim());
+        output::emit(&report, json)?;
+        if !json {
+            eprintln!("{}", tr!("merge.failed", stderr = stderr.trim()));
         }
-        std::process::exit(1);
+        std::process::exit(output::EXIT_FAILURE);
     }
 
-    if json {
-        let report = serde_json::json!({
-            "agent": agent_key,
-            "branch": branch,
-            "success": true,
-            "message": message,
-        });
-        println!("{}", serde_json::to_string_pretty(&report)?);
-    } else {
-        println!("Merged '{agent_key}' branch '{branch}'");
+    let result = serde_json::json!({
+        "agent": agent_key,
+        "branch": branch,
+        "success": true,
+        "message": message,
+    });
+    output::emit(&result, json)?;
+    if !json {
+        println!(
+            "{}",
+            tr!("merge.merged", agent = agent_key, branch = branch)
+        );
         if !stdout.is_empty() {
             println!("{stdout}");
         }
@@ -322,17 +446,17 @@ fn run_real_branch_merge(
 
 fn run_real_patch_merge(
     repo_root: &Path,
-    branch: &str,
-    patch_path: &Path,
+    patch: &PatchInput,
     layout: &RunLayout,
     run_id: Uuid,
     agent_key: &str,
     json: bool,
 ) -> Result<()> {
-    let patch_arg = patch_path.to_string_lossy().to_string();
+    let branch = patch.branch.as_str();
+    let source = patch.source();
 
     let check_output = std::process::Command::new("git")
-        .args(["apply", "--check", "--3way", &patch_arg])
+        .args(patch.apply_args(&["--check", "--3way"]))
         .current_dir(repo_root)
         .output()
         .context("failed to run git apply --check before merge")?;
@@ -348,20 +472,19 @@ fn run_real_patch_merge(
             has_conflicts: true,
             stdout: stdout.clone(),
             stderr: stderr.clone(),
-            source: "patch".to_string(),
+            source: source.to_string(),
         };
-        let (_report_path, report_json) = write_merge_report(layout, &report)?;
+        write_merge_report(layout, &report)?;
 
-        if json {
-            println!("{report_json}");
-        } else {
-            eprintln!("Merge failed: {}", stderr.trim());
+        output::emit(&report, json)?;
+        if !json {
+            eprintln!("{}", tr!("merge.failed", stderr = stderr.trim()));
         }
-        std::process::exit(1);
+        std::process::exit(output::EXIT_FAILURE);
     }
 
     let apply_output = std::process::Command::new("git")
-        .args(["apply", "--index", "--3way", &patch_arg])
+        .args(patch.apply_args(&["--index", "--3way"]))
         .current_dir(repo_root)
         .output()
         .context("failed to run git apply --index")?;
@@ -377,16 +500,15 @@ fn run_real_patch_merge(
             has_conflicts: true,
             stdout: stdout.clone(),
             stderr: stderr.clone(),
-            source: "patch".to_string(),
+            source: source.to_string(),
         };
-        let (_report_path, report_json) = write_merge_report(layout, &report)?;
+        write_merge_report(layout, &report)?;
 
-        if json {
-            println!("{report_json}");
-        } else {
-            eprintln!("Merge failed: {}", stderr.trim());
+        output::emit(&report, json)?;
+        if !json {
+            eprintln!("{}", tr!("merge.failed", stderr = stderr.trim()));
         }
-        std::process::exit(1);
+        std::process::exit(output::EXIT_FAILURE);
     }
 
     let staged_status = std::process::Command::new("git")
@@ -414,39 +536,50 @@ fn run_real_patch_merge(
                 has_conflicts: false,
                 stdout: stdout.clone(),
                 stderr: stderr.clone(),
-                source: "patch".to_string(),
+                source: source.to_string(),
             };
-            let (_report_path, report_json) = write_merge_report(layout, &report)?;
+            write_merge_report(layout, &report)?;
 
-            if json {
-                println!("{report_json}");
-            } else {
-                eprintln!("Merge commit failed: {}", stderr.trim());
+            output::emit(&report, json)?;
+            if !json {
+                eprintln!("{}", tr!("merge.commit_failed", stderr = stderr.trim()));
             }
-            std::process::exit(1);
+            std::process::exit(output::EXIT_FAILURE);
         }
     }
 
+    let result = serde_json::json!({
+        "agent": agent_key,
+        "branch": branch,
+        "success": true,
+        "source": source,
+        "message": if has_staged_changes {
+            message.clone()
+        } else {
+            format!("No changes to apply for '{}' (patch already present)", agent_key)
+        },
+    });
+    output::emit(&result, json)?;
     if json {
-        let report = serde_json::json!({
-            "agent": agent_key,
-            "branch": branch,
-            "success": true,
-            "message": if has_staged_changes {
-                message.clone()
-            } else {
-                format!("No changes to apply for '{}' (patch already present)", agent_key)
-            },
-        });
-        println!("{}", serde_json::to_string_pretty(&report)?);
-    } else if has_staged_changes {
+        return Ok(());
+    }
+    if has_staged_changes && !patch.include.is_empty() {
         println!(
-            "Merged '{agent_key}' by applying persisted patch artifact (branch '{branch}' was unavailable)"
+            "{}",
+            tr!(
+                "merge.merged_paths",
+                count = patch.include.len(),
+                agent = agent_key,
+                branch = branch
+            )
         );
-    } else {
+    } else if has_staged_changes {
         println!(
-            "No changes applied for '{agent_key}' because the patch already matches the current tree"
+            "{}",
+            tr!("merge.merged_patch", agent = agent_key, branch = branch)
         );
+    } else {
+        println!("{}", tr!("merge.no_changes", agent = agent_key));
     }
 
     Ok(())
@@ -481,27 +614,18 @@ fn pick_winner(layout: &RunLayout, manifest: &RunManifest) -> Result<String> {
         }
     }
 
-    best.map(|(k, _)| k).ok_or_else(|| {
-        anyhow::anyhow!("no mergeable agent found in run. Use --agent to specify explicitly")
-    })
+    best.map(|(k, _)| k)
+        .ok_or_else(|| anyhow::anyhow!(tr!("merge.no_winner")))
 }
 
 fn load_agent_score(layout: &RunLayout, agent_key: &str) -> Result<AgentScore> {
-    let path = layout.agent_score(agent_key);
-    if !path.exists() {
-        bail!("score file not found for agent '{agent_key}'");
-    }
-    let data = std::fs::read_to_string(&path)?;
-    let score: AgentScore = serde_json::from_str(&data)?;
-    Ok(score)
+    read_agent_score(layout, agent_key)?
+        .ok_or_else(|| anyhow::anyhow!("score file not found for agent '{agent_key}'"))
 }
 
 fn check_not_in_merge_state(repo_root: &Path) -> Result<()> {
     if merge_head_exists(repo_root)? {
-        bail!(
-            "repository is already in a merge state. \
-             Resolve or abort the current merge before running hydra merge"
-        );
+        bail!(tr!("merge.in_merge_state"));
     }
     Ok(())
 }
@@ -539,12 +663,10 @@ fn check_clean_working_tree(repo_root: &Path) -> Result<()> {
         } else {
             String::new()
         };
-        bail!(
-            "working tree has uncommitted changes in: {}{}. \
-             Commit or stash changes before running hydra merge",
-            shown.join(", "),
-            suffix
-        );
+        bail!(tr!(
+            "merge.dirty_tree",
+            files = format!("{}{suffix}", shown.join(", "))
+        ));
     }
     Ok(())
 }
@@ -566,7 +688,7 @@ fn is_hydra_artifact_path(path: &str) -> bool {
     normalized == ".hydra" || normalized.starts_with(".hydra/")
 }
 
-fn branch_exists(repo_root: &Path, branch: &str) -> Result<bool> {
+pub(crate) fn branch_exists(repo_root: &Path, branch: &str) -> Result<bool> {
     let output = std::process::Command::new("git")
         .args(["rev-parse", "--verify", &format!("refs/heads/{branch}")])
         .current_dir(repo_root)
@@ -642,7 +764,7 @@ mod tests {
 
     use super::{
         check_clean_working_tree, is_hydra_artifact_path, parse_porcelain_path,
-        validate_merge_inputs, write_merge_report, MergeReport,
+        resolve_merge_input, validate_merge_inputs, write_merge_report, MergeInput, MergeReport,
     };
 
     #[test]
@@ -725,4 +847,74 @@ mod tests {
             serde_json::from_str(&json).expect("written report should be valid JSON");
         assert_eq!(parsed["agent"], "claude");
     }
+
+    #[test]
+    fn incomplete_diff_artifact_is_not_merged() {
+        let temp = tempdir().expect("tempdir");
+        let repo = temp.path();
+        let status = Command::new("git")
+            .args(["init"])
+            .current_dir(repo)
+            .status()
+            .expect("git init");
+        assert!(status.success());
+
+        let layout = RunLayout::new(&repo.join(".hydra"), Uuid::new_v4());
+        layout.create_dirs(&["claude"]).expect("create run dirs");
+        fs::write(layout.agent_diff("claude"), "diff --git a/x b/x\n").expect("write diff");
+        let branch = "hydra/run/agent/claude";
+        assert!(resolve_merge_input(repo, &layout, "claude", branch, true).is_ok());
+
+        fs::write(layout.agent_diff_omitted("claude"), "[]").expect("write omitted list");
+        let err = resolve_merge_input(repo, &layout, "claude", branch, true)
+            .err()
+            .expect("incomplete diff should not be merged");
+        assert!(err
+            .to_string()
+            .contains("leaves out binary or oversized files"));
+    }
+
+    #[test]
+    fn patch_is_preferred_when_the_branch_lacks_uncommitted_work() {
+        let temp = tempdir().expect("tempdir");
+        let repo = temp.path();
+        assert!(Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(repo)
+            .status()
+            .expect("git init")
+            .success());
+        let branch = "hydra/run/agent/claude";
+        assert!(Command::new("git")
+            .args([
+                "-c",
+                "user.name=T",
+                "-c",
+                "user.email=t@example.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "base",
+            ])
+            .current_dir(repo)
+            .status()
+            .expect("git commit")
+            .success());
+        assert!(Command::new("git")
+            .args(["branch", branch])
+            .current_dir(repo)
+            .status()
+            .expect("git branch")
+            .success());
+
+        let layout = RunLayout::new(&repo.join(".hydra"), Uuid::new_v4());
+        layout.create_dirs(&["claude"]).expect("create run dirs");
+        fs::write(layout.agent_diff("claude"), "diff --git a/x b/x\n").expect("write diff");
+
+        let complete = resolve_merge_input(repo, &layout, "claude", branch, true).unwrap();
+        assert!(matches!(complete, MergeInput::Branch { .. }));
+        let partial = resolve_merge_input(repo, &layout, "claude", branch, false).unwrap();
+        assert!(matches!(partial, MergeInput::DiffPatch(_)));
+    }
 }
@@ -0,0 +1,424 @@
+//! Prometheus metrics for machines that run hydra as a service.
+//!
+//! Everything is derived from `.hydra/runs` and the repository's worktrees
+//! at scrape time, so counters survive restarts of `hydra serve` and cover
+//! races started from any front end. Served at `GET /metrics` by
+//! `hydra serve`; `hydra stats --prometheus` prints the same text for the
+//! node exporter's textfile collector or a Pushgateway.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use hydra_core::artifact::{EventKind, EventReader, RunEvent, RunLayout, RunManifest, RunStatus};
+use hydra_core::config::HydraConfig;
+use hydra_core::worktree::WorktreeService;
+
+use crate::run::load_agent_score;
+
+/// `Content-Type` of the exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const DURATION_BUCKETS: &[f64] = &[30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0];
+const SCORE_BUCKETS: &[f64] = &[10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 100.0];
+
+#[derive(Default)]
+struct Metrics {
+    runs: BTreeMap<&'static str, u64>,
+    agent_runs: BTreeMap<(String, String), u64>,
+    mergeable: BTreeMap<String, u64>,
+    tokens: BTreeMap<String, f64>,
+    cost_usd: BTreeMap<String, f64>,
+    timeouts: BTreeMap<(String, String), u64>,
+    durations: BTreeMap<String, Histogram>,
+    scores: BTreeMap<String, Histogram>,
+}
+
+struct Histogram {
+    bounds: &'static [f64],
+    /// Observations at or below each bound; not yet cumulative.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(i) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.counts[i] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Render the metrics for the repository at `repo_root`.
+pub async fn render(repo_root: &Path, config: &HydraConfig) -> Result<String> {
+    let metrics = collect(&repo_root.join(".hydra"))?;
+    let service = WorktreeService::new(
+        repo_root.to_path_buf(),
+        repo_root.join(&config.worktree.base_dir),
+    );
+    let worktrees = service
+        .list()
+        .await
+        .context("failed to list worktrees")?
+        .iter()
+        .filter(|entry| entry.agent_info().is_some())
+        .count();
+    Ok(metrics.render(worktrees))
+}
+
+fn collect(hydra_root: &Path) -> Result<Metrics> {
+    let mut metrics = Metrics::default();
+    for run_id in RunLayout::list_runs(hydra_root).context("failed to list runs")? {
+        let layout = RunLayout::new(hydra_root, run_id);
+        let Ok(manifest) = RunManifest::read_from(&layout.manifest_path()) else {
+            continue;
+        };
+        *metrics
+            .runs
+            .entry(status_label(&manifest.status))
+            .or_default() += 1;
+
+        let run_events = EventReader::read_all(&layout.events_path()).unwrap_or_default();
+        for agent in &manifest.agents {
+            let key = agent.agent_key.clone();
+            let agent_events =
+                EventReader::read_all(&layout.agent_events(&key)).unwrap_or_default();
+
+            if let Some(event) = terminal_event(&run_events, &key) {
+                let status = event
+                    .data
+                    .get("status")
+                    .and_then(|v| v.as_str())
+                    .map(snake_case)
+                    .unwrap_or_else(|| "unknown".to_string());
+                *metrics.agent_runs.entry((key.clone(), status)).or_default() += 1;
+                if let Some(tokens) = event.data.get("total_tokens").and_then(|v| v.as_f64()) {
+                    *metrics.tokens.entry(key.clone()).or_default() += tokens;
+                }
+                if let Some(cost) = event
+                    .data
+                    .get("estimated_cost_usd")
+                    .and_then(|v| v.as_f64())
+                {
+                    *metrics.cost_usd.entry(key.clone()).or_default() += cost;
+                }
+            }
+
+            if let Some(event) = terminal_event(&agent_events, &key) {
+                if let Some(ms) = event.data.get("duration_ms").and_then(|v| v.as_f64()) {
+                    metrics
+                        .durations
+                        .entry(key.clone())
+                        .or_insert_with(|| Histogram::new(DURATION_BUCKETS))
+                        .observe(ms / 1000.0);
+                }
+                // The supervisor reports timeouts as "timed out (<kind>)".
+                let timeout_kind = event
+                    .data
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .and_then(|e| e.strip_prefix("timed out ("))
+                    .and_then(|rest| rest.strip_suffix(')'));
+                if let Some(kind) = timeout_kind {
+                    *metrics
+                        .timeouts
+                        .entry((key.clone(), kind.to_string()))
+                        .or_default() += 1;
+                }
+            }
+
+            if let Some(score) = load_agent_score(&layout, &key)? {
+                metrics
+                    .scores
+                    .entry(key.clone())
+                    .or_insert_with(|| Histogram::new(SCORE_BUCKETS))
+                    .observe(score.composite);
+                if score.mergeable {
+                    *metrics.mergeable.entry(key).or_default() += 1;
+                }
+            }
+        }
+    }
+    Ok(metrics)
+}
+
+impl Metrics {
+    fn render(&self, worktrees: usize) -> String {
+        let mut out = Exposition::default();
+
+        out.family("hydra_runs_total", "counter", "Runs by current status.");
+        for (status, count) in &self.runs {
+            out.sample("hydra_runs_total", &[("status", status)], *count as f64);
+        }
+
+        out.family(
+            "hydra_agent_runs_total",
+            "counter",
+            "Finished agent runs by outcome.",
+        );
+        for ((agent, status), count) in &self.agent_runs {
+            out.sample(
+                "hydra_agent_runs_total",
+                &[("agent", agent), ("status", status)],
+                *count as f64,
+            );
+        }
+
+        out.family(
+            "hydra_agent_mergeable_total",
+            "counter",
+            "Agent runs whose score passed every merge gate.",
+        );
+        for (agent, count) in &self.mergeable {
+            out.sample(
+                "hydra_agent_mergeable_total",
+                &[("agent", agent)],
+                *count as f64,
+            );
+        }
+
+        out.family(
+            "hydra_agent_tokens_total",
+            "counter",
+            "Tokens used by agents.",
+        );
+        for (agent, tokens) in &self.tokens {
+            out.sample("hydra_agent_tokens_total", &[("agent", agent)], *tokens);
+        }
+
+        out.family(
+            "hydra_agent_cost_usd_total",
+            "counter",
+            "Estimated agent cost in US dollars.",
+        );
+        for (agent, cost) in &self.cost_usd {
+            out.sample("hydra_agent_cost_usd_total", &[("agent", agent)], *cost);
+        }
+
+        out.family(
+            "hydra_supervisor_timeouts_total",
+            "counter",
+            "Agents stopped by a supervisor timeout, by timeout kind.",
+        );
+        for ((agent, kind), count) in &self.timeouts {
+            out.sample(
+                "hydra_supervisor_timeouts_total",
+                &[("agent", agent), ("kind", kind)],
+                *count as f64,
+            );
+        }
+
+        out.family(
+            "hydra_agent_duration_seconds",
+            "histogram",
+            "Wall-clock time of agent runs.",
+        );
+        for (agent, histogram) in &self.durations {
+            out.histogram("hydra_agent_duration_seconds", agent, histogram);
+        }
+
+        out.family(
+            "hydra_score_composite",
+            "histogram",
+            "Composite scores of scored agent runs (0-100).",
+        );
+        for (agent, histogram) in &self.scores {
+            out.histogram("hydra_score_composite", agent, histogram);
+        }
+
+        out.family("hydra_worktrees", "gauge", "Agent worktrees on disk.");
+        out.sample("hydra_worktrees", &[], worktrees as f64);
+
+        out.0
+    }
+}
+
+#[derive(Default)]
+struct Exposition(String);
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+
+    fn histogram(&mut self, name: &str, agent: &str, histogram: &Histogram) {
+        let bucket = format!("{name}_bucket");
+        let mut cumulative = 0;
+        for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+            cumulative += count;
+            let le = bound.to_string();
+            self.sample(&bucket, &[("agent", agent), ("le", &le)], cumulative as f64);
+        }
+        self.sample(
+            &bucket,
+            &[("agent", agent), ("le", "+Inf")],
+            histogram.count as f64,
+        );
+        self.sample(&format!("{name}_sum"), &[("agent", agent)], histogram.sum);
+        self.sample(
+            &format!("{name}_count"),
+            &[("agent", agent)],
+            histogram.count as f64,
+        );
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn status_label(status: &RunStatus) -> &'static str {
+    match status {
+        RunStatus::Running => "running",
+        RunStatus::Completed => "completed",
+        RunStatus::Failed => "failed",
+        RunStatus::TimedOut => "timed_out",
+        RunStatus::Interrupted => "interrupted",
+    }
+}
+
+/// Agent outcomes are recorded as `RunStatus` debug names (`TimedOut`).
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The agent's last completed/failed event.
+fn terminal_event<'a>(events: &'a [RunEvent], agent_key: &str) -> Option<&'a RunEvent> {
+    events
+        .iter()
+        .rev()
+        .filter(|e| matches!(e.kind, EventKind::AgentCompleted | EventKind::AgentFailed))
+        .find(|e| e.agent_key.as_deref() == Some(agent_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::EventWriter;
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+
+    fn write_run(
+        hydra_root: &Path,
+        status: RunStatus,
+        agent_events: &[(EventKind, serde_json::Value)],
+    ) {
+        let (layout, mut manifest) = new_run(hydra_root, &["claude"]);
+        manifest.mark_completed(status.clone());
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let mut run_events = EventWriter::create(&layout.events_path()).unwrap();
+        run_events
+            .write_event(&RunEvent::new(
+                EventKind::AgentCompleted,
+                Some("claude".to_string()),
+                serde_json::json!({
+                    "status": format!("{status:?}"),
+                    "total_tokens": 1500,
+                    "estimated_cost_usd": 0.25,
+                }),
+            ))
+            .unwrap();
+        let mut events = EventWriter::create(&layout.agent_events("claude")).unwrap();
+        for (kind, data) in agent_events {
+            events
+                .write_event(&RunEvent::new(
+                    kind.clone(),
+                    Some("claude".to_string()),
+                    data.clone(),
+                ))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn metrics_aggregate_runs_into_counters_and_histograms() {
+        let tmp = TempDir::new().unwrap();
+        let hydra_root = tmp.path().join(".hydra");
+        write_run(
+            &hydra_root,
+            RunStatus::Completed,
+            &[(
+                EventKind::AgentCompleted,
+                serde_json::json!({ "duration_ms": 45_000 }),
+            )],
+        );
+        write_run(
+            &hydra_root,
+            RunStatus::TimedOut,
+            &[(
+                EventKind::AgentFailed,
+                serde_json::json!({ "duration_ms": 4_000_000, "error": "timed out (hard)" }),
+            )],
+        );
+
+        let text = collect(&hydra_root).unwrap().render(3);
+        for line in [
+            "# TYPE hydra_runs_total counter",
+            "hydra_runs_total{status=\"completed\"} 1",
+            "hydra_runs_total{status=\"timed_out\"} 1",
+            "hydra_agent_runs_total{agent=\"claude\",status=\"timed_out\"} 1",
+            "hydra_agent_tokens_total{agent=\"claude\"} 3000",
+            "hydra_agent_cost_usd_total{agent=\"claude\"} 0.5",
+            "hydra_supervisor_timeouts_total{agent=\"claude\",kind=\"hard\"} 1",
+            "hydra_agent_duration_seconds_bucket{agent=\"claude\",le=\"30\"} 0",
+            "hydra_agent_duration_seconds_bucket{agent=\"claude\",le=\"60\"} 1",
+            "hydra_agent_duration_seconds_bucket{agent=\"claude\",le=\"3600\"} 1",
+            "hydra_agent_duration_seconds_bucket{agent=\"claude\",le=\"+Inf\"} 2",
+            "hydra_agent_duration_seconds_count{agent=\"claude\"} 2",
+            "hydra_worktrees 3",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line:?} in:\n{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+        assert_eq!(snake_case("TimedOut"), "timed_out");
+    }
+}
@@ -0,0 +1,639 @@
+//! Span export over OTLP/HTTP with JSON encoding, so long races can be
+//! inspected in an existing tracing backend (Jaeger, Tempo, Honeycomb, an
+//! OpenTelemetry Collector, ...).
+//!
+//! Spans opened by hydra-core under a `race` span share a trace id derived
+//! from the run id, so a run can be looked up by the id `hydra` prints.
+//! Only plain `http://` endpoints are supported; send to a local collector
+//! to reach a TLS backend.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context as LayerContext;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+use uuid::Uuid;
+
+pub const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+pub const TRACES_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+const HEADERS_ENV: &str = "OTEL_EXPORTER_OTLP_HEADERS";
+const SERVICE_NAME_ENV: &str = "OTEL_SERVICE_NAME";
+
+/// Spans are sent at least this often while hydra runs.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_BATCH: usize = 512;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long closing a root span waits for its trace to be sent.
+const FLUSH_WAIT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    /// Resolve the endpoint from `--otlp-endpoint`, then the standard
+    /// OpenTelemetry variables. `None` when export is not configured.
+    pub fn resolve(flag: Option<&str>) -> Result<Option<Self>> {
+        if let Some(url) = flag {
+            return Self::parse(url, true).map(Some);
+        }
+        if let Some(url) = non_empty_env(TRACES_ENDPOINT_ENV) {
+            return Self::parse(&url, false)
+                .with_context(|| format!("invalid {TRACES_ENDPOINT_ENV}"))
+                .map(Some);
+        }
+        if let Some(url) = non_empty_env(ENDPOINT_ENV) {
+            return Self::parse(&url, true)
+                .with_context(|| format!("invalid {ENDPOINT_ENV}"))
+                .map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Parse an `http://host[:port][/path]` URL. A base endpoint has
+    /// `/v1/traces` appended, as the OpenTelemetry spec requires for
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    fn parse(url: &str, base: bool) -> Result<Self> {
+        let url = url.trim();
+        let rest = match url.strip_prefix("http://") {
+            Some(rest) => rest,
+            None if url.starts_with("https://") => {
+                bail!("https endpoints are not supported; export to a local collector over http")
+            }
+            None => bail!("expected an http:// URL, got '{url}'"),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+        let (host, port) = split_host_port(authority)?;
+        let path = if base {
+            format!("{}/v1/traces", path.trim_end_matches('/'))
+        } else if path.is_empty() {
+            "/".to_string()
+        } else {
+            path.to_string()
+        };
+        Ok(Self { host, port, path })
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "http://{}:{}{}", self.host, self.port, self.path)
+    }
+}
+
+fn split_host_port(authority: &str) -> Result<(String, u16)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest
+            .split_once(']')
+            .with_context(|| format!("unterminated IPv6 address in '{authority}'"))?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        bail!("missing host in '{authority}'");
+    }
+    let port = match port {
+        Some(port) => port
+            .parse()
+            .with_context(|| format!("invalid port in '{authority}'"))?,
+        None => 80,
+    };
+    Ok((host.to_string(), port))
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Parse `OTEL_EXPORTER_OTLP_HEADERS`: comma-separated `key=value` pairs
+/// with percent-encoded values.
+fn parse_headers(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            (!key.is_empty()).then(|| (key.to_string(), percent_decode(value.trim())))
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// A finished span, ready to encode.
+#[derive(Debug, Clone)]
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    name: String,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, Value)>,
+    events: Vec<SpanEvent>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct SpanEvent {
+    time: SystemTime,
+    name: String,
+    attributes: Vec<(String, Value)>,
+}
+
+enum Message {
+    Span(SpanData),
+    Flush(SyncSender<()>),
+}
+
+/// A `tracing` layer that exports closed spans, and the events logged
+/// inside them, to an OTLP endpoint from a background thread.
+pub struct OtlpLayer {
+    tx: Mutex<Sender<Message>>,
+    /// Cleared after a failed export so closing spans stops waiting on an
+    /// unreachable collector.
+    healthy: Arc<AtomicBool>,
+}
+
+impl OtlpLayer {
+    pub fn new(endpoint: Endpoint) -> Self {
+        let headers = non_empty_env(HEADERS_ENV)
+            .map(|raw| parse_headers(&raw))
+            .unwrap_or_default();
+        let service_name = non_empty_env(SERVICE_NAME_ENV).unwrap_or_else(|| "hydra".to_string());
+        let healthy = Arc::new(AtomicBool::new(true));
+
+        let (tx, rx) = mpsc::channel();
+        let exporter = Exporter {
+            endpoint,
+            headers,
+            service_name,
+            healthy: Arc::clone(&healthy),
+        };
+        std::thread::Builder::new()
+            .name("hydra-otlp".to_string())
+            .spawn(move || exporter.run(rx))
+            .expect("failed to spawn OTLP exporter thread");
+
+        Self {
+            tx: Mutex::new(tx),
+            healthy,
+        }
+    }
+
+    fn send(&self, message: Message) {
+        if let Ok(tx) = self.tx.lock() {
+            let _ = tx.send(message);
+        }
+    }
+
+    /// Block until everything sent so far has been exported. hydra exits
+    /// with `process::exit` in many places, so this runs whenever a root
+    /// span closes rather than at shutdown.
+    fn flush(&self) {
+        if !self.healthy.load(Ordering::Relaxed) {
+            return;
+        }
+        let (ack_tx, ack_rx) = mpsc::sync_channel(1);
+        self.send(Message::Flush(ack_tx));
+        let _ = ack_rx.recv_timeout(FLUSH_WAIT);
+    }
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanData>()
+                .map(|d| (d.trace_id, d.span_id))
+        });
+        let trace_id = match parent {
+            Some((trace_id, _)) => trace_id,
+            None => visitor
+                .fields
+                .iter()
+                .find(|(key, _)| key == "run_id")
+                .and_then(|(_, value)| value.as_str()?.parse::<Uuid>().ok())
+                .unwrap_or_else(Uuid::new_v4)
+                .into_bytes(),
+        };
+        let mut span_id = [0u8; 8];
+        span_id.copy_from_slice(&Uuid::new_v4().as_bytes()[..8]);
+
+        let now = SystemTime::now();
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id,
+            parent_span_id: parent.map(|(_, span_id)| span_id),
+            name: span.name().to_string(),
+            start: now,
+            end: now,
+            attributes: visitor.fields,
+            events: Vec::new(),
+            error: None,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            for (key, value) in visitor.fields {
+                data.attributes.retain(|(existing, _)| *existing != key);
+                data.attributes.push((key, value));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut name = None;
+        let mut attributes = vec![(
+            "level".to_string(),
+            Value::String(event.metadata().level().to_string()),
+        )];
+        for (key, value) in visitor.fields {
+            if key == "message" {
+                name = value.as_str().map(str::to_string);
+            } else {
+                attributes.push((key, value));
+            }
+        }
+        let name = name.unwrap_or_else(|| event.metadata().name().to_string());
+
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            if *event.metadata().level() == Level::ERROR {
+                data.error = Some(name.clone());
+            }
+            data.events.push(SpanEvent {
+                time: SystemTime::now(),
+                name,
+                attributes,
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(mut data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+        data.end = SystemTime::now();
+        let is_root = data.parent_span_id.is_none();
+        self.send(Message::Span(data));
+        if is_root {
+            self.flush();
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    fields: Vec<(String, Value)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.fields.push((
+            field.name().to_string(),
+            Value::String(format!("{value:?}")),
+        ));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields
+            .push((field.name().to_string(), Value::String(value.to_string())));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.push((field.name().to_string(), json!(value)));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.push((field.name().to_string(), json!(value)));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.fields.push((field.name().to_string(), json!(value)));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.push((field.name().to_string(), json!(value)));
+    }
+}
+
+struct Exporter {
+    endpoint: Endpoint,
+    headers: Vec<(String, String)>,
+    service_name: String,
+    healthy: Arc<AtomicBool>,
+}
+
+impl Exporter {
+    fn run(self, rx: Receiver<Message>) {
+        let mut batch: Vec<SpanData> = Vec::new();
+        loop {
+            match rx.recv_timeout(FLUSH_INTERVAL) {
+                Ok(Message::Span(span)) => {
+                    batch.push(span);
+                    if batch.len() >= MAX_BATCH {
+                        self.export(&mut batch);
+                    }
+                }
+                Ok(Message::Flush(ack)) => {
+                    self.export(&mut batch);
+                    let _ = ack.send(());
+                }
+                Err(RecvTimeoutError::Timeout) => self.export(&mut batch),
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.export(&mut batch);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn export(&self, batch: &mut Vec<SpanData>) {
+        if batch.is_empty() {
+            return;
+        }
+        let body = encode(&self.service_name, batch).to_string();
+        batch.clear();
+        match self.post(&body) {
+            Ok(()) => self.healthy.store(true, Ordering::Relaxed),
+            Err(err) => {
+                // Logging through tracing here would feed back into this layer.
+                if self.healthy.swap(false, Ordering::Relaxed) {
+                    eprintln!("warning: OTLP export to {} failed: {err:#}", self.endpoint);
+                }
+            }
+        }
+    }
+
+    fn post(&self, body: &str) -> Result<()> {
+        let addr = (self.endpoint.host.as_str(), self.endpoint.port)
+            .to_socket_addrs()?
+            .next()
+            .context("endpoint did not resolve to an address")?;
+        let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.endpoint.path,
+            self.endpoint.host,
+            self.endpoint.port,
+            body.len()
+        );
+        for (key, value) in &self.headers {
+            request.push_str(&format!("{key}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+        stream.write_all(request.as_bytes())?;
+
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response);
+        let head = String::from_utf8_lossy(&response);
+        let status_line = head.lines().next().unwrap_or_default();
+        let status: u16 = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .with_context(|| format!("malformed response '{status_line}'"))?;
+        if !(200..300).contains(&status) {
+            bail!("collector responded with {status_line}");
+        }
+        Ok(())
+    }
+}
+
+/// Encode spans as an OTLP `ExportTraceServiceRequest` in JSON form.
+fn encode(service_name: &str, spans: &[SpanData]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": encode_attributes(&[
+                    ("service.name".to_string(), Value::String(service_name.to_string())),
+                    (
+                        "service.version".to_string(),
+                        Value::String(env!("CARGO_PKG_VERSION").to_string()),
+                    ),
+                ]),
+            },
+            "scopeSpans": [{
+                "scope": { "name": "hydra", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans.iter().map(encode_span).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+fn encode_span(span: &SpanData) -> Value {
+    let mut value = json!({
+        "traceId": hex(&span.trace_id),
+        "spanId": hex(&span.span_id),
+        "name": span.name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(span.start),
+        "endTimeUnixNano": unix_nanos(span.end),
+        "attributes": encode_attributes(&span.attributes),
+        "events": span.events.iter().map(|event| json!({
+            "timeUnixNano": unix_nanos(event.time),
+            "name": event.name,
+            "attributes": encode_attributes(&event.attributes),
+        })).collect::<Vec<_>>(),
+    });
+    if let Some(parent) = span.parent_span_id {
+        value["parentSpanId"] = Value::String(hex(&parent));
+    }
+    if let Some(message) = &span.error {
+        // STATUS_CODE_ERROR
+        value["status"] = json!({ "code": 2, "message": message });
+    }
+    value
+}
+
+fn encode_attributes(attributes: &[(String, Value)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": any_value(value) }))
+        .collect()
+}
+
+fn any_value(value: &Value) -> Value {
+    match value {
+        Value::Bool(b) => json!({ "boolValue": b }),
+        // 64-bit integers are strings in OTLP JSON.
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "intValue": n.to_string() }),
+        Value::Number(n) => json!({ "doubleValue": n.as_f64() }),
+        Value::String(s) => json!({ "stringValue": s }),
+        other => json!({ "stringValue": other.to_string() }),
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn endpoints_follow_the_otel_path_rules() {
+        let base = Endpoint::parse("http://localhost:4318", true).unwrap();
+        assert_eq!(base.to_string(), "http://localhost:4318/v1/traces");
+        let prefixed = Endpoint::parse("http://collector/otlp/", true).unwrap();
+        assert_eq!(prefixed.to_string(), "http://collector:80/otlp/v1/traces");
+        let exact = Endpoint::parse("http://[::1]:4318/custom", false).unwrap();
+        assert_eq!(exact.host, "::1");
+        assert_eq!(exact.path, "/custom");
+
+        assert!(Endpoint::parse("https://api.honeycomb.io", true).is_err());
+        assert!(Endpoint::parse("localhost:4318", true).is_err());
+
+        assert_eq!(
+            parse_headers("x-honeycomb-team=abc, authorization=Bearer%20t"),
+            vec![
+                ("x-honeycomb-team".to_string(), "abc".to_string()),
+                ("authorization".to_string(), "Bearer t".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn race_spans_share_the_run_id_as_trace_id() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let collector = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(IO_TIMEOUT)).unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length: usize = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("Content-Length: "))
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    if body.len() >= length {
+                        stream
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                            .unwrap();
+                        return (head.to_string(), body.to_string());
+                    }
+                }
+            }
+        });
+
+        let endpoint = Endpoint::parse(&format!("http://127.0.0.1:{port}"), true).unwrap();
+        let subscriber = tracing_subscriber::registry().with(OtlpLayer::new(endpoint));
+        let run_id = Uuid::new_v4();
+        tracing::subscriber::with_default(subscriber, || {
+            let race = tracing::info_span!("race", %run_id);
+            let _race = race.enter();
+            let check = tracing::info_span!("scoring.check", check = "build");
+            let _check = check.enter();
+            tracing::error!("build failed");
+        });
+
+        let (head, body) = collector.join().unwrap();
+        assert!(head.starts_with("POST /v1/traces HTTP/1.1"));
+        let request: Value = serde_json::from_str(&body).unwrap();
+        let spans = request["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+        let (check, race) = (&spans[0], &spans[1]);
+        assert_eq!(race["name"], "race");
+        assert_eq!(race["traceId"], hex(run_id.as_bytes()));
+        assert_eq!(check["traceId"], race["traceId"]);
+        assert_eq!(check["parentSpanId"], race["spanId"]);
+        assert!(race.get("parentSpanId").is_none());
+        assert_eq!(check["status"]["code"], 2);
+        assert_eq!(check["events"][0]["name"], "build failed");
+        assert_eq!(
+            check["attributes"][0],
+            json!({ "key": "check", "value": { "stringValue": "build" } })
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! Machine-readable result contract shared by every subcommand.
+//!
+//! Exit codes:
+//!
+//! | Code | Meaning                                                    |
+//! |------|------------------------------------------------------------|
+//! | 0    | Success                                                    |
+//! | 1    | Command failed, or the run/operation did not succeed       |
+//! | 2    | Run completed but no candidate is mergeable                |
+//! | 3    | Infrastructure failure: no verdict (`race --ci`), or the   |
+//! |      | command hit an agent, git or process error                 |
+//! | 64   | Invalid command-line usage                                 |
+//!
+//! A failed command prints its error code (`HY-ADAPTER-003`, see
+//! `hydra_core::error`) with the message, headed by a translated summary
+//! when a non-English locale is active.
+//!
+//! With the global `--output <file>` flag, a command's JSON result (the same
+//! document `--json` prints) is also written atomically to that file. A failed
+//! command writes `{"error": {"code", "message", "retryable"}}` instead, plus
+//! `context` (`run_id`, `agent`, `phase`) when the error came from a race.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use hydra_core::error::{ErrorCategory, ErrorCode};
+use hydra_core::i18n;
+use hydra_core::orchestrator::ErrorContext;
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_NOT_MERGEABLE: i32 = 2;
+pub const EXIT_INFRA_FAILURE: i32 = 3;
+pub const EXIT_USAGE: i32 = 64;
+
+/// Appended to `hydra --help`.
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0   success
+  1   command failed, or the run/operation did not succeed
+  2   run completed but no candidate is mergeable
+  3   infrastructure failure: no verdict (race --ci), or an agent, git or
+      process error
+  64  invalid command-line usage";
+
+static OUTPUT_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Record the global `--output` path. Called once from `main`.
+pub fn set_output_path(path: PathBuf) -> Result<()> {
+    if OUTPUT_PATH.set(path).is_err() {
+        bail!("output path already set");
+    }
+    Ok(())
+}
+
+/// Whether the caller asked for a result file.
+pub fn requested() -> bool {
+    OUTPUT_PATH.get().is_some()
+}
+
+/// Emit a command's JSON result: pretty-printed to stdout when `print` is set
+/// (i.e. `--json`), and written to the `--output` file when one was given.
+pub fn emit<T: Serialize + ?Sized>(value: &T, print: bool) -> Result<()> {
+    let rendered = serde_json::to_string_pretty(value)?;
+    if let Some(path) = OUTPUT_PATH.get() {
+        write_atomic(path, rendered.as_bytes())?;
+    }
+    if print {
+        println!("{rendered}");
+    }
+    Ok(())
+}
+
+/// Exit code for a command that failed with `code`. Errors that trying
+/// again or fixing the machine can clear (a missing agent CLI, git or
+/// process trouble) are infrastructure failures, so automation can tell them
+/// from a bad request.
+pub fn exit_code_for(code: Option<ErrorCode>) -> i32 {
+    let Some(code) = code else {
+        return EXIT_FAILURE;
+    };
+    let infrastructure = code.retryable()
+        || matches!(code.category(), ErrorCategory::Git | ErrorCategory::Process)
+        || matches!(
+            code,
+            ErrorCode::AdapterMissing | ErrorCode::AdapterAuth | ErrorCode::AdapterUnsupported
+        );
+    if infrastructure {
+        EXIT_INFRA_FAILURE
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+/// Print a failed command's error with its code, record it in the
+/// `--output` file, and return the exit code to use.
+pub fn report_error(err: &anyhow::Error) -> i32 {
+    let source: &(dyn std::error::Error + 'static) = err.as_ref();
+    let code = ErrorCode::find(source);
+    let catalog = i18n::catalog();
+    let label = catalog.text("common.error");
+    match (code, code.and_then(|c| catalog.error_summary(c))) {
+        (Some(code), Some(summary)) => eprintln!("{label} [{code}]: {summary}\n  {err:?}"),
+        (Some(code), None) => eprintln!("{label} [{code}]: {err:?}"),
+        (None, _) => eprintln!("{label}: {err:?}"),
+    }
+    if requested() {
+        if let Err(e) = emit(&error_json(err, code), false) {
+            eprintln!("Error: {e:#}");
+        }
+    }
+    exit_code_for(code)
+}
+
+/// The `{"error": ...}` document for `--output`. `context` names the run,
+/// agent and phase the error came from, when known.
+fn error_json(err: &anyhow::Error, code: Option<ErrorCode>) -> serde_json::Value {
+    let mut error = serde_json::json!({
+        "code": code,
+        "message": format!("{err:#}"),
+        "retryable": code.is_some_and(ErrorCode::retryable),
+    });
+    if let Some(context) = ErrorContext::find(err.as_ref()) {
+        error["context"] = serde_json::json!(context);
+    }
+    serde_json::json!({ "error": error })
+}
+
+/// Write via a temp file and rename so readers never see a partial file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, contents).with_context(|| format!("failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("failed to move result into place at {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomic_creates_parent_and_leaves_no_temp_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("out").join("result.json");
+        write_atomic(&path, b"{}").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+        assert!(!tmp.path().join("out").join("result.json.tmp").exists());
+    }
+
+    #[test]
+    fn error_codes_map_to_exit_codes() {
+        assert_eq!(exit_code_for(None), EXIT_FAILURE);
+        assert_eq!(exit_code_for(Some(ErrorCode::ConfigInvalid)), EXIT_FAILURE);
+        assert_eq!(
+            exit_code_for(Some(ErrorCode::BudgetExhausted)),
+            EXIT_FAILURE
+        );
+        assert_eq!(
+            exit_code_for(Some(ErrorCode::AdapterMissing)),
+            EXIT_INFRA_FAILURE
+        );
+        assert_eq!(
+            exit_code_for(Some(ErrorCode::GitFailed)),
+            EXIT_INFRA_FAILURE
+        );
+
+        let err = anyhow::Error::new(hydra_core::orchestrator::OrchestratorError::NoAgents)
+            .context("race failed");
+        assert_eq!(report_error(&err), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn error_json_carries_run_and_agent_context() {
+        use hydra_core::orchestrator::{ErrorPhase, OrchestratorError};
+
+        let run_id = uuid::Uuid::new_v4();
+        let inner = OrchestratorError::TaskFailed("boom".to_string())
+            .with_context(ErrorContext::agent("codex"))
+            .with_context(ErrorContext {
+                run_id: Some(run_id),
+                agent_key: None,
+                phase: Some(ErrorPhase::Scoring),
+            });
+        let err = anyhow::Error::new(inner).context("race failed");
+        let code = ErrorCode::find(err.as_ref());
+        assert_eq!(code, Some(ErrorCode::RaceAgentFailed));
+
+        let json = error_json(&err, code);
+        assert_eq!(json["error"]["context"]["run_id"], run_id.to_string());
+        assert_eq!(json["error"]["context"]["agent"], "codex");
+        assert_eq!(json["error"]["context"]["phase"], "scoring");
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains(&format!(
+                "run {run_id}, agent 'codex', during scoring: boom"
+            )));
+
+        let plain = anyhow::Error::new(OrchestratorError::NoAgents);
+        assert!(error_json(&plain, None)["error"].get("context").is_none());
+    }
+}
@@ -0,0 +1,423 @@
+//! Live per-agent spinners shown while a race runs.
+//!
+//! Drawn only when stdout is a terminal and the race prints human output, so
+//! `--json`, `--ci`, batch runs, and redirected output are unaffected.
+
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use hydra_core::adapter::AgentEvent;
+use hydra_core::artifact::RunStatus;
+use hydra_core::orchestrator::{AgentPhase, ProgressEstimate, RaceEvent, RaceEventSink};
+
+use crate::approval::ApprovalPrompter;
+
+const SNIPPET_CHARS: usize = 60;
+const TICK_INTERVAL: Duration = Duration::from_millis(120);
+
+pub struct RaceProgress {
+    multi: MultiProgress,
+}
+
+impl RaceProgress {
+    /// Returns `None` when progress should not be drawn.
+    pub fn new(enabled: bool) -> Option<Self> {
+        if !enabled || !std::io::stdout().is_terminal() {
+            return None;
+        }
+        Some(Self {
+            multi: MultiProgress::with_draw_target(ProgressDrawTarget::stdout()),
+        })
+    }
+
+    pub fn agent(&self, agent_key: &str) -> AgentProgress {
+        let bar = self.multi.add(ProgressBar::new_spinner());
+        bar.set_style(
+            ProgressStyle::with_template("{spinner:.cyan} {prefix:<14.bold} {elapsed:>4} {msg}")
+                .expect("valid progress template"),
+        );
+        bar.set_prefix(agent_key.to_string());
+        bar.enable_steady_tick(TICK_INTERVAL);
+        let mut progress = AgentProgress {
+            bar: Some(bar),
+            status: "starting",
+            estimate: None,
+            tokens: 0,
+            files: 0,
+            snippet: String::new(),
+        };
+        progress.redraw();
+        progress
+    }
+
+    /// Remove the spinners before the summary is printed.
+    pub fn clear(&self) {
+        let _ = self.multi.clear();
+    }
+}
+
+/// Drives the spinners from orchestrator events. Agents get disabled
+/// handles when progress is not drawn.
+pub struct ProgressSink {
+    progress: Option<RaceProgress>,
+    agents: Mutex<HashMap<String, AgentProgress>>,
+    approvals: Option<ApprovalPrompter>,
+}
+
+impl ProgressSink {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            progress: RaceProgress::new(enabled),
+            agents: Mutex::new(HashMap::new()),
+            approvals: None,
+        }
+    }
+
+    /// Ask on the terminal when an agent is paused for approval.
+    pub fn with_approvals(mut self, approvals: ApprovalPrompter) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    pub fn clear(&self) {
+        if let Some(progress) = &self.progress {
+            progress.clear();
+        }
+    }
+}
+
+impl RaceEventSink for ProgressSink {
+    fn emit(&self, event: RaceEvent) {
+        let mut agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        match event {
+            RaceEvent::Queued { holder } => {
+                let notice =
+                    format!("Waiting for {holder} to finish setting up in this repository...");
+                match &self.progress {
+                    Some(progress) => progress.multi.suspend(|| eprintln!("{notice}")),
+                    None => eprintln!("{notice}"),
+                }
+            }
+            RaceEvent::RunStarted { agents: keys, .. } => {
+                for key in keys {
+                    let handle = self
+                        .progress
+                        .as_ref()
+                        .map(|p| p.agent(&key))
+                        .unwrap_or_else(AgentProgress::disabled);
+                    agents.insert(key, handle);
+                }
+            }
+            RaceEvent::StaleBase { freshness } => {
+                let mut notice = if freshness.fast_forwarded {
+                    format!(
+                        "Fast-forwarded {} to {}",
+                        short_branch(&freshness.branch),
+                        freshness.upstream
+                    )
+                } else {
+                    format!(
+                        "warning: {} is {} commit(s) behind {}; agents start from the older code \
+                         (set race.base_freshness = \"fast_forward\" to update it first)",
+                        short_branch(&freshness.branch),
+                        freshness.behind,
+                        freshness.upstream
+                    )
+                };
+                if let Some(error) = &freshness.fetch_error {
+                    notice.push_str(&format!(
+                        "\n  fetch failed, compared with the last fetch: {error}"
+                    ));
+                }
+                match &self.progress {
+                    Some(progress) => progress.multi.suspend(|| eprintln!("{notice}")),
+                    None => eprintln!("{notice}"),
+                }
+            }
+            RaceEvent::BaselineSkipped { reused_from } => {
+                let notice = match reused_from {
+                    Some(run_id) => format!("Reusing the baseline from run {run_id}"),
+                    None => "warning: no cached baseline for this commit; \
+                             agents are scored without one"
+                        .to_string(),
+                };
+                match &self.progress {
+                    Some(progress) => progress.multi.suspend(|| eprintln!("{notice}")),
+                    None => eprintln!("{notice}"),
+                }
+            }
+            RaceEvent::PromptInjectionDetected { findings, stripped } => {
+                let mut warning = String::from(
+                    "warning: the task sources contain text that looks like instructions to the agents:",
+                );
+                for finding in &findings {
+                    warning.push_str(&format!("\n  {finding}"));
+                }
+                if stripped {
+                    warning.push_str("\n  Matches were removed from the prompt.");
+                }
+                match &self.progress {
+                    Some(progress) => progress.multi.suspend(|| eprintln!("{warning}")),
+                    None => eprintln!("{warning}"),
+                }
+            }
+            RaceEvent::AgentStarted { agent_key } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.running();
+                }
+            }
+            RaceEvent::AgentOutput {
+                agent_key, line, ..
+            } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.event(&line);
+                }
+            }
+            RaceEvent::AgentEvent {
+                agent_key,
+                event,
+                total_tokens,
+            } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.agent_event(&event);
+                    agent.tokens(total_tokens);
+                }
+            }
+            RaceEvent::AgentProgress {
+                agent_key,
+                progress,
+            } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.progress(&progress);
+                }
+            }
+            RaceEvent::FileEdited {
+                agent_key, files, ..
+            } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.files(files);
+                }
+            }
+            RaceEvent::ApprovalRequested {
+                agent_key,
+                approval_id,
+                action,
+            } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.paused(&action.to_string());
+                }
+                if let Some(approvals) = &self.approvals {
+                    let multi = self.progress.as_ref().map(|p| p.multi.clone());
+                    approvals.request(&agent_key, approval_id, &action, multi);
+                }
+            }
+            RaceEvent::ApprovalResolved { agent_key, .. } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.running();
+                }
+            }
+            RaceEvent::AgentFinished {
+                agent_key, status, ..
+            } => {
+                if let Some(agent) = agents.get_mut(&agent_key) {
+                    agent.finish(&status);
+                }
+            }
+            RaceEvent::BudgetWarning { warning, .. } => {
+                let notice = format!("warning: {warning}");
+                match &self.progress {
+                    Some(progress) => progress.multi.suspend(|| eprintln!("{notice}")),
+                    None => eprintln!("{notice}"),
+                }
+            }
+            RaceEvent::StoppingEarly {
+                agent_key,
+                composite,
+            } => {
+                let notice = format!(
+                    "{agent_key} passed the gates (score {composite:.1}); stopping the other agents"
+                );
+                match &self.progress {
+                    Some(progress) => progress.multi.suspend(|| eprintln!("{notice}")),
+                    None => eprintln!("{notice}"),
+                }
+            }
+            RaceEvent::ScoringStarted
+            | RaceEvent::ScoringFinished { .. }
+            | RaceEvent::RunFinished { .. } => {}
+        }
+    }
+}
+
+/// One agent's line. A disabled handle (no bar) ignores every update.
+pub struct AgentProgress {
+    bar: Option<ProgressBar>,
+    status: &'static str,
+    /// Estimated phase and percentage, shown in place of "running".
+    estimate: Option<(AgentPhase, u8)>,
+    tokens: u64,
+    /// Files the agent has reported editing.
+    files: usize,
+    snippet: String,
+}
+
+impl AgentProgress {
+    pub fn disabled() -> Self {
+        Self {
+            bar: None,
+            status: "",
+            estimate: None,
+            tokens: 0,
+            files: 0,
+            snippet: String::new(),
+        }
+    }
+
+    pub fn running(&mut self) {
+        self.status = "running";
+        self.redraw();
+    }
+
+    /// Waiting for the user to approve `action`.
+    pub fn paused(&mut self, action: &str) {
+        self.status = "paused";
+        self.snippet = snippet(action);
+        self.redraw();
+    }
+
+    /// Show the latest non-empty output line. Unparsed JSON stream records
+    /// are skipped; their parsed form arrives via [`Self::agent_event`].
+    pub fn event(&mut self, line: &str) {
+        if self.bar.is_none() {
+            return;
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('{') {
+            return;
+        }
+        self.snippet = snippet(line);
+        self.redraw();
+    }
+
+    /// Summarize a parsed agent event (message text, tool name, progress).
+    pub fn agent_event(&mut self, event: &AgentEvent) {
+        match event {
+            AgentEvent::Message { content } => self.event(content),
+            AgentEvent::ToolCall { tool, .. } => self.event(&format!("→ {tool}")),
+            AgentEvent::Progress { message, .. } => self.event(message),
+            AgentEvent::Failed { error } => self.event(error),
+            AgentEvent::Completed {
+                summary: Some(summary),
+            } => self.event(summary),
+            AgentEvent::Completed { summary: None }
+            | AgentEvent::ToolResult { .. }
+            | AgentEvent::Usage { .. } => {}
+        }
+    }
+
+    pub fn progress(&mut self, estimate: &ProgressEstimate) {
+        self.estimate = Some((estimate.phase, estimate.percent));
+        self.redraw();
+    }
+
+    pub fn tokens(&mut self, total: u64) {
+        self.tokens = total;
+        self.redraw();
+    }
+
+    pub fn files(&mut self, files: usize) {
+        self.files = files;
+        self.redraw();
+    }
+
+    pub fn finish(&mut self, status: &RunStatus) {
+        self.status = match status {
+            RunStatus::Completed => "done",
+            RunStatus::Failed => "failed",
+            RunStatus::TimedOut => "timed out",
+            RunStatus::Interrupted => "interrupted",
+            RunStatus::Running => "running",
+        };
+        self.redraw();
+        if let Some(bar) = &self.bar {
+            bar.disable_steady_tick();
+            bar.finish();
+        }
+    }
+
+    fn redraw(&mut self) {
+        let Some(bar) = &self.bar else {
+            return;
+        };
+        let mut msg = match self.estimate {
+            Some((phase, percent)) if self.status == "running" => {
+                format!("{:<11}", format!("{percent:>3}% {}", phase.as_str()))
+            }
+            _ => format!("{:<11}", self.status),
+        };
+        if self.tokens > 0 {
+            msg.push_str(&format!(" {:>8} tok", self.tokens));
+        }
+        if self.files > 0 {
+            msg.push_str(&format!(" {:>3} files", self.files));
+        }
+        if !self.snippet.is_empty() {
+            msg.push_str("  ");
+            msg.push_str(&self.snippet);
+        }
+        bar.set_message(msg);
+    }
+}
+
+fn short_branch(branch: &str) -> &str {
+    branch.strip_prefix("refs/heads/").unwrap_or(branch)
+}
+
+/// First line of `text`, truncated to a fixed width with control characters
+/// stripped so escape sequences cannot corrupt the display.
+fn snippet(text: &str) -> String {
+    let line: String = text
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    if line.chars().count() <= SNIPPET_CHARS {
+        return line;
+    }
+    let mut truncated: String = line.chars().take(SNIPPET_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippet_truncates_and_strips_control_characters() {
+        assert_eq!(
+            snippet("editing \x1b[1msrc/lib.rs\nmore"),
+            "editing [1msrc/lib.rs"
+        );
+        let long = "x".repeat(200);
+        let short = snippet(&long);
+        assert_eq!(short.chars().count(), SNIPPET_CHARS);
+        assert!(short.ends_with('…'));
+    }
+
+    #[test]
+    fn disabled_progress_ignores_updates() {
+        let mut progress = AgentProgress::disabled();
+        progress.running();
+        progress.event("hello");
+        progress.tokens(10);
+        progress.finish(&RunStatus::Completed);
+        assert!(progress.snippet.is_empty());
+    }
+}
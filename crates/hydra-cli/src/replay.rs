@@ -0,0 +1,302 @@
+//! `hydra replay`: re-emit a recorded run's events as a new run, with the
+//! original timing scaled by `--speed`.
+//!
+//! The replay is written to its own run directory the same way a race writes
+//! one, so `hydra watch`, `hydra serve` (including its WebSocket stream) and
+//! the desktop app follow it exactly as they would a live race, without any
+//! agent running. Artifacts such as diffs and scores are copied in when the
+//! recording reaches scoring.
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use uuid::Uuid;
+
+use hydra_core::artifact::{
+    EventKind, EventReader, EventWriter, RunEvent, RunLayout, RunManifest, RunStatus,
+};
+
+use crate::run::discover_repo_root;
+use crate::serve;
+use crate::watch::WatchState;
+
+pub struct ReplayOpts {
+    pub run_id: Uuid,
+    pub speed: f64,
+    pub json: bool,
+    /// Serve the API on this address while replaying.
+    pub serve: Option<SocketAddr>,
+    pub token: Option<String>,
+}
+
+pub async fn run_replay(opts: ReplayOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let hydra_root = repo_root.join(".hydra");
+
+    let server = match opts.serve {
+        Some(addr) => {
+            let (listener, server) =
+                serve::bind(repo_root.clone(), addr.ip(), addr.port(), opts.token).await?;
+            tokio::spawn(Arc::clone(&server).serve(listener));
+            Some(server)
+        }
+        None => None,
+    };
+
+    let mut state = WatchState::default();
+    let json = opts.json;
+    let replay_id = replay(&hydra_root, opts.run_id, opts.speed, |event| {
+        if json {
+            if let Ok(line) = serde_json::to_string(event) {
+                println!("{line}");
+            }
+        } else if let Some(line) = state.apply(event) {
+            println!("{line}");
+        }
+    })
+    .await?;
+    eprintln!("Replay finished: run {replay_id}");
+
+    if let Some(server) = server {
+        eprintln!("Still serving; press Ctrl-C to stop");
+        let _ = tokio::signal::ctrl_c().await;
+        server.shutdown().await;
+    }
+    Ok(())
+}
+
+/// Replay `source` under a new run ID, calling `emit` with each event as it
+/// is written. Ctrl-C stops early and leaves the replay `Interrupted`.
+async fn replay(
+    hydra_root: &Path,
+    source: Uuid,
+    speed: f64,
+    mut emit: impl FnMut(&RunEvent),
+) -> Result<Uuid> {
+    let from = RunLayout::new(hydra_root, source);
+    let manifest = RunManifest::read_from(&from.manifest_path())
+        .with_context(|| format!("run {source} not found"))?;
+    if manifest.status == RunStatus::Running {
+        bail!("run {source} is still running; replay it once it finishes");
+    }
+    let timeline = timeline(&from, &manifest)?;
+    let Some(origin) = timeline.first().map(|(_, event)| event.timestamp) else {
+        bail!("run {source} has no recorded events");
+    };
+
+    let replay_id = Uuid::new_v4();
+    let to = RunLayout::new(hydra_root, replay_id);
+    let agent_keys: Vec<&str> = manifest
+        .agents
+        .iter()
+        .map(|a| a.agent_key.as_str())
+        .collect();
+    to.create_dirs(&agent_keys)?;
+    let mut replayed = manifest.clone();
+    replayed.run_id = replay_id;
+    replayed.started_at = Utc::now();
+    replayed.completed_at = None;
+    replayed.status = RunStatus::Running;
+    replayed.replay_of = Some(source);
+    replayed.write_to(&to.manifest_path())?;
+    eprintln!("Replaying run {source} as {replay_id} at {speed}x");
+
+    let mut run_writer = EventWriter::create(&to.events_path())?;
+    let mut agent_writers = HashMap::new();
+    let started = tokio::time::Instant::now();
+    let wall_started = Utc::now();
+    let mut copied = false;
+    let mut interrupted = false;
+
+    for (agent, mut event) in timeline {
+        let offset = (event.timestamp - origin)
+            .to_std()
+            .unwrap_or_default()
+            .div_f64(speed);
+        tokio::select! {
+            _ = tokio::time::sleep_until(started + offset) => {}
+            _ = tokio::signal::ctrl_c() => {
+                interrupted = true;
+                break;
+            }
+        }
+        // Clients read scores as soon as scoring is reported finished.
+        if event.kind == EventKind::ScoreFinished && !copied {
+            copy_artifacts(from.base_dir(), to.base_dir())?;
+            copied = true;
+        }
+        event.timestamp = wall_started
+            + chrono::Duration::from_std(offset).unwrap_or_else(|_| chrono::Duration::zero());
+        match agent {
+            Some(key) => {
+                let writer = match agent_writers.entry(key.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(EventWriter::create(&to.agent_events(&key))?)
+                    }
+                };
+                writer.write_event(&event)?;
+            }
+            None => run_writer.write_event(&event)?,
+        }
+        emit(&event);
+    }
+
+    if !copied {
+        copy_artifacts(from.base_dir(), to.base_dir())?;
+    }
+    replayed.mark_completed(if interrupted {
+        RunStatus::Interrupted
+    } else {
+        manifest.status
+    });
+    replayed.write_to(&to.manifest_path())?;
+    Ok(replay_id)
+}
+
+/// Every recorded event in time order, tagged with the agent whose log it
+/// came from (`None` for the run log). Ties keep run events first.
+fn timeline(layout: &RunLayout, manifest: &RunManifest) -> Result<Vec<(Option<String>, RunEvent)>> {
+    let mut timeline: Vec<(Option<String>, RunEvent)> =
+        EventReader::read_all(&layout.events_path())
+            .with_context(|| format!("failed to read {}", layout.events_path().display()))?
+            .into_iter()
+            .map(|event| (None, event))
+            .collect();
+    for agent in &manifest.agents {
+        let path = layout.agent_events(&agent.agent_key);
+        if !path.exists() {
+            continue;
+        }
+        let events = EventReader::read_all(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        timeline.extend(
+            events
+                .into_iter()
+                .map(|event| (Some(agent.agent_key.clone()), event)),
+        );
+    }
+    timeline.sort_by_key(|(_, event)| event.timestamp);
+    Ok(timeline)
+}
+
+/// Copy a run's artifacts other than its manifest, event logs and cancel
+/// markers.
+fn copy_artifacts(from: &Path, to: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let target = to.join(&name);
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_artifacts(&entry.path(), &target)?;
+        } else if !matches!(
+            name.to_str(),
+            Some("manifest.json" | "events.jsonl" | "cancel")
+        ) {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse a playback speed such as `10x`, `0.5x` or `4`.
+pub fn parse_speed(raw: &str) -> Result<f64, String> {
+    let number = raw.trim().strip_suffix(['x', 'X']).unwrap_or(raw.trim());
+    match number.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("'{raw}' is not a speed like 10x")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::test_support::new_run;
+
+    #[test]
+    fn parses_speeds() {
+        assert_eq!(parse_speed("10x"), Ok(10.0));
+        assert_eq!(parse_speed("0.5X"), Ok(0.5));
+        assert_eq!(parse_speed("4"), Ok(4.0));
+        assert!(parse_speed("0x").is_err());
+        assert!(parse_speed("fast").is_err());
+    }
+
+    #[tokio::test]
+    async fn replays_events_and_artifacts_into_a_new_run() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let hydra_root = tmp.path();
+        let (layout, mut manifest) = new_run(hydra_root, &["claude"]);
+        let source = layout.run_id();
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+        std::fs::write(layout.agent_diff("claude"), "diff --git a/x b/x\n").unwrap();
+
+        let origin = Utc::now() - chrono::Duration::seconds(30);
+        let at = |secs: i64, kind: EventKind, agent: Option<&str>| {
+            let mut event = RunEvent::new(kind, agent.map(String::from), serde_json::json!({}));
+            event.timestamp = origin + chrono::Duration::seconds(secs);
+            event
+        };
+        let mut run_log = EventWriter::create(&layout.events_path()).unwrap();
+        run_log
+            .write_event(&at(0, EventKind::RunStarted, None))
+            .unwrap();
+        run_log
+            .write_event(&at(20, EventKind::ScoreStarted, None))
+            .unwrap();
+        run_log
+            .write_event(&at(21, EventKind::ScoreFinished, None))
+            .unwrap();
+        run_log
+            .write_event(&at(22, EventKind::RunCompleted, None))
+            .unwrap();
+        let mut agent_log = EventWriter::create(&layout.agent_events("claude")).unwrap();
+        agent_log
+            .write_event(&at(1, EventKind::AgentStarted, Some("claude")))
+            .unwrap();
+        agent_log
+            .write_event(&at(19, EventKind::AgentCompleted, Some("claude")))
+            .unwrap();
+
+        let mut kinds = Vec::new();
+        let replay_id = replay(hydra_root, source, 1000.0, |event| {
+            kinds.push(event.kind.clone())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::RunStarted,
+                EventKind::AgentStarted,
+                EventKind::AgentCompleted,
+                EventKind::ScoreStarted,
+                EventKind::ScoreFinished,
+                EventKind::RunCompleted,
+            ]
+        );
+        let replayed = RunLayout::new(hydra_root, replay_id);
+        let manifest = RunManifest::read_from(&replayed.manifest_path()).unwrap();
+        assert_eq!(manifest.replay_of, Some(source));
+        assert_eq!(manifest.status, RunStatus::Completed);
+        assert_eq!(
+            EventReader::read_all(&replayed.events_path())
+                .unwrap()
+                .len(),
+            4
+        );
+        let agent_events = EventReader::read_all(&replayed.agent_events("claude")).unwrap();
+        assert_eq!(agent_events.len(), 2);
+        // Timing is scaled: 18s between the agent events becomes ~18ms.
+        let gap = agent_events[1].timestamp - agent_events[0].timestamp;
+        assert!(gap < chrono::Duration::seconds(1));
+        assert!(replayed.agent_diff("claude").exists());
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use hydra_core::artifact::{LockMode, RunLayout, RunManifest};
+use hydra_core::orchestrator::PromptVariant;
+
+use crate::race::RaceOpts;
+use crate::run::discover_repo_root;
+
+pub struct RerunOpts {
+    pub run_id: Uuid,
+    /// Overrides the original agent list when non-empty.
+    pub agents: Vec<String>,
+    /// Branch from the current HEAD instead of the original base ref.
+    pub head: bool,
+    pub json: bool,
+    pub unsafe_mode: bool,
+    pub allow_experimental_adapters: bool,
+}
+
+/// `variants.json` of a prompt-variant race.
+#[derive(Deserialize)]
+struct StoredVariants {
+    agent: String,
+    variants: Vec<PromptVariant>,
+}
+
+/// Rebuild the `RaceOpts` of a previous run from its manifest.
+pub fn prepare_rerun(opts: RerunOpts) -> Result<RaceOpts> {
+    let repo_root = discover_repo_root()?;
+    let layout = RunLayout::new(&repo_root.join(".hydra"), opts.run_id);
+    let manifest = RunManifest::read_from(&layout.manifest_path()).with_context(|| {
+        format!(
+            "failed to read manifest for run {} at {}",
+            opts.run_id,
+            layout.manifest_path().display()
+        )
+    })?;
+
+    let agents_overridden = !opts.agents.is_empty();
+    let mut race_opts = race_opts_from_manifest(&manifest, opts)?;
+    if layout.task_spec().exists() {
+        let data = std::fs::read_to_string(layout.task_spec())
+            .with_context(|| format!("failed to read {}", layout.task_spec().display()))?;
+        race_opts.task = Some(serde_json::from_str(&data).context("invalid task.json")?);
+    }
+    if layout.variants().exists() {
+        let data = std::fs::read_to_string(layout.variants())
+            .with_context(|| format!("failed to read {}", layout.variants().display()))?;
+        let stored: StoredVariants =
+            serde_json::from_str(&data).context("invalid variants.json")?;
+        // The manifest lists one candidate per variant, not the adapter.
+        if !agents_overridden {
+            race_opts.agents = vec![stored.agent];
+        }
+        race_opts.variants = stored.variants;
+    }
+    if manifest.task_prompt_redacted {
+        tracing::warn!(
+            run_id = %manifest.run_id,
+            "original prompt contained secrets; rerunning with the redacted prompt"
+        );
+    }
+    Ok(race_opts)
+}
+
+fn race_opts_from_manifest(manifest: &RunManifest, opts: RerunOpts) -> Result<RaceOpts> {
+    let Some(prompt) = manifest.task_prompt.clone() else {
+        bail!(
+            "run {} did not store its task prompt (only hash {}); it cannot be rerun",
+            manifest.run_id,
+            manifest.task_prompt_hash
+        );
+    };
+
+    let agents = if opts.agents.is_empty() {
+        manifest
+            .agents
+            .iter()
+            .map(|a| a.agent_key.clone())
+            .collect()
+    } else {
+        opts.agents
+    };
+
+    let base_ref = if opts.head {
+        "HEAD".to_string()
+    } else {
+        manifest.base_ref.clone()
+    };
+
+    Ok(RaceOpts {
+        agents,
+        prompt,
+        prompt_source: manifest.prompt_source.clone(),
+        tags: manifest.tags.clone(),
+        task: None,
+        variants: Vec::new(),
+        base_ref,
+        json: opts.json,
+        unsafe_mode: opts.unsafe_mode,
+        allow_experimental_adapters: opts.allow_experimental_adapters,
+        run_id: None,
+        deterministic: false,
+        lock: LockMode::Fail,
+        stop_on_first_success: false,
+        skip_baseline: false,
+        quiet: false,
+        config: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::security::SecretRedactor;
+    use hydra_core::test_support::run_manifest;
+
+    fn manifest(prompt: Option<&str>) -> RunManifest {
+        let mut m = run_manifest(Uuid::new_v4(), &["claude", "codex"]);
+        m.base_ref = "release-1.2".to_string();
+        match prompt {
+            Some(p) => m.with_task_prompt(p, false, &SecretRedactor::new()),
+            None => m,
+        }
+    }
+
+    fn opts(agents: &[&str], head: bool) -> RerunOpts {
+        RerunOpts {
+            run_id: Uuid::new_v4(),
+            agents: agents.iter().map(|a| a.to_string()).collect(),
+            head,
+            json: false,
+            unsafe_mode: false,
+            allow_experimental_adapters: false,
+        }
+    }
+
+    #[test]
+    fn reuses_original_prompt_agents_and_base_ref() {
+        let race =
+            race_opts_from_manifest(&manifest(Some("fix the bug")), opts(&[], false)).unwrap();
+        assert_eq!(race.prompt, "fix the bug");
+        assert_eq!(race.agents, vec!["claude", "codex"]);
+        assert_eq!(race.base_ref, "release-1.2");
+        assert!(race.run_id.is_none());
+    }
+
+    #[test]
+    fn overrides_agents_and_base_ref() {
+        let race = race_opts_from_manifest(&manifest(Some("fix the bug")), opts(&["codex"], true))
+            .unwrap();
+        assert_eq!(race.agents, vec!["codex"]);
+        assert_eq!(race.base_ref, "HEAD");
+    }
+
+    #[test]
+    fn rejects_runs_without_stored_prompt() {
+        let err = race_opts_from_manifest(&manifest(None), opts(&[], false)).unwrap_err();
+        assert!(err.to_string().contains("cannot be rerun"));
+    }
+}
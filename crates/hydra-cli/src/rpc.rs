@@ -0,0 +1,579 @@
+//! `hydra rpc`: the `hydra serve` API as JSON-RPC 2.0 over stdin/stdout,
+//! for editor extensions that keep hydra running as a child process.
+//!
+//! Messages are framed with `Content-Length` headers as in LSP, or one per
+//! line; replies use whichever framing the client used. There is no token:
+//! only the parent process can reach the pipes. Closing stdin cancels the
+//! races started here and exits once their worktrees are cleaned up.
+//!
+//! | Method               | Params                                                 |
+//! |----------------------|--------------------------------------------------------|
+//! | `health`             |                                                        |
+//! | `metrics`            |                                                        |
+//! | `runs/list`          | `tags`                                                 |
+//! | `race/start`         | `prompt`, `agents`, `base_ref`, `allow_experimental_adapters`, `prompt_source`, `tags` |
+//! | `run/get`            | `run_id`                                               |
+//! | `run/events`         | `run_id`, `since`, `agent`                             |
+//! | `run/score`          | `run_id`, `agent`                                      |
+//! | `run/diff`           | `run_id`, `agent`                                      |
+//! | `run/cancel`         | `run_id`, `agent`                                      |
+//! | `run/approve`        | `run_id`, `approval_id`, `decision`                    |
+//! | `run/merge`          | `run_id`, `agent`, `dry_run`, `force`, `only`          |
+//! | `events/subscribe`   | `run_id`                                               |
+//! | `events/unsubscribe` | `subscription`                                         |
+//!
+//! A subscription sends `events/event` notifications with `subscription`
+//! and `event` until the run ends, then one `events/end`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinSet};
+use uuid::Uuid;
+
+use hydra_core::artifact::{EventKind, RunLayout};
+
+use crate::run::discover_repo_root;
+use crate::serve::{ApiError, Request, Server};
+use crate::watch::{manifest_is_settled, RunEventSources};
+
+const MAX_MESSAGE: usize = 16 * 1024 * 1024;
+
+/// How often a subscription checks the run's logs.
+const SUBSCRIPTION_POLL: Duration = Duration::from_millis(250);
+
+// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// Application errors; `data.status` carries the `hydra serve` HTTP status.
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Headers,
+    Lines,
+}
+
+pub async fn run_rpc() -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let server = Arc::new(Server::new(repo_root, String::new()));
+    let mut input = tokio::io::BufReader::new(tokio::io::stdin());
+    serve(Arc::clone(&server), &mut input, tokio::io::stdout()).await?;
+    server.shutdown().await;
+    Ok(())
+}
+
+/// Answer messages from `input` until it closes.
+async fn serve<R, W>(server: Arc<Server>, input: &mut R, output: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (out_tx, out_rx) = mpsc::unbounded_channel::<(Framing, Value)>();
+    let writer = tokio::spawn(write_messages(output, out_rx));
+    let session = Arc::new(Session {
+        server,
+        out: out_tx,
+        subscriptions: Mutex::new(HashMap::new()),
+    });
+
+    let mut requests = JoinSet::new();
+    while let Some((framing, raw)) = read_message(input).await? {
+        let session = Arc::clone(&session);
+        requests.spawn(async move { session.handle(framing, &raw).await });
+    }
+
+    // Requests already sent are still answered; event streams stop.
+    for (_, task) in session.lock().drain() {
+        task.abort();
+    }
+    while requests.join_next().await.is_some() {}
+    drop(session);
+    writer.await??;
+    Ok(())
+}
+
+struct Session {
+    server: Arc<Server>,
+    out: mpsc::UnboundedSender<(Framing, Value)>,
+    subscriptions: Mutex<HashMap<Uuid, AbortHandle>>,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+    status: Option<u16>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl std::fmt::Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            status: None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+        if let Some(status) = self.status {
+            error["data"] = json!({ "status": status });
+        }
+        error
+    }
+}
+
+impl From<ApiError> for RpcError {
+    fn from(e: ApiError) -> Self {
+        Self {
+            code: if e.status == 400 {
+                INVALID_PARAMS
+            } else {
+                SERVER_ERROR
+            },
+            message: e.message,
+            status: Some(e.status),
+        }
+    }
+}
+
+impl Session {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, AbortHandle>> {
+        self.subscriptions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn send(&self, framing: Framing, message: Value) {
+        let _ = self.out.send((framing, message));
+    }
+
+    async fn handle(self: Arc<Self>, framing: Framing, raw: &[u8]) {
+        let message: Value = match serde_json::from_slice(raw) {
+            Ok(message) => message,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, e);
+                self.send(framing, reply(Value::Null, Err(error)));
+                return;
+            }
+        };
+        let id = message.get("id").cloned();
+        let result = match (
+            message.get("jsonrpc").and_then(Value::as_str),
+            message.get("method").and_then(Value::as_str),
+        ) {
+            (Some("2.0"), Some(method)) => {
+                let params = message.get("params").cloned().unwrap_or(Value::Null);
+                Arc::clone(&self).call(framing, method, params).await
+            }
+            _ => Err(RpcError::new(
+                INVALID_REQUEST,
+                "expected a JSON-RPC 2.0 request object",
+            )),
+        };
+        // Notifications (no id) get no reply, errors included.
+        if let Some(id) = id {
+            self.send(framing, reply(id, result));
+        }
+    }
+
+    async fn call(
+        self: Arc<Self>,
+        framing: Framing,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, RpcError> {
+        let (http_method, path, query, body) = match method {
+            "events/subscribe" => {
+                let params: RunParams = parse_params(params)?;
+                return self.subscribe(framing, &params.run_id);
+            }
+            "events/unsubscribe" => {
+                let params: UnsubscribeParams = parse_params(params)?;
+                let removed = self.lock().remove(&params.subscription);
+                if let Some(task) = &removed {
+                    task.abort();
+                }
+                return Ok(json!({ "unsubscribed": removed.is_some() }));
+            }
+            "health" => ("GET", "/v1/health".to_string(), HashMap::new(), None),
+            "metrics" => ("GET", "/metrics".to_string(), HashMap::new(), None),
+            "runs/list" => {
+                let p: Option<ListParams> = parse_params(params)?;
+                let mut query = HashMap::new();
+                if let Some(tags) = p.map(|p| p.tags).filter(|tags| !tags.is_empty()) {
+                    query.insert("tag".to_string(), tags.join(","));
+                }
+                ("GET", "/v1/runs".to_string(), query, None)
+            }
+            "race/start" => (
+                "POST",
+                "/v1/races".to_string(),
+                HashMap::new(),
+                Some(params),
+            ),
+            "run/get" => {
+                let p: RunParams = parse_params(params)?;
+                (
+                    "GET",
+                    format!("/v1/runs/{}", p.run_id),
+                    HashMap::new(),
+                    None,
+                )
+            }
+            "run/events" => {
+                let p: EventsParams = parse_params(params)?;
+                let mut query = HashMap::new();
+                if let Some(since) = p.since {
+                    query.insert("since".to_string(), since.to_string());
+                }
+                if let Some(agent) = p.agent {
+                    query.insert("agent".to_string(), agent);
+                }
+                ("GET", format!("/v1/runs/{}/events", p.run_id), query, None)
+            }
+            "run/score" | "run/diff" => {
+                let p: AgentParams = parse_params(params)?;
+                let artifact = method.trim_start_matches("run/");
+                let path = format!("/v1/runs/{}/agents/{}/{artifact}", p.run_id, p.agent);
+                ("GET", path, HashMap::new(), None)
+            }
+            "run/cancel" | "run/merge" => {
+                let (run_id, rest) = split_run_id(params)?;
+                let action = method.trim_start_matches("run/");
+                let path = format!("/v1/runs/{run_id}/{action}");
+                ("POST", path, HashMap::new(), Some(rest))
+            }
+            "run/approve" => {
+                let (run_id, mut rest) = split_run_id(params)?;
+                let approval_id = rest
+                    .as_object_mut()
+                    .and_then(|o| o.remove("approval_id"))
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing approval_id"))?;
+                let path = format!("/v1/runs/{run_id}/approvals/{approval_id}");
+                ("POST", path, HashMap::new(), Some(rest))
+            }
+            _ => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("unknown method '{method}'"),
+                ))
+            }
+        };
+
+        // Path segments come from the client; keep them to one segment each.
+        if path.split('/').any(|segment| segment == "..") || path.contains(['?', '#']) {
+            return Err(RpcError::new(INVALID_PARAMS, "invalid run or agent"));
+        }
+        let body = match body {
+            Some(body) => {
+                serde_json::to_vec(&body).map_err(|e| RpcError::new(INTERNAL_ERROR, e))?
+            }
+            None => Vec::new(),
+        };
+        let request = Request::new(http_method, path, query, body);
+        let response = self.server.route(&request).await?;
+        if response.content_type == "application/json" {
+            serde_json::from_slice(&response.body).map_err(|e| RpcError::new(INTERNAL_ERROR, e))
+        } else {
+            Ok(Value::String(
+                String::from_utf8_lossy(&response.body).into_owned(),
+            ))
+        }
+    }
+
+    fn subscribe(self: Arc<Self>, framing: Framing, run: &str) -> Result<Value, RpcError> {
+        let layout = self.server.layout(run)?;
+        let subscription = Uuid::new_v4();
+        let session = Arc::clone(&self);
+        // Holding the lock across spawn and insert keeps a run that is
+        // already over from ending the subscription before it is recorded.
+        let mut subscriptions = self.lock();
+        let task = tokio::spawn(async move {
+            let result = forward_events(&session, framing, subscription, &layout).await;
+            let mut params = json!({ "subscription": subscription });
+            if let Err(e) = result {
+                params["error"] = Value::String(e.to_string());
+            }
+            session.lock().remove(&subscription);
+            session.send(framing, notification("events/end", params));
+        });
+        subscriptions.insert(subscription, task.abort_handle());
+        Ok(json!({ "subscription": subscription }))
+    }
+}
+
+/// Send the run's events (run and agent logs, in timestamp order) until it
+/// finishes.
+async fn forward_events(
+    session: &Session,
+    framing: Framing,
+    subscription: Uuid,
+    layout: &RunLayout,
+) -> Result<()> {
+    let mut sources = RunEventSources::open(layout);
+    let mut ticker = tokio::time::interval(SUBSCRIPTION_POLL);
+    loop {
+        ticker.tick().await;
+        let events = sources.poll()?;
+        let finished = events
+            .iter()
+            .any(|e| matches!(e.kind, EventKind::RunCompleted | EventKind::RunFailed));
+        for event in &events {
+            session.send(
+                framing,
+                notification(
+                    "events/event",
+                    json!({ "subscription": subscription, "event": event }),
+                ),
+            );
+        }
+        // A run killed before its terminal event still settles its manifest.
+        if finished || (events.is_empty() && manifest_is_settled(layout)) {
+            return Ok(());
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RunParams {
+    run_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListParams {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EventsParams {
+    run_id: String,
+    since: Option<usize>,
+    agent: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AgentParams {
+    run_id: String,
+    agent: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnsubscribeParams {
+    subscription: Uuid,
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
+
+/// Take `run_id` out of `params`, leaving the request body for the route.
+fn split_run_id(params: Value) -> Result<(String, Value), RpcError> {
+    let mut params = match params {
+        Value::Null => serde_json::Map::new(),
+        Value::Object(map) => map,
+        _ => return Err(RpcError::new(INVALID_PARAMS, "params must be an object")),
+    };
+    let run_id = params
+        .remove("run_id")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing run_id"))?;
+    Ok((run_id, Value::Object(params)))
+}
+
+fn reply(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() }),
+    }
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+async fn write_messages<W: AsyncWrite + Unpin>(
+    mut output: W,
+    mut messages: mpsc::UnboundedReceiver<(Framing, Value)>,
+) -> std::io::Result<()> {
+    while let Some((framing, message)) = messages.recv().await {
+        let body = message.to_string();
+        match framing {
+            Framing::Headers => {
+                output
+                    .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+                    .await?;
+                output.write_all(body.as_bytes()).await?;
+            }
+            Framing::Lines => {
+                output.write_all(body.as_bytes()).await?;
+                output.write_all(b"\n").await?;
+            }
+        }
+        output.flush().await?;
+    }
+    Ok(())
+}
+
+fn invalid(message: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn is_header(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Read the next message, in either framing. `None` at end of input.
+async fn read_message<R: AsyncBufRead + Unpin>(
+    input: &mut R,
+) -> std::io::Result<Option<(Framing, Vec<u8>)>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        // Anything that is not a header starts a line-framed message; a
+        // malformed one gets a parse error reply rather than ending the
+        // session.
+        if !is_header(trimmed) {
+            return Ok(Some((Framing::Lines, trimmed.as_bytes().to_vec())));
+        }
+
+        // A header block, ended by a blank line.
+        let mut length = None;
+        loop {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                break;
+            }
+            let (name, value) = trimmed
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("malformed header '{trimmed}'")))?;
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| invalid("invalid Content-Length"))?,
+                );
+            }
+            line.clear();
+            if input.read_line(&mut line).await? == 0 {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+        let length = length.ok_or_else(|| invalid("missing Content-Length"))?;
+        if length > MAX_MESSAGE {
+            return Err(invalid("message too large"));
+        }
+        let mut body = vec![0u8; length];
+        input.read_exact(&mut body).await?;
+        return Ok(Some((Framing::Headers, body)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::{EventWriter, RunEvent, RunStatus};
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+    use tokio::io::{duplex, BufReader};
+
+    fn completed_run(repo_root: &std::path::Path) -> Uuid {
+        let (layout, mut manifest) = new_run(&repo_root.join(".hydra"), &["claude"]);
+        std::fs::write(layout.agent_diff("claude"), "diff --git a/x b/x\n").unwrap();
+        let mut writer = EventWriter::create(&layout.events_path()).unwrap();
+        writer
+            .write_event(&RunEvent::new(
+                EventKind::RunCompleted,
+                None,
+                json!({ "status": "completed" }),
+            ))
+            .unwrap();
+        manifest.repo_root = repo_root.display().to_string();
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+        layout.run_id()
+    }
+
+    #[tokio::test]
+    async fn framed_and_line_requests_get_matching_replies() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = completed_run(tmp.path());
+        let server = Arc::new(Server::new(tmp.path().to_path_buf(), String::new()));
+
+        let diff = json!({
+            "jsonrpc": "2.0", "id": 1, "method": "run/diff",
+            "params": { "run_id": run_id, "agent": "claude" },
+        })
+        .to_string();
+        let input = format!(
+            "Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{diff}\
+             {{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"run/get\",\"params\":{{\"run_id\":\"nope\"}}}}\n\
+             {{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"races/list\"}}\n\
+             not json\n",
+            diff.len()
+        );
+        let (client, server_out) = duplex(64 * 1024);
+        serve(server, &mut BufReader::new(input.as_bytes()), server_out)
+            .await
+            .unwrap();
+
+        let mut replies = String::new();
+        BufReader::new(client)
+            .read_to_string(&mut replies)
+            .await
+            .unwrap();
+        let mut by_id = HashMap::new();
+        let mut rest = replies.as_str();
+        while !rest.is_empty() {
+            let (framing, message) = match rest.strip_prefix("Content-Length: ") {
+                Some(framed) => {
+                    let (len, body) = framed.split_once("\r\n\r\n").unwrap();
+                    let len: usize = len.parse().unwrap();
+                    rest = &body[len..];
+                    (Framing::Headers, &body[..len])
+                }
+                None => {
+                    let (line, tail) = rest.split_once('\n').unwrap();
+                    rest = tail;
+                    (Framing::Lines, line)
+                }
+            };
+            let message: Value = serde_json::from_str(message).unwrap();
+            by_id.insert(message["id"].to_string(), (framing, message));
+        }
+
+        let (framing, diff) = &by_id["1"];
+        assert_eq!(*framing, Framing::Headers);
+        assert_eq!(diff["result"], "diff --git a/x b/x\n");
+        let (framing, bad_run) = &by_id["2"];
+        assert_eq!(*framing, Framing::Lines);
+        assert_eq!(bad_run["error"]["code"], INVALID_PARAMS);
+        assert_eq!(bad_run["error"]["data"]["status"], 400);
+        assert_eq!(by_id["3"].1["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(by_id["null"].1["error"]["code"], PARSE_ERROR);
+    }
+}
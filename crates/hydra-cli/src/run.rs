@@ -7,11 +7,26 @@ use anyhow::{bail, Context, Result};
 use clap::Subcommand;
 use uuid::Uuid;
 
-use hydra_core::artifact::{RunLayout, RunManifest};
+use hydra_core::artifact::{has_tags, read_agent_score, RunLayout, RunManifest, RunStatus};
 use hydra_core::scoring::ranking::AgentScore;
+use hydra_core::tr;
+use hydra_core::worktree::{WorktreeEntry, WorktreeInfo, WorktreeService};
+
+use crate::race::{field, mergeable_label, print_commits, print_rankings, title};
+use crate::{config, output};
 
 #[derive(Subcommand)]
 pub enum RunCommand {
+    /// List stored runs, newest first
+    List {
+        /// Only runs carrying this tag (repeatable; runs must have all of them)
+        #[arg(long = "tag", value_parser = hydra_core::artifact::parse_tag)]
+        tags: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Show summary details for a stored run
     Show {
         /// Explicit run ID to inspect
@@ -22,6 +37,19 @@ pub enum RunCommand {
         #[arg(long)]
         latest: bool,
 
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete a stored run with its agent worktrees and branches
+    Rm {
+        /// Run ID to delete
+        run_id: Uuid,
+
+        /// Delete even while the run is active
+        #[arg(long)]
+        force: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -30,15 +58,79 @@ pub enum RunCommand {
 
 pub fn run_command(command: RunCommand) -> Result<()> {
     match command {
+        RunCommand::List { tags, json } => run_list(&tags, json),
         RunCommand::Show {
             run_id,
             latest,
             json,
         } => run_show(run_id, latest, json),
+        RunCommand::Rm {
+            run_id,
+            force,
+            json,
+        } => run_rm(run_id, force, json),
     }
 }
 
-fn run_show(run_id: Option<Uuid>, latest: bool, json: bool) -> Result<()> {
+fn run_list(tags: &[String], json: bool) -> Result<()> {
+    let hydra_root = discover_repo_root()?.join(".hydra");
+    let manifests = list_manifests(&hydra_root, tags)?;
+
+    let runs: Vec<serde_json::Value> = manifests
+        .iter()
+        .map(|m| {
+            serde_json::json!({
+                "run_id": m.run_id,
+                "status": m.status,
+                "started_at": m.started_at,
+                "completed_at": m.completed_at,
+                "agents": m.agents.iter().map(|a| &a.agent_key).collect::<Vec<_>>(),
+                "tags": m.tags,
+            })
+        })
+        .collect();
+    output::emit(&serde_json::json!({ "runs": runs }), json)?;
+    if json {
+        return Ok(());
+    }
+
+    if manifests.is_empty() {
+        println!("No runs found.");
+        return Ok(());
+    }
+    for m in &manifests {
+        let agents: Vec<&str> = m.agents.iter().map(|a| a.agent_key.as_str()).collect();
+        println!(
+            "{}  {:<11}  {}  {}{}",
+            m.run_id,
+            format!("{:?}", m.status),
+            m.started_at.format("%Y-%m-%d %H:%M"),
+            agents.join(","),
+            if m.tags.is_empty() {
+                String::new()
+            } else {
+                format!("  [{}]", m.tags.join(", "))
+            }
+        );
+    }
+    Ok(())
+}
+
+/// Manifests of every readable run carrying all of `tags`, newest first.
+fn list_manifests(hydra_root: &Path, tags: &[String]) -> Result<Vec<RunManifest>> {
+    let mut manifests: Vec<RunManifest> = RunLayout::list_runs(hydra_root)
+        .context("failed to list runs")?
+        .into_iter()
+        .filter_map(|id| {
+            RunManifest::read_from(&RunLayout::new(hydra_root, id).manifest_path()).ok()
+        })
+        .filter(|m| has_tags(&m.tags, tags))
+        .collect();
+    manifests.sort_by_key(|m| std::cmp::Reverse(m.started_at));
+    Ok(manifests)
+}
+
+pub(crate) fn run_show(run_id: Option<Uuid>, latest: bool, json: bool) -> Result<()> {
     let repo_root = discover_repo_root()?;
     let hydra_root = repo_root.join(".hydra");
     let selected_run_id = resolve_run_id(&hydra_root, run_id, latest)?;
@@ -69,7 +161,7 @@ fn run_show(run_id: Option<Uuid>, latest: bool, json: bool) -> Result<()> {
             .unwrap_or(Ordering::Equal)
     });
 
-    if json {
+    if json || output::requested() {
         let winner = rankings
             .iter()
             .find(|score| score.mergeable)
@@ -85,84 +177,149 @@ fn run_show(run_id: Option<Uuid>, latest: bool, json: bool) -> Result<()> {
                     "tier": agent.tier,
                     "branch": agent.branch,
                     "worktree_path": agent.worktree_path,
+                    "commits": manifest.candidate_commits.get(&agent.agent_key),
                     "score": score,
                 })
             })
             .collect::<Vec<_>>();
 
-        let output = serde_json::json!({
+        let result = serde_json::json!({
             "run_id": manifest.run_id,
             "status": manifest.status,
             "started_at": manifest.started_at,
             "completed_at": manifest.completed_at,
+            "tags": manifest.tags,
             "manifest_path": layout.manifest_path(),
             "artifacts_path": layout.base_dir(),
             "winner": winner,
             "rankings": rankings,
             "agents": agents,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        output::emit(&result, json)?;
+        if json {
+            return Ok(());
+        }
     }
 
-    println!("Run Summary");
-    println!("===========");
-    println!("  Run ID:    {}", manifest.run_id);
-    println!("  Status:    {:?}", manifest.status);
-    println!("  Started:   {}", manifest.started_at);
+    title("summary.title");
+    field("  ", "summary.run_id", manifest.run_id);
+    field("  ", "summary.status", format!("{:?}", manifest.status));
+    field("  ", "summary.started", manifest.started_at);
     if let Some(completed_at) = manifest.completed_at {
-        println!("  Completed: {}", completed_at);
+        field("  ", "summary.completed", completed_at);
+    }
+    field("  ", "summary.artifacts", layout.base_dir().display());
+    field("  ", "summary.manifest", layout.manifest_path().display());
+    if !manifest.tags.is_empty() {
+        field("  ", "summary.tags", manifest.tags.join(", "));
     }
-    println!("  Artifacts: {}", layout.base_dir().display());
-    println!("  Manifest:  {}", layout.manifest_path().display());
     println!();
 
     for agent in &manifest.agents {
-        println!("  Agent:     {}", agent.agent_key);
-        println!("    Tier:      {}", agent.tier);
-        println!("    Branch:    {}", agent.branch);
+        field("  ", "summary.agent", &agent.agent_key);
+        field("    ", "summary.tier", &agent.tier);
+        field("    ", "summary.branch", &agent.branch);
+        print_commits(manifest.candidate_commits.get(&agent.agent_key));
         if let Some(score) = scores_by_agent.get(&agent.agent_key) {
-            println!(
-                "    Score:     {:.1} ({})",
-                score.composite,
-                if score.mergeable {
-                    "mergeable"
-                } else {
-                    "not mergeable"
-                }
+            field(
+                "    ",
+                "summary.score",
+                format!(
+                    "{:.1} ({})",
+                    score.composite,
+                    mergeable_label(score.mergeable)
+                ),
             );
             if !score.gate_failures.is_empty() {
-                println!("    Gates:     {}", score.gate_failures.join("; "));
+                field("    ", "summary.gates", score.gate_failures.join("; "));
             }
         } else {
-            println!("    Score:     unavailable");
+            field("    ", "summary.score", tr!("common.unavailable"));
         }
         println!();
     }
 
-    println!("  Rankings:");
-    if rankings.is_empty() {
-        println!("    (none)");
-    } else {
-        for (idx, score) in rankings.iter().enumerate() {
-            println!(
-                "    {}. {} {:.1} {}",
-                idx + 1,
-                score.agent_key,
-                score.composite,
-                if score.mergeable {
-                    "(mergeable)"
-                } else {
-                    "(not mergeable)"
-                }
-            );
+    print_rankings(&rankings);
+
+    Ok(())
+}
+
+fn run_rm(run_id: Uuid, force: bool, json: bool) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let config = config::load()?;
+    let service =
+        WorktreeService::new(repo_root.clone(), repo_root.join(&config.worktree.base_dir));
+    let layout = RunLayout::new(&repo_root.join(".hydra"), run_id);
+    let rt = tokio::runtime::Runtime::new()?;
+    let branches = rt.block_on(remove_run(&service, &layout, force))?;
+
+    output::emit(
+        &serde_json::json!({ "run_id": run_id, "deleted_branches": branches }),
+        json,
+    )?;
+    if !json {
+        println!(
+            "Removed run {run_id} and {} agent branch(es)",
+            branches.len()
+        );
+    }
+    Ok(())
+}
+
+/// Delete a run's worktrees, agent branches and artifacts, returning the
+/// branches. Agents are taken from the manifest and from the worktrees git
+/// knows, so a run whose setup broke off part way is removed too.
+async fn remove_run(
+    service: &WorktreeService,
+    layout: &RunLayout,
+    force: bool,
+) -> Result<Vec<String>> {
+    let run_id = layout.run_id();
+    let manifest = RunManifest::read_from(&layout.manifest_path()).ok();
+    if !force && manifest.as_ref().map(|m| &m.status) == Some(&RunStatus::Running) {
+        bail!("run {run_id} is still running; cancel it first or pass --force");
+    }
+
+    let mut agents: Vec<WorktreeInfo> = service
+        .list()
+        .await
+        .context("failed to list worktrees")?
+        .iter()
+        .filter_map(WorktreeEntry::agent_info)
+        .filter(|info| info.run_id == run_id)
+        .collect();
+    for agent in manifest.iter().flat_map(|m| &m.agents) {
+        if !agents.iter().any(|info| info.branch == agent.branch) {
+            agents.push(WorktreeInfo {
+                path: agent.worktree_path.clone().unwrap_or_default().into(),
+                branch: agent.branch.clone(),
+                run_id,
+                agent_key: agent.agent_key.clone(),
+            });
         }
     }
+    if agents.is_empty() && !layout.base_dir().exists() {
+        bail!("run {run_id} not found");
+    }
 
-    Ok(())
+    for info in &agents {
+        service
+            .force_cleanup(info)
+            .await
+            .with_context(|| format!("failed to remove the worktree of {}", info.agent_key))?;
+    }
+    if layout.base_dir().exists() {
+        std::fs::remove_dir_all(layout.base_dir())
+            .with_context(|| format!("failed to remove {}", layout.base_dir().display()))?;
+    }
+    Ok(agents.into_iter().map(|info| info.branch).collect())
 }
 
-fn resolve_run_id(hydra_root: &Path, run_id: Option<Uuid>, latest: bool) -> Result<Uuid> {
+pub(crate) fn resolve_run_id(
+    hydra_root: &Path,
+    run_id: Option<Uuid>,
+    latest: bool,
+) -> Result<Uuid> {
     if run_id.is_some() && latest {
         bail!("use either --run-id or --latest, not both");
     }
@@ -212,30 +369,17 @@ fn latest_run_id(hydra_root: &Path) -> Result<Uuid> {
         .ok_or_else(|| anyhow::anyhow!("no valid run directories found at {}", runs_dir.display()))
 }
 
-fn load_agent_score(layout: &RunLayout, agent_key: &str) -> Result<Option<AgentScore>> {
-    let score_path = layout.agent_score(agent_key);
-    if !score_path.exists() {
-        return Ok(None);
-    }
-
-    let content = std::fs::read_to_string(&score_path).with_context(|| {
+pub(crate) fn load_agent_score(layout: &RunLayout, agent_key: &str) -> Result<Option<AgentScore>> {
+    read_agent_score(layout, agent_key).with_context(|| {
         format!(
             "failed to read score file for agent '{}' at {}",
             agent_key,
-            score_path.display()
-        )
-    })?;
-    let score: AgentScore = serde_json::from_str(&content).with_context(|| {
-        format!(
-            "failed to parse score file for agent '{}' at {}",
-            agent_key,
-            score_path.display()
+            layout.agent_score(agent_key).display()
         )
-    })?;
-    Ok(Some(score))
+    })
 }
 
-fn discover_repo_root() -> Result<PathBuf> {
+pub(crate) fn discover_repo_root() -> Result<PathBuf> {
     let output = std::process::Command::new("git")
         .args(["rev-parse", "--show-toplevel"])
         .output()
@@ -254,6 +398,16 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
     #[test]
     fn resolve_run_id_rejects_conflicting_flags() {
         let hydra_root = PathBuf::from("/tmp/does-not-matter");
@@ -278,4 +432,74 @@ mod tests {
         let selected = latest_run_id(&hydra_root).unwrap();
         assert_eq!(selected, id_new);
     }
+
+    #[test]
+    fn list_manifests_filters_by_every_tag() {
+        let tmp = TempDir::new().unwrap();
+        let hydra_root = tmp.path().join(".hydra");
+        let write = |tags: &[&str]| {
+            let (layout, manifest) = hydra_core::test_support::new_run(&hydra_root, &[]);
+            manifest
+                .with_tags(tags.iter().map(|t| t.to_string()).collect())
+                .write_to(&layout.manifest_path())
+                .unwrap();
+            layout.run_id()
+        };
+        let both = write(&["backend", "sprint-42"]);
+        write(&["backend"]);
+        write(&[]);
+
+        assert_eq!(list_manifests(&hydra_root, &[]).unwrap().len(), 3);
+        assert_eq!(
+            list_manifests(&hydra_root, &["backend".to_string()])
+                .unwrap()
+                .len(),
+            2
+        );
+        let tagged = list_manifests(
+            &hydra_root,
+            &["backend".to_string(), "sprint-42".to_string()],
+        )
+        .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].run_id, both);
+    }
+
+    #[tokio::test]
+    async fn remove_run_deletes_worktrees_branches_and_artifacts() {
+        let repo = TempDir::new().unwrap();
+        git(repo.path(), &["init", "-q"]);
+        git(repo.path(), &["config", "user.email", "test@example.com"]);
+        git(repo.path(), &["config", "user.name", "Test"]);
+        std::fs::write(repo.path().join("a.txt"), "one\n").unwrap();
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "-q", "-m", "init"]);
+
+        let hydra_root = repo.path().join(".hydra");
+        let service = WorktreeService::new(repo.path().to_path_buf(), hydra_root.join("worktrees"));
+        let (layout, mut manifest) =
+            hydra_core::test_support::new_run(&hydra_root, &["claude", "codex"]);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+        let run_id = layout.run_id();
+        let claude = service.create(run_id, "claude", "HEAD").await.unwrap();
+        // Setup stopped before codex got a worktree, leaving only its branch.
+        git(
+            repo.path(),
+            &["branch", &format!("hydra/{run_id}/agent/codex")],
+        );
+
+        let err = remove_run(&service, &layout, false).await.unwrap_err();
+        assert!(err.to_string().contains("still running"));
+        assert!(claude.path.exists());
+
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+        let branches = remove_run(&service, &layout, false).await.unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(!claude.path.exists());
+        assert!(!layout.base_dir().exists());
+        assert_eq!(git(repo.path(), &["branch", "--list", "hydra/*"]), "");
+
+        assert!(remove_run(&service, &layout, false).await.is_err());
+    }
 }
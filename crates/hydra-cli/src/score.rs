@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use uuid::Uuid;
+
+use hydra_core::artifact::AgentScoreArtifact;
+use hydra_core::config::HydraConfig;
+use hydra_core::scoring::baseline::{capture_baseline, resolve_commands};
+use hydra_core::scoring::evaluate::evaluate_agent_dimensions;
+use hydra_core::scoring::ranking::{rank_agents, AgentScore};
+use hydra_core::security::SecretRedactor;
+use hydra_core::worktree::{WorktreeInfo, WorktreeService};
+
+use crate::run::discover_repo_root;
+use crate::{config, output};
+
+pub struct ScoreOpts {
+    /// Branch/ref name, or path to an existing checkout.
+    pub target: String,
+    pub base_ref: String,
+    pub json: bool,
+}
+
+/// What is being scored.
+#[derive(Debug, PartialEq, Eq)]
+enum ScoreTarget {
+    /// A directory scored in place, e.g. an existing worktree.
+    Path(PathBuf),
+    /// A ref checked out into a temporary worktree.
+    Ref(String),
+}
+
+impl ScoreTarget {
+    fn resolve(repo_root: &Path, target: &str) -> Result<Self> {
+        let path = Path::new(target);
+        if path.is_dir() {
+            return Ok(Self::Path(path.canonicalize()?));
+        }
+        if target.starts_with('-') || target.chars().any(char::is_whitespace) {
+            bail!("invalid target '{target}'");
+        }
+        let output = std::process::Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet"])
+            .arg(format!("{target}^{{commit}}"))
+            .current_dir(repo_root)
+            .output()
+            .context("failed to run git rev-parse")?;
+        if !output.status.success() {
+            bail!("target '{target}' is neither a directory nor a git ref");
+        }
+        Ok(Self::Ref(target.to_string()))
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Path(path) => path.display().to_string(),
+            Self::Ref(name) => name.clone(),
+        }
+    }
+}
+
+/// Score a branch or checkout against `base_ref`. Returns whether it passed
+/// the mergeability gates.
+pub async fn run_score(opts: ScoreOpts) -> Result<bool> {
+    let repo_root = discover_repo_root()?;
+    let config = config::load()?;
+    let target = ScoreTarget::resolve(&repo_root, &opts.target)?;
+
+    let score_id = Uuid::new_v4();
+    let artifacts_dir = repo_root
+        .join(".hydra")
+        .join("scores")
+        .join(score_id.to_string());
+    std::fs::create_dir_all(&artifacts_dir)
+        .with_context(|| format!("failed to create {}", artifacts_dir.display()))?;
+
+    let wt_service =
+        WorktreeService::new(repo_root.clone(), repo_root.join(&config.worktree.base_dir));
+    let mut worktrees = Vec::new();
+    let result = score_target(
+        &wt_service,
+        &mut worktrees,
+        score_id,
+        &target,
+        &opts.base_ref,
+        &config,
+        &artifacts_dir,
+    )
+    .await;
+
+    for wt in &worktrees {
+        if let Err(e) = wt_service.force_cleanup(wt).await {
+            tracing::warn!(path = %wt.path.display(), error = %e, "failed to clean up scoring worktree");
+        }
+    }
+    let score = result?;
+
+    let score_path = artifacts_dir.join("score.json");
+    AgentScoreArtifact::new(score.clone())
+        .write_to(
+            &score_path,
+            &SecretRedactor::from_config(&config.security.redaction),
+        )
+        .with_context(|| format!("failed to write {}", score_path.display()))?;
+
+    let result = serde_json::json!({
+        "target": target.label(),
+        "base_ref": opts.base_ref,
+        "artifacts_path": artifacts_dir,
+        "score": score,
+    });
+    output::emit(&result, opts.json)?;
+    if opts.json {
+        return Ok(score.mergeable);
+    }
+
+    println!("Score");
+    println!("=====");
+    println!("  Target:    {}", target.label());
+    println!("  Base:      {}", opts.base_ref);
+    println!(
+        "  Composite: {:.1} ({})",
+        score.composite,
+        if score.mergeable {
+            "mergeable"
+        } else {
+            "not mergeable"
+        }
+    );
+    if !score.gate_failures.is_empty() {
+        println!("  Gates:     {}", score.gate_failures.join("; "));
+    }
+    println!("  Artifacts: {}", artifacts_dir.display());
+    println!();
+    println!("  Dimensions:");
+    for dim in &score.dimensions {
+        println!("    {:<12} {:>6.1}", dim.name, dim.score);
+    }
+
+    Ok(score.mergeable)
+}
+
+/// Check out the baseline (and the candidate, for refs) and run the scoring
+/// pipeline. Created worktrees are pushed to `worktrees` for the caller to
+/// clean up even when scoring fails.
+async fn score_target(
+    wt_service: &WorktreeService,
+    worktrees: &mut Vec<WorktreeInfo>,
+    score_id: Uuid,
+    target: &ScoreTarget,
+    base_ref: &str,
+    config: &HydraConfig,
+    artifacts_dir: &Path,
+) -> Result<AgentScore> {
+    let baseline_wt = wt_service
+        .create(score_id, "baseline", base_ref)
+        .await
+        .with_context(|| format!("failed to check out base ref '{base_ref}'"))?;
+    worktrees.push(baseline_wt.clone());
+
+    let candidate_path = match target {
+        ScoreTarget::Path(path) => path.clone(),
+        ScoreTarget::Ref(name) => {
+            let wt = wt_service
+                .create(score_id, "candidate", name)
+                .await
+                .with_context(|| format!("failed to check out target '{name}'"))?;
+            worktrees.push(wt.clone());
+            wt.path
+        }
+    };
+
+    let baseline = capture_baseline(&baseline_wt.path, &config.scoring)
+        .await
+        .context("failed to capture baseline")?;
+    let commands = resolve_commands(&config.scoring);
+    let dimensions = evaluate_agent_dimensions(
+        &candidate_path,
+        artifacts_dir,
+        base_ref,
+        config,
+        &baseline,
+        &commands,
+    )
+    .await?;
+
+    rank_agents(
+        vec![(target.label(), dimensions)],
+        &config.scoring.weights,
+        &config.scoring.gates,
+        &HashMap::new(),
+    )
+    .pop()
+    .context("scoring produced no result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn init_repo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.email", "test@example.com"],
+            &["config", "user.name", "Test"],
+            &["commit", "-q", "--allow-empty", "-m", "init"],
+        ] {
+            let out = std::process::Command::new("git")
+                .args(args)
+                .current_dir(tmp.path())
+                .output()
+                .unwrap();
+            assert!(out.status.success());
+        }
+        tmp
+    }
+
+    #[test]
+    fn resolves_directories_and_refs() {
+        let repo = init_repo();
+        let dir = repo.path().to_string_lossy().to_string();
+        assert!(matches!(
+            ScoreTarget::resolve(repo.path(), &dir).unwrap(),
+            ScoreTarget::Path(_)
+        ));
+        assert_eq!(
+            ScoreTarget::resolve(repo.path(), "main").unwrap(),
+            ScoreTarget::Ref("main".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_option_like_targets() {
+        let repo = init_repo();
+        assert!(ScoreTarget::resolve(repo.path(), "no-such-branch").is_err());
+        assert!(ScoreTarget::resolve(repo.path(), "--output=x").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use uuid::Uuid;
+
+use hydra_core::artifact::RunLayout;
+use hydra_core::security::{dry_run_policy, DryRunReport, PolicyOutcome};
+
+use crate::run::{discover_repo_root, resolve_run_id};
+use crate::{config, output};
+
+#[derive(Subcommand)]
+pub enum SecurityCommand {
+    /// Replay a stored run against the current security policy and report
+    /// what it would have blocked or redacted
+    Check {
+        /// Run ID to replay
+        #[arg(long)]
+        run_id: Option<Uuid>,
+
+        /// Replay the most recently modified run directory
+        #[arg(long)]
+        latest: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run_command(command: SecurityCommand) -> Result<()> {
+    match command {
+        SecurityCommand::Check {
+            run_id,
+            latest,
+            json,
+        } => check(run_id, latest, json),
+    }
+}
+
+fn check(run_id: Option<Uuid>, latest: bool, json: bool) -> Result<()> {
+    let hydra_root = discover_repo_root()?.join(".hydra");
+    let run_id = resolve_run_id(&hydra_root, run_id, latest)?;
+    let layout = RunLayout::new(&hydra_root, run_id);
+    if !layout.base_dir().exists() {
+        bail!("run {run_id} not found at {}", layout.base_dir().display());
+    }
+
+    let config = config::load()?;
+    let report = dry_run_policy(&layout, &config.security)
+        .with_context(|| format!("failed to replay run {run_id}"))?;
+
+    output::emit(
+        &serde_json::json!({ "run_id": run_id, "findings": report.findings }),
+        json,
+    )?;
+    if !json {
+        print_report(&run_id, &report);
+    }
+    Ok(())
+}
+
+fn print_report(run_id: &Uuid, report: &DryRunReport) {
+    if report.findings.is_empty() {
+        println!("Run {run_id}: nothing would be blocked or redacted under the current policy.");
+        return;
+    }
+
+    println!("Run {run_id} under the current policy:");
+    for finding in &report.findings {
+        println!(
+            "  [{}] {} ({}): {}",
+            finding.agent_key.as_deref().unwrap_or("run"),
+            finding.outcome,
+            finding.area,
+            finding.detail
+        );
+        println!("      at {}", finding.source);
+    }
+    println!(
+        "{} blocked, {} needing approval, {} reverted, {} redacted",
+        report.count(PolicyOutcome::Blocked),
+        report.count(PolicyOutcome::NeedsApproval),
+        report.count(PolicyOutcome::Reverted),
+        report.count(PolicyOutcome::Redacted),
+    );
+}
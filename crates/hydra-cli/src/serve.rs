@@ -0,0 +1,1150 @@
+//! `hydra serve`: the orchestrator over HTTP, for editors, scripts and
+//! remote UIs that cannot embed the desktop app.
+//!
+//! Every endpoint except `GET /v1/health` needs `Authorization: Bearer
+//! <token>`. Browsers cannot set headers on a WebSocket, so the event
+//! stream also accepts the token as `?token=`.
+//!
+//! | Method | Path                                      |                                   |
+//! |--------|-------------------------------------------|-----------------------------------|
+//! | GET    | `/v1/health`                              | liveness and version              |
+//! | GET    | `/metrics`                                | Prometheus metrics                |
+//! | GET    | `/v1/runs?tag=A,B`                        | run summaries, newest first       |
+//! | POST   | `/v1/races`                               | start a race                      |
+//! | GET    | `/v1/runs/{id}`                           | run manifest                      |
+//! | GET    | `/v1/runs/{id}/events?since=N&agent=KEY`  | events (WebSocket upgrade streams)|
+//! | GET    | `/v1/runs/{id}/agents/{key}/score`        | agent score                       |
+//! | GET    | `/v1/runs/{id}/agents/{key}/diff`         | agent diff                        |
+//! | GET    | `/v1/runs/{id}/agents/{key}/edited-files` | files the agent has edited so far |
+//! | POST   | `/v1/runs/{id}/cancel`                    | cancel the run or one agent       |
+//! | POST   | `/v1/runs/{id}/approvals/{approval_id}`   | answer an approval request        |
+//! | POST   | `/v1/runs/{id}/merge`                     | merge a candidate                 |
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use hydra_core::artifact::{
+    dedup_tags, has_tags, parse_tag, EditedFiles, EventBuffer, EventKind, EventReader, EventSinks,
+    LockMode, PromptSource, RunEvent, RunLayout, RunManifest,
+};
+use hydra_core::config::HydraConfig;
+use hydra_core::orchestrator::{
+    ApprovalGate, CancelToken, Orchestrator, PromptVariant, RaceEvent, RaceOptions,
+};
+use hydra_core::security::ApprovalDecision;
+use hydra_core::task::TaskSpec;
+
+use crate::run::discover_repo_root;
+use crate::watch::{manifest_is_settled, RunEventSources};
+use crate::websocket::{self, ClientFrame};
+
+pub const DEFAULT_PORT: u16 = 7700;
+
+/// Token used when `--token` is not given.
+const TOKEN_ENV: &str = "HYDRA_SERVE_TOKEN";
+
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+pub(crate) const MAX_REQUEST_BODY: usize = 1024 * 1024;
+
+/// How often a WebSocket event stream checks the run's logs.
+const STREAM_POLL: Duration = Duration::from_millis(250);
+
+pub struct ServeOpts {
+    pub host: IpAddr,
+    pub port: u16,
+    pub token: Option<String>,
+}
+
+pub async fn run_serve(opts: ServeOpts) -> Result<()> {
+    let (listener, server) = bind(discover_repo_root()?, opts.host, opts.port, opts.token).await?;
+    tokio::select! {
+        result = Arc::clone(&server).serve(listener) => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    server.shutdown().await;
+    Ok(())
+}
+
+/// Listen on `host:port` and announce the address on stderr, along with the
+/// token when it had to be generated.
+pub(crate) async fn bind(
+    repo_root: PathBuf,
+    host: IpAddr,
+    port: u16,
+    token: Option<String>,
+) -> Result<(TcpListener, Arc<Server>)> {
+    let configured = token
+        .or_else(|| std::env::var(TOKEN_ENV).ok())
+        .filter(|token| !token.is_empty());
+    let generated = configured.is_none();
+    let token = configured.unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+
+    let listener = TcpListener::bind((host, port))
+        .await
+        .with_context(|| format!("failed to listen on {host}:{port}"))?;
+    let addr = listener.local_addr()?;
+    if !host.is_loopback() {
+        tracing::warn!(%addr, "API reachable from other machines; anyone with the token can run agents");
+    }
+
+    eprintln!("Hydra API listening on http://{addr}");
+    if generated {
+        eprintln!("  token: {token}");
+        eprintln!("  (set {TOKEN_ENV} or pass --token to choose one)");
+    }
+
+    Ok((listener, Arc::new(Server::new(repo_root, token))))
+}
+
+pub(crate) struct Server {
+    repo_root: PathBuf,
+    hydra_root: PathBuf,
+    token: String,
+    /// Config to load instead of the resolved `hydra.toml`.
+    config_path: Option<PathBuf>,
+    races: Mutex<HashMap<Uuid, RaceState>>,
+}
+
+/// A race started through this server.
+#[derive(Clone)]
+struct RaceState {
+    approvals: ApprovalGate,
+    cancel: CancelToken,
+    /// Every event the race has written so far, for WebSocket clients.
+    events: EventBuffer,
+    /// `None` while running; the error when the race ended with one.
+    result: Option<std::result::Result<(), String>>,
+}
+
+impl Server {
+    pub(crate) fn new(repo_root: PathBuf, token: String) -> Self {
+        Self {
+            hydra_root: repo_root.join(".hydra"),
+            repo_root,
+            token,
+            config_path: None,
+            races: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Load config from `path`, which may not exist yet, rather than from
+    /// the `hydra.toml` around the working directory.
+    pub(crate) fn with_config(mut self, path: PathBuf) -> Self {
+        self.config_path = Some(path);
+        self
+    }
+
+    fn config_path(&self) -> PathBuf {
+        self.config_path.clone().unwrap_or_else(crate::config::path)
+    }
+
+    fn config(&self) -> Result<HydraConfig, ApiError> {
+        let path = self.config_path();
+        HydraConfig::load_or_default(&path, &self.repo_root)
+            .map_err(|e| ApiError::internal(format!("failed to load {}: {e}", path.display())))
+    }
+
+    pub(crate) async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = server.handle(stream).await {
+                    tracing::debug!(%peer, error = %e, "API connection ended");
+                }
+            });
+        }
+    }
+
+    /// Cancel the races this server started and wait for them to clean up
+    /// their worktrees.
+    pub(crate) async fn shutdown(&self) {
+        let running: Vec<CancelToken> = self
+            .lock()
+            .values()
+            .filter(|race| race.result.is_none())
+            .map(|race| race.cancel.clone())
+            .collect();
+        if running.is_empty() {
+            return;
+        }
+        eprintln!("Cancelling {} running race(s)...", running.len());
+        for cancel in &running {
+            cancel.cancel();
+        }
+        while self.lock().values().any(|race| race.result.is_none()) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, RaceState>> {
+        self.races.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn handle(self: Arc<Self>, mut stream: TcpStream) -> std::io::Result<()> {
+        let request = match read_request(&mut stream, |_| MAX_REQUEST_BODY).await {
+            Ok(request) => request,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return ApiError::bad_request(e)
+                    .into_response()
+                    .write_to(&mut stream)
+                    .await;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let upgrade = request
+            .header("upgrade")
+            .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+        let public = request.method == "GET" && request.path == "/v1/health";
+        if !public && !self.authorized(&request, upgrade) {
+            return ApiError::new(401, "missing or invalid token")
+                .into_response()
+                .write_to(&mut stream)
+                .await;
+        }
+
+        if upgrade {
+            return match self.stream_target(&request) {
+                Ok((layout, key)) => self.stream_events(stream, &layout, &key).await,
+                Err(e) => e.into_response().write_to(&mut stream).await,
+            };
+        }
+        let response = self
+            .route(&request)
+            .await
+            .unwrap_or_else(ApiError::into_response);
+        response.write_to(&mut stream).await
+    }
+
+    fn authorized(&self, request: &Request, upgrade: bool) -> bool {
+        let bearer = request
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "));
+        // Query tokens end up in access logs, so only the WebSocket
+        // handshake, which cannot carry headers from a browser, takes one.
+        let query = upgrade
+            .then(|| request.query.get("token").map(String::as_str))
+            .flatten();
+        bearer
+            .or(query)
+            .is_some_and(|given| constant_time_eq(given.trim(), &self.token))
+    }
+
+    pub(crate) async fn route(self: &Arc<Self>, request: &Request) -> Result<Response, ApiError> {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["v1", "health"]) => Ok(Response::json(
+                200,
+                &serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+            )),
+            ("GET", ["v1", "runs"]) => self.list_runs(request),
+            ("GET", ["metrics"]) => {
+                let config = self.config()?;
+                let text = crate::metrics::render(&self.repo_root, &config)
+                    .await
+                    .map_err(|e| ApiError::internal(format!("{e:#}")))?;
+                Ok(Response::text(200, crate::metrics::CONTENT_TYPE, text))
+            }
+            ("POST", ["v1", "races"]) => self.start_race(request),
+            ("GET", ["v1", "runs", run]) => self.show_run(run),
+            ("GET", ["v1", "runs", run, "events"]) => self.poll_events(run, request),
+            ("GET", ["v1", "runs", run, "agents", agent, "score"]) => {
+                let path = self.layout(run)?.agent_score(&agent_key(agent)?);
+                let score = read_artifact(&path, "score")?;
+                let score: serde_json::Value = serde_json::from_str(&score)
+                    .map_err(|e| ApiError::internal(format!("invalid score file: {e}")))?;
+                Ok(Response::json(200, &score))
+            }
+            ("GET", ["v1", "runs", run, "agents", agent, "diff"]) => {
+                let path = self.layout(run)?.agent_diff(&agent_key(agent)?);
+                Ok(Response::text(
+                    200,
+                    "text/x-diff",
+                    read_artifact(&path, "diff")?,
+                ))
+            }
+            ("GET", ["v1", "runs", run, "agents", agent, "edited-files"]) => {
+                let path = self.layout(run)?.agent_edited_files(&agent_key(agent)?);
+                let edited = EditedFiles::read_from(&path).map_err(ApiError::internal)?;
+                Ok(Response::json(200, &edited))
+            }
+            ("POST", ["v1", "runs", run, "cancel"]) => self.cancel(run, request),
+            ("POST", ["v1", "runs", run, "approvals", approval]) => {
+                self.resolve_approval(run, approval, request)
+            }
+            ("POST", ["v1", "runs", run, "merge"]) => self.merge(run, request).await,
+            _ => Err(ApiError::not_found(format!(
+                "no route for {} {}",
+                request.method, request.path
+            ))),
+        }
+    }
+
+    fn list_runs(&self, request: &Request) -> Result<Response, ApiError> {
+        let tags = request
+            .query
+            .get("tag")
+            .map(|raw| raw.split(',').map(parse_tag).collect::<Result<Vec<_>, _>>())
+            .transpose()
+            .map_err(ApiError::bad_request)?
+            .unwrap_or_default();
+        let ids = RunLayout::list_runs(&self.hydra_root).map_err(ApiError::internal)?;
+        let mut runs: Vec<RunManifest> = ids
+            .into_iter()
+            .filter_map(|id| {
+                RunManifest::read_from(&RunLayout::new(&self.hydra_root, id).manifest_path()).ok()
+            })
+            .filter(|m| has_tags(&m.tags, &tags))
+            .collect();
+        runs.sort_by_key(|m| std::cmp::Reverse(m.started_at));
+
+        let runs: Vec<serde_json::Value> = runs
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "run_id": m.run_id,
+                    "status": m.status,
+                    "started_at": m.started_at,
+                    "completed_at": m.completed_at,
+                    "agents": m.agents.iter().map(|a| &a.agent_key).collect::<Vec<_>>(),
+                    "tags": m.tags,
+                })
+            })
+            .collect();
+        Ok(Response::json(200, &serde_json::json!({ "runs": runs })))
+    }
+
+    fn start_race(self: &Arc<Self>, request: &Request) -> Result<Response, ApiError> {
+        let body: RaceRequest = request.json()?;
+        let prompt = crate::parse_prompt(&body.prompt).map_err(ApiError::bad_request)?;
+        let base_ref = crate::parse_base_ref(&body.base_ref).map_err(ApiError::bad_request)?;
+        let agents = body
+            .agents
+            .iter()
+            .map(|key| crate::parse_agent_key(key))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(ApiError::bad_request)?;
+        let tags = body
+            .tags
+            .iter()
+            .map(|tag| parse_tag(tag))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(ApiError::bad_request)?;
+        // Reloaded per race so edits to hydra.toml apply without a restart.
+        let config = self.config()?;
+
+        let run_id = Uuid::new_v4();
+        let state = RaceState {
+            approvals: ApprovalGate::new(),
+            cancel: CancelToken::new(),
+            events: EventBuffer::new(),
+            result: None,
+        };
+        let options = RaceOptions {
+            agents,
+            prompt,
+            prompt_source: body.prompt_source,
+            tags: dedup_tags(tags),
+            task: body.task,
+            variants: body.variants,
+            base_ref,
+            unsafe_mode: false,
+            unsafe_consent: None,
+            allow_experimental_adapters: body.allow_experimental_adapters,
+            run_id: Some(run_id),
+            deterministic: false,
+            lock: LockMode::Wait,
+            stop_on_first_success: body.stop_on_first_success,
+            skip_baseline: body.skip_baseline,
+            cancel: state.cancel.clone(),
+            approvals: state.approvals.clone(),
+            event_sinks: EventSinks::new().with(state.events.clone()),
+        };
+        self.lock().insert(run_id, state);
+
+        let orchestrator = Orchestrator::new(self.repo_root.clone(), config);
+        let server = Arc::clone(self);
+        tokio::spawn(async move {
+            // WebSocket clients follow the run's event bus instead.
+            let result = orchestrator
+                .race(options, Arc::new(|_: RaceEvent| {}))
+                .await;
+            if let Err(e) = &result {
+                tracing::warn!(%run_id, error = %e, "race started over the API failed");
+            }
+            if let Some(race) = server.lock().get_mut(&run_id) {
+                race.result = Some(result.map(|_| ()).map_err(|e| e.to_string()));
+            }
+        });
+
+        Ok(Response::json(
+            202,
+            &serde_json::json!({
+                "run_id": run_id,
+                "events": format!("/v1/runs/{run_id}/events"),
+            }),
+        ))
+    }
+
+    fn show_run(&self, run: &str) -> Result<Response, ApiError> {
+        let layout = self.layout(run)?;
+        match RunManifest::read_from(&layout.manifest_path()) {
+            Ok(manifest) => Ok(Response::json(200, &manifest)),
+            // Races started here have no manifest until their worktrees and
+            // baseline are ready, or at all when they fail before that.
+            Err(_) => match self.lock().get(&layout.run_id()).map(|r| r.result.clone()) {
+                Some(Some(Err(error))) => Ok(Response::json(
+                    200,
+                    &serde_json::json!({ "run_id": layout.run_id(), "status": "failed", "error": error }),
+                )),
+                Some(_) => Ok(Response::json(
+                    200,
+                    &serde_json::json!({ "run_id": layout.run_id(), "status": "starting" }),
+                )),
+                None => Err(ApiError::not_found(format!("run {run} has no manifest"))),
+            },
+        }
+    }
+
+    fn poll_events(&self, run: &str, request: &Request) -> Result<Response, ApiError> {
+        let layout = self.layout(run)?;
+        let since: usize = match request.query.get("since") {
+            Some(raw) => raw
+                .parse()
+                .map_err(|_| ApiError::bad_request(format!("invalid since '{raw}'")))?,
+            None => 0,
+        };
+        let path = match request.query.get("agent") {
+            Some(agent) => layout.agent_events(&agent_key(agent)?),
+            None => layout.events_path(),
+        };
+
+        let events = if path.exists() {
+            EventReader::read_all(&path).map_err(ApiError::internal)?
+        } else {
+            Vec::new()
+        };
+        let next = events.len();
+        let events: Vec<_> = events.into_iter().skip(since).collect();
+        Ok(Response::json(
+            200,
+            &serde_json::json!({ "events": events, "next": next }),
+        ))
+    }
+
+    fn cancel(&self, run: &str, request: &Request) -> Result<Response, ApiError> {
+        let layout = self.layout(run)?;
+        let body: CancelRequest = if request.body.is_empty() {
+            CancelRequest::default()
+        } else {
+            request.json()?
+        };
+        let agent = body.agent.as_deref().map(agent_key).transpose()?;
+        let marker = crate::cancel::request_cancel(&layout, agent.as_deref())
+            .map_err(|e| ApiError::new(409, format!("{e:#}")))?;
+        Ok(Response::json(
+            202,
+            &serde_json::json!({ "run_id": layout.run_id(), "agent": agent, "marker_path": marker }),
+        ))
+    }
+
+    fn resolve_approval(
+        &self,
+        run: &str,
+        approval: &str,
+        request: &Request,
+    ) -> Result<Response, ApiError> {
+        let layout = self.layout(run)?;
+        let approval_id = Uuid::parse_str(approval)
+            .map_err(|_| ApiError::bad_request(format!("invalid approval ID '{approval}'")))?;
+        let body: ApprovalRequest = request.json()?;
+
+        let Some(approvals) = self
+            .lock()
+            .get(&layout.run_id())
+            .map(|race| race.approvals.clone())
+        else {
+            return Err(ApiError::not_found(format!(
+                "run {run} was not started by this server"
+            )));
+        };
+        if !approvals.resolve(approval_id, body.decision) {
+            return Err(ApiError::not_found(format!(
+                "no pending approval {approval_id}"
+            )));
+        }
+        Ok(Response::json(
+            200,
+            &serde_json::json!({ "approval_id": approval_id, "decision": body.decision }),
+        ))
+    }
+
+    /// Merges go through `hydra merge` in a child process, so they get the
+    /// same safety checks and report as on the command line.
+    async fn merge(&self, run: &str, request: &Request) -> Result<Response, ApiError> {
+        let layout = self.layout(run)?;
+        let body: MergeRequest = request.json()?;
+
+        let mut args = vec![
+            "merge".to_string(),
+            "--run-id".to_string(),
+            layout.run_id().to_string(),
+            "--json".to_string(),
+        ];
+        if let Some(agent) = &body.agent {
+            args.extend(["--agent".to_string(), agent_key(agent)?]);
+        }
+        args.push(
+            if body.dry_run {
+                "--dry-run"
+            } else {
+                "--confirm"
+            }
+            .to_string(),
+        );
+        if body.force {
+            args.push("--force".to_string());
+        }
+        if !body.only.is_empty() {
+            args.extend(["--only".to_string(), body.only.join(",")]);
+        }
+        let config_path = self.config_path();
+        if config_path.is_file() {
+            args.extend(["--config".to_string(), config_path.display().to_string()]);
+        }
+
+        let exe = std::env::current_exe().map_err(ApiError::internal)?;
+        let repo_root = self.repo_root.clone();
+        let output = tokio::task::spawn_blocking(move || {
+            std::process::Command::new(exe)
+                .args(&args)
+                .current_dir(repo_root)
+                .output()
+        })
+        .await
+        .map_err(ApiError::internal)?
+        .map_err(ApiError::internal)?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.trim().trim_start_matches("Error: ");
+            return Err(ApiError::new(409, message));
+        }
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| ApiError::internal(format!("unreadable merge report: {e}")))?;
+        Ok(Response::json(200, &report))
+    }
+
+    /// Layout for a run that exists on disk or was started by this server.
+    pub(crate) fn layout(&self, run: &str) -> Result<RunLayout, ApiError> {
+        let run_id = Uuid::parse_str(run)
+            .map_err(|_| ApiError::bad_request(format!("invalid run ID '{run}'")))?;
+        let layout = RunLayout::new(&self.hydra_root, run_id);
+        if !layout.base_dir().exists() && !self.lock().contains_key(&run_id) {
+            return Err(ApiError::not_found(format!("run {run_id} not found")));
+        }
+        Ok(layout)
+    }
+
+    fn race_ended(&self, run_id: Uuid) -> bool {
+        self.lock()
+            .get(&run_id)
+            .is_some_and(|race| race.result.is_some())
+    }
+
+    /// Push the run's events until it finishes or the client goes away.
+    /// Races started by this server are read from their event bus as
+    /// written; others from their run and agent logs, in timestamp order.
+    async fn stream_events(
+        &self,
+        mut stream: TcpStream,
+        layout: &RunLayout,
+        client_key: &str,
+    ) -> std::io::Result<()> {
+        let handshake = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            websocket::accept_key(client_key)
+        );
+        stream.write_all(handshake.as_bytes()).await?;
+
+        let (mut reader, mut writer) = stream.into_split();
+        let (frames_tx, mut frames) = mpsc::channel(8);
+        let read_task = tokio::spawn(async move {
+            loop {
+                let frame = websocket::read_frame(&mut reader)
+                    .await
+                    .unwrap_or(ClientFrame::Close);
+                let closed = frame == ClientFrame::Close;
+                if frames_tx.send(frame).await.is_err() || closed {
+                    break;
+                }
+            }
+        });
+
+        let live = self
+            .lock()
+            .get(&layout.run_id())
+            .map(|race| race.events.clone());
+        let mut source = match live {
+            Some(buffer) => EventSource::Live { buffer, next: 0 },
+            None => EventSource::Logs(RunEventSources::open(layout)),
+        };
+        let mut ticker = tokio::time::interval(STREAM_POLL);
+        let result = loop {
+            tokio::select! {
+                frame = frames.recv() => match frame {
+                    Some(ClientFrame::Ping(payload)) => websocket::write_pong(&mut writer, &payload).await?,
+                    Some(ClientFrame::Other) => {}
+                    Some(ClientFrame::Close) | None => break Ok(()),
+                },
+                _ = ticker.tick() => {
+                    let events = source.poll().map_err(std::io::Error::other)?;
+                    let finished = events
+                        .iter()
+                        .any(|e| matches!(e.kind, EventKind::RunCompleted | EventKind::RunFailed));
+                    for event in &events {
+                        let line = serde_json::to_string(event).map_err(std::io::Error::other)?;
+                        websocket::write_text(&mut writer, &line).await?;
+                    }
+                    // A run killed before its terminal event still settles its
+                    // manifest; one that failed during setup may have neither.
+                    let ended = manifest_is_settled(layout) || self.race_ended(layout.run_id());
+                    if finished || (events.is_empty() && ended) {
+                        break Ok(());
+                    }
+                }
+            }
+        };
+        let _ = websocket::write_close(&mut writer).await;
+        read_task.abort();
+        result
+    }
+
+    fn stream_target(&self, request: &Request) -> Result<(RunLayout, String), ApiError> {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        let ["v1", "runs", run, "events"] = segments.as_slice() else {
+            return Err(ApiError::not_found(format!(
+                "no event stream at {}",
+                request.path
+            )));
+        };
+        let layout = self.layout(run)?;
+        let key = request
+            .header("sec-websocket-key")
+            .ok_or_else(|| ApiError::bad_request("missing Sec-WebSocket-Key"))?;
+        Ok((layout, key.to_string()))
+    }
+}
+
+/// Where a WebSocket client's events come from.
+enum EventSource {
+    /// A race started by this server.
+    Live {
+        buffer: EventBuffer,
+        next: usize,
+    },
+    Logs(RunEventSources),
+}
+
+impl EventSource {
+    fn poll(&mut self) -> Result<Vec<RunEvent>> {
+        match self {
+            EventSource::Live { buffer, next } => {
+                let (events, end) = buffer.since(*next);
+                *next = end;
+                Ok(events)
+            }
+            EventSource::Logs(sources) => sources.poll(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RaceRequest {
+    prompt: String,
+    #[serde(default)]
+    agents: Vec<String>,
+    #[serde(default = "default_base_ref")]
+    base_ref: String,
+    #[serde(default)]
+    allow_experimental_adapters: bool,
+    #[serde(default)]
+    stop_on_first_success: bool,
+    #[serde(default)]
+    skip_baseline: bool,
+    #[serde(default)]
+    prompt_source: Option<PromptSource>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    task: Option<TaskSpec>,
+    #[serde(default)]
+    variants: Vec<PromptVariant>,
+}
+
+fn default_base_ref() -> String {
+    "HEAD".to_string()
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CancelRequest {
+    agent: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApprovalRequest {
+    decision: ApprovalDecision,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MergeRequest {
+    agent: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    force: bool,
+    #[serde(default)]
+    only: Vec<String>,
+}
+
+fn agent_key(raw: &str) -> Result<String, ApiError> {
+    crate::parse_agent_key(raw).map_err(ApiError::bad_request)
+}
+
+fn read_artifact(path: &std::path::Path, what: &str) -> Result<String, ApiError> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(ApiError::not_found(format!("no {what} recorded")))
+        }
+        Err(e) => Err(ApiError::internal(e)),
+    }
+}
+
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: HashMap<String, String>,
+    /// Lower-cased names.
+    headers: HashMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    /// A request that did not come over HTTP, such as one translated from
+    /// `hydra rpc`.
+    pub(crate) fn new(
+        method: &str,
+        path: String,
+        query: HashMap<String, String>,
+        body: Vec<u8>,
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            path,
+            query,
+            headers: HashMap::new(),
+            body,
+        }
+    }
+
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| ApiError::bad_request(format!("invalid request body: {e}")))
+    }
+}
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Read one request. `max_body` sees the request line and headers and
+/// returns the largest body to accept. Each connection carries a single
+/// request; responses close it.
+pub(crate) async fn read_request(
+    stream: &mut TcpStream,
+    max_body: impl Fn(&Request) -> usize,
+) -> std::io::Result<Request> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if data.len() > MAX_REQUEST_HEAD {
+            return Err(invalid("request head too large"));
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let mut request = parse_head(&data[..head_end]).ok_or_else(|| invalid("malformed request"))?;
+    let length: usize = match request.header("content-length") {
+        Some(raw) => raw.parse().map_err(|_| invalid("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > max_body(&request) {
+        return Err(invalid("request body too large"));
+    }
+    let mut body = data.split_off(head_end);
+    while body.len() < length {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    body.truncate(length);
+    request.body = body;
+    Ok(request)
+}
+
+fn parse_head(head: &[u8]) -> Option<Request> {
+    let head = std::str::from_utf8(head).ok()?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    if !request_line.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+        body: Vec::new(),
+    })
+}
+
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) content_type: &'static str,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Response {
+    pub(crate) fn json<T: Serialize + ?Sized>(status: u16, value: &T) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec_pretty(value).unwrap_or_default(),
+        }
+    }
+
+    pub(crate) fn text(status: u16, content_type: &'static str, body: String) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into_bytes(),
+        }
+    }
+
+    pub(crate) async fn write_to(self, stream: &mut TcpStream) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            202 => "Accepted",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            409 => "Conflict",
+            _ => "Internal Server Error",
+        };
+        let auth = if self.status == 401 {
+            "WWW-Authenticate: Bearer\r\n"
+        } else {
+            ""
+        };
+        let head = format!(
+            "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{auth}Connection: close\r\n\r\n",
+            self.status,
+            self.content_type,
+            self.body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&self.body).await?;
+        stream.shutdown().await
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    pub(crate) status: u16,
+    pub(crate) message: String,
+}
+
+impl ApiError {
+    pub(crate) fn new(status: u16, message: impl std::fmt::Display) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn bad_request(message: impl std::fmt::Display) -> Self {
+        Self::new(400, message)
+    }
+
+    pub(crate) fn not_found(message: impl std::fmt::Display) -> Self {
+        Self::new(404, message)
+    }
+
+    pub(crate) fn internal(message: impl std::fmt::Display) -> Self {
+        Self::new(500, message)
+    }
+
+    pub(crate) fn into_response(self) -> Response {
+        Response::json(self.status, &serde_json::json!({ "error": self.message }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::{EventSink, EventWriter, RunStatus};
+    use hydra_core::test_support::new_run;
+    use tempfile::TempDir;
+
+    const TOKEN: &str = "secret-token";
+
+    async fn start(repo_root: PathBuf) -> std::net::SocketAddr {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Arc::new(Server::new(repo_root, TOKEN.to_string()));
+        tokio::spawn(server.serve(listener));
+        addr
+    }
+
+    async fn send(addr: std::net::SocketAddr, raw: String) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    fn get(path: &str, token: Option<&str>) -> String {
+        let auth = token
+            .map(|t| format!("Authorization: Bearer {t}\r\n"))
+            .unwrap_or_default();
+        format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n{auth}\r\n")
+    }
+
+    fn completed_run(repo_root: &std::path::Path) -> Uuid {
+        let (layout, mut manifest) = new_run(&repo_root.join(".hydra"), &["claude"]);
+        let run_id = layout.run_id();
+        manifest.repo_root = repo_root.display().to_string();
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let mut events = EventWriter::create(&layout.events_path()).unwrap();
+        for kind in [EventKind::RunStarted, EventKind::RunCompleted] {
+            events
+                .write_event(&RunEvent::new(kind, None, serde_json::json!({})))
+                .unwrap();
+        }
+        std::fs::write(layout.agent_diff("claude"), "diff --git a/x b/x\n").unwrap();
+        run_id
+    }
+
+    #[test]
+    fn parse_head_splits_path_query_and_headers() {
+        let request = parse_head(
+            b"GET /v1/runs/abc/events?since=3&token=t HTTP/1.1\r\nAuthorization: Bearer x\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/v1/runs/abc/events");
+        assert_eq!(request.query.get("since").map(String::as_str), Some("3"));
+        assert_eq!(request.header("authorization"), Some("Bearer x"));
+        assert!(parse_head(b"GET /\r\n\r\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn requests_need_the_token_except_health() {
+        let tmp = TempDir::new().unwrap();
+        let addr = start(tmp.path().to_path_buf()).await;
+
+        assert_eq!(send(addr, get("/v1/health", None)).await.0, 200);
+        assert_eq!(send(addr, get("/v1/runs", None)).await.0, 401);
+        assert_eq!(send(addr, get("/v1/runs", Some("wrong"))).await.0, 401);
+        // Query tokens are only for the WebSocket handshake.
+        assert_eq!(
+            send(addr, get("/v1/runs?token=secret-token", None)).await.0,
+            401
+        );
+
+        let (status, body) = send(addr, get("/v1/runs", Some(TOKEN))).await;
+        assert_eq!(status, 200);
+        assert_eq!(body.trim(), "{\n  \"runs\": []\n}");
+    }
+
+    #[tokio::test]
+    async fn serves_run_artifacts_and_rejects_bad_input() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = completed_run(tmp.path());
+        let addr = start(tmp.path().to_path_buf()).await;
+
+        let (status, body) = send(addr, get(&format!("/v1/runs/{run_id}"), Some(TOKEN))).await;
+        assert_eq!(status, 200);
+        let manifest: RunManifest = serde_json::from_str(&body).unwrap();
+        assert_eq!(manifest.status, RunStatus::Completed);
+
+        let path = format!("/v1/runs/{run_id}/events?since=1");
+        let (status, body) = send(addr, get(&path, Some(TOKEN))).await;
+        assert_eq!(status, 200);
+        let events: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(events["next"], 2);
+        assert_eq!(events["events"][0]["kind"], "run_completed");
+
+        let path = format!("/v1/runs/{run_id}/agents/claude/diff");
+        let (status, body) = send(addr, get(&path, Some(TOKEN))).await;
+        assert_eq!((status, body.as_str()), (200, "diff --git a/x b/x\n"));
+
+        let path = format!("/v1/runs/{run_id}/agents/claude/score");
+        assert_eq!(send(addr, get(&path, Some(TOKEN))).await.0, 404);
+        let path = format!("/v1/runs/{run_id}/agents/claude/edited-files");
+        let (status, body) = send(addr, get(&path, Some(TOKEN))).await;
+        assert_eq!(status, 200);
+        let edited: EditedFiles = serde_json::from_str(&body).unwrap();
+        assert!(edited.files.is_empty());
+        let path = format!("/v1/runs/{run_id}/agents/..%2F..%2Fetc/diff");
+        assert_eq!(send(addr, get(&path, Some(TOKEN))).await.0, 400);
+        assert_eq!(
+            send(addr, get("/v1/runs/not-a-uuid", Some(TOKEN))).await.0,
+            400
+        );
+
+        let body = r#"{"prompt": ""}"#;
+        let post = format!(
+            "POST /v1/races HTTP/1.1\r\nAuthorization: Bearer {TOKEN}\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        let (status, body) = send(addr, post).await;
+        assert_eq!(status, 400, "{body}");
+    }
+
+    #[tokio::test]
+    async fn websocket_streams_events_until_the_run_ends() {
+        let tmp = TempDir::new().unwrap();
+        let run_id = completed_run(tmp.path());
+        let addr = start(tmp.path().to_path_buf()).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let handshake = format!(
+            "GET /v1/runs/{run_id}/events?token={TOKEN} HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        stream.write_all(handshake.as_bytes()).await.unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+
+        let text = String::from_utf8_lossy(&received);
+        assert!(text.starts_with("HTTP/1.1 101"), "{text}");
+        assert!(text.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+        assert!(text.contains("\"run_started\""));
+        assert!(text.contains("\"run_completed\""));
+        // Ends with a close frame carrying status 1000.
+        assert_eq!(&received[received.len() - 4..], &[0x88, 2, 0x03, 0xE8]);
+    }
+
+    #[tokio::test]
+    async fn websocket_follows_the_event_bus_of_races_started_here() {
+        let tmp = TempDir::new().unwrap();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Arc::new(Server::new(tmp.path().to_path_buf(), TOKEN.to_string()));
+        tokio::spawn(Arc::clone(&server).serve(listener));
+
+        // Nothing on disk: the events only exist on the bus.
+        let run_id = Uuid::new_v4();
+        let events = EventBuffer::new();
+        server.lock().insert(
+            run_id,
+            RaceState {
+                approvals: ApprovalGate::new(),
+                cancel: CancelToken::new(),
+                events: events.clone(),
+                result: None,
+            },
+        );
+        let publish = move |kind, agent: Option<&str>| {
+            events.publish(&RunEvent::new(
+                kind,
+                agent.map(str::to_string),
+                serde_json::json!({}),
+            ))
+        };
+        publish(EventKind::RunStarted, None);
+        publish(EventKind::AgentStarted, Some("claude"));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let handshake = format!(
+            "GET /v1/runs/{run_id}/events?token={TOKEN} HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        stream.write_all(handshake.as_bytes()).await.unwrap();
+        tokio::time::sleep(STREAM_POLL * 2).await;
+        publish(EventKind::RunCompleted, None);
+
+        let mut received = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut received))
+            .await
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8_lossy(&received);
+        let started = text.find("\"run_started\"").unwrap();
+        let agent = text.find("\"agent_started\"").unwrap();
+        let completed = text.find("\"run_completed\"").unwrap();
+        assert!(started < agent && agent < completed, "{text}");
+    }
+}
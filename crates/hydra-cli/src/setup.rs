@@ -0,0 +1,421 @@
+//! `hydra setup`: a guided first run that probes adapters, asks for the
+//! handful of settings most repos change, writes `hydra.toml`, and verifies
+//! the result with the doctor checks.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use hydra_core::adapter::{AdapterTier, ProbeReport};
+use hydra_core::config::{HydraConfig, RetentionPolicy, ScoringProfile};
+
+use crate::run::discover_repo_root;
+use crate::{config, doctor, output};
+
+pub struct SetupOpts {
+    /// Accept every default without prompting.
+    pub yes: bool,
+    /// Overwrite an existing config without asking.
+    pub force: bool,
+}
+
+/// Returns whether the doctor checks passed with the written config.
+pub fn run_setup(opts: SetupOpts) -> Result<bool> {
+    if !opts.yes && !std::io::stdin().is_terminal() {
+        bail!("hydra setup is interactive; pass --yes to accept the defaults");
+    }
+
+    let config_path = config::path();
+    let repo_root = discover_repo_root().ok();
+    let stdin = std::io::stdin();
+    let mut prompter = Prompter::new(stdin.lock(), std::io::stdout(), opts.yes);
+
+    if config_path.exists() && !opts.force {
+        if opts.yes {
+            bail!(
+                "{} already exists; pass --force to overwrite it",
+                config_path.display()
+            );
+        }
+        let question = format!("{} already exists. Overwrite it?", config_path.display());
+        if !prompter.confirm(&question, false)? {
+            println!("Setup cancelled; nothing was written.");
+            return Ok(true);
+        }
+    }
+
+    // Start from the existing config so re-running setup only changes what
+    // the wizard asks about.
+    let base = hydra_core::config::load_config(&config_path).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "existing config is invalid, starting from defaults");
+        HydraConfig::default()
+    });
+
+    println!("Probing agent CLIs...");
+    let probe = doctor::probe_adapters(base.adapters.clone());
+    for result in &probe.results {
+        println!(
+            "  {:<8} {}",
+            result.adapter_key,
+            result.detect.status_label()
+        );
+    }
+    println!();
+
+    let detected_profile = repo_root.as_deref().and_then(detect_profile);
+    let config = build_config(&mut prompter, base, &probe, detected_profile)?;
+    let rendered = hydra_core::config::render_config(&config)?;
+
+    println!();
+    println!("{rendered}");
+    if !prompter.confirm(&format!("Write {}?", config_path.display()), true)? {
+        println!("Setup cancelled; nothing was written.");
+        return Ok(true);
+    }
+    output::write_atomic(&config_path, rendered.as_bytes())?;
+    println!("Wrote {}", config_path.display());
+
+    // Verify by loading the file back and running the doctor checks on it.
+    let written = hydra_core::config::load_config(&config_path)
+        .with_context(|| format!("written config {} failed to load", config_path.display()))?;
+    let report = doctor::report_for(repo_root.as_deref(), &config_path, &written, false);
+
+    output::emit(
+        &serde_json::json!({
+            "config_path": config_path,
+            "config": config,
+            "doctor": report,
+        }),
+        false,
+    )?;
+    println!();
+    doctor::print_human_report(&report);
+    Ok(report.healthy())
+}
+
+/// Ask for each setting, defaulting to the current value or a detected one.
+fn build_config<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    mut config: HydraConfig,
+    probe: &ProbeReport,
+    detected_profile: Option<ScoringProfile>,
+) -> Result<HydraConfig> {
+    let known: Vec<&str> = probe
+        .results
+        .iter()
+        .map(|r| r.adapter_key.as_str())
+        .collect();
+    let tier1: Vec<&str> = probe
+        .results
+        .iter()
+        .filter(|r| r.tier == AdapterTier::Tier1)
+        .map(|r| r.adapter_key.as_str())
+        .collect();
+    let ready: Vec<&str> = probe
+        .results
+        .iter()
+        .filter(|r| r.tier == AdapterTier::Tier1 && r.detect.status.is_available())
+        .map(|r| r.adapter_key.as_str())
+        .collect();
+    let default_agents = if !config.race.default_agents.is_empty() {
+        config.race.default_agents.join(",")
+    } else if !ready.is_empty() {
+        ready.join(",")
+    } else {
+        tier1.join(",")
+    };
+    config.race.default_agents = prompter.ask(
+        &format!("Default agents to race ({})", known.join(", ")),
+        &default_agents,
+        |raw| parse_agents(raw, &known),
+    )?;
+    let experimental: Vec<&str> = probe
+        .results
+        .iter()
+        .filter(|r| r.tier == AdapterTier::Experimental)
+        .map(|r| r.adapter_key.as_str())
+        .filter(|key| config.race.default_agents.iter().any(|a| a == key))
+        .collect();
+    if !experimental.is_empty() {
+        prompter.note(&format!(
+            "{} experimental; races will need --allow-experimental-adapters",
+            experimental.join(", ")
+        ))?;
+    }
+
+    let profile = config.scoring.profile.or(detected_profile);
+    config.scoring.profile = prompter.ask(
+        "Scoring profile (rust, js-node, python, none)",
+        profile.map_or("none", profile_name),
+        parse_profile,
+    )?;
+
+    config.scoring.budget.max_tokens_total = prompter.ask(
+        "Token budget per race (blank for none)",
+        &config
+            .scoring
+            .budget
+            .max_tokens_total
+            .map(|v| v.to_string())
+            .unwrap_or_default(),
+        |raw| parse_optional(raw, |v: &u64| *v > 0),
+    )?;
+    config.scoring.budget.max_cost_usd = prompter.ask(
+        "Cost budget per race in USD (blank for none)",
+        &config
+            .scoring
+            .budget
+            .max_cost_usd
+            .map(|v| v.to_string())
+            .unwrap_or_default(),
+        |raw| parse_optional(raw, |v: &f64| v.is_finite() && *v >= 0.0),
+    )?;
+
+    config.worktree.retain = prompter.ask(
+        "Keep agent worktrees after a run (none, failed, all)",
+        retention_name(config.worktree.retain),
+        parse_retention,
+    )?;
+
+    Ok(config)
+}
+
+/// Line-oriented prompts; with `assume_defaults` every question takes its default.
+struct Prompter<R, W> {
+    input: R,
+    out: W,
+    assume_defaults: bool,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    fn new(input: R, out: W, assume_defaults: bool) -> Self {
+        Self {
+            input,
+            out,
+            assume_defaults,
+        }
+    }
+
+    /// Ask until `parse` accepts the answer; a blank answer means `default`.
+    fn ask<T>(
+        &mut self,
+        question: &str,
+        default: &str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<T> {
+        loop {
+            write!(self.out, "{question} [{default}]: ")?;
+            let answer = if self.assume_defaults {
+                writeln!(self.out)?;
+                String::new()
+            } else {
+                self.out.flush()?;
+                let mut line = String::new();
+                if self.input.read_line(&mut line)? == 0 {
+                    bail!("input closed before setup finished");
+                }
+                line
+            };
+            let answer = answer.trim();
+            let answer = if answer.is_empty() { default } else { answer };
+            match parse(answer) {
+                Ok(value) => return Ok(value),
+                Err(e) if self.assume_defaults => bail!("invalid default for '{question}': {e}"),
+                Err(e) => writeln!(self.out, "  {e}")?,
+            }
+        }
+    }
+
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        self.ask(
+            question,
+            if default { "Y/n" } else { "y/N" },
+            |raw| match raw.to_ascii_lowercase().as_str() {
+                "y" | "yes" => Ok(true),
+                "n" | "no" => Ok(false),
+                "y/n" => Ok(default),
+                _ => Err("answer y or n".to_string()),
+            },
+        )
+    }
+
+    fn note(&mut self, message: &str) -> Result<()> {
+        writeln!(self.out, "  note: {message}")?;
+        Ok(())
+    }
+}
+
+fn parse_agents(raw: &str, known: &[&str]) -> Result<Vec<String>, String> {
+    let mut agents: Vec<String> = Vec::new();
+    for key in raw.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+        if !known.contains(&key) {
+            return Err(format!(
+                "unknown agent '{key}' (known: {})",
+                known.join(", ")
+            ));
+        }
+        if !agents.iter().any(|a| a == key) {
+            agents.push(key.to_string());
+        }
+    }
+    if agents.is_empty() {
+        return Err("choose at least one agent".to_string());
+    }
+    Ok(agents)
+}
+
+fn parse_profile(raw: &str) -> Result<Option<ScoringProfile>, String> {
+    match raw {
+        "none" => Ok(None),
+        "rust" => Ok(Some(ScoringProfile::Rust)),
+        "js-node" => Ok(Some(ScoringProfile::JsNode)),
+        "python" => Ok(Some(ScoringProfile::Python)),
+        _ => Err(format!("unknown profile '{raw}'")),
+    }
+}
+
+pub(crate) fn profile_name(profile: ScoringProfile) -> &'static str {
+    match profile {
+        ScoringProfile::Rust => "rust",
+        ScoringProfile::JsNode => "js-node",
+        ScoringProfile::Python => "python",
+    }
+}
+
+fn parse_retention(raw: &str) -> Result<RetentionPolicy, String> {
+    match raw {
+        "none" => Ok(RetentionPolicy::None),
+        "failed" => Ok(RetentionPolicy::Failed),
+        "all" => Ok(RetentionPolicy::All),
+        _ => Err(format!("unknown retention policy '{raw}'")),
+    }
+}
+
+fn retention_name(policy: RetentionPolicy) -> &'static str {
+    match policy {
+        RetentionPolicy::None => "none",
+        RetentionPolicy::Failed => "failed",
+        RetentionPolicy::All => "all",
+    }
+}
+
+/// Blank or `none` clears the value; anything else must parse and pass `valid`.
+fn parse_optional<T: std::str::FromStr>(
+    raw: &str,
+    valid: impl Fn(&T) -> bool,
+) -> Result<Option<T>, String> {
+    if raw.is_empty() || raw == "none" {
+        return Ok(None);
+    }
+    match raw.parse::<T>() {
+        Ok(value) if valid(&value) => Ok(Some(value)),
+        _ => Err(format!("'{raw}' is not a valid amount")),
+    }
+}
+
+/// Guess the scoring profile from well-known manifests at the repo root.
+pub(crate) fn detect_profile(repo_root: &Path) -> Option<ScoringProfile> {
+    if repo_root.join("Cargo.toml").exists() {
+        Some(ScoringProfile::Rust)
+    } else if repo_root.join("package.json").exists() {
+        Some(ScoringProfile::JsNode)
+    } else if ["pyproject.toml", "setup.py", "requirements.txt"]
+        .iter()
+        .any(|f| repo_root.join(f).exists())
+    {
+        Some(ScoringProfile::Python)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::adapter::claude::ClaudeAdapter;
+    use hydra_core::adapter::{
+        AgentAdapter, CapabilityConfidence, DetectResult, DetectStatus, ProbeResult,
+    };
+
+    fn probe(results: &[(&str, AdapterTier, DetectStatus)]) -> ProbeReport {
+        ProbeReport {
+            timestamp: chrono::Utc::now(),
+            results: results
+                .iter()
+                .map(|(key, tier, status)| ProbeResult {
+                    adapter_key: key.to_string(),
+                    tier: *tier,
+                    detect: DetectResult {
+                        status: status.clone(),
+                        binary_path: None,
+                        version: None,
+                        supported_flags: Vec::new(),
+                        confidence: CapabilityConfidence::Verified,
+                        error: None,
+                    },
+                    capabilities: ClaudeAdapter::new(None).capabilities(),
+                })
+                .collect(),
+            all_tier1_ready: false,
+        }
+    }
+
+    fn run_wizard(input: &str, base: HydraConfig) -> (Result<HydraConfig>, String) {
+        let report = probe(&[
+            ("claude", AdapterTier::Tier1, DetectStatus::Ready),
+            ("codex", AdapterTier::Tier1, DetectStatus::Missing),
+            (
+                "cursor",
+                AdapterTier::Experimental,
+                DetectStatus::ExperimentalReady,
+            ),
+        ]);
+        let mut out = Vec::new();
+        let mut prompter = Prompter::new(input.as_bytes(), &mut out, false);
+        let result = build_config(&mut prompter, base, &report, Some(ScoringProfile::Rust));
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn blank_answers_take_detected_defaults() {
+        let (config, out) = run_wizard("\n\n\n\n\n", HydraConfig::default());
+        let config = config.unwrap();
+        assert_eq!(config.race.default_agents, vec!["claude"]);
+        assert_eq!(config.scoring.profile, Some(ScoringProfile::Rust));
+        assert_eq!(config.scoring.budget.max_tokens_total, None);
+        assert_eq!(config.worktree.retain, RetentionPolicy::Failed);
+        assert!(out.contains("Default agents to race (claude, codex, cursor) [claude]"));
+    }
+
+    #[test]
+    fn invalid_answers_are_asked_again() {
+        let input = "gemini\nclaude, cursor\nruby\nnone\n-5\n50000\n1.5\nall\n";
+        let (config, out) = run_wizard(input, HydraConfig::default());
+        let config = config.unwrap();
+        assert_eq!(config.race.default_agents, vec!["claude", "cursor"]);
+        assert_eq!(config.scoring.profile, None);
+        assert_eq!(config.scoring.budget.max_tokens_total, Some(50_000));
+        assert_eq!(config.scoring.budget.max_cost_usd, Some(1.5));
+        assert_eq!(config.worktree.retain, RetentionPolicy::All);
+        assert!(out.contains("unknown agent 'gemini'"));
+        assert!(out.contains("unknown profile 'ruby'"));
+        assert!(out.contains("--allow-experimental-adapters"));
+    }
+
+    #[test]
+    fn closed_input_fails_instead_of_looping() {
+        let (config, _) = run_wizard("", HydraConfig::default());
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn detects_profile_from_repo_manifests() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert_eq!(detect_profile(tmp.path()), None);
+        std::fs::write(tmp.path().join("package.json"), "{}").unwrap();
+        assert_eq!(detect_profile(tmp.path()), Some(ScoringProfile::JsNode));
+        std::fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(detect_profile(tmp.path()), Some(ScoringProfile::Rust));
+    }
+}
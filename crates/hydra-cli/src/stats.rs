@@ -0,0 +1,548 @@
+//! Per-agent statistics aggregated across stored runs.
+//!
+//! Runs are read from `.hydra/runs`: the manifest gives membership and start
+//! time, score files give mergeability and dimension scores, and the terminal
+//! agent events give cost and duration. Spend totals come from the cost
+//! ledger instead, so they still count runs that have since been cleaned up.
+//! With `--tag`, only runs carrying every given tag count, and spend is
+//! limited to the ledger entries of those runs.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use hydra_core::artifact::{
+    has_tags, CostLedger, EventKind, EventReader, RunEvent, RunLayout, RunManifest, RunStatus,
+    SpendPeriod,
+};
+use hydra_core::config::BudgetConfig;
+use uuid::Uuid;
+
+use crate::output;
+use crate::run::{discover_repo_root, load_agent_score};
+
+pub struct StatsOpts {
+    pub since: Option<chrono::Duration>,
+    pub agent: Option<String>,
+    pub tags: Vec<String>,
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsReport {
+    since: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    runs: usize,
+    agents: Vec<AgentStats>,
+    spend: Spend,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Spend {
+    /// Ledger spend within the `--since` window (all time without one).
+    total_usd: f64,
+    today_usd: f64,
+    month_usd: f64,
+    daily_limit_usd: Option<f64>,
+    monthly_limit_usd: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct AgentStats {
+    agent_key: String,
+    runs: usize,
+    wins: usize,
+    mergeable: usize,
+    win_rate: f64,
+    mergeable_rate: f64,
+    mean_composite: Option<f64>,
+    mean_dimensions: BTreeMap<String, f64>,
+    avg_cost_usd: Option<f64>,
+    avg_duration_ms: Option<u64>,
+}
+
+#[derive(Default)]
+struct Totals {
+    runs: usize,
+    wins: usize,
+    mergeable: usize,
+    composite: Mean,
+    dimensions: BTreeMap<String, Mean>,
+    cost_usd: Mean,
+    duration_ms: Mean,
+}
+
+#[derive(Default)]
+struct Mean {
+    sum: f64,
+    count: usize,
+}
+
+impl Mean {
+    fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn value(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+pub fn run_stats(opts: StatsOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let hydra_root = repo_root.join(".hydra");
+    let config = crate::config::load()?;
+    let cutoff = opts.since.map(|since| Utc::now() - since);
+    let (mut report, run_ids) = collect(&hydra_root, cutoff, opts.agent.as_deref(), &opts.tags)?;
+    report.spend = collect_spend(
+        &hydra_root,
+        cutoff,
+        opts.agent.as_deref(),
+        (!opts.tags.is_empty()).then_some(&run_ids),
+        &config.scoring.budget,
+        Utc::now(),
+    )?;
+
+    output::emit(&report, opts.json)?;
+    if opts.json {
+        return Ok(());
+    }
+
+    if report.agents.is_empty() {
+        println!("No finished runs found.");
+        return Ok(());
+    }
+
+    let tagged = if report.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" tagged {}", report.tags.join(", "))
+    };
+    match report.since {
+        Some(since) => println!(
+            "{} run(s){tagged} since {}",
+            report.runs,
+            since.to_rfc3339()
+        ),
+        None => println!("{} run(s){tagged}", report.runs),
+    }
+    println!();
+    println!(
+        "  {:<12} {:>5} {:>7} {:>10} {:>7} {:>10} {:>9}",
+        "Agent", "Runs", "Win %", "Mergeable", "Score", "Avg cost", "Avg time"
+    );
+    for agent in &report.agents {
+        println!(
+            "  {:<12} {:>5} {:>6.0}% {:>9.0}% {:>7} {:>10} {:>9}",
+            agent.agent_key,
+            agent.runs,
+            agent.win_rate * 100.0,
+            agent.mergeable_rate * 100.0,
+            agent
+                .mean_composite
+                .map(|v| format!("{v:.1}"))
+                .unwrap_or_else(|| "-".to_string()),
+            agent
+                .avg_cost_usd
+                .map(|v| format!("${v:.4}"))
+                .unwrap_or_else(|| "-".to_string()),
+            agent
+                .avg_duration_ms
+                .map(|v| format!("{:.1}s", v as f64 / 1000.0))
+                .unwrap_or_else(|| "-".to_string()),
+        );
+    }
+
+    let dimension_names: Vec<&String> = report
+        .agents
+        .iter()
+        .flat_map(|a| a.mean_dimensions.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if !dimension_names.is_empty() {
+        println!();
+        println!("  Mean dimension scores:");
+        for agent in &report.agents {
+            let dims = dimension_names
+                .iter()
+                .map(|name| match agent.mean_dimensions.get(*name) {
+                    Some(score) => format!("{name}={score:.1}"),
+                    None => format!("{name}=-"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            println!("    {:<12} {dims}", agent.agent_key);
+        }
+    }
+
+    let spend = &report.spend;
+    let with_limit = |spent: f64, limit: Option<f64>| match limit {
+        Some(limit) => format!("${spent:.2} of ${limit:.2}"),
+        None => format!("${spent:.2}"),
+    };
+    println!();
+    println!("  Spend: ${:.2}", spend.total_usd);
+    println!(
+        "    today       {}",
+        with_limit(spend.today_usd, spend.daily_limit_usd)
+    );
+    println!(
+        "    this month  {}",
+        with_limit(spend.month_usd, spend.monthly_limit_usd)
+    );
+
+    Ok(())
+}
+
+/// Ledger spend since `cutoff`, today, and this month, optionally limited to
+/// one agent and to the runs in `run_filter`. Budget limits are global, so
+/// today and this month are not narrowed by `run_filter`.
+fn collect_spend(
+    hydra_root: &Path,
+    cutoff: Option<DateTime<Utc>>,
+    agent_filter: Option<&str>,
+    run_filter: Option<&HashSet<Uuid>>,
+    budget: &BudgetConfig,
+    now: DateTime<Utc>,
+) -> Result<Spend> {
+    let entries = CostLedger::new(hydra_root)
+        .read_all()
+        .context("failed to read cost ledger")?;
+    let spent_since = |since: Option<DateTime<Utc>>, runs: Option<&HashSet<Uuid>>| -> f64 {
+        entries
+            .iter()
+            .filter(|e| agent_filter.is_none_or(|agent| agent == e.agent_key))
+            .filter(|e| runs.is_none_or(|runs| runs.contains(&e.run_id)))
+            .filter(|e| since.is_none_or(|since| e.timestamp >= since))
+            .filter_map(|e| e.cost_usd)
+            .sum()
+    };
+    Ok(Spend {
+        total_usd: spent_since(cutoff, run_filter),
+        today_usd: spent_since(Some(SpendPeriod::Day.start(now)), None),
+        month_usd: spent_since(Some(SpendPeriod::Month.start(now)), None),
+        daily_limit_usd: SpendPeriod::Day.limit(budget),
+        monthly_limit_usd: SpendPeriod::Month.limit(budget),
+    })
+}
+
+/// Aggregate every finished run started at or after `cutoff` that carries
+/// all of `tags`, optionally limited to one agent. Runs still in progress
+/// are skipped. Also returns the IDs of the runs counted.
+fn collect(
+    hydra_root: &Path,
+    cutoff: Option<DateTime<Utc>>,
+    agent_filter: Option<&str>,
+    tags: &[String],
+) -> Result<(StatsReport, HashSet<Uuid>)> {
+    let run_ids = RunLayout::list_runs(hydra_root).context("failed to list runs")?;
+    let mut totals: BTreeMap<String, Totals> = BTreeMap::new();
+    let mut counted = HashSet::new();
+
+    for run_id in run_ids {
+        let layout = RunLayout::new(hydra_root, run_id);
+        let Ok(manifest) = RunManifest::read_from(&layout.manifest_path()) else {
+            tracing::debug!(run_id = %run_id, "skipping run without a readable manifest");
+            continue;
+        };
+        if manifest.status == RunStatus::Running || manifest.replay_of.is_some() {
+            continue;
+        }
+        if cutoff.is_some_and(|cutoff| manifest.started_at < cutoff) {
+            continue;
+        }
+        if !has_tags(&manifest.tags, tags) {
+            continue;
+        }
+        if let Some(agent) = agent_filter {
+            if !manifest.agents.iter().any(|a| a.agent_key == agent) {
+                continue;
+            }
+        }
+        counted.insert(run_id);
+
+        let run_events = EventReader::read_all(&layout.events_path()).unwrap_or_default();
+        let mut scores = HashMap::new();
+        for agent in &manifest.agents {
+            if let Some(score) = load_agent_score(&layout, &agent.agent_key)? {
+                scores.insert(agent.agent_key.clone(), score);
+            }
+        }
+        let winner = scores
+            .values()
+            .filter(|s| s.mergeable)
+            .max_by(|a, b| a.composite.total_cmp(&b.composite))
+            .map(|s| s.agent_key.clone());
+
+        for agent in &manifest.agents {
+            let key = &agent.agent_key;
+            if agent_filter.is_some_and(|filter| filter != key) {
+                continue;
+            }
+            let entry = totals.entry(key.clone()).or_default();
+            entry.runs += 1;
+            if winner.as_deref() == Some(key.as_str()) {
+                entry.wins += 1;
+            }
+            if let Some(score) = scores.get(key) {
+                if score.mergeable {
+                    entry.mergeable += 1;
+                }
+                entry.composite.add(score.composite);
+                for dim in &score.dimensions {
+                    entry
+                        .dimensions
+                        .entry(dim.name.clone())
+                        .or_default()
+                        .add(dim.score);
+                }
+            }
+
+            let agent_events = EventReader::read_all(&layout.agent_events(key)).unwrap_or_default();
+            if let Some(cost) = terminal_value(&run_events, key, "estimated_cost_usd") {
+                entry.cost_usd.add(cost);
+            }
+            if let Some(duration) = terminal_value(&agent_events, key, "duration_ms") {
+                entry.duration_ms.add(duration);
+            }
+        }
+    }
+
+    let agents = totals
+        .into_iter()
+        .map(|(agent_key, t)| AgentStats {
+            agent_key,
+            runs: t.runs,
+            wins: t.wins,
+            mergeable: t.mergeable,
+            win_rate: t.wins as f64 / t.runs as f64,
+            mergeable_rate: t.mergeable as f64 / t.runs as f64,
+            mean_composite: t.composite.value(),
+            mean_dimensions: t
+                .dimensions
+                .into_iter()
+                .filter_map(|(name, mean)| mean.value().map(|v| (name, v)))
+                .collect(),
+            avg_cost_usd: t.cost_usd.value(),
+            avg_duration_ms: t.duration_ms.value().map(|v| v.round() as u64),
+        })
+        .collect();
+
+    let report = StatsReport {
+        since: cutoff,
+        tags: tags.to_vec(),
+        runs: counted.len(),
+        agents,
+        spend: Spend::default(),
+    };
+    Ok((report, counted))
+}
+
+/// Numeric field from the agent's last completed/failed event.
+fn terminal_value(events: &[RunEvent], agent_key: &str, field: &str) -> Option<f64> {
+    events
+        .iter()
+        .rev()
+        .filter(|e| matches!(e.kind, EventKind::AgentCompleted | EventKind::AgentFailed))
+        .filter(|e| e.agent_key.as_deref() == Some(agent_key))
+        .find_map(|e| e.data.get(field).and_then(serde_json::Value::as_f64))
+}
+
+/// Parse a lookback window such as `30d`, `12h`, `2w`, or `45m`.
+pub fn parse_since(raw: &str) -> Result<chrono::Duration, String> {
+    let raw = raw.trim();
+    let split = raw
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("'{raw}' needs a unit (m, h, d, or w)"))?;
+    let (count, unit) = raw.split_at(split);
+    let count: i64 = count
+        .parse()
+        .map_err(|_| format!("'{raw}' must start with a number"))?;
+    match unit {
+        "m" => Ok(chrono::Duration::minutes(count)),
+        "h" => Ok(chrono::Duration::hours(count)),
+        "d" => Ok(chrono::Duration::days(count)),
+        "w" => Ok(chrono::Duration::weeks(count)),
+        _ => Err(format!(
+            "unknown unit '{unit}' in '{raw}' (use m, h, d, or w)"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::{EventWriter, LedgerEntry};
+    use hydra_core::scoring::ranking::AgentScore;
+    use hydra_core::scoring::DimensionScore;
+    use hydra_core::test_support::new_run;
+
+    fn write_run(
+        hydra_root: &Path,
+        scores: &[(&str, f64, bool)],
+        age: chrono::Duration,
+        tags: &[&str],
+    ) -> Uuid {
+        let keys: Vec<&str> = scores.iter().map(|(k, _, _)| *k).collect();
+        let (layout, mut manifest) = new_run(hydra_root, &keys);
+        let run_id = layout.run_id();
+        manifest.started_at = Utc::now() - age;
+        manifest.tags = tags.iter().map(|t| t.to_string()).collect();
+        manifest.mark_completed(RunStatus::Completed);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+
+        let mut run_events = EventWriter::create(&layout.events_path()).unwrap();
+        for (key, composite, mergeable) in scores {
+            let score = AgentScore {
+                agent_key: key.to_string(),
+                dimensions: vec![DimensionScore {
+                    name: "tests".to_string(),
+                    score: *composite,
+                    evidence: serde_json::Value::Null,
+                }],
+                composite: *composite,
+                mergeable: *mergeable,
+                gate_failures: Vec::new(),
+                partial: false,
+            };
+            std::fs::write(
+                layout.agent_score(key),
+                serde_json::to_string(&score).unwrap(),
+            )
+            .unwrap();
+            run_events
+                .write_event(&RunEvent::new(
+                    EventKind::AgentCompleted,
+                    Some(key.to_string()),
+                    serde_json::json!({ "estimated_cost_usd": 0.5 }),
+                ))
+                .unwrap();
+            EventWriter::create(&layout.agent_events(key))
+                .unwrap()
+                .write_event(&RunEvent::new(
+                    EventKind::AgentCompleted,
+                    Some(key.to_string()),
+                    serde_json::json!({ "duration_ms": 2000 }),
+                ))
+                .unwrap();
+        }
+        run_id
+    }
+
+    #[test]
+    fn aggregates_wins_mergeability_and_means() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path();
+        let recent = chrono::Duration::hours(1);
+        write_run(
+            root,
+            &[("claude", 90.0, true), ("codex", 95.0, false)],
+            recent,
+            &["backend"],
+        );
+        let tagged = write_run(
+            root,
+            &[("claude", 70.0, true), ("codex", 80.0, true)],
+            recent,
+            &["backend", "sprint-42"],
+        );
+        write_run(
+            root,
+            &[("claude", 10.0, false)],
+            chrono::Duration::days(60),
+            &[],
+        );
+
+        let (report, _) = collect(root, None, None, &[]).unwrap();
+        assert_eq!(report.runs, 3);
+        let claude = &report.agents[0];
+        assert_eq!(claude.agent_key, "claude");
+        assert_eq!((claude.runs, claude.wins, claude.mergeable), (3, 1, 2));
+        assert_eq!(claude.mean_composite, Some(170.0 / 3.0));
+        assert_eq!(claude.mean_dimensions["tests"], 170.0 / 3.0);
+        assert_eq!(claude.avg_cost_usd, Some(0.5));
+        assert_eq!(claude.avg_duration_ms, Some(2000));
+
+        let (recent_only, _) = collect(
+            root,
+            Some(Utc::now() - chrono::Duration::days(30)),
+            None,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(recent_only.runs, 2);
+        assert_eq!(recent_only.agents[0].win_rate, 0.5);
+        assert_eq!(recent_only.agents[1].mergeable_rate, 0.5);
+
+        let (codex_only, _) = collect(root, None, Some("codex"), &[]).unwrap();
+        assert_eq!(codex_only.runs, 2);
+        assert_eq!(codex_only.agents.len(), 1);
+        assert_eq!(codex_only.agents[0].wins, 1);
+
+        let (sprint, run_ids) = collect(root, None, None, &["sprint-42".to_string()]).unwrap();
+        assert_eq!(sprint.runs, 1);
+        assert_eq!(run_ids, HashSet::from([tagged]));
+        assert_eq!(sprint.agents[1].wins, 1);
+    }
+
+    #[test]
+    fn spend_comes_from_the_ledger_by_period_and_agent() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let now: DateTime<Utc> = "2026-04-15T12:00:00Z".parse().unwrap();
+        let codex_run = Uuid::new_v4();
+        let entry = |agent: &str, at: &str, cost: f64| LedgerEntry {
+            timestamp: at.parse().unwrap(),
+            run_id: if agent == "codex" {
+                codex_run
+            } else {
+                Uuid::new_v4()
+            },
+            agent_key: agent.to_string(),
+            model: None,
+            total_tokens: 100,
+            cost_usd: Some(cost),
+        };
+        CostLedger::new(tmp.path())
+            .append(&[
+                entry("claude", "2026-03-30T10:00:00Z", 4.0),
+                entry("claude", "2026-04-02T10:00:00Z", 2.0),
+                entry("codex", "2026-04-15T09:00:00Z", 1.0),
+            ])
+            .unwrap();
+        let budget = BudgetConfig {
+            max_cost_usd_per_day: Some(5.0),
+            ..BudgetConfig::default()
+        };
+
+        let spend = collect_spend(tmp.path(), None, None, None, &budget, now).unwrap();
+        assert_eq!(
+            (spend.total_usd, spend.today_usd, spend.month_usd),
+            (7.0, 1.0, 3.0)
+        );
+        assert_eq!(spend.daily_limit_usd, Some(5.0));
+        assert_eq!(spend.monthly_limit_usd, None);
+
+        let claude = collect_spend(tmp.path(), None, Some("claude"), None, &budget, now).unwrap();
+        assert_eq!((claude.total_usd, claude.today_usd), (6.0, 0.0));
+
+        let one_run = HashSet::from([codex_run]);
+        let tagged = collect_spend(tmp.path(), None, None, Some(&one_run), &budget, now).unwrap();
+        assert_eq!((tagged.total_usd, tagged.month_usd), (1.0, 3.0));
+    }
+
+    #[test]
+    fn parses_since_windows() {
+        assert_eq!(parse_since("30d"), Ok(chrono::Duration::days(30)));
+        assert_eq!(parse_since("12h"), Ok(chrono::Duration::hours(12)));
+        assert_eq!(parse_since("2w"), Ok(chrono::Duration::weeks(2)));
+        assert!(parse_since("30").is_err());
+        assert!(parse_since("d").is_err());
+        assert!(parse_since("5y").is_err());
+    }
+}
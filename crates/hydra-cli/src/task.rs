@@ -0,0 +1,73 @@
+use std::path::{Component, Path};
+
+use anyhow::{bail, Context, Result};
+use hydra_core::task::TaskSpec;
+
+/// Read and check a `--task` file.
+pub fn load(path: &Path) -> Result<TaskSpec> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read task file {}", path.display()))?;
+    parse(&data)
+}
+
+fn parse(data: &str) -> Result<TaskSpec> {
+    let spec: TaskSpec = serde_yaml::from_str(data).context("invalid task file")?;
+    if spec.title.trim().is_empty() {
+        bail!("task file has an empty title");
+    }
+    for path in spec.relevant_paths.iter().chain(&spec.forbidden_paths) {
+        check_path(path)?;
+    }
+    if spec.sparse && spec.relevant_paths.is_empty() {
+        bail!("task file sets sparse but lists no relevant_paths");
+    }
+    Ok(spec)
+}
+
+fn check_path(path: &str) -> Result<()> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        bail!("task file has an empty path");
+    }
+    if trimmed.starts_with(['!', '#']) {
+        bail!("task path '{path}' cannot start with '!' or '#'");
+    }
+    if Path::new(trimmed)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("task path '{path}' must be relative to the repository root");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_spec() {
+        let spec = parse(
+            "title: Add retries\n\
+             description: Retry failed uploads.\n\
+             acceptance_criteria:\n  - uploads retry 3 times\n\
+             relevant_paths: [src/upload]\n\
+             forbidden_paths: ['migrations/**']\n\
+             sparse: true\n",
+        )
+        .unwrap();
+        assert_eq!(spec.title, "Add retries");
+        assert_eq!(spec.relevant_paths, vec!["src/upload"]);
+        assert!(spec.sparse);
+    }
+
+    #[test]
+    fn rejects_invalid_specs() {
+        assert!(parse("title: ''\n").is_err());
+        assert!(parse("title: x\nprompt: y\n").is_err());
+        assert!(parse("title: x\nsparse: true\n").is_err());
+        assert!(parse("title: x\nrelevant_paths: [../outside]\n").is_err());
+        assert!(parse("title: x\nrelevant_paths: [/etc]\n").is_err());
+        assert!(parse("title: x\nforbidden_paths: ['!src']\n").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! `hydra timeline`: a text Gantt chart of where a run spent its time,
+//! computed from its event timestamps.
+
+use anyhow::{bail, Context, Result};
+use uuid::Uuid;
+
+use hydra_core::artifact::{EventReader, RunLayout, RunManifest, RunTimeline, Span};
+
+use crate::output;
+use crate::run::{discover_repo_root, resolve_run_id};
+
+const MIN_WIDTH: usize = 20;
+
+/// One line of the chart: label, bars with their fill, and the note after.
+type Row = (String, Vec<(Span, char)>, String);
+
+pub struct TimelineOpts {
+    pub run_id: Option<Uuid>,
+    pub latest: bool,
+    pub json: bool,
+    /// Columns for the bars.
+    pub width: usize,
+}
+
+pub fn run_timeline(opts: TimelineOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let hydra_root = repo_root.join(".hydra");
+    let run_id = resolve_run_id(&hydra_root, opts.run_id, opts.latest)?;
+    let layout = RunLayout::new(&hydra_root, run_id);
+    if !layout.base_dir().exists() {
+        bail!(
+            "run {} not found at {}",
+            run_id,
+            layout.base_dir().display()
+        );
+    }
+
+    let mut events =
+        EventReader::read_all(&layout.events_path()).context("failed to read run events")?;
+    let manifest = RunManifest::read_from(&layout.manifest_path()).ok();
+    for agent in manifest.iter().flat_map(|m| &m.agents) {
+        events.extend(
+            EventReader::read_all(&layout.agent_events(&agent.agent_key)).unwrap_or_default(),
+        );
+    }
+    let Some(timeline) = RunTimeline::from_events(&events) else {
+        bail!("run {run_id} has no events yet");
+    };
+
+    output::emit(&timeline, opts.json)?;
+    if !opts.json {
+        let status = manifest.map(|m| format!("{:?}", m.status).to_lowercase());
+        println!(
+            "Run {run_id}: {} {}",
+            format_ms(timeline.total_ms),
+            status.as_deref().unwrap_or("unknown")
+        );
+        println!();
+        print!("{}", render(&timeline, opts.width.max(MIN_WIDTH)));
+    }
+    Ok(())
+}
+
+/// Bars for every phase, agent and scoring check, `width` columns wide.
+fn render(timeline: &RunTimeline, width: usize) -> String {
+    let mut rows: Vec<Row> = Vec::new();
+    if let Some(setup) = timeline.setup {
+        rows.push((
+            "setup".into(),
+            vec![(setup, '=')],
+            format_ms(setup.duration_ms()),
+        ));
+    }
+    for agent in &timeline.agents {
+        let run = agent.run;
+        let waiting_until = agent.first_output_ms.unwrap_or(run.end_ms).min(run.end_ms);
+        let mut bars = vec![(
+            Span {
+                start_ms: run.start_ms,
+                end_ms: waiting_until,
+            },
+            '.',
+        )];
+        if waiting_until < run.end_ms {
+            bars.push((
+                Span {
+                    start_ms: waiting_until,
+                    end_ms: run.end_ms,
+                },
+                '=',
+            ));
+        }
+        let mut note = format_ms(run.duration_ms());
+        if let Some(first) = agent.first_output_ms {
+            note.push_str(&format!(
+                ", first output +{}",
+                format_ms(first.saturating_sub(run.start_ms))
+            ));
+        }
+        note.push_str(&format!(
+            "  {}",
+            agent.status.as_deref().unwrap_or("running")
+        ));
+        rows.push((agent.agent_key.clone(), bars, note));
+
+        if agent.checks.is_empty() {
+            if let Some(scoring) = agent.scoring {
+                rows.push((
+                    "  scoring".into(),
+                    vec![(scoring, '#')],
+                    format_ms(scoring.duration_ms()),
+                ));
+            }
+        }
+        for check in &agent.checks {
+            rows.push((
+                format!("  {}", check.check),
+                vec![(check.span, '#')],
+                format_ms(check.span.duration_ms()),
+            ));
+        }
+    }
+    if let Some(scoring) = timeline.scoring {
+        rows.push((
+            "scoring".into(),
+            vec![(scoring, '#')],
+            format_ms(scoring.duration_ms()),
+        ));
+    }
+    if let Some(cleanup) = timeline.cleanup {
+        rows.push((
+            "cleanup".into(),
+            vec![(cleanup, '=')],
+            format_ms(cleanup.duration_ms()),
+        ));
+    }
+
+    let label_width = rows
+        .iter()
+        .map(|(label, ..)| label.len())
+        .max()
+        .unwrap_or(0);
+    let total = timeline.total_ms.max(1);
+    let column = |ms: u64| ((ms.min(total) as u128 * width as u128) / total as u128) as usize;
+
+    let end_label = format_ms(timeline.total_ms);
+    let mut out = format!(
+        "{:label_width$}  0s{:>pad$}\n",
+        "",
+        end_label,
+        pad = width.saturating_sub(2)
+    );
+    out.push_str(&format!(
+        "{:label_width$}  |{}|\n",
+        "",
+        "-".repeat(width.saturating_sub(2))
+    ));
+    for (label, bars, note) in rows {
+        let mut line = vec![' '; width];
+        for (span, fill) in bars {
+            let start = column(span.start_ms).min(width - 1);
+            let end = column(span.end_ms).clamp(start + 1, width);
+            line[start..end].fill(fill);
+        }
+        let line: String = line.into_iter().collect();
+        out.push_str(&format!("{label:label_width$}  {line}  {note}\n"));
+    }
+    out.push_str("\n. waiting for first output   = running   # scoring\n");
+    out
+}
+
+/// `850ms`, `8.2s`, `2m31s`, `1h04m`.
+fn format_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    if ms < 1000 {
+        format!("{ms}ms")
+    } else if secs < 60 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::{AgentTimeline, CheckTiming};
+
+    #[test]
+    fn render_draws_bars_in_proportion() {
+        let span = |start_ms, end_ms| Span { start_ms, end_ms };
+        let timeline = RunTimeline {
+            started_at: chrono::Utc::now(),
+            total_ms: 100_000,
+            setup: Some(span(0, 10_000)),
+            agents: vec![AgentTimeline {
+                agent_key: "claude".into(),
+                run: span(10_000, 60_000),
+                first_output_ms: Some(20_000),
+                status: Some("completed".into()),
+                scoring: Some(span(70_000, 90_000)),
+                checks: vec![CheckTiming {
+                    check: "build".into(),
+                    span: span(70_000, 80_000),
+                }],
+            }],
+            scoring: Some(span(60_000, 95_000)),
+            cleanup: Some(span(95_000, 100_000)),
+            finished: true,
+        };
+
+        assert_eq!(
+            render(&timeline, 20),
+            "         0s             1m40s
+         |------------------|
+setup    ==                    10.0s
+claude     ..========          50.0s, first output +10.0s  completed
+  build                ##      10.0s
+scoring              #######   35.0s
+cleanup                     =  5.0s
+
+. waiting for first output   = running   # scoring
+"
+        );
+    }
+
+    #[test]
+    fn format_ms_picks_a_readable_unit() {
+        assert_eq!(format_ms(850), "850ms");
+        assert_eq!(format_ms(8_200), "8.2s");
+        assert_eq!(format_ms(151_000), "2m31s");
+        assert_eq!(format_ms(3_840_000), "1h04m");
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use hydra_core::security::TrustStore;
+
+use crate::output;
+use crate::run::discover_repo_root;
+
+#[derive(Subcommand)]
+pub enum TrustCommand {
+    /// Trust a repository so agents and scoring may run in it
+    Add {
+        /// Repository to trust (defaults to the current one)
+        path: Option<PathBuf>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stop trusting a repository
+    Remove {
+        /// Repository to stop trusting (defaults to the current one)
+        path: Option<PathBuf>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List trusted repositories
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run_command(command: TrustCommand) -> Result<()> {
+    let store = TrustStore::user_default()?;
+    match command {
+        TrustCommand::Add { path, json } => {
+            let repo = target_repo(path)?;
+            let entry = store.trust(&repo)?;
+            output::emit(&entry, json)?;
+            if !json {
+                println!("Trusted {}.", entry.path.display());
+            }
+        }
+        TrustCommand::Remove { path, json } => {
+            let repo = target_repo(path)?;
+            let removed = store.revoke(&repo)?;
+            output::emit(
+                &serde_json::json!({ "path": repo, "removed": removed }),
+                json,
+            )?;
+            if !json {
+                if removed {
+                    println!("No longer trusting {}.", repo.display());
+                } else {
+                    println!("{} was not trusted.", repo.display());
+                }
+            }
+        }
+        TrustCommand::List { json } => {
+            let repos = store.list()?;
+            output::emit(&repos, json)?;
+            if !json {
+                if repos.is_empty() {
+                    println!("No trusted repositories.");
+                }
+                for repo in &repos {
+                    println!(
+                        "{}  (since {})",
+                        repo.path.display(),
+                        repo.trusted_at.format("%Y-%m-%d %H:%M UTC")
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Refuse to go on in an untrusted repository. On a terminal the user is
+/// asked once and the answer is remembered; otherwise `hydra trust add` has
+/// to be run first.
+pub fn ensure_trusted(can_prompt: bool) -> Result<()> {
+    let repo = discover_repo_root()?;
+    let store = TrustStore::user_default()?;
+    if store.is_trusted(&repo)? {
+        return Ok(());
+    }
+
+    let interactive = can_prompt && std::io::stdin().is_terminal();
+    if interactive && confirm(&repo)? {
+        store.trust(&repo)?;
+        return Ok(());
+    }
+    Ok(store.require_trusted(&repo)?)
+}
+
+fn confirm(repo: &std::path::Path) -> Result<bool> {
+    eprintln!(
+        "hydra has not been trusted in {}.\n\
+         Running agents or scoring executes this repository's build and test \
+         commands; only trust code you have reviewed.",
+        repo.display()
+    );
+    eprint!("Trust this repository? [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .context("failed to read answer")?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+fn target_repo(path: Option<PathBuf>) -> Result<PathBuf> {
+    match path {
+        Some(path) => path
+            .canonicalize()
+            .with_context(|| format!("cannot resolve {}", path.display())),
+        None => discover_repo_root(),
+    }
+}
@@ -0,0 +1,581 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use uuid::Uuid;
+
+use hydra_core::artifact::{EventKind, RunLayout, RunManifest, RunStatus};
+use hydra_core::scoring::ranking::AgentScore;
+
+use crate::cancel::request_cancel;
+use crate::run::{discover_repo_root, load_agent_score};
+use crate::watch::{RunEventSources, WatchState};
+
+/// Lines of output retained per agent pane.
+const MAX_PANE_LINES: usize = 500;
+
+/// How often the run list is rescanned for new runs.
+const RUN_LIST_REFRESH: Duration = Duration::from_secs(2);
+
+const TICK: Duration = Duration::from_millis(250);
+
+pub struct TuiOpts {
+    pub run_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaneView {
+    Output,
+    Diff,
+}
+
+#[derive(Debug, Clone)]
+struct RunEntry {
+    run_id: Uuid,
+    status: RunStatus,
+    started_at: DateTime<Utc>,
+}
+
+/// Live state for the run currently shown in the detail pane.
+struct WatchedRun {
+    layout: RunLayout,
+    agents: Vec<String>,
+    sources: RunEventSources,
+    state: WatchState,
+    output: HashMap<String, VecDeque<String>>,
+    scores: HashMap<String, AgentScore>,
+}
+
+impl WatchedRun {
+    fn open(hydra_root: &Path, run_id: Uuid) -> Self {
+        let layout = RunLayout::new(hydra_root, run_id);
+        let agents = RunManifest::read_from(&layout.manifest_path())
+            .map(|m| m.agents.into_iter().map(|a| a.agent_key).collect())
+            .unwrap_or_default();
+        let sources = RunEventSources::open(&layout);
+        let mut watched = Self {
+            layout,
+            agents,
+            sources,
+            state: WatchState::default(),
+            output: HashMap::new(),
+            scores: HashMap::new(),
+        };
+        watched.reload_scores();
+        watched
+    }
+
+    fn ingest(&mut self) -> Result<()> {
+        let events = self.sources.poll()?;
+        let mut scores_changed = false;
+        for event in &events {
+            if event.kind == EventKind::ScoreFinished {
+                scores_changed = true;
+            }
+            let Some(line) = self.state.apply(event) else {
+                continue;
+            };
+            let Some(agent) = event.agent_key.as_deref() else {
+                continue;
+            };
+            let prefix = format!("[{agent}] ");
+            let text = line.strip_prefix(&prefix).unwrap_or(&line).to_string();
+            let pane = self.output.entry(agent.to_string()).or_default();
+            pane.push_back(text);
+            if pane.len() > MAX_PANE_LINES {
+                pane.pop_front();
+            }
+        }
+        if scores_changed {
+            self.reload_scores();
+        }
+        Ok(())
+    }
+
+    fn reload_scores(&mut self) {
+        for agent in &self.agents {
+            if let Ok(Some(score)) = load_agent_score(&self.layout, agent) {
+                self.scores.insert(agent.clone(), score);
+            }
+        }
+    }
+}
+
+struct App {
+    hydra_root: PathBuf,
+    hard_timeout: Duration,
+    runs: Vec<RunEntry>,
+    run_list: ListState,
+    selected_agent: usize,
+    view: PaneView,
+    diff_scroll: u16,
+    diff_cache: Option<(String, String)>,
+    watched: Option<WatchedRun>,
+    last_run_refresh: Instant,
+    notice: Option<String>,
+}
+
+impl App {
+    fn new(hydra_root: PathBuf, hard_timeout: Duration, initial_run: Option<Uuid>) -> Self {
+        let mut app = Self {
+            hydra_root,
+            hard_timeout,
+            runs: Vec::new(),
+            run_list: ListState::default(),
+            selected_agent: 0,
+            view: PaneView::Output,
+            diff_scroll: 0,
+            diff_cache: None,
+            watched: None,
+            last_run_refresh: Instant::now(),
+            notice: None,
+        };
+        app.refresh_runs();
+        let index = match initial_run {
+            Some(id) => app.runs.iter().position(|r| r.run_id == id),
+            None if app.runs.is_empty() => None,
+            None => Some(0),
+        };
+        app.select_run(index);
+        app
+    }
+
+    fn refresh_runs(&mut self) {
+        let selected = self.selected_run_id();
+        self.runs = load_run_entries(&self.hydra_root);
+        let index = selected.and_then(|id| self.runs.iter().position(|r| r.run_id == id));
+        self.run_list
+            .select(index.or(if self.runs.is_empty() { None } else { Some(0) }));
+        self.last_run_refresh = Instant::now();
+    }
+
+    fn selected_run_id(&self) -> Option<Uuid> {
+        self.run_list
+            .selected()
+            .and_then(|i| self.runs.get(i))
+            .map(|r| r.run_id)
+    }
+
+    fn select_run(&mut self, index: Option<usize>) {
+        self.run_list.select(index);
+        self.selected_agent = 0;
+        self.view = PaneView::Output;
+        self.diff_cache = None;
+        self.diff_scroll = 0;
+        self.watched = self
+            .selected_run_id()
+            .map(|id| WatchedRun::open(&self.hydra_root, id));
+    }
+
+    fn move_run(&mut self, delta: isize) {
+        if self.runs.is_empty() {
+            return;
+        }
+        let current = self.run_list.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.runs.len() as isize - 1) as usize;
+        if Some(next) != self.run_list.selected() {
+            self.select_run(Some(next));
+        }
+    }
+
+    fn move_agent(&mut self, delta: isize) {
+        let Some(watched) = &self.watched else {
+            return;
+        };
+        if watched.agents.is_empty() {
+            return;
+        }
+        let len = watched.agents.len() as isize;
+        self.selected_agent = ((self.selected_agent as isize + delta).rem_euclid(len)) as usize;
+        self.diff_cache = None;
+        self.diff_scroll = 0;
+    }
+
+    fn selected_agent_key(&self) -> Option<&str> {
+        self.watched
+            .as_ref()
+            .and_then(|w| w.agents.get(self.selected_agent))
+            .map(String::as_str)
+    }
+
+    fn toggle_view(&mut self) {
+        self.view = match self.view {
+            PaneView::Output => PaneView::Diff,
+            PaneView::Diff => PaneView::Output,
+        };
+        self.diff_scroll = 0;
+    }
+
+    fn diff_text(&mut self) -> &str {
+        let agent = self.selected_agent_key().unwrap_or_default().to_string();
+        let stale = self
+            .diff_cache
+            .as_ref()
+            .is_none_or(|(key, _)| *key != agent);
+        if stale {
+            let text = self
+                .watched
+                .as_ref()
+                .and_then(|w| std::fs::read_to_string(w.layout.agent_diff(&agent)).ok())
+                .unwrap_or_else(|| "(no diff.patch yet)".to_string());
+            self.diff_cache = Some((agent, text));
+        }
+        self.diff_cache
+            .as_ref()
+            .map(|(_, t)| t.as_str())
+            .unwrap_or("")
+    }
+
+    fn cancel(&mut self, whole_run: bool) {
+        let Some(watched) = &self.watched else {
+            return;
+        };
+        let agent = if whole_run {
+            None
+        } else {
+            self.selected_agent_key()
+        };
+        self.notice = Some(match request_cancel(&watched.layout, agent) {
+            Ok(_) => format!("cancel requested for {}", agent.unwrap_or("all agents")),
+            Err(e) => format!("cancel failed: {e}"),
+        });
+    }
+
+    fn tick(&mut self) -> Result<()> {
+        if let Some(watched) = &mut self.watched {
+            watched.ingest()?;
+        }
+        if self.last_run_refresh.elapsed() >= RUN_LIST_REFRESH {
+            self.refresh_runs();
+        }
+        Ok(())
+    }
+
+    /// Handle a key press, returning `false` when the dashboard should exit.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') if self.view == PaneView::Output => self.move_run(-1),
+            KeyCode::Down | KeyCode::Char('j') if self.view == PaneView::Output => self.move_run(1),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.diff_scroll = self.diff_scroll.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.diff_scroll = self.diff_scroll.saturating_add(1)
+            }
+            KeyCode::PageUp => self.diff_scroll = self.diff_scroll.saturating_sub(20),
+            KeyCode::PageDown => self.diff_scroll = self.diff_scroll.saturating_add(20),
+            KeyCode::Left | KeyCode::BackTab => self.move_agent(-1),
+            KeyCode::Right | KeyCode::Tab => self.move_agent(1),
+            KeyCode::Char('d') => self.toggle_view(),
+            KeyCode::Char('c') => self.cancel(false),
+            KeyCode::Char('x') => self.cancel(true),
+            KeyCode::Char('r') => {
+                self.refresh_runs();
+                let index = self.run_list.selected();
+                self.select_run(index);
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+pub fn run_tui(opts: TuiOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let config = crate::config::load()?;
+    let hard_timeout = Duration::from_secs(config.supervisor.hard_timeout_seconds);
+    let mut app = App::new(repo_root.join(".hydra"), hard_timeout, opts.run_id);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    loop {
+        app.tick()?;
+        terminal.draw(|frame| draw(frame, app))?;
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let title = match app.selected_run_id() {
+        Some(id) => format!(" Hydra  run {id}"),
+        None => " Hydra  no runs found under .hydra/runs".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+        header,
+    );
+    let help = " q quit  up/down run  left/right agent  d output/diff  c cancel agent  x cancel run  r reload";
+    let footer_text = match &app.notice {
+        Some(notice) => format!(" {notice}  |{help}"),
+        None => help.to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(footer_text).style(Style::default().fg(Color::DarkGray)),
+        footer,
+    );
+
+    let [runs_area, detail_area] =
+        Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(body);
+    draw_run_list(frame, app, runs_area);
+    draw_detail(frame, app, detail_area);
+}
+
+fn draw_run_list(frame: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .runs
+        .iter()
+        .map(|run| {
+            let short_id: String = run.run_id.to_string().chars().take(8).collect();
+            ListItem::new(format!(
+                "{} {:<11} {}",
+                short_id,
+                status_label(&run.status),
+                run.started_at.format("%m-%d %H:%M")
+            ))
+            .style(Style::default().fg(status_color(&run.status)))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(" Runs "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.run_list);
+}
+
+fn draw_detail(frame: &mut Frame, app: &mut App, area: Rect) {
+    let [tabs_area, status_area, pane_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    let Some(watched) = &app.watched else {
+        frame.render_widget(
+            Paragraph::new("Select a run to inspect.")
+                .block(Block::default().borders(Borders::ALL)),
+            area,
+        );
+        return;
+    };
+
+    let tabs = Tabs::new(watched.agents.iter().map(|a| Line::from(a.as_str())))
+        .select(app.selected_agent)
+        .block(Block::default().borders(Borders::ALL).title(" Agents "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_widget(tabs, tabs_area);
+
+    let agent = app.selected_agent_key().unwrap_or_default().to_string();
+    let mut status = watched
+        .state
+        .agent_summary(&agent, Utc::now(), app.hard_timeout)
+        .unwrap_or_else(|| "pending".to_string());
+    if let Some(score) = watched.scores.get(&agent) {
+        status.push_str(&format!(
+            "  score {:.1} ({})",
+            score.composite,
+            if score.mergeable {
+                "mergeable"
+            } else {
+                "not mergeable"
+            }
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(status).block(Block::default().borders(Borders::ALL).title(" Status ")),
+        status_area,
+    );
+
+    match app.view {
+        PaneView::Output => {
+            let visible = pane_area.height.saturating_sub(2) as usize;
+            let lines: Vec<Line> = watched
+                .output
+                .get(&agent)
+                .map(|pane| {
+                    let skip = pane.len().saturating_sub(visible);
+                    pane.iter()
+                        .skip(skip)
+                        .map(|l| Line::from(l.as_str()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title(" Output ")),
+                pane_area,
+            );
+        }
+        PaneView::Diff => {
+            let scroll = app.diff_scroll;
+            let text = app.diff_text().to_string();
+            let lines: Vec<Line> = text
+                .lines()
+                .map(|l| {
+                    let color = match l.chars().next() {
+                        Some('+') => Color::Green,
+                        Some('-') => Color::Red,
+                        Some('@') => Color::Cyan,
+                        _ => Color::Reset,
+                    };
+                    Line::styled(l.to_string(), Style::default().fg(color))
+                })
+                .collect();
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .scroll((scroll, 0))
+                    .block(Block::default().borders(Borders::ALL).title(" Diff ")),
+                pane_area,
+            );
+        }
+    }
+}
+
+/// Load every run under `.hydra/runs`, newest first.
+fn load_run_entries(hydra_root: &Path) -> Vec<RunEntry> {
+    let mut entries: Vec<RunEntry> = RunLayout::list_runs(hydra_root)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|run_id| {
+            let layout = RunLayout::new(hydra_root, run_id);
+            let manifest = RunManifest::read_from(&layout.manifest_path()).ok()?;
+            Some(RunEntry {
+                run_id,
+                status: manifest.status,
+                started_at: manifest.started_at,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.started_at));
+    entries
+}
+
+fn status_label(status: &RunStatus) -> &'static str {
+    match status {
+        RunStatus::Running => "running",
+        RunStatus::Completed => "completed",
+        RunStatus::Failed => "failed",
+        RunStatus::TimedOut => "timed_out",
+        RunStatus::Interrupted => "interrupted",
+    }
+}
+
+fn status_color(status: &RunStatus) -> Color {
+    match status {
+        RunStatus::Running => Color::Yellow,
+        RunStatus::Completed => Color::Green,
+        RunStatus::Failed | RunStatus::TimedOut => Color::Red,
+        RunStatus::Interrupted => Color::Magenta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hydra_core::artifact::{EventWriter, RunEvent};
+    use hydra_core::test_support::new_run;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use tempfile::TempDir;
+
+    fn write_run(hydra_root: &Path, agents: &[&str]) -> Uuid {
+        let (layout, manifest) = new_run(hydra_root, agents);
+        manifest.write_to(&layout.manifest_path()).unwrap();
+        layout.run_id()
+    }
+
+    #[test]
+    fn watched_run_routes_output_to_agent_panes() {
+        let tmp = TempDir::new().unwrap();
+        let hydra_root = tmp.path().join(".hydra");
+        let run_id = write_run(&hydra_root, &["claude", "codex"]);
+        let layout = RunLayout::new(&hydra_root, run_id);
+
+        for (kind, agent, data) in [
+            (EventKind::AgentStarted, "claude", serde_json::json!({})),
+            (
+                EventKind::AgentStdout,
+                "claude",
+                serde_json::json!({"line": "hello"}),
+            ),
+            (
+                EventKind::AgentStderr,
+                "codex",
+                serde_json::json!({"line": "oops"}),
+            ),
+        ] {
+            EventWriter::create(&layout.agent_events(agent))
+                .unwrap()
+                .write_event(&RunEvent::new(kind, Some(agent.to_string()), data))
+                .unwrap();
+        }
+
+        let mut watched = WatchedRun::open(&hydra_root, run_id);
+        watched.ingest().unwrap();
+        assert_eq!(watched.agents, vec!["claude", "codex"]);
+        assert_eq!(watched.output["claude"].back().unwrap(), "hello");
+        assert_eq!(watched.output["codex"].back().unwrap(), "stderr: oops");
+    }
+
+    #[test]
+    fn app_selects_newest_run_and_renders() {
+        let tmp = TempDir::new().unwrap();
+        let hydra_root = tmp.path().join(".hydra");
+        write_run(&hydra_root, &["claude"]);
+        std::thread::sleep(Duration::from_millis(5));
+        let newest = write_run(&hydra_root, &["codex"]);
+
+        let mut app = App::new(hydra_root, Duration::from_secs(60), None);
+        assert_eq!(app.selected_run_id(), Some(newest));
+        assert_eq!(app.selected_agent_key(), Some("codex"));
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+        let rendered: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(rendered.contains(&newest.to_string()));
+        assert!(rendered.contains("codex"));
+    }
+
+    #[test]
+    fn handle_key_quits_and_toggles_diff_view() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = App::new(tmp.path().join(".hydra"), Duration::from_secs(60), None);
+        assert!(app.selected_run_id().is_none());
+        assert!(app.handle_key(KeyCode::Char('d')));
+        assert_eq!(app.view, PaneView::Diff);
+        assert!(!app.handle_key(KeyCode::Char('q')));
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use hydra_core::orchestrator::PromptVariant;
+use serde::Deserialize;
+
+/// A `--variants` file: phrasings of one task to race on a single agent.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VariantsFile {
+    variants: Vec<PromptVariant>,
+}
+
+pub fn load(path: &Path) -> Result<Vec<PromptVariant>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read variants file {}", path.display()))?;
+    parse(&data)
+}
+
+fn parse(data: &str) -> Result<Vec<PromptVariant>> {
+    let file: VariantsFile = serde_yaml::from_str(data).context("invalid variants file")?;
+    if file.variants.len() < 2 {
+        bail!("variants file needs at least two variants to compare");
+    }
+    Ok(file.variants)
+}
+
+/// The run's recorded task prompt: every variant under its name.
+pub fn combined_prompt(variants: &[PromptVariant]) -> String {
+    variants
+        .iter()
+        .map(|v| format!("## Variant: {}\n\n{}\n", v.name, v.prompt.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_variants_and_combines_prompts() {
+        let variants = parse(
+            "variants:\n  - name: terse\n    prompt: Fix it.\n  - name: detailed\n    prompt: |\n      Fix the bug.\n      Add a test.\n",
+        )
+        .unwrap();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[1].prompt, "Fix the bug.\nAdd a test.\n");
+        assert_eq!(
+            combined_prompt(&variants),
+            "## Variant: terse\n\nFix it.\n\n## Variant: detailed\n\nFix the bug.\nAdd a test.\n"
+        );
+    }
+
+    #[test]
+    fn rejects_a_single_variant_and_unknown_keys() {
+        assert!(parse("variants:\n  - name: a\n    prompt: x\n").is_err());
+        assert!(parse("variants: []\nagents: [claude]\n").is_err());
+    }
+}
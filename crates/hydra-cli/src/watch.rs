@@ -0,0 +1,658 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use hydra_core::adapter::AgentEvent;
+use hydra_core::artifact::{EventFollower, EventKind, RunEvent, RunLayout, RunManifest, RunStatus};
+use hydra_core::orchestrator::{BudgetWarning, ProgressEstimate};
+use hydra_core::security::{DangerousAction, InjectionFinding, PathViolation};
+
+use crate::output;
+use crate::run::{discover_repo_root, resolve_run_id};
+
+/// Maximum characters of agent output shown per streamed line.
+const MAX_OUTPUT_CHARS: usize = 160;
+
+/// How often the per-agent status block is reprinted while agents run.
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct WatchOpts {
+    pub run_id: Option<Uuid>,
+    pub latest: bool,
+    pub json: bool,
+    pub poll_interval_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AgentWatchStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl AgentWatchStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            AgentWatchStatus::Running => "running",
+            AgentWatchStatus::Completed => "completed",
+            AgentWatchStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AgentWatchState {
+    status: AgentWatchStatus,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    input_tokens: u64,
+    output_tokens: u64,
+    progress: Option<ProgressEstimate>,
+}
+
+/// Aggregated view of a run, rebuilt incrementally from events.jsonl.
+#[derive(Debug, Default)]
+pub(crate) struct WatchState {
+    agents: BTreeMap<String, AgentWatchState>,
+    pub(crate) finished: Option<RunStatus>,
+}
+
+impl WatchState {
+    /// Apply one event and return the line to print for it, if any.
+    pub(crate) fn apply(&mut self, event: &RunEvent) -> Option<String> {
+        let agent = event.agent_key.as_deref().unwrap_or("-");
+        match event.kind {
+            EventKind::RunStarted => {
+                let agents = event
+                    .data
+                    .get("agents")
+                    .and_then(|v| v.as_array())
+                    .map(|keys| {
+                        keys.iter()
+                            .filter_map(|k| k.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default();
+                Some(format!("Run started (agents: {agents})"))
+            }
+            EventKind::AgentStarted => {
+                let key = event.agent_key.clone()?;
+                // Race writes AgentStarted both at dispatch and once the process
+                // spawns; only the first one starts the clock.
+                if self.agents.contains_key(&key) {
+                    return None;
+                }
+                self.agents.insert(
+                    key,
+                    AgentWatchState {
+                        status: AgentWatchStatus::Running,
+                        started_at: event.timestamp,
+                        finished_at: None,
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        progress: None,
+                    },
+                );
+                Some(format!("[{agent}] started"))
+            }
+            EventKind::AgentStdout => {
+                if let Some(line) = event.data.get("line").and_then(|v| v.as_str()) {
+                    return Some(match event.data.get("step").and_then(|v| v.as_str()) {
+                        Some(step) => format!("[{agent}:{step}] {}", truncate(line)),
+                        None => format!("[{agent}] {}", truncate(line)),
+                    });
+                }
+                let agent_event: AgentEvent = serde_json::from_value(event.data.clone()).ok()?;
+                self.render_agent_event(agent, &agent_event)
+            }
+            EventKind::AgentStderr => {
+                let line = event.data.get("line").and_then(|v| v.as_str())?;
+                Some(match event.data.get("step").and_then(|v| v.as_str()) {
+                    Some(step) => format!("[{agent}:{step}] stderr: {}", truncate(line)),
+                    None => format!("[{agent}] stderr: {}", truncate(line)),
+                })
+            }
+            EventKind::AgentStep => {
+                let step = event.data.get("step").and_then(|v| v.as_str())?;
+                Some(format!("[{agent}:{step}] done"))
+            }
+            EventKind::AgentProgress => {
+                let progress: ProgressEstimate = serde_json::from_value(event.data.clone()).ok()?;
+                let state = self.agents.get_mut(agent)?;
+                let previous = state.progress.replace(progress.clone());
+                // Percentages tick often; only a new phase is worth a line.
+                if previous.is_some_and(|p| p.phase == progress.phase) {
+                    return None;
+                }
+                Some(format!(
+                    "[{agent}] {} ({}%)",
+                    progress.phase.as_str(),
+                    progress.percent
+                ))
+            }
+            EventKind::AgentCompleted => {
+                if !self.finish_agent(agent, AgentWatchStatus::Completed, event.timestamp) {
+                    return None;
+                }
+                let exit_code = event
+                    .data
+                    .get("exit_code")
+                    .and_then(|v| v.as_i64())
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                Some(format!("[{agent}] completed (exit {exit_code})"))
+            }
+            EventKind::AgentFailed => {
+                if !self.finish_agent(agent, AgentWatchStatus::Failed, event.timestamp) {
+                    return None;
+                }
+                let error = event
+                    .data
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown error");
+                Some(format!("[{agent}] failed: {error}"))
+            }
+            EventKind::ScoreStarted => Some("Scoring started".to_string()),
+            EventKind::ScoreFinished => Some("Scoring finished".to_string()),
+            EventKind::AgentScored => None,
+            EventKind::BudgetWarning => {
+                let warning: BudgetWarning = serde_json::from_value(event.data.clone()).ok()?;
+                Some(format!("Budget warning: {warning}"))
+            }
+            EventKind::RaceStoppedEarly => {
+                let composite = event.data.get("composite").and_then(|v| v.as_f64())?;
+                Some(format!(
+                    "[{agent}] passed the gates (score {composite:.1}); stopping the other agents"
+                ))
+            }
+            EventKind::RunCompleted => {
+                self.finished = Some(RunStatus::Completed);
+                Some("Run completed".to_string())
+            }
+            EventKind::RunFailed => {
+                self.finished = Some(RunStatus::Failed);
+                Some("Run failed".to_string())
+            }
+            EventKind::SecurityViolation => {
+                if let Some(action) = event.data.get("action") {
+                    let action: DangerousAction = serde_json::from_value(action.clone()).ok()?;
+                    return Some(format!("[{agent}] stopped: not allowed to {action}"));
+                }
+                if event.data.get("kind").and_then(|v| v.as_str()) == Some("prompt_injection") {
+                    let findings: Vec<InjectionFinding> =
+                        serde_json::from_value(event.data.get("findings")?.clone()).ok()?;
+                    let sources: BTreeSet<&str> =
+                        findings.iter().map(|f| f.source.as_str()).collect();
+                    return Some(format!(
+                        "Possible prompt injection in {} ({} match(es))",
+                        sources.into_iter().collect::<Vec<_>>().join(", "),
+                        findings.len()
+                    ));
+                }
+                if event.data.get("kind").and_then(|v| v.as_str()) == Some("path_policy") {
+                    let violation: PathViolation =
+                        serde_json::from_value(event.data.clone()).ok()?;
+                    return Some(format!("[{agent}] write policy: touched {violation}"));
+                }
+                let host = event.data.get("host").and_then(|v| v.as_str())?;
+                let port = event.data.get("port").and_then(|v| v.as_u64()).unwrap_or(0);
+                Some(format!("[{agent}] blocked network access to {host}:{port}"))
+            }
+            EventKind::ApprovalRequested => {
+                let action: DangerousAction =
+                    serde_json::from_value(event.data.get("action")?.clone()).ok()?;
+                Some(format!(
+                    "[{agent}] paused: waiting for approval to {action}"
+                ))
+            }
+            EventKind::ApprovalResolved => {
+                let decision = event.data.get("decision").and_then(|v| v.as_str())?;
+                Some(match decision {
+                    "approve" => format!("[{agent}] approved, resuming"),
+                    _ => format!("[{agent}] denied, stopping"),
+                })
+            }
+            EventKind::MergeReady | EventKind::MergeSucceeded | EventKind::MergeConflict => None,
+        }
+    }
+
+    fn render_agent_event(&mut self, agent: &str, event: &AgentEvent) -> Option<String> {
+        match event {
+            AgentEvent::Message { content } => Some(format!("[{agent}] {}", truncate(content))),
+            AgentEvent::ToolCall { tool, .. } => Some(format!("[{agent}] tool: {tool}")),
+            AgentEvent::ToolResult { .. } => None,
+            AgentEvent::Progress { message, percent } => Some(match percent {
+                Some(p) => format!("[{agent}] progress {p:.0}%: {}", truncate(message)),
+                None => format!("[{agent}] progress: {}", truncate(message)),
+            }),
+            AgentEvent::Completed { summary } => summary
+                .as_deref()
+                .map(|s| format!("[{agent}] summary: {}", truncate(s))),
+            AgentEvent::Failed { error } => Some(format!("[{agent}] error: {}", truncate(error))),
+            AgentEvent::Usage {
+                input_tokens,
+                output_tokens,
+                ..
+            } => {
+                if let Some(state) = self.agents.get_mut(agent) {
+                    state.input_tokens += input_tokens;
+                    state.output_tokens += output_tokens;
+                }
+                None
+            }
+        }
+    }
+
+    /// Mark an agent finished. Returns `false` if it had already finished,
+    /// since race records the terminal event in both the agent and run logs.
+    fn finish_agent(&mut self, agent: &str, status: AgentWatchStatus, at: DateTime<Utc>) -> bool {
+        match self.agents.get_mut(agent) {
+            Some(state) if state.finished_at.is_some() => false,
+            Some(state) => {
+                state.status = status;
+                state.finished_at = Some(at);
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Final per-agent state for the `--output` result file.
+    fn result_json(&self, run_id: Uuid, status: Option<&RunStatus>) -> serde_json::Value {
+        let agents: Vec<serde_json::Value> = self
+            .agents
+            .iter()
+            .map(|(key, a)| {
+                serde_json::json!({
+                    "agent_key": key,
+                    "status": a.status.as_str(),
+                    "started_at": a.started_at,
+                    "finished_at": a.finished_at,
+                    "input_tokens": a.input_tokens,
+                    "output_tokens": a.output_tokens,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "run_id": run_id,
+            "status": status,
+            "agents": agents,
+        })
+    }
+
+    fn any_running(&self) -> bool {
+        self.agents
+            .values()
+            .any(|a| a.status == AgentWatchStatus::Running)
+    }
+
+    fn status_lines(&self, now: DateTime<Utc>, hard_timeout: Duration) -> Vec<String> {
+        self.agents
+            .keys()
+            .filter_map(|key| {
+                self.agent_summary(key, now, hard_timeout)
+                    .map(|summary| format!("  {key:<10} {summary}"))
+            })
+            .collect()
+    }
+
+    /// One-line status, timing, and token usage for a single agent.
+    pub(crate) fn agent_summary(
+        &self,
+        agent_key: &str,
+        now: DateTime<Utc>,
+        hard_timeout: Duration,
+    ) -> Option<String> {
+        let state = self.agents.get(agent_key)?;
+        let end = state.finished_at.unwrap_or(now);
+        let elapsed = (end - state.started_at).to_std().unwrap_or_default();
+        let timing = if state.status == AgentWatchStatus::Running {
+            let progress = state
+                .progress
+                .as_ref()
+                .map(|p| format!("{:>3}% {}  ", p.percent, p.phase.as_str()))
+                .unwrap_or_default();
+            format!(
+                "{progress}elapsed {}  remaining {}",
+                format_duration(elapsed),
+                format_duration(hard_timeout.saturating_sub(elapsed))
+            )
+        } else {
+            format!("took {}", format_duration(elapsed))
+        };
+        Some(format!(
+            "{:<10} {}  tokens {} (in {} / out {})",
+            state.status.as_str(),
+            timing,
+            state.input_tokens + state.output_tokens,
+            state.input_tokens,
+            state.output_tokens
+        ))
+    }
+}
+
+pub fn run_watch(opts: WatchOpts) -> Result<()> {
+    let repo_root = discover_repo_root()?;
+    let hydra_root = repo_root.join(".hydra");
+    let run_id = resolve_run_id(&hydra_root, opts.run_id, opts.latest)?;
+    let layout = RunLayout::new(&hydra_root, run_id);
+
+    if !layout.base_dir().exists() {
+        bail!(
+            "run {} not found at {}",
+            run_id,
+            layout.base_dir().display()
+        );
+    }
+
+    let config = crate::config::load()?;
+    let hard_timeout = Duration::from_secs(config.supervisor.hard_timeout_seconds);
+    let poll_interval = Duration::from_millis(opts.poll_interval_ms.max(50));
+
+    if !opts.json {
+        println!("Watching run {run_id}");
+        println!();
+    }
+
+    let mut sources = RunEventSources::open(&layout);
+    let mut state = WatchState::default();
+    let mut last_status = Instant::now();
+
+    loop {
+        let events = sources.poll()?;
+        let saw_events = !events.is_empty();
+
+        for event in &events {
+            let line = state.apply(event);
+            if opts.json {
+                println!("{}", serde_json::to_string(event)?);
+            } else if let Some(line) = line {
+                println!("{line}");
+            }
+        }
+
+        if state.finished.is_some() {
+            break;
+        }
+
+        // A run that stopped without writing a terminal event (crash, kill -9)
+        // still gets its manifest left behind; stop once it is no longer running.
+        if !saw_events && manifest_is_settled(&layout) {
+            break;
+        }
+
+        if !opts.json && state.any_running() && last_status.elapsed() >= STATUS_INTERVAL {
+            println!();
+            for line in state.status_lines(Utc::now(), hard_timeout) {
+                println!("{line}");
+            }
+            println!();
+            last_status = Instant::now();
+        }
+
+        std::thread::sleep(poll_interval);
+    }
+
+    if output::requested() {
+        let status = state.finished.clone().or_else(|| {
+            RunManifest::read_from(&layout.manifest_path())
+                .ok()
+                .map(|m| m.status)
+        });
+        // --json already streamed the events, so only the file gets the result.
+        output::emit(&state.result_json(run_id, status.as_ref()), false)?;
+    }
+
+    if !opts.json {
+        println!();
+        println!("Final Status");
+        println!("============");
+        for line in state.status_lines(Utc::now(), hard_timeout) {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Followers for the run-level event log and each agent's own log.
+///
+/// Race writes lifecycle events to the run's events.jsonl while agent output
+/// goes to `agents/<key>/events.jsonl`, so both have to be tailed.
+pub(crate) struct RunEventSources {
+    layout: RunLayout,
+    followers: Vec<EventFollower>,
+    agents_loaded: bool,
+}
+
+impl RunEventSources {
+    pub(crate) fn open(layout: &RunLayout) -> Self {
+        let mut sources = Self {
+            layout: layout.clone(),
+            followers: vec![EventFollower::new(&layout.events_path())],
+            agents_loaded: false,
+        };
+        sources.load_agent_followers();
+        sources
+    }
+
+    /// Add agent followers once the manifest exists. A source opened before
+    /// the race writes its manifest picks the agents up on a later poll.
+    fn load_agent_followers(&mut self) {
+        if self.agents_loaded {
+            return;
+        }
+        let Ok(manifest) = RunManifest::read_from(&self.layout.manifest_path()) else {
+            return;
+        };
+        self.followers.extend(
+            manifest
+                .agents
+                .iter()
+                .map(|agent| EventFollower::new(&self.layout.agent_events(&agent.agent_key))),
+        );
+        self.agents_loaded = true;
+    }
+
+    /// Poll every source and return new events in timestamp order.
+    pub(crate) fn poll(&mut self) -> Result<Vec<RunEvent>> {
+        self.load_agent_followers();
+        let mut events = Vec::new();
+        for follower in &mut self.followers {
+            events.extend(follower.poll().context("failed to read run events")?);
+        }
+        events.sort_by_key(|e| e.timestamp);
+        Ok(events)
+    }
+}
+
+pub(crate) fn manifest_is_settled(layout: &RunLayout) -> bool {
+    RunManifest::read_from(&layout.manifest_path())
+        .map(|m| m.status != RunStatus::Running)
+        .unwrap_or(false)
+}
+
+fn truncate(text: &str) -> String {
+    let single_line = text.replace(['\n', '\r'], " ");
+    if single_line.chars().count() <= MAX_OUTPUT_CHARS {
+        return single_line;
+    }
+    let mut out: String = single_line.chars().take(MAX_OUTPUT_CHARS).collect();
+    out.push_str("...");
+    out
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, agent: Option<&str>, data: serde_json::Value) -> RunEvent {
+        RunEvent::new(kind, agent.map(str::to_string), data)
+    }
+
+    #[test]
+    fn watch_state_tracks_agent_lifecycle_and_usage() {
+        let mut state = WatchState::default();
+        state.apply(&event(
+            EventKind::AgentStarted,
+            Some("claude"),
+            serde_json::json!({}),
+        ));
+        assert!(state
+            .apply(&event(
+                EventKind::AgentStarted,
+                Some("claude"),
+                serde_json::json!({}),
+            ))
+            .is_none());
+
+        let usage = serde_json::json!({
+            "type": "usage",
+            "input_tokens": 100,
+            "output_tokens": 20,
+            "extra": {},
+        });
+        assert!(state
+            .apply(&event(EventKind::AgentStdout, Some("claude"), usage))
+            .is_none());
+        assert!(state.any_running());
+
+        let progress = |phase: &str, percent: u8| {
+            event(
+                EventKind::AgentProgress,
+                Some("claude"),
+                serde_json::json!({ "phase": phase, "percent": percent, "tokens_per_minute": null }),
+            )
+        };
+        assert_eq!(
+            state.apply(&progress("editing", 31)).as_deref(),
+            Some("[claude] editing (31%)")
+        );
+        assert!(state.apply(&progress("editing", 40)).is_none());
+        let summary = state
+            .agent_summary("claude", Utc::now(), Duration::from_secs(600))
+            .unwrap();
+        assert!(summary.contains(" 40% editing"), "{summary}");
+
+        state.apply(&event(
+            EventKind::AgentCompleted,
+            Some("claude"),
+            serde_json::json!({ "exit_code": 0 }),
+        ));
+        let agent = &state.agents["claude"];
+        assert_eq!(agent.status, AgentWatchStatus::Completed);
+        assert_eq!(agent.input_tokens, 100);
+        assert_eq!(agent.output_tokens, 20);
+        assert!(!state.any_running());
+    }
+
+    #[test]
+    fn watch_state_summarizes_prompt_injection_findings() {
+        let mut state = WatchState::default();
+        let line = state.apply(&event(
+            EventKind::SecurityViolation,
+            None,
+            serde_json::json!({
+                "kind": "prompt_injection",
+                "findings": [
+                    { "source": "<prompt>", "line": 1, "pattern": "ignore_instructions", "excerpt": "ignore previous instructions" },
+                    { "source": "docs/bug.md", "line": 4, "pattern": "hidden_instruction", "excerpt": "<!-- agent: ... -->" },
+                ],
+                "stripped": false,
+            }),
+        ));
+        assert_eq!(
+            line.as_deref(),
+            Some("Possible prompt injection in <prompt>, docs/bug.md (2 match(es))")
+        );
+    }
+
+    #[test]
+    fn watch_state_reports_agent_completion_once() {
+        let mut state = WatchState::default();
+        state.apply(&event(
+            EventKind::AgentStarted,
+            Some("codex"),
+            serde_json::json!({}),
+        ));
+        let first = state.apply(&event(
+            EventKind::AgentFailed,
+            Some("codex"),
+            serde_json::json!({ "error": "timed out (hard)" }),
+        ));
+        let second = state.apply(&event(
+            EventKind::AgentFailed,
+            Some("codex"),
+            serde_json::json!({ "error": "timed out (hard)" }),
+        ));
+        assert_eq!(first.as_deref(), Some("[codex] failed: timed out (hard)"));
+        assert!(second.is_none());
+    }
+
+    #[test]
+    fn watch_state_finishes_on_terminal_run_event() {
+        let mut state = WatchState::default();
+        let line = state.apply(&event(EventKind::RunFailed, None, serde_json::json!({})));
+        assert_eq!(line.as_deref(), Some("Run failed"));
+        assert_eq!(state.finished, Some(RunStatus::Failed));
+    }
+
+    #[test]
+    fn event_sources_merge_run_and_agent_logs() {
+        use hydra_core::artifact::EventWriter;
+        use hydra_core::test_support::run_manifest;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let run_id = Uuid::new_v4();
+        let layout = RunLayout::new(tmp.path(), run_id);
+        // Opened before the run exists, as `--ci` does when it starts a race.
+        let mut sources = RunEventSources::open(&layout);
+        layout.create_dirs(&["claude"]).unwrap();
+        run_manifest(run_id, &["claude"])
+            .write_to(&layout.manifest_path())
+            .unwrap();
+
+        let mut run_writer = EventWriter::create(&layout.events_path()).unwrap();
+        let mut agent_writer = EventWriter::create(&layout.agent_events("claude")).unwrap();
+        run_writer
+            .write_event(&event(EventKind::RunStarted, None, serde_json::json!({})))
+            .unwrap();
+        agent_writer
+            .write_event(&event(
+                EventKind::AgentStdout,
+                Some("claude"),
+                serde_json::json!({ "line": "working" }),
+            ))
+            .unwrap();
+
+        let events = sources.poll().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::RunStarted);
+        assert_eq!(events[1].kind, EventKind::AgentStdout);
+        assert!(sources.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn truncate_flattens_and_shortens_output() {
+        assert_eq!(truncate("a\nb"), "a b");
+        let long = "x".repeat(MAX_OUTPUT_CHARS + 10);
+        let out = truncate(&long);
+        assert!(out.ends_with("..."));
+        assert_eq!(out.chars().count(), MAX_OUTPUT_CHARS + 3);
+    }
+
+    #[test]
+    fn format_duration_uses_minutes_and_seconds() {
+        assert_eq!(format_duration(Duration::from_secs(125)), "02:05");
+    }
+}
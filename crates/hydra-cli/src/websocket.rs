@@ -0,0 +1,214 @@
+//! The server side of RFC 6455, as much as `hydra serve` needs to push
+//! events: the opening handshake, unfragmented text frames out, and control
+//! frames in. Client data frames are read and discarded.
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest client frame accepted. Clients only send control frames, which
+/// are limited to 125 bytes anyway.
+const MAX_CLIENT_FRAME: u64 = 64 * 1024;
+
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// `Sec-WebSocket-Accept` for a client's `Sec-WebSocket-Key`.
+pub fn accept_key(client_key: &str) -> String {
+    let mut input = client_key.trim().as_bytes().to_vec();
+    input.extend_from_slice(ACCEPT_GUID.as_bytes());
+    base64(&sha1(&input))
+}
+
+/// A control frame received from the client.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClientFrame {
+    Ping(Vec<u8>),
+    Close,
+    /// Any data frame or unsolicited pong; ignored.
+    Other,
+}
+
+pub async fn write_text<W: AsyncWrite + Unpin>(out: &mut W, text: &str) -> std::io::Result<()> {
+    write_frame(out, OP_TEXT, text.as_bytes()).await
+}
+
+pub async fn write_pong<W: AsyncWrite + Unpin>(out: &mut W, payload: &[u8]) -> std::io::Result<()> {
+    write_frame(out, OP_PONG, payload).await
+}
+
+pub async fn write_close<W: AsyncWrite + Unpin>(out: &mut W) -> std::io::Result<()> {
+    // 1000: normal closure.
+    write_frame(out, OP_CLOSE, &1000u16.to_be_bytes()).await
+}
+
+async fn write_frame<W: AsyncWrite + Unpin>(
+    out: &mut W,
+    opcode: u8,
+    payload: &[u8],
+) -> std::io::Result<()> {
+    // Server frames are final and unmasked.
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    out.write_all(&frame).await?;
+    out.flush().await
+}
+
+/// Read one frame from the client. EOF counts as a close.
+pub async fn read_frame<R: AsyncRead + Unpin>(input: &mut R) -> std::io::Result<ClientFrame> {
+    let mut header = [0u8; 2];
+    match input.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(ClientFrame::Close),
+        Err(e) => return Err(e),
+    }
+    let opcode = header[0] & 0x0F;
+    let masked = header[1] & 0x80 != 0;
+    let len = match header[1] & 0x7F {
+        126 => u64::from(input.read_u16().await?),
+        127 => input.read_u64().await?,
+        len => u64::from(len),
+    };
+    if len > MAX_CLIENT_FRAME {
+        return Err(std::io::Error::other("websocket frame too large"));
+    }
+
+    let mut mask = [0u8; 4];
+    if masked {
+        input.read_exact(&mut mask).await?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    input.read_exact(&mut payload).await?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+
+    Ok(match opcode {
+        OP_PING => ClientFrame::Ping(payload),
+        OP_CLOSE => ClientFrame::Close,
+        _ => ClientFrame::Other,
+    })
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut out = [0u8; 20];
+    for (chunk, word) in out.chunks_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_matches_rfc_example() {
+        // RFC 6455, section 1.3.
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+    }
+
+    #[tokio::test]
+    async fn frames_roundtrip_through_a_masked_client() {
+        let mut out = Vec::new();
+        write_text(&mut out, &"x".repeat(300)).await.unwrap();
+        assert_eq!(&out[..4], &[0x81, 126, 0x01, 0x2C]);
+
+        // A masked ping as a browser would send it.
+        let mask = [1u8, 2, 3, 4];
+        let mut ping = vec![0x89, 0x80 | 2, 1, 2, 3, 4];
+        ping.extend(b"hi".iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+        ping.extend([0x88, 0x80, 0, 0, 0, 0]);
+        let mut input = ping.as_slice();
+        assert_eq!(
+            read_frame(&mut input).await.unwrap(),
+            ClientFrame::Ping(b"hi".to_vec())
+        );
+        assert_eq!(read_frame(&mut input).await.unwrap(), ClientFrame::Close);
+        assert_eq!(read_frame(&mut input).await.unwrap(), ClientFrame::Close);
+    }
+}
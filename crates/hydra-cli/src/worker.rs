@@ -0,0 +1,585 @@
+//! `hydra worker`: run races sent by `hydra race --worker` on another
+//! machine, so agents use this machine's CPUs while the sender only
+//! controls the race and collects its artifacts.
+//!
+//! Each controller repository gets a git repository under
+//! `<dir>/repos/<name>`. The controller pushes the race's base commit as a
+//! git bundle, uploads its `hydra.toml`, then drives the race through the
+//! `hydra serve` routes nested under `/v1/repos/{name}`. Once the run has
+//! settled it downloads the run directory as a tar archive.
+//!
+//! Every endpoint except `GET /v1/health` needs `Authorization: Bearer
+//! <token>`.
+//!
+//! | Method | Path                                   |                                       |
+//! |--------|----------------------------------------|---------------------------------------|
+//! | GET    | `/v1/health`                           | liveness and version                  |
+//! | GET    | `/v1/repos/{name}`                     | last synced commit                    |
+//! | POST   | `/v1/repos/{name}/sync`                | git bundle of `refs/hydra/dispatch`   |
+//! | PUT    | `/v1/repos/{name}/config`              | `hydra.toml` for races (empty: defaults) |
+//! | GET    | `/v1/repos/{name}/runs/{id}/archive`   | tar of a settled run's artifacts      |
+//! | any    | `/v1/repos/{name}/...`                 | the `hydra serve` route under `/v1/`  |
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::process::Command;
+use uuid::Uuid;
+
+use hydra_core::artifact::{ArtifactError, RepoLock};
+use hydra_core::config::parse_config;
+
+use crate::serve::{
+    constant_time_eq, read_request, ApiError, Request, Response, Server, MAX_REQUEST_BODY,
+};
+use crate::watch::manifest_is_settled;
+
+pub const DEFAULT_PORT: u16 = 7701;
+
+/// Token used when `--token` is not given, and the one `hydra race
+/// --worker` sends.
+pub(crate) const TOKEN_ENV: &str = "HYDRA_WORKER_TOKEN";
+
+/// Ref a controller's bundle carries the base commit under.
+pub(crate) const BUNDLE_REF: &str = "refs/hydra/dispatch";
+
+/// Where the worker keeps the last synced commit.
+const BASE_REF: &str = "refs/hydra/base";
+
+/// The controller's `hydra.toml`, inside the repository's `.hydra`.
+const CONFIG_FILE: &str = "controller.toml";
+
+const MAX_BUNDLE: usize = 1024 * 1024 * 1024;
+
+pub struct WorkerOpts {
+    pub listen: SocketAddr,
+    pub token: Option<String>,
+    pub dir: Option<PathBuf>,
+}
+
+pub async fn run_worker(opts: WorkerOpts) -> Result<()> {
+    let dir = match opts.dir {
+        Some(dir) => dir,
+        None => default_dir()?,
+    };
+    std::fs::create_dir_all(dir.join("repos"))
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let configured = opts
+        .token
+        .or_else(|| std::env::var(TOKEN_ENV).ok())
+        .filter(|token| !token.is_empty());
+    let generated = configured.is_none();
+    let token = configured.unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+
+    let listener = TcpListener::bind(opts.listen)
+        .await
+        .with_context(|| format!("failed to listen on {}", opts.listen))?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        tracing::warn!(%addr, "worker reachable from other machines; anyone with the token can run agents");
+    }
+
+    eprintln!("Hydra worker listening on http://{addr}");
+    eprintln!("  repositories: {}", dir.join("repos").display());
+    if generated {
+        eprintln!("  token: {token}");
+        eprintln!("  (set {TOKEN_ENV} or pass --token to choose one)");
+    }
+
+    let worker = Arc::new(Worker::new(dir, token));
+    tokio::select! {
+        result = Arc::clone(&worker).serve(listener) => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    worker.shutdown().await;
+    Ok(())
+}
+
+/// `$XDG_DATA_HOME/hydra/worker` or `~/.local/share/hydra/worker`.
+fn default_dir() -> Result<PathBuf> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|v| !v.is_empty())
+                .map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .context("cannot locate the worker directory: set HOME or pass --dir")?;
+    Ok(data_dir.join("hydra").join("worker"))
+}
+
+pub(crate) struct Worker {
+    dir: PathBuf,
+    token: String,
+    /// One API server per synced repository, created on first use.
+    repos: Mutex<HashMap<String, Arc<Server>>>,
+    /// Syncs fetch and check out one at a time.
+    sync_lock: tokio::sync::Mutex<()>,
+}
+
+impl Worker {
+    pub(crate) fn new(dir: PathBuf, token: String) -> Self {
+        Self {
+            dir,
+            token,
+            repos: Mutex::new(HashMap::new()),
+            sync_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub(crate) async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let worker = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = worker.handle(stream).await {
+                    tracing::debug!(%peer, error = %e, "worker connection ended");
+                }
+            });
+        }
+    }
+
+    /// Cancel running races in every repository and wait for their cleanup.
+    async fn shutdown(&self) {
+        let servers: Vec<Arc<Server>> = self.lock().values().cloned().collect();
+        for server in servers {
+            server.shutdown().await;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<Server>>> {
+        self.repos.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn handle(self: Arc<Self>, mut stream: TcpStream) -> std::io::Result<()> {
+        // Only an authorized sync may send a bundle-sized body.
+        let max_body = |request: &Request| {
+            if request.method == "POST"
+                && request.path.ends_with("/sync")
+                && self.authorized(request)
+            {
+                MAX_BUNDLE
+            } else {
+                MAX_REQUEST_BODY
+            }
+        };
+        let request = match read_request(&mut stream, max_body).await {
+            Ok(request) => request,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return ApiError::bad_request(e)
+                    .into_response()
+                    .write_to(&mut stream)
+                    .await;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let public = request.method == "GET" && request.path == "/v1/health";
+        if !public && !self.authorized(&request) {
+            return ApiError::new(401, "missing or invalid token")
+                .into_response()
+                .write_to(&mut stream)
+                .await;
+        }
+        let response = self
+            .route(request)
+            .await
+            .unwrap_or_else(ApiError::into_response);
+        response.write_to(&mut stream).await
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        request
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given.trim(), &self.token))
+    }
+
+    async fn route(&self, mut request: Request) -> Result<Response, ApiError> {
+        let path = std::mem::take(&mut request.path);
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["v1", "health"]) => Ok(Response::json(
+                200,
+                &serde_json::json!({
+                    "status": "ok",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "role": "worker",
+                }),
+            )),
+            ("GET", ["v1", "repos", name]) => self.repo_info(name).await,
+            ("POST", ["v1", "repos", name, "sync"]) => self.sync(name, &request).await,
+            ("PUT", ["v1", "repos", name, "config"]) => self.put_config(name, &request),
+            ("GET", ["v1", "repos", name, "runs", run, "archive"]) => self.archive(name, run).await,
+            (_, ["v1", "repos", name, rest @ ..]) if !rest.is_empty() => {
+                let server = self.server(name)?;
+                request.path = format!("/v1/{}", rest.join("/"));
+                server.route(&request).await
+            }
+            _ => Err(ApiError::not_found(format!(
+                "no route for {} {path}",
+                request.method
+            ))),
+        }
+    }
+
+    fn repo_dir(&self, name: &str) -> Result<PathBuf, ApiError> {
+        if !valid_repo_name(name) {
+            return Err(ApiError::bad_request(format!(
+                "invalid repository name '{name}'"
+            )));
+        }
+        Ok(self.dir.join("repos").join(name))
+    }
+
+    /// The API server for a repository that has been synced.
+    fn server(&self, name: &str) -> Result<Arc<Server>, ApiError> {
+        let dir = self.repo_dir(name)?;
+        let mut repos = self.lock();
+        if let Some(server) = repos.get(name) {
+            return Ok(Arc::clone(server));
+        }
+        if !dir.join(".git").exists() {
+            return Err(ApiError::not_found(format!(
+                "repository '{name}' has not been synced"
+            )));
+        }
+        let server =
+            Arc::new(Server::new(dir.clone(), self.token.clone()).with_config(config_path(&dir)));
+        repos.insert(name.to_string(), Arc::clone(&server));
+        Ok(server)
+    }
+
+    async fn repo_info(&self, name: &str) -> Result<Response, ApiError> {
+        let dir = self.repo_dir(name)?;
+        let commit = if dir.join(".git").exists() {
+            synced_commit(&dir).await
+        } else {
+            None
+        };
+        Ok(Response::json(
+            200,
+            &serde_json::json!({ "name": name, "commit": commit }),
+        ))
+    }
+
+    /// Fetch the bundle's base commit and check it out, holding the
+    /// repository's `.hydra/lock` so a race cannot set up meanwhile.
+    async fn sync(&self, name: &str, request: &Request) -> Result<Response, ApiError> {
+        let dir = self.repo_dir(name)?;
+        if request.body.is_empty() {
+            return Err(ApiError::bad_request("sync needs a git bundle body"));
+        }
+
+        let _guard = self.sync_lock.lock().await;
+        let _repo_lock = match RepoLock::try_acquire(&dir.join(".hydra"), Uuid::new_v4()) {
+            Ok(lock) => lock,
+            Err(ArtifactError::Locked { holder }) => {
+                return Err(ApiError::new(
+                    409,
+                    format!("repository is locked by {holder}; retry once it settles"),
+                ));
+            }
+            Err(e) => return Err(ApiError::internal(e)),
+        };
+        if !dir.join(".git").exists() {
+            std::fs::create_dir_all(&dir).map_err(ApiError::internal)?;
+            git(&dir, &["init", "--quiet"]).await?;
+        }
+        let bundle = dir
+            .join(".git")
+            .join(format!("hydra-sync-{}.bundle", Uuid::new_v4().simple()));
+        std::fs::write(&bundle, &request.body).map_err(ApiError::internal)?;
+        let refspec = format!("+{BUNDLE_REF}:{BASE_REF}");
+        let fetched = git(
+            &dir,
+            &[
+                "fetch",
+                "--quiet",
+                "--no-tags",
+                &bundle.display().to_string(),
+                &refspec,
+            ],
+        )
+        .await;
+        let _ = std::fs::remove_file(&bundle);
+        fetched?;
+
+        let commit = synced_commit(&dir)
+            .await
+            .ok_or_else(|| ApiError::internal("bundle fetched but no commit recorded"))?;
+        git(
+            &dir,
+            &["checkout", "--quiet", "--force", "--detach", &commit],
+        )
+        .await?;
+        tracing::info!(repo = name, %commit, "synced repository");
+        Ok(Response::json(
+            200,
+            &serde_json::json!({ "name": name, "commit": commit }),
+        ))
+    }
+
+    fn put_config(&self, name: &str, request: &Request) -> Result<Response, ApiError> {
+        let dir = self.repo_dir(name)?;
+        if !dir.join(".git").exists() {
+            return Err(ApiError::not_found(format!(
+                "repository '{name}' has not been synced"
+            )));
+        }
+        let path = config_path(&dir);
+        if request.body.is_empty() {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(ApiError::internal(e)),
+            }
+        } else {
+            let text = std::str::from_utf8(&request.body)
+                .map_err(|_| ApiError::bad_request("config is not UTF-8"))?;
+            parse_config(text)
+                .map_err(|e| ApiError::bad_request(format!("invalid config: {e}")))?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(ApiError::internal)?;
+            }
+            std::fs::write(&path, text).map_err(ApiError::internal)?;
+        }
+        Ok(Response::json(
+            200,
+            &serde_json::json!({ "name": name, "config": !request.body.is_empty() }),
+        ))
+    }
+
+    async fn archive(&self, name: &str, run: &str) -> Result<Response, ApiError> {
+        let layout = self.server(name)?.layout(run)?;
+        if !manifest_is_settled(&layout) {
+            return Err(ApiError::new(409, format!("run {run} has not finished")));
+        }
+        let runs_dir = layout
+            .base_dir()
+            .parent()
+            .ok_or_else(|| ApiError::internal("run directory has no parent"))?;
+        let output = Command::new("tar")
+            .arg("-C")
+            .arg(runs_dir)
+            .args(["-cf", "-"])
+            .arg(layout.run_id().to_string())
+            .output()
+            .await
+            .map_err(|e| ApiError::internal(format!("failed to run tar: {e}")))?;
+        if !output.status.success() {
+            return Err(ApiError::internal(format!(
+                "tar failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(Response {
+            status: 200,
+            content_type: "application/x-tar",
+            body: output.stdout,
+        })
+    }
+}
+
+/// Names are a single path component controllers derive from their
+/// repository's directory name.
+pub(crate) fn valid_repo_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn config_path(repo_dir: &Path) -> PathBuf {
+    repo_dir.join(".hydra").join(CONFIG_FILE)
+}
+
+async fn synced_commit(repo_dir: &Path) -> Option<String> {
+    git(
+        repo_dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{BASE_REF}^{{commit}}"),
+        ],
+    )
+    .await
+    .ok()
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<String, ApiError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| ApiError::internal(format!("failed to run git: {e}")))?;
+    if !output.status.success() {
+        return Err(ApiError::new(
+            409,
+            format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const TOKEN: &str = "worker-token";
+
+    fn git_in(dir: &Path, args: &[&str]) -> Vec<u8> {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        output.stdout
+    }
+
+    async fn send(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> (u16, Vec<u8>) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let head = format!(
+            "{method} {path} HTTP/1.1\r\nAuthorization: Bearer {TOKEN}\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(body).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let status = std::str::from_utf8(&response[9..12])
+            .unwrap()
+            .parse()
+            .unwrap();
+        let start = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        (status, response[start..].to_vec())
+    }
+
+    #[tokio::test]
+    async fn sync_checks_out_the_bundled_commit_and_proxies_serve_routes() {
+        let source = tempfile::TempDir::new().unwrap();
+        git_in(source.path(), &["init", "--quiet"]);
+        std::fs::write(source.path().join("a.txt"), "a\n").unwrap();
+        git_in(source.path(), &["add", "."]);
+        git_in(source.path(), &["commit", "--quiet", "-m", "init"]);
+        git_in(source.path(), &["update-ref", BUNDLE_REF, "HEAD"]);
+        let bundle = git_in(
+            source.path(),
+            &["bundle", "create", "--quiet", "-", BUNDLE_REF],
+        );
+        let head = String::from_utf8(git_in(source.path(), &["rev-parse", "HEAD"])).unwrap();
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let worker = Arc::new(Worker::new(dir.path().to_path_buf(), TOKEN.to_string()));
+        tokio::spawn(worker.serve(listener));
+
+        let (status, body) = send(addr, "GET", "/v1/repos/app", b"").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["commit"],
+            serde_json::Value::Null
+        );
+        assert_eq!(send(addr, "GET", "/v1/repos/app/runs", b"").await.0, 404);
+        assert_eq!(send(addr, "GET", "/v1/repos/..", b"").await.0, 400);
+
+        let (status, body) = send(addr, "POST", "/v1/repos/app/sync", &bundle).await;
+        assert_eq!(status, 200, "{}", String::from_utf8_lossy(&body));
+        let synced: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(synced["commit"], head.trim());
+        let checkout = dir.path().join("repos").join("app");
+        assert_eq!(
+            std::fs::read_to_string(checkout.join("a.txt")).unwrap(),
+            "a\n"
+        );
+
+        assert_eq!(
+            send(
+                addr,
+                "PUT",
+                "/v1/repos/app/config",
+                b"[race]\nnot_a_key = 1\n"
+            )
+            .await
+            .0,
+            400
+        );
+        assert_eq!(
+            send(
+                addr,
+                "PUT",
+                "/v1/repos/app/config",
+                b"[race]\ndefault_agents = [\"claude\"]\n"
+            )
+            .await
+            .0,
+            200
+        );
+        assert!(config_path(&checkout).is_file());
+
+        let (status, body) = send(addr, "GET", "/v1/repos/app/runs", b"").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["runs"],
+            serde_json::json!([])
+        );
+        let missing = format!("/v1/repos/app/runs/{}/archive", Uuid::new_v4());
+        assert_eq!(send(addr, "GET", &missing, b"").await.0, 404);
+    }
+
+    #[tokio::test]
+    async fn sync_is_refused_while_a_race_holds_the_repository_lock() {
+        let source = tempfile::TempDir::new().unwrap();
+        git_in(source.path(), &["init", "--quiet"]);
+        std::fs::write(source.path().join("a.txt"), "a\n").unwrap();
+        git_in(source.path(), &["add", "."]);
+        git_in(source.path(), &["commit", "--quiet", "-m", "init"]);
+        git_in(source.path(), &["update-ref", BUNDLE_REF, "HEAD"]);
+        let bundle = git_in(
+            source.path(),
+            &["bundle", "create", "--quiet", "-", BUNDLE_REF],
+        );
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let worker = Arc::new(Worker::new(dir.path().to_path_buf(), TOKEN.to_string()));
+        tokio::spawn(worker.serve(listener));
+
+        let hydra_root = dir.path().join("repos").join("app").join(".hydra");
+        let run_id = Uuid::new_v4();
+        let race = RepoLock::try_acquire(&hydra_root, run_id).unwrap();
+        let (status, body) = send(addr, "POST", "/v1/repos/app/sync", &bundle).await;
+        assert_eq!(status, 409);
+        assert!(String::from_utf8_lossy(&body).contains(&run_id.to_string()));
+        assert!(!dir.path().join("repos").join("app").join("a.txt").exists());
+        assert_eq!(
+            RepoLock::current_holder(&hydra_root).unwrap().run_id,
+            run_id
+        );
+
+        drop(race);
+        let (status, body) = send(addr, "POST", "/v1/repos/app/sync", &bundle).await;
+        assert_eq!(status, 200, "{}", String::from_utf8_lossy(&body));
+        assert!(RepoLock::current_holder(&hydra_root).is_none());
+    }
+}
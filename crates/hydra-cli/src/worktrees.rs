@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use serde::Serialize;
+use uuid::Uuid;
+
+use hydra_core::artifact::{RunLayout, RunManifest, RunStatus};
+use hydra_core::worktree::{WorktreeEntry, WorktreeInfo, WorktreeService};
+
+use crate::run::discover_repo_root;
+use crate::{config, output};
+
+#[derive(Subcommand)]
+pub enum WorktreesCommand {
+    /// List agent worktrees with their run, agent, and disk usage
+    List {
+        /// Include worktrees not created by Hydra (e.g. the main checkout)
+        #[arg(long)]
+        all: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove an agent worktree by path, or every worktree of a run by run ID
+    Rm {
+        /// Worktree path or run ID
+        target: String,
+
+        /// Remove even with uncommitted changes or while the run is active
+        #[arg(long)]
+        force: bool,
+
+        /// Also delete the agent branch (discards work that was not merged)
+        #[arg(long)]
+        delete_branch: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct WorktreeListing {
+    path: PathBuf,
+    branch: String,
+    head_commit: String,
+    run_id: Option<Uuid>,
+    agent_key: Option<String>,
+    /// Status from the run manifest; `None` when no run artifacts exist.
+    run_status: Option<RunStatus>,
+    size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct RemovalReport {
+    path: PathBuf,
+    branch: String,
+    branch_deleted: bool,
+    error: Option<String>,
+}
+
+pub async fn run_command(command: WorktreesCommand) -> Result<bool> {
+    let repo_root = discover_repo_root()?;
+    let config = config::load()?;
+    let service =
+        WorktreeService::new(repo_root.clone(), repo_root.join(&config.worktree.base_dir));
+    let hydra_root = repo_root.join(".hydra");
+
+    match command {
+        WorktreesCommand::List { all, json } => {
+            list(&service, &hydra_root, all, json).await?;
+            Ok(true)
+        }
+        WorktreesCommand::Rm {
+            target,
+            force,
+            delete_branch,
+            json,
+        } => remove(&service, &hydra_root, &target, force, delete_branch, json).await,
+    }
+}
+
+async fn list(service: &WorktreeService, hydra_root: &Path, all: bool, json: bool) -> Result<()> {
+    let entries = service.list().await.context("failed to list worktrees")?;
+    let listings: Vec<WorktreeListing> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let info = entry.agent_info();
+            if info.is_none() && !all {
+                return None;
+            }
+            let status = info
+                .as_ref()
+                .and_then(|info| run_status(hydra_root, info.run_id));
+            Some(WorktreeListing {
+                size_bytes: dir_size(&entry.path),
+                run_id: info.as_ref().map(|info| info.run_id),
+                agent_key: info.map(|info| info.agent_key),
+                run_status: status,
+                path: entry.path,
+                branch: entry.branch,
+                head_commit: entry.head_commit,
+            })
+        })
+        .collect();
+
+    output::emit(&listings, json)?;
+    if json {
+        return Ok(());
+    }
+    if listings.is_empty() {
+        println!("No agent worktrees.");
+        return Ok(());
+    }
+
+    println!(
+        "  {:<36}  {:<12} {:<10} {:>9}  Path",
+        "Run ID", "Agent", "Run", "Size"
+    );
+    for listing in &listings {
+        println!(
+            "  {:<36}  {:<12} {:<10} {:>9}  {}",
+            listing
+                .run_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            listing.agent_key.as_deref().unwrap_or("-"),
+            listing
+                .run_status
+                .as_ref()
+                .map(|status| format!("{status:?}"))
+                .unwrap_or_else(|| "-".to_string()),
+            format_size(listing.size_bytes),
+            listing.path.display()
+        );
+    }
+    let total: u64 = listings.iter().map(|l| l.size_bytes).sum();
+    println!();
+    println!(
+        "  {} worktree(s), {} total",
+        listings.len(),
+        format_size(total)
+    );
+    Ok(())
+}
+
+async fn remove(
+    service: &WorktreeService,
+    hydra_root: &Path,
+    target: &str,
+    force: bool,
+    delete_branch: bool,
+    json: bool,
+) -> Result<bool> {
+    let entries = service.list().await.context("failed to list worktrees")?;
+    let selected = select_targets(&entries, target)?;
+
+    if !force {
+        for info in &selected {
+            if run_status(hydra_root, info.run_id) == Some(RunStatus::Running) {
+                bail!(
+                    "run {} is still running; cancel it first or pass --force",
+                    info.run_id
+                );
+            }
+        }
+    }
+
+    let mut reports = Vec::new();
+    for info in selected {
+        let result = if delete_branch {
+            service.force_cleanup(&info).await
+        } else {
+            service.remove(&info.path, force).await
+        };
+        reports.push(RemovalReport {
+            path: info.path,
+            branch: info.branch,
+            branch_deleted: delete_branch && result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+    let all_removed = reports.iter().all(|r| r.error.is_none());
+
+    output::emit(&serde_json::json!({ "removed": reports }), json)?;
+    if json {
+        return Ok(all_removed);
+    }
+    for report in &reports {
+        match &report.error {
+            None if report.branch_deleted => println!(
+                "Removed {} and deleted branch {}",
+                report.path.display(),
+                report.branch
+            ),
+            None => println!(
+                "Removed {} (branch {} kept)",
+                report.path.display(),
+                report.branch
+            ),
+            Some(error) => eprintln!("Failed to remove {}: {error}", report.path.display()),
+        }
+    }
+    Ok(all_removed)
+}
+
+/// Resolve `target` to Hydra agent worktrees: every worktree of a run when it
+/// is a run ID, otherwise the worktree at that path.
+fn select_targets(entries: &[WorktreeEntry], target: &str) -> Result<Vec<WorktreeInfo>> {
+    if let Ok(run_id) = Uuid::parse_str(target) {
+        let selected: Vec<_> = entries
+            .iter()
+            .filter_map(WorktreeEntry::agent_info)
+            .filter(|info| info.run_id == run_id)
+            .collect();
+        if selected.is_empty() {
+            bail!("no worktrees found for run {run_id}");
+        }
+        return Ok(selected);
+    }
+
+    let path = Path::new(target)
+        .canonicalize()
+        .with_context(|| format!("worktree '{target}' not found"))?;
+    let entry = entries
+        .iter()
+        .find(|e| e.path.canonicalize().is_ok_and(|p| p == path))
+        .with_context(|| format!("'{target}' is not a git worktree of this repository"))?;
+    let info = entry
+        .agent_info()
+        .with_context(|| format!("'{target}' is not a Hydra agent worktree"))?;
+    Ok(vec![info])
+}
+
+fn run_status(hydra_root: &Path, run_id: Uuid) -> Option<RunStatus> {
+    let layout = RunLayout::new(hydra_root, run_id);
+    RunManifest::read_from(&layout.manifest_path())
+        .ok()
+        .map(|manifest| manifest.status)
+}
+
+/// Apparent size of a directory tree; symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &Path, branch: &str) -> WorktreeEntry {
+        WorktreeEntry {
+            path: path.to_path_buf(),
+            branch: branch.to_string(),
+            head_commit: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn dir_size_sums_nested_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(tmp.path().join("sub")).unwrap();
+        std::fs::write(tmp.path().join("a"), [0u8; 10]).unwrap();
+        std::fs::write(tmp.path().join("sub").join("b"), [0u8; 5]).unwrap();
+        assert_eq!(dir_size(tmp.path()), 15);
+    }
+
+    #[test]
+    fn selects_by_run_id_or_agent_worktree_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let run_id = Uuid::new_v4();
+        let claude = tmp.path().join("claude");
+        let codex = tmp.path().join("codex");
+        std::fs::create_dir(&claude).unwrap();
+        std::fs::create_dir(&codex).unwrap();
+        let entries = vec![
+            entry(tmp.path(), "main"),
+            entry(&claude, &format!("hydra/{run_id}/agent/claude")),
+            entry(&codex, &format!("hydra/{run_id}/agent/codex")),
+        ];
+
+        assert_eq!(
+            select_targets(&entries, &run_id.to_string()).unwrap().len(),
+            2
+        );
+        let by_path = select_targets(&entries, &claude.to_string_lossy()).unwrap();
+        assert_eq!(by_path.len(), 1);
+        assert_eq!(by_path[0].path, claude);
+
+        assert!(select_targets(&entries, &Uuid::new_v4().to_string()).is_err());
+        assert!(select_targets(&entries, &tmp.path().to_string_lossy()).is_err());
+    }
+}
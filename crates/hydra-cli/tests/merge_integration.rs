@@ -30,6 +30,8 @@ fn run_hydra(dir: &Path, args: &[&str]) -> Output {
     Command::new(HYDRA_BIN)
         .args(args)
         .current_dir(dir)
+        // Assertions below match the English messages.
+        .env("LC_ALL", "C")
         .output()
         .expect("failed to execute hydra")
 }
@@ -222,6 +224,72 @@ fn dry_run_clean_merge_writes_report_and_cleans_merge_state() {
     assert!(!merge_head_exists(&repo), "merge state should be cleaned");
 }
 
+#[test]
+fn output_flag_writes_json_result_alongside_human_output() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_test_repo(&repo);
+
+    let run_id = Uuid::new_v4();
+    let branch = create_agent_branch(
+        &repo,
+        "main",
+        run_id,
+        "claude",
+        "claude_output.txt",
+        "claude was here\n",
+    );
+    write_run_artifacts(
+        &repo,
+        run_id,
+        "main",
+        &[AgentSpec {
+            key: "claude".to_string(),
+            branch,
+            mergeable: true,
+            composite: 90.0,
+        }],
+    );
+
+    let result_path = tmp.path().join("results").join("merge.json");
+    let run_id_arg = run_id.to_string();
+    let result_arg = result_path.display().to_string();
+    let out = run_hydra(
+        &repo,
+        &[
+            "merge",
+            "--run-id",
+            &run_id_arg,
+            "--agent",
+            "claude",
+            "--dry-run",
+            "--output",
+            &result_arg,
+        ],
+    );
+
+    assert!(out.status.success(), "{}", output_text(&out));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("clean merge"));
+
+    let result: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&result_path).unwrap()).unwrap();
+    assert_eq!(result["agent"], "claude");
+    assert_eq!(result["dry_run"], true);
+    assert_eq!(result["success"], true);
+}
+
+#[test]
+fn usage_errors_exit_with_usage_code() {
+    let tmp = TempDir::new().unwrap();
+    let out = run_hydra(tmp.path(), &["merge", "--no-such-flag"]);
+    assert_eq!(out.status.code(), Some(64), "{}", output_text(&out));
+
+    let help = run_hydra(tmp.path(), &["--help"]);
+    assert!(help.status.success());
+    assert!(String::from_utf8_lossy(&help.stdout).contains("Exit codes:"));
+}
+
 #[test]
 fn dry_run_conflict_reports_failure_and_cleans_merge_state() {
     let tmp = TempDir::new().unwrap();
@@ -678,6 +746,62 @@ fn merge_with_confirm_succeeds() {
     assert!(repo.join("claude_output.txt").exists());
 }
 
+#[test]
+fn merge_only_takes_selected_paths_from_branch() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_test_repo(&repo);
+
+    let run_id = Uuid::new_v4();
+    let branch = format!("hydra/{run_id}/agent/claude");
+    run_git_ok(&repo, &["checkout", "-b", &branch]);
+    std::fs::write(repo.join("keep.txt"), "approved\n").unwrap();
+    std::fs::write(repo.join("skip.txt"), "rejected\n").unwrap();
+    run_git_ok(&repo, &["add", "keep.txt", "skip.txt"]);
+    run_git_ok(&repo, &["commit", "-m", "claude changes"]);
+    run_git_ok(&repo, &["checkout", "main"]);
+    write_run_artifacts(
+        &repo,
+        run_id,
+        "main",
+        &[AgentSpec {
+            key: "claude".to_string(),
+            branch,
+            mergeable: true,
+            composite: 90.0,
+        }],
+    );
+
+    let run_id_arg = run_id.to_string();
+    let out = run_hydra(
+        &repo,
+        &[
+            "merge",
+            "--run-id",
+            &run_id_arg,
+            "--agent",
+            "claude",
+            "--only",
+            "keep.txt",
+            "--confirm",
+        ],
+    );
+
+    assert!(out.status.success(), "{}", output_text(&out));
+    assert!(repo.join("keep.txt").exists());
+    assert!(!repo.join("skip.txt").exists());
+    assert!(!merge_head_exists(&repo));
+    // A partial merge is a plain commit, not a merge of the whole branch.
+    let parents = run_git_ok(&repo, &["rev-list", "--parents", "-n", "1", "HEAD"]);
+    assert_eq!(
+        String::from_utf8_lossy(&parents.stdout)
+            .split_whitespace()
+            .count(),
+        2
+    );
+}
+
 #[test]
 fn pick_winner_prefers_highest_mergeable_agent() {
     let tmp = TempDir::new().unwrap();
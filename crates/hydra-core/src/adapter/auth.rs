@@ -0,0 +1,245 @@
+//! Credential checks behind [`AgentAdapter::auth`](super::AgentAdapter::auth).
+//!
+//! These only look at environment variables and the CLIs' own credential
+//! files; nothing is sent to a provider, so a revoked key still reads as
+//! authenticated. The point is to catch the common case — never logged in,
+//! or a login that lapsed — before a race rather than halfway through it.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::types::{AuthResult, AuthStatus};
+
+const CLAUDE_LOGIN: &str = "run `claude` and complete `/login`, or set ANTHROPIC_API_KEY";
+const CODEX_LOGIN: &str = "run `codex login`, or set OPENAI_API_KEY";
+const CURSOR_LOGIN: &str = "run `cursor-agent login`, or set CURSOR_API_KEY";
+
+pub(crate) fn claude_auth() -> AuthResult {
+    if let Some(var) = env_credential(&[
+        "ANTHROPIC_API_KEY",
+        "CLAUDE_CODE_OAUTH_TOKEN",
+        "ANTHROPIC_AUTH_TOKEN",
+    ]) {
+        return AuthResult::authenticated(var);
+    }
+    if env_credential(&["CLAUDE_CODE_USE_BEDROCK", "CLAUDE_CODE_USE_VERTEX"]).is_some() {
+        return AuthResult::unknown("cloud provider credentials are not checked");
+    }
+    match config_dir("CLAUDE_CONFIG_DIR", ".claude") {
+        Some(dir) => claude_credentials(&dir.join(".credentials.json"), Utc::now()),
+        None => missing(
+            "no API key and no home directory to look for a login",
+            CLAUDE_LOGIN,
+        ),
+    }
+}
+
+pub(crate) fn codex_auth() -> AuthResult {
+    if let Some(var) = env_credential(&["OPENAI_API_KEY", "CODEX_API_KEY"]) {
+        return AuthResult::authenticated(var);
+    }
+    match config_dir("CODEX_HOME", ".codex") {
+        Some(dir) => codex_credentials(&dir.join("auth.json")),
+        None => missing(
+            "no API key and no home directory to look for a login",
+            CODEX_LOGIN,
+        ),
+    }
+}
+
+pub(crate) fn cursor_auth() -> AuthResult {
+    match env_credential(&["CURSOR_API_KEY"]) {
+        Some(var) => AuthResult::authenticated(var),
+        None => AuthResult::unknown("cursor-agent keeps its login where hydra cannot check it")
+            .with_remediation(CURSOR_LOGIN),
+    }
+}
+
+/// Claude Code's OAuth login. An expired access token is fine as long as
+/// there is a refresh token to renew it with.
+fn claude_credentials(path: &Path, now: DateTime<Utc>) -> AuthResult {
+    let Some(creds) = read_json(path) else {
+        if cfg!(target_os = "macos") {
+            return AuthResult::unknown("the login may be stored in the macOS keychain")
+                .with_remediation(CLAUDE_LOGIN);
+        }
+        return missing(
+            format!("no API key and no login at {}", path.display()),
+            CLAUDE_LOGIN,
+        );
+    };
+
+    let Some(oauth) = creds
+        .get("claudeAiOauth")
+        .filter(|o| has_string(o, "accessToken"))
+    else {
+        return missing(format!("{} holds no login", path.display()), CLAUDE_LOGIN);
+    };
+
+    let expires_at = oauth
+        .get("expiresAt")
+        .and_then(Value::as_i64)
+        .and_then(DateTime::from_timestamp_millis);
+    match expires_at {
+        Some(expires_at) if expires_at <= now && !has_string(oauth, "refreshToken") => AuthResult {
+            status: AuthStatus::Expired,
+            source: Some(path.display().to_string()),
+            detail: Some(format!(
+                "login expired at {}",
+                expires_at.format("%Y-%m-%d %H:%M UTC")
+            )),
+            remediation: Some(CLAUDE_LOGIN.to_string()),
+        },
+        _ => AuthResult::authenticated(path.display().to_string()),
+    }
+}
+
+/// Codex stores either an API key or ChatGPT login tokens in `auth.json`.
+fn codex_credentials(path: &Path) -> AuthResult {
+    let Some(auth) = read_json(path) else {
+        return missing(
+            format!("no API key and no login at {}", path.display()),
+            CODEX_LOGIN,
+        );
+    };
+
+    let has_key = has_string(&auth, "OPENAI_API_KEY");
+    let has_tokens = auth
+        .get("tokens")
+        .is_some_and(|t| has_string(t, "refresh_token") || has_string(t, "access_token"));
+    if has_key || has_tokens {
+        AuthResult::authenticated(path.display().to_string())
+    } else {
+        missing(
+            format!("{} holds no API key or login", path.display()),
+            CODEX_LOGIN,
+        )
+    }
+}
+
+fn missing(detail: impl Into<String>, login: &str) -> AuthResult {
+    AuthResult {
+        status: AuthStatus::Missing,
+        source: None,
+        detail: Some(detail.into()),
+        remediation: Some(login.to_string()),
+    }
+}
+
+/// First of `names` set to a non-empty value.
+fn env_credential(names: &[&'static str]) -> Option<&'static str> {
+    names
+        .iter()
+        .copied()
+        .find(|name| std::env::var_os(name).is_some_and(|v| !v.is_empty()))
+}
+
+/// `$override_var`, else `~/<default>`.
+fn config_dir(override_var: &str, default: &str) -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(override_var).filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|h| !h.is_empty())
+        .map(|home| PathBuf::from(home).join(default))
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let data = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn has_string(value: &Value, key: &str) -> bool {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .is_some_and(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, json: Value) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, json.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn claude_login_expires_only_without_refresh_token() {
+        let tmp = TempDir::new().unwrap();
+        let now = Utc::now();
+        let past = (now - chrono::Duration::hours(1)).timestamp_millis();
+        let future = (now + chrono::Duration::hours(1)).timestamp_millis();
+
+        let valid = write(
+            &tmp,
+            "valid.json",
+            serde_json::json!({ "claudeAiOauth": { "accessToken": "a", "expiresAt": future } }),
+        );
+        assert_eq!(
+            claude_credentials(&valid, now).status,
+            AuthStatus::Authenticated
+        );
+
+        let refreshable = write(
+            &tmp,
+            "refreshable.json",
+            serde_json::json!({ "claudeAiOauth": {
+                "accessToken": "a", "refreshToken": "r", "expiresAt": past
+            } }),
+        );
+        assert_eq!(
+            claude_credentials(&refreshable, now).status,
+            AuthStatus::Authenticated
+        );
+
+        let expired = write(
+            &tmp,
+            "expired.json",
+            serde_json::json!({ "claudeAiOauth": { "accessToken": "a", "expiresAt": past } }),
+        );
+        let result = claude_credentials(&expired, now);
+        assert_eq!(result.status, AuthStatus::Expired);
+        assert!(result.remediation.is_some());
+
+        let empty = write(&tmp, "empty.json", serde_json::json!({}));
+        assert_eq!(claude_credentials(&empty, now).status, AuthStatus::Missing);
+    }
+
+    #[test]
+    fn codex_accepts_api_key_or_login_tokens() {
+        let tmp = TempDir::new().unwrap();
+
+        let key = write(
+            &tmp,
+            "key.json",
+            serde_json::json!({ "OPENAI_API_KEY": "sk-test", "tokens": null }),
+        );
+        assert_eq!(codex_credentials(&key).status, AuthStatus::Authenticated);
+
+        let login = write(
+            &tmp,
+            "login.json",
+            serde_json::json!({ "OPENAI_API_KEY": null, "tokens": { "refresh_token": "r" } }),
+        );
+        assert_eq!(codex_credentials(&login).status, AuthStatus::Authenticated);
+
+        let logged_out = write(
+            &tmp,
+            "logged-out.json",
+            serde_json::json!({ "OPENAI_API_KEY": null, "tokens": null }),
+        );
+        let result = codex_credentials(&logged_out);
+        assert_eq!(result.status, AuthStatus::Missing);
+        assert_eq!(result.remediation.as_deref(), Some(CODEX_LOGIN));
+
+        let absent = codex_credentials(&tmp.path().join("absent.json"));
+        assert_eq!(absent.status, AuthStatus::Missing);
+    }
+}
@@ -264,6 +264,10 @@ impl AgentAdapter for ClaudeAdapter {
         }
     }
 
+    fn auth(&self) -> AuthResult {
+        super::auth::claude_auth()
+    }
+
     fn capabilities(&self) -> CapabilitySet {
         CapabilitySet {
             json_stream: CapabilityEntry::verified(true),
@@ -304,9 +308,15 @@ impl AgentAdapter for ClaudeAdapter {
             args,
             env: vec![],
             cwd: req.worktree_path.clone(),
+            ..Default::default()
         })
     }
 
+    /// Claude follows XML-tagged sections more reliably than Markdown.
+    fn render_task(&self, spec: &crate::task::TaskSpec) -> String {
+        spec.to_xml()
+    }
+
     fn parse_line(&self, line: &str) -> Option<AgentEvent> {
         Self::parse_stream_json_line(line)
     }
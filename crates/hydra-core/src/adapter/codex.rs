@@ -400,6 +400,10 @@ impl AgentAdapter for CodexAdapter {
         }
     }
 
+    fn auth(&self) -> AuthResult {
+        super::auth::codex_auth()
+    }
+
     fn capabilities(&self) -> CapabilitySet {
         CapabilitySet {
             json_stream: CapabilityEntry::verified(true),
@@ -432,6 +436,7 @@ impl AgentAdapter for CodexAdapter {
             args,
             env: vec![],
             cwd: req.worktree_path.clone(),
+            ..Default::default()
         })
     }
 
@@ -212,6 +212,10 @@ impl AgentAdapter for CursorAdapter {
         }
     }
 
+    fn auth(&self) -> AuthResult {
+        super::auth::cursor_auth()
+    }
+
     fn capabilities(&self) -> CapabilitySet {
         CapabilitySet {
             json_stream: CapabilityEntry::observed(true),
@@ -247,6 +251,7 @@ impl AgentAdapter for CursorAdapter {
             args,
             env: vec![],
             cwd: req.worktree_path.clone(),
+            ..Default::default()
         })
     }
 
@@ -0,0 +1,207 @@
+//! Launch helpers for interactive (PTY) sessions.
+//!
+//! Interactive sessions invoke the agent CLI directly, without the
+//! race-mode flags the adapters add in `build_command`.
+
+use std::path::PathBuf;
+
+use crate::config::AdaptersConfig;
+
+use super::resolve_binary;
+
+/// Resolve the binary for an interactive session, honouring the configured
+/// path for the adapter in `hydra.toml`.
+pub fn resolve_interactive_binary(adapter_key: &str, adapters: &AdaptersConfig) -> Option<PathBuf> {
+    let (configured, candidates): (Option<&str>, &[&str]) = match adapter_key {
+        "claude" => (adapters.claude.as_deref(), &["claude"]),
+        "codex" => (adapters.codex.as_deref(), &["codex"]),
+        "cursor-agent" => (adapters.cursor.as_deref(), &["cursor-agent", "cursor"]),
+        _ => (None, &[]),
+    };
+    resolve_binary(configured, candidates)
+}
+
+/// Whether the adapter exposes an explicit sandbox-bypass flag usable in
+/// interactive unsafe mode.
+pub fn supports_interactive_unsafe_mode(adapter_key: &str, supported_flags: &[String]) -> bool {
+    match adapter_key {
+        // Codex uses explicit dangerous bypass flag for unsafe mode.
+        "codex" => supported_flags
+            .iter()
+            .any(|f| f == "--dangerously-bypass-approvals-and-sandbox"),
+        // Claude uses permission-mode bypass in force-edit flows.
+        "claude" => supported_flags.iter().any(|f| f == "--permission-mode"),
+        _ => false,
+    }
+}
+
+pub fn unsafe_mode_requirement_hint(adapter_key: &str) -> &'static str {
+    match adapter_key {
+        "codex" => "Expected flag: --dangerously-bypass-approvals-and-sandbox.",
+        "claude" => "Expected flag: --permission-mode.",
+        _ => "Unsafe mode is only supported for adapters with explicit sandbox-bypass controls.",
+    }
+}
+
+/// Build minimal interactive CLI args for direct external tool invocation.
+/// No race-mode flags (--output-format json, --verbose, --stream-json, etc.).
+pub fn build_interactive_args(
+    adapter_key: &str,
+    task_prompt: &str,
+    unsafe_mode: bool,
+    supported_flags: &[String],
+) -> Vec<String> {
+    match adapter_key {
+        "claude" => {
+            // Keep interactive launches tool-native: no print/headless flags.
+            let mut args = Vec::new();
+            if unsafe_mode && supported_flags.iter().any(|f| f == "--permission-mode") {
+                args.extend([
+                    "--permission-mode".to_string(),
+                    "bypassPermissions".to_string(),
+                ]);
+            }
+            if !task_prompt.trim().is_empty() {
+                args.push(task_prompt.to_string());
+            }
+            args
+        }
+        "codex" => {
+            // Empty prompt launches native interactive mode; non-empty prompt
+            // keeps one-shot bootstrap behavior via `exec`.
+            let mut args = if task_prompt.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec!["exec".to_string(), task_prompt.to_string()]
+            };
+            if unsafe_mode
+                && supported_flags
+                    .iter()
+                    .any(|f| f == "--dangerously-bypass-approvals-and-sandbox")
+            {
+                args.push("--dangerously-bypass-approvals-and-sandbox".to_string());
+            }
+            args
+        }
+        "cursor-agent" => {
+            if task_prompt.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![task_prompt.to_string()]
+            }
+        }
+        _ => {
+            if task_prompt.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![task_prompt.to_string()]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsafe_mode_support_for_codex_requires_dangerous_flag() {
+        let ok = supports_interactive_unsafe_mode(
+            "codex",
+            &[
+                "--json".to_string(),
+                "--dangerously-bypass-approvals-and-sandbox".to_string(),
+            ],
+        );
+        let blocked = supports_interactive_unsafe_mode(
+            "codex",
+            &["--json".to_string(), "--permission-mode".to_string()],
+        );
+        assert!(ok);
+        assert!(!blocked);
+    }
+
+    #[test]
+    fn unsafe_mode_support_for_claude_requires_permission_mode() {
+        let ok = supports_interactive_unsafe_mode(
+            "claude",
+            &["--print".to_string(), "--permission-mode".to_string()],
+        );
+        let blocked = supports_interactive_unsafe_mode("claude", &["--print".to_string()]);
+        assert!(ok);
+        assert!(!blocked);
+    }
+
+    #[test]
+    fn unsafe_mode_support_rejects_unknown_adapters() {
+        let blocked = supports_interactive_unsafe_mode("cursor-agent", &["--force".to_string()]);
+        assert!(!blocked);
+    }
+
+    #[test]
+    fn build_interactive_args_claude_basic() {
+        let args = build_interactive_args("claude", "fix the bug", false, &[]);
+        assert_eq!(args, vec!["fix the bug"]);
+    }
+
+    #[test]
+    fn build_interactive_args_claude_unsafe_with_permission_mode() {
+        let flags = vec!["--permission-mode".to_string()];
+        let args = build_interactive_args("claude", "fix it", true, &flags);
+        assert_eq!(
+            args,
+            vec!["--permission-mode", "bypassPermissions", "fix it"]
+        );
+    }
+
+    #[test]
+    fn build_interactive_args_claude_unsafe_without_permission_mode() {
+        let args = build_interactive_args("claude", "fix it", true, &[]);
+        assert_eq!(args, vec!["fix it"]);
+    }
+
+    #[test]
+    fn build_interactive_args_codex_basic() {
+        let args = build_interactive_args("codex", "refactor the module", false, &[]);
+        assert_eq!(args, vec!["exec", "refactor the module"]);
+    }
+
+    #[test]
+    fn build_interactive_args_codex_unsafe_with_dangerous_flag() {
+        let flags = vec!["--dangerously-bypass-approvals-and-sandbox".to_string()];
+        let args = build_interactive_args("codex", "fix", true, &flags);
+        assert_eq!(
+            args,
+            vec!["exec", "fix", "--dangerously-bypass-approvals-and-sandbox"]
+        );
+    }
+
+    #[test]
+    fn build_interactive_args_cursor_agent() {
+        let args = build_interactive_args("cursor-agent", "do it", false, &[]);
+        assert_eq!(args, vec!["do it"]);
+    }
+
+    #[test]
+    fn build_interactive_args_unknown_adapter() {
+        let args = build_interactive_args("unknown", "task", false, &[]);
+        assert_eq!(args, vec!["task"]);
+    }
+
+    #[test]
+    fn build_interactive_args_empty_prompt_launches_interactive_shell() {
+        let claude = build_interactive_args("claude", "", false, &[]);
+        let codex = build_interactive_args("codex", "", false, &[]);
+        let cursor = build_interactive_args("cursor-agent", "", false, &[]);
+        assert!(claude.is_empty());
+        assert!(codex.is_empty());
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    fn build_interactive_args_codex_empty_prompt_with_unsafe_flag() {
+        let flags = vec!["--dangerously-bypass-approvals-and-sandbox".to_string()];
+        let codex = build_interactive_args("codex", "", true, &flags);
+        assert_eq!(codex, vec!["--dangerously-bypass-approvals-and-sandbox"]);
+    }
+}
@@ -1,17 +1,22 @@
+mod auth;
 pub mod claude;
 pub mod codex;
 pub mod cursor;
 mod error;
+pub mod interactive;
+mod ping;
 mod probe;
 pub mod registry;
 mod types;
 
 pub use error::AdapterError;
+pub use ping::{ping_adapter, PingResult, PingStatus, DEFAULT_PING_TIMEOUT, PING_PROMPT};
 pub use probe::{ProbeReport, ProbeRunner};
 pub use registry::{AdapterRegistry, RegistryError};
 pub use types::{
-    AdapterTier, AgentEvent, BuiltCommand, CapabilityConfidence, CapabilityEntry, CapabilitySet,
-    DetectResult, DetectStatus, ProbeResult, SpawnRequest,
+    AdapterTier, AgentEvent, AuthResult, AuthStatus, BuiltCommand, CapabilityConfidence,
+    CapabilityEntry, CapabilitySet, CommandStep, DetectResult, DetectStatus, ProbeResult,
+    SpawnRequest,
 };
 
 use std::path::PathBuf;
@@ -26,6 +31,11 @@ pub trait AgentAdapter: Send + Sync {
     fn detect(&self) -> DetectResult;
     fn capabilities(&self) -> CapabilitySet;
 
+    /// Check for usable credentials without running the agent.
+    fn auth(&self) -> AuthResult {
+        AuthResult::unknown(format!("{} has no auth check", self.key()))
+    }
+
     fn build_command(&self, _req: &SpawnRequest) -> Result<BuiltCommand, AdapterError> {
         Err(AdapterError::NotImplemented {
             adapter: self.key().to_string(),
@@ -33,6 +43,11 @@ pub trait AgentAdapter: Send + Sync {
         })
     }
 
+    /// Render a structured task as this agent's prompt.
+    fn render_task(&self, spec: &crate::task::TaskSpec) -> String {
+        spec.to_markdown()
+    }
+
     fn parse_line(&self, _line: &str) -> Option<AgentEvent> {
         None
     }
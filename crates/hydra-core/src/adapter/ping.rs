@@ -0,0 +1,232 @@
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::SpawnRequest;
+use super::{AdapterError, AgentAdapter};
+
+/// Prompt sent by a live check. Small enough to cost a handful of tokens.
+pub const PING_PROMPT: &str = "Reply with the single word: pong";
+
+pub const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Captured output kept in a ping result, in characters.
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// Outcome of a live check. A successful `--version` probe only shows the
+/// binary exists; this shows the agent can actually answer a prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PingStatus {
+    Ok,
+    AuthFailed,
+    QuotaExceeded,
+    TimedOut,
+    Failed,
+    /// The adapter cannot build a headless command (e.g. not implemented).
+    Skipped,
+}
+
+impl PingStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::AuthFailed => "auth failed",
+            Self::QuotaExceeded => "quota exceeded",
+            Self::TimedOut => "timed out",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingResult {
+    pub adapter_key: String,
+    pub status: PingStatus,
+    pub duration_ms: u64,
+    /// Tail of the agent's output when the check did not pass.
+    pub detail: Option<String>,
+}
+
+/// Run `adapter` on [`PING_PROMPT`] without edit permissions and classify
+/// the result. The agent runs in the system temp dir so it cannot touch
+/// the repository.
+pub async fn ping_adapter(
+    adapter: &dyn AgentAdapter,
+    supported_flags: Vec<String>,
+    timeout: Duration,
+) -> PingResult {
+    let key = adapter.key().to_string();
+    let req = SpawnRequest {
+        task_prompt: PING_PROMPT.to_string(),
+        worktree_path: std::env::temp_dir(),
+        timeout_seconds: timeout.as_secs().max(1),
+        allow_network: true,
+        force_edit: false,
+        output_json_stream: true,
+        unsafe_mode: false,
+        supported_flags,
+    };
+    let started = Instant::now();
+    let result = |status, detail: Option<String>| PingResult {
+        adapter_key: key.clone(),
+        status,
+        duration_ms: started.elapsed().as_millis() as u64,
+        detail,
+    };
+
+    let built = match adapter.build_command(&req) {
+        Ok(built) => built,
+        Err(e @ AdapterError::NotImplemented { .. }) => {
+            return result(PingStatus::Skipped, Some(e.to_string()))
+        }
+        Err(e) => return result(PingStatus::Failed, Some(e.to_string())),
+    };
+
+    let child = tokio::process::Command::new(&built.program)
+        .args(&built.args)
+        .envs(built.env.iter().map(|(k, v)| (k, v)))
+        .current_dir(&built.cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+
+    match tokio::time::timeout(timeout, child).await {
+        Err(_) => result(
+            PingStatus::TimedOut,
+            Some(format!("no response within {timeout:?}")),
+        ),
+        Ok(Err(e)) => result(PingStatus::Failed, Some(format!("failed to spawn: {e}"))),
+        Ok(Ok(output)) => {
+            let text = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let status = classify_output(output.status.success(), &text);
+            let detail = (status != PingStatus::Ok).then(|| tail(text.trim()));
+            result(status, detail)
+        }
+    }
+}
+
+/// Auth and quota problems get their own status because they need a
+/// different fix (log in, wait, or top up) than a broken install.
+fn classify_output(success: bool, text: &str) -> PingStatus {
+    const AUTH: &[&str] = &[
+        "not logged in",
+        "please run /login",
+        "invalid api key",
+        "unauthorized",
+        "authentication",
+        "401",
+    ];
+    const QUOTA: &[&str] = &[
+        "rate limit",
+        "rate_limit",
+        "quota",
+        "usage limit",
+        "credit balance",
+        "429",
+    ];
+
+    if success {
+        return PingStatus::Ok;
+    }
+    let lower = text.to_lowercase();
+    if QUOTA.iter().any(|needle| lower.contains(needle)) {
+        PingStatus::QuotaExceeded
+    } else if AUTH.iter().any(|needle| lower.contains(needle)) {
+        PingStatus::AuthFailed
+    } else {
+        PingStatus::Failed
+    }
+}
+
+fn tail(text: &str) -> String {
+    let count = text.chars().count();
+    if count <= MAX_DETAIL_CHARS {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - MAX_DETAIL_CHARS).collect();
+    format!("…{tail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::types::*;
+
+    struct ScriptAdapter {
+        script: &'static str,
+    }
+
+    impl AgentAdapter for ScriptAdapter {
+        fn key(&self) -> &'static str {
+            "script"
+        }
+
+        fn tier(&self) -> AdapterTier {
+            AdapterTier::Tier1
+        }
+
+        fn detect(&self) -> DetectResult {
+            unreachable!("ping does not detect")
+        }
+
+        fn capabilities(&self) -> CapabilitySet {
+            unreachable!("ping does not read capabilities")
+        }
+
+        fn build_command(&self, req: &SpawnRequest) -> Result<BuiltCommand, AdapterError> {
+            Ok(BuiltCommand {
+                program: "sh".to_string(),
+                args: vec!["-c".to_string(), self.script.to_string()],
+                env: vec![],
+                cwd: req.worktree_path.clone(),
+                ..Default::default()
+            })
+        }
+    }
+
+    async fn ping(script: &'static str, timeout: Duration) -> PingResult {
+        ping_adapter(&ScriptAdapter { script }, vec![], timeout).await
+    }
+
+    #[test]
+    fn classifies_auth_and_quota_failures() {
+        assert_eq!(classify_output(true, "pong"), PingStatus::Ok);
+        assert_eq!(
+            classify_output(false, "Invalid API key · Please run /login"),
+            PingStatus::AuthFailed
+        );
+        assert_eq!(
+            classify_output(false, "error: 429 Rate limit reached"),
+            PingStatus::QuotaExceeded
+        );
+        assert_eq!(classify_output(false, "segfault"), PingStatus::Failed);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn ping_reports_process_outcome() {
+        let ok = ping("echo pong", DEFAULT_PING_TIMEOUT).await;
+        assert_eq!(ok.status, PingStatus::Ok);
+        assert!(ok.detail.is_none());
+
+        let auth = ping(
+            "echo 'Error: not logged in' >&2; exit 1",
+            DEFAULT_PING_TIMEOUT,
+        )
+        .await;
+        assert_eq!(auth.status, PingStatus::AuthFailed);
+        assert_eq!(auth.detail.as_deref(), Some("Error: not logged in"));
+
+        let slow = ping("sleep 5", Duration::from_millis(200)).await;
+        assert_eq!(slow.status, PingStatus::TimedOut);
+    }
+}
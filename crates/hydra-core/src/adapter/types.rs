@@ -112,6 +112,69 @@ impl DetectResult {
     }
 }
 
+/// Whether an adapter's CLI has usable credentials. Kept apart from
+/// [`DetectResult`]: a binary can be found and still fail on first use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthResult {
+    pub status: AuthStatus,
+    /// Where the credential was found (env var name or credentials file).
+    pub source: Option<String>,
+    pub detail: Option<String>,
+    /// How to log in, when the status calls for it.
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthStatus {
+    Authenticated,
+    Missing,
+    Expired,
+    /// Credentials live somewhere hydra cannot inspect.
+    Unknown,
+}
+
+impl AuthStatus {
+    /// `Missing` and `Expired` mean the agent would fail on first use.
+    pub fn is_failure(self) -> bool {
+        matches!(self, Self::Missing | Self::Expired)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Authenticated => "authenticated",
+            Self::Missing => "not logged in",
+            Self::Expired => "expired",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl AuthResult {
+    pub fn authenticated(source: impl Into<String>) -> Self {
+        Self {
+            status: AuthStatus::Authenticated,
+            source: Some(source.into()),
+            detail: None,
+            remediation: None,
+        }
+    }
+
+    pub fn unknown(detail: impl Into<String>) -> Self {
+        Self {
+            status: AuthStatus::Unknown,
+            source: None,
+            detail: Some(detail.into()),
+            remediation: None,
+        }
+    }
+
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+}
+
 /// Individual probe result contributed to the overall report.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProbeResult {
@@ -167,10 +230,38 @@ pub struct SpawnRequest {
 }
 
 /// Command built by an adapter for the process supervisor.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct BuiltCommand {
     pub program: String,
     pub args: Vec<String>,
     pub env: Vec<(String, String)>,
     pub cwd: PathBuf,
+    /// Steps run before `program`, each only if the one before succeeded.
+    pub pre: Vec<CommandStep>,
+    /// Steps run after `program` exits successfully.
+    pub post: Vec<CommandStep>,
+}
+
+/// A command the supervisor runs around the agent, in its working directory,
+/// environment and sandbox. Output is reported under the step's label rather
+/// than as agent output, and a failing step fails the agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandStep {
+    /// Run `program` to completion.
+    Run {
+        label: String,
+        program: String,
+        args: Vec<String>,
+    },
+    /// Source a POSIX shell script, such as a virtualenv's `bin/activate`,
+    /// and pass the variables it sets on to every later command.
+    Source { label: String, script: PathBuf },
+}
+
+impl CommandStep {
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Run { label, .. } | Self::Source { label, .. } => label,
+        }
+    }
 }
@@ -0,0 +1,75 @@
+//! Repository-wide audit log (`.hydra/audit.jsonl`) of security-relevant
+//! decisions that are not tied to a single run, such as granting unsafe
+//! mode.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ArtifactError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// What happened, e.g. `unsafe_consent_granted`.
+    pub action: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl AuditEntry {
+    pub fn new(action: impl Into<String>, data: serde_json::Value) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action: action.into(),
+            data,
+        }
+    }
+}
+
+/// Append-only JSONL log under the `.hydra` directory.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(hydra_root: &Path) -> Self {
+        Self {
+            path: hydra_root.join("audit.jsonl"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), ArtifactError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Every entry, oldest first. Lines that do not parse are skipped.
+    pub fn read_all(&self) -> Result<Vec<AuditEntry>, ArtifactError> {
+        let data = match std::fs::read_to_string(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(data
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
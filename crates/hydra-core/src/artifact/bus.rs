@@ -0,0 +1,161 @@
+//! Fan-out of a run's events: each one is appended to its `events.jsonl`
+//! and handed, redacted, to the sinks registered for the run, so consumers
+//! such as the API's WebSocket stream see it without re-reading the file.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::{ArtifactError, EventWriter, RunEvent};
+
+/// Receives a run's events as they are written. Called on the writer's
+/// task, so implementations must not block.
+pub trait EventSink: Send + Sync {
+    fn publish(&self, event: &RunEvent);
+}
+
+impl<F> EventSink for F
+where
+    F: Fn(&RunEvent) + Send + Sync,
+{
+    fn publish(&self, event: &RunEvent) {
+        self(event)
+    }
+}
+
+/// The sinks registered for one run.
+#[derive(Clone, Default)]
+pub struct EventSinks(Vec<Arc<dyn EventSink>>);
+
+impl EventSinks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, sink: impl EventSink + 'static) -> Self {
+        self.0.push(Arc::new(sink));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for EventSinks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EventSinks").field(&self.0.len()).finish()
+    }
+}
+
+/// An events log plus the sinks that follow it.
+pub struct EventBus {
+    writer: EventWriter,
+    sinks: EventSinks,
+}
+
+impl EventBus {
+    pub fn new(writer: EventWriter, sinks: EventSinks) -> Self {
+        Self { writer, sinks }
+    }
+
+    /// Append `event` to the log, then publish it as written. Sinks only see
+    /// events that made it to disk.
+    pub fn write_event(&mut self, event: &RunEvent) -> Result<(), ArtifactError> {
+        let line = self.writer.write_line(event)?;
+        if self.sinks.is_empty() {
+            return Ok(());
+        }
+        let redacted: RunEvent = serde_json::from_str(&line)?;
+        for sink in &self.sinks.0 {
+            sink.publish(&redacted);
+        }
+        Ok(())
+    }
+}
+
+/// In-memory copy of every event published to it, for readers that poll by
+/// position, e.g. a live view of a run started in the same process.
+#[derive(Clone, Default)]
+pub struct EventBuffer {
+    events: Arc<Mutex<Vec<RunEvent>>>,
+}
+
+impl EventBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events from position `index` on, and the position to read from next.
+    pub fn since(&self, index: usize) -> (Vec<RunEvent>, usize) {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let start = index.min(events.len());
+        (events[start..].to_vec(), events.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl EventSink for EventBuffer {
+    fn publish(&self, event: &RunEvent) {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(event.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::{EventKind, EventReader};
+    use crate::security::SecretRedactor;
+
+    #[test]
+    fn bus_writes_the_log_and_publishes_redacted_events() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let buffer = EventBuffer::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let hook = move |event: &RunEvent| recorded.lock().unwrap().push(event.kind.clone());
+
+        let writer = EventWriter::create(&path)
+            .unwrap()
+            .with_redactor(SecretRedactor::new());
+        let mut bus = EventBus::new(writer, EventSinks::new().with(buffer.clone()).with(hook));
+        bus.write_event(&RunEvent::new(
+            EventKind::AgentStdout,
+            Some("claude".to_string()),
+            serde_json::json!({ "line": "token sk-ant-REDACTED" }),
+        ))
+        .unwrap();
+        bus.write_event(&RunEvent::new(
+            EventKind::RunCompleted,
+            None,
+            serde_json::json!({}),
+        ))
+        .unwrap();
+
+        let on_disk = EventReader::read_all(&path).unwrap();
+        let (published, next) = buffer.since(0);
+        assert_eq!(next, 2);
+        assert_eq!(published.len(), on_disk.len());
+        assert_eq!(published[0].data, on_disk[0].data);
+        assert!(!published[0].data.to_string().contains("abcdefghijklmnop"));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![EventKind::AgentStdout, EventKind::RunCompleted]
+        );
+
+        let (rest, next) = buffer.since(1);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].kind, EventKind::RunCompleted);
+        assert_eq!(buffer.since(next).0.len(), 0);
+    }
+}
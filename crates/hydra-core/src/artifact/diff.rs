@@ -0,0 +1,310 @@
+//! Size limits for an agent's `diff.patch`. Binary files are recorded by
+//! path and size only, and the diffs of files that are too large are moved
+//! to `diff-blobs/` so the patch stays small enough to read and render.
+
+use serde::{Deserialize, Serialize};
+
+use super::{ArtifactError, RunLayout};
+use crate::config::ArtifactsConfig;
+
+/// Marker lines added to `diff.patch` start with this, outside any hunk.
+const MARKER: &str = "# hydra:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffLimits {
+    /// Largest diff of a single file kept inline.
+    pub max_file_bytes: u64,
+    /// Size the patch may reach before further files are moved out.
+    pub max_total_bytes: u64,
+}
+
+impl DiffLimits {
+    pub fn from_config(config: &ArtifactsConfig) -> Self {
+        Self {
+            max_file_bytes: config.max_diff_file_bytes,
+            max_total_bytes: config.max_diff_total_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OmittedReason {
+    Binary,
+    /// The file's diff alone is over `max_file_bytes`.
+    FileTooLarge,
+    /// The patch had already reached `max_total_bytes`.
+    TotalTooLarge,
+}
+
+/// A file whose diff is not in `diff.patch`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OmittedFile {
+    pub path: String,
+    pub reason: OmittedReason,
+    /// Size of the file for binaries, of its diff otherwise. `None` for a
+    /// deleted binary.
+    pub bytes: Option<u64>,
+    /// Where the full diff was stored, relative to the agent directory.
+    /// Binary diffs carry no content and are not stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LimitedPatch {
+    pub patch: String,
+    pub omitted: Vec<OmittedFile>,
+    /// Full diffs moved out of the patch, by their `OmittedFile::blob` name.
+    pub blobs: Vec<(String, String)>,
+}
+
+/// Apply `limits` to `patch`. `file_size` reports the size of a file in the
+/// checkout, by repository-relative path, for binary entries.
+///
+/// Omitted files keep their `diff --git` header lines followed by a marker,
+/// so tools that list the files in a patch still see them.
+pub fn limit_patch(
+    patch: &str,
+    limits: &DiffLimits,
+    file_size: impl Fn(&str) -> Option<u64>,
+) -> LimitedPatch {
+    let mut out = LimitedPatch::default();
+    for section in split_sections(patch) {
+        let Some(path) = section_path(section) else {
+            out.patch.push_str(section);
+            continue;
+        };
+        let header = section_header(section);
+        let section_bytes = section.len() as u64;
+
+        let (reason, bytes) = if is_binary(section) {
+            (OmittedReason::Binary, file_size(&path))
+        } else if section_bytes > limits.max_file_bytes {
+            (OmittedReason::FileTooLarge, Some(section_bytes))
+        } else if out.patch.len() as u64 + section_bytes > limits.max_total_bytes {
+            (OmittedReason::TotalTooLarge, Some(section_bytes))
+        } else {
+            out.patch.push_str(section);
+            continue;
+        };
+
+        out.patch.push_str(header);
+        let blob = match reason {
+            OmittedReason::Binary => {
+                let size = bytes.map(|b| format!(" ({b} bytes)")).unwrap_or_default();
+                out.patch
+                    .push_str(&format!("{MARKER} binary file {path}{size} not included\n"));
+                None
+            }
+            OmittedReason::FileTooLarge | OmittedReason::TotalTooLarge => {
+                let name = format!("diff-blobs/{:04}.patch", out.blobs.len() + 1);
+                let limit = if reason == OmittedReason::FileTooLarge {
+                    "artifacts.max_diff_file_bytes"
+                } else {
+                    "artifacts.max_diff_total_bytes"
+                };
+                out.patch.push_str(&format!(
+                    "{MARKER} diff of {path} ({section_bytes} bytes) is over {limit}; \
+                     full diff in {name}\n"
+                ));
+                out.blobs.push((name.clone(), section.to_string()));
+                Some(name)
+            }
+        };
+        out.omitted.push(OmittedFile {
+            path,
+            reason,
+            bytes,
+            blob,
+        });
+    }
+    out
+}
+
+/// Write `patch` as the agent's `diff.patch` within `limits`, along with
+/// `diff-blobs/` and `diff-omitted.json` when files were left out. Returns
+/// the omitted files.
+pub fn write_diff_artifact(
+    layout: &RunLayout,
+    agent_key: &str,
+    patch: &str,
+    limits: &DiffLimits,
+    file_size: impl Fn(&str) -> Option<u64>,
+) -> Result<Vec<OmittedFile>, ArtifactError> {
+    let limited = limit_patch(patch, limits, file_size);
+    let agent_dir = layout.agent_dir(agent_key);
+    if !limited.blobs.is_empty() {
+        std::fs::create_dir_all(layout.agent_diff_blobs_dir(agent_key))?;
+    }
+    for (name, content) in &limited.blobs {
+        std::fs::write(agent_dir.join(name), content)?;
+    }
+    if !limited.omitted.is_empty() {
+        let json = serde_json::to_string_pretty(&limited.omitted)?;
+        std::fs::write(layout.agent_diff_omitted(agent_key), json)?;
+    }
+    std::fs::write(layout.agent_diff(agent_key), &limited.patch)?;
+    Ok(limited.omitted)
+}
+
+/// Split a patch before each `diff --git` line; text ahead of the first one
+/// is its own section.
+fn split_sections(patch: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in patch.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    let mut sections = Vec::with_capacity(starts.len() + 1);
+    let mut prev = 0;
+    for start in starts.into_iter().chain([patch.len()]) {
+        if start > prev {
+            sections.push(&patch[prev..start]);
+        }
+        prev = start;
+    }
+    sections
+}
+
+fn section_path(section: &str) -> Option<String> {
+    let first = section.lines().next()?;
+    let rest = first.strip_prefix("diff --git a/")?;
+    let (_, new) = rest.split_once(" b/")?;
+    Some(new.to_string())
+}
+
+/// The lines before the first hunk or binary payload.
+fn section_header(section: &str) -> &str {
+    let mut end = 0;
+    for line in section.split_inclusive('\n') {
+        if line.starts_with("@@")
+            || line.starts_with("Binary files ")
+            || line.starts_with("GIT binary patch")
+        {
+            break;
+        }
+        end += line.len();
+    }
+    &section[..end]
+}
+
+fn is_binary(section: &str) -> bool {
+    section.lines().any(|line| {
+        (line.starts_with("Binary files ") && line.ends_with(" differ"))
+            || line == "GIT binary patch"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                        index 1111111..2222222 100644\n\
+                        --- a/src/lib.rs\n\
+                        +++ b/src/lib.rs\n\
+                        @@ -1 +1,2 @@\n\
+                        \x20fn a() {}\n\
+                        +fn b() {}\n";
+    const BINARY: &str = "diff --git a/logo.png b/logo.png\n\
+                          new file mode 100644\n\
+                          index 0000000..3333333\n\
+                          Binary files /dev/null and b/logo.png differ\n";
+
+    fn limits(max_file_bytes: u64, max_total_bytes: u64) -> DiffLimits {
+        DiffLimits {
+            max_file_bytes,
+            max_total_bytes,
+        }
+    }
+
+    #[test]
+    fn small_text_patch_is_unchanged() {
+        let limited = limit_patch(TEXT, &limits(1024, 1024), |_| None);
+        assert_eq!(limited.patch, TEXT);
+        assert!(limited.omitted.is_empty());
+    }
+
+    #[test]
+    fn binary_files_are_recorded_by_path_and_size() {
+        let patch = format!("{TEXT}{BINARY}");
+        let limited = limit_patch(&patch, &limits(1024, 1024), |path| {
+            (path == "logo.png").then_some(2048)
+        });
+
+        assert!(limited.patch.starts_with(TEXT));
+        assert!(limited
+            .patch
+            .contains("# hydra: binary file logo.png (2048 bytes) not included\n"));
+        assert!(!limited.patch.contains("Binary files"));
+        assert!(limited.blobs.is_empty());
+        assert_eq!(
+            limited.omitted,
+            vec![OmittedFile {
+                path: "logo.png".to_string(),
+                reason: OmittedReason::Binary,
+                bytes: Some(2048),
+                blob: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn oversized_diffs_move_to_blobs() {
+        let other = TEXT.replace("src/lib.rs", "src/other.rs");
+        let patch = format!("{TEXT}{other}");
+
+        let limited = limit_patch(&patch, &limits(64, 1024), |_| None);
+        assert_eq!(limited.omitted.len(), 2);
+        assert_eq!(limited.omitted[0].reason, OmittedReason::FileTooLarge);
+        assert_eq!(
+            limited.blobs[0],
+            ("diff-blobs/0001.patch".to_string(), TEXT.to_string())
+        );
+        assert!(limited.patch.starts_with(
+            "diff --git a/src/lib.rs b/src/lib.rs\n\
+             index 1111111..2222222 100644\n\
+             --- a/src/lib.rs\n\
+             +++ b/src/lib.rs\n\
+             # hydra: diff of src/lib.rs"
+        ));
+        assert!(!limited.patch.contains("@@"));
+
+        // The first file fits; the second would push the patch over the total.
+        let limited = limit_patch(&patch, &limits(1024, TEXT.len() as u64 + 10), |_| None);
+        assert!(limited.patch.starts_with(TEXT));
+        assert_eq!(limited.omitted.len(), 1);
+        assert_eq!(limited.omitted[0].path, "src/other.rs");
+        assert_eq!(limited.omitted[0].reason, OmittedReason::TotalTooLarge);
+        assert!(limited.patch.contains(
+            "is over artifacts.max_diff_total_bytes; full diff in diff-blobs/0001.patch"
+        ));
+    }
+
+    #[test]
+    fn write_diff_artifact_stores_blobs_and_omitted_list() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let layout = RunLayout::new(tmp.path(), uuid::Uuid::new_v4());
+        layout.create_dirs(&["claude"]).unwrap();
+
+        let patch = format!("{TEXT}{BINARY}");
+        let omitted =
+            write_diff_artifact(&layout, "claude", &patch, &limits(64, 1024), |_| None).unwrap();
+        assert_eq!(omitted.len(), 2);
+
+        let blob = layout.agent_diff_blobs_dir("claude").join("0001.patch");
+        assert_eq!(std::fs::read_to_string(blob).unwrap(), TEXT);
+        let recorded: Vec<OmittedFile> = serde_json::from_str(
+            &std::fs::read_to_string(layout.agent_diff_omitted("claude")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(recorded, omitted);
+        assert!(std::fs::read_to_string(layout.agent_diff("claude"))
+            .unwrap()
+            .contains("# hydra: binary file logo.png not included"));
+    }
+}
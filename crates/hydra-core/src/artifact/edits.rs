@@ -0,0 +1,390 @@
+//! Files an agent has edited so far, parsed from the edit tool calls it
+//! reports. Kept in `edited_files.json` while the agent runs, so front ends
+//! can show what it touched and diff scope can be checked before the final
+//! diff exists. Line counts are approximate: they come from the tool input,
+//! not from git.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ArtifactError;
+use crate::scoring::diff_scope::ScopePrecheck;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOperation {
+    /// The whole file was written: created, or replaced.
+    Write,
+    Edit,
+    Delete,
+}
+
+/// One file change reported by a tool call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEdit {
+    /// As the tool named it; may be absolute.
+    pub path: String,
+    pub operation: FileOperation,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+}
+
+impl FileEdit {
+    /// The edits a tool call makes; empty for tools that do not edit files.
+    pub fn from_tool_call(tool: &str, input: &serde_json::Value) -> Vec<Self> {
+        let text = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| input.get(key).and_then(|v| v.as_str()))
+        };
+        let path = || text(&["file_path", "path", "target_file", "notebook_path"]);
+        let edit = |path: &str, operation, added: Option<&str>, removed: Option<&str>| Self {
+            path: path.to_string(),
+            operation,
+            lines_added: line_count(added),
+            lines_removed: line_count(removed),
+        };
+
+        let tool = tool.to_ascii_lowercase();
+        match tool.as_str() {
+            "apply_patch" => {
+                let patch = input
+                    .as_str()
+                    .or_else(|| text(&["patch", "input"]))
+                    .unwrap_or("");
+                parse_patch(patch)
+            }
+            "multiedit" => {
+                let Some(path) = path() else {
+                    return Vec::new();
+                };
+                let (mut added, mut removed) = (0, 0);
+                for e in input
+                    .get("edits")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    added += line_count(e.get("new_string").and_then(|v| v.as_str()));
+                    removed += line_count(e.get("old_string").and_then(|v| v.as_str()));
+                }
+                vec![Self {
+                    path: path.to_string(),
+                    operation: FileOperation::Edit,
+                    lines_added: added,
+                    lines_removed: removed,
+                }]
+            }
+            // The editor tool also views files; only its writing commands count.
+            "str_replace_editor" => {
+                let Some(path) = path() else {
+                    return Vec::new();
+                };
+                match text(&["command"]) {
+                    Some("create") => {
+                        vec![edit(path, FileOperation::Write, text(&["file_text"]), None)]
+                    }
+                    Some("str_replace") | Some("insert") => vec![edit(
+                        path,
+                        FileOperation::Edit,
+                        text(&["new_str"]),
+                        text(&["old_str"]),
+                    )],
+                    _ => Vec::new(),
+                }
+            }
+            "edit" | "edit_file" | "str_replace" => path()
+                .map(|path| {
+                    edit(
+                        path,
+                        FileOperation::Edit,
+                        text(&["new_string", "new_str", "code_edit"]),
+                        text(&["old_string", "old_str"]),
+                    )
+                })
+                .into_iter()
+                .collect(),
+            "notebookedit" => path()
+                .map(|path| edit(path, FileOperation::Edit, text(&["new_source"]), None))
+                .into_iter()
+                .collect(),
+            "write" | "write_file" | "create_file" => path()
+                .map(|path| {
+                    edit(
+                        path,
+                        FileOperation::Write,
+                        text(&["content", "contents", "file_text"]),
+                        None,
+                    )
+                })
+                .into_iter()
+                .collect(),
+            "delete_file" => path()
+                .map(|path| edit(path, FileOperation::Delete, None, None))
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn line_count(text: Option<&str>) -> u32 {
+    text.map_or(0, |t| t.lines().count() as u32)
+}
+
+/// Edits in a Codex-style patch (`*** Update File: src/lib.rs` sections).
+fn parse_patch(patch: &str) -> Vec<FileEdit> {
+    let mut edits: Vec<FileEdit> = Vec::new();
+    for line in patch.lines() {
+        let header = [
+            ("*** Add File: ", FileOperation::Write),
+            ("*** Update File: ", FileOperation::Edit),
+            ("*** Delete File: ", FileOperation::Delete),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| Some((line.strip_prefix(prefix)?, op)));
+        if let Some((path, operation)) = header {
+            edits.push(FileEdit {
+                path: path.trim().to_string(),
+                operation,
+                lines_added: 0,
+                lines_removed: 0,
+            });
+            continue;
+        }
+        let Some(current) = edits.last_mut() else {
+            continue;
+        };
+        if line.starts_with("***") {
+            continue;
+        } else if line.starts_with('+') {
+            current.lines_added += 1;
+        } else if line.starts_with('-') {
+            current.lines_removed += 1;
+        }
+    }
+    edits
+}
+
+/// Everything one agent has edited so far, per file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditedFile {
+    /// The file's net change: written files stay written when edited later.
+    pub operation: FileOperation,
+    pub edits: u32,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    pub last_edited_at: DateTime<Utc>,
+}
+
+/// Contents of `agents/<key>/edited_files.json`, keyed by path relative to
+/// the worktree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditedFiles {
+    pub files: BTreeMap<String, EditedFile>,
+    /// Diff-scope check of the files above, when one was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<ScopePrecheck>,
+}
+
+impl EditedFiles {
+    /// Load the file at `path`; a missing file means nothing was edited.
+    pub fn read_from(path: &Path) -> Result<Self, ArtifactError> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => Ok(serde_json::from_str(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<(), ArtifactError> {
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Add `edit`, made in the worktree at `root`, and return the path it
+    /// was recorded under. Edits outside the worktree are ignored.
+    pub fn record(&mut self, root: &Path, edit: FileEdit) -> Option<String> {
+        let path = relative_path(root, &edit.path)?;
+        match self.files.get_mut(&path) {
+            // Written, then deleted: as if it never existed, for a new file.
+            Some(file)
+                if file.operation == FileOperation::Write
+                    && edit.operation == FileOperation::Delete =>
+            {
+                self.files.remove(&path);
+            }
+            Some(file) => {
+                if file.operation != FileOperation::Write || edit.operation != FileOperation::Edit {
+                    file.operation = edit.operation;
+                }
+                file.edits += 1;
+                file.lines_added += edit.lines_added;
+                file.lines_removed += edit.lines_removed;
+                file.last_edited_at = Utc::now();
+            }
+            None => {
+                self.files.insert(
+                    path.clone(),
+                    EditedFile {
+                        operation: edit.operation,
+                        edits: 1,
+                        lines_added: edit.lines_added,
+                        lines_removed: edit.lines_removed,
+                        last_edited_at: Utc::now(),
+                    },
+                );
+            }
+        }
+        Some(path)
+    }
+
+    /// Lines added plus lines removed, over every file.
+    pub fn churn(&self) -> u32 {
+        self.files
+            .values()
+            .map(|f| f.lines_added + f.lines_removed)
+            .sum()
+    }
+}
+
+/// `path` relative to `root`, with `/` separators; `None` outside it.
+fn relative_path(root: &Path, path: &str) -> Option<String> {
+    let path = Path::new(path);
+    let relative = if path.is_absolute() {
+        path.strip_prefix(root).ok()?
+    } else {
+        path
+    };
+    let parts: Vec<String> = relative
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(part) => Some(Some(part.to_string_lossy().into_owned())),
+            std::path::Component::CurDir => None,
+            _ => Some(None),
+        })
+        .collect::<Option<_>>()?;
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn edit_tool_calls_become_file_edits() {
+        let edit = FileEdit::from_tool_call(
+            "Edit",
+            &json!({ "file_path": "/wt/src/lib.rs", "old_string": "a\nb", "new_string": "c" }),
+        );
+        assert_eq!(
+            edit,
+            vec![FileEdit {
+                path: "/wt/src/lib.rs".into(),
+                operation: FileOperation::Edit,
+                lines_added: 1,
+                lines_removed: 2,
+            }]
+        );
+
+        let multi = FileEdit::from_tool_call(
+            "MultiEdit",
+            &json!({
+                "file_path": "src/a.rs",
+                "edits": [
+                    { "old_string": "x", "new_string": "y\nz" },
+                    { "old_string": "p", "new_string": "q" },
+                ],
+            }),
+        );
+        assert_eq!((multi[0].lines_added, multi[0].lines_removed), (3, 2));
+
+        let written = FileEdit::from_tool_call(
+            "Write",
+            &json!({ "file_path": "new.rs", "content": "1\n2\n3\n" }),
+        );
+        assert_eq!(written[0].operation, FileOperation::Write);
+        assert_eq!(written[0].lines_added, 3);
+
+        assert!(FileEdit::from_tool_call("Read", &json!({ "file_path": "a.rs" })).is_empty());
+        assert!(FileEdit::from_tool_call(
+            "str_replace_editor",
+            &json!({ "command": "view", "path": "a.rs" })
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn apply_patch_sections_become_file_edits() {
+        let patch = "*** Begin Patch\n\
+                     *** Update File: src/lib.rs\n\
+                     @@\n-old\n+new\n+more\n\
+                     *** Add File: docs/a.md\n+hello\n\
+                     *** Delete File: old.txt\n\
+                     *** End Patch\n";
+        let edits = FileEdit::from_tool_call("apply_patch", &json!({ "input": patch }));
+        let summary: Vec<_> = edits
+            .iter()
+            .map(|e| (e.path.as_str(), e.operation, e.lines_added, e.lines_removed))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/lib.rs", FileOperation::Edit, 2, 1),
+                ("docs/a.md", FileOperation::Write, 1, 0),
+                ("old.txt", FileOperation::Delete, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn record_merges_edits_per_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("wt");
+        let edit = |path: &str, operation, added| FileEdit {
+            path: path.to_string(),
+            operation,
+            lines_added: added,
+            lines_removed: 0,
+        };
+
+        let mut files = EditedFiles::default();
+        let abs = root.join("src/lib.rs").display().to_string();
+        assert_eq!(
+            files.record(&root, edit(&abs, FileOperation::Edit, 2)),
+            Some("src/lib.rs".to_string())
+        );
+        files.record(&root, edit("./src/lib.rs", FileOperation::Edit, 3));
+        files.record(&root, edit("new.rs", FileOperation::Write, 4));
+        files.record(&root, edit("new.rs", FileOperation::Edit, 1));
+        files.record(&root, edit("tmp.rs", FileOperation::Write, 1));
+        files.record(&root, edit("tmp.rs", FileOperation::Delete, 0));
+        assert_eq!(
+            files.record(&root, edit("/etc/passwd", FileOperation::Edit, 1)),
+            None
+        );
+        assert_eq!(
+            files.record(&root, edit("../x", FileOperation::Edit, 1)),
+            None
+        );
+
+        assert_eq!(files.files.len(), 2);
+        assert_eq!(files.files["src/lib.rs"].edits, 2);
+        assert_eq!(files.files["new.rs"].operation, FileOperation::Write);
+        assert_eq!(files.churn(), 10);
+
+        let path = tmp.path().join("edited_files.json");
+        files.write_to(&path).unwrap();
+        assert_eq!(EditedFiles::read_from(&path).unwrap(), files);
+        assert_eq!(
+            EditedFiles::read_from(&tmp.path().join("missing.json")).unwrap(),
+            EditedFiles::default()
+        );
+    }
+}
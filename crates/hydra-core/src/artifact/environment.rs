@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::adapter::{parse_version_string, DetectResult};
+use crate::config::HydraConfig;
+use crate::scoring::pricing::agent_model;
+
+/// The environment a run started in, recorded in its manifest so results
+/// from different machines or toolchain versions can be told apart later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    pub captured_at: DateTime<Utc>,
+    pub hydra_version: String,
+    pub os: String,
+    pub arch: String,
+    /// `None` when `git --version` could not be run or parsed.
+    pub git_version: Option<String>,
+    pub adapters: Vec<AdapterEnvironment>,
+    /// Short SHA-256 of the effective configuration. Equal hashes mean the
+    /// runs were configured identically; the config itself is not stored.
+    pub config_hash: String,
+    /// Commit the base ref resolved to when the run started. `None` for
+    /// runs recorded before this was captured or when it did not resolve.
+    #[serde(default)]
+    pub base_commit: Option<String>,
+    /// Whether the run ID was derived from the race inputs rather than
+    /// generated at random.
+    #[serde(default)]
+    pub deterministic: bool,
+}
+
+/// What is known about one agent's toolchain. None of the supported agent
+/// CLIs accepts a sampling seed, so there is no seed to record; the model is
+/// the one configured in `[pricing.agents]` or the adapter's known default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdapterEnvironment {
+    pub key: String,
+    pub version: Option<String>,
+    pub binary_path: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl EnvironmentSnapshot {
+    /// Snapshot the current process environment. `adapters` are the detect
+    /// results the caller already holds for the agents taking part.
+    pub fn capture<'a>(
+        config: &HydraConfig,
+        adapters: impl IntoIterator<Item = (&'a str, &'a DetectResult)>,
+    ) -> Self {
+        Self {
+            captured_at: Utc::now(),
+            hydra_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            git_version: git_version(),
+            adapters: adapters
+                .into_iter()
+                .map(|(key, detect)| AdapterEnvironment {
+                    key: key.to_string(),
+                    version: detect.version.clone(),
+                    binary_path: detect.binary_path.as_ref().map(|p| p.display().to_string()),
+                    model: agent_model(&config.pricing, key),
+                })
+                .collect(),
+            config_hash: config_hash(config),
+            base_commit: None,
+            deterministic: false,
+        }
+    }
+
+    pub fn with_base_commit(mut self, commit: Option<String>) -> Self {
+        self.base_commit = commit;
+        self
+    }
+
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+}
+
+fn git_version() -> Option<String> {
+    let output = Command::new("git").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version_string(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Short SHA-256 of the effective configuration, as recorded in
+/// [`EnvironmentSnapshot::config_hash`].
+pub fn config_hash(config: &HydraConfig) -> String {
+    use sha2::{Digest, Sha256};
+    // Serializing the parsed config ignores formatting and comments in
+    // hydra.toml, so only changes that affect behaviour alter the hash.
+    let json = serde_json::to_vec(config).unwrap_or_default();
+    Sha256::digest(&json)
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::{CapabilityConfidence, DetectStatus};
+    use std::path::PathBuf;
+
+    #[test]
+    fn capture_records_adapters_and_config_hash() {
+        let detect = DetectResult {
+            status: DetectStatus::Ready,
+            binary_path: Some(PathBuf::from("/usr/bin/claude")),
+            version: Some("1.2.3".to_string()),
+            supported_flags: Vec::new(),
+            confidence: CapabilityConfidence::Verified,
+            error: None,
+        };
+        let config = HydraConfig::default();
+        let snapshot = EnvironmentSnapshot::capture(&config, [("claude", &detect)]);
+
+        assert_eq!(snapshot.os, std::env::consts::OS);
+        assert_eq!(
+            snapshot.adapters,
+            vec![AdapterEnvironment {
+                key: "claude".to_string(),
+                version: Some("1.2.3".to_string()),
+                binary_path: Some("/usr/bin/claude".to_string()),
+                model: Some("claude-sonnet-4".to_string()),
+            }]
+        );
+        assert_eq!(snapshot.config_hash.len(), 16);
+        assert_eq!(
+            snapshot.config_hash,
+            EnvironmentSnapshot::capture(&config, []).config_hash
+        );
+
+        let mut changed = HydraConfig::default();
+        changed.scoring.timeout_per_check_seconds += 1;
+        assert_ne!(
+            snapshot.config_hash,
+            EnvironmentSnapshot::capture(&changed, []).config_hash
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use super::LockHolder;
+
 #[derive(Debug, Error)]
 pub enum ArtifactError {
     #[error("artifact I/O error: {0}")]
@@ -11,9 +13,19 @@ pub enum ArtifactError {
     #[error("run directory already exists: {path}")]
     RunAlreadyExists { path: String },
 
+    #[error("another run holds the repository lock: {holder}")]
+    Locked { holder: LockHolder },
+
     #[error("run directory not found: {path}")]
     RunNotFound { path: String },
 
     #[error("manifest not found at {path}")]
     ManifestNotFound { path: String },
+
+    #[error("{path} has schema version {version}; this Hydra reads up to {supported}")]
+    UnsupportedSchema {
+        path: String,
+        version: u32,
+        supported: u32,
+    },
 }
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 use super::ArtifactError;
 use crate::security::SecretRedactor;
@@ -18,11 +18,29 @@ pub enum EventKind {
     AgentFailed,
     AgentStdout,
     AgentStderr,
+    /// The agent's estimated phase or completion percentage changed.
+    AgentProgress,
+    /// A setup or teardown step around the agent finished. Output from
+    /// steps is logged as agent output carrying the step's label.
+    AgentStep,
     ScoreStarted,
     ScoreFinished,
+    /// One candidate's checks finished, with how long each took. Written to
+    /// the agent's own log.
+    AgentScored,
+    /// Run spend crossed one of `scoring.budget.warn_at_percent`.
+    BudgetWarning,
+    /// A candidate passed the gates and the other agents were stopped.
+    RaceStoppedEarly,
     MergeReady,
     MergeSucceeded,
     MergeConflict,
+    /// An agent tried something its security policy forbids, e.g. network
+    /// egress to a host outside `security.network_allowlist`.
+    SecurityViolation,
+    /// An agent was paused until the user approves a dangerous action.
+    ApprovalRequested,
+    ApprovalResolved,
 }
 
 /// A single event line in `events.jsonl`.
@@ -63,12 +81,24 @@ impl EventWriter {
         })
     }
 
+    /// Redact with `redactor` (typically built from `security.redaction`)
+    /// instead of the built-in patterns only.
+    pub fn with_redactor(mut self, redactor: SecretRedactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
     pub fn write_event(&mut self, event: &RunEvent) -> Result<(), ArtifactError> {
+        self.write_line(event).map(drop)
+    }
+
+    /// Write `event`, returning the line as written, after redaction.
+    pub(super) fn write_line(&mut self, event: &RunEvent) -> Result<String, ArtifactError> {
         let line = serde_json::to_string(event)?;
         let redacted = self.redactor.redact_line(&line);
         writeln!(self.file, "{}", redacted)?;
         self.file.flush()?;
-        Ok(())
+        Ok(redacted.into_owned())
     }
 }
 
@@ -94,6 +124,56 @@ impl EventReader {
     }
 }
 
+/// Incremental reader that tails events.jsonl while a run is still writing it.
+///
+/// Each call to [`EventFollower::poll`] returns only events appended since the
+/// previous call. A trailing line without a newline is held back until the
+/// writer finishes it, so a poll never observes a half-written event.
+pub struct EventFollower {
+    path: PathBuf,
+    offset: u64,
+    pending: Vec<u8>,
+}
+
+impl EventFollower {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Read any complete events appended since the last poll.
+    ///
+    /// A missing file yields no events, since the run may not have created it yet.
+    pub fn poll(&mut self) -> Result<Vec<RunEvent>, ArtifactError> {
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let read = file.read_to_end(&mut self.pending)?;
+        self.offset += read as u64;
+
+        let Some(last_newline) = self.pending.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let complete: Vec<u8> = self.pending.drain(..=last_newline).collect();
+
+        let mut events = Vec::new();
+        for line in String::from_utf8_lossy(&complete).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(serde_json::from_str(line)?);
+        }
+        Ok(events)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,11 +267,17 @@ mod tests {
             EventKind::AgentFailed,
             EventKind::AgentStdout,
             EventKind::AgentStderr,
+            EventKind::AgentProgress,
+            EventKind::AgentStep,
             EventKind::ScoreStarted,
             EventKind::ScoreFinished,
+            EventKind::AgentScored,
             EventKind::MergeReady,
             EventKind::MergeSucceeded,
             EventKind::MergeConflict,
+            EventKind::SecurityViolation,
+            EventKind::ApprovalRequested,
+            EventKind::ApprovalResolved,
         ];
 
         for kind in kinds {
@@ -226,4 +312,64 @@ mod tests {
         let line = events[0].data["line"].as_str().unwrap();
         assert!(line.contains("[REDACTED:OPENAI_KEY]"));
     }
+
+    #[test]
+    fn follower_returns_only_new_events() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let mut follower = EventFollower::new(&path);
+
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut writer = EventWriter::create(&path).unwrap();
+        writer
+            .write_event(&RunEvent::new(
+                EventKind::RunStarted,
+                None,
+                serde_json::json!({}),
+            ))
+            .unwrap();
+        let first = follower.poll().unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].kind, EventKind::RunStarted);
+        assert!(follower.poll().unwrap().is_empty());
+
+        writer
+            .write_event(&RunEvent::new(
+                EventKind::RunCompleted,
+                None,
+                serde_json::json!({}),
+            ))
+            .unwrap();
+        let second = follower.poll().unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].kind, EventKind::RunCompleted);
+    }
+
+    #[test]
+    fn follower_holds_back_partial_lines() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let line = serde_json::to_string(&RunEvent::new(
+            EventKind::AgentStarted,
+            Some("claude".to_string()),
+            serde_json::json!({}),
+        ))
+        .unwrap();
+        let (head, tail) = line.split_at(line.len() / 2);
+
+        std::fs::write(&path, head).unwrap();
+        let mut follower = EventFollower::new(&path);
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(file, "{tail}").unwrap();
+
+        let events = follower.poll().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].agent_key.as_deref(), Some("claude"));
+    }
 }
@@ -0,0 +1,92 @@
+//! Portable `.tar.gz` bundles of a run's artifacts, for attaching to a bug
+//! report or handing to a teammate.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use super::{ArtifactError, RunLayout};
+
+/// File name of the bundle for a run: `hydra-run-<run_id>.tar.gz`.
+pub fn bundle_file_name(layout: &RunLayout) -> String {
+    format!("hydra-run-{}.tar.gz", layout.run_id())
+}
+
+/// Archive the run directory into `dest_dir` and return the bundle path.
+///
+/// Entries are rooted at `hydra-run-<run_id>/`. Symlinks are stored as
+/// links rather than followed, so nothing outside the run directory ends up
+/// in the bundle. An existing bundle for the same run is replaced.
+pub fn export_run_bundle(layout: &RunLayout, dest_dir: &Path) -> Result<PathBuf, ArtifactError> {
+    if !layout.base_dir().is_dir() {
+        return Err(ArtifactError::RunNotFound {
+            path: layout.base_dir().display().to_string(),
+        });
+    }
+    std::fs::create_dir_all(dest_dir)?;
+
+    let file_name = bundle_file_name(layout);
+    let bundle_path = dest_dir.join(&file_name);
+    let tmp_path = dest_dir.join(format!("{file_name}.tmp"));
+
+    let result = write_bundle(layout, &tmp_path)
+        .and_then(|()| std::fs::rename(&tmp_path, &bundle_path).map_err(Into::into));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result.map(|()| bundle_path)
+}
+
+fn write_bundle(layout: &RunLayout, path: &Path) -> Result<(), ArtifactError> {
+    let root = format!("hydra-run-{}", layout.run_id());
+    let encoder = GzEncoder::new(File::create(path)?, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+    builder.append_dir_all(&root, layout.base_dir())?;
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    #[test]
+    fn bundle_contains_run_artifacts() {
+        let tmp = TempDir::new().unwrap();
+        let layout = RunLayout::new(&tmp.path().join(".hydra"), Uuid::new_v4());
+        layout.create_dirs(&["claude"]).unwrap();
+        std::fs::write(layout.manifest_path(), "{}").unwrap();
+        std::fs::write(layout.agent_stdout("claude"), "hello\n").unwrap();
+
+        let dest = tmp.path().join("exports");
+        let bundle = export_run_bundle(&layout, &dest).unwrap();
+        assert_eq!(bundle, dest.join(bundle_file_name(&layout)));
+
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(&bundle).unwrap()));
+        let names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().display().to_string())
+            .collect();
+        let root = format!("hydra-run-{}", layout.run_id());
+        assert!(names.contains(&format!("{root}/manifest.json")));
+        assert!(names.contains(&format!("{root}/agents/claude/stdout.log")));
+        assert!(!dest
+            .join(format!("{}.tmp", bundle_file_name(&layout)))
+            .exists());
+    }
+
+    #[test]
+    fn missing_run_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let layout = RunLayout::new(&tmp.path().join(".hydra"), Uuid::new_v4());
+        let err = export_run_bundle(&layout, tmp.path()).unwrap_err();
+        assert!(matches!(err, ArtifactError::RunNotFound { .. }));
+    }
+}
@@ -0,0 +1,229 @@
+//! Context passed from one interactive session to the next when the user
+//! hands a checkout to a different agent: the tail of the previous agent's
+//! transcript and the changes it left behind.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::{ArtifactError, SessionLayout, SessionMetadata};
+use crate::git_exec::run_git;
+
+/// Transcript lines carried over, counted after escape codes are removed.
+const TRANSCRIPT_TAIL_LINES: usize = 80;
+/// Diff carried over before it is cut short.
+const MAX_DIFF_BYTES: usize = 32 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionHandoff {
+    pub from_session: String,
+    pub from_agent: String,
+    /// End of the previous session's transcript, as plain text.
+    pub transcript_tail: String,
+    /// `git diff HEAD` in the session's checkout, truncated to
+    /// `MAX_DIFF_BYTES`.
+    pub diff: String,
+    pub diff_truncated: bool,
+    /// Files the previous agent created but did not add.
+    pub untracked: Vec<String>,
+}
+
+impl SessionHandoff {
+    /// Collect the handoff context of a session that has stopped. `cwd` is
+    /// the checkout it ran in.
+    pub async fn capture(layout: &SessionLayout, cwd: &Path) -> Result<Self, ArtifactError> {
+        let metadata = SessionMetadata::read_from(&layout.session_json_path())?;
+        let transcript = match std::fs::read(layout.transcript_path()) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let git = |args: &'static [&'static str]| async move {
+            run_git(args, cwd)
+                .await
+                .map(|out| out.stdout)
+                .map_err(|e| ArtifactError::Io(std::io::Error::other(e.to_string())))
+        };
+        let diff = git(&["diff", "HEAD"]).await?;
+        let untracked = git(&["ls-files", "--others", "--exclude-standard"]).await?;
+
+        let (diff, diff_truncated) = truncate(diff, MAX_DIFF_BYTES);
+        Ok(Self {
+            from_session: metadata.session_id,
+            from_agent: metadata.agent_key,
+            transcript_tail: tail_lines(&strip_ansi(&transcript), TRANSCRIPT_TAIL_LINES),
+            diff,
+            diff_truncated,
+            untracked: untracked.lines().map(str::to_string).collect(),
+        })
+    }
+
+    /// Prompt for the next agent: the handoff context, then `task` when the
+    /// user gave one.
+    pub fn to_prompt(&self, task: Option<&str>) -> String {
+        let mut out = format!(
+            "You are taking over this checkout from another coding agent ({}), which \
+             stopped before finishing. Its work so far is already in the working tree; \
+             build on it rather than starting over.\n",
+            self.from_agent
+        );
+        if !self.transcript_tail.trim().is_empty() {
+            out.push_str(&format!(
+                "\n## End of the previous session\n\n```\n{}\n```\n",
+                self.transcript_tail.trim_end()
+            ));
+        }
+        if self.diff.trim().is_empty() {
+            out.push_str("\n## Changes so far\n\nNo changes to tracked files.\n");
+        } else {
+            out.push_str(&format!(
+                "\n## Changes so far\n\n```diff\n{}\n```\n",
+                self.diff.trim_end()
+            ));
+            if self.diff_truncated {
+                out.push_str("(Diff truncated; run `git diff HEAD` for the rest.)\n");
+            }
+        }
+        if !self.untracked.is_empty() {
+            out.push_str("\n## New files\n\n");
+            for path in &self.untracked {
+                out.push_str(&format!("- {path}\n"));
+            }
+        }
+        if let Some(task) = task.map(str::trim).filter(|t| !t.is_empty()) {
+            out.push_str(&format!("\n## Task\n\n{task}\n"));
+        }
+        out
+    }
+}
+
+/// `text` without terminal escape sequences or carriage returns.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameters and intermediates up to a final byte.
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ST.
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {}
+            c if c.is_control() && c != '\n' && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn tail_lines(text: &str, lines: usize) -> String {
+    let kept: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    kept[kept.len().saturating_sub(lines)..].join("\n")
+}
+
+fn truncate(mut text: String, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text, false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    (text, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::SessionArtifactWriter;
+
+    #[test]
+    fn strip_ansi_keeps_plain_text() {
+        assert_eq!(
+            strip_ansi("\u{1b}[1;32mok\u{1b}[0m done\r\n\u{1b}]0;title\u{7}next"),
+            "ok done\nnext"
+        );
+    }
+
+    #[tokio::test]
+    async fn capture_collects_transcript_tail_and_changes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.email", "t@example.com"],
+            &["config", "user.name", "T"],
+        ] {
+            run_git(args, &repo).await.unwrap();
+        }
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\n").unwrap();
+        run_git(&["add", "."], &repo).await.unwrap();
+        run_git(&["commit", "-qm", "init"], &repo).await.unwrap();
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(repo.join("new.rs"), "").unwrap();
+
+        let hydra_root = tmp.path().join(".hydra");
+        let mut writer = SessionArtifactWriter::init(
+            &hydra_root,
+            "s1",
+            "claude",
+            "2026-01-01T00:00:00Z",
+            &repo.to_string_lossy(),
+            false,
+            false,
+        )
+        .unwrap();
+        writer
+            .record_output(b"\x1b[2mthinking\x1b[0m\r\nstuck on the borrow checker\r\n")
+            .unwrap();
+        writer
+            .finalize("stopped", "2026-01-01T00:05:00Z", 1000)
+            .unwrap();
+
+        let handoff = SessionHandoff::capture(writer.layout(), &repo)
+            .await
+            .unwrap();
+        assert_eq!(handoff.from_agent, "claude");
+        assert_eq!(
+            handoff.transcript_tail,
+            "thinking\nstuck on the borrow checker"
+        );
+        assert!(handoff.diff.contains("+fn b() {}"));
+        assert_eq!(handoff.untracked, vec!["new.rs"]);
+
+        let prompt = handoff.to_prompt(Some("Finish the refactor."));
+        assert!(prompt.contains("another coding agent (claude)"));
+        assert!(prompt.contains("stuck on the borrow checker"));
+        assert!(prompt.contains("```diff\n"));
+        assert!(prompt.contains("- new.rs\n"));
+        assert!(prompt.ends_with("## Task\n\nFinish the refactor.\n"));
+    }
+
+    #[test]
+    fn long_diffs_are_truncated_on_a_char_boundary() {
+        let (text, truncated) = truncate("ab\u{e9}cd".to_string(), 3);
+        assert_eq!(text, "ab");
+        assert!(truncated);
+        assert_eq!(truncate("abc".to_string(), 3), ("abc".to_string(), false));
+    }
+}
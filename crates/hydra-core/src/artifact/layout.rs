@@ -14,7 +14,11 @@ use super::ArtifactError;
 ///     stdout.log
 ///     stderr.log
 ///     diff.patch
+///     diff-omitted.json
+///     diff-blobs/<n>.patch
+///     notes.md
 ///     score.json
+///     review.json
 /// ```
 #[derive(Debug, Clone)]
 pub struct RunLayout {
@@ -40,6 +44,16 @@ impl RunLayout {
         self.base_dir.join("manifest.json")
     }
 
+    /// Structured task spec of a `--task` race.
+    pub fn task_spec(&self) -> PathBuf {
+        self.base_dir.join("task.json")
+    }
+
+    /// Adapter and prompts of a prompt-variant race.
+    pub fn variants(&self) -> PathBuf {
+        self.base_dir.join("variants.json")
+    }
+
     pub fn events_path(&self) -> PathBuf {
         self.base_dir.join("events.jsonl")
     }
@@ -48,6 +62,10 @@ impl RunLayout {
         self.base_dir.join("agents").join(agent_key)
     }
 
+    pub fn agent_events(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("events.jsonl")
+    }
+
     pub fn agent_stdout(&self, agent_key: &str) -> PathBuf {
         self.agent_dir(agent_key).join("stdout.log")
     }
@@ -60,10 +78,50 @@ impl RunLayout {
         self.agent_dir(agent_key).join("diff.patch")
     }
 
+    /// Files left out of `diff.patch` because they are binary or too large.
+    pub fn agent_diff_omitted(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("diff-omitted.json")
+    }
+
+    /// Where the diffs of files too large for `diff.patch` are stored.
+    pub fn agent_diff_blobs_dir(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("diff-blobs")
+    }
+
+    /// The agent's own account of its approach, from `.hydra-notes.md`.
+    pub fn agent_notes(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("notes.md")
+    }
+
+    /// Files the agent has reported editing so far.
+    pub fn agent_edited_files(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("edited_files.json")
+    }
+
     pub fn agent_score(&self, agent_key: &str) -> PathBuf {
         self.agent_dir(agent_key).join("score.json")
     }
 
+    /// Per-file review decisions made on this agent's diff.
+    pub fn agent_review(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("review.json")
+    }
+
+    /// Marker whose presence asks a live race to cancel every agent.
+    pub fn run_cancel_marker(&self) -> PathBuf {
+        self.base_dir.join("cancel")
+    }
+
+    /// Marker whose presence asks a live race to cancel a single agent.
+    pub fn agent_cancel_marker(&self, agent_key: &str) -> PathBuf {
+        self.agent_dir(agent_key).join("cancel")
+    }
+
+    /// Whether cancellation was requested for this agent or the whole run.
+    pub fn cancel_requested(&self, agent_key: &str) -> bool {
+        self.run_cancel_marker().exists() || self.agent_cancel_marker(agent_key).exists()
+    }
+
     pub fn baseline_dir(&self) -> PathBuf {
         self.base_dir.join("baseline")
     }
@@ -151,6 +209,24 @@ mod tests {
         assert!(layout
             .agent_stdout("claude")
             .ends_with("agents/claude/stdout.log"));
+        assert!(layout
+            .agent_events("claude")
+            .ends_with("agents/claude/events.jsonl"));
+    }
+
+    #[test]
+    fn cancel_markers_cover_run_and_agent() {
+        let tmp = TempDir::new().unwrap();
+        let layout = RunLayout::new(&tmp.path().join(".hydra"), Uuid::new_v4());
+        layout.create_dirs(&["claude", "codex"]).unwrap();
+        assert!(!layout.cancel_requested("claude"));
+
+        std::fs::write(layout.agent_cancel_marker("claude"), "").unwrap();
+        assert!(layout.cancel_requested("claude"));
+        assert!(!layout.cancel_requested("codex"));
+
+        std::fs::write(layout.run_cancel_marker(), "").unwrap();
+        assert!(layout.cancel_requested("codex"));
     }
 
     #[test]
@@ -0,0 +1,267 @@
+//! Repository-wide cost ledger (`.hydra/ledger.jsonl`): one entry per agent
+//! per run with its token count and cost. Unlike run directories it is never
+//! pruned, so spend stays on the books after `hydra clean` and retention
+//! policies remove the runs themselves.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{ArtifactError, EventKind, EventReader, RunLayout, RunManifest};
+use crate::config::BudgetConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub timestamp: DateTime<Utc>,
+    pub run_id: Uuid,
+    pub agent_key: String,
+    /// Model the agent was priced as, when its cost came from the price table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub total_tokens: u64,
+    /// `None` when the agent reported tokens but no cost and no price is known.
+    pub cost_usd: Option<f64>,
+}
+
+/// Calendar window a spend limit applies to, in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendPeriod {
+    Day,
+    Month,
+}
+
+impl SpendPeriod {
+    pub const ALL: [SpendPeriod; 2] = [SpendPeriod::Day, SpendPeriod::Month];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpendPeriod::Day => "daily",
+            SpendPeriod::Month => "monthly",
+        }
+    }
+
+    /// Start of the period containing `now`.
+    pub fn start(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let date = match self {
+            SpendPeriod::Day => now.date_naive(),
+            SpendPeriod::Month => {
+                NaiveDate::from_ymd_opt(now.year(), now.month(), 1).expect("valid first of month")
+            }
+        };
+        Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("valid midnight"))
+    }
+
+    /// The configured limit for this period, if any.
+    pub fn limit(self, budget: &BudgetConfig) -> Option<f64> {
+        match self {
+            SpendPeriod::Day => budget.max_cost_usd_per_day,
+            SpendPeriod::Month => budget.max_cost_usd_per_month,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CostLedger {
+    hydra_root: PathBuf,
+    path: PathBuf,
+}
+
+impl CostLedger {
+    pub fn new(hydra_root: &Path) -> Self {
+        Self {
+            hydra_root: hydra_root.to_path_buf(),
+            path: hydra_root.join("ledger.jsonl"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entries: &[LedgerEntry]) -> Result<(), ArtifactError> {
+        self.ensure()?;
+        std::fs::create_dir_all(&self.hydra_root)?;
+        let mut data = String::new();
+        for entry in entries {
+            data.push_str(&serde_json::to_string(entry)?);
+            data.push('\n');
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    /// Every entry, oldest first, with at most one per run and agent (the
+    /// last one written wins). Lines that do not parse are skipped.
+    pub fn read_all(&self) -> Result<Vec<LedgerEntry>, ArtifactError> {
+        self.ensure()?;
+        let data = match std::fs::read_to_string(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries: Vec<LedgerEntry> = Vec::new();
+        let mut index: HashMap<(Uuid, String), usize> = HashMap::new();
+        for entry in data
+            .lines()
+            .filter_map(|line| serde_json::from_str::<LedgerEntry>(line).ok())
+        {
+            match index.get(&(entry.run_id, entry.agent_key.clone())) {
+                Some(&i) => entries[i] = entry,
+                None => {
+                    index.insert((entry.run_id, entry.agent_key.clone()), entries.len());
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.sort_by_key(|e| e.timestamp);
+        Ok(entries)
+    }
+
+    /// Total cost of entries recorded at or after `since`.
+    pub fn spend_since(&self, since: DateTime<Utc>) -> Result<f64, ArtifactError> {
+        Ok(self
+            .read_all()?
+            .iter()
+            .filter(|e| e.timestamp >= since)
+            .filter_map(|e| e.cost_usd)
+            .sum())
+    }
+
+    /// Create the ledger from the runs already on disk the first time it is
+    /// needed, so repositories that predate it start with their history.
+    fn ensure(&self) -> Result<(), ArtifactError> {
+        if self.path.exists() || !self.hydra_root.exists() {
+            return Ok(());
+        }
+        let mut entries = Vec::new();
+        for run_id in RunLayout::list_runs(&self.hydra_root)? {
+            let layout = RunLayout::new(&self.hydra_root, run_id);
+            let replayed = RunManifest::read_from(&layout.manifest_path())
+                .is_ok_and(|manifest| manifest.replay_of.is_some());
+            if replayed {
+                continue;
+            }
+            // Runs with missing or corrupt events have nothing to recover.
+            let Ok(events) = EventReader::read_all(&layout.events_path()) else {
+                continue;
+            };
+            entries.extend(
+                events
+                    .iter()
+                    .filter(|e| {
+                        matches!(e.kind, EventKind::AgentCompleted | EventKind::AgentFailed)
+                    })
+                    .filter_map(|e| {
+                        let agent_key = e.agent_key.clone()?;
+                        let total_tokens = e.data.get("total_tokens")?.as_u64()?;
+                        let cost_usd = e
+                            .data
+                            .get("estimated_cost_usd")
+                            .and_then(serde_json::Value::as_f64);
+                        (total_tokens > 0 || cost_usd.is_some()).then(|| LedgerEntry {
+                            timestamp: e.timestamp,
+                            run_id,
+                            agent_key,
+                            model: e
+                                .data
+                                .get("model")
+                                .and_then(|m| m.as_str())
+                                .map(String::from),
+                            total_tokens,
+                            cost_usd,
+                        })
+                    }),
+            );
+        }
+        entries.sort_by_key(|e| e.timestamp);
+
+        let mut data = String::new();
+        for entry in &entries {
+            data.push_str(&serde_json::to_string(entry)?);
+            data.push('\n');
+        }
+        // Written aside and linked into place so a race appending at the same
+        // moment is never overwritten.
+        let tmp = self
+            .hydra_root
+            .join(format!("ledger.jsonl.{}.tmp", Uuid::new_v4()));
+        std::fs::write(&tmp, data)?;
+        let linked = std::fs::hard_link(&tmp, &self.path);
+        let _ = std::fs::remove_file(&tmp);
+        match linked {
+            Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::{EventWriter, RunEvent};
+
+    fn entry(run_id: Uuid, agent: &str, at: &str, cost: Option<f64>) -> LedgerEntry {
+        LedgerEntry {
+            timestamp: at.parse().unwrap(),
+            run_id,
+            agent_key: agent.to_string(),
+            model: None,
+            total_tokens: 1000,
+            cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn backfills_from_runs_then_sums_spend_by_period() {
+        let tmp = tempfile::tempdir().unwrap();
+        let hydra_root = tmp.path().join(".hydra");
+        let old_run = Uuid::new_v4();
+        let layout = RunLayout::new(&hydra_root, old_run);
+        layout.create_dirs(&["claude"]).unwrap();
+        let mut writer = EventWriter::create(&layout.events_path()).unwrap();
+        let mut event = RunEvent::new(
+            EventKind::AgentCompleted,
+            Some("claude".to_string()),
+            serde_json::json!({"total_tokens": 500, "estimated_cost_usd": 1.5}),
+        );
+        event.timestamp = "2026-02-27T10:00:00Z".parse().unwrap();
+        writer.write_event(&event).unwrap();
+
+        let ledger = CostLedger::new(&hydra_root);
+        let run = Uuid::new_v4();
+        ledger
+            .append(&[
+                entry(run, "claude", "2026-03-01T09:00:00Z", Some(2.0)),
+                entry(run, "codex", "2026-03-02T09:00:00Z", None),
+            ])
+            .unwrap();
+        // A later entry for the same agent replaces the earlier one.
+        ledger
+            .append(&[entry(run, "claude", "2026-03-02T08:00:00Z", Some(3.0))])
+            .unwrap();
+
+        let entries = ledger.read_all().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].run_id, old_run);
+        assert_eq!(entries[0].cost_usd, Some(1.5));
+
+        let now: DateTime<Utc> = "2026-03-02T12:00:00Z".parse().unwrap();
+        let day = SpendPeriod::Day.start(now);
+        let month = SpendPeriod::Month.start(now);
+        assert_eq!(
+            month,
+            "2026-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(ledger.spend_since(day).unwrap(), 3.0);
+        assert_eq!(ledger.spend_since(month).unwrap(), 3.0);
+        assert_eq!(ledger.spend_since(DateTime::<Utc>::MIN_UTC).unwrap(), 4.5);
+    }
+}
@@ -0,0 +1,242 @@
+//! Repository-wide run lock (`.hydra/lock`). A race holds it while it
+//! creates worktrees, captures the baseline and writes its manifest, the
+//! steps where two runs in the same repository would step on each other.
+//! Agents run and score without it, so queued races only wait for setup.
+
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ArtifactError;
+
+/// The run that holds the lock, as recorded in the lock file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub run_id: Uuid,
+    pub pid: u32,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl LockHolder {
+    fn current(run_id: Uuid) -> Self {
+        Self {
+            run_id,
+            pid: std::process::id(),
+            acquired_at: Utc::now(),
+        }
+    }
+
+    /// Whether the process that took the lock has exited without releasing
+    /// it. Only checkable on Unix; elsewhere a lock is never presumed stale.
+    pub fn is_stale(&self) -> bool {
+        if self.pid == std::process::id() {
+            return false;
+        }
+        #[cfg(unix)]
+        {
+            let Ok(pid) = i32::try_from(self.pid) else {
+                return true;
+            };
+            // SAFETY: signal 0 only checks that the process exists.
+            let alive = unsafe { libc::kill(pid, 0) } == 0
+                || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+            !alive
+        }
+        #[cfg(not(unix))]
+        {
+            false
+        }
+    }
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "run {} (pid {}, since {})",
+            self.run_id,
+            self.pid,
+            self.acquired_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+/// What a race does when another run holds the lock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LockMode {
+    /// Fail with [`ArtifactError::Locked`].
+    #[default]
+    Fail,
+    /// Queue until the lock is released.
+    Wait,
+    /// Take the lock from its holder.
+    Force,
+}
+
+/// A held `.hydra/lock`, released on drop.
+#[derive(Debug)]
+pub struct RepoLock {
+    path: PathBuf,
+    holder: LockHolder,
+}
+
+impl RepoLock {
+    pub fn path(hydra_root: &Path) -> PathBuf {
+        hydra_root.join("lock")
+    }
+
+    /// The current holder, if the lock is taken.
+    pub fn current_holder(hydra_root: &Path) -> Option<LockHolder> {
+        let data = std::fs::read(Self::path(hydra_root)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Take the lock for `run_id`, clearing it first if its holder has
+    /// exited. Fails with [`ArtifactError::Locked`] while a live run holds it.
+    pub fn try_acquire(hydra_root: &Path, run_id: Uuid) -> Result<Self, ArtifactError> {
+        std::fs::create_dir_all(hydra_root)?;
+        let path = Self::path(hydra_root);
+        let holder = LockHolder::current(run_id);
+
+        // Write the record beside the lock and link it into place, so the
+        // lock file never exists without its contents.
+        let staged = hydra_root.join(format!("lock.{run_id}.tmp"));
+        std::fs::write(&staged, serde_json::to_vec(&holder)?)?;
+        let result = loop {
+            match std::fs::hard_link(&staged, &path) {
+                Ok(()) => break Ok(Self { path, holder }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    match std::fs::read(&path) {
+                        Ok(data) => match serde_json::from_slice::<LockHolder>(&data) {
+                            Ok(current) if !current.is_stale() => {
+                                break Err(ArtifactError::Locked { holder: current });
+                            }
+                            Ok(current) => {
+                                tracing::warn!(holder = %current, "clearing stale repository lock");
+                            }
+                            Err(_) => tracing::warn!("clearing unreadable repository lock"),
+                        },
+                        Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                        Err(e) => break Err(e.into()),
+                    }
+                    if let Err(e) = remove_if_unchanged(&path, None) {
+                        break Err(e.into());
+                    }
+                }
+                Err(e) => break Err(e.into()),
+            }
+        };
+        let _ = std::fs::remove_file(&staged);
+        result
+    }
+
+    /// Take the lock for `run_id` whoever holds it.
+    pub fn force(hydra_root: &Path, run_id: Uuid) -> Result<Self, ArtifactError> {
+        if let Some(holder) = Self::current_holder(hydra_root) {
+            tracing::warn!(%holder, "taking the repository lock by force");
+        }
+        match std::fs::remove_file(Self::path(hydra_root)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        Self::try_acquire(hydra_root, run_id)
+    }
+
+    pub fn holder(&self) -> &LockHolder {
+        &self.holder
+    }
+}
+
+impl Drop for RepoLock {
+    fn drop(&mut self) {
+        // Leave the file alone if another run has forced the lock since.
+        if let Err(e) = remove_if_unchanged(&self.path, Some(&self.holder)) {
+            tracing::warn!(path = %self.path.display(), error = %e, "failed to release repository lock");
+        }
+    }
+}
+
+/// Remove the lock file unless it now belongs to someone other than
+/// `expected` (any readable holder when `expected` is `None`, meaning the
+/// caller saw a stale or unreadable record a moment ago and another run may
+/// have replaced it).
+fn remove_if_unchanged(path: &Path, expected: Option<&LockHolder>) -> std::io::Result<()> {
+    let current = match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice::<LockHolder>(&data).ok(),
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let replaced = match (expected, &current) {
+        (Some(expected), current) => current.as_ref() != Some(expected),
+        (None, Some(current)) => !current.is_stale(),
+        (None, None) => false,
+    };
+    if replaced {
+        return Ok(());
+    }
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn second_run_is_locked_out_until_release() {
+        let tmp = TempDir::new().unwrap();
+        let first = Uuid::new_v4();
+        let lock = RepoLock::try_acquire(tmp.path(), first).unwrap();
+        assert_eq!(RepoLock::current_holder(tmp.path()).unwrap().run_id, first);
+
+        match RepoLock::try_acquire(tmp.path(), Uuid::new_v4()) {
+            Err(ArtifactError::Locked { holder }) => assert_eq!(holder.run_id, first),
+            other => panic!("expected Locked, got {other:?}"),
+        }
+
+        drop(lock);
+        assert!(!RepoLock::path(tmp.path()).exists());
+        let second = Uuid::new_v4();
+        let _lock = RepoLock::try_acquire(tmp.path(), second).unwrap();
+        assert_eq!(RepoLock::current_holder(tmp.path()).unwrap().run_id, second);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_and_forced_locks_are_taken_over() {
+        let tmp = TempDir::new().unwrap();
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+        let stale = LockHolder {
+            run_id: Uuid::new_v4(),
+            pid: dead_pid,
+            acquired_at: Utc::now(),
+        };
+        assert!(stale.is_stale());
+        std::fs::write(
+            RepoLock::path(tmp.path()),
+            serde_json::to_vec(&stale).unwrap(),
+        )
+        .unwrap();
+
+        let run_id = Uuid::new_v4();
+        let lock = RepoLock::try_acquire(tmp.path(), run_id).unwrap();
+        assert_eq!(lock.holder().run_id, run_id);
+
+        let forced = RepoLock::force(tmp.path(), Uuid::new_v4()).unwrap();
+        // The displaced holder must not release the new holder's lock.
+        drop(lock);
+        assert_eq!(
+            RepoLock::current_holder(tmp.path()).as_ref(),
+            Some(forced.holder())
+        );
+    }
+}
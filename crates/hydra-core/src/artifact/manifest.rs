@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use uuid::Uuid;
 
-use super::ArtifactError;
+use super::{ArtifactError, EnvironmentSnapshot};
+use crate::config::DetectedProject;
+use crate::security::SecretRedactor;
 
 /// Top-level manifest written to `manifest.json` for every run.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,10 +17,39 @@ pub struct RunManifest {
     pub repo_root: String,
     pub base_ref: String,
     pub task_prompt_hash: String,
+    /// Task prompt, secret-redacted unless full prompt storage is enabled.
+    /// Absent in manifests written before prompts were stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_prompt: Option<String>,
+    /// True when redaction replaced part of `task_prompt`.
+    #[serde(default)]
+    pub task_prompt_redacted: bool,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub status: RunStatus,
     pub agents: Vec<AgentEntry>,
+    /// Tool versions and config the run started with. Absent in manifests
+    /// written before environments were captured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentSnapshot>,
+    /// GitHub issue or pull request the task prompt was built from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_source: Option<PromptSource>,
+    /// Run whose recorded events `hydra replay` re-emitted as this run. No
+    /// agent ran, so replays are left out of stats and spend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<Uuid>,
+    /// Scoring commands inferred from the project because the repository
+    /// has no `hydra.toml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_project: Option<DetectedProject>,
+    /// User-defined labels from `--tag`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// How each agent left its work, by agent key. Filled in as agents
+    /// finish.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub candidate_commits: BTreeMap<String, CandidateCommits>,
 }
 
 impl RunManifest {
@@ -38,13 +70,61 @@ impl RunManifest {
             repo_root,
             base_ref,
             task_prompt_hash,
+            task_prompt: None,
+            task_prompt_redacted: false,
             started_at: Utc::now(),
             completed_at: None,
             status: RunStatus::Running,
             agents,
+            environment: None,
+            prompt_source: None,
+            replay_of: None,
+            detected_project: None,
+            tags: Vec::new(),
+            candidate_commits: BTreeMap::new(),
         }
     }
 
+    /// Record the task prompt so the run can be repeated later.
+    ///
+    /// Secrets are redacted with `redactor` unless `store_full` is set.
+    pub fn with_task_prompt(
+        mut self,
+        prompt: &str,
+        store_full: bool,
+        redactor: &SecretRedactor,
+    ) -> Self {
+        if store_full {
+            self.task_prompt = Some(prompt.to_string());
+            self.task_prompt_redacted = false;
+        } else {
+            let redacted = redactor.redact(prompt);
+            self.task_prompt_redacted = redacted.redaction_count > 0;
+            self.task_prompt = Some(redacted.value);
+        }
+        self
+    }
+
+    pub fn with_environment(mut self, environment: EnvironmentSnapshot) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    pub fn with_detected_project(mut self, detected: Option<DetectedProject>) -> Self {
+        self.detected_project = detected;
+        self
+    }
+
+    pub fn with_prompt_source(mut self, source: Option<PromptSource>) -> Self {
+        self.prompt_source = source;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
     pub fn write_to(&self, path: &Path) -> Result<(), ArtifactError> {
         let json = serde_json::to_string_pretty(self)?;
         std::fs::write(path, json)?;
@@ -78,6 +158,61 @@ pub enum RunStatus {
     Interrupted,
 }
 
+/// Where a task prompt came from, when it was composed from a GitHub issue
+/// or pull request rather than typed in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptSource {
+    pub kind: PromptSourceKind,
+    /// `owner/name`.
+    pub repo: String,
+    pub number: u64,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSourceKind {
+    Issue,
+    PullRequest,
+}
+
+/// What an agent's worktree held when it finished, compared with the run's
+/// base commit. Its diff always covers both commits and uncommitted changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CandidateCommits {
+    pub strategy: CommitStrategy,
+    /// Commits the agent made on its branch, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<AgentCommit>,
+}
+
+/// How an agent left its work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitStrategy {
+    /// Nothing changed.
+    Unchanged,
+    /// Changes left in the worktree, nothing committed.
+    Uncommitted,
+    /// Everything committed; the branch holds the whole candidate.
+    Committed,
+    /// Commits plus further changes in the worktree.
+    Mixed,
+}
+
+impl CommitStrategy {
+    /// Whether the agent's branch alone holds all of its changes.
+    pub fn branch_is_complete(self) -> bool {
+        matches!(self, Self::Unchanged | Self::Committed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentCommit {
+    pub sha: String,
+    pub subject: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentEntry {
     pub agent_key: String,
@@ -122,6 +257,67 @@ mod tests {
         assert_eq!(loaded.agents[0].agent_key, "claude");
     }
 
+    #[test]
+    fn task_prompt_is_redacted_unless_full_storage_enabled() {
+        let prompt = "use key sk-proj-abcdefghijklmnop to call the API";
+
+        let redacted = make_test_manifest().with_task_prompt(prompt, false, &SecretRedactor::new());
+        assert!(redacted.task_prompt_redacted);
+        assert!(!redacted
+            .task_prompt
+            .as_deref()
+            .unwrap()
+            .contains("abcdefghijklmnop"));
+
+        let full = make_test_manifest().with_task_prompt(prompt, true, &SecretRedactor::new());
+        assert!(!full.task_prompt_redacted);
+        assert_eq!(full.task_prompt.as_deref(), Some(prompt));
+    }
+
+    #[test]
+    fn manifest_without_task_prompt_still_parses() {
+        let mut value = serde_json::to_value(make_test_manifest()).unwrap();
+        let obj = value.as_object_mut().unwrap();
+        obj.remove("task_prompt");
+        obj.remove("task_prompt_redacted");
+        let parsed: RunManifest = serde_json::from_value(value).unwrap();
+        assert!(parsed.task_prompt.is_none());
+        assert!(!parsed.task_prompt_redacted);
+        assert!(parsed.environment.is_none());
+    }
+
+    #[test]
+    fn environment_snapshot_roundtrips() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("manifest.json");
+
+        let environment = EnvironmentSnapshot::capture(&crate::config::HydraConfig::default(), []);
+        let original = make_test_manifest().with_environment(environment.clone());
+        original.write_to(&path).unwrap();
+
+        let loaded = RunManifest::read_from(&path).unwrap();
+        assert_eq!(loaded.environment, Some(environment));
+
+        let json = serde_json::to_string(&make_test_manifest()).unwrap();
+        assert!(!json.contains("\"environment\""));
+        assert!(!json.contains("\"prompt_source\""));
+    }
+
+    #[test]
+    fn prompt_source_roundtrips() {
+        let source = PromptSource {
+            kind: PromptSourceKind::PullRequest,
+            repo: "octo/hydra".to_string(),
+            number: 456,
+            url: "https://github.com/octo/hydra/pull/456".to_string(),
+        };
+        let manifest = make_test_manifest().with_prompt_source(Some(source.clone()));
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["prompt_source"]["kind"], "pull_request");
+        let parsed: RunManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.prompt_source, Some(source));
+    }
+
     #[test]
     fn manifest_mark_completed() {
         let mut manifest = make_test_manifest();
@@ -1,16 +1,47 @@
+mod audit;
+mod bus;
+mod diff;
+mod edits;
+mod environment;
 mod error;
 mod events;
+mod export;
+mod handoff;
 mod layout;
+mod ledger;
+mod lock;
 mod manifest;
+mod review;
 pub mod schema;
+mod score;
 pub mod session;
+mod tags;
+mod timeline;
 
+pub use audit::{AuditEntry, AuditLog};
+pub use bus::{EventBuffer, EventBus, EventSink, EventSinks};
+pub use diff::{
+    limit_patch, write_diff_artifact, DiffLimits, LimitedPatch, OmittedFile, OmittedReason,
+};
+pub use edits::{EditedFile, EditedFiles, FileEdit, FileOperation};
+pub use environment::{config_hash, AdapterEnvironment, EnvironmentSnapshot};
 pub use error::ArtifactError;
-pub use events::{EventKind, EventReader, EventWriter, RunEvent};
+pub use events::{EventFollower, EventKind, EventReader, EventWriter, RunEvent};
+pub use export::{bundle_file_name, export_run_bundle};
+pub use handoff::{strip_ansi, SessionHandoff};
 pub use layout::RunLayout;
-pub use manifest::{AgentEntry, RunManifest, RunStatus};
+pub use ledger::{CostLedger, LedgerEntry, SpendPeriod};
+pub use lock::{LockHolder, LockMode, RepoLock};
+pub use manifest::{
+    AgentCommit, AgentEntry, CandidateCommits, CommitStrategy, PromptSource, PromptSourceKind,
+    RunManifest, RunStatus,
+};
+pub use review::{AgentReview, FileReview, FileReviewState};
 pub use schema::{EventSchemaDefinition, RunHealthMetrics};
+pub use score::{read_agent_score, AgentScoreArtifact};
 pub use session::{
     SessionArtifactWriter, SessionEvent, SessionEventReader, SessionEventWriter, SessionLayout,
     SessionMetadata, SessionSummary, TranscriptWriter,
 };
+pub use tags::{dedup_tags, has_tags, parse_tag};
+pub use timeline::{AgentTimeline, CheckTiming, RunTimeline, Span};
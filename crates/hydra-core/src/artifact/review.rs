@@ -0,0 +1,131 @@
+//! Per-file review decisions on an agent's diff, so a reviewer can approve
+//! or reject individual files and merge only what they approved.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ArtifactError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileReviewState {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileReview {
+    pub state: FileReviewState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Review state for one agent in a run, keyed by repository-relative path.
+/// Files without an entry are pending.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentReview {
+    pub files: BTreeMap<String, FileReview>,
+}
+
+impl AgentReview {
+    /// Load the review at `path`; a missing file is an empty review.
+    pub fn read_from(path: &Path) -> Result<Self, ArtifactError> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => Ok(serde_json::from_str(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<(), ArtifactError> {
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Record a decision for `file`. Setting a file back to pending without
+    /// a note drops its entry.
+    pub fn set(&mut self, file: &str, state: FileReviewState, note: Option<String>) {
+        let note = note.filter(|n| !n.trim().is_empty());
+        if state == FileReviewState::Pending && note.is_none() {
+            self.files.remove(file);
+            return;
+        }
+        self.files.insert(
+            file.to_string(),
+            FileReview {
+                state,
+                note,
+                updated_at: Utc::now(),
+            },
+        );
+    }
+
+    pub fn approved_paths(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .filter(|(_, review)| review.state == FileReviewState::Approved)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn review_roundtrip_and_approved_paths() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("review.json");
+        assert_eq!(
+            AgentReview::read_from(&path).unwrap(),
+            AgentReview::default()
+        );
+
+        let mut review = AgentReview::default();
+        review.set("src/lib.rs", FileReviewState::Approved, None);
+        review.set(
+            "src/main.rs",
+            FileReviewState::Rejected,
+            Some("drops error handling".to_string()),
+        );
+        review.set("README.md", FileReviewState::Approved, None);
+        review.write_to(&path).unwrap();
+
+        let loaded = AgentReview::read_from(&path).unwrap();
+        assert_eq!(loaded, review);
+        assert_eq!(loaded.approved_paths(), vec!["README.md", "src/lib.rs"]);
+        assert_eq!(
+            loaded.files["src/main.rs"].note.as_deref(),
+            Some("drops error handling")
+        );
+    }
+
+    #[test]
+    fn pending_without_note_clears_entry() {
+        let mut review = AgentReview::default();
+        review.set("src/lib.rs", FileReviewState::Approved, None);
+        review.set(
+            "src/lib.rs",
+            FileReviewState::Pending,
+            Some("  ".to_string()),
+        );
+        assert!(review.files.is_empty());
+
+        review.set(
+            "src/lib.rs",
+            FileReviewState::Pending,
+            Some("check later".to_string()),
+        );
+        assert_eq!(review.files["src/lib.rs"].state, FileReviewState::Pending);
+    }
+}
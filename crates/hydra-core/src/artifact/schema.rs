@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashSet};
+
 use serde::{Deserialize, Serialize};
 
 use super::events::{EventKind, RunEvent};
@@ -7,6 +9,9 @@ use super::events::{EventKind, RunEvent};
 const CANCELLED_INDICATOR: &str = "cancelled";
 const TIMED_OUT_INDICATOR: &str = "timed out";
 
+/// Gaps between an agent's events at least this long count as idle time.
+const IDLE_GAP_MS: u64 = 10_000;
+
 /// Versioned event schema definition.
 /// All EventKind variants are enumerated here for stability guarantees.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,27 +53,55 @@ pub struct RunHealthMetrics {
     pub total_events: u32,
     pub orchestration_overhead_ms: Option<u64>,
     pub adapter_errors: u32,
+    #[serde(default)]
+    pub phases: PhaseTimings,
+    /// Idle-gap statistics by agent key.
+    #[serde(default)]
+    pub agent_idle: BTreeMap<String, IdleStats>,
+}
+
+/// Wall-clock time spent in each phase of a run. A phase is `None` when the
+/// events marking its start or end are missing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTimings {
+    /// Worktree creation and the baseline, up to `run_started`.
+    pub setup_ms: Option<u64>,
+    /// From `run_started` until scoring begins.
+    pub agents_ms: Option<u64>,
+    pub scoring_ms: Option<u64>,
+    /// Diff artifacts and worktree cleanup, after scoring.
+    pub cleanup_ms: Option<u64>,
+}
+
+/// Gaps between consecutive events of one agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdleStats {
+    pub max_gap_ms: u64,
+    /// Gaps of at least ten seconds.
+    pub idle_gaps: u32,
+    /// Sum of those gaps.
+    pub idle_ms: u64,
 }
 
 impl RunHealthMetrics {
-    /// Compute health metrics from a list of events.
+    /// Compute health metrics from a list of events: the run's
+    /// `events.jsonl`, optionally followed by the agents' own event logs.
     pub fn from_events(events: &[RunEvent]) -> Self {
         let total_events = events.len() as u32;
 
-        let agents_started = events
-            .iter()
-            .filter(|e| e.kind == EventKind::AgentStarted)
-            .count() as u32;
-
-        let agents_completed = events
-            .iter()
-            .filter(|e| e.kind == EventKind::AgentCompleted)
-            .count() as u32;
-
-        let agents_failed = events
-            .iter()
-            .filter(|e| e.kind == EventKind::AgentFailed)
-            .count() as u32;
+        // Agent logs repeat the start and finish events of the run log, so
+        // agents are counted by key.
+        let agents_with = |kind: EventKind| {
+            events
+                .iter()
+                .filter(|e| e.kind == kind)
+                .filter_map(|e| e.agent_key.as_deref())
+                .collect::<HashSet<_>>()
+                .len() as u32
+        };
+        let agents_started = agents_with(EventKind::AgentStarted);
+        let agents_completed = agents_with(EventKind::AgentCompleted);
+        let agents_failed = agents_with(EventKind::AgentFailed);
 
         let total_agents = agents_started;
 
@@ -91,7 +124,9 @@ impl RunHealthMetrics {
                             !s.contains(CANCELLED_INDICATOR) && !s.contains(TIMED_OUT_INDICATOR)
                         })
             })
-            .count() as u32;
+            .map(|e| e.agent_key.as_deref())
+            .collect::<HashSet<_>>()
+            .len() as u32;
 
         RunHealthMetrics {
             total_agents,
@@ -101,8 +136,61 @@ impl RunHealthMetrics {
             total_events,
             orchestration_overhead_ms,
             adapter_errors,
+            phases: compute_phases(events),
+            agent_idle: compute_idle(events),
+        }
+    }
+}
+
+fn compute_phases(events: &[RunEvent]) -> PhaseTimings {
+    let first = |kind: EventKind| events.iter().find(|e| e.kind == kind);
+    let run_started = first(EventKind::RunStarted);
+    let score_started = first(EventKind::ScoreStarted);
+    let score_finished = first(EventKind::ScoreFinished);
+    let run_finished = events
+        .iter()
+        .find(|e| matches!(e.kind, EventKind::RunCompleted | EventKind::RunFailed));
+
+    PhaseTimings {
+        setup_ms: run_started.and_then(|e| e.data.get("setup_ms")?.as_u64()),
+        agents_ms: between(run_started, score_started),
+        scoring_ms: between(score_started, score_finished),
+        cleanup_ms: between(score_finished, run_finished),
+    }
+}
+
+fn between(start: Option<&RunEvent>, end: Option<&RunEvent>) -> Option<u64> {
+    let elapsed = end?.timestamp.signed_duration_since(start?.timestamp);
+    Some(elapsed.num_milliseconds().max(0) as u64)
+}
+
+fn compute_idle(events: &[RunEvent]) -> BTreeMap<String, IdleStats> {
+    let mut timestamps: BTreeMap<String, Vec<_>> = BTreeMap::new();
+    // Scoring happens after the agent has stopped; the wait is not idling.
+    for event in events.iter().filter(|e| e.kind != EventKind::AgentScored) {
+        if let Some(key) = &event.agent_key {
+            timestamps
+                .entry(key.clone())
+                .or_default()
+                .push(event.timestamp);
         }
     }
+    timestamps
+        .into_iter()
+        .map(|(key, mut times)| {
+            times.sort();
+            let mut stats = IdleStats::default();
+            for pair in times.windows(2) {
+                let gap = pair[1].signed_duration_since(pair[0]).num_milliseconds() as u64;
+                stats.max_gap_ms = stats.max_gap_ms.max(gap);
+                if gap >= IDLE_GAP_MS {
+                    stats.idle_gaps += 1;
+                    stats.idle_ms += gap;
+                }
+            }
+            (key, stats)
+        })
+        .collect()
 }
 
 fn compute_overhead(events: &[RunEvent]) -> Option<u64> {
@@ -182,12 +270,62 @@ mod tests {
         assert_eq!(metrics.adapter_errors, 1);
     }
 
+    #[test]
+    fn health_metrics_phases_and_idle_gaps() {
+        let start = Utc::now();
+        let at = |secs: i64, kind: EventKind, agent_key: Option<&str>| RunEvent {
+            timestamp: start + chrono::Duration::seconds(secs),
+            ..make_event(kind, agent_key)
+        };
+        let mut events = vec![
+            RunEvent {
+                data: serde_json::json!({"setup_ms": 4200}),
+                ..at(0, EventKind::RunStarted, None)
+            },
+            at(1, EventKind::AgentStarted, Some("claude")),
+            at(60, EventKind::AgentCompleted, Some("claude")),
+            at(61, EventKind::ScoreStarted, None),
+            at(91, EventKind::ScoreFinished, None),
+            at(93, EventKind::RunCompleted, None),
+        ];
+        // The agent's own log repeats its start and finish.
+        events.extend([
+            at(1, EventKind::AgentStarted, Some("claude")),
+            at(5, EventKind::AgentStdout, Some("claude")),
+            at(45, EventKind::AgentStdout, Some("claude")),
+            at(60, EventKind::AgentCompleted, Some("claude")),
+        ]);
+
+        let metrics = RunHealthMetrics::from_events(&events);
+        assert_eq!(metrics.total_agents, 1);
+        assert_eq!(metrics.agents_completed, 1);
+        assert_eq!(
+            metrics.phases,
+            PhaseTimings {
+                setup_ms: Some(4200),
+                agents_ms: Some(61_000),
+                scoring_ms: Some(30_000),
+                cleanup_ms: Some(2_000),
+            }
+        );
+        assert_eq!(
+            metrics.agent_idle["claude"],
+            IdleStats {
+                max_gap_ms: 40_000,
+                idle_gaps: 2,
+                idle_ms: 55_000,
+            }
+        );
+    }
+
     #[test]
     fn health_metrics_empty_events() {
         let metrics = RunHealthMetrics::from_events(&[]);
         assert_eq!(metrics.total_agents, 0);
         assert_eq!(metrics.success_rate, 0.0);
         assert!(metrics.orchestration_overhead_ms.is_none());
+        assert_eq!(metrics.phases, PhaseTimings::default());
+        assert!(metrics.agent_idle.is_empty());
     }
 
     #[test]
@@ -0,0 +1,133 @@
+//! `agents/<key>/score.json`: an agent's score as written after ranking and
+//! read back by the merge gate, reports and the GUI.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ArtifactError, RunLayout};
+use crate::scoring::ranking::AgentScore;
+use crate::security::SecretRedactor;
+
+/// Contents of an agent's `score.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentScoreArtifact {
+    /// Missing from scores written before the file was versioned, which read
+    /// as version 0 and otherwise match version 1.
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub score: AgentScore,
+}
+
+impl AgentScoreArtifact {
+    pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+    pub fn new(score: AgentScore) -> Self {
+        Self {
+            schema_version: Self::CURRENT_SCHEMA_VERSION,
+            score,
+        }
+    }
+
+    pub fn write_to(&self, path: &Path, redactor: &SecretRedactor) -> Result<(), ArtifactError> {
+        std::fs::write(path, redactor.redact_json(self)?)?;
+        Ok(())
+    }
+
+    /// Read a score file. Files from a newer Hydra are refused rather than
+    /// read with fields silently dropped.
+    pub fn read_from(path: &Path) -> Result<Self, ArtifactError> {
+        let data = std::fs::read_to_string(path)?;
+        let artifact: Self = serde_json::from_str(&data)?;
+        if artifact.schema_version > Self::CURRENT_SCHEMA_VERSION {
+            return Err(ArtifactError::UnsupportedSchema {
+                path: path.display().to_string(),
+                version: artifact.schema_version,
+                supported: Self::CURRENT_SCHEMA_VERSION,
+            });
+        }
+        Ok(artifact)
+    }
+}
+
+/// The score recorded for `agent_key`, or `None` when it was not scored.
+pub fn read_agent_score(
+    layout: &RunLayout,
+    agent_key: &str,
+) -> Result<Option<AgentScore>, ArtifactError> {
+    let path = layout.agent_score(agent_key);
+    if !path.exists() {
+        return Ok(None);
+    }
+    AgentScoreArtifact::read_from(&path).map(|artifact| Some(artifact.score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::DimensionScore;
+
+    fn score() -> AgentScore {
+        AgentScore {
+            agent_key: "claude".to_string(),
+            dimensions: vec![DimensionScore {
+                name: "build".to_string(),
+                score: 100.0,
+                evidence: serde_json::json!({}),
+            }],
+            composite: 91.5,
+            mergeable: true,
+            gate_failures: Vec::new(),
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn round_trips_with_the_current_version() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let layout = RunLayout::new(tmp.path(), uuid::Uuid::new_v4());
+        layout.create_dirs(&["claude"]).unwrap();
+
+        assert!(read_agent_score(&layout, "claude").unwrap().is_none());
+        AgentScoreArtifact::new(score())
+            .write_to(&layout.agent_score("claude"), &SecretRedactor::new())
+            .unwrap();
+
+        let raw: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(layout.agent_score("claude")).unwrap())
+                .unwrap();
+        assert_eq!(raw["schema_version"], 1);
+        assert_eq!(raw["agent_key"], "claude");
+        let read = read_agent_score(&layout, "claude").unwrap().unwrap();
+        assert_eq!(read.composite, 91.5);
+        assert!(read.mergeable);
+    }
+
+    #[test]
+    fn reads_unversioned_scores_and_refuses_newer_ones() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("score.json");
+
+        // As written before versioning and before `partial` existed.
+        std::fs::write(
+            &path,
+            r#"{"agent_key":"codex","dimensions":[],"composite":40.0,"mergeable":false,"gate_failures":["tests regressed"]}"#,
+        )
+        .unwrap();
+        let old = AgentScoreArtifact::read_from(&path).unwrap();
+        assert_eq!(old.schema_version, 0);
+        assert_eq!(old.score.gate_failures, vec!["tests regressed"]);
+        assert!(!old.score.partial);
+
+        std::fs::write(
+            &path,
+            r#"{"schema_version":99,"agent_key":"codex","dimensions":[],"composite":40.0,"mergeable":false,"gate_failures":[]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            AgentScoreArtifact::read_from(&path),
+            Err(ArtifactError::UnsupportedSchema { version: 99, .. })
+        ));
+    }
+}
@@ -104,6 +104,9 @@ pub struct SessionMetadata {
     pub cwd: String,
     pub unsafe_mode: bool,
     pub experimental: bool,
+    /// User-defined labels from `--tag`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 impl SessionMetadata {
@@ -125,6 +128,7 @@ impl SessionMetadata {
             cwd: cwd.to_string(),
             unsafe_mode,
             experimental,
+            tags: Vec::new(),
         }
     }
 
@@ -179,6 +183,11 @@ impl SessionEventWriter {
         })
     }
 
+    pub fn with_redactor(mut self, redactor: SecretRedactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
     pub fn write_event(&mut self, event: &SessionEvent) -> Result<(), ArtifactError> {
         let line = serde_json::to_string(event)?;
         let redacted = self.redactor.redact_line(&line);
@@ -231,6 +240,11 @@ impl TranscriptWriter {
         })
     }
 
+    pub fn with_redactor(mut self, redactor: SecretRedactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
     pub fn append_output(&mut self, raw_bytes: &[u8]) -> Result<(), ArtifactError> {
         let text = String::from_utf8_lossy(raw_bytes);
         let redacted = self.redactor.redact_line(&text);
@@ -358,6 +372,22 @@ impl SessionArtifactWriter {
         Ok(writer)
     }
 
+    /// Redact events and the transcript with `redactor` from here on.
+    pub fn with_redactor(self, redactor: SecretRedactor) -> Self {
+        Self {
+            event_writer: self.event_writer.with_redactor(redactor.clone()),
+            transcript_writer: self.transcript_writer.with_redactor(redactor),
+            ..self
+        }
+    }
+
+    /// Label the session, rewriting `session.json`.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Result<Self, ArtifactError> {
+        self.metadata.tags = tags;
+        self.metadata.write_to(&self.layout.session_json_path())?;
+        Ok(self)
+    }
+
     pub fn record_output(&mut self, raw_bytes: &[u8]) -> Result<(), ArtifactError> {
         let text = String::from_utf8_lossy(raw_bytes);
         self.event_writer.write_event(&SessionEvent::new(
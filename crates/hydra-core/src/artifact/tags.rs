@@ -0,0 +1,67 @@
+//! User-defined labels on runs and sessions (`--tag backend`), used to
+//! filter listings and stats once the artifact store grows.
+
+const MAX_TAG_CHARS: usize = 64;
+
+/// Validate one tag: non-empty, at most 64 characters, and limited to ASCII
+/// letters, digits and `-_.:/` so tags stay easy to type and to filter on.
+pub fn parse_tag(raw: &str) -> Result<String, String> {
+    let tag = raw.trim();
+    if tag.is_empty() {
+        return Err("tag must not be empty".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_CHARS {
+        return Err(format!(
+            "tag '{tag}' is longer than {MAX_TAG_CHARS} characters"
+        ));
+    }
+    if let Some(c) = tag
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !"-_.:/".contains(*c))
+    {
+        return Err(format!(
+            "tag '{tag}' contains '{c}'; use letters, digits and -_.:/"
+        ));
+    }
+    Ok(tag.to_string())
+}
+
+/// Tags in first-seen order without duplicates.
+pub fn dedup_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        if !out.contains(&tag) {
+            out.push(tag);
+        }
+    }
+    out
+}
+
+/// Whether `tags` includes every tag in `wanted`. An empty filter matches
+/// everything.
+pub fn has_tags(tags: &[String], wanted: &[String]) -> bool {
+    wanted.iter().all(|w| tags.contains(w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tag_trims_and_rejects_odd_characters() {
+        assert_eq!(parse_tag(" sprint-42 "), Ok("sprint-42".to_string()));
+        assert_eq!(parse_tag("team/backend"), Ok("team/backend".to_string()));
+        assert!(parse_tag("").is_err());
+        assert!(parse_tag("two words").is_err());
+        assert!(parse_tag(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn filters_require_every_tag() {
+        let tags = dedup_tags(["a".to_string(), "b".to_string(), "a".to_string()]);
+        assert_eq!(tags, vec!["a", "b"]);
+        assert!(has_tags(&tags, &[]));
+        assert!(has_tags(&tags, &["b".to_string()]));
+        assert!(!has_tags(&tags, &["b".to_string(), "c".to_string()]));
+    }
+}
@@ -0,0 +1,353 @@
+//! Where a run spent its time, rebuilt from event timestamps: setup, each
+//! agent from spawn to first output to exit, its scoring checks, and the
+//! cleanup after scoring.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::events::{EventKind, RunEvent};
+
+/// A stretch of the run, in milliseconds since it began.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl Span {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms.saturating_sub(self.start_ms)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AgentTimeline {
+    pub agent_key: String,
+    /// Spawn until the agent stopped, or until the last event of a run that
+    /// is still going.
+    pub run: Span,
+    pub first_output_ms: Option<u64>,
+    /// `completed`, `failed`, `timed_out`, ...; `None` while running.
+    pub status: Option<String>,
+    pub scoring: Option<Span>,
+    /// Scoring checks in the order they ran.
+    pub checks: Vec<CheckTiming>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckTiming {
+    pub check: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunTimeline {
+    pub started_at: DateTime<Utc>,
+    pub total_ms: u64,
+    /// Worktree creation and the baseline, before `run_started`.
+    pub setup: Option<Span>,
+    pub agents: Vec<AgentTimeline>,
+    pub scoring: Option<Span>,
+    /// Diff artifacts and worktree cleanup, after scoring.
+    pub cleanup: Option<Span>,
+    pub finished: bool,
+}
+
+impl RunTimeline {
+    /// Build the timeline from the run's `events.jsonl` followed by the
+    /// agents' own logs. `None` without any events.
+    pub fn from_events(events: &[RunEvent]) -> Option<Self> {
+        let first = |kind: EventKind| {
+            events
+                .iter()
+                .filter(|e| e.kind == kind)
+                .min_by_key(|e| e.timestamp)
+        };
+        let run_started = first(EventKind::RunStarted);
+        let run_finished = events
+            .iter()
+            .filter(|e| matches!(e.kind, EventKind::RunCompleted | EventKind::RunFailed))
+            .min_by_key(|e| e.timestamp);
+
+        let setup_ms = run_started
+            .and_then(|e| e.data.get("setup_ms")?.as_u64())
+            .unwrap_or(0);
+        let started_at = match run_started {
+            Some(e) => e.timestamp - chrono::Duration::milliseconds(setup_ms as i64),
+            None => events.iter().map(|e| e.timestamp).min()?,
+        };
+        let offset = |at: DateTime<Utc>| {
+            at.signed_duration_since(started_at)
+                .num_milliseconds()
+                .max(0) as u64
+        };
+        let last_ms = events.iter().map(|e| offset(e.timestamp)).max()?;
+        let total_ms = run_finished.map_or(last_ms, |e| offset(e.timestamp));
+        let span = |start: Option<&RunEvent>, end: Option<&RunEvent>| {
+            Some(Span {
+                start_ms: offset(start?.timestamp),
+                end_ms: offset(end?.timestamp),
+            })
+        };
+
+        let mut keys: Vec<(&str, u64)> = Vec::new();
+        for event in events.iter().filter(|e| e.kind == EventKind::AgentStarted) {
+            let Some(key) = event.agent_key.as_deref() else {
+                continue;
+            };
+            let at = offset(event.timestamp);
+            match keys.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, start)) => *start = (*start).min(at),
+                None => keys.push((key, at)),
+            }
+        }
+        keys.sort_by_key(|(_, start)| *start);
+
+        let agents = keys
+            .into_iter()
+            .map(|(key, start_ms)| {
+                let of_agent = |kinds: &[EventKind]| {
+                    events
+                        .iter()
+                        .filter(|e| e.agent_key.as_deref() == Some(key) && kinds.contains(&e.kind))
+                        .min_by_key(|e| e.timestamp)
+                };
+                // Output from setup steps is not the agent responding.
+                let output = events
+                    .iter()
+                    .filter(|e| e.agent_key.as_deref() == Some(key))
+                    .filter(|e| matches!(e.kind, EventKind::AgentStdout | EventKind::AgentStderr))
+                    .filter(|e| e.data.get("step").is_none())
+                    .min_by_key(|e| e.timestamp);
+                let end = of_agent(&[EventKind::AgentCompleted, EventKind::AgentFailed]);
+                let scored = of_agent(&[EventKind::AgentScored]);
+                let scoring = scored.map(|e| {
+                    let end_ms = offset(e.timestamp);
+                    let took = e
+                        .data
+                        .get("duration_ms")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    Span {
+                        start_ms: end_ms.saturating_sub(took),
+                        end_ms,
+                    }
+                });
+                AgentTimeline {
+                    agent_key: key.to_string(),
+                    run: Span {
+                        start_ms,
+                        end_ms: end.map_or(total_ms, |e| offset(e.timestamp)),
+                    },
+                    first_output_ms: output.map(|e| offset(e.timestamp)),
+                    status: end.map(|end| agent_status(events, key, end)),
+                    checks: scored
+                        .zip(scoring)
+                        .map(|(e, span)| check_timings(e, span))
+                        .unwrap_or_default(),
+                    scoring,
+                }
+            })
+            .collect();
+
+        Some(Self {
+            started_at,
+            total_ms,
+            setup: run_started.map(|e| Span {
+                start_ms: 0,
+                end_ms: offset(e.timestamp),
+            }),
+            agents,
+            scoring: span(
+                first(EventKind::ScoreStarted),
+                first(EventKind::ScoreFinished),
+            ),
+            cleanup: span(first(EventKind::ScoreFinished), run_finished),
+            finished: run_finished.is_some(),
+        })
+    }
+}
+
+/// Checks run one after another from the start of scoring.
+fn check_timings(event: &RunEvent, scoring: Span) -> Vec<CheckTiming> {
+    let Some(checks) = event.data.get("checks").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    let mut cursor = scoring.start_ms;
+    checks
+        .iter()
+        .filter_map(|check| {
+            let name = check.get("check")?.as_str()?;
+            let took = check.get("duration_ms")?.as_u64()?;
+            let start_ms = cursor.min(scoring.end_ms);
+            cursor = start_ms + took;
+            Some(CheckTiming {
+                check: name.to_string(),
+                span: Span {
+                    start_ms,
+                    end_ms: cursor.min(scoring.end_ms),
+                },
+            })
+        })
+        .collect()
+}
+
+/// Run-level agent events record the status in `Debug` form (`TimedOut`);
+/// the agent's own log only by the kind of `end`.
+fn agent_status(events: &[RunEvent], key: &str, end: &RunEvent) -> String {
+    let recorded = events
+        .iter()
+        .filter(|e| matches!(e.kind, EventKind::AgentCompleted | EventKind::AgentFailed))
+        .filter(|e| e.agent_key.as_deref() == Some(key))
+        .find_map(|e| e.data.get("status")?.as_str());
+    match recorded {
+        Some(status) => {
+            let mut out = String::new();
+            for (i, c) in status.chars().enumerate() {
+                if c.is_ascii_uppercase() && i > 0 {
+                    out.push('_');
+                }
+                out.push(c.to_ascii_lowercase());
+            }
+            out
+        }
+        None if end.kind == EventKind::AgentCompleted => "completed".to_string(),
+        None => "failed".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(ms: i64, kind: EventKind, agent: Option<&str>, data: serde_json::Value) -> RunEvent {
+        RunEvent {
+            timestamp: DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::milliseconds(ms),
+            kind,
+            agent_key: agent.map(str::to_string),
+            data,
+        }
+    }
+
+    #[test]
+    fn timeline_places_agents_checks_and_phases() {
+        let events = vec![
+            at(
+                2_000,
+                EventKind::RunStarted,
+                None,
+                json!({ "setup_ms": 2_000 }),
+            ),
+            at(2_100, EventKind::AgentStarted, Some("claude"), json!({})),
+            at(2_200, EventKind::AgentStarted, Some("codex"), json!({})),
+            at(
+                60_000,
+                EventKind::AgentCompleted,
+                Some("claude"),
+                json!({ "status": "Completed" }),
+            ),
+            at(
+                90_000,
+                EventKind::AgentFailed,
+                Some("codex"),
+                json!({ "status": "TimedOut" }),
+            ),
+            at(90_100, EventKind::ScoreStarted, None, json!({})),
+            at(100_000, EventKind::ScoreFinished, None, json!({})),
+            at(101_000, EventKind::RunCompleted, None, json!({})),
+            // Agent logs.
+            at(5_000, EventKind::AgentStdout, Some("claude"), json!({})),
+            at(59_900, EventKind::AgentCompleted, Some("claude"), json!({})),
+            at(
+                99_000,
+                EventKind::AgentScored,
+                Some("claude"),
+                json!({
+                    "duration_ms": 8_000,
+                    "checks": [
+                        { "check": "build", "duration_ms": 5_000 },
+                        { "check": "tests", "duration_ms": 2_500 },
+                    ],
+                }),
+            ),
+        ];
+
+        let timeline = RunTimeline::from_events(&events).unwrap();
+        assert_eq!(timeline.total_ms, 101_000);
+        assert!(timeline.finished);
+        assert_eq!(
+            timeline.setup,
+            Some(Span {
+                start_ms: 0,
+                end_ms: 2_000
+            })
+        );
+        assert_eq!(timeline.scoring.unwrap().duration_ms(), 9_900);
+        assert_eq!(timeline.cleanup.unwrap().duration_ms(), 1_000);
+
+        let claude = &timeline.agents[0];
+        assert_eq!(claude.agent_key, "claude");
+        assert_eq!(
+            claude.run,
+            Span {
+                start_ms: 2_100,
+                end_ms: 59_900
+            }
+        );
+        assert_eq!(claude.first_output_ms, Some(5_000));
+        assert_eq!(claude.status.as_deref(), Some("completed"));
+        assert_eq!(
+            claude.scoring,
+            Some(Span {
+                start_ms: 91_000,
+                end_ms: 99_000
+            })
+        );
+        assert_eq!(
+            claude.checks,
+            vec![
+                CheckTiming {
+                    check: "build".into(),
+                    span: Span {
+                        start_ms: 91_000,
+                        end_ms: 96_000
+                    },
+                },
+                CheckTiming {
+                    check: "tests".into(),
+                    span: Span {
+                        start_ms: 96_000,
+                        end_ms: 98_500
+                    },
+                },
+            ]
+        );
+
+        let codex = &timeline.agents[1];
+        assert_eq!(codex.status.as_deref(), Some("timed_out"));
+        assert_eq!(codex.first_output_ms, None);
+        assert!(codex.checks.is_empty());
+    }
+
+    #[test]
+    fn unfinished_runs_end_at_the_last_event() {
+        let events = vec![
+            at(1_000, EventKind::RunStarted, None, json!({})),
+            at(1_500, EventKind::AgentStarted, Some("claude"), json!({})),
+            at(7_000, EventKind::AgentStdout, Some("claude"), json!({})),
+        ];
+        let timeline = RunTimeline::from_events(&events).unwrap();
+        assert!(!timeline.finished);
+        assert_eq!(timeline.total_ms, 6_000);
+        assert_eq!(
+            timeline.agents[0].run,
+            Span {
+                start_ms: 500,
+                end_ms: 6_000
+            }
+        );
+        assert_eq!(timeline.agents[0].status, None);
+        assert!(RunTimeline::from_events(&[]).is_none());
+    }
+}
@@ -0,0 +1,281 @@
+//! Zero-setup defaults for repositories without a `hydra.toml`.
+//!
+//! Without scoring commands every candidate scores the same on build, tests
+//! and lint, so a race in a fresh repository would only compare diff size.
+//! Instead the project's language, package manager and scripts are read from
+//! its manifests and turned into the commands a `hydra.toml` would set.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{CommandsConfig, HydraConfig};
+
+/// What autodetection inferred for a repository without a `hydra.toml`.
+/// Recorded in run manifests so results can be traced to the guess.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedProject {
+    /// `rust`, `typescript`, `javascript`, `python` or `go`.
+    pub language: String,
+    /// Tool the commands run through, e.g. `cargo`, `pnpm` or `uv`.
+    pub package_manager: String,
+    /// Manifest the guess was made from, relative to the repository root.
+    pub manifest: String,
+    pub commands: CommandsConfig,
+}
+
+impl HydraConfig {
+    /// Fill in scoring commands from [`detect_project`] when the config sets
+    /// neither a profile nor any command, recording the guess in `detected`.
+    pub fn autodetect(mut self, repo_root: &Path) -> Self {
+        let commands = &self.scoring.commands;
+        if self.scoring.profile.is_some()
+            || commands.build.is_some()
+            || commands.test.is_some()
+            || commands.lint.is_some()
+        {
+            return self;
+        }
+        if let Some(project) = detect_project(repo_root) {
+            tracing::debug!(
+                language = %project.language,
+                package_manager = %project.package_manager,
+                "no hydra.toml, using detected project commands"
+            );
+            self.scoring.commands = project.commands.clone();
+            self.detected = Some(project);
+        }
+        self
+    }
+}
+
+/// Infer build, test and lint commands from the manifests at `repo_root`.
+/// Checked in the order Cargo, npm-style, Python, Go; `None` when there is
+/// no recognised manifest.
+pub fn detect_project(repo_root: &Path) -> Option<DetectedProject> {
+    detect_rust(repo_root)
+        .or_else(|| detect_node(repo_root))
+        .or_else(|| detect_python(repo_root))
+        .or_else(|| detect_go(repo_root))
+}
+
+fn detect_rust(root: &Path) -> Option<DetectedProject> {
+    let manifest = read_toml(&root.join("Cargo.toml"))?;
+    let scope = if manifest.contains_key("workspace") {
+        " --workspace"
+    } else {
+        ""
+    };
+    Some(DetectedProject {
+        language: "rust".to_string(),
+        package_manager: "cargo".to_string(),
+        manifest: "Cargo.toml".to_string(),
+        commands: CommandsConfig {
+            build: Some(format!("cargo build{scope} --all-targets")),
+            test: Some(format!("cargo test{scope}")),
+            lint: Some(format!("cargo clippy{scope} --all-targets -- -D warnings")),
+        },
+    })
+}
+
+/// The script `npm init` writes when a package has no tests.
+const NPM_PLACEHOLDER_TEST: &str = "no test specified";
+
+fn detect_node(root: &Path) -> Option<DetectedProject> {
+    let data = std::fs::read_to_string(root.join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&data).ok()?;
+
+    // `packageManager` (Corepack) wins over lockfiles.
+    let declared = package
+        .get("packageManager")
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.split('@').next())
+        .filter(|pm| ["npm", "pnpm", "yarn", "bun"].contains(pm));
+    let package_manager = declared
+        .or_else(|| {
+            [
+                ("pnpm-lock.yaml", "pnpm"),
+                ("yarn.lock", "yarn"),
+                ("bun.lock", "bun"),
+                ("bun.lockb", "bun"),
+            ]
+            .iter()
+            .find(|(lockfile, _)| root.join(lockfile).exists())
+            .map(|(_, pm)| *pm)
+        })
+        .unwrap_or("npm");
+
+    let script = |name: &str| {
+        package
+            .get("scripts")
+            .and_then(|s| s.get(name))
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.contains(NPM_PLACEHOLDER_TEST))
+            .map(|_| format!("{package_manager} run {name}"))
+    };
+    let language = if root.join("tsconfig.json").exists() {
+        "typescript"
+    } else {
+        "javascript"
+    };
+    Some(DetectedProject {
+        language: language.to_string(),
+        package_manager: package_manager.to_string(),
+        manifest: "package.json".to_string(),
+        commands: CommandsConfig {
+            build: script("build"),
+            test: script("test"),
+            lint: script("lint"),
+        },
+    })
+}
+
+fn detect_python(root: &Path) -> Option<DetectedProject> {
+    let manifest = ["pyproject.toml", "setup.py", "requirements.txt"]
+        .into_iter()
+        .find(|f| root.join(f).exists())?;
+    let pyproject = read_toml(&root.join("pyproject.toml")).unwrap_or_default();
+    let tool = pyproject.get("tool").and_then(|t| t.as_table());
+    let uses_tool = |name: &str| tool.is_some_and(|t| t.contains_key(name));
+
+    let package_manager = if root.join("uv.lock").exists() || uses_tool("uv") {
+        "uv"
+    } else if root.join("poetry.lock").exists() || uses_tool("poetry") {
+        "poetry"
+    } else if root.join("pdm.lock").exists() || uses_tool("pdm") {
+        "pdm"
+    } else {
+        "pip"
+    };
+    let run = match package_manager {
+        "pip" => String::new(),
+        pm => format!("{pm} run "),
+    };
+    let has_ruff =
+        uses_tool("ruff") || root.join("ruff.toml").exists() || root.join(".ruff.toml").exists();
+    Some(DetectedProject {
+        language: "python".to_string(),
+        package_manager: package_manager.to_string(),
+        manifest: manifest.to_string(),
+        commands: CommandsConfig {
+            build: None,
+            test: Some(format!("{run}pytest -q")),
+            lint: has_ruff.then(|| format!("{run}ruff check .")),
+        },
+    })
+}
+
+fn detect_go(root: &Path) -> Option<DetectedProject> {
+    if !root.join("go.mod").is_file() {
+        return None;
+    }
+    Some(DetectedProject {
+        language: "go".to_string(),
+        package_manager: "go".to_string(),
+        manifest: "go.mod".to_string(),
+        commands: CommandsConfig {
+            build: Some("go build ./...".to_string()),
+            test: Some("go test ./...".to_string()),
+            lint: Some("go vet ./...".to_string()),
+        },
+    })
+}
+
+/// A TOML manifest's top-level table; an empty table when it does not parse,
+/// since a broken manifest still says what kind of project this is.
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    let data = std::fs::read_to_string(path).ok()?;
+    Some(data.parse().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, name: &str, contents: &str) {
+        std::fs::write(root.join(name), contents).unwrap();
+    }
+
+    #[test]
+    fn detects_rust_workspaces_and_crates() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(detect_project(tmp.path()), None);
+
+        write(tmp.path(), "Cargo.toml", "[package]\nname = \"x\"\n");
+        let crate_project = detect_project(tmp.path()).unwrap();
+        assert_eq!(crate_project.language, "rust");
+        assert_eq!(crate_project.commands.test.as_deref(), Some("cargo test"));
+
+        write(tmp.path(), "Cargo.toml", "[workspace]\nmembers = []\n");
+        let workspace = detect_project(tmp.path()).unwrap();
+        assert_eq!(
+            workspace.commands.build.as_deref(),
+            Some("cargo build --workspace --all-targets")
+        );
+    }
+
+    #[test]
+    fn node_commands_follow_scripts_and_package_manager() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(
+            tmp.path(),
+            "package.json",
+            r#"{"scripts": {"build": "tsc", "test": "echo \"Error: no test specified\" && exit 1", "lint": "eslint ."}}"#,
+        );
+        write(tmp.path(), "pnpm-lock.yaml", "");
+        write(tmp.path(), "tsconfig.json", "{}");
+        let project = detect_project(tmp.path()).unwrap();
+        assert_eq!(project.language, "typescript");
+        assert_eq!(project.package_manager, "pnpm");
+        assert_eq!(project.commands.build.as_deref(), Some("pnpm run build"));
+        assert_eq!(project.commands.test, None);
+        assert_eq!(project.commands.lint.as_deref(), Some("pnpm run lint"));
+
+        write(
+            tmp.path(),
+            "package.json",
+            r#"{"packageManager": "yarn@4.1.0", "scripts": {"test": "vitest"}}"#,
+        );
+        let project = detect_project(tmp.path()).unwrap();
+        assert_eq!(project.package_manager, "yarn");
+        assert_eq!(project.commands.test.as_deref(), Some("yarn run test"));
+        assert_eq!(project.commands.build, None);
+    }
+
+    #[test]
+    fn python_commands_run_through_the_project_tool() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(
+            tmp.path(),
+            "pyproject.toml",
+            "[project]\nname = \"x\"\n\n[tool.ruff]\nline-length = 100\n",
+        );
+        write(tmp.path(), "uv.lock", "");
+        let project = detect_project(tmp.path()).unwrap();
+        assert_eq!(project.package_manager, "uv");
+        assert_eq!(project.commands.test.as_deref(), Some("uv run pytest -q"));
+        assert_eq!(
+            project.commands.lint.as_deref(),
+            Some("uv run ruff check .")
+        );
+    }
+
+    #[test]
+    fn autodetect_leaves_configured_commands_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(tmp.path(), "go.mod", "module example.com/x\n");
+
+        let detected = HydraConfig::default().autodetect(tmp.path());
+        assert_eq!(detected.detected.as_ref().unwrap().language, "go");
+        assert_eq!(
+            detected.scoring.commands.test.as_deref(),
+            Some("go test ./...")
+        );
+
+        let mut configured = HydraConfig::default();
+        configured.scoring.commands.test = Some("make check".to_string());
+        let configured = configured.autodetect(tmp.path());
+        assert_eq!(configured.detected, None);
+        assert_eq!(configured.scoring.commands.build, None);
+    }
+}
@@ -1,13 +1,22 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use thiserror::Error;
 
+use crate::security::NetworkAccess;
+
+mod detect;
 mod schema;
 
+pub use detect::{detect_project, DetectedProject};
+
 pub use schema::{
-    AdaptersConfig, BudgetConfig, CommandsConfig, DiffScopeConfig, GatesConfig, HydraConfig,
-    RetentionPolicy, ScoringConfig, ScoringProfile, SupervisorConfig, WeightsConfig,
-    WorktreeConfig,
+    AdaptersConfig, ApprovalConfig, ArtifactsConfig, BaseFreshnessPolicy, BudgetConfig,
+    BuildCacheConfig, BuildCacheMode, CommandsConfig, ContainerConfig, DiffScopeConfig,
+    GatesConfig, HydraConfig, IsolationMode, ModelPrice, NotificationsConfig, NotifyOn,
+    PricingConfig, PromptGuardConfig, PromptGuardPattern, RaceConfig, RedactionConfig,
+    RedactionPattern, RetentionPolicy, ScoringConfig, ScoringProfile, SecurityConfig,
+    SupervisorConfig, UiConfig, WebhookConfig, WebhookKind, WeightsConfig, WorktreeConfig,
+    WritePolicyConfig,
 };
 
 #[derive(Debug, Error)]
@@ -22,7 +31,32 @@ pub enum ConfigError {
     ParseFailed(#[from] toml::de::Error),
 
     #[error("config validation error: {message}")]
-    Validation { message: String },
+    Validation {
+        /// Dotted path of the offending key, e.g. `supervisor.hard_timeout_seconds`.
+        field: String,
+        message: String,
+    },
+
+    #[error("failed to write config file '{path}': {source}")]
+    WriteFailed {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("failed to serialize config: {0}")]
+    SerializeFailed(#[from] toml::ser::Error),
+}
+
+/// File name looked up by [`discover_config`].
+pub const CONFIG_FILE_NAME: &str = "hydra.toml";
+
+/// Find `hydra.toml` in `start` or the nearest ancestor directory, the way
+/// git finds `.git`.
+pub fn discover_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|candidate| candidate.is_file())
 }
 
 /// Load and validate a `HydraConfig` from a TOML file path.
@@ -42,6 +76,18 @@ pub fn load_config(path: &Path) -> Result<HydraConfig, ConfigError> {
     parse_config(&data)
 }
 
+impl HydraConfig {
+    /// Load `path`, or when it does not exist, defaults with scoring commands
+    /// detected from the project at `repo_root`.
+    pub fn load_or_default(path: &Path, repo_root: &Path) -> Result<Self, ConfigError> {
+        if path.exists() {
+            load_config(path)
+        } else {
+            Ok(HydraConfig::default().autodetect(repo_root))
+        }
+    }
+}
+
 /// Parse and validate a `HydraConfig` from a TOML string.
 pub fn parse_config(data: &str) -> Result<HydraConfig, ConfigError> {
     let config: HydraConfig = toml::from_str(data)?;
@@ -49,17 +95,90 @@ pub fn parse_config(data: &str) -> Result<HydraConfig, ConfigError> {
     Ok(config)
 }
 
+/// Validate a `HydraConfig` and render it as TOML suitable for `hydra.toml`.
+pub fn render_config(config: &HydraConfig) -> Result<String, ConfigError> {
+    validate(config)?;
+    Ok(toml::to_string_pretty(config)?)
+}
+
+/// Layer a partial `hydra.toml` table over `base` and validate the result.
+///
+/// Nested tables merge key by key; any other value replaces the base value.
+pub fn apply_overrides(
+    base: &HydraConfig,
+    overrides: toml::Table,
+) -> Result<HydraConfig, ConfigError> {
+    let mut merged = toml::Value::try_from(base)?;
+    if let toml::Value::Table(table) = &mut merged {
+        merge_tables(table, overrides);
+    }
+    let mut config: HydraConfig = merged.try_into()?;
+    validate(&config)?;
+    // Detected commands stay on record for as long as they are the ones used.
+    if config.scoring.commands == base.scoring.commands && config.scoring.profile.is_none() {
+        config.detected = base.detected.clone();
+    }
+    Ok(config)
+}
+
+/// Merge `overrides` into the TOML file at `path` and write it back.
+///
+/// Only keys already in the file or in `overrides` are written, so defaults
+/// stay implicit. The result is validated before anything is written and
+/// replaces the file atomically; comments in the file are not preserved.
+pub fn update_config_file(path: &Path, overrides: toml::Table) -> Result<HydraConfig, ConfigError> {
+    let mut table: toml::Table = if path.exists() {
+        let data = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadFailed {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        toml::from_str(&data)?
+    } else {
+        toml::Table::new()
+    };
+    merge_tables(&mut table, overrides);
+
+    let rendered = toml::to_string_pretty(&table)?;
+    let config = parse_config(&rendered)?;
+
+    let write_failed = |source| ConfigError::WriteFailed {
+        path: path.display().to_string(),
+        source,
+    };
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, rendered).map_err(write_failed)?;
+    std::fs::rename(&tmp, path).map_err(write_failed)?;
+    Ok(config)
+}
+
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_tables(existing, incoming);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 fn validate(config: &HydraConfig) -> Result<(), ConfigError> {
     let w = &config.scoring.weights;
     let total = w.build + w.tests + w.lint + w.diff_scope + w.speed;
     if total == 0 {
         return Err(ConfigError::Validation {
+            field: "scoring.weights".to_string(),
             message: "scoring weights must not all be zero".to_string(),
         });
     }
 
     if config.scoring.gates.max_test_regression_percent > 100.0 {
         return Err(ConfigError::Validation {
+            field: "scoring.gates.max_test_regression_percent".to_string(),
             message: format!(
                 "max_test_regression_percent must be 0..=100, got {}",
                 config.scoring.gates.max_test_regression_percent
@@ -67,33 +186,284 @@ fn validate(config: &HydraConfig) -> Result<(), ConfigError> {
         });
     }
 
+    if let Some(dir) = config.scoring.build_cache.dirs.iter().find(|dir| {
+        let path = std::path::Path::new(dir.as_str());
+        dir.is_empty()
+            || path.is_absolute()
+            || path
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    }) {
+        return Err(ConfigError::Validation {
+            field: "scoring.build_cache.dirs".to_string(),
+            message: format!("'{dir}' must be a plain path relative to the worktree root"),
+        });
+    }
+
     if config.supervisor.hard_timeout_seconds == 0 {
         return Err(ConfigError::Validation {
+            field: "supervisor.hard_timeout_seconds".to_string(),
             message: "supervisor.hard_timeout_seconds must be > 0".to_string(),
         });
     }
 
     if config.supervisor.idle_timeout_seconds == 0 {
         return Err(ConfigError::Validation {
+            field: "supervisor.idle_timeout_seconds".to_string(),
             message: "supervisor.idle_timeout_seconds must be > 0".to_string(),
         });
     }
 
+    for (name, limit) in [
+        ("max_diff_file_bytes", config.artifacts.max_diff_file_bytes),
+        (
+            "max_diff_total_bytes",
+            config.artifacts.max_diff_total_bytes,
+        ),
+    ] {
+        if limit == 0 {
+            return Err(ConfigError::Validation {
+                field: format!("artifacts.{name}"),
+                message: format!("artifacts.{name} must be > 0"),
+            });
+        }
+    }
+
+    for key in &config.race.default_agents {
+        crate::git_ref::validate_agent_key(key).map_err(|e| ConfigError::Validation {
+            field: "race.default_agents".to_string(),
+            message: format!("race.default_agents: invalid agent key '{key}': {e}"),
+        })?;
+    }
+    if !(0.0..=100.0).contains(&config.race.success_threshold) {
+        return Err(ConfigError::Validation {
+            field: "race.success_threshold".to_string(),
+            message: "race.success_threshold must be between 0 and 100".to_string(),
+        });
+    }
+
     if let Some(max_tokens_total) = config.scoring.budget.max_tokens_total {
         if max_tokens_total == 0 {
             return Err(ConfigError::Validation {
+                field: "scoring.budget.max_tokens_total".to_string(),
                 message: "scoring.budget.max_tokens_total must be > 0".to_string(),
             });
         }
     }
+    if let Some(percent) = config
+        .scoring
+        .budget
+        .warn_at_percent
+        .iter()
+        .find(|p| !(1..=99).contains(*p))
+    {
+        return Err(ConfigError::Validation {
+            field: "scoring.budget.warn_at_percent".to_string(),
+            message: format!(
+                "scoring.budget.warn_at_percent must be between 1 and 99, got {percent}"
+            ),
+        });
+    }
+
+    let budget = &config.scoring.budget;
+    for (name, limit) in [
+        ("max_cost_usd", budget.max_cost_usd),
+        ("max_cost_usd_per_day", budget.max_cost_usd_per_day),
+        ("max_cost_usd_per_month", budget.max_cost_usd_per_month),
+    ] {
+        if let Some(limit) = limit {
+            if !limit.is_finite() || limit < 0.0 {
+                return Err(ConfigError::Validation {
+                    field: format!("scoring.budget.{name}"),
+                    message: format!("scoring.budget.{name} must be a finite number >= 0"),
+                });
+            }
+        }
+    }
+
+    if config.security.allow_network == NetworkAccess::Allowlist {
+        if config.security.network_allowlist.is_empty() {
+            return Err(ConfigError::Validation {
+                field: "security.network_allowlist".to_string(),
+                message: "security.network_allowlist must not be empty when allow_network is \"allowlist\""
+                    .to_string(),
+            });
+        }
+        if config.security.isolation == IsolationMode::Container {
+            return Err(ConfigError::Validation {
+                field: "security.allow_network".to_string(),
+                message:
+                    "allow_network = \"allowlist\" is not supported with container isolation; \
+                          set security.container.network to a restricted network instead"
+                        .to_string(),
+            });
+        }
+    }
+
+    let redaction = &config.security.redaction;
+    for pattern in &redaction.patterns {
+        if pattern.name.is_empty()
+            || !pattern
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(ConfigError::Validation {
+                field: "security.redaction.patterns".to_string(),
+                message: format!(
+                    "redaction pattern name '{}' must be non-empty and use only letters, digits and '_'",
+                    pattern.name
+                ),
+            });
+        }
+        if let Err(e) = regex::Regex::new(&pattern.regex) {
+            return Err(ConfigError::Validation {
+                field: "security.redaction.patterns".to_string(),
+                message: format!(
+                    "redaction pattern '{}' is not a valid regex: {e}",
+                    pattern.name
+                ),
+            });
+        }
+    }
+    if !(1.0..=8.0).contains(&redaction.entropy_threshold) {
+        return Err(ConfigError::Validation {
+            field: "security.redaction.entropy_threshold".to_string(),
+            message: "security.redaction.entropy_threshold must be between 1 and 8 bits"
+                .to_string(),
+        });
+    }
+    if redaction.entropy_min_length < 8 {
+        return Err(ConfigError::Validation {
+            field: "security.redaction.entropy_min_length".to_string(),
+            message: "security.redaction.entropy_min_length must be >= 8".to_string(),
+        });
+    }
+
+    if config.security.isolation == IsolationMode::Container
+        && config
+            .security
+            .container
+            .image
+            .as_deref()
+            .is_none_or(|image| image.trim().is_empty())
+    {
+        return Err(ConfigError::Validation {
+            field: "security.container.image".to_string(),
+            message:
+                "security.container.image must be set when security.isolation is \"container\""
+                    .to_string(),
+        });
+    }
+
+    if config.security.unsafe_consent_minutes == 0 {
+        return Err(ConfigError::Validation {
+            field: "security.unsafe_consent_minutes".to_string(),
+            message: "security.unsafe_consent_minutes must be > 0".to_string(),
+        });
+    }
+
+    for pattern in &config.security.prompt_guard.patterns {
+        if pattern.name.trim().is_empty() {
+            return Err(ConfigError::Validation {
+                field: "security.prompt_guard.patterns".to_string(),
+                message: "prompt guard pattern names must not be empty".to_string(),
+            });
+        }
+        if let Err(e) = regex::Regex::new(&pattern.regex) {
+            return Err(ConfigError::Validation {
+                field: "security.prompt_guard.patterns".to_string(),
+                message: format!(
+                    "prompt guard pattern '{}' is not a valid regex: {e}",
+                    pattern.name
+                ),
+            });
+        }
+    }
+
+    let write_policy = &config.security.write_policy;
+    for (field, globs) in [
+        ("security.write_policy.read_only", &write_policy.read_only),
+        ("security.write_policy.forbidden", &write_policy.forbidden),
+    ] {
+        if let Some(glob) = globs
+            .iter()
+            .find(|g| g.trim().is_empty() || g.starts_with('/') || g.split('/').any(|c| c == ".."))
+        {
+            return Err(ConfigError::Validation {
+                field: field.to_string(),
+                message: format!(
+                    "{field}: '{glob}' must be a non-empty glob relative to the repository root"
+                ),
+            });
+        }
+    }
+
+    if let Some(locale) = &config.ui.locale {
+        if crate::i18n::Locale::parse(locale).is_none() {
+            let known: Vec<&str> = crate::i18n::Locale::ALL.iter().map(|l| l.tag()).collect();
+            return Err(ConfigError::Validation {
+                field: "ui.locale".to_string(),
+                message: format!(
+                    "no messages for locale '{locale}'; available: {}",
+                    known.join(", ")
+                ),
+            });
+        }
+    }
 
-    if let Some(max_cost_usd) = config.scoring.budget.max_cost_usd {
-        if !max_cost_usd.is_finite() || max_cost_usd < 0.0 {
+    for (agent, model) in &config.pricing.agents {
+        if crate::scoring::pricing::model_price(&config.pricing, model).is_none() {
             return Err(ConfigError::Validation {
-                message: "scoring.budget.max_cost_usd must be a finite number >= 0".to_string(),
+                field: format!("pricing.agents.{agent}"),
+                message: format!(
+                    "no price known for model '{model}'; add it under [pricing.models]"
+                ),
             });
         }
     }
+    for (model, price) in &config.pricing.models {
+        let rates = [
+            Some(price.input_per_mtok),
+            Some(price.output_per_mtok),
+            price.cached_input_per_mtok,
+        ];
+        if rates
+            .into_iter()
+            .flatten()
+            .any(|r| !r.is_finite() || r < 0.0)
+        {
+            return Err(ConfigError::Validation {
+                field: format!("pricing.models.{model}"),
+                message: "model prices must be finite numbers >= 0".to_string(),
+            });
+        }
+    }
+
+    for webhook in &config.notifications.webhooks {
+        let field = "notifications.webhooks".to_string();
+        match (&webhook.url, &webhook.url_env) {
+            (Some(_), Some(_)) | (None, None) => {
+                return Err(ConfigError::Validation {
+                    field,
+                    message: "each webhook needs exactly one of url or url_env".to_string(),
+                });
+            }
+            (Some(url), None) if !url.starts_with("https://") && !url.starts_with("http://") => {
+                return Err(ConfigError::Validation {
+                    field,
+                    message: format!("webhook url '{url}' must be an http(s) URL"),
+                });
+            }
+            (None, Some(env)) if env.trim().is_empty() => {
+                return Err(ConfigError::Validation {
+                    field,
+                    message: "webhook url_env must name an environment variable".to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
 
     Ok(())
 }
@@ -101,6 +471,7 @@ fn validate(config: &HydraConfig) -> Result<(), ConfigError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::security::ApprovalPolicy;
 
     #[test]
     fn default_config_passes_validation() {
@@ -108,6 +479,32 @@ mod tests {
         validate(&config).unwrap();
     }
 
+    #[test]
+    fn discover_config_walks_up_to_nearest_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let nested = tmp.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        std::fs::write(tmp.path().join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            discover_config(&nested),
+            Some(tmp.path().join(CONFIG_FILE_NAME))
+        );
+
+        std::fs::write(tmp.path().join("a").join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            discover_config(&nested),
+            Some(tmp.path().join("a").join(CONFIG_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn rendered_default_config_round_trips() {
+        let rendered = render_config(&HydraConfig::default()).unwrap();
+        let parsed = parse_config(&rendered).unwrap();
+        assert_eq!(parsed, HydraConfig::default());
+    }
+
     #[test]
     fn minimal_toml_parses_to_defaults() {
         let config = parse_config("").unwrap();
@@ -119,12 +516,80 @@ mod tests {
         assert!(config.scoring.gates.require_build_pass);
     }
 
+    #[test]
+    fn overrides_merge_nested_tables_and_revalidate() {
+        let base = HydraConfig::default();
+        let overrides: toml::Table = toml::from_str(
+            r#"
+[scoring.weights]
+speed = 0
+
+[supervisor]
+hard_timeout_seconds = 60
+"#,
+        )
+        .unwrap();
+
+        let merged = apply_overrides(&base, overrides).unwrap();
+        assert_eq!(merged.scoring.weights.speed, 0);
+        assert_eq!(merged.scoring.weights.build, base.scoring.weights.build);
+        assert_eq!(merged.supervisor.hard_timeout_seconds, 60);
+        assert_eq!(
+            merged.supervisor.idle_timeout_seconds,
+            base.supervisor.idle_timeout_seconds
+        );
+
+        let invalid: toml::Table =
+            toml::from_str("[supervisor]\nhard_timeout_seconds = 0\n").unwrap();
+        assert!(apply_overrides(&base, invalid).is_err());
+        let unknown: toml::Table = toml::from_str("[scoring]\nbogus = 1\n").unwrap();
+        assert!(apply_overrides(&base, unknown).is_err());
+    }
+
+    #[test]
+    fn update_config_file_merges_and_keeps_file_minimal() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "[race]\ndefault_agents = [\"claude\"]\n").unwrap();
+
+        let overrides: toml::Table =
+            toml::from_str("[supervisor]\nhard_timeout_seconds = 60\n").unwrap();
+        let config = update_config_file(&path, overrides).unwrap();
+        assert_eq!(config.supervisor.hard_timeout_seconds, 60);
+        assert_eq!(config.race.default_agents, vec!["claude"]);
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("hard_timeout_seconds = 60"));
+        assert!(!written.contains("idle_timeout_seconds"));
+        assert!(!tmp.path().join("hydra.toml.tmp").exists());
+    }
+
+    #[test]
+    fn update_config_file_leaves_file_untouched_when_invalid() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "[race]\ndefault_agents = [\"claude\"]\n").unwrap();
+
+        let overrides: toml::Table =
+            toml::from_str("[supervisor]\nhard_timeout_seconds = 0\n").unwrap();
+        let err = update_config_file(&path, overrides).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::Validation { ref field, .. } if field == "supervisor.hard_timeout_seconds"
+        ));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[race]\ndefault_agents = [\"claude\"]\n"
+        );
+    }
+
     #[test]
     fn full_example_config_parses() {
         let data = r#"
 [scoring]
 profile = "js-node"
 timeout_per_check_seconds = 300
+score_timed_out = true
 
 [scoring.weights]
 build = 30
@@ -142,6 +607,10 @@ max_files_soft = 20
 max_churn_soft = 800
 protected_paths = ["infra/", "scripts/release/"]
 
+[scoring.build_cache]
+mode = "clone"
+dirs = ["target", "node_modules"]
+
 [adapters]
 claude = "/opt/claude"
 codex = "/opt/codex"
@@ -160,12 +629,19 @@ output_buffer_bytes = 10485760
         let config = parse_config(data).unwrap();
         assert_eq!(config.scoring.profile, Some(ScoringProfile::JsNode));
         assert_eq!(config.scoring.timeout_per_check_seconds, 300);
+        assert!(config.scoring.score_timed_out);
         assert_eq!(config.scoring.diff_scope.max_files_soft, 20);
         assert_eq!(config.scoring.diff_scope.max_churn_soft, 800);
         assert_eq!(
             config.scoring.diff_scope.protected_paths,
             vec!["infra/", "scripts/release/"]
         );
+        assert_eq!(config.scoring.build_cache.mode, BuildCacheMode::Clone);
+        assert_eq!(
+            config.scoring.build_cache.dirs,
+            vec!["target", "node_modules"]
+        );
+        assert!(parse_config("[scoring.build_cache]\ndirs = [\"../shared\"]\n").is_err());
         assert_eq!(config.adapters.claude.as_deref(), Some("/opt/claude"));
         assert_eq!(config.adapters.codex.as_deref(), Some("/opt/codex"));
         assert_eq!(config.adapters.cursor.as_deref(), Some("/opt/cursor-agent"));
@@ -175,6 +651,39 @@ output_buffer_bytes = 10485760
         assert_eq!(config.supervisor.output_buffer_bytes, 10_485_760);
     }
 
+    #[test]
+    fn webhooks_need_exactly_one_url_source() {
+        let config = parse_config(
+            r#"
+[[notifications.webhooks]]
+kind = "slack"
+url_env = "HYDRA_SLACK_WEBHOOK"
+
+[[notifications.webhooks]]
+kind = "discord"
+url = "https://discord.com/api/webhooks/1/abc"
+on = "failure"
+"#,
+        )
+        .unwrap();
+        let webhooks = &config.notifications.webhooks;
+        assert_eq!(webhooks[0].kind, WebhookKind::Slack);
+        assert_eq!(webhooks[0].on, NotifyOn::Always);
+        assert_eq!(webhooks[1].on, NotifyOn::Failure);
+
+        for invalid in [
+            "kind = \"slack\"",
+            "kind = \"slack\"\nurl = \"https://x\"\nurl_env = \"X\"",
+            "kind = \"slack\"\nurl = \"hooks.slack.com/services/x\"",
+        ] {
+            let err = parse_config(&format!("[[notifications.webhooks]]\n{invalid}")).unwrap_err();
+            assert!(
+                matches!(err, ConfigError::Validation { ref field, .. } if field == "notifications.webhooks"),
+                "{invalid}: {err}"
+            );
+        }
+    }
+
     #[test]
     fn partial_config_fills_defaults() {
         let data = r#"
@@ -275,6 +784,19 @@ max_cost_usd = 4.5
         assert_eq!(config.scoring.budget.max_cost_usd, Some(4.5));
     }
 
+    #[test]
+    fn race_default_agents_parse_and_validate() {
+        let config = parse_config("[race]\ndefault_agents = [\"claude\", \"codex\"]\n").unwrap();
+        assert_eq!(config.race.default_agents, vec!["claude", "codex"]);
+        assert!(HydraConfig::default().race.default_agents.is_empty());
+
+        let err = parse_config("[race]\ndefault_agents = [\"../x\"]\n").unwrap_err();
+        assert!(matches!(err, ConfigError::Validation { .. }));
+
+        let err = parse_config("[race]\nsuccess_threshold = 120.0\n").unwrap_err();
+        assert!(matches!(err, ConfigError::Validation { .. }));
+    }
+
     #[test]
     fn zero_max_tokens_budget_rejected() {
         let data = r#"
@@ -285,6 +807,24 @@ max_tokens_total = 0
         assert!(err.to_string().contains("max_tokens_total"));
     }
 
+    #[test]
+    fn zero_diff_size_limit_rejected() {
+        let err = parse_config("[artifacts]\nmax_diff_file_bytes = 0\n").unwrap_err();
+        assert!(err.to_string().contains("max_diff_file_bytes"));
+    }
+
+    #[test]
+    fn budget_warnings_default_and_validate() {
+        let budget = HydraConfig::default().scoring.budget;
+        assert_eq!(
+            (budget.warn_at_percent, budget.grace_seconds),
+            (vec![80], 30)
+        );
+
+        let err = parse_config("[scoring.budget]\nwarn_at_percent = [50, 100]\n").unwrap_err();
+        assert!(err.to_string().contains("warn_at_percent"));
+    }
+
     #[test]
     fn negative_max_cost_budget_rejected() {
         let data = r#"
@@ -294,4 +834,172 @@ max_cost_usd = -0.1
         let err = parse_config(data).unwrap_err();
         assert!(err.to_string().contains("max_cost_usd"));
     }
+
+    #[test]
+    fn pricing_tables_parse_and_validate() {
+        let data = r#"
+[scoring.budget]
+max_cost_usd_per_day = 20.0
+max_cost_usd_per_month = 300.0
+
+[pricing.agents]
+claude = "claude-opus-4"
+cursor-agent = "house-model"
+
+[pricing.models.house-model]
+input_per_mtok = 2.0
+output_per_mtok = 8.0
+"#;
+        let config = parse_config(data).unwrap();
+        assert_eq!(config.scoring.budget.max_cost_usd_per_day, Some(20.0));
+        assert_eq!(config.pricing.agents["claude"], "claude-opus-4");
+        assert_eq!(config.pricing.models["house-model"].output_per_mtok, 8.0);
+
+        let err = parse_config(
+            "[pricing.agents]
+claude = \"mystery\"\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("mystery"));
+
+        let data = "[pricing.models.m]\ninput_per_mtok = -1.0\noutput_per_mtok = 1.0\n";
+        assert!(matches!(
+            parse_config(data).unwrap_err(),
+            ConfigError::Validation { .. }
+        ));
+
+        let err = parse_config("[scoring.budget]\nmax_cost_usd_per_month = -5.0\n").unwrap_err();
+        assert!(err.to_string().contains("max_cost_usd_per_month"));
+    }
+
+    #[test]
+    fn ui_locale_must_have_a_catalog() {
+        let config = parse_config("[ui]\nlocale = \"de_DE.UTF-8\"\n").unwrap();
+        assert_eq!(config.ui.locale.as_deref(), Some("de_DE.UTF-8"));
+
+        let err = parse_config("[ui]\nlocale = \"tlh\"\n").unwrap_err();
+        assert!(matches!(err, ConfigError::Validation { ref field, .. } if field == "ui.locale"));
+    }
+
+    #[test]
+    fn container_isolation_requires_image() {
+        let data = r#"
+[security]
+isolation = "container"
+"#;
+        let err = parse_config(data).unwrap_err();
+        assert!(err.to_string().contains("security.container.image"));
+
+        let data = r#"
+[security]
+isolation = "container"
+
+[security.container]
+image = "ghcr.io/example/agents:latest"
+network = "none"
+env = ["ANTHROPIC_API_KEY"]
+"#;
+        let config = parse_config(data).unwrap();
+        assert_eq!(config.security.isolation, IsolationMode::Container);
+        assert_eq!(config.security.container.network, "none");
+        assert_eq!(config.security.container.runtime, None);
+    }
+
+    #[test]
+    fn network_allowlist_validated() {
+        let err = parse_config("[security]\nallow_network = \"allowlist\"\n").unwrap_err();
+        assert!(err.to_string().contains("network_allowlist"));
+
+        let config = parse_config(
+            "[security]\nallow_network = \"allowlist\"\nnetwork_allowlist = [\"anthropic.com\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.security.allow_network, NetworkAccess::Allowlist);
+
+        let config = parse_config("[security]\nallow_network = false\n").unwrap();
+        assert_eq!(config.security.allow_network, NetworkAccess::Deny);
+    }
+
+    #[test]
+    fn redaction_patterns_validated() {
+        let config = parse_config(
+            r#"
+[[security.redaction.patterns]]
+name = "ACME_TOKEN"
+regex = "acme_[0-9a-f]{32}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.security.redaction.patterns[0].name, "ACME_TOKEN");
+        assert!(config.security.redaction.entropy_detection);
+
+        let err = parse_config(
+            r#"
+[[security.redaction.patterns]]
+name = "BAD"
+regex = "acme_[0-9"
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not a valid regex"));
+
+        let err = parse_config("[security.redaction]\nentropy_min_length = 2\n").unwrap_err();
+        assert!(err.to_string().contains("entropy_min_length"));
+    }
+
+    #[test]
+    fn approval_policy_parses() {
+        let config = HydraConfig::default();
+        assert_eq!(config.security.approval.policy, ApprovalPolicy::AutoApprove);
+        assert!(config
+            .security
+            .approval
+            .safe_commands
+            .contains(&"cargo test".to_string()));
+
+        let config =
+            parse_config("[security.approval]\npolicy = \"ask\"\nsafe_commands = [\"make\"]\n")
+                .unwrap();
+        assert_eq!(config.security.approval.policy, ApprovalPolicy::Ask);
+        assert_eq!(config.security.approval.safe_commands, vec!["make"]);
+
+        assert!(parse_config("[security.approval]\npolicy = \"sometimes\"\n").is_err());
+    }
+
+    #[test]
+    fn write_policy_globs_must_be_relative() {
+        let config = parse_config(
+            "[security.write_policy]\nread_only = [\"migrations/**\"]\nforbidden = [\"**/*.pem\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.security.write_policy.read_only,
+            vec!["migrations/**"]
+        );
+        assert!(!config.security.write_policy.revert_violations);
+
+        let err = parse_config("[security.write_policy]\nforbidden = [\"/etc/**\"]\n").unwrap_err();
+        assert!(err.to_string().contains("security.write_policy.forbidden"));
+        assert!(parse_config("[security.write_policy]\nread_only = [\"\"]\n").is_err());
+    }
+
+    #[test]
+    fn prompt_guard_patterns_must_compile() {
+        let config = HydraConfig::default();
+        assert!(config.security.prompt_guard.enabled);
+        assert!(!config.security.prompt_guard.strip);
+
+        let config = parse_config(
+            "[security.prompt_guard]\nstrip = true\n\n[[security.prompt_guard.patterns]]\nname = \"acme\"\nregex = \"acme bot\"\n",
+        )
+        .unwrap();
+        assert!(config.security.prompt_guard.strip);
+        assert_eq!(config.security.prompt_guard.patterns[0].name, "acme");
+
+        let err = parse_config(
+            "[[security.prompt_guard.patterns]]\nname = \"bad\"\nregex = \"(unclosed\"\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("prompt guard pattern 'bad'"));
+    }
 }
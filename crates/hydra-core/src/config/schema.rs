@@ -1,13 +1,28 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::DetectedProject;
+use crate::security::{ApprovalPolicy, NetworkAccess, OsSandboxMode};
+
 /// Top-level configuration loaded from `hydra.toml`.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
 pub struct HydraConfig {
     pub scoring: ScoringConfig,
     pub adapters: AdaptersConfig,
+    pub race: RaceConfig,
     pub worktree: WorktreeConfig,
     pub supervisor: SupervisorConfig,
+    pub artifacts: ArtifactsConfig,
+    pub security: SecurityConfig,
+    pub notifications: NotificationsConfig,
+    pub pricing: PricingConfig,
+    pub ui: UiConfig,
+    /// Set when the scoring commands were inferred because there is no
+    /// `hydra.toml`. Never read from or written to the file.
+    #[serde(skip)]
+    pub detected: Option<DetectedProject>,
 }
 
 /// Scoring configuration: profile, weights, gates, timeouts.
@@ -16,11 +31,15 @@ pub struct HydraConfig {
 pub struct ScoringConfig {
     pub profile: Option<ScoringProfile>,
     pub timeout_per_check_seconds: u64,
+    /// Score agents stopped by the hard or idle timeout too. Their scores
+    /// are flagged partial, since the work may be unfinished.
+    pub score_timed_out: bool,
     pub weights: WeightsConfig,
     pub gates: GatesConfig,
     pub diff_scope: DiffScopeConfig,
     pub commands: CommandsConfig,
     pub budget: BudgetConfig,
+    pub build_cache: BuildCacheConfig,
 }
 
 impl Default for ScoringConfig {
@@ -28,11 +47,13 @@ impl Default for ScoringConfig {
         Self {
             profile: None,
             timeout_per_check_seconds: 300,
+            score_timed_out: false,
             weights: WeightsConfig::default(),
             gates: GatesConfig::default(),
             diff_scope: DiffScopeConfig::default(),
             commands: CommandsConfig::default(),
             budget: BudgetConfig::default(),
+            build_cache: BuildCacheConfig::default(),
         }
     }
 }
@@ -93,6 +114,9 @@ pub struct DiffScopeConfig {
     pub max_files_soft: u32,
     pub max_churn_soft: u32,
     pub protected_paths: Vec<String>,
+    /// Paths the change is expected to stay within. Files changed elsewhere
+    /// lower the score; empty disables the check.
+    pub relevant_paths: Vec<String>,
 }
 
 impl Default for DiffScopeConfig {
@@ -101,10 +125,48 @@ impl Default for DiffScopeConfig {
             max_files_soft: 20,
             max_churn_soft: 800,
             protected_paths: Vec::new(),
+            relevant_paths: Vec::new(),
         }
     }
 }
 
+/// Seeding each candidate worktree's build outputs from the baseline
+/// worktree, so scoring does not rebuild every dependency from scratch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct BuildCacheConfig {
+    pub mode: BuildCacheMode,
+    /// Directories, relative to the worktree root, to seed. Only those git
+    /// ignores are copied, so seeding never shows up in a diff.
+    pub dirs: Vec<String>,
+}
+
+impl Default for BuildCacheConfig {
+    fn default() -> Self {
+        Self {
+            mode: BuildCacheMode::Off,
+            dirs: vec![
+                "target".to_string(),
+                "node_modules".to_string(),
+                ".venv".to_string(),
+            ],
+        }
+    }
+}
+
+/// How build outputs are carried from the baseline worktree to candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildCacheMode {
+    Off,
+    /// Copy-on-write clones where the filesystem supports them (btrfs, XFS,
+    /// APFS), plain copies elsewhere.
+    Clone,
+    /// Hard links, falling back to copies across filesystems. Cheapest, but
+    /// a tool that rewrites a file in place changes it for every worktree.
+    Hardlink,
+}
+
 /// Explicit build/test/lint command overrides.
 /// When set, these take precedence over the scoring profile defaults.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -116,11 +178,35 @@ pub struct CommandsConfig {
 }
 
 /// Optional run budget controls for token and cost limits.
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
 pub struct BudgetConfig {
     pub max_tokens_total: Option<u64>,
     pub max_cost_usd: Option<f64>,
+    /// Spend allowed across all runs in a UTC calendar day, as recorded in
+    /// the cost ledger. Races are refused once it is used up.
+    pub max_cost_usd_per_day: Option<f64>,
+    /// Spend allowed across all runs in a UTC calendar month.
+    pub max_cost_usd_per_month: Option<f64>,
+    /// Percentages of `max_tokens_total` and `max_cost_usd` at which the
+    /// run warns that it is approaching the hard stop.
+    pub warn_at_percent: Vec<u8>,
+    /// Once the budget is spent, how long an agent in the middle of a tool
+    /// call may take to finish it before it is stopped; 0 stops at once.
+    pub grace_seconds: u64,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens_total: None,
+            max_cost_usd: None,
+            max_cost_usd_per_day: None,
+            max_cost_usd_per_month: None,
+            warn_at_percent: vec![80],
+            grace_seconds: 30,
+        }
+    }
 }
 
 /// Adapter binary path overrides.
@@ -132,6 +218,50 @@ pub struct AdaptersConfig {
     pub cursor: Option<String>,
 }
 
+/// Race defaults applied when a command does not pass `--agents`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RaceConfig {
+    /// Agents to race by default; empty means all Tier-1 adapters.
+    pub default_agents: Vec<String>,
+    /// Prompt template for `--from-issue`/`--from-pr`; see
+    /// `hydra race --help` for its placeholders.
+    pub github_template: Option<String>,
+    /// Composite score a mergeable candidate needs to end a
+    /// `--stop-on-first-success` race early; 0 lets the gates alone decide.
+    pub success_threshold: f64,
+    /// Ask agents to explain their approach in `.hydra-notes.md`, which is
+    /// kept for reviewers as the agent's `notes.md`.
+    pub request_notes: bool,
+    /// What to do when the base branch is behind its upstream once fetched.
+    pub base_freshness: BaseFreshnessPolicy,
+}
+
+/// Handling of a base branch that is behind its upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaseFreshnessPolicy {
+    /// Skip the fetch and the check.
+    Off,
+    /// Report how far behind the branch is and race on it as it is.
+    Warn,
+    /// Fast-forward the branch to its upstream when it has no commits of its
+    /// own; otherwise warn.
+    FastForward,
+}
+
+impl Default for RaceConfig {
+    fn default() -> Self {
+        Self {
+            default_agents: Vec::new(),
+            github_template: None,
+            success_threshold: 0.0,
+            request_notes: true,
+            base_freshness: BaseFreshnessPolicy::Warn,
+        }
+    }
+}
+
 /// Worktree management configuration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
@@ -158,6 +288,108 @@ pub enum RetentionPolicy {
     All,
 }
 
+/// Run artifact persistence configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ArtifactsConfig {
+    /// Store the task prompt verbatim in `manifest.json` instead of a
+    /// secret-redacted copy.
+    pub store_full_prompt: bool,
+    /// Largest diff of a single file kept in `diff.patch`; larger ones are
+    /// stored under `diff-blobs/` instead.
+    pub max_diff_file_bytes: u64,
+    /// Size `diff.patch` may grow to before further files go to
+    /// `diff-blobs/`.
+    pub max_diff_total_bytes: u64,
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        Self {
+            store_full_prompt: false,
+            max_diff_file_bytes: 1024 * 1024,
+            max_diff_total_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Chat notifications sent when a race finishes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct NotificationsConfig {
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// Presentation of human-readable output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct UiConfig {
+    /// Language for CLI summaries and error messages, e.g. `de`. Unset
+    /// follows `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    pub locale: Option<String>,
+}
+
+/// Token prices for agents whose output reports tokens but not cost. Both
+/// tables extend the built-in ones in `scoring::pricing`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct PricingConfig {
+    /// Model each agent is billed as, by agent key.
+    pub agents: BTreeMap<String, String>,
+    /// Prices by model name.
+    pub models: BTreeMap<String, ModelPrice>,
+}
+
+/// USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    /// Rate for input tokens served from the provider's prompt cache;
+    /// `input_per_mtok` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_mtok: Option<f64>,
+}
+
+/// An incoming webhook to post race results to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub kind: WebhookKind,
+    /// Environment variable holding the webhook URL. Preferred over `url`,
+    /// since webhook URLs carry their own credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub on: NotifyOn,
+    /// Message text with `{run_id}`, `{status}`, `{prompt}`, `{winner}`,
+    /// `{score}`, `{cost}` and `{duration}` placeholders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookKind {
+    Slack,
+    Discord,
+}
+
+/// Which race outcomes a webhook is told about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyOn {
+    #[default]
+    Always,
+    /// Only races that completed with a result.
+    Success,
+    /// Only races that failed, timed out or were interrupted.
+    Failure,
+}
+
 /// Process supervisor configuration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
@@ -165,6 +397,13 @@ pub struct SupervisorConfig {
     pub hard_timeout_seconds: u64,
     pub idle_timeout_seconds: u64,
     pub output_buffer_bytes: usize,
+    /// Kernel enforcement of the worktree sandbox (Landlock + seccomp on
+    /// Linux); off unless set. Ignored for agents run in unsafe mode or in a
+    /// container.
+    pub os_sandbox: OsSandboxMode,
+    /// Extra directories agents may write to under the OS sandbox, e.g. a
+    /// shared build cache.
+    pub sandbox_writable_paths: Vec<String>,
 }
 
 impl Default for SupervisorConfig {
@@ -173,6 +412,214 @@ impl Default for SupervisorConfig {
             hard_timeout_seconds: 1800,
             idle_timeout_seconds: 300,
             output_buffer_bytes: 10 * 1024 * 1024, // 10 MiB
+            os_sandbox: OsSandboxMode::default(),
+            sandbox_writable_paths: Vec::new(),
+        }
+    }
+}
+
+/// Agent isolation and artifact hygiene configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct SecurityConfig {
+    pub isolation: IsolationMode,
+    pub container: ContainerConfig,
+    /// Agent network egress: `true`, `false`, or `"allowlist"` to allow
+    /// only `network_allowlist`. Applies in unsafe mode too.
+    pub allow_network: NetworkAccess,
+    /// Hosts (and their subdomains) reachable under `"allowlist"`,
+    /// typically just the model provider's API.
+    pub network_allowlist: Vec<String>,
+    /// How long a grant of unsafe mode stays valid.
+    pub unsafe_consent_minutes: u64,
+    pub redaction: RedactionConfig,
+    pub approval: ApprovalConfig,
+    pub write_policy: WritePolicyConfig,
+    pub prompt_guard: PromptGuardConfig,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            isolation: IsolationMode::default(),
+            container: ContainerConfig::default(),
+            allow_network: NetworkAccess::default(),
+            network_allowlist: Vec::new(),
+            unsafe_consent_minutes: 60,
+            redaction: RedactionConfig::default(),
+            approval: ApprovalConfig::default(),
+            write_policy: WritePolicyConfig::default(),
+            prompt_guard: PromptGuardConfig::default(),
+        }
+    }
+}
+
+/// Where agent processes run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationMode {
+    /// On the host, confined by `supervisor.os_sandbox`.
+    #[default]
+    Process,
+    /// Inside a Docker/Podman container with the worktree bind-mounted.
+    Container,
+}
+
+/// Container backend settings, used when `isolation = "container"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ContainerConfig {
+    /// Runtime binary or path; defaults to `docker`, then `podman`.
+    pub runtime: Option<String>,
+    /// Image that provides the agent CLIs.
+    pub image: Option<String>,
+    /// Network the container joins (`--network`), e.g. `none` to cut
+    /// off everything but what the image itself provides.
+    pub network: String,
+    /// Host environment variables forwarded into the container, e.g.
+    /// API keys the agent CLIs read.
+    pub env: Vec<String>,
+    /// Extra arguments passed to `<runtime> run` before the image.
+    pub extra_args: Vec<String>,
+}
+
+impl Default for ContainerConfig {
+    fn default() -> Self {
+        Self {
+            runtime: None,
+            image: None,
+            network: "bridge".to_string(),
+            env: Vec::new(),
+            extra_args: Vec::new(),
         }
     }
 }
+
+/// Secret redaction applied to every artifact Hydra writes, on top of the
+/// built-in token prefixes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RedactionConfig {
+    pub patterns: Vec<RedactionPattern>,
+    /// Redact random-looking tokens that match no known pattern.
+    pub entropy_detection: bool,
+    /// Minimum Shannon entropy, in bits per character.
+    pub entropy_threshold: f64,
+    /// Shorter tokens are never treated as high-entropy secrets.
+    pub entropy_min_length: usize,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            entropy_detection: true,
+            entropy_threshold: 4.5,
+            entropy_min_length: 24,
+        }
+    }
+}
+
+/// A user-defined secret pattern; matches become `[REDACTED:<name>]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionPattern {
+    pub name: String,
+    pub regex: String,
+}
+
+/// Approval of shell commands and file deletions agents report in their
+/// event stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ApprovalConfig {
+    pub policy: ApprovalPolicy,
+    /// Commands that never need approval, matched on their leading words
+    /// (`"cargo test"` covers `cargo test -p core`). Deletions always do.
+    pub safe_commands: Vec<String>,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            policy: ApprovalPolicy::AutoApprove,
+            safe_commands: [
+                "ls",
+                "cat",
+                "head",
+                "tail",
+                "wc",
+                "pwd",
+                "echo",
+                "grep",
+                "rg",
+                "find",
+                "git status",
+                "git diff",
+                "git log",
+                "git show",
+                "git add",
+                "git commit",
+                "cargo build",
+                "cargo check",
+                "cargo test",
+                "cargo fmt",
+                "cargo clippy",
+                "npm test",
+                "npm run",
+                "pnpm test",
+                "yarn test",
+                "pytest",
+                "go build",
+                "go test",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
+
+/// Paths inside the worktree agents may not change, checked against the
+/// files their tool calls name and against the final diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct WritePolicyConfig {
+    /// Globs agents may read but not modify, e.g. `"migrations/**"`.
+    pub read_only: Vec<String>,
+    /// Globs agents may neither read nor modify, e.g. `"**/*.pem"`.
+    pub forbidden: Vec<String>,
+    /// Restore offending files to the base ref before scoring instead of
+    /// failing the candidate's gate.
+    pub revert_violations: bool,
+}
+
+/// Scanning of the task prompt, and the repository files it refers to, for
+/// prompt-injection attempts before a race starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct PromptGuardConfig {
+    pub enabled: bool,
+    /// Remove matches from the prompt instead of only warning.
+    pub strip: bool,
+    /// Patterns checked in addition to the built-in ones.
+    pub patterns: Vec<PromptGuardPattern>,
+}
+
+impl Default for PromptGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strip: false,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// A user-defined injection pattern, reported under `name`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptGuardPattern {
+    pub name: String,
+    pub regex: String,
+}
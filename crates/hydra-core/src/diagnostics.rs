@@ -0,0 +1,122 @@
+//! Shared vocabulary for health checks.
+//!
+//! `hydra doctor` and the desktop preflight report checks under the same
+//! identifiers so the GUI and external tooling can key off `id` instead of
+//! matching English descriptions. Check and remediation IDs are part of the
+//! JSON contract: rename one only together with its consumers.
+
+use serde::{Deserialize, Serialize};
+
+/// How much a check matters when it does not pass. Only `Error` findings
+/// make a report unhealthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A way out of a failed check. `automatic` remediations are what
+/// `hydra doctor --fix` applies; the rest need the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Remediation {
+    pub id: String,
+    pub description: String,
+    /// Shell command that performs the remediation, when there is one.
+    pub command: Option<String>,
+    pub automatic: bool,
+}
+
+impl Remediation {
+    pub fn manual(id: &str, description: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            description: description.into(),
+            command: None,
+            automatic: false,
+        }
+    }
+
+    pub fn automatic(id: &str, description: impl Into<String>) -> Self {
+        Self {
+            automatic: true,
+            ..Self::manual(id, description)
+        }
+    }
+
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+}
+
+/// Stable check identifiers.
+pub mod check {
+    pub const GIT_REPO: &str = "git.repo";
+    pub const GIT_COMMITS: &str = "git.commits";
+    pub const GIT_CLEAN_TREE: &str = "git.clean_tree";
+    pub const GIT_WORKTREE: &str = "git.worktree";
+    pub const GIT_PROTECTED_BRANCH: &str = "git.protected_branch";
+    pub const GIT_LONG_PATHS: &str = "git.long_paths";
+    pub const GIT_PRUNABLE_WORKTREES: &str = "git.prunable_worktrees";
+    pub const ENV_HOME: &str = "env.home";
+    pub const CONFIG_FILE: &str = "config.file";
+    pub const HYDRA_GITIGNORE: &str = "hydra.gitignore";
+    pub const HYDRA_STALE_LOCKS: &str = "hydra.stale_locks";
+    pub const DISK_SPACE: &str = "disk.space";
+    pub const ADAPTERS_TIER1: &str = "adapters.tier1";
+    pub const SCORING_COMMANDS: &str = "scoring.commands";
+
+    /// `adapter.<key>.binary`: the adapter's CLI was found and probed.
+    pub fn adapter_binary(key: &str) -> String {
+        format!("adapter.{key}.binary")
+    }
+
+    /// `adapter.<key>.auth`: the adapter's CLI has credentials.
+    pub fn adapter_auth(key: &str) -> String {
+        format!("adapter.{key}.auth")
+    }
+
+    /// `adapter.<key>.live`: the adapter answered a trivial prompt.
+    pub fn adapter_live(key: &str) -> String {
+        format!("adapter.{key}.live")
+    }
+
+    /// `scoring.<dimension>`: the dimension's command resolved and its
+    /// programs are on PATH.
+    pub fn scoring_dimension(dimension: &str) -> String {
+        format!("scoring.{dimension}")
+    }
+}
+
+/// Stable remediation identifiers.
+pub mod remediation {
+    pub const INIT_GIT_REPO: &str = "init_git_repo";
+    pub const CREATE_INITIAL_COMMIT: &str = "create_initial_commit";
+    pub const COMMIT_OR_STASH: &str = "commit_or_stash";
+    pub const UPGRADE_GIT: &str = "upgrade_git";
+    pub const SWITCH_BRANCH: &str = "switch_branch";
+    pub const ENABLE_LONG_PATHS: &str = "enable_long_paths";
+    pub const PRUNE_WORKTREES: &str = "prune_worktrees";
+    pub const SET_HOME: &str = "set_home";
+    pub const CREATE_CONFIG: &str = "create_config";
+    pub const IGNORE_HYDRA_DIR: &str = "ignore_hydra_dir";
+    pub const REMOVE_STALE_LOCKS: &str = "remove_stale_locks";
+    pub const FREE_DISK_SPACE: &str = "free_disk_space";
+    pub const INSTALL_ADAPTER: &str = "install_adapter";
+    pub const UPGRADE_ADAPTER: &str = "upgrade_adapter";
+    pub const LOGIN_ADAPTER: &str = "login_adapter";
+    pub const SET_SCORING_COMMANDS: &str = "set_scoring_commands";
+    pub const INSTALL_TOOL: &str = "install_tool";
+}
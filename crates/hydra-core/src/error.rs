@@ -0,0 +1,568 @@
+//! Stable, machine-readable codes for every error hydra-core returns.
+//!
+//! Each module keeps its own error type; this module maps them all onto one
+//! taxonomy of [`ErrorCode`]s such as `HY-ADAPTER-003`, so the CLI, the API
+//! and the desktop app can react to an error without parsing its message.
+//! Codes are part of the public contract: never renumber or reuse one, only
+//! add new codes or stop emitting old ones.
+
+use std::error::Error as StdError;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::adapter::{AdapterError, RegistryError};
+use crate::artifact::ArtifactError;
+use crate::config::ConfigError;
+use crate::git_exec::GitExecError;
+use crate::git_ref::RefNameError;
+use crate::notify::NotifyError;
+use crate::orchestrator::OrchestratorError;
+use crate::scoring::baseline::BaselineError;
+use crate::security::{ConsentError, ContainerError, TrustError};
+use crate::supervisor::pty::PtyError;
+use crate::supervisor::SupervisorError;
+use crate::worktree::WorktreeError;
+
+/// Area of the system an error comes from; the middle part of its code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    Config,
+    Adapter,
+    Race,
+    Budget,
+    Worktree,
+    Git,
+    Artifact,
+    Security,
+    Process,
+    Notify,
+}
+
+impl ErrorCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCategory::Config => "CONFIG",
+            ErrorCategory::Adapter => "ADAPTER",
+            ErrorCategory::Race => "RACE",
+            ErrorCategory::Budget => "BUDGET",
+            ErrorCategory::Worktree => "WORKTREE",
+            ErrorCategory::Git => "GIT",
+            ErrorCategory::Artifact => "ARTIFACT",
+            ErrorCategory::Security => "SECURITY",
+            ErrorCategory::Process => "PROCESS",
+            ErrorCategory::Notify => "NOTIFY",
+        }
+    }
+}
+
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident = ($category:ident, $code:literal, $retryable:literal),)+) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant,)+
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)+];
+
+            /// The stable code, e.g. `HY-ADAPTER-003`.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => concat!("HY-", stringify!($category), "-", $code),)+
+                }
+            }
+
+            pub fn category(self) -> ErrorCategory {
+                match self {
+                    $(ErrorCode::$variant => error_codes!(@category $category),)+
+                }
+            }
+
+            /// Whether the same operation may succeed if tried again
+            /// unchanged. Everything else needs the input, config or
+            /// environment fixed first.
+            pub fn retryable(self) -> bool {
+                match self {
+                    $(ErrorCode::$variant => $retryable,)+
+                }
+            }
+        }
+    };
+    (@category CONFIG) => { ErrorCategory::Config };
+    (@category ADAPTER) => { ErrorCategory::Adapter };
+    (@category RACE) => { ErrorCategory::Race };
+    (@category BUDGET) => { ErrorCategory::Budget };
+    (@category WORKTREE) => { ErrorCategory::Worktree };
+    (@category GIT) => { ErrorCategory::Git };
+    (@category ARTIFACT) => { ErrorCategory::Artifact };
+    (@category SECURITY) => { ErrorCategory::Security };
+    (@category PROCESS) => { ErrorCategory::Process };
+    (@category NOTIFY) => { ErrorCategory::Notify };
+}
+
+error_codes! {
+    /// `hydra.toml` could not be read or written.
+    ConfigIo = (CONFIG, "001", false),
+    /// `hydra.toml` is not valid TOML or does not match the schema.
+    ConfigParse = (CONFIG, "002", false),
+    /// `hydra.toml` parsed but a value is out of range or inconsistent.
+    ConfigInvalid = (CONFIG, "003", false),
+
+    /// No adapter is registered under the requested key.
+    AdapterUnknown = (ADAPTER, "001", false),
+    /// The adapter is experimental and was not explicitly allowed.
+    AdapterExperimental = (ADAPTER, "002", false),
+    /// The agent CLI is not installed or not on `PATH`.
+    AdapterMissing = (ADAPTER, "003", false),
+    /// The agent CLI is installed but not logged in.
+    AdapterAuth = (ADAPTER, "004", false),
+    /// The installed agent CLI lacks a version, flag or feature Hydra needs.
+    AdapterUnsupported = (ADAPTER, "005", false),
+    /// The adapter's readiness probe failed.
+    AdapterNotReady = (ADAPTER, "006", true),
+    /// The agent process could not be started.
+    AdapterSpawn = (ADAPTER, "007", true),
+    /// The agent's output stream could not be parsed.
+    AdapterStream = (ADAPTER, "008", true),
+    /// The agent ran past its timeout.
+    AdapterTimeout = (ADAPTER, "009", true),
+    /// The agent was interrupted before it finished.
+    AdapterInterrupted = (ADAPTER, "010", true),
+
+    /// No agents were selected for the race.
+    RaceNoAgents = (RACE, "001", false),
+    /// The candidate has no changes to merge.
+    RaceNothingToPromote = (RACE, "002", false),
+    /// An agent task failed or panicked.
+    RaceAgentFailed = (RACE, "003", true),
+    /// Scoring a candidate failed.
+    RaceScoringFailed = (RACE, "004", true),
+    /// Prompt variants were given with several agents, or are malformed.
+    RaceInvalidVariants = (RACE, "005", false),
+
+    /// A daily or monthly spend limit in `[scoring.budget]` is used up.
+    BudgetExhausted = (BUDGET, "001", false),
+
+    /// A git command run on a worktree failed.
+    WorktreeGit = (WORKTREE, "001", true),
+    /// A worktree already exists at the target path.
+    WorktreeExists = (WORKTREE, "002", false),
+    /// The worktree does not exist.
+    WorktreeNotFound = (WORKTREE, "003", false),
+    /// The working directory is not inside a git repository.
+    WorktreeNotARepo = (WORKTREE, "004", false),
+    /// An agent key or branch name is not a valid git ref component.
+    WorktreeInvalidName = (WORKTREE, "005", false),
+    /// Filesystem error while managing a worktree.
+    WorktreeIo = (WORKTREE, "006", true),
+
+    /// A git command timed out.
+    GitTimeout = (GIT, "001", true),
+    /// A git command exited with an error.
+    GitFailed = (GIT, "002", false),
+    /// git could not be run at all.
+    GitIo = (GIT, "003", true),
+
+    /// Reading or writing a run artifact failed.
+    ArtifactIo = (ARTIFACT, "001", true),
+    /// A run directory with this ID already exists.
+    ArtifactRunExists = (ARTIFACT, "002", false),
+    /// The run or its manifest does not exist.
+    ArtifactRunNotFound = (ARTIFACT, "003", false),
+    /// Another run holds the repository lock.
+    ArtifactLocked = (ARTIFACT, "004", true),
+    /// An artifact was written by a newer Hydra with a schema this one
+    /// cannot read.
+    ArtifactUnsupportedSchema = (ARTIFACT, "005", false),
+
+    /// The repository has not been trusted to run agents or scoring.
+    SecurityUntrusted = (SECURITY, "001", false),
+    /// Unsafe mode was requested without a valid consent grant.
+    SecurityUnsafeBlocked = (SECURITY, "002", false),
+    /// The sandbox refused a command's working directory.
+    SecuritySandboxBlocked = (SECURITY, "003", false),
+    /// Container isolation is configured but cannot be used.
+    SecurityContainerUnavailable = (SECURITY, "004", false),
+    /// The trust list or consent record could not be read or written.
+    SecurityStore = (SECURITY, "005", false),
+
+    /// A supervised process or PTY could not be started or sandboxed.
+    ProcessSpawn = (PROCESS, "001", true),
+    /// A supervised process ran past its timeout.
+    ProcessTimeout = (PROCESS, "002", true),
+    /// A supervised process was cancelled.
+    ProcessCancelled = (PROCESS, "003", false),
+    /// A supervised process exited with a non-zero status.
+    ProcessFailed = (PROCESS, "004", false),
+    /// I/O with a supervised process or PTY failed.
+    ProcessIo = (PROCESS, "005", true),
+
+    /// A webhook's URL is not configured.
+    NotifyConfig = (NOTIFY, "001", false),
+    /// Posting to a webhook failed.
+    NotifyFailed = (NOTIFY, "002", true),
+}
+
+impl ErrorCode {
+    /// Code of the first Hydra error in `err`'s source chain, starting with
+    /// `err` itself. Outer errors win, since they delegate to what they wrap.
+    pub fn find(err: &(dyn StdError + 'static)) -> Option<ErrorCode> {
+        let mut current = Some(err);
+        while let Some(err) = current {
+            if let Some(code) = Self::of(err) {
+                return Some(code);
+            }
+            current = err.source();
+        }
+        None
+    }
+
+    fn of(err: &(dyn StdError + 'static)) -> Option<ErrorCode> {
+        macro_rules! try_types {
+            ($($ty:ty),+) => {
+                $(if let Some(e) = err.downcast_ref::<$ty>() {
+                    return Some(e.code());
+                })+
+            };
+        }
+        try_types!(
+            HydraError,
+            OrchestratorError,
+            ConfigError,
+            AdapterError,
+            RegistryError,
+            WorktreeError,
+            RefNameError,
+            GitExecError,
+            ArtifactError,
+            ConsentError,
+            TrustError,
+            ContainerError,
+            SupervisorError,
+            PtyError,
+            BaselineError,
+            NotifyError
+        );
+        None
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ErrorCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorCode::ALL
+            .iter()
+            .copied()
+            .find(|code| code.as_str() == s)
+            .ok_or_else(|| format!("unknown error code '{s}'"))
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Any error hydra-core returns, for callers that handle them uniformly.
+#[derive(Debug, Error)]
+pub enum HydraError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Orchestrator(#[from] OrchestratorError),
+    #[error(transparent)]
+    Adapter(#[from] AdapterError),
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[error(transparent)]
+    Worktree(#[from] WorktreeError),
+    #[error(transparent)]
+    Git(#[from] GitExecError),
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+    #[error(transparent)]
+    Consent(#[from] ConsentError),
+    #[error(transparent)]
+    Trust(#[from] TrustError),
+    #[error(transparent)]
+    Supervisor(#[from] SupervisorError),
+    #[error(transparent)]
+    Notify(#[from] NotifyError),
+}
+
+impl HydraError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            HydraError::Config(e) => e.code(),
+            HydraError::Orchestrator(e) => e.code(),
+            HydraError::Adapter(e) => e.code(),
+            HydraError::Registry(e) => e.code(),
+            HydraError::Worktree(e) => e.code(),
+            HydraError::Git(e) => e.code(),
+            HydraError::Artifact(e) => e.code(),
+            HydraError::Consent(e) => e.code(),
+            HydraError::Trust(e) => e.code(),
+            HydraError::Supervisor(e) => e.code(),
+            HydraError::Notify(e) => e.code(),
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        self.code().retryable()
+    }
+}
+
+impl OrchestratorError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            OrchestratorError::NoAgents => ErrorCode::RaceNoAgents,
+            OrchestratorError::InvalidVariants { .. } => ErrorCode::RaceInvalidVariants,
+            OrchestratorError::Registry(e) => e.code(),
+            OrchestratorError::RunExists { .. } => ErrorCode::ArtifactRunExists,
+            OrchestratorError::AdapterNotReady { .. } => ErrorCode::AdapterNotReady,
+            OrchestratorError::UnsafeConsent(e) => e.code(),
+            OrchestratorError::SpendLimitReached { .. } => ErrorCode::BudgetExhausted,
+            OrchestratorError::Worktree { source, .. } => source.code(),
+            OrchestratorError::Baseline(e) => e.code(),
+            OrchestratorError::Command(e) => e.code(),
+            OrchestratorError::SandboxBlocked { .. } => ErrorCode::SecuritySandboxBlocked,
+            OrchestratorError::Supervisor(e) => e.code(),
+            OrchestratorError::TaskFailed(_) => ErrorCode::RaceAgentFailed,
+            OrchestratorError::NothingToPromote { .. } => ErrorCode::RaceNothingToPromote,
+            OrchestratorError::Git(e) => e.code(),
+            OrchestratorError::Scoring { .. } => ErrorCode::RaceScoringFailed,
+            OrchestratorError::Artifact(e) => e.code(),
+            OrchestratorError::Io(_) | OrchestratorError::Serialization(_) => ErrorCode::ArtifactIo,
+            OrchestratorError::Context { source, .. } => source.code(),
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        self.code().retryable()
+    }
+}
+
+impl ConfigError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ConfigError::ReadFailed { .. } | ConfigError::WriteFailed { .. } => ErrorCode::ConfigIo,
+            ConfigError::ParseFailed(_) | ConfigError::SerializeFailed(_) => ErrorCode::ConfigParse,
+            ConfigError::Validation { .. } => ErrorCode::ConfigInvalid,
+        }
+    }
+}
+
+impl AdapterError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AdapterError::BinaryMissing { .. } => ErrorCode::AdapterMissing,
+            AdapterError::AuthMissing { .. } => ErrorCode::AdapterAuth,
+            AdapterError::UnsupportedVersion { .. }
+            | AdapterError::UnsupportedFlag { .. }
+            | AdapterError::NotImplemented { .. } => ErrorCode::AdapterUnsupported,
+            AdapterError::SpawnFailed { .. } => ErrorCode::AdapterSpawn,
+            AdapterError::StreamParseError { .. } => ErrorCode::AdapterStream,
+            AdapterError::TimedOut { .. } => ErrorCode::AdapterTimeout,
+            AdapterError::Interrupted { .. } => ErrorCode::AdapterInterrupted,
+            AdapterError::ProbeFailed { .. } => ErrorCode::AdapterNotReady,
+        }
+    }
+}
+
+impl RegistryError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RegistryError::UnknownAdapter { .. } => ErrorCode::AdapterUnknown,
+            RegistryError::ExperimentalBlocked { .. } => ErrorCode::AdapterExperimental,
+        }
+    }
+}
+
+impl WorktreeError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            WorktreeError::GitFailed { .. } => ErrorCode::WorktreeGit,
+            WorktreeError::AlreadyExists { .. } => ErrorCode::WorktreeExists,
+            WorktreeError::NotFound { .. } => ErrorCode::WorktreeNotFound,
+            WorktreeError::NotARepo => ErrorCode::WorktreeNotARepo,
+            WorktreeError::InvalidAgentKey { .. } | WorktreeError::InvalidBranchName { .. } => {
+                ErrorCode::WorktreeInvalidName
+            }
+            WorktreeError::Io(_) => ErrorCode::WorktreeIo,
+        }
+    }
+}
+
+impl RefNameError {
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::WorktreeInvalidName
+    }
+}
+
+impl GitExecError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            GitExecError::TimedOut { .. } => ErrorCode::GitTimeout,
+            GitExecError::NonZeroExit { .. } => ErrorCode::GitFailed,
+            GitExecError::Io(_) => ErrorCode::GitIo,
+        }
+    }
+}
+
+impl ArtifactError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ArtifactError::Io(_) | ArtifactError::Serialization(_) => ErrorCode::ArtifactIo,
+            ArtifactError::RunAlreadyExists { .. } => ErrorCode::ArtifactRunExists,
+            ArtifactError::Locked { .. } => ErrorCode::ArtifactLocked,
+            ArtifactError::UnsupportedSchema { .. } => ErrorCode::ArtifactUnsupportedSchema,
+            ArtifactError::RunNotFound { .. } | ArtifactError::ManifestNotFound { .. } => {
+                ErrorCode::ArtifactRunNotFound
+            }
+        }
+    }
+}
+
+impl ConsentError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ConsentError::NotGranted
+            | ConsentError::Expired { .. }
+            | ConsentError::TokenMismatch => ErrorCode::SecurityUnsafeBlocked,
+            ConsentError::Io(_) | ConsentError::Serialization(_) => ErrorCode::SecurityStore,
+            ConsentError::Audit(e) => e.code(),
+        }
+    }
+}
+
+impl TrustError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            TrustError::Untrusted { .. } => ErrorCode::SecurityUntrusted,
+            TrustError::NoTrustFile | TrustError::Io(_) | TrustError::Serialization(_) => {
+                ErrorCode::SecurityStore
+            }
+        }
+    }
+}
+
+impl ContainerError {
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::SecurityContainerUnavailable
+    }
+}
+
+impl SupervisorError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SupervisorError::SpawnFailed(_) | SupervisorError::SandboxSetup(_) => {
+                ErrorCode::ProcessSpawn
+            }
+            SupervisorError::TimedOut { .. } => ErrorCode::ProcessTimeout,
+            SupervisorError::Cancelled => ErrorCode::ProcessCancelled,
+            SupervisorError::NonZeroExit { .. } => ErrorCode::ProcessFailed,
+            SupervisorError::Io(_) => ErrorCode::ProcessIo,
+            SupervisorError::Container(e) => e.code(),
+        }
+    }
+}
+
+impl PtyError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PtyError::OpenFailed(_) | PtyError::SpawnFailed(_) => ErrorCode::ProcessSpawn,
+            PtyError::AlreadyStopped => ErrorCode::ProcessCancelled,
+            PtyError::WriteFailed(_) | PtyError::ResizeFailed(_) | PtyError::Io(_) => {
+                ErrorCode::ProcessIo
+            }
+        }
+    }
+}
+
+impl BaselineError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            BaselineError::TimedOut { .. } => ErrorCode::ProcessTimeout,
+            BaselineError::Io(_) => ErrorCode::ProcessIo,
+        }
+    }
+}
+
+impl NotifyError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            NotifyError::MissingUrl(_) => ErrorCode::NotifyConfig,
+            NotifyError::CurlUnavailable(_) | NotifyError::RequestFailed(_) => {
+                ErrorCode::NotifyFailed
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique_well_formed_and_round_trip() {
+        let mut seen = HashSet::new();
+        for code in ErrorCode::ALL {
+            let text = code.as_str();
+            assert!(seen.insert(text), "duplicate code {text}");
+            let prefix = format!("HY-{}-", code.category().as_str());
+            let number = text
+                .strip_prefix(&prefix)
+                .expect("code carries its category");
+            assert!(number.len() == 3 && number.chars().all(|c| c.is_ascii_digit()));
+
+            let json = serde_json::to_string(code).unwrap();
+            assert_eq!(serde_json::from_str::<ErrorCode>(&json).unwrap(), *code);
+        }
+        assert!("HY-NOPE-001".parse::<ErrorCode>().is_err());
+    }
+
+    #[test]
+    fn wrapped_errors_report_the_innermost_meaningful_code() {
+        let err = OrchestratorError::Registry(RegistryError::UnknownAdapter {
+            key: "gemini".to_string(),
+            known: "claude".to_string(),
+        });
+        assert_eq!(err.code().as_str(), "HY-ADAPTER-001");
+        assert!(!err.retryable());
+
+        let err = HydraError::from(OrchestratorError::Git(GitExecError::TimedOut {
+            command: "git fetch".to_string(),
+            timeout_secs: 30,
+        }));
+        assert_eq!(err.code(), ErrorCode::GitTimeout);
+        assert!(err.retryable());
+    }
+
+    #[test]
+    fn find_walks_the_source_chain() {
+        #[derive(Debug, Error)]
+        #[error("while starting the race")]
+        struct Context(#[source] OrchestratorError);
+
+        let err = Context(OrchestratorError::NoAgents);
+        assert_eq!(ErrorCode::find(&err), Some(ErrorCode::RaceNoAgents));
+        assert_eq!(ErrorCode::find(&std::io::Error::other("plain")), None);
+    }
+}
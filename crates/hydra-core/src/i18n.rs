@@ -0,0 +1,272 @@
+//! Message catalogs for user-facing text in CLI summaries and error
+//! messages.
+//!
+//! `locales/en.toml` is the reference catalog. Community locales translate
+//! any subset of its keys and fall back to English for the rest, so a
+//! partial translation is always safe to ship. Machine-readable output
+//! (`--json`, `--output`, error codes) is never translated.
+//!
+//! The active locale is process-wide: `[ui] locale` in `hydra.toml` when set,
+//! otherwise `LC_ALL`, `LC_MESSAGES` or `LANG`.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use crate::error::ErrorCode;
+
+/// Locales with a catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    En,
+    De,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Es, Locale::Fr];
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// Parse a language tag or POSIX locale such as `de`, `de-AT` or
+    /// `de_DE.UTF-8`. `C` and `POSIX` are English.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let language = raw
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "c" | "posix" => Some(Locale::En),
+            tag => Locale::ALL.into_iter().find(|l| l.tag() == tag),
+        }
+    }
+
+    /// The locale `configured` names, else the one the environment asks for,
+    /// else English. Like gettext, only the first of `LC_ALL`, `LC_MESSAGES`
+    /// and `LANG` that is set counts.
+    pub fn resolve(configured: Option<&str>) -> Self {
+        configured
+            .and_then(Locale::parse)
+            .or_else(|| {
+                ["LC_ALL", "LC_MESSAGES", "LANG"]
+                    .iter()
+                    .filter_map(|var| std::env::var(var).ok())
+                    .find(|value| !value.is_empty())
+                    .and_then(|value| Locale::parse(&value))
+            })
+            .unwrap_or(Locale::En)
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.toml"),
+            Locale::De => include_str!("../locales/de.toml"),
+            Locale::Es => include_str!("../locales/es.toml"),
+            Locale::Fr => include_str!("../locales/fr.toml"),
+        }
+    }
+}
+
+static ACTIVE: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the process-wide locale used by [`catalog`] and [`tr!`](crate::tr).
+pub fn set_locale(locale: Locale) {
+    let index = Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0);
+    ACTIVE.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    Locale::ALL[ACTIVE.load(Ordering::Relaxed) as usize]
+}
+
+/// The catalog for the active locale.
+pub fn catalog() -> Catalog {
+    Catalog::new(locale())
+}
+
+type Messages = HashMap<String, String>;
+
+fn messages(locale: Locale) -> &'static Messages {
+    static CATALOGS: OnceLock<HashMap<Locale, Messages>> = OnceLock::new();
+    let catalogs = CATALOGS.get_or_init(|| {
+        Locale::ALL
+            .into_iter()
+            .map(|locale| (locale, load(locale)))
+            .collect()
+    });
+    &catalogs[&locale]
+}
+
+fn load(locale: Locale) -> Messages {
+    let mut messages = Messages::new();
+    match locale.source().parse::<toml::Table>() {
+        Ok(table) => flatten("", &table, &mut messages),
+        Err(e) => {
+            tracing::warn!(locale = locale.tag(), error = %e, "ignoring unreadable message catalog")
+        }
+    }
+    messages
+}
+
+fn flatten(prefix: &str, table: &toml::Table, out: &mut Messages) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::Table(table) => flatten(&key, table, out),
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Messages for one locale, falling back to English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Catalog {
+    locale: Locale,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Self {
+        Self { locale }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// The message for `key`. Unknown keys come back as the key itself
+    /// rather than failing, so a typo shows up in the output.
+    pub fn text<'a>(&self, key: &'a str) -> &'a str {
+        messages(self.locale)
+            .get(key)
+            .or_else(|| messages(Locale::En).get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    /// The message for `key` with each `{name}` replaced by its value.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.text(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+
+    /// A translated headline for `code`, by code and then by category.
+    /// `None` in English, where the error's own message already reads well.
+    pub fn error_summary(&self, code: ErrorCode) -> Option<&'static str> {
+        if self.locale == Locale::En {
+            return None;
+        }
+        let messages = messages(self.locale);
+        messages
+            .get(&format!("errors.{}", code.as_str()))
+            .or_else(|| messages.get(&format!("errors.{}", code.category().as_str())))
+            .map(String::as_str)
+    }
+}
+
+/// Look up a message in the active locale, filling in `{name}` placeholders:
+/// `tr!("merge.failed", stderr = err)`.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr $(,)?) => {
+        $crate::i18n::catalog().text($key).to_string()
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::catalog().format(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn translations_match_the_english_catalog() {
+        let english = messages(Locale::En);
+        assert!(english.contains_key("summary.title"));
+        for locale in Locale::ALL {
+            let catalog = messages(locale);
+            assert!(
+                !catalog.is_empty(),
+                "{} catalog did not parse",
+                locale.tag()
+            );
+            for (key, text) in catalog {
+                let reference = english
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{}: unknown key {key}", locale.tag()));
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(reference),
+                    "{}: placeholders of {key}",
+                    locale.tag()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parses_tags_and_posix_locales() {
+        assert_eq!(Locale::parse("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::parse("fr-CA"), Some(Locale::Fr));
+        assert_eq!(Locale::parse("ES"), Some(Locale::Es));
+        assert_eq!(Locale::parse("C.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::parse("ja_JP"), None);
+        assert_eq!(Locale::resolve(Some("es")), Locale::Es);
+    }
+
+    #[test]
+    fn formats_with_english_fallback() {
+        let german = Catalog::new(Locale::De);
+        assert_eq!(
+            german.format("merge.failed", &[("stderr", &"boom")]),
+            "Merge fehlgeschlagen: boom"
+        );
+        assert_eq!(german.text("no.such.key"), "no.such.key");
+
+        assert_eq!(
+            Catalog::new(Locale::En).error_summary(ErrorCode::GitFailed),
+            None
+        );
+        assert_eq!(
+            german.error_summary(ErrorCode::AdapterMissing),
+            Some("Die Agent-CLI ist nicht installiert")
+        );
+        assert_eq!(
+            german.error_summary(ErrorCode::GitFailed),
+            Some("Ein Git-Befehl ist fehlgeschlagen")
+        );
+    }
+}
@@ -1,9 +1,17 @@
 pub mod adapter;
 pub mod artifact;
 pub mod config;
+pub mod diagnostics;
+pub mod error;
 pub mod git_exec;
 pub mod git_ref;
+pub mod i18n;
+pub mod notify;
+pub mod orchestrator;
 pub mod scoring;
 pub mod security;
 pub mod supervisor;
+pub mod task;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod worktree;
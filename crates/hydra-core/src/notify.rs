@@ -0,0 +1,299 @@
+//! Chat notifications (Slack and Discord incoming webhooks) posted when a
+//! race finishes, configured under `[[notifications.webhooks]]`.
+//!
+//! Webhooks are posted with `curl`, which keeps a TLS stack out of this
+//! crate. The URL and body are passed on curl's stdin so webhook secrets
+//! never show up in the process list.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::artifact::RunStatus;
+use crate::config::{HydraConfig, NotifyOn, WebhookConfig, WebhookKind};
+use crate::orchestrator::RaceOutcome;
+use crate::security::SecretRedactor;
+
+pub const DEFAULT_TEMPLATE: &str = "Hydra race {status}. Winner: {winner}, score {score}, cost {cost}.\n> {prompt}\nRun {run_id} took {duration}.";
+
+/// Longest prompt excerpt included in a message.
+const PROMPT_SUMMARY_CHARS: usize = 120;
+/// Discord rejects messages longer than this.
+const DISCORD_MAX_CHARS: usize = 2000;
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Error)]
+pub enum NotifyError {
+    #[error("environment variable {0} is not set")]
+    MissingUrl(String),
+
+    #[error("failed to run curl: {0}")]
+    CurlUnavailable(#[source] std::io::Error),
+
+    #[error("webhook request failed: {0}")]
+    RequestFailed(String),
+}
+
+/// The values a message template can refer to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaceSummary {
+    pub run_id: String,
+    pub status: String,
+    pub prompt: String,
+    pub winner: Option<String>,
+    pub score: Option<f64>,
+    pub cost_usd: Option<f64>,
+    pub duration: Duration,
+    succeeded: bool,
+}
+
+impl RaceSummary {
+    /// Summarize `outcome`. The prompt is cut to its first line and
+    /// secret-redacted, since messages leave the machine.
+    pub fn new(outcome: &RaceOutcome, prompt: &str, redactor: &SecretRedactor) -> Self {
+        let winner = outcome.winner().map(str::to_string);
+        let score = winner.as_deref().and_then(|key| {
+            outcome
+                .rankings
+                .iter()
+                .find(|s| s.agent_key == key)
+                .map(|s| s.composite)
+        });
+        Self {
+            run_id: outcome.run_id.to_string(),
+            status: status_label(&outcome.status).to_string(),
+            prompt: prompt_summary(&redactor.redact(prompt).value),
+            winner,
+            score,
+            cost_usd: outcome.usage.estimated_cost_usd,
+            duration: Duration::from_millis(outcome.duration_ms),
+            succeeded: outcome.status == RunStatus::Completed,
+        }
+    }
+
+    /// Fill in `template`'s placeholders.
+    pub fn render(&self, template: &str) -> String {
+        let score = self
+            .score
+            .map(|s| format!("{s:.1}"))
+            .unwrap_or_else(|| "n/a".to_string());
+        let cost = self
+            .cost_usd
+            .map(|c| format!("${c:.2}"))
+            .unwrap_or_else(|| "n/a".to_string());
+        template
+            .replace("{run_id}", &self.run_id)
+            .replace("{status}", &self.status)
+            .replace("{winner}", self.winner.as_deref().unwrap_or("none"))
+            .replace("{score}", &score)
+            .replace("{cost}", &cost)
+            .replace("{duration}", &format_duration(self.duration))
+            // Last, so placeholders typed into the prompt stay literal.
+            .replace("{prompt}", &self.prompt)
+    }
+
+    fn wanted_by(&self, webhook: &WebhookConfig) -> bool {
+        match webhook.on {
+            NotifyOn::Always => true,
+            NotifyOn::Success => self.succeeded,
+            NotifyOn::Failure => !self.succeeded,
+        }
+    }
+}
+
+/// Post `outcome` to every configured webhook. Failures are logged and
+/// never fail the race.
+pub async fn race_finished(config: &HydraConfig, prompt: &str, outcome: &RaceOutcome) {
+    let webhooks = &config.notifications.webhooks;
+    if webhooks.is_empty() {
+        return;
+    }
+    let redactor = SecretRedactor::from_config(&config.security.redaction);
+    let summary = RaceSummary::new(outcome, prompt, &redactor);
+    for webhook in webhooks.iter().filter(|w| summary.wanted_by(w)) {
+        if let Err(e) = send(webhook, &summary).await {
+            tracing::warn!(
+                run_id = %summary.run_id,
+                kind = ?webhook.kind,
+                error = %e,
+                "failed to send race notification"
+            );
+        }
+    }
+}
+
+/// Post `summary` to a single webhook.
+pub async fn send(webhook: &WebhookConfig, summary: &RaceSummary) -> Result<(), NotifyError> {
+    let url = match (&webhook.url, &webhook.url_env) {
+        (Some(url), _) => url.clone(),
+        (None, Some(var)) => std::env::var(var)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .ok_or_else(|| NotifyError::MissingUrl(var.clone()))?,
+        // Rejected by config validation.
+        (None, None) => return Ok(()),
+    };
+    let text = summary.render(webhook.template.as_deref().unwrap_or(DEFAULT_TEMPLATE));
+    post_json(&url, &payload(webhook.kind, &text)).await
+}
+
+fn payload(kind: WebhookKind, text: &str) -> serde_json::Value {
+    match kind {
+        WebhookKind::Slack => serde_json::json!({ "text": text }),
+        WebhookKind::Discord => {
+            let content: String = if text.chars().count() > DISCORD_MAX_CHARS {
+                let mut cut: String = text.chars().take(DISCORD_MAX_CHARS - 1).collect();
+                cut.push('…');
+                cut
+            } else {
+                text.to_string()
+            };
+            serde_json::json!({ "content": content })
+        }
+    }
+}
+
+async fn post_json(url: &str, body: &serde_json::Value) -> Result<(), NotifyError> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--proto",
+            "=http,https",
+        ])
+        .arg("--max-time")
+        .arg(REQUEST_TIMEOUT_SECS.to_string())
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(NotifyError::CurlUnavailable)?;
+
+    let config = format!(
+        "url = \"{}\"\nheader = \"Content-Type: application/json\"\ndata-binary = \"{}\"\n",
+        curl_quote(url),
+        curl_quote(&body.to_string())
+    );
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .await
+            .map_err(NotifyError::CurlUnavailable)?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(NotifyError::CurlUnavailable)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(NotifyError::RequestFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Escape a value for a double-quoted string in a curl config file.
+fn curl_quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn prompt_summary(prompt: &str) -> String {
+    let line = prompt
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() > PROMPT_SUMMARY_CHARS || prompt.trim() != line {
+        let mut summary: String = line.chars().take(PROMPT_SUMMARY_CHARS).collect();
+        summary.push('…');
+        summary
+    } else {
+        line.to_string()
+    }
+}
+
+fn status_label(status: &RunStatus) -> &'static str {
+    match status {
+        RunStatus::Running => "running",
+        RunStatus::Completed => "completed",
+        RunStatus::Failed => "failed",
+        RunStatus::TimedOut => "timed out",
+        RunStatus::Interrupted => "interrupted",
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> RaceSummary {
+        RaceSummary {
+            run_id: "0193".to_string(),
+            status: "completed".to_string(),
+            prompt: "Fix the {winner} parser".to_string(),
+            winner: Some("claude".to_string()),
+            score: Some(91.46),
+            cost_usd: Some(0.4213),
+            duration: Duration::from_secs(754),
+            succeeded: true,
+        }
+    }
+
+    #[test]
+    fn default_template_renders_every_field() {
+        assert_eq!(
+            summary().render(DEFAULT_TEMPLATE),
+            "Hydra race completed. Winner: claude, score 91.5, cost $0.42.\n\
+             > Fix the {winner} parser\n\
+             Run 0193 took 12m 34s."
+        );
+
+        let empty = RaceSummary {
+            winner: None,
+            score: None,
+            cost_usd: None,
+            ..summary()
+        };
+        assert_eq!(empty.render("{winner} {score} {cost}"), "none n/a n/a");
+    }
+
+    #[test]
+    fn prompt_summary_keeps_the_first_line() {
+        assert_eq!(
+            prompt_summary("  \nAdd retries\nto the client"),
+            "Add retries…"
+        );
+        assert_eq!(prompt_summary("Add retries"), "Add retries");
+        assert_eq!(prompt_summary(&"x".repeat(200)).chars().count(), 121);
+    }
+
+    #[test]
+    fn payload_matches_each_service() {
+        assert_eq!(
+            payload(WebhookKind::Slack, "hi"),
+            serde_json::json!({ "text": "hi" })
+        );
+        let long = payload(WebhookKind::Discord, &"x".repeat(3000));
+        assert_eq!(
+            long["content"].as_str().unwrap().chars().count(),
+            DISCORD_MAX_CHARS
+        );
+        assert_eq!(curl_quote(r#"{"a":"b\n"}"#), r#"{\"a\":\"b\\n\"}"#);
+    }
+}
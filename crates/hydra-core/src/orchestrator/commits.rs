@@ -0,0 +1,95 @@
+//! Agent-created commits: some agent CLIs commit their work, others leave it
+//! in the worktree. Once an agent finishes, find out which it did so the
+//! candidate can be compared with the run's base commit either way.
+
+use std::path::Path;
+
+use crate::artifact::{AgentCommit, CandidateCommits, CommitStrategy};
+use crate::git_exec::{run_git, GitExecError};
+
+/// Compare the worktree at `worktree` with `base_commit`: the commits on its
+/// `HEAD` that the base does not have, and whether anything is left
+/// uncommitted. Run artifacts under `.hydra/` do not count as changes.
+pub(super) async fn detect_candidate_commits(
+    worktree: &Path,
+    base_commit: &str,
+) -> Result<CandidateCommits, GitExecError> {
+    let range = format!("{base_commit}..HEAD");
+    let log = run_git(&["log", "--reverse", "--format=%H%x09%s", &range], worktree).await?;
+    let commits: Vec<AgentCommit> = log
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(sha, subject)| AgentCommit {
+            sha: sha.to_string(),
+            subject: subject.to_string(),
+        })
+        .collect();
+
+    let status = run_git(
+        &["status", "--porcelain", "-z", "--untracked-files=all"],
+        worktree,
+    )
+    .await?;
+    let dirty = status
+        .stdout
+        .split('\0')
+        .filter_map(|entry| entry.get(3..))
+        .any(|path| !path.is_empty() && !path.starts_with(".hydra/"));
+
+    let strategy = match (commits.is_empty(), dirty) {
+        (true, false) => CommitStrategy::Unchanged,
+        (true, true) => CommitStrategy::Uncommitted,
+        (false, false) => CommitStrategy::Committed,
+        (false, true) => CommitStrategy::Mixed,
+    };
+    Ok(CandidateCommits { strategy, commits })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn git(dir: &Path, args: &[&str]) -> String {
+        run_git(args, dir).await.unwrap().stdout.trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn tells_committed_work_from_worktree_changes() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        git(repo, &["init", "-q"]).await;
+        git(repo, &["config", "user.email", "t@example.com"]).await;
+        git(repo, &["config", "user.name", "T"]).await;
+        std::fs::write(repo.join("a"), "a").unwrap();
+        git(repo, &["add", "."]).await;
+        git(repo, &["commit", "-qm", "base"]).await;
+        let base = git(repo, &["rev-parse", "HEAD"]).await;
+
+        let detect = || detect_candidate_commits(repo, &base);
+        assert_eq!(detect().await.unwrap().strategy, CommitStrategy::Unchanged);
+
+        std::fs::create_dir(repo.join(".hydra")).unwrap();
+        std::fs::write(repo.join(".hydra/state"), "").unwrap();
+        std::fs::write(repo.join("b"), "b").unwrap();
+        assert_eq!(
+            detect().await.unwrap().strategy,
+            CommitStrategy::Uncommitted
+        );
+
+        git(repo, &["add", "b"]).await;
+        git(repo, &["commit", "-qm", "Add b"]).await;
+        let committed = detect().await.unwrap();
+        assert_eq!(committed.strategy, CommitStrategy::Committed);
+        assert_eq!(committed.commits.len(), 1);
+        assert_eq!(committed.commits[0].subject, "Add b");
+        assert_eq!(
+            committed.commits[0].sha,
+            git(repo, &["rev-parse", "HEAD"]).await
+        );
+
+        std::fs::write(repo.join("a"), "changed").unwrap();
+        assert_eq!(detect().await.unwrap().strategy, CommitStrategy::Mixed);
+    }
+}
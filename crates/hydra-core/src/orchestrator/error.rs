@@ -0,0 +1,227 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::adapter::{AdapterError, RegistryError};
+use crate::artifact::ArtifactError;
+use crate::git_exec::GitExecError;
+use crate::scoring::baseline::BaselineError;
+use crate::security::ConsentError;
+use crate::supervisor::SupervisorError;
+use crate::worktree::WorktreeError;
+
+#[derive(Debug, Error)]
+pub enum OrchestratorError {
+    #[error("no adapters selected for race")]
+    NoAgents,
+
+    #[error("invalid prompt variants: {reason}")]
+    InvalidVariants { reason: String },
+
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+
+    #[error("run {run_id} already exists; remove it with `hydra run rm {run_id}` to race again")]
+    RunExists { run_id: Uuid },
+
+    #[error("adapter '{adapter}' is not ready ({status}): {detail}")]
+    AdapterNotReady {
+        adapter: String,
+        status: String,
+        detail: String,
+    },
+
+    #[error(transparent)]
+    UnsafeConsent(#[from] ConsentError),
+
+    #[error("{period} cost budget used up: ${spent:.2} of ${limit:.2} spent")]
+    SpendLimitReached {
+        period: &'static str,
+        spent: f64,
+        limit: f64,
+    },
+
+    #[error("failed to create worktree for {adapter}: {source}")]
+    Worktree {
+        adapter: String,
+        source: WorktreeError,
+    },
+
+    #[error("failed to capture baseline: {0}")]
+    Baseline(#[from] BaselineError),
+
+    #[error("failed to build agent command: {0}")]
+    Command(#[from] AdapterError),
+
+    #[error("sandbox blocked command cwd '{}' (allowed root '{}')", path.display(), allowed_root.display())]
+    SandboxBlocked {
+        path: PathBuf,
+        allowed_root: PathBuf,
+    },
+
+    #[error("failed to supervise agent process: {0}")]
+    Supervisor(#[from] SupervisorError),
+
+    #[error("{0}")]
+    TaskFailed(String),
+
+    #[error("no changes to promote in '{}'", path.display())]
+    NothingToPromote { path: PathBuf },
+
+    #[error(transparent)]
+    Git(#[from] GitExecError),
+
+    #[error("failed scoring candidate for agent '{adapter}': {source}")]
+    Scoring {
+        adapter: String,
+        source: std::io::Error,
+    },
+
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+
+    #[error("artifact I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("artifact serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("{context}: {source}")]
+    Context {
+        context: ErrorContext,
+        source: Box<OrchestratorError>,
+    },
+}
+
+impl OrchestratorError {
+    /// Attach `context` to the error. Fields already set by a context closer
+    /// to where the error was raised are kept.
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            OrchestratorError::Context {
+                context: inner,
+                source,
+            } => OrchestratorError::Context {
+                context: ErrorContext {
+                    run_id: inner.run_id.or(context.run_id),
+                    agent_key: inner.agent_key.or(context.agent_key),
+                    phase: inner.phase.or(context.phase),
+                },
+                source,
+            },
+            error => OrchestratorError::Context {
+                context,
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// The run, agent and phase the error was raised in, where known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            OrchestratorError::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The error without its context.
+    pub fn root(&self) -> &OrchestratorError {
+        match self {
+            OrchestratorError::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+}
+
+/// Which part of a race or promotion was running when an error was raised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorPhase {
+    /// Resolving adapters, creating worktrees and writing the manifest.
+    Setup,
+    Baseline,
+    Agents,
+    Scoring,
+    Cleanup,
+    Promote,
+}
+
+impl ErrorPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorPhase::Setup => "setup",
+            ErrorPhase::Baseline => "baseline",
+            ErrorPhase::Agents => "agents",
+            ErrorPhase::Scoring => "scoring",
+            ErrorPhase::Cleanup => "cleanup",
+            ErrorPhase::Promote => "promote",
+        }
+    }
+}
+
+impl fmt::Display for ErrorPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Identifies the run, agent and phase an [`OrchestratorError`] came from,
+/// for logs and JSON error output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<Uuid>,
+    #[serde(rename = "agent", skip_serializing_if = "Option::is_none")]
+    pub agent_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<ErrorPhase>,
+}
+
+impl ErrorContext {
+    pub fn agent(agent_key: &str) -> Self {
+        Self {
+            agent_key: Some(agent_key.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Context of the first [`OrchestratorError`] in `err`'s source chain
+    /// that carries one.
+    pub fn find<'a>(err: &'a (dyn StdError + 'static)) -> Option<&'a ErrorContext> {
+        let mut current = Some(err);
+        while let Some(err) = current {
+            if let Some(context) = err
+                .downcast_ref::<OrchestratorError>()
+                .and_then(OrchestratorError::context)
+            {
+                return Some(context);
+            }
+            current = err.source();
+        }
+        None
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(run_id) = self.run_id {
+            parts.push(format!("run {run_id}"));
+        }
+        if let Some(agent) = &self.agent_key {
+            parts.push(format!("agent '{agent}'"));
+        }
+        if let Some(phase) = self.phase {
+            parts.push(format!("during {phase}"));
+        }
+        if parts.is_empty() {
+            f.write_str("orchestrator")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Base-branch freshness: before any worktree is created, fetch the base
+//! branch's upstream and see whether the race would start from stale code.
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::config::BaseFreshnessPolicy;
+use crate::git_exec::{run_git, run_git_program_with_timeout, GitExecError};
+
+/// Fetching is best effort; a slow or unreachable remote must not hold up
+/// the race for long.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How the base branch compares to its upstream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BaseFreshness {
+    /// Local branch the base ref names, e.g. `refs/heads/main`.
+    pub branch: String,
+    /// Upstream it was compared with, e.g. `origin/main`.
+    pub upstream: String,
+    /// Commits on the branch that the upstream does not have.
+    pub ahead: u32,
+    /// Commits on the upstream that the branch does not have, after any
+    /// fast-forward.
+    pub behind: u32,
+    /// Whether the branch was fast-forwarded to the upstream.
+    pub fast_forwarded: bool,
+    /// Why the fetch failed; the comparison then used the last fetched
+    /// state of the upstream.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_error: Option<String>,
+}
+
+impl BaseFreshness {
+    /// Worth telling the user about: still behind, or moved under them.
+    pub fn is_notable(&self) -> bool {
+        self.behind > 0 || self.fast_forwarded
+    }
+}
+
+/// Compare `base_ref` with its upstream under `policy`. `None` when the check
+/// is off, or `base_ref` is not a local branch with an upstream. Git failures
+/// other than the fetch are logged and also give `None`: a stale-base check
+/// must never stop a race.
+pub(super) async fn check_base_freshness(
+    repo_root: &Path,
+    base_ref: &str,
+    policy: BaseFreshnessPolicy,
+) -> Option<BaseFreshness> {
+    if policy == BaseFreshnessPolicy::Off {
+        return None;
+    }
+    match compare(repo_root, base_ref, policy).await {
+        Ok(freshness) => freshness,
+        Err(e) => {
+            tracing::warn!(base_ref, error = %e, "skipping base freshness check");
+            None
+        }
+    }
+}
+
+async fn compare(
+    repo_root: &Path,
+    base_ref: &str,
+    policy: BaseFreshnessPolicy,
+) -> Result<Option<BaseFreshness>, GitExecError> {
+    let branch = git(repo_root, &["rev-parse", "--symbolic-full-name", base_ref]).await?;
+    if !branch.starts_with("refs/heads/") {
+        return Ok(None);
+    }
+    let tracking = git(
+        repo_root,
+        &[
+            "for-each-ref",
+            "--format=%(upstream)\t%(upstream:short)\t%(upstream:remotename)\t%(upstream:remoteref)",
+            &branch,
+        ],
+    )
+    .await?;
+    let [upstream_ref, upstream, remote, remote_ref] =
+        match tracking.split('\t').collect::<Vec<_>>()[..] {
+            [a, b, c, d] if !a.is_empty() => [a, b, c, d],
+            _ => return Ok(None),
+        };
+
+    let mut fetch_error = None;
+    if remote != "." && !remote.is_empty() && !remote_ref.is_empty() {
+        if let Err(e) = run_git_program_with_timeout(
+            "git",
+            &["fetch", "--quiet", "--no-tags", remote, remote_ref],
+            repo_root,
+            FETCH_TIMEOUT,
+        )
+        .await
+        {
+            tracing::warn!(remote, error = %e, "could not fetch the base branch's upstream");
+            fetch_error = Some(fetch_error_summary(&e));
+        }
+    }
+
+    let (ahead, mut behind) = ahead_behind(repo_root, &branch, upstream_ref).await?;
+    let mut fast_forwarded = false;
+    if behind > 0 && ahead == 0 && policy == BaseFreshnessPolicy::FastForward {
+        match fast_forward(repo_root, &branch, upstream_ref).await {
+            Ok(()) => {
+                tracing::info!(branch, upstream, behind, "fast-forwarded base branch");
+                fast_forwarded = true;
+                behind = 0;
+            }
+            Err(e) => tracing::warn!(branch, error = %e, "could not fast-forward base branch"),
+        }
+    }
+
+    Ok(Some(BaseFreshness {
+        branch,
+        upstream: upstream.to_string(),
+        ahead,
+        behind,
+        fast_forwarded,
+        fetch_error,
+    }))
+}
+
+async fn ahead_behind(
+    repo_root: &Path,
+    branch: &str,
+    upstream_ref: &str,
+) -> Result<(u32, u32), GitExecError> {
+    let range = format!("{branch}...{upstream_ref}");
+    let counts = git(repo_root, &["rev-list", "--left-right", "--count", &range]).await?;
+    let mut parts = counts
+        .split_whitespace()
+        .map(|n| n.parse::<u32>().unwrap_or(0));
+    Ok((parts.next().unwrap_or(0), parts.next().unwrap_or(0)))
+}
+
+/// Move `branch` to `upstream_ref`. A checked-out branch is merged so the
+/// working tree follows, which git refuses if local changes would be
+/// overwritten; any other branch is updated with a fast-forward-only fetch.
+async fn fast_forward(
+    repo_root: &Path,
+    branch: &str,
+    upstream_ref: &str,
+) -> Result<(), GitExecError> {
+    let head = git(repo_root, &["symbolic-ref", "-q", "HEAD"])
+        .await
+        .unwrap_or_default();
+    if head == branch {
+        run_git(&["merge", "--ff-only", "--quiet", upstream_ref], repo_root).await?;
+    } else {
+        let refspec = format!("{upstream_ref}:{branch}");
+        run_git(&["fetch", "--quiet", ".", &refspec], repo_root).await?;
+    }
+    Ok(())
+}
+
+async fn git(repo_root: &Path, args: &[&str]) -> Result<String, GitExecError> {
+    Ok(run_git(args, repo_root).await?.stdout.trim().to_string())
+}
+
+fn fetch_error_summary(err: &GitExecError) -> String {
+    match err {
+        GitExecError::NonZeroExit { stderr, .. } => stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("git fetch failed")
+            .to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn git_in(dir: &Path, args: &[&str]) {
+        run_git(args, dir).await.unwrap();
+    }
+
+    async fn commit(dir: &Path, file: &str) {
+        std::fs::write(dir.join(file), file).unwrap();
+        git_in(dir, &["add", "."]).await;
+        git_in(dir, &["commit", "-qm", file]).await;
+    }
+
+    /// An `origin` repository and a clone of it that is one commit behind.
+    async fn stale_clone(tmp: &TempDir) -> std::path::PathBuf {
+        let origin = tmp.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git_in(&origin, &["init", "-q", "-b", "main"]).await;
+        git_in(&origin, &["config", "user.email", "t@example.com"]).await;
+        git_in(&origin, &["config", "user.name", "T"]).await;
+        commit(&origin, "a").await;
+
+        git_in(tmp.path(), &["clone", "-q", "origin", "clone"]).await;
+        commit(&origin, "b").await;
+        tmp.path().join("clone")
+    }
+
+    #[tokio::test]
+    async fn warns_when_the_base_is_behind_after_fetching() {
+        let tmp = TempDir::new().unwrap();
+        let clone = stale_clone(&tmp).await;
+
+        let freshness = check_base_freshness(&clone, "HEAD", BaseFreshnessPolicy::Warn)
+            .await
+            .unwrap();
+        assert_eq!(freshness.branch, "refs/heads/main");
+        assert_eq!(freshness.upstream, "origin/main");
+        assert_eq!((freshness.ahead, freshness.behind), (0, 1));
+        assert!(!freshness.fast_forwarded);
+        assert!(freshness.fetch_error.is_none());
+        assert!(freshness.is_notable());
+
+        assert!(
+            check_base_freshness(&clone, "HEAD", BaseFreshnessPolicy::Off)
+                .await
+                .is_none()
+        );
+        let head = git(&clone, &["rev-parse", "HEAD"]).await.unwrap();
+        assert!(
+            check_base_freshness(&clone, &head, BaseFreshnessPolicy::Warn)
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn fast_forwards_the_checked_out_base() {
+        let tmp = TempDir::new().unwrap();
+        let clone = stale_clone(&tmp).await;
+
+        let freshness = check_base_freshness(&clone, "main", BaseFreshnessPolicy::FastForward)
+            .await
+            .unwrap();
+        assert!(freshness.fast_forwarded);
+        assert_eq!(freshness.behind, 0);
+        assert!(clone.join("b").exists());
+        assert_eq!(
+            git(&clone, &["rev-parse", "main"]).await.unwrap(),
+            git(&clone, &["rev-parse", "origin/main"]).await.unwrap()
+        );
+    }
+}
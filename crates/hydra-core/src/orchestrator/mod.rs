@@ -0,0 +1,486 @@
+//! Race orchestration: one task raced across several adapters, each in its
+//! own worktree, then scored against a baseline.
+//!
+//! Front ends (the CLI, the desktop app) call [`Orchestrator::race`] and
+//! follow progress through a [`RaceEventSink`]. Run artifacts are written
+//! under `.hydra/runs/<run_id>/` exactly as `hydra race` always has.
+
+mod commits;
+mod error;
+mod freshness;
+mod progress;
+mod promote;
+mod race;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::adapter::{AdapterTier, AgentEvent};
+use crate::artifact::{
+    CandidateCommits, EventSinks, FileOperation, LockHolder, LockMode, PromptSource,
+    RunHealthMetrics, RunStatus,
+};
+use crate::config::HydraConfig;
+use crate::scoring::baseline::ResolvedCommands;
+use crate::scoring::cost::CostEstimate;
+use crate::scoring::diff_scope::ScopePrecheck;
+use crate::scoring::ranking::AgentScore;
+use crate::security::{ApprovalDecision, DangerousAction, InjectionFinding};
+use crate::task::TaskSpec;
+
+pub use error::{ErrorContext, ErrorPhase, OrchestratorError};
+pub use freshness::BaseFreshness;
+pub use progress::{AgentPhase, ProgressEstimate, ProgressEstimator};
+
+/// Runs races for one repository with a fixed config.
+#[derive(Debug, Clone)]
+pub struct Orchestrator {
+    repo_root: PathBuf,
+    config: HydraConfig,
+}
+
+impl Orchestrator {
+    pub fn new(repo_root: impl Into<PathBuf>, config: HydraConfig) -> Self {
+        Self {
+            repo_root: repo_root.into(),
+            config,
+        }
+    }
+
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    pub fn config(&self) -> &HydraConfig {
+        &self.config
+    }
+
+    /// Race `opts.prompt` across the selected adapters and score the
+    /// results.
+    ///
+    /// Setup failures (unknown or unavailable adapters, worktree or baseline
+    /// errors) return `Err` after rolling back any worktrees created so far.
+    /// Once agents start, agent failures are reported in the outcome rather
+    /// than as errors.
+    pub async fn race(
+        &self,
+        opts: RaceOptions,
+        sink: Arc<dyn RaceEventSink>,
+    ) -> Result<RaceOutcome, OrchestratorError> {
+        // Recorded by `race::run` once the id is known, before any child
+        // span opens, so every span of the race carries the same id.
+        let prompt = opts.prompt.clone();
+        let outcome = race::run(self, opts, sink)
+            .instrument(tracing::info_span!("race", run_id = tracing::field::Empty))
+            .await?;
+        crate::notify::race_finished(&self.config, &prompt, &outcome).await;
+        Ok(outcome)
+    }
+
+    /// Turn work done outside a race (an interactive session's checkout)
+    /// into a scored, single-agent run that can be merged like any race
+    /// candidate.
+    ///
+    /// The changes since `opts.base_ref` are committed to a fresh
+    /// `hydra/<run_id>/agent/<key>` branch, which is kept after scoring.
+    /// Nothing is written when the checkout has no changes.
+    pub async fn promote(&self, opts: PromoteOptions) -> Result<PromoteOutcome, OrchestratorError> {
+        promote::run(self, opts).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RaceOptions {
+    /// Adapter keys to race. When empty, `race.default_agents` from the
+    /// config is used, then every Tier-1 adapter.
+    pub agents: Vec<String>,
+    pub prompt: String,
+    /// Recorded in the manifest when `prompt` was built from a GitHub issue
+    /// or pull request.
+    pub prompt_source: Option<PromptSource>,
+    /// User-defined labels recorded in the manifest.
+    pub tags: Vec<String>,
+    /// Structured task `prompt` was rendered from. Each adapter gets its own
+    /// rendering, the spec's paths are applied to scoring and write policy,
+    /// and it is stored in the run as `task.json`.
+    pub task: Option<TaskSpec>,
+    /// Race the one selected adapter once per variant instead of once with
+    /// `prompt`. Each variant is its own candidate, keyed
+    /// `<adapter>-<name>`, with its own worktree and branch.
+    pub variants: Vec<PromptVariant>,
+    pub base_ref: String,
+    pub unsafe_mode: bool,
+    /// Token from [`ConsentStore::grant`](crate::security::ConsentStore::grant)
+    /// authorizing `unsafe_mode`; the repository's current grant when
+    /// `None`.
+    pub unsafe_consent: Option<String>,
+    pub allow_experimental_adapters: bool,
+    /// Run ID to use instead of a fresh one.
+    pub run_id: Option<Uuid>,
+    /// Derive the run ID from the race inputs (prompt, base commit, agents
+    /// and config) instead of generating a random one, so repeating the
+    /// same race yields the same ID. Ignored when `run_id` is set.
+    pub deterministic: bool,
+    /// What to do when another run in the repository is still setting up
+    /// and holds `.hydra/lock`.
+    pub lock: LockMode,
+    /// Score each agent as soon as it completes and stop the others once
+    /// one is mergeable with a composite of at least
+    /// `race.success_threshold`. Otherwise every agent runs to the end.
+    pub stop_on_first_success: bool,
+    /// Reuse the most recent baseline captured on the same base commit with
+    /// the same scoring commands instead of running them again. Without
+    /// one, agents are scored with nothing to compare against.
+    pub skip_baseline: bool,
+    /// Stops every agent when cancelled, like the run cancel marker.
+    pub cancel: CancelToken,
+    /// Where answers to [`RaceEvent::ApprovalRequested`] are sent under
+    /// `security.approval.policy = "ask"`.
+    pub approvals: ApprovalGate,
+    /// Receive every event of the run and its agents as it is written to
+    /// their `events.jsonl`.
+    pub event_sinks: EventSinks,
+}
+
+/// One phrasing of the task in a prompt-variant race.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptVariant {
+    /// Short label, appended to the adapter key to name the candidate.
+    pub name: String,
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PromoteOptions {
+    pub agent_key: String,
+    /// Checkout holding the work to promote.
+    pub source_path: PathBuf,
+    /// Ref the work started from; resolved inside `source_path`, and the
+    /// merge base with its `HEAD` is what gets diffed against.
+    pub base_ref: String,
+    /// Recorded as the run's task prompt and used as the commit message.
+    pub description: String,
+    pub run_id: Option<Uuid>,
+}
+
+/// Result of [`Orchestrator::promote`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PromoteOutcome {
+    pub run_id: Uuid,
+    pub agent_key: String,
+    pub branch: String,
+    /// Commit the candidate branch was cut from.
+    pub base_ref: String,
+    pub artifacts_dir: PathBuf,
+    pub worktree_removed: bool,
+    pub score: AgentScore,
+}
+
+/// Shared flag for cancelling a race from another task.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Approval requests a race is waiting on. The orchestrator opens a
+/// request and pauses the agent; the front end answers it with
+/// [`resolve`](Self::resolve) after seeing [`RaceEvent::ApprovalRequested`].
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalGate(Arc<Mutex<HashMap<Uuid, oneshot::Sender<ApprovalDecision>>>>);
+
+impl ApprovalGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer a pending request. Returns `false` if there is no such
+    /// request, e.g. because the agent has already finished.
+    pub fn resolve(&self, approval_id: Uuid, decision: ApprovalDecision) -> bool {
+        let sender = self.lock().remove(&approval_id);
+        sender.is_some_and(|tx| tx.send(decision).is_ok())
+    }
+
+    /// IDs of the requests still waiting for an answer.
+    pub fn pending(&self) -> Vec<Uuid> {
+        self.lock().keys().copied().collect()
+    }
+
+    pub(crate) fn open(&self) -> (Uuid, oneshot::Receiver<ApprovalDecision>) {
+        let (tx, rx) = oneshot::channel();
+        let approval_id = Uuid::new_v4();
+        self.lock().insert(approval_id, tx);
+        (approval_id, rx)
+    }
+
+    pub(crate) fn withdraw(&self, approval_id: Uuid) {
+        self.lock().remove(&approval_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, oneshot::Sender<ApprovalDecision>>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Live progress of a race, in the order it happens.
+#[derive(Debug, Clone)]
+pub enum RaceEvent {
+    /// Another run holds the repository lock; under [`LockMode::Wait`] the
+    /// race is queued until it is released. Sent once, before `RunStarted`.
+    Queued {
+        holder: LockHolder,
+    },
+    RunStarted {
+        run_id: Uuid,
+        agents: Vec<String>,
+    },
+    /// The base branch was behind its upstream: the race starts from it
+    /// anyway, or it was fast-forwarded first under
+    /// `race.base_freshness = "fast_forward"`. Sent before `RunStarted`.
+    StaleBase {
+        freshness: BaseFreshness,
+    },
+    /// Under [`RaceOptions::skip_baseline`], the baseline was taken from
+    /// run `reused_from`, or left empty when that is `None`.
+    BaselineSkipped {
+        reused_from: Option<Uuid>,
+    },
+    /// The task prompt, or a repository file it refers to, contains text
+    /// that looks like instructions to the agents. With `stripped`, the
+    /// agents were given a cleaned-up prompt.
+    PromptInjectionDetected {
+        findings: Vec<InjectionFinding>,
+        stripped: bool,
+    },
+    /// The agent's process is up.
+    AgentStarted {
+        agent_key: String,
+    },
+    /// A raw output line, as recorded in the agent's `events.jsonl`.
+    AgentOutput {
+        agent_key: String,
+        stream: OutputStream,
+        line: String,
+    },
+    /// A line the adapter parsed into a structured event.
+    AgentEvent {
+        agent_key: String,
+        event: AgentEvent,
+        /// Tokens the agent has used so far.
+        total_tokens: u64,
+    },
+    /// The agent's estimated progress moved; see [`ProgressEstimator`].
+    AgentProgress {
+        agent_key: String,
+        progress: ProgressEstimate,
+    },
+    /// The agent reported editing `path`. `files` and `churn` cover every
+    /// file it has edited so far, as recorded in `edited_files.json`.
+    FileEdited {
+        agent_key: String,
+        path: String,
+        operation: FileOperation,
+        files: usize,
+        churn: u32,
+        scope: ScopePrecheck,
+    },
+    /// The agent is paused until the request is answered through
+    /// [`RaceOptions::approvals`].
+    ApprovalRequested {
+        agent_key: String,
+        approval_id: Uuid,
+        action: DangerousAction,
+    },
+    ApprovalResolved {
+        agent_key: String,
+        approval_id: Uuid,
+        decision: ApprovalDecision,
+    },
+    AgentFinished {
+        agent_key: String,
+        status: RunStatus,
+        error: Option<String>,
+        duration_ms: u64,
+        total_tokens: u64,
+    },
+    /// Run spend crossed a `scoring.budget.warn_at_percent` threshold with
+    /// `agent_key`'s latest usage.
+    BudgetWarning {
+        agent_key: String,
+        warning: BudgetWarning,
+    },
+    /// Under [`RaceOptions::stop_on_first_success`], `agent_key` passed the
+    /// gates and the agents still running are being stopped.
+    StoppingEarly {
+        agent_key: String,
+        composite: f64,
+    },
+    ScoringStarted,
+    ScoringFinished {
+        ranked_agents: usize,
+        error: Option<String>,
+    },
+    RunFinished {
+        status: RunStatus,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Receives [`RaceEvent`]s while a race runs. Called from agent tasks, so
+/// implementations must not block.
+pub trait RaceEventSink: Send + Sync {
+    fn emit(&self, event: RaceEvent);
+}
+
+impl<F> RaceEventSink for F
+where
+    F: Fn(RaceEvent) + Send + Sync,
+{
+    fn emit(&self, event: RaceEvent) {
+        self(event)
+    }
+}
+
+/// Which run budget a [`BudgetWarning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    Tokens,
+    CostUsd,
+}
+
+/// The run has used `percent` of one of its budgets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetWarning {
+    pub limit: BudgetLimit,
+    /// The threshold crossed, from `scoring.budget.warn_at_percent`.
+    pub percent: u8,
+    pub used: f64,
+    pub max: f64,
+}
+
+impl std::fmt::Display for BudgetWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            BudgetLimit::Tokens => write!(
+                f,
+                "{}% of the token budget used ({} of {})",
+                self.percent, self.used as u64, self.max as u64
+            ),
+            BudgetLimit::CostUsd => write!(
+                f,
+                "{}% of the cost budget used (${:.4} of ${:.4})",
+                self.percent, self.used, self.max
+            ),
+        }
+    }
+}
+
+/// Whether token usage was reported by an agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageStatus {
+    Captured,
+    /// The adapter reports usage but this run produced none.
+    Missing,
+    /// The adapter does not report usage.
+    Unavailable,
+}
+
+impl UsageStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UsageStatus::Captured => "captured",
+            UsageStatus::Missing => "missing",
+            UsageStatus::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// Result of a finished race.
+#[derive(Debug, Clone, Serialize)]
+pub struct RaceOutcome {
+    pub run_id: Uuid,
+    pub status: RunStatus,
+    pub duration_ms: u64,
+    pub artifacts_dir: PathBuf,
+    pub baseline_path: PathBuf,
+    pub commands: ResolvedCommands,
+    pub agents: Vec<AgentOutcome>,
+    /// Scored agents, best first. Empty when scoring failed.
+    pub rankings: Vec<AgentScore>,
+    /// Usage summed over all agents; the cost is `None` when no agent
+    /// reported one.
+    pub usage: CostEstimate,
+    pub budget_stop_reason: Option<String>,
+    /// Agent whose passing score stopped the race early.
+    pub stopped_early_by: Option<String>,
+    pub health: Option<RunHealthMetrics>,
+}
+
+impl RaceOutcome {
+    /// Highest-scoring mergeable agent, if any.
+    pub fn winner(&self) -> Option<&str> {
+        self.agents
+            .iter()
+            .filter_map(|a| a.score.as_ref())
+            .filter(|s| s.mergeable)
+            .max_by(|a, b| a.composite.total_cmp(&b.composite))
+            .map(|s| s.agent_key.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentOutcome {
+    pub agent_key: String,
+    pub tier: AdapterTier,
+    pub status: RunStatus,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub branch: String,
+    /// Whether the worktree was removed under `worktree.retain`.
+    pub worktree_cleaned: bool,
+    /// `None` when the agent task failed before reporting usage.
+    pub usage: Option<CostEstimate>,
+    pub usage_status: UsageStatus,
+    pub score: Option<AgentScore>,
+    /// What the agent wrote in `.hydra-notes.md`, secret-redacted.
+    pub notes: Option<String>,
+    /// Whether the agent committed its work; `None` when it could not be
+    /// checked.
+    pub commits: Option<CandidateCommits>,
+}
+
+impl AgentOutcome {
+    pub fn total_tokens(&self) -> Option<u64> {
+        self.usage.as_ref().map(|u| u.total_tokens)
+    }
+
+    pub fn estimated_cost_usd(&self) -> Option<f64> {
+        self.usage.as_ref().and_then(|u| u.estimated_cost_usd)
+    }
+}
@@ -0,0 +1,291 @@
+//! Heuristic progress for a running agent.
+//!
+//! Agent CLIs rarely report how far along they are, so progress is inferred
+//! from what they do: reading the code (planning), changing it (editing),
+//! then building and running tests (testing). Each phase covers a band of
+//! the percentage, and activity within a phase (tool calls and tokens)
+//! moves the estimate toward the top of the band without reaching it. The
+//! estimate never goes backwards, and a percentage the adapter does report
+//! always wins when it is higher.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::AgentEvent;
+
+/// What an agent appears to be doing, in the order agents usually work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentPhase {
+    Starting,
+    Planning,
+    Editing,
+    Testing,
+    Done,
+}
+
+impl AgentPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AgentPhase::Starting => "starting",
+            AgentPhase::Planning => "planning",
+            AgentPhase::Editing => "editing",
+            AgentPhase::Testing => "testing",
+            AgentPhase::Done => "done",
+        }
+    }
+
+    /// Percentage band the phase spans.
+    fn band(self) -> (f64, f64) {
+        match self {
+            AgentPhase::Starting => (0.0, 5.0),
+            AgentPhase::Planning => (5.0, 30.0),
+            AgentPhase::Editing => (30.0, 75.0),
+            AgentPhase::Testing => (75.0, 95.0),
+            AgentPhase::Done => (100.0, 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressEstimate {
+    pub phase: AgentPhase,
+    /// 0–100; only `Done` reaches 100.
+    pub percent: u8,
+    /// Token throughput since the agent started, once it has used any.
+    pub tokens_per_minute: Option<u64>,
+}
+
+/// Activity it takes to get about two thirds of the way through a phase's
+/// band: one unit per event, plus one per thousand tokens.
+const PHASE_ACTIVITY_SCALE: f64 = 12.0;
+const TOKENS_PER_ACTIVITY: f64 = 1000.0;
+
+/// Tracks one agent's events and turns them into a [`ProgressEstimate`].
+#[derive(Debug, Clone)]
+pub struct ProgressEstimator {
+    started: Instant,
+    phase: AgentPhase,
+    activity: f64,
+    percent: u8,
+    tokens: u64,
+}
+
+impl Default for ProgressEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressEstimator {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            phase: AgentPhase::Starting,
+            activity: 0.0,
+            percent: 0,
+            tokens: 0,
+        }
+    }
+
+    /// Account for `event`, with `total_tokens` the agent's usage so far.
+    /// Returns the new estimate when its phase or percentage changed.
+    pub fn observe(&mut self, event: &AgentEvent, total_tokens: u64) -> Option<ProgressEstimate> {
+        let before = (self.phase, self.percent);
+
+        let new_tokens = total_tokens.saturating_sub(self.tokens);
+        self.tokens = self.tokens.max(total_tokens);
+        self.activity += new_tokens as f64 / TOKENS_PER_ACTIVITY;
+        if !matches!(event, AgentEvent::Usage { .. }) {
+            self.activity += 1.0;
+        }
+
+        let reported = match event {
+            AgentEvent::Progress {
+                percent: Some(p), ..
+            } => Some(p.clamp(0.0, 99.0) as u8),
+            _ => None,
+        };
+        if let Some(phase) = classify(event) {
+            // Going back to reading code mid-edit is normal; only moving on
+            // to a later phase changes it.
+            if phase > self.phase {
+                self.phase = phase;
+                self.activity = 0.0;
+            }
+        }
+
+        let (low, high) = self.phase.band();
+        let within = 1.0 - (-self.activity / PHASE_ACTIVITY_SCALE).exp();
+        let estimated = (low + (high - low) * within).floor() as u8;
+        self.percent = self
+            .percent
+            .max(estimated)
+            .max(reported.unwrap_or_default());
+
+        ((self.phase, self.percent) != before).then(|| self.estimate())
+    }
+
+    pub fn estimate(&self) -> ProgressEstimate {
+        let minutes = self.started.elapsed().as_secs_f64() / 60.0;
+        ProgressEstimate {
+            phase: self.phase,
+            percent: self.percent,
+            tokens_per_minute: (self.tokens > 0 && minutes > 0.0)
+                .then(|| (self.tokens as f64 / minutes).round() as u64),
+        }
+    }
+}
+
+fn classify(event: &AgentEvent) -> Option<AgentPhase> {
+    match event {
+        AgentEvent::Message { .. } | AgentEvent::Progress { .. } => Some(AgentPhase::Planning),
+        AgentEvent::ToolCall { tool, input } => Some(classify_tool(tool, input)),
+        AgentEvent::Completed { .. } => Some(AgentPhase::Done),
+        AgentEvent::ToolResult { .. } | AgentEvent::Failed { .. } | AgentEvent::Usage { .. } => {
+            None
+        }
+    }
+}
+
+const EDIT_TOOLS: &[&str] = &["edit", "write", "patch", "replace", "insert", "create"];
+const SHELL_TOOLS: &[&str] = &["bash", "shell", "exec", "command", "terminal", "run"];
+const TEST_COMMANDS: &[&str] = &[
+    "test", "pytest", "jest", "vitest", "mocha", "check", "clippy", "lint", "build", "tsc", "vet",
+    "ruff", "mypy", "make",
+];
+const EDIT_COMMANDS: &[&str] = &["apply_patch", "tee", "mv", "cp", "rm", "mkdir", "touch"];
+
+fn classify_tool(tool: &str, input: &serde_json::Value) -> AgentPhase {
+    let tool = tool.to_ascii_lowercase();
+    // Task lists (Claude's TodoWrite) are planning, whatever their name.
+    if tool.contains("todo") {
+        return AgentPhase::Planning;
+    }
+    if EDIT_TOOLS.iter().any(|name| tool.contains(name)) {
+        return AgentPhase::Editing;
+    }
+    if SHELL_TOOLS.iter().any(|name| tool.contains(name)) {
+        let command = match input.get("command").unwrap_or(input) {
+            serde_json::Value::String(command) => command.clone(),
+            other => other.to_string(),
+        };
+        let words = || command.split(|c: char| !c.is_ascii_alphanumeric() && c != '_');
+        if words().any(|word| TEST_COMMANDS.contains(&word)) {
+            return AgentPhase::Testing;
+        }
+        if words().any(|word| EDIT_COMMANDS.contains(&word))
+            || command.contains("sed -i")
+            || command.contains("> ")
+        {
+            return AgentPhase::Editing;
+        }
+    }
+    AgentPhase::Planning
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(tool: &str, input: serde_json::Value) -> AgentEvent {
+        AgentEvent::ToolCall {
+            tool: tool.to_string(),
+            input,
+        }
+    }
+
+    #[test]
+    fn classifies_phases_from_tool_calls() {
+        let cases = [
+            (
+                tool("Read", serde_json::json!({"file_path": "src/lib.rs"})),
+                AgentPhase::Planning,
+            ),
+            (
+                tool("Grep", serde_json::json!({"pattern": "fn main"})),
+                AgentPhase::Planning,
+            ),
+            (
+                tool("TodoWrite", serde_json::json!({"todos": []})),
+                AgentPhase::Planning,
+            ),
+            (
+                tool("MultiEdit", serde_json::json!({})),
+                AgentPhase::Editing,
+            ),
+            (
+                tool(
+                    "shell",
+                    serde_json::json!({"command": ["bash", "-lc", "apply_patch <<'EOF'"]}),
+                ),
+                AgentPhase::Editing,
+            ),
+            (
+                tool("Bash", serde_json::json!({"command": "cargo test -p core"})),
+                AgentPhase::Testing,
+            ),
+            (
+                tool("shell", serde_json::json!({"command": "npm run lint"})),
+                AgentPhase::Testing,
+            ),
+            (
+                tool("Bash", serde_json::json!({"command": "cat README.md"})),
+                AgentPhase::Planning,
+            ),
+            (
+                tool("Bash", serde_json::json!({"command": "ls latest/"})),
+                AgentPhase::Planning,
+            ),
+        ];
+        for (event, phase) in cases {
+            assert_eq!(classify(&event), Some(phase), "{event:?}");
+        }
+    }
+
+    #[test]
+    fn estimate_moves_forward_through_phase_bands() {
+        let mut estimator = ProgressEstimator::new();
+        let mut last = 0;
+        let mut observe = |event: AgentEvent, tokens: u64| {
+            if let Some(estimate) = estimator.observe(&event, tokens) {
+                assert!(estimate.percent >= last, "progress went backwards");
+                last = estimate.percent;
+            }
+            estimator.estimate()
+        };
+
+        let planning = observe(tool("Read", serde_json::json!({})), 500);
+        assert_eq!(planning.phase, AgentPhase::Planning);
+        assert!((5..30).contains(&planning.percent));
+
+        let mut editing = observe(tool("Edit", serde_json::json!({})), 1_000);
+        for tokens in (2..40).map(|n| n * 1_000) {
+            editing = observe(tool("Read", serde_json::json!({})), tokens);
+        }
+        assert_eq!(editing.phase, AgentPhase::Editing, "reads do not go back");
+        assert!((60..75).contains(&editing.percent), "{}", editing.percent);
+
+        let testing = observe(
+            tool("Bash", serde_json::json!({"command": "pytest"})),
+            41_000,
+        );
+        assert_eq!(testing.phase, AgentPhase::Testing);
+        assert!((75..95).contains(&testing.percent));
+        assert!(testing.tokens_per_minute.is_some());
+
+        // A percentage the adapter reports wins when it is ahead.
+        let reported = observe(
+            AgentEvent::Progress {
+                message: "almost there".to_string(),
+                percent: Some(97.5),
+            },
+            41_000,
+        );
+        assert_eq!(reported.percent, 97);
+
+        let done = observe(AgentEvent::Completed { summary: None }, 41_000);
+        assert_eq!((done.phase, done.percent), (AgentPhase::Done, 100));
+    }
+}
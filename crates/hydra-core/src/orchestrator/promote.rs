@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::adapter::AdapterRegistry;
+use crate::artifact::{
+    write_diff_artifact, AgentEntry, AgentScoreArtifact, DiffLimits, EventKind, EventWriter,
+    RunEvent, RunLayout, RunManifest, RunStatus,
+};
+use crate::git_exec::run_git;
+use crate::scoring::baseline::{capture_baseline, persist_baseline, resolve_commands};
+use crate::scoring::evaluate::evaluate_agent_dimensions;
+use crate::scoring::ranking::rank_agents;
+use crate::security::SecretRedactor;
+use crate::worktree::{WorktreeInfo, WorktreeService};
+
+use super::race::{file_size, generate_diff_patch, persist_baseline_logs, sha256_short};
+use super::{
+    ErrorContext, ErrorPhase, Orchestrator, OrchestratorError, PromoteOptions, PromoteOutcome,
+};
+
+type Result<T> = std::result::Result<T, OrchestratorError>;
+
+const PROMOTE_COMMITTER_NAME: &str = "Hydra";
+const PROMOTE_COMMITTER_EMAIL: &str = "hydra@local";
+
+pub(super) async fn run(
+    orchestrator: &Orchestrator,
+    opts: PromoteOptions,
+) -> Result<PromoteOutcome> {
+    let run_id = opts.run_id.unwrap_or_else(Uuid::new_v4);
+    promote(orchestrator, &opts, run_id).await.map_err(|e| {
+        e.with_context(ErrorContext {
+            run_id: Some(run_id),
+            agent_key: Some(opts.agent_key.clone()),
+            phase: Some(ErrorPhase::Promote),
+        })
+    })
+}
+
+async fn promote(
+    orchestrator: &Orchestrator,
+    opts: &PromoteOptions,
+    run_id: Uuid,
+) -> Result<PromoteOutcome> {
+    let config = &orchestrator.config;
+    let repo_root = &orchestrator.repo_root;
+
+    // Diff from the commit the checkout forked from, so commits made during
+    // the session are carried over along with uncommitted work.
+    let base_commit = run_git(&["merge-base", "HEAD", &opts.base_ref], &opts.source_path)
+        .await?
+        .stdout
+        .trim()
+        .to_string();
+    let patch = generate_diff_patch(&opts.source_path, &base_commit).await?;
+    if patch.trim().is_empty() {
+        return Err(OrchestratorError::NothingToPromote {
+            path: opts.source_path.clone(),
+        });
+    }
+
+    let hydra_root = repo_root.join(".hydra");
+    let layout = RunLayout::new(&hydra_root, run_id);
+    layout.create_dirs(&[opts.agent_key.as_str()])?;
+
+    let wt_service =
+        WorktreeService::new(repo_root.clone(), repo_root.join(&config.worktree.base_dir));
+    let wt_info = match wt_service
+        .create(run_id, &opts.agent_key, &base_commit)
+        .await
+    {
+        Ok(info) => info,
+        Err(source) => {
+            discard_layout(&layout);
+            return Err(OrchestratorError::Worktree {
+                adapter: opts.agent_key.clone(),
+                source,
+            });
+        }
+    };
+
+    let candidate = Candidate {
+        run_id,
+        layout: &layout,
+        wt_service: &wt_service,
+        wt_info: &wt_info,
+        base_commit: &base_commit,
+        patch: &patch,
+    };
+    match score_candidate(orchestrator, opts, &candidate).await {
+        Ok(outcome) => Ok(outcome),
+        Err(e) => {
+            if let Err(cleanup_err) = wt_service.force_cleanup(&wt_info).await {
+                tracing::warn!(
+                    branch = %wt_info.branch,
+                    error = %cleanup_err,
+                    "failed to rollback worktree after promote error"
+                );
+            }
+            discard_layout(&layout);
+            Err(e)
+        }
+    }
+}
+
+struct Candidate<'a> {
+    run_id: Uuid,
+    layout: &'a RunLayout,
+    wt_service: &'a WorktreeService,
+    wt_info: &'a WorktreeInfo,
+    base_commit: &'a str,
+    patch: &'a str,
+}
+
+async fn score_candidate(
+    orchestrator: &Orchestrator,
+    opts: &PromoteOptions,
+    candidate: &Candidate<'_>,
+) -> Result<PromoteOutcome> {
+    let Candidate {
+        run_id,
+        layout,
+        wt_service,
+        wt_info,
+        base_commit,
+        patch,
+    } = *candidate;
+    let config = &orchestrator.config;
+    let agent_key = opts.agent_key.as_str();
+    let redactor = SecretRedactor::from_config(&config.security.redaction);
+
+    // The fresh worktree still sits at the base commit, which is exactly
+    // what the baseline should measure.
+    let commands = resolve_commands(&config.scoring);
+    let baseline = capture_baseline(&wt_info.path, &config.scoring).await?;
+    persist_baseline(&baseline, &layout.baseline_result(), &redactor)?;
+    persist_baseline_logs(layout, &baseline, &redactor)?;
+
+    // Apply the real patch, then keep only a redacted copy as the artifact.
+    let diff_path = layout.agent_diff(agent_key);
+    std::fs::write(&diff_path, patch)?;
+    apply_and_commit(&wt_info.path, &diff_path, &opts.description).await?;
+    write_diff_artifact(
+        layout,
+        agent_key,
+        &redactor.redact(patch).value,
+        &DiffLimits::from_config(&config.artifacts),
+        |path| file_size(&wt_info.path, path),
+    )?;
+
+    let tier = AdapterRegistry::from_config(&config.adapters)
+        .resolve(agent_key, true)
+        .map(|adapter| adapter.tier().to_string())
+        .unwrap_or_else(|_| "experimental".to_string());
+    let mut manifest = RunManifest::new(
+        run_id,
+        orchestrator.repo_root.display().to_string(),
+        base_commit.to_string(),
+        sha256_short(&opts.description),
+        vec![AgentEntry {
+            agent_key: agent_key.to_string(),
+            tier,
+            branch: wt_info.branch.clone(),
+            worktree_path: Some(wt_info.path.display().to_string()),
+        }],
+    )
+    .with_task_prompt(
+        &opts.description,
+        config.artifacts.store_full_prompt,
+        &redactor,
+    );
+    manifest.write_to(&layout.manifest_path())?;
+
+    let mut events = EventWriter::create(&layout.events_path())?.with_redactor(redactor.clone());
+    events.write_event(&RunEvent::new(
+        EventKind::RunStarted,
+        None,
+        serde_json::json!({
+            "run_id": run_id.to_string(),
+            "agents": [agent_key],
+            "task_prompt": &opts.description,
+            "promoted_from": opts.source_path.display().to_string(),
+        }),
+    ))?;
+    events.write_event(&RunEvent::new(
+        EventKind::ScoreStarted,
+        None,
+        serde_json::json!({}),
+    ))?;
+
+    let dimensions = evaluate_agent_dimensions(
+        &wt_info.path,
+        &layout.agent_dir(agent_key),
+        base_commit,
+        config,
+        &baseline,
+        &commands,
+    )
+    .await
+    .map_err(|source| OrchestratorError::Scoring {
+        adapter: agent_key.to_string(),
+        source,
+    })?;
+    // No speed dimension: the session's wall-clock time says nothing about
+    // the agent.
+    let score = rank_agents(
+        vec![(agent_key.to_string(), dimensions)],
+        &config.scoring.weights,
+        &config.scoring.gates,
+        &HashMap::new(),
+    )
+    .remove(0);
+    AgentScoreArtifact::new(score.clone()).write_to(&layout.agent_score(agent_key), &redactor)?;
+
+    events.write_event(&RunEvent::new(
+        EventKind::ScoreFinished,
+        None,
+        serde_json::json!({ "ranked_agents": 1, "error": null }),
+    ))?;
+    events.write_event(&RunEvent::new(
+        EventKind::RunCompleted,
+        None,
+        serde_json::json!({ "status": format!("{:?}", RunStatus::Completed) }),
+    ))?;
+
+    manifest.mark_completed(RunStatus::Completed);
+    manifest.write_to(&layout.manifest_path())?;
+
+    // The branch stays behind as the merge candidate; only the checkout goes.
+    let worktree_removed = match wt_service.remove(&wt_info.path, true).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(path = %wt_info.path.display(), error = %e, "failed to remove promote worktree");
+            false
+        }
+    };
+
+    Ok(PromoteOutcome {
+        run_id,
+        agent_key: agent_key.to_string(),
+        branch: wt_info.branch.clone(),
+        base_ref: base_commit.to_string(),
+        artifacts_dir: layout.base_dir().to_path_buf(),
+        worktree_removed,
+        score,
+    })
+}
+
+async fn apply_and_commit(worktree: &Path, patch_path: &Path, message: &str) -> Result<()> {
+    let patch_path = patch_path.display().to_string();
+    run_git(&["apply", "--whitespace=nowarn", &patch_path], worktree).await?;
+    run_git(&["add", "-A"], worktree).await?;
+    let name = format!("user.name={PROMOTE_COMMITTER_NAME}");
+    let email = format!("user.email={PROMOTE_COMMITTER_EMAIL}");
+    run_git(
+        &[
+            "-c",
+            &name,
+            "-c",
+            &email,
+            "commit",
+            "--no-verify",
+            "-m",
+            message,
+        ],
+        worktree,
+    )
+    .await?;
+    Ok(())
+}
+
+fn discard_layout(layout: &RunLayout) {
+    if let Err(e) = layout.cleanup() {
+        tracing::warn!(path = %layout.base_dir().display(), error = %e, "failed to remove partial run artifacts");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HydraConfig;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn init_repo(repo: &Path) {
+        git(repo, &["init"]);
+        git(repo, &["config", "user.email", "test@example.com"]);
+        git(repo, &["config", "user.name", "Test User"]);
+        std::fs::write(repo.join("README.md"), "base\n").unwrap();
+        git(repo, &["add", "README.md"]);
+        git(repo, &["commit", "-m", "init"]);
+    }
+
+    fn options(source: &Path) -> PromoteOptions {
+        PromoteOptions {
+            agent_key: "claude".to_string(),
+            source_path: source.to_path_buf(),
+            base_ref: "HEAD".to_string(),
+            description: "interactive session".to_string(),
+            run_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn promote_commits_changes_to_a_scored_branch() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        init_repo(repo);
+        std::fs::write(repo.join("snake.py"), "print('snake')\n").unwrap();
+
+        let orchestrator = Orchestrator::new(repo, HydraConfig::default());
+        let outcome = orchestrator.promote(options(repo)).await.unwrap();
+
+        assert_eq!(
+            outcome.branch,
+            format!("hydra/{}/agent/claude", outcome.run_id)
+        );
+        assert!(outcome.worktree_removed);
+        let files = git(repo, &["show", "--name-only", "--format=", &outcome.branch]);
+        assert_eq!(files, "snake.py");
+
+        let layout = RunLayout::new(&repo.join(".hydra"), outcome.run_id);
+        let manifest = RunManifest::read_from(&layout.manifest_path()).unwrap();
+        assert_eq!(manifest.status, RunStatus::Completed);
+        assert_eq!(manifest.agents[0].branch, outcome.branch);
+        assert!(layout.agent_score("claude").exists());
+        assert!(std::fs::read_to_string(layout.agent_diff("claude"))
+            .unwrap()
+            .contains("+print('snake')"));
+    }
+
+    #[tokio::test]
+    async fn promote_without_changes_leaves_no_artifacts() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        init_repo(repo);
+
+        let orchestrator = Orchestrator::new(repo, HydraConfig::default());
+        let err = orchestrator.promote(options(repo)).await.unwrap_err();
+
+        assert!(matches!(
+            err.root(),
+            OrchestratorError::NothingToPromote { .. }
+        ));
+        assert_eq!(err.context().unwrap().phase, Some(ErrorPhase::Promote));
+        assert!(!repo.join(".hydra").exists());
+    }
+}
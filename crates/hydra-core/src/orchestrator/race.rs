@@ -0,0 +1,3198 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+use tokio::process::Command as TokioCommand;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::task::JoinSet;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::adapter::{AdapterRegistry, AgentAdapter, AgentEvent, BuiltCommand, SpawnRequest};
+use crate::artifact::{
+    config_hash, write_diff_artifact, AgentEntry, AgentScoreArtifact, ArtifactError, CostLedger,
+    DiffLimits, EditedFiles, EnvironmentSnapshot, EventBus, EventKind, EventReader, EventSinks,
+    EventWriter, FileEdit, FileOperation, LedgerEntry, LockMode, RepoLock, RunEvent,
+    RunHealthMetrics, RunLayout, RunManifest, RunStatus, SpendPeriod,
+};
+use crate::config::{
+    BudgetConfig, BuildCacheConfig, BuildCacheMode, HydraConfig, IsolationMode, RetentionPolicy,
+    SecurityConfig, SupervisorConfig,
+};
+use crate::git_exec::{run_git, GitExecError};
+use crate::git_ref::validate_agent_key;
+use crate::scoring::baseline::{
+    capture_baseline, persist_baseline, resolve_commands, BaselineResult, ResolvedCommands,
+};
+use crate::scoring::build_cache::seed_build_cache;
+use crate::scoring::cost::{CostEstimate, UsageAccumulator};
+use crate::scoring::diff_scope::precheck_diff_scope;
+use crate::scoring::evaluate::{evaluate_agent_dimensions, write_command_artifact};
+use crate::scoring::pricing::{agent_price, AgentPrice};
+use crate::scoring::ranking::{rank_agents, AgentScore};
+use crate::scoring::write_policy::record_runtime_violations;
+use crate::scoring::DimensionScore;
+use crate::security::{
+    dangerous_action, private_netns_supported, tool_file_access, ApprovalDecision, ApprovalPolicy,
+    ConsentStore, ContainerSpec, DangerousAction, EgressProxy, NetworkAccess, NetworkViolation,
+    OsSandbox, PathRules, PathViolation, PromptGuard, SandboxMode, SandboxPolicy, SandboxResult,
+    SecretRedactor,
+};
+use crate::supervisor::{
+    supervise, SupervisorError, SupervisorEvent, SupervisorHandle, SupervisorPolicy,
+};
+use crate::worktree::{self, WorktreeInfo, WorktreeService};
+
+use super::commits::detect_candidate_commits;
+use super::freshness::check_base_freshness;
+use super::{
+    AgentOutcome, ApprovalGate, BudgetLimit, BudgetWarning, CancelToken, ErrorContext, ErrorPhase,
+    Orchestrator, OrchestratorError, OutputStream, ProgressEstimator, PromptVariant, RaceEvent,
+    RaceEventSink, RaceOptions, RaceOutcome, UsageStatus,
+};
+
+type Result<T> = std::result::Result<T, OrchestratorError>;
+
+pub(super) async fn run(
+    orchestrator: &Orchestrator,
+    opts: RaceOptions,
+    sink: Arc<dyn RaceEventSink>,
+) -> Result<RaceOutcome> {
+    let mut context = ErrorContext {
+        phase: Some(ErrorPhase::Setup),
+        ..ErrorContext::default()
+    };
+    let result = race(orchestrator, opts, sink, &mut context).await;
+    result.map_err(|e| e.with_context(context))
+}
+
+/// The race itself. `context` follows its progress so that an error it
+/// returns can say which run and phase it came from.
+async fn race(
+    orchestrator: &Orchestrator,
+    opts: RaceOptions,
+    sink: Arc<dyn RaceEventSink>,
+    context: &mut ErrorContext,
+) -> Result<RaceOutcome> {
+    let run_started_at = Instant::now();
+
+    let task_config;
+    let config = match &opts.task {
+        Some(spec) => {
+            task_config = spec.apply_to(&orchestrator.config);
+            &task_config
+        }
+        None => &orchestrator.config,
+    };
+    let repo_root = &orchestrator.repo_root;
+
+    let registry = AdapterRegistry::from_config(&config.adapters);
+    let requested_agents = normalize_requested_agents(&opts.agents);
+    let selected_agents = if !requested_agents.is_empty() {
+        requested_agents
+    } else if !config.race.default_agents.is_empty() {
+        normalize_requested_agents(&config.race.default_agents)
+    } else {
+        default_tier1_keys(&registry)
+    };
+    if selected_agents.is_empty() {
+        return Err(OrchestratorError::NoAgents);
+    }
+
+    // Before resolving the base: a fast-forward moves it.
+    let freshness =
+        check_base_freshness(repo_root, &opts.base_ref, config.race.base_freshness).await;
+    if let Some(freshness) = freshness.as_ref().filter(|f| f.is_notable()) {
+        sink.emit(RaceEvent::StaleBase {
+            freshness: freshness.clone(),
+        });
+    }
+
+    // A deterministic ID is only as stable as its base, so a ref that does
+    // not resolve is an error there; otherwise the commit is informational.
+    let base_commit = match resolve_commit(repo_root, &opts.base_ref).await {
+        Ok(commit) => Some(commit),
+        Err(e) if opts.deterministic && opts.run_id.is_none() => return Err(e.into()),
+        Err(_) => None,
+    };
+    // Agents may commit in their worktrees, which moves a symbolic base
+    // such as `HEAD` there; diffs and scoring compare with the commit.
+    let diff_base = base_commit.clone().unwrap_or_else(|| opts.base_ref.clone());
+    let run_id = match (opts.run_id, &base_commit) {
+        (Some(run_id), _) => run_id,
+        (None, Some(commit)) if opts.deterministic => {
+            deterministic_run_id(&opts.prompt, commit, &selected_agents, config)
+        }
+        _ => Uuid::new_v4(),
+    };
+    tracing::Span::current().record("run_id", tracing::field::display(run_id));
+    context.run_id = Some(run_id);
+    // Repeating a deterministic race lands on the first one's ID; refuse
+    // before queueing for the lock rather than part way through setup.
+    if opts.deterministic
+        && opts.run_id.is_none()
+        && RunLayout::new(&repo_root.join(".hydra"), run_id)
+            .base_dir()
+            .exists()
+    {
+        return Err(OrchestratorError::RunExists { run_id });
+    }
+
+    let unsafe_consent = if opts.unsafe_mode {
+        let consent = ConsentStore::new(&repo_root.join(".hydra"))
+            .authorize(opts.unsafe_consent.as_deref(), &format!("race {run_id}"))?;
+        Some(consent.token)
+    } else {
+        None
+    };
+
+    let adapters = registry.resolve_many(&selected_agents, opts.allow_experimental_adapters)?;
+
+    let detects: Vec<_> = adapters.iter().map(|adapter| adapter.detect()).collect();
+    for (adapter, detect) in adapters.iter().zip(&detects) {
+        if !detect.status.is_available() {
+            return Err(OrchestratorError::AdapterNotReady {
+                adapter: adapter.key().to_string(),
+                status: detect.status_label().to_string(),
+                detail: detect
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "probe failed with no detail".to_string()),
+            });
+        }
+    }
+
+    let slots = agent_slots(&adapters, &opts.variants)?;
+
+    let redactor = SecretRedactor::from_config(&config.security.redaction);
+    let prompt_guard = PromptGuard::from_config(&config.security.prompt_guard);
+    let guarded_prompt = prompt_guard.guard_prompt(repo_root, &opts.prompt);
+    let agent_keys: Vec<&str> = slots.iter().map(|slot| slot.key.as_str()).collect();
+    let hydra_root = repo_root.join(".hydra");
+    let ledger = CostLedger::new(&hydra_root);
+    let run_budget = period_budget(&config.scoring.budget, &ledger, chrono::Utc::now())?;
+    let repo_lock = acquire_repo_lock(&hydra_root, run_id, &opts, sink.as_ref()).await?;
+    let layout = RunLayout::new(&hydra_root, run_id);
+    layout.create_dirs(&agent_keys)?;
+    let partial_run = PartialRunDir::new(layout.base_dir());
+    if let Some(spec) = &opts.task {
+        std::fs::write(layout.task_spec(), redactor.redact_json(spec)?)?;
+    }
+    if !opts.variants.is_empty() {
+        let variants = serde_json::json!({
+            "agent": adapters[0].key(),
+            "variants": opts.variants,
+        });
+        std::fs::write(layout.variants(), redactor.redact_json(&variants)?)?;
+    }
+
+    let wt_base = repo_root.join(&config.worktree.base_dir);
+    let wt_service = Arc::new(WorktreeService::new(repo_root.clone(), wt_base));
+
+    let mut worktrees: Vec<WorktreeInfo> = Vec::new();
+    let supported_flags_map: HashMap<String, Vec<String>> = adapters
+        .iter()
+        .zip(&detects)
+        .map(|(adapter, detect)| (adapter.key().to_string(), detect.supported_flags.clone()))
+        .collect();
+
+    for slot in &slots {
+        let wt_info = match wt_service
+            .create(run_id, &slot.key, &opts.base_ref)
+            .instrument(tracing::info_span!("worktree.create", agent = %slot.key))
+            .await
+        {
+            Ok(info) => info,
+            Err(source) => {
+                rollback_worktrees(&wt_service, &worktrees).await;
+                return Err(OrchestratorError::Worktree {
+                    adapter: slot.key.clone(),
+                    source,
+                }
+                .with_context(ErrorContext::agent(&slot.key)));
+            }
+        };
+        worktrees.push(wt_info);
+        if let Some(spec) = opts.task.as_ref().filter(|spec| spec.sparse) {
+            let wt_path = &worktrees[worktrees.len() - 1].path;
+            if let Err(source) = wt_service
+                .set_sparse_paths(wt_path, &spec.relevant_paths)
+                .await
+            {
+                rollback_worktrees(&wt_service, &worktrees).await;
+                return Err(OrchestratorError::Worktree {
+                    adapter: slot.key.clone(),
+                    source,
+                }
+                .with_context(ErrorContext::agent(&slot.key)));
+            }
+        }
+    }
+
+    context.phase = Some(ErrorPhase::Baseline);
+    let resolved_commands = resolve_commands(&config.scoring);
+    let mut baseline_reused_from = None;
+    let baseline = if opts.skip_baseline {
+        match base_commit
+            .as_deref()
+            .and_then(|commit| cached_baseline(&hydra_root, commit, &resolved_commands))
+        {
+            Some((from_run, cached)) => {
+                tracing::info!(%from_run, "reusing cached baseline");
+                baseline_reused_from = Some(from_run);
+                cached
+            }
+            None => {
+                tracing::warn!("no cached baseline for this commit; scoring without one");
+                BaselineResult {
+                    build: None,
+                    test: None,
+                    lint: None,
+                }
+            }
+        }
+    } else {
+        match capture_baseline(&worktrees[0].path, &config.scoring)
+            .instrument(tracing::info_span!("scoring.baseline"))
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                rollback_worktrees(&wt_service, &worktrees).await;
+                return Err(e.into());
+            }
+        }
+    };
+    if let Err(e) = persist_baseline(&baseline, &layout.baseline_result(), &redactor)
+        .and_then(|()| persist_baseline_logs(&layout, &baseline, &redactor))
+    {
+        rollback_worktrees(&wt_service, &worktrees).await;
+        return Err(e.into());
+    }
+    let build_cache = if opts.skip_baseline {
+        None
+    } else {
+        seed_candidate_build_caches(&slots, &worktrees, &config.scoring.build_cache).await
+    };
+    context.phase = Some(ErrorPhase::Setup);
+
+    let agent_entries: Vec<AgentEntry> = slots
+        .iter()
+        .zip(worktrees.iter())
+        .map(|(slot, wt)| AgentEntry {
+            agent_key: slot.key.clone(),
+            tier: slot.adapter.tier().to_string(),
+            branch: wt.branch.clone(),
+            worktree_path: Some(wt.path.display().to_string()),
+        })
+        .collect();
+
+    let mut manifest = RunManifest::new(
+        run_id,
+        repo_root.display().to_string(),
+        opts.base_ref.clone(),
+        sha256_short(&opts.prompt),
+        agent_entries,
+    )
+    .with_task_prompt(&opts.prompt, config.artifacts.store_full_prompt, &redactor)
+    .with_prompt_source(opts.prompt_source.clone())
+    .with_tags(opts.tags.clone())
+    .with_detected_project(config.detected.clone())
+    .with_environment(
+        EnvironmentSnapshot::capture(config, adapters.iter().map(|a| a.key()).zip(&detects))
+            .with_base_commit(base_commit.clone())
+            .with_deterministic(opts.deterministic && opts.run_id.is_none()),
+    );
+    manifest.write_to(&layout.manifest_path())?;
+    partial_run.keep();
+    // Forgets the run on every way out of here, including errors.
+    let live_run = worktree::track_run(run_id, layout.manifest_path());
+    // Worktrees, baseline and manifest are in place; the next queued run
+    // can start its setup while this one's agents work.
+    drop(repo_lock);
+
+    let mut run_event_writer = EventBus::new(
+        EventWriter::create(&layout.events_path())?.with_redactor(redactor.clone()),
+        opts.event_sinks.clone(),
+    );
+
+    let agents_json = agent_keys.clone();
+    run_event_writer.write_event(&RunEvent::new(
+        EventKind::RunStarted,
+        None,
+        serde_json::json!({
+            "run_id": run_id.to_string(),
+            "agents": agents_json,
+            "task_prompt": &opts.prompt,
+            "unsafe_mode": opts.unsafe_mode,
+            "unsafe_consent": unsafe_consent,
+            "baseline_commands": {
+                "build": resolved_commands.build.is_some(),
+                "test": resolved_commands.test.is_some(),
+                "lint": resolved_commands.lint.is_some(),
+            },
+            "skip_baseline": opts.skip_baseline,
+            "baseline_reused_from": baseline_reused_from.map(|id| id.to_string()),
+            "base_freshness": freshness,
+            "build_cache": build_cache,
+            "setup_ms": run_started_at.elapsed().as_millis() as u64,
+        }),
+    ))?;
+    sink.emit(RaceEvent::RunStarted {
+        run_id,
+        agents: agents_json.iter().map(|key| key.to_string()).collect(),
+    });
+    if opts.skip_baseline {
+        sink.emit(RaceEvent::BaselineSkipped {
+            reused_from: baseline_reused_from,
+        });
+    }
+
+    if !guarded_prompt.findings.is_empty() {
+        tracing::warn!(
+            findings = guarded_prompt.findings.len(),
+            stripped = guarded_prompt.stripped,
+            "possible prompt injection in task sources"
+        );
+        run_event_writer.write_event(&RunEvent::new(
+            EventKind::SecurityViolation,
+            None,
+            serde_json::json!({
+                "kind": "prompt_injection",
+                "findings": guarded_prompt.findings,
+                "stripped": guarded_prompt.stripped,
+            }),
+        ))?;
+        sink.emit(RaceEvent::PromptInjectionDetected {
+            findings: guarded_prompt.findings.clone(),
+            stripped: guarded_prompt.stripped,
+        });
+    }
+
+    tracing::info!(
+        run_id = %run_id,
+        agents = ?agents_json,
+        "race started with {} agent(s)",
+        slots.len()
+    );
+
+    context.phase = Some(ErrorPhase::Agents);
+    let shared_budget = Arc::new(SharedBudgetState::default());
+    let early_stop = CancelToken::new();
+    let mut join_set = JoinSet::new();
+    let mut task_agents: HashMap<tokio::task::Id, String> = HashMap::new();
+
+    for (slot, wt_info) in slots.iter().zip(worktrees.iter()) {
+        let adapter = &slot.adapter;
+        // Each adapter gets the task in its own format, unless the guard had
+        // to strip injected text from the rendered prompt.
+        let task_prompt = match (&slot.variant, &opts.task) {
+            (Some(variant), _) => prompt_guard.guard_prompt(repo_root, &variant.prompt).prompt,
+            (None, Some(spec)) if !guarded_prompt.stripped => adapter.render_task(spec),
+            _ => guarded_prompt.prompt.clone(),
+        };
+        let agent_prompt = if config.race.request_notes {
+            format!("{task_prompt}\n\n{NOTES_REQUEST}")
+        } else {
+            task_prompt
+        };
+
+        run_event_writer.write_event(&RunEvent::new(
+            EventKind::AgentStarted,
+            Some(slot.key.clone()),
+            serde_json::json!({
+                "tier": adapter.tier().to_string(),
+                "variant": slot.variant.as_ref().map(|v| &v.name),
+            }),
+        ))?;
+
+        let adapter = Arc::clone(adapter);
+        let config = config.clone();
+        let wt_info = wt_info.clone();
+        let prompt = agent_prompt;
+        let unsafe_mode = opts.unsafe_mode;
+        let flags = supported_flags_map
+            .get(adapter.key())
+            .cloned()
+            .unwrap_or_default();
+        let agent_events_path = layout.agent_events(&slot.key);
+        let edited_files_path = layout.agent_edited_files(&slot.key);
+        let run_cancel_marker = layout.run_cancel_marker();
+        let agent_cancel_marker = layout.agent_cancel_marker(&slot.key);
+        let expects_usage = adapter.capabilities().emits_usage.supported;
+        let shared_budget = Arc::clone(&shared_budget);
+        let budget = run_budget.clone();
+        let price = agent_price(&config.pricing, adapter.key());
+        let cancel = opts.cancel.clone();
+        let early_stop = early_stop.clone();
+        let approvals = opts.approvals.clone();
+        let sink = Arc::clone(&sink);
+        let event_sinks = opts.event_sinks.clone();
+
+        let task_agent_key = slot.key.clone();
+        let task_agent_key_map = task_agent_key.clone();
+        // Created here rather than inside the task so it nests under the
+        // race span.
+        let agent_span = tracing::info_span!("agent", %run_id, agent = %slot.key);
+        let abort_handle = join_set.spawn(
+            async move {
+                let start = Instant::now();
+                let run_ctx = SingleAgentRunCtx {
+                    prompt: &prompt,
+                    unsafe_mode,
+                    config: &config,
+                    wt_info: &wt_info,
+                    events_path: agent_events_path,
+                    edited_files_path,
+                    supported_flags: flags,
+                    expects_usage,
+                    budget,
+                    price,
+                    shared_budget,
+                    cancel,
+                    early_stop,
+                    approvals,
+                    run_cancel_marker,
+                    agent_cancel_marker,
+                    sink: sink.as_ref(),
+                    event_sinks,
+                };
+                let result = run_single_agent(adapter, run_ctx).await;
+                let duration = start.elapsed();
+                let (status, error, total_tokens) = match &result {
+                    Ok(outcome) => (
+                        outcome.status.clone(),
+                        outcome.error.clone(),
+                        outcome.usage.total_tokens,
+                    ),
+                    Err(e) => (RunStatus::Failed, Some(e.to_string()), 0),
+                };
+                sink.emit(RaceEvent::AgentFinished {
+                    agent_key: task_agent_key.clone(),
+                    status,
+                    error,
+                    duration_ms: duration.as_millis() as u64,
+                    total_tokens,
+                });
+                (task_agent_key, result, duration)
+            }
+            .instrument(agent_span),
+        );
+        task_agents.insert(abort_handle.id(), task_agent_key_map);
+    }
+
+    let score_ctx = ScoreRunCtx {
+        layout: &layout,
+        base_ref: &diff_base,
+        config,
+        baseline: &baseline,
+        commands: &resolved_commands,
+        redactor: &redactor,
+    };
+    // Dimensions of agents scored as they finished, reused by the ranking.
+    let mut evaluated: HashMap<String, Vec<DimensionScore>> = HashMap::new();
+    let mut stopped_early_by: Option<String> = None;
+    let mut agent_notes: HashMap<String, String> = HashMap::new();
+    let mut candidate_commits = BTreeMap::new();
+
+    let mut results: Vec<(String, Result<AgentRunResult>, Duration)> = Vec::new();
+    let mut task_panics: Vec<serde_json::Value> = Vec::new();
+    while let Some(join_result) = join_set.join_next_with_id().await {
+        match join_result {
+            Ok((task_id, tuple)) => {
+                task_agents.remove(&task_id);
+                let agent_key = &tuple.0;
+                if let Some(wt) = worktrees.iter().find(|w| &w.agent_key == agent_key) {
+                    let dest = layout.agent_notes(agent_key);
+                    if let Some(notes) = collect_notes(&wt.path, &dest, &redactor) {
+                        agent_notes.insert(agent_key.clone(), notes);
+                    }
+                    match detect_candidate_commits(&wt.path, &diff_base).await {
+                        Ok(commits) => {
+                            tracing::info!(
+                                agent = %agent_key,
+                                strategy = ?commits.strategy,
+                                commits = commits.commits.len(),
+                                "checked for agent commits"
+                            );
+                            candidate_commits.insert(agent_key.clone(), commits);
+                        }
+                        Err(e) => tracing::warn!(
+                            agent = %agent_key,
+                            error = %e,
+                            "could not check the worktree for agent commits"
+                        ),
+                    }
+                }
+                if let (agent_key, Ok(outcome), duration) = &tuple {
+                    if opts.stop_on_first_success
+                        && stopped_early_by.is_none()
+                        && outcome.status == RunStatus::Completed
+                    {
+                        let wt_path = worktrees
+                            .iter()
+                            .find(|w| &w.agent_key == agent_key)
+                            .map(|w| w.path.as_path());
+                        if let Some((dimensions, score)) =
+                            score_finished_agent(&score_ctx, agent_key, wt_path, outcome, *duration)
+                                .await
+                        {
+                            evaluated.insert(agent_key.clone(), dimensions);
+                            if score.mergeable && score.composite >= config.race.success_threshold {
+                                tracing::info!(
+                                    agent = %agent_key,
+                                    composite = score.composite,
+                                    "candidate passed the gates; stopping the remaining agents"
+                                );
+                                run_event_writer.write_event(&RunEvent::new(
+                                    EventKind::RaceStoppedEarly,
+                                    Some(agent_key.clone()),
+                                    serde_json::json!({
+                                        "composite": score.composite,
+                                        "success_threshold": config.race.success_threshold,
+                                    }),
+                                ))?;
+                                sink.emit(RaceEvent::StoppingEarly {
+                                    agent_key: agent_key.clone(),
+                                    composite: score.composite,
+                                });
+                                early_stop.cancel();
+                                stopped_early_by = Some(agent_key.clone());
+                            }
+                        }
+                    }
+                }
+                results.push(tuple);
+            }
+            Err(e) => {
+                let task_id = e.id();
+                let agent_key = task_agents
+                    .remove(&task_id)
+                    .unwrap_or_else(|| "unknown".to_string());
+                let panic_context = if e.is_panic() {
+                    format!("panic in task id {task_id}: {e}")
+                } else if e.is_cancelled() {
+                    format!("task id {task_id} was cancelled: {e}")
+                } else {
+                    e.to_string()
+                };
+
+                tracing::error!(
+                    agent = %agent_key,
+                    error = %panic_context,
+                    "agent task join failure"
+                );
+                task_panics.push(serde_json::json!({
+                    "agent": agent_key.clone(),
+                    "task_id": format!("{task_id}"),
+                    "error": panic_context.clone(),
+                }));
+                let error = format!("agent task join failure: {panic_context}");
+                sink.emit(RaceEvent::AgentFinished {
+                    agent_key: agent_key.clone(),
+                    status: RunStatus::Failed,
+                    error: Some(error.clone()),
+                    duration_ms: 0,
+                    total_tokens: 0,
+                });
+                results.push((
+                    agent_key,
+                    Err(OrchestratorError::TaskFailed(error)),
+                    Duration::default(),
+                ));
+            }
+        }
+    }
+
+    let mut any_completed = false;
+    let mut ledger_entries = Vec::new();
+
+    for (agent_key, result, _duration) in &results {
+        let (status, error, usage_status, usage_total_tokens, usage_cost, priced_model) =
+            match result {
+                Ok(outcome) => (
+                    outcome.status.clone(),
+                    outcome.error.clone(),
+                    outcome.usage_status.as_str(),
+                    outcome.usage.total_tokens,
+                    outcome.usage.estimated_cost_usd,
+                    outcome.priced_model.clone(),
+                ),
+                Err(e) => (
+                    RunStatus::Failed,
+                    Some(e.to_string()),
+                    "unavailable",
+                    0,
+                    None,
+                    None,
+                ),
+            };
+        if usage_total_tokens > 0 || usage_cost.is_some() {
+            ledger_entries.push(LedgerEntry {
+                timestamp: chrono::Utc::now(),
+                run_id,
+                agent_key: agent_key.clone(),
+                model: priced_model.clone(),
+                total_tokens: usage_total_tokens,
+                cost_usd: usage_cost,
+            });
+        }
+
+        run_event_writer.write_event(&RunEvent::new(
+            match &status {
+                RunStatus::Completed => EventKind::AgentCompleted,
+                _ => EventKind::AgentFailed,
+            },
+            Some(agent_key.clone()),
+            serde_json::json!({
+                "status": format!("{status:?}"),
+                "error": error,
+                "usage_status": usage_status,
+                "total_tokens": usage_total_tokens,
+                "estimated_cost_usd": usage_cost,
+                "model": priced_model,
+            }),
+        ))?;
+
+        if status == RunStatus::Completed {
+            any_completed = true;
+        }
+    }
+    // Spend is already on the books in events.jsonl; a ledger that cannot
+    // be written is rebuilt from it next time, so this must not fail the run.
+    if let Err(e) = ledger.append(&ledger_entries) {
+        tracing::warn!(path = %ledger.path().display(), error = %e, "failed to record spend in cost ledger");
+    }
+
+    let mut durations: HashMap<String, Duration> = HashMap::new();
+    let mut path_violations: HashMap<String, Vec<PathViolation>> = HashMap::new();
+    for (agent_key, result, duration) in &results {
+        durations.insert(agent_key.clone(), *duration);
+        if let Ok(outcome) = result {
+            path_violations.insert(agent_key.clone(), outcome.path_violations.clone());
+        }
+    }
+
+    context.phase = Some(ErrorPhase::Scoring);
+    run_event_writer.write_event(&RunEvent::new(
+        EventKind::ScoreStarted,
+        None,
+        serde_json::json!({}),
+    ))?;
+    sink.emit(RaceEvent::ScoringStarted);
+
+    let (candidates, timed_out) =
+        scoring_candidates(&worktrees, &results, config.scoring.score_timed_out);
+    let (ranked_scores, scoring_error) = match score_agents(
+        &candidates,
+        &score_ctx,
+        &durations,
+        &path_violations,
+        evaluated,
+        &timed_out,
+    )
+    .instrument(tracing::info_span!("scoring"))
+    .await
+    {
+        Ok(scores) => (scores, None),
+        Err(err) => {
+            tracing::error!(error = %err, "scoring failed");
+            (Vec::new(), Some(err.to_string()))
+        }
+    };
+
+    run_event_writer.write_event(&RunEvent::new(
+        EventKind::ScoreFinished,
+        None,
+        serde_json::json!({
+            "ranked_agents": ranked_scores.len(),
+            "error": scoring_error,
+        }),
+    ))?;
+    sink.emit(RaceEvent::ScoringFinished {
+        ranked_agents: ranked_scores.len(),
+        error: scoring_error.clone(),
+    });
+
+    let overall_status = determine_overall_status(
+        any_completed,
+        shared_budget.should_stop(),
+        opts.cancel.is_cancelled() || layout.run_cancel_marker().exists(),
+        !task_panics.is_empty(),
+        scoring_error.is_some(),
+    );
+
+    let score_map: HashMap<String, AgentScore> = ranked_scores
+        .iter()
+        .map(|score| (score.agent_key.clone(), score.clone()))
+        .collect();
+
+    context.phase = Some(ErrorPhase::Cleanup);
+    // Persist diff.patch for each agent before worktree cleanup.
+    // The diff artifact must survive cleanup so the GUI can display it later.
+    let diff_limits = DiffLimits::from_config(&config.artifacts);
+    for wt_info in &worktrees {
+        let agent_key = wt_info.agent_key.as_str();
+        match generate_diff_patch(&wt_info.path, &diff_base)
+            .instrument(tracing::info_span!("diff", agent = agent_key))
+            .await
+        {
+            Ok(patch) => {
+                let written = write_diff_artifact(
+                    &layout,
+                    agent_key,
+                    &redactor.redact(&patch).value,
+                    &diff_limits,
+                    |path| file_size(&wt_info.path, path),
+                );
+                match written {
+                    Ok(omitted) if !omitted.is_empty() => tracing::info!(
+                        agent = agent_key,
+                        omitted = omitted.len(),
+                        "left binary or oversized files out of diff.patch"
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(
+                        agent = agent_key,
+                        error = %e,
+                        "failed to write diff.patch artifact"
+                    ),
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    agent = agent_key,
+                    error = %e,
+                    "failed to generate diff.patch; skipping"
+                );
+            }
+        }
+    }
+
+    // Cleanup worktrees
+    let mut cleanup_results: HashMap<String, bool> = HashMap::new();
+    for wt_info in &worktrees {
+        let agent_key = &wt_info.agent_key;
+        let agent_status = results
+            .iter()
+            .find(|(k, _, _)| k == agent_key)
+            .map(|(_, r, _)| match r {
+                Ok(o) => o.status.clone(),
+                Err(_) => RunStatus::Failed,
+            })
+            .unwrap_or(RunStatus::Failed);
+
+        let cleanup_requested = should_cleanup_worktree(config.worktree.retain, &agent_status);
+        if cleanup_requested {
+            match wt_service.force_cleanup(wt_info).await {
+                Ok(()) => {
+                    cleanup_results.insert(agent_key.clone(), true);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        agent = %agent_key,
+                        error = %e,
+                        "worktree cleanup failed"
+                    );
+                    cleanup_results.insert(agent_key.clone(), false);
+                }
+            }
+        } else {
+            cleanup_results.insert(agent_key.clone(), false);
+        }
+    }
+
+    let budget_reason = shared_budget.stop_reason().await;
+    run_event_writer.write_event(&RunEvent::new(
+        match overall_status {
+            RunStatus::Completed => EventKind::RunCompleted,
+            _ => EventKind::RunFailed,
+        },
+        None,
+        serde_json::json!({
+            "status": format!("{overall_status:?}"),
+            "budget_stop_reason": budget_reason,
+            "task_panics": task_panics,
+        }),
+    ))?;
+
+    manifest.candidate_commits = candidate_commits.clone();
+    manifest.mark_completed(overall_status.clone());
+    manifest.write_to(&layout.manifest_path())?;
+    drop(live_run);
+    sink.emit(RaceEvent::RunFinished {
+        status: overall_status.clone(),
+    });
+
+    let health = EventReader::read_all(&layout.events_path())
+        .ok()
+        .map(|mut events| {
+            for key in &agent_keys {
+                events.extend(EventReader::read_all(&layout.agent_events(key)).unwrap_or_default());
+            }
+            RunHealthMetrics::from_events(&events)
+        });
+
+    let agents = results
+        .into_iter()
+        .map(|(key, result, duration)| {
+            let tier = slots
+                .iter()
+                .find(|slot| slot.key == key)
+                .map(|slot| slot.adapter.tier())
+                .unwrap_or(crate::adapter::AdapterTier::Experimental);
+            let branch = worktrees
+                .iter()
+                .find(|w| w.agent_key == key)
+                .map(|w| w.branch.clone())
+                .unwrap_or_default();
+            let (status, error, usage, usage_status) = match result {
+                Ok(o) => (o.status, o.error, Some(o.usage), o.usage_status),
+                Err(e) => (
+                    RunStatus::Failed,
+                    Some(e.to_string()),
+                    None,
+                    UsageStatus::Unavailable,
+                ),
+            };
+            AgentOutcome {
+                worktree_cleaned: cleanup_results.get(&key).copied().unwrap_or(false),
+                score: score_map.get(&key).cloned(),
+                notes: agent_notes.remove(&key),
+                commits: candidate_commits.remove(&key),
+                agent_key: key,
+                tier,
+                status,
+                error,
+                duration_ms: duration.as_millis() as u64,
+                branch,
+                usage,
+                usage_status,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(RaceOutcome {
+        run_id,
+        status: overall_status,
+        duration_ms: run_started_at.elapsed().as_millis() as u64,
+        artifacts_dir: layout.base_dir().to_path_buf(),
+        baseline_path: layout.baseline_result(),
+        commands: resolved_commands,
+        usage: aggregate_usage(&agents),
+        agents,
+        rankings: ranked_scores,
+        budget_stop_reason: budget_reason,
+        stopped_early_by,
+        health,
+    })
+}
+
+struct SingleAgentRunCtx<'a> {
+    prompt: &'a str,
+    unsafe_mode: bool,
+    config: &'a HydraConfig,
+    wt_info: &'a WorktreeInfo,
+    events_path: PathBuf,
+    edited_files_path: PathBuf,
+    supported_flags: Vec<String>,
+    expects_usage: bool,
+    budget: BudgetConfig,
+    /// Prices usage the agent reports without a cost.
+    price: Option<AgentPrice>,
+    shared_budget: Arc<SharedBudgetState>,
+    cancel: CancelToken,
+    /// Set once another agent passed the gates under `stop_on_first_success`.
+    early_stop: CancelToken,
+    approvals: ApprovalGate,
+    /// Written by `hydra cancel`; polled alongside the budget stop flag.
+    run_cancel_marker: PathBuf,
+    agent_cancel_marker: PathBuf,
+    sink: &'a dyn RaceEventSink,
+    event_sinks: EventSinks,
+}
+
+impl SingleAgentRunCtx<'_> {
+    fn cancel_requested(&self) -> bool {
+        self.cancel.is_cancelled()
+            || self.run_cancel_marker.exists()
+            || self.agent_cancel_marker.exists()
+    }
+}
+
+/// Add the edits a tool call reports to the agent's `edited_files.json` and
+/// announce each one. The artifact is best effort: it only feeds live
+/// displays and pre-checks, the final diff is what gets scored.
+fn record_file_edits(
+    ctx: &SingleAgentRunCtx<'_>,
+    edited_files: &mut EditedFiles,
+    tool: &str,
+    input: &serde_json::Value,
+) {
+    let edits = FileEdit::from_tool_call(tool, input);
+    if edits.is_empty() {
+        return;
+    }
+    let protected_before = edited_files.scope.as_ref().map_or(0, |s| s.protected.len());
+    let recorded: Vec<(String, FileOperation)> = edits
+        .into_iter()
+        .filter_map(|edit| {
+            let operation = edit.operation;
+            Some((edited_files.record(&ctx.wt_info.path, edit)?, operation))
+        })
+        .collect();
+    let scope = precheck_diff_scope(
+        edited_files.files.keys().map(String::as_str),
+        edited_files.churn(),
+        &ctx.config.scoring.diff_scope,
+    );
+    if scope.protected.len() > protected_before {
+        tracing::warn!(
+            agent = %ctx.wt_info.agent_key,
+            paths = ?scope.protected,
+            "agent edited protected paths; its diff scope score will be capped"
+        );
+    }
+    edited_files.scope = Some(scope.clone());
+    if let Err(e) = edited_files.write_to(&ctx.edited_files_path) {
+        tracing::warn!(agent = %ctx.wt_info.agent_key, error = %e, "failed to write edited_files.json");
+    }
+    for (path, operation) in recorded {
+        ctx.sink.emit(RaceEvent::FileEdited {
+            agent_key: ctx.wt_info.agent_key.clone(),
+            path,
+            operation,
+            files: edited_files.files.len(),
+            churn: edited_files.churn(),
+            scope: scope.clone(),
+        });
+    }
+}
+
+/// Kernel confinement for an agent in `worktree`, per `supervisor.os_sandbox`.
+fn os_sandbox(worktree: &Path, cfg: &SupervisorConfig) -> OsSandbox {
+    cfg.sandbox_writable_paths.iter().fold(
+        OsSandbox::for_worktree(worktree, cfg.os_sandbox),
+        |sandbox, path| sandbox.allow_write(path),
+    )
+}
+
+/// Apply `security.allow_network` to an agent about to be spawned. When a
+/// proxy is needed it is returned with its feed of refused connections; the
+/// proxy must outlive the agent.
+async fn apply_egress_policy(
+    security: &SecurityConfig,
+    cmd: &mut BuiltCommand,
+    policy: &mut SupervisorPolicy,
+) -> Result<Option<(EgressProxy, mpsc::Receiver<NetworkViolation>)>> {
+    let allowlist = match security.allow_network {
+        NetworkAccess::Allow => return Ok(None),
+        NetworkAccess::Deny => {
+            if let Some(container) = policy.container.take() {
+                policy.container = Some(container.without_network());
+                return Ok(None);
+            }
+            if private_netns_supported() {
+                policy.isolate_network = true;
+                return Ok(None);
+            }
+            tracing::warn!(
+                "private network namespaces are unavailable; denying egress through a proxy only"
+            );
+            Vec::new()
+        }
+        NetworkAccess::Allowlist => security.network_allowlist.clone(),
+    };
+    let (proxy, violations) = EgressProxy::start(allowlist)
+        .await
+        .map_err(SupervisorError::Io)?;
+    cmd.env.extend(proxy.env());
+    Ok(Some((proxy, violations)))
+}
+
+/// Next refused connection, or never if the agent has no egress proxy.
+async fn next_violation(
+    violations: &mut Option<mpsc::Receiver<NetworkViolation>>,
+) -> NetworkViolation {
+    match violations.as_mut() {
+        Some(rx) => match rx.recv().await {
+            Some(violation) => violation,
+            None => std::future::pending().await,
+        },
+        None => std::future::pending().await,
+    }
+}
+
+/// A dangerous action the agent is paused on.
+struct PendingApproval {
+    approval_id: Uuid,
+    action: DangerousAction,
+    decision: oneshot::Receiver<ApprovalDecision>,
+}
+
+/// Pause the agent and ask the front end about `action`.
+async fn request_approval(
+    ctx: &SingleAgentRunCtx<'_>,
+    handle: &SupervisorHandle,
+    event_writer: &mut EventBus,
+    agent_key: &str,
+    action: DangerousAction,
+) -> Result<PendingApproval> {
+    if !handle.is_paused() {
+        handle.pause().await;
+    }
+    let (approval_id, decision) = ctx.approvals.open();
+    event_writer.write_event(&RunEvent::new(
+        EventKind::ApprovalRequested,
+        Some(agent_key.to_string()),
+        serde_json::json!({
+            "approval_id": approval_id.to_string(),
+            "action": action,
+        }),
+    ))?;
+    ctx.sink.emit(RaceEvent::ApprovalRequested {
+        agent_key: agent_key.to_string(),
+        approval_id,
+        action: action.clone(),
+    });
+    Ok(PendingApproval {
+        approval_id,
+        action,
+        decision,
+    })
+}
+
+/// Answer to the pending approval, or never if there is none. A request
+/// dropped without an answer counts as denied.
+async fn next_decision(pending: &mut Option<PendingApproval>) -> ApprovalDecision {
+    match pending.as_mut() {
+        Some(pending) => (&mut pending.decision)
+            .await
+            .unwrap_or(ApprovalDecision::Deny),
+        None => std::future::pending().await,
+    }
+}
+
+async fn run_single_agent(
+    adapter: Arc<dyn AgentAdapter>,
+    ctx: SingleAgentRunCtx<'_>,
+) -> Result<AgentRunResult> {
+    let mut event_writer = EventBus::new(
+        EventWriter::create(&ctx.events_path)?
+            .with_redactor(SecretRedactor::from_config(&ctx.config.security.redaction)),
+        ctx.event_sinks.clone(),
+    );
+
+    let sandbox = if ctx.unsafe_mode {
+        SandboxPolicy::unsafe_mode(ctx.wt_info.path.clone())
+    } else {
+        SandboxPolicy::strict(ctx.wt_info.path.clone())
+    }
+    .with_path_rules(PathRules::from_config(&ctx.config.security.write_policy));
+
+    let req = SpawnRequest {
+        task_prompt: ctx.prompt.to_string(),
+        worktree_path: ctx.wt_info.path.clone(),
+        timeout_seconds: ctx.config.supervisor.hard_timeout_seconds,
+        allow_network: ctx.unsafe_mode,
+        force_edit: true,
+        output_json_stream: true,
+        unsafe_mode: ctx.unsafe_mode,
+        supported_flags: ctx.supported_flags.clone(),
+    };
+
+    let mut cmd: BuiltCommand = adapter.build_command(&req)?;
+    match sandbox.check_path(&cmd.cwd) {
+        SandboxResult::Allowed => {}
+        SandboxResult::Blocked { path, allowed_root } => {
+            return Err(OrchestratorError::SandboxBlocked { path, allowed_root });
+        }
+    }
+
+    let mut policy = SupervisorPolicy::from_hydra_config(&ctx.config.supervisor);
+    if *sandbox.mode() == SandboxMode::Strict {
+        match ctx.config.security.isolation {
+            IsolationMode::Process => {
+                policy.sandbox = Some(os_sandbox(&ctx.wt_info.path, &ctx.config.supervisor));
+            }
+            IsolationMode::Container => {
+                let name = format!("hydra-{}-{}", adapter.key(), Uuid::new_v4().simple());
+                let container = ContainerSpec::for_worktree(
+                    &ctx.config.security.container,
+                    &ctx.wt_info.path,
+                    name,
+                )
+                .map_err(SupervisorError::from)?;
+                policy.container = Some(container);
+            }
+        }
+    }
+    // Held until the agent exits; dropping it stops the proxy.
+    let (_egress_proxy, mut violations) =
+        apply_egress_policy(&ctx.config.security, &mut cmd, &mut policy)
+            .instrument(tracing::info_span!("supervise.egress"))
+            .await?
+            .unzip();
+    let (event_tx, mut event_rx) = mpsc::channel::<SupervisorEvent>(256);
+
+    let agent_key = ctx.wt_info.agent_key.clone();
+    let line_parser = {
+        let parser_adapter = Arc::clone(&adapter);
+        move |line: &str| parser_adapter.parse_line(line)
+    };
+
+    let handle = supervise(cmd, policy, event_tx, line_parser)
+        .instrument(tracing::info_span!("supervise.spawn"))
+        .await?;
+    // Spans the agent's lifetime: from spawn until its event stream ends.
+    let run_span = tracing::info_span!("supervise.run", status = tracing::field::Empty);
+
+    event_writer.write_event(&RunEvent::new(
+        EventKind::AgentStarted,
+        Some(agent_key.clone()),
+        serde_json::json!({}),
+    ))?;
+    ctx.sink.emit(RaceEvent::AgentStarted {
+        agent_key: agent_key.clone(),
+    });
+
+    let mut usage = match &ctx.price {
+        Some(price) => UsageAccumulator::with_price(price.price),
+        None => UsageAccumulator::new(),
+    };
+    let mut progress = ProgressEstimator::new();
+    let mut edited_files = EditedFiles::default();
+    let mut cancel_sent = false;
+    let mut user_cancelled = false;
+    // Whether a tool call is still running, and when a budget-stopped agent
+    // that was in one must be stopped anyway.
+    let mut mid_step = false;
+    let mut grace_until: Option<Instant> = None;
+    let grace = Duration::from_secs(ctx.budget.grace_seconds);
+    let approval = &ctx.config.security.approval;
+    let mut pending_approval: Option<PendingApproval> = None;
+    let mut queued_actions: VecDeque<DangerousAction> = VecDeque::new();
+    let mut denied_action: Option<DangerousAction> = None;
+    let mut outcome = AgentRunResult {
+        status: RunStatus::Failed,
+        error: None,
+        usage: CostEstimate {
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            estimated_cost_usd: None,
+        },
+        priced_model: None,
+        usage_status: if ctx.expects_usage {
+            UsageStatus::Missing
+        } else {
+            UsageStatus::Unavailable
+        },
+        path_violations: Vec::new(),
+    };
+    loop {
+        tokio::select! {
+            evt = event_rx.recv() => {
+                let Some(evt) = evt else {
+                    break;
+                };
+                match &evt {
+                    SupervisorEvent::Started { pid } => {
+                        tracing::info!(pid = pid, agent = %agent_key, "agent process started");
+                    }
+                    SupervisorEvent::Stdout(line) => {
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentStdout,
+                            Some(agent_key.clone()),
+                            serde_json::json!({ "line": line }),
+                        ))?;
+                        ctx.sink.emit(RaceEvent::AgentOutput {
+                            agent_key: agent_key.clone(),
+                            stream: OutputStream::Stdout,
+                            line: line.clone(),
+                        });
+                    }
+                    SupervisorEvent::Stderr(line) => {
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentStderr,
+                            Some(agent_key.clone()),
+                            serde_json::json!({ "line": line }),
+                        ))?;
+                        ctx.sink.emit(RaceEvent::AgentOutput {
+                            agent_key: agent_key.clone(),
+                            stream: OutputStream::Stderr,
+                            line: line.clone(),
+                        });
+                    }
+                    SupervisorEvent::StepOutput {
+                        label,
+                        line,
+                        stderr,
+                    } => {
+                        let (kind, stream) = if *stderr {
+                            (EventKind::AgentStderr, OutputStream::Stderr)
+                        } else {
+                            (EventKind::AgentStdout, OutputStream::Stdout)
+                        };
+                        event_writer.write_event(&RunEvent::new(
+                            kind,
+                            Some(agent_key.clone()),
+                            serde_json::json!({ "line": line, "step": label }),
+                        ))?;
+                        ctx.sink.emit(RaceEvent::AgentOutput {
+                            agent_key: agent_key.clone(),
+                            stream,
+                            line: format!("[{label}] {line}"),
+                        });
+                    }
+                    SupervisorEvent::StepCompleted { label, duration } => {
+                        tracing::info!(
+                            step = %label,
+                            duration_ms = duration.as_millis() as u64,
+                            agent = %agent_key,
+                            "agent chain step completed"
+                        );
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentStep,
+                            Some(agent_key.clone()),
+                            serde_json::json!({
+                                "step": label,
+                                "duration_ms": duration.as_millis() as u64,
+                            }),
+                        ))?;
+                    }
+                    SupervisorEvent::AgentEvent(agent_evt) => {
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentStdout,
+                            Some(agent_key.clone()),
+                            serde_json::to_value(agent_evt).unwrap_or_default(),
+                        ))?;
+                        let event_cost = usage.process_event(agent_evt);
+                        ctx.sink.emit(RaceEvent::AgentEvent {
+                            agent_key: agent_key.clone(),
+                            event: agent_evt.clone(),
+                            total_tokens: usage.total_tokens(),
+                        });
+                        if let Some(estimate) = progress.observe(agent_evt, usage.total_tokens()) {
+                            event_writer.write_event(&RunEvent::new(
+                                EventKind::AgentProgress,
+                                Some(agent_key.clone()),
+                                serde_json::to_value(&estimate).unwrap_or_default(),
+                            ))?;
+                            ctx.sink.emit(RaceEvent::AgentProgress {
+                                agent_key: agent_key.clone(),
+                                progress: estimate,
+                            });
+                        }
+                        if let AgentEvent::ToolCall { tool, input } = agent_evt {
+                            let violation = tool_file_access(tool, input).and_then(|(access, path)| {
+                                sandbox.check_access(Path::new(&path), access)
+                            });
+                            if let Some(violation) = violation {
+                                tracing::warn!(agent = %agent_key, violation = %violation, "write policy violation");
+                                event_writer.write_event(&RunEvent::new(
+                                    EventKind::SecurityViolation,
+                                    Some(agent_key.clone()),
+                                    serde_json::json!({
+                                        "kind": "path_policy",
+                                        "path": violation.path,
+                                        "rule": violation.rule,
+                                        "pattern": violation.pattern,
+                                    }),
+                                ))?;
+                                outcome.path_violations.push(violation);
+                            }
+                            record_file_edits(&ctx, &mut edited_files, tool, input);
+                        }
+                        let action = match agent_evt {
+                            AgentEvent::ToolCall { tool, input } if !cancel_sent => {
+                                dangerous_action(tool, input, &approval.safe_commands)
+                            }
+                            _ => None,
+                        };
+                        match (approval.policy, action) {
+                            (_, None) | (ApprovalPolicy::AutoApprove, _) => {}
+                            (ApprovalPolicy::Deny, Some(action)) => {
+                                tracing::warn!(agent = %agent_key, action = %action, "dangerous action denied by policy");
+                                event_writer.write_event(&RunEvent::new(
+                                    EventKind::SecurityViolation,
+                                    Some(agent_key.clone()),
+                                    serde_json::json!({
+                                        "kind": "dangerous_action",
+                                        "action": action,
+                                    }),
+                                ))?;
+                                denied_action = Some(action);
+                                handle.cancel().await;
+                                cancel_sent = true;
+                            }
+                            (ApprovalPolicy::Ask, Some(action)) => {
+                                if pending_approval.is_some() {
+                                    queued_actions.push_back(action);
+                                } else {
+                                    pending_approval = Some(
+                                        request_approval(&ctx, &handle, &mut event_writer, &agent_key, action)
+                                            .await?,
+                                    );
+                                }
+                            }
+                        }
+                        if let AgentEvent::Usage {
+                            input_tokens,
+                            output_tokens,
+                            ..
+                        } = agent_evt
+                        {
+                            let delta_tokens = *input_tokens + *output_tokens;
+                            match ctx
+                                .shared_budget
+                                .note_usage(delta_tokens, event_cost, &ctx.budget)
+                                .await
+                            {
+                                Some(_reason) => {
+                                    if !cancel_sent
+                                        && budget_stop_due(mid_step, &mut grace_until, grace)
+                                    {
+                                        handle.cancel().await;
+                                        cancel_sent = true;
+                                    }
+                                }
+                                None => {
+                                    for warning in ctx.shared_budget.new_warnings(&ctx.budget).await {
+                                        tracing::warn!(agent = %agent_key, %warning, "approaching run budget");
+                                        event_writer.write_event(&RunEvent::new(
+                                            EventKind::BudgetWarning,
+                                            Some(agent_key.clone()),
+                                            serde_json::to_value(&warning).unwrap_or_default(),
+                                        ))?;
+                                        ctx.sink.emit(RaceEvent::BudgetWarning {
+                                            agent_key: agent_key.clone(),
+                                            warning,
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                        match agent_evt {
+                            AgentEvent::ToolCall { .. } => mid_step = true,
+                            AgentEvent::ToolResult { .. }
+                            | AgentEvent::Message { .. }
+                            | AgentEvent::Completed { .. } => mid_step = false,
+                            _ => {}
+                        }
+                    }
+                    SupervisorEvent::Completed {
+                        exit_code,
+                        duration,
+                    } => {
+                        tracing::info!(
+                            exit_code = exit_code,
+                            duration_ms = duration.as_millis() as u64,
+                            agent = %agent_key,
+                            "agent completed"
+                        );
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentCompleted,
+                            Some(agent_key.clone()),
+                            serde_json::json!({
+                                "exit_code": exit_code,
+                                "duration_ms": duration.as_millis() as u64,
+                            }),
+                        ))?;
+                        outcome.status = RunStatus::Completed;
+                        break;
+                    }
+                    SupervisorEvent::Failed { error, duration } => {
+                        tracing::warn!(
+                            error = %error,
+                            duration_ms = duration.as_millis() as u64,
+                            agent = %agent_key,
+                            "agent failed"
+                        );
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentFailed,
+                            Some(agent_key.clone()),
+                            serde_json::json!({
+                                "error": error,
+                                "duration_ms": duration.as_millis() as u64,
+                            }),
+                        ))?;
+                        if let Some(action) = &denied_action {
+                            outcome.status = RunStatus::Failed;
+                            outcome.error = Some(format!("stopped: not allowed to {action}"));
+                        } else if error.contains("cancelled") {
+                            outcome.status = RunStatus::Interrupted;
+                            outcome.error = if user_cancelled {
+                                Some("cancelled by user".to_string())
+                            } else if ctx.shared_budget.should_stop() {
+                                Some(
+                                    ctx.shared_budget
+                                        .stop_reason()
+                                        .await
+                                        .unwrap_or_else(|| "budget exceeded".to_string()),
+                                )
+                            } else if ctx.early_stop.is_cancelled() {
+                                Some(STOPPED_EARLY_ERROR.to_string())
+                            } else {
+                                Some(error.clone())
+                            };
+                        } else {
+                            outcome.status = RunStatus::Failed;
+                            outcome.error = Some(error.clone());
+                        }
+                        break;
+                    }
+                    SupervisorEvent::TimedOut { kind, duration } => {
+                        tracing::warn!(
+                            kind = %kind,
+                            duration_ms = duration.as_millis() as u64,
+                            agent = %agent_key,
+                            "agent timed out"
+                        );
+                        event_writer.write_event(&RunEvent::new(
+                            EventKind::AgentFailed,
+                            Some(agent_key.clone()),
+                            serde_json::json!({
+                                "error": format!("timed out ({kind})"),
+                                "duration_ms": duration.as_millis() as u64,
+                            }),
+                        ))?;
+                        outcome.status = RunStatus::TimedOut;
+                        outcome.error = Some(format!("timed out ({kind})"));
+                        break;
+                    }
+                }
+            }
+            violation = next_violation(&mut violations) => {
+                tracing::warn!(
+                    agent = %agent_key,
+                    host = %violation.host,
+                    port = violation.port,
+                    "blocked network egress"
+                );
+                event_writer.write_event(&RunEvent::new(
+                    EventKind::SecurityViolation,
+                    Some(agent_key.clone()),
+                    serde_json::json!({
+                        "kind": "network_egress",
+                        "host": violation.host,
+                        "port": violation.port,
+                    }),
+                ))?;
+            }
+            decision = next_decision(&mut pending_approval) => {
+                let Some(resolved) = pending_approval.take() else {
+                    continue;
+                };
+                tracing::info!(
+                    agent = %agent_key,
+                    action = %resolved.action,
+                    decision = decision.as_str(),
+                    "approval resolved"
+                );
+                event_writer.write_event(&RunEvent::new(
+                    EventKind::ApprovalResolved,
+                    Some(agent_key.clone()),
+                    serde_json::json!({
+                        "approval_id": resolved.approval_id.to_string(),
+                        "decision": decision,
+                    }),
+                ))?;
+                ctx.sink.emit(RaceEvent::ApprovalResolved {
+                    agent_key: agent_key.clone(),
+                    approval_id: resolved.approval_id,
+                    decision,
+                });
+                match decision {
+                    ApprovalDecision::Approve => match queued_actions.pop_front() {
+                        Some(next) => {
+                            pending_approval = Some(
+                                request_approval(&ctx, &handle, &mut event_writer, &agent_key, next)
+                                    .await?,
+                            );
+                        }
+                        None => handle.resume().await,
+                    },
+                    ApprovalDecision::Deny => {
+                        queued_actions.clear();
+                        denied_action = Some(resolved.action);
+                        if !cancel_sent {
+                            handle.cancel().await;
+                            cancel_sent = true;
+                        }
+                    }
+                }
+            }
+            _ = tokio::time::sleep(Duration::from_millis(100)), if !cancel_sent => {
+                if ctx.cancel_requested() {
+                    tracing::info!(agent = %agent_key, "cancellation requested");
+                    user_cancelled = true;
+                    handle.cancel().await;
+                    cancel_sent = true;
+                } else if ctx.early_stop.is_cancelled()
+                    || (ctx.shared_budget.should_stop()
+                        && budget_stop_due(mid_step, &mut grace_until, grace))
+                {
+                    handle.cancel().await;
+                    cancel_sent = true;
+                }
+            }
+        }
+    }
+
+    run_span.record("status", tracing::field::debug(&outcome.status));
+    drop(run_span);
+
+    if let Some(pending) = pending_approval {
+        ctx.approvals.withdraw(pending.approval_id);
+    }
+    outcome.usage = usage.to_estimate();
+    if usage.priced_cost_usd > 0.0 {
+        outcome.priced_model = ctx.price.as_ref().map(|p| p.model.clone());
+    }
+    outcome.usage_status = if usage.has_usage_data() {
+        UsageStatus::Captured
+    } else if ctx.expects_usage {
+        UsageStatus::Missing
+    } else {
+        UsageStatus::Unavailable
+    };
+    Ok(outcome)
+}
+
+/// One candidate in a race: an adapter, and the prompt variant it runs
+/// when racing variants.
+struct AgentSlot {
+    adapter: Arc<dyn AgentAdapter>,
+    /// Agent key for the candidate's worktree, branch and artifacts.
+    key: String,
+    variant: Option<PromptVariant>,
+}
+
+/// The race's candidates: one per adapter, or one per variant when racing
+/// prompt variants on a single adapter.
+fn agent_slots(
+    adapters: &[Arc<dyn AgentAdapter>],
+    variants: &[PromptVariant],
+) -> Result<Vec<AgentSlot>> {
+    if variants.is_empty() {
+        return Ok(adapters
+            .iter()
+            .map(|adapter| AgentSlot {
+                adapter: Arc::clone(adapter),
+                key: adapter.key().to_string(),
+                variant: None,
+            })
+            .collect());
+    }
+    let invalid = |reason: String| OrchestratorError::InvalidVariants { reason };
+    let [adapter] = adapters else {
+        return Err(invalid(format!(
+            "variants race a single agent, but {} were selected",
+            adapters.len()
+        )));
+    };
+    let mut slots: Vec<AgentSlot> = Vec::with_capacity(variants.len());
+    for variant in variants {
+        if variant.prompt.trim().is_empty() {
+            return Err(invalid(format!(
+                "variant '{}' has an empty prompt",
+                variant.name
+            )));
+        }
+        let key = format!("{}-{}", adapter.key(), variant.name);
+        validate_agent_key(&key)
+            .map_err(|e| invalid(format!("variant name '{}': {e}", variant.name)))?;
+        if slots.iter().any(|slot| slot.key == key) {
+            return Err(invalid(format!("duplicate variant '{}'", variant.name)));
+        }
+        slots.push(AgentSlot {
+            adapter: Arc::clone(adapter),
+            key,
+            variant: Some(variant.clone()),
+        });
+    }
+    Ok(slots)
+}
+
+/// Error recorded for agents stopped because another passed the gates.
+const STOPPED_EARLY_ERROR: &str = "stopped early: another agent passed the gates";
+
+/// The worktrees worth scoring, and which of them timed out. Agents stopped
+/// because another passed the gates are not worth building and testing.
+/// Timed-out ones may hold nearly finished work and are scored as partial
+/// when `score_timed_out` is on.
+fn scoring_candidates<'a>(
+    worktrees: &'a [WorktreeInfo],
+    results: &'a [(String, Result<AgentRunResult>, Duration)],
+    score_timed_out: bool,
+) -> (Vec<&'a WorktreeInfo>, HashSet<&'a str>) {
+    let timed_out: HashSet<&str> = results
+        .iter()
+        .filter(|(_, result, _)| matches!(result, Ok(o) if o.status == RunStatus::TimedOut))
+        .map(|(key, _, _)| key.as_str())
+        .collect();
+    let candidates = worktrees
+        .iter()
+        .filter(|wt| {
+            !results.iter().any(|(key, result, _)| {
+                key == &wt.agent_key
+                    && matches!(result, Ok(o) if o.error.as_deref() == Some(STOPPED_EARLY_ERROR))
+            })
+        })
+        .filter(|wt| score_timed_out || !timed_out.contains(wt.agent_key.as_str()))
+        .collect();
+    (candidates, timed_out)
+}
+
+struct ScoreRunCtx<'a> {
+    layout: &'a RunLayout,
+    base_ref: &'a str,
+    config: &'a HydraConfig,
+    baseline: &'a BaselineResult,
+    commands: &'a ResolvedCommands,
+    redactor: &'a SecretRedactor,
+}
+
+impl ScoreRunCtx<'_> {
+    /// Build, test and lint one agent's worktree. `violations` are the
+    /// write-policy violations seen in its tool calls.
+    async fn evaluate(
+        &self,
+        agent_key: &str,
+        wt_path: &Path,
+        violations: Option<&Vec<PathViolation>>,
+    ) -> Result<Vec<DimensionScore>> {
+        let started = Instant::now();
+        let mut dimensions = evaluate_agent_dimensions(
+            wt_path,
+            &self.layout.agent_dir(agent_key),
+            self.base_ref,
+            self.config,
+            self.baseline,
+            self.commands,
+        )
+        .instrument(tracing::info_span!("scoring.agent", agent = agent_key))
+        .await
+        .map_err(|source| {
+            OrchestratorError::Scoring {
+                adapter: agent_key.to_string(),
+                source,
+            }
+            .with_context(ErrorContext::agent(agent_key))
+        })?;
+        if let Some(violations) = violations {
+            record_runtime_violations(&mut dimensions, violations);
+        }
+
+        // The agent's task has ended, so nothing else writes its log now.
+        let checks: Vec<serde_json::Value> = dimensions
+            .iter()
+            .filter_map(|dim| {
+                let ms = dim.evidence.get("duration_ms")?.as_u64()?;
+                Some(serde_json::json!({ "check": dim.name, "duration_ms": ms }))
+            })
+            .collect();
+        EventWriter::create(&self.layout.agent_events(agent_key))
+            .map(|writer| writer.with_redactor(self.redactor.clone()))
+            .and_then(|mut writer| {
+                writer.write_event(&RunEvent::new(
+                    EventKind::AgentScored,
+                    Some(agent_key.to_string()),
+                    serde_json::json!({
+                        "duration_ms": started.elapsed().as_millis() as u64,
+                        "checks": checks,
+                    }),
+                ))
+            })
+            .unwrap_or_else(
+                |e| tracing::warn!(agent = agent_key, error = %e, "failed to record scoring times"),
+            );
+        Ok(dimensions)
+    }
+}
+
+/// Score an agent the moment it completes, for `stop_on_first_success`,
+/// returning its evaluated dimensions alongside the score.
+/// Alone it gets full marks for speed, which is also what the final ranking
+/// gives it unless an agent that failed earlier was faster. A scoring error
+/// is only logged here; the final ranking runs into it again and reports it.
+async fn score_finished_agent(
+    ctx: &ScoreRunCtx<'_>,
+    agent_key: &str,
+    wt_path: Option<&Path>,
+    outcome: &AgentRunResult,
+    duration: Duration,
+) -> Option<(Vec<DimensionScore>, AgentScore)> {
+    let dimensions = match ctx
+        .evaluate(agent_key, wt_path?, Some(&outcome.path_violations))
+        .await
+    {
+        Ok(dimensions) => dimensions,
+        Err(e) => {
+            tracing::warn!(agent = %agent_key, error = %e, "early scoring failed");
+            return None;
+        }
+    };
+    let score = rank_agents(
+        vec![(agent_key.to_string(), dimensions.clone())],
+        &ctx.config.scoring.weights,
+        &ctx.config.scoring.gates,
+        &HashMap::from([(agent_key.to_string(), duration)]),
+    )
+    .pop()?;
+    Some((dimensions, score))
+}
+
+/// Rank `candidates`, evaluating those not already in `evaluated`. Scores
+/// of agents in `partial` are flagged as such.
+async fn score_agents(
+    candidates: &[&WorktreeInfo],
+    ctx: &ScoreRunCtx<'_>,
+    durations: &HashMap<String, Duration>,
+    path_violations: &HashMap<String, Vec<PathViolation>>,
+    mut evaluated: HashMap<String, Vec<DimensionScore>>,
+    partial: &HashSet<&str>,
+) -> Result<Vec<AgentScore>> {
+    let mut agent_dimensions: Vec<(String, Vec<DimensionScore>)> = Vec::new();
+
+    for wt_info in candidates {
+        let key = &wt_info.agent_key;
+        let dimensions = match evaluated.remove(key) {
+            Some(dimensions) => dimensions,
+            None => {
+                ctx.evaluate(key, &wt_info.path, path_violations.get(key))
+                    .await?
+            }
+        };
+        agent_dimensions.push((key.clone(), dimensions));
+    }
+
+    let mut ranked = rank_agents(
+        agent_dimensions,
+        &ctx.config.scoring.weights,
+        &ctx.config.scoring.gates,
+        durations,
+    );
+    for score in &mut ranked {
+        score.partial = partial.contains(score.agent_key.as_str());
+    }
+    for score in &ranked {
+        AgentScoreArtifact::new(score.clone())
+            .write_to(&ctx.layout.agent_score(&score.agent_key), ctx.redactor)
+            .map_err(|e| {
+                OrchestratorError::from(e).with_context(ErrorContext::agent(&score.agent_key))
+            })?;
+    }
+    Ok(ranked)
+}
+
+/// The most recent baseline recorded on `base_commit` with the same scoring
+/// commands, and the run that recorded it.
+fn cached_baseline(
+    hydra_root: &Path,
+    base_commit: &str,
+    commands: &ResolvedCommands,
+) -> Option<(Uuid, BaselineResult)> {
+    RunLayout::list_runs(hydra_root)
+        .ok()?
+        .into_iter()
+        .filter_map(|run_id| {
+            let layout = RunLayout::new(hydra_root, run_id);
+            let manifest = RunManifest::read_from(&layout.manifest_path()).ok()?;
+            let environment = manifest.environment.as_ref()?;
+            if environment.base_commit.as_deref() != Some(base_commit) {
+                return None;
+            }
+            let data = std::fs::read_to_string(layout.baseline_result()).ok()?;
+            let baseline: BaselineResult = serde_json::from_str(&data).ok()?;
+            baseline
+                .ran_commands(commands)
+                .then_some((manifest.started_at, run_id, baseline))
+        })
+        .max_by_key(|(started_at, ..)| *started_at)
+        .map(|(_, run_id, baseline)| (run_id, baseline))
+}
+
+/// Seed every candidate worktree's build outputs from the first worktree,
+/// where the baseline was just built. Keyed by agent; `None` when seeding is
+/// off or there is only one worktree. Failures leave that candidate to build
+/// cold.
+async fn seed_candidate_build_caches(
+    slots: &[AgentSlot],
+    worktrees: &[WorktreeInfo],
+    config: &BuildCacheConfig,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    if config.mode == BuildCacheMode::Off || worktrees.len() < 2 {
+        return None;
+    }
+    let source = &worktrees[0].path;
+    let mut seeded = serde_json::Map::new();
+    for (slot, wt) in slots.iter().zip(worktrees).skip(1) {
+        match seed_build_cache(source, &wt.path, config)
+            .instrument(tracing::info_span!("scoring.build_cache", agent = %slot.key))
+            .await
+        {
+            Ok(seed) => {
+                tracing::info!(
+                    agent = %slot.key,
+                    files = seed.files,
+                    bytes = seed.bytes,
+                    cloned = seed.cloned,
+                    linked = seed.linked,
+                    copied = seed.copied,
+                    "seeded build cache"
+                );
+                seeded.insert(slot.key.clone(), serde_json::json!(seed));
+            }
+            Err(e) => {
+                tracing::warn!(agent = %slot.key, error = %e, "could not seed build cache");
+            }
+        }
+    }
+    Some(seeded)
+}
+
+pub(super) fn persist_baseline_logs(
+    layout: &RunLayout,
+    baseline: &BaselineResult,
+    redactor: &SecretRedactor,
+) -> std::result::Result<(), std::io::Error> {
+    if let Some(build) = baseline.build.as_ref() {
+        write_command_artifact(&layout.baseline_build_log(), build, redactor)?;
+    }
+    if let Some(test) = baseline.test.as_ref() {
+        write_command_artifact(&layout.baseline_test_log(), &test.command_result, redactor)?;
+    }
+    if let Some(lint) = baseline.lint.as_ref() {
+        write_command_artifact(&layout.baseline_lint_log(), &lint.command_result, redactor)?;
+    }
+    Ok(())
+}
+
+async fn rollback_worktrees(wt_service: &WorktreeService, worktrees: &[WorktreeInfo]) {
+    for wt in worktrees {
+        if let Err(e) = wt_service.force_cleanup(wt).await {
+            tracing::warn!(
+                branch = %wt.branch,
+                path = %wt.path.display(),
+                error = %e,
+                "failed to rollback worktree after setup error"
+            );
+        }
+    }
+}
+
+fn aggregate_usage(agents: &[AgentOutcome]) -> CostEstimate {
+    let mut total = CostEstimate {
+        input_tokens: 0,
+        output_tokens: 0,
+        total_tokens: 0,
+        estimated_cost_usd: None,
+    };
+    for usage in agents.iter().filter_map(|a| a.usage.as_ref()) {
+        total.input_tokens += usage.input_tokens;
+        total.output_tokens += usage.output_tokens;
+        total.total_tokens += usage.total_tokens;
+        if let Some(cost) = usage.estimated_cost_usd {
+            *total.estimated_cost_usd.get_or_insert(0.0) += cost;
+        }
+    }
+    total
+}
+
+/// A run directory whose setup has not finished. Dropping it removes the
+/// directory, so a race that fails before writing its manifest leaves
+/// nothing behind that would block the same run ID later.
+struct PartialRunDir {
+    path: Option<PathBuf>,
+}
+
+impl PartialRunDir {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    /// Setup finished; keep the directory.
+    fn keep(mut self) {
+        self.path = None;
+    }
+}
+
+impl Drop for PartialRunDir {
+    fn drop(&mut self) {
+        let Some(path) = self.path.take() else {
+            return;
+        };
+        if let Err(e) = std::fs::remove_dir_all(&path) {
+            tracing::warn!(path = %path.display(), error = %e, "failed to remove partial run directory");
+        }
+    }
+}
+
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+async fn acquire_repo_lock(
+    hydra_root: &Path,
+    run_id: Uuid,
+    opts: &RaceOptions,
+    sink: &dyn RaceEventSink,
+) -> Result<RepoLock> {
+    match opts.lock {
+        LockMode::Force => return Ok(RepoLock::force(hydra_root, run_id)?),
+        LockMode::Fail => return Ok(RepoLock::try_acquire(hydra_root, run_id)?),
+        LockMode::Wait => {}
+    }
+    let mut queued = false;
+    loop {
+        match RepoLock::try_acquire(hydra_root, run_id) {
+            Err(ArtifactError::Locked { holder }) if !opts.cancel.is_cancelled() => {
+                if !queued {
+                    tracing::info!(%holder, "waiting for the repository lock");
+                    sink.emit(RaceEvent::Queued { holder });
+                    queued = true;
+                }
+                tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+            }
+            result => return Ok(result?),
+        }
+    }
+}
+
+async fn resolve_commit(
+    repo_root: &Path,
+    base_ref: &str,
+) -> std::result::Result<String, GitExecError> {
+    let rev = format!("{base_ref}^{{commit}}");
+    let output = run_git(&["rev-parse", "--verify", &rev], repo_root).await?;
+    Ok(output.stdout.trim().to_string())
+}
+
+/// Run ID for a deterministic race: a v8 UUID over the prompt, the commit
+/// the agents branch from, the agent set (order-insensitive) and the config
+/// hash. The leading tag versions the scheme so it can change without
+/// colliding with IDs minted by an older one.
+pub(super) fn deterministic_run_id(
+    prompt: &str,
+    base_commit: &str,
+    agents: &[String],
+    config: &HydraConfig,
+) -> Uuid {
+    use sha2::{Digest, Sha256};
+    let mut agents: Vec<&str> = agents.iter().map(String::as_str).collect();
+    agents.sort_unstable();
+    agents.dedup();
+    let mut hasher = Sha256::new();
+    for part in [
+        "hydra-run-id-v1",
+        &sha256_short(prompt),
+        base_commit,
+        &agents.join(","),
+        &config_hash(config),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+pub(super) fn sha256_short(input: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(input.as_bytes());
+    let mut out = String::with_capacity(16);
+    for byte in digest.iter().take(8) {
+        use std::fmt::Write as _;
+        let _ = write!(&mut out, "{byte:02x}");
+    }
+    out
+}
+
+/// Where agents are asked to explain their approach, at the worktree root.
+const NOTES_FILE: &str = ".hydra-notes.md";
+
+const NOTES_REQUEST: &str = "When you are done, explain your approach for the reviewer in a \
+     short Markdown file named `.hydra-notes.md` at the repository root. It is collected \
+     separately and is not part of your changes.";
+
+/// Move the agent's `.hydra-notes.md` out of its worktree to `dest`, so it
+/// reaches reviewers without counting as one of its changes.
+fn collect_notes(worktree: &Path, dest: &Path, redactor: &SecretRedactor) -> Option<String> {
+    let source = worktree.join(NOTES_FILE);
+    let notes = std::fs::read_to_string(&source).ok()?;
+    if let Err(e) = std::fs::remove_file(&source) {
+        tracing::warn!(error = %e, "failed to remove {NOTES_FILE} from the worktree");
+    }
+    let notes = redactor.redact(notes.trim()).value;
+    if notes.is_empty() {
+        return None;
+    }
+    if let Err(e) = std::fs::write(dest, &notes) {
+        tracing::warn!(error = %e, "failed to write notes.md artifact");
+    }
+    Some(notes)
+}
+
+/// Size of `rel_path` in `worktree`, for binary entries in `diff.patch`.
+pub(super) fn file_size(worktree: &Path, rel_path: &str) -> Option<u64> {
+    std::fs::metadata(worktree.join(rel_path))
+        .ok()
+        .map(|meta| meta.len())
+}
+
+pub(super) async fn generate_diff_patch(
+    worktree_path: &Path,
+    base_ref: &str,
+) -> std::result::Result<String, std::io::Error> {
+    let base_output = TokioCommand::new("git")
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "diff",
+            "--no-color",
+            "--patch",
+            base_ref,
+        ])
+        .output()
+        .await?;
+
+    if !base_output.status.success() {
+        let stderr = String::from_utf8_lossy(&base_output.stderr)
+            .trim()
+            .to_string();
+        return Err(std::io::Error::other(format!(
+            "git diff exited with non-zero status: {stderr}"
+        )));
+    }
+
+    let mut patch = String::from_utf8_lossy(&base_output.stdout).to_string();
+
+    let untracked_output = TokioCommand::new("git")
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+        ])
+        .output()
+        .await?;
+
+    if !untracked_output.status.success() {
+        let stderr = String::from_utf8_lossy(&untracked_output.stderr)
+            .trim()
+            .to_string();
+        return Err(std::io::Error::other(format!(
+            "git ls-files exited with non-zero status: {stderr}"
+        )));
+    }
+
+    for rel_path in String::from_utf8_lossy(&untracked_output.stdout).lines() {
+        let rel_path = rel_path.trim();
+        // Run artifacts live under `.hydra/`; only a primary checkout that
+        // does not ignore it would list them.
+        if rel_path.is_empty() || rel_path.starts_with(".hydra/") || rel_path == NOTES_FILE {
+            continue;
+        }
+
+        let output = TokioCommand::new("git")
+            .args([
+                "-C",
+                &worktree_path.to_string_lossy(),
+                "diff",
+                "--no-color",
+                "--patch",
+                "--no-index",
+                "--",
+                "/dev/null",
+                rel_path,
+            ])
+            .output()
+            .await?;
+
+        // `git diff --no-index` exits with status 1 when differences are present.
+        if !output.status.success() && output.status.code() != Some(1) {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(std::io::Error::other(format!(
+                "git diff --no-index exited with non-zero status: {stderr}"
+            )));
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        if !text.trim().is_empty() {
+            if !patch.is_empty() && !patch.ends_with('\n') {
+                patch.push('\n');
+            }
+            patch.push_str(&text);
+        }
+    }
+
+    Ok(patch)
+}
+
+fn should_cleanup_worktree(retain: RetentionPolicy, status: &RunStatus) -> bool {
+    match retain {
+        RetentionPolicy::None => true,
+        RetentionPolicy::Failed => matches!(status, RunStatus::Completed),
+        RetentionPolicy::All => false,
+    }
+}
+
+fn determine_overall_status(
+    any_completed: bool,
+    budget_stop_triggered: bool,
+    run_cancel_requested: bool,
+    had_task_panics: bool,
+    scoring_failed: bool,
+) -> RunStatus {
+    if had_task_panics || scoring_failed {
+        RunStatus::Failed
+    } else if run_cancel_requested {
+        RunStatus::Interrupted
+    } else if any_completed {
+        RunStatus::Completed
+    } else if budget_stop_triggered {
+        RunStatus::Interrupted
+    } else {
+        RunStatus::Failed
+    }
+}
+
+fn normalize_requested_agents(requested: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for key in requested {
+        let trimmed = key.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if seen.insert(trimmed.to_string()) {
+            out.push(trimmed.to_string());
+        }
+    }
+    out
+}
+
+fn default_tier1_keys(registry: &AdapterRegistry) -> Vec<String> {
+    registry
+        .tier1()
+        .into_iter()
+        .map(|a| a.key().to_string())
+        .collect()
+}
+
+struct AgentRunResult {
+    status: RunStatus,
+    error: Option<String>,
+    usage: CostEstimate,
+    /// Model the usage was priced as, when the agent did not report a cost.
+    priced_model: Option<String>,
+    usage_status: UsageStatus,
+    path_violations: Vec<PathViolation>,
+}
+
+/// The run's budget with `max_cost_usd` capped at what is left of the daily
+/// and monthly allowances in the cost ledger. Fails when either is used up.
+fn period_budget(
+    budget: &BudgetConfig,
+    ledger: &CostLedger,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<BudgetConfig> {
+    let mut run_budget = budget.clone();
+    for period in SpendPeriod::ALL {
+        let Some(limit) = period.limit(budget) else {
+            continue;
+        };
+        let spent = ledger.spend_since(period.start(now))?;
+        let remaining = limit - spent;
+        if remaining <= 0.0 {
+            return Err(OrchestratorError::SpendLimitReached {
+                period: period.as_str(),
+                spent,
+                limit,
+            });
+        }
+        run_budget.max_cost_usd = Some(
+            run_budget
+                .max_cost_usd
+                .map_or(remaining, |max| max.min(remaining)),
+        );
+    }
+    Ok(run_budget)
+}
+
+#[derive(Default)]
+struct SharedBudgetState {
+    total_tokens: AtomicU64,
+    stop_requested: AtomicBool,
+    total_cost_usd: Mutex<f64>,
+    stop_reason: Mutex<Option<String>>,
+    /// Highest `warn_at_percent` threshold already reported, per budget.
+    tokens_warned: AtomicU8,
+    cost_warned: AtomicU8,
+}
+
+impl SharedBudgetState {
+    fn should_stop(&self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+
+    async fn stop_reason(&self) -> Option<String> {
+        self.stop_reason.lock().await.clone()
+    }
+
+    async fn note_usage(
+        &self,
+        delta_tokens: u64,
+        delta_cost_usd: Option<f64>,
+        budget: &BudgetConfig,
+    ) -> Option<String> {
+        let new_total_tokens =
+            self.total_tokens.fetch_add(delta_tokens, Ordering::SeqCst) + delta_tokens;
+        if let Some(max_tokens) = budget.max_tokens_total {
+            if new_total_tokens >= max_tokens {
+                return self
+                    .trigger_stop(format!(
+                        "token budget exceeded: {} >= {}",
+                        new_total_tokens, max_tokens
+                    ))
+                    .await;
+            }
+        }
+
+        if let Some(cost_delta) = delta_cost_usd {
+            let mut total_cost = self.total_cost_usd.lock().await;
+            *total_cost += cost_delta;
+            if let Some(max_cost) = budget.max_cost_usd {
+                if *total_cost >= max_cost {
+                    return self
+                        .trigger_stop(format!(
+                            "cost budget exceeded: ${:.4} >= ${:.4}",
+                            *total_cost, max_cost
+                        ))
+                        .await;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Warnings for `budget.warn_at_percent` thresholds crossed since the
+    /// last call. Each is reported once per run, and only the highest when
+    /// several are crossed at once.
+    async fn new_warnings(&self, budget: &BudgetConfig) -> Vec<BudgetWarning> {
+        let mut warnings = Vec::new();
+        if let Some(max) = budget.max_tokens_total {
+            let used = self.total_tokens.load(Ordering::SeqCst) as f64;
+            warnings.extend(crossed_threshold(
+                &self.tokens_warned,
+                BudgetLimit::Tokens,
+                used,
+                max as f64,
+                &budget.warn_at_percent,
+            ));
+        }
+        if let Some(max) = budget.max_cost_usd {
+            let used = *self.total_cost_usd.lock().await;
+            warnings.extend(crossed_threshold(
+                &self.cost_warned,
+                BudgetLimit::CostUsd,
+                used,
+                max,
+                &budget.warn_at_percent,
+            ));
+        }
+        warnings
+    }
+
+    async fn trigger_stop(&self, reason: String) -> Option<String> {
+        if self
+            .stop_requested
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            let mut guard = self.stop_reason.lock().await;
+            *guard = Some(reason.clone());
+            Some(reason)
+        } else {
+            self.stop_reason().await
+        }
+    }
+}
+
+fn crossed_threshold(
+    warned: &AtomicU8,
+    limit: BudgetLimit,
+    used: f64,
+    max: f64,
+    thresholds: &[u8],
+) -> Option<BudgetWarning> {
+    if max <= 0.0 {
+        return None;
+    }
+    let percent_used = used / max * 100.0;
+    let percent = thresholds
+        .iter()
+        .copied()
+        .filter(|t| f64::from(*t) <= percent_used)
+        .max()?;
+    (warned.fetch_max(percent, Ordering::SeqCst) < percent).then_some(BudgetWarning {
+        limit,
+        percent,
+        used,
+        max,
+    })
+}
+
+/// Whether an agent should be stopped now that the budget is spent: at once
+/// between steps, or when `grace` runs out if it is in the middle of a tool
+/// call. Starts the grace period on the first call.
+fn budget_stop_due(mid_step: bool, grace_until: &mut Option<Instant>, grace: Duration) -> bool {
+    if !mid_step || grace.is_zero() {
+        return true;
+    }
+    let deadline = *grace_until.get_or_insert_with(|| {
+        tracing::info!(
+            grace_seconds = grace.as_secs(),
+            "budget spent; letting the agent finish its current tool call"
+        );
+        Instant::now() + grace
+    });
+    Instant::now() >= deadline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    use crate::security::ConsentError;
+
+    #[test]
+    fn sha256_short_matches_known_vector() {
+        assert_eq!(sha256_short("abc"), "ba7816bf8f01cfea");
+    }
+
+    #[test]
+    fn deterministic_run_id_depends_only_on_race_inputs() {
+        let config = HydraConfig::default();
+        let agents = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let id = deterministic_run_id("fix it", "abc123", &agents(&["codex", "claude"]), &config);
+
+        assert_eq!(id.get_version(), Some(uuid::Version::Custom));
+        assert_eq!(
+            id,
+            deterministic_run_id("fix it", "abc123", &agents(&["claude", "codex"]), &config)
+        );
+        assert_ne!(
+            id,
+            deterministic_run_id("fix it!", "abc123", &agents(&["claude", "codex"]), &config)
+        );
+        assert_ne!(
+            id,
+            deterministic_run_id("fix it", "def456", &agents(&["claude", "codex"]), &config)
+        );
+        assert_ne!(
+            id,
+            deterministic_run_id("fix it", "abc123", &agents(&["claude"]), &config)
+        );
+
+        let mut changed = HydraConfig::default();
+        changed.scoring.timeout_per_check_seconds += 1;
+        assert_ne!(
+            id,
+            deterministic_run_id("fix it", "abc123", &agents(&["claude", "codex"]), &changed)
+        );
+    }
+
+    #[test]
+    fn retention_policy_cleanup_behavior() {
+        assert!(should_cleanup_worktree(
+            RetentionPolicy::None,
+            &RunStatus::Completed
+        ));
+        assert!(should_cleanup_worktree(
+            RetentionPolicy::None,
+            &RunStatus::Failed
+        ));
+
+        assert!(should_cleanup_worktree(
+            RetentionPolicy::Failed,
+            &RunStatus::Completed
+        ));
+        assert!(!should_cleanup_worktree(
+            RetentionPolicy::Failed,
+            &RunStatus::Failed
+        ));
+
+        assert!(!should_cleanup_worktree(
+            RetentionPolicy::All,
+            &RunStatus::Completed
+        ));
+    }
+
+    #[test]
+    fn normalize_requested_agents_trims_and_deduplicates() {
+        let input = vec![
+            "claude".to_string(),
+            " codex ".to_string(),
+            "".to_string(),
+            "claude".to_string(),
+        ];
+        let normalized = normalize_requested_agents(&input);
+        assert_eq!(normalized, vec!["claude", "codex"]);
+    }
+
+    #[test]
+    fn overall_status_fails_when_any_task_panics() {
+        let status = determine_overall_status(
+            true,  // at least one agent completed
+            false, // budget stop not triggered
+            false, // run not cancelled
+            true,  // another agent panicked
+            false, // scoring succeeded
+        );
+        assert_eq!(status, RunStatus::Failed);
+    }
+
+    #[test]
+    fn overall_status_interrupted_when_run_cancelled() {
+        let status = determine_overall_status(
+            true,  // an agent finished before the cancel landed
+            false, // budget stop not triggered
+            true,  // `hydra cancel` targeted the whole run
+            false, // no panics
+            false, // scoring succeeded
+        );
+        assert_eq!(status, RunStatus::Interrupted);
+    }
+
+    #[test]
+    fn period_budget_caps_run_cost_at_remaining_allowance() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ledger = CostLedger::new(tmp.path());
+        let now: chrono::DateTime<chrono::Utc> = "2026-05-20T12:00:00Z".parse().unwrap();
+        ledger
+            .append(&[LedgerEntry {
+                timestamp: "2026-05-20T08:00:00Z".parse().unwrap(),
+                run_id: Uuid::new_v4(),
+                agent_key: "claude".to_string(),
+                model: None,
+                total_tokens: 10_000,
+                cost_usd: Some(7.0),
+            }])
+            .unwrap();
+
+        let budget = BudgetConfig {
+            max_cost_usd: Some(5.0),
+            max_cost_usd_per_day: Some(10.0),
+            max_cost_usd_per_month: Some(100.0),
+            ..BudgetConfig::default()
+        };
+        let run_budget = period_budget(&budget, &ledger, now).unwrap();
+        assert_eq!(run_budget.max_cost_usd, Some(3.0));
+
+        let budget = BudgetConfig {
+            max_cost_usd_per_day: Some(7.0),
+            ..BudgetConfig::default()
+        };
+        let err = period_budget(&budget, &ledger, now).unwrap_err();
+        assert!(matches!(
+            err,
+            OrchestratorError::SpendLimitReached {
+                period: "daily",
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn shared_budget_stops_on_token_limit() {
+        let state = Arc::new(SharedBudgetState::default());
+        let budget = BudgetConfig {
+            max_tokens_total: Some(100),
+            max_cost_usd: None,
+            ..BudgetConfig::default()
+        };
+
+        assert!(state.note_usage(50, None, &budget).await.is_none());
+        let reason = state.note_usage(60, None, &budget).await;
+        assert!(reason.is_some());
+        assert!(state.should_stop());
+    }
+
+    #[tokio::test]
+    async fn budget_warnings_fire_once_per_threshold() {
+        let state = SharedBudgetState::default();
+        let budget = BudgetConfig {
+            max_tokens_total: Some(1000),
+            max_cost_usd: Some(2.0),
+            warn_at_percent: vec![50, 80],
+            ..BudgetConfig::default()
+        };
+
+        assert!(state.note_usage(400, Some(0.5), &budget).await.is_none());
+        assert!(state.new_warnings(&budget).await.is_empty());
+
+        // Jumping past both thresholds reports only the higher one.
+        assert!(state.note_usage(450, Some(0.6), &budget).await.is_none());
+        let warnings = state.new_warnings(&budget).await;
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            (warnings[0].limit, warnings[0].percent),
+            (BudgetLimit::Tokens, 80)
+        );
+        assert_eq!(
+            (warnings[1].limit, warnings[1].percent),
+            (BudgetLimit::CostUsd, 50)
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "50% of the cost budget used ($1.1000 of $2.0000)"
+        );
+
+        assert!(state.note_usage(50, Some(0.1), &budget).await.is_none());
+        assert!(state.new_warnings(&budget).await.is_empty());
+    }
+
+    #[test]
+    fn cached_baseline_picks_latest_run_on_the_same_commit() {
+        let tmp = TempDir::new().unwrap();
+        let commands = ResolvedCommands {
+            build: Some("make".to_string()),
+            test: None,
+            lint: None,
+        };
+        let record = |commit: &str, command: &str, minutes_ago: i64| {
+            let run_id = Uuid::new_v4();
+            let layout = RunLayout::new(tmp.path(), run_id);
+            layout.create_dirs(&[]).unwrap();
+            let mut manifest = RunManifest::new(
+                run_id,
+                "/repo".to_string(),
+                "HEAD".to_string(),
+                "hash".to_string(),
+                Vec::new(),
+            )
+            .with_environment(
+                EnvironmentSnapshot::capture(&HydraConfig::default(), [])
+                    .with_base_commit(Some(commit.to_string())),
+            );
+            manifest.started_at = chrono::Utc::now() - chrono::Duration::minutes(minutes_ago);
+            manifest.write_to(&layout.manifest_path()).unwrap();
+            let baseline = BaselineResult {
+                build: Some(crate::scoring::baseline::CommandResult {
+                    command: command.to_string(),
+                    success: true,
+                    exit_code: 0,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    duration_ms: 1,
+                }),
+                test: None,
+                lint: None,
+            };
+            persist_baseline(&baseline, &layout.baseline_result(), &SecretRedactor::new()).unwrap();
+            run_id
+        };
+
+        assert!(cached_baseline(tmp.path(), "abc", &commands).is_none());
+        let older = record("abc", "make", 30);
+        let newer = record("abc", "make", 5);
+        record("abc", "cargo build", 1);
+        record("def", "make", 1);
+
+        let (from_run, baseline) = cached_baseline(tmp.path(), "abc", &commands).unwrap();
+        assert_eq!(from_run, newer);
+        assert_ne!(from_run, older);
+        assert!(baseline.ran_commands(&commands));
+    }
+
+    #[test]
+    fn budget_stop_lets_a_running_tool_call_finish() {
+        let grace = Duration::from_millis(50);
+        let mut grace_until = None;
+        assert!(budget_stop_due(false, &mut grace_until, grace));
+        assert!(budget_stop_due(true, &mut grace_until, Duration::ZERO));
+        assert!(grace_until.is_none());
+
+        assert!(!budget_stop_due(true, &mut grace_until, grace));
+        assert!(grace_until.is_some());
+        std::thread::sleep(grace);
+        assert!(budget_stop_due(true, &mut grace_until, grace));
+    }
+
+    #[test]
+    fn cancel_token_is_shared_between_clones() {
+        let token = CancelToken::new();
+        let ctx_token = token.clone();
+        assert!(!ctx_token.is_cancelled());
+        token.cancel();
+        assert!(ctx_token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn approval_gate_delivers_each_decision_once() {
+        let gate = ApprovalGate::new();
+        let (id, decision) = gate.open();
+        assert_eq!(gate.pending(), vec![id]);
+
+        assert!(gate.clone().resolve(id, ApprovalDecision::Approve));
+        assert_eq!(decision.await.unwrap(), ApprovalDecision::Approve);
+        assert!(!gate.resolve(id, ApprovalDecision::Deny));
+
+        let (id, decision) = gate.open();
+        gate.withdraw(id);
+        assert!(gate.pending().is_empty());
+        assert!(decision.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_adapter_is_rejected_before_any_artifacts_are_written() {
+        let tmp = TempDir::new().unwrap();
+        let orchestrator = Orchestrator::new(tmp.path(), HydraConfig::default());
+        let events: Arc<std::sync::Mutex<Vec<RaceEvent>>> = Arc::default();
+        let recorded = Arc::clone(&events);
+
+        let err = orchestrator
+            .race(
+                RaceOptions {
+                    agents: vec!["nope".to_string()],
+                    prompt: "task".to_string(),
+                    prompt_source: None,
+                    tags: Vec::new(),
+                    task: None,
+                    variants: Vec::new(),
+                    base_ref: "HEAD".to_string(),
+                    unsafe_mode: false,
+                    unsafe_consent: None,
+                    allow_experimental_adapters: false,
+                    run_id: None,
+                    deterministic: false,
+                    lock: LockMode::Fail,
+                    stop_on_first_success: false,
+                    skip_baseline: false,
+                    cancel: CancelToken::new(),
+                    approvals: ApprovalGate::new(),
+                    event_sinks: EventSinks::new(),
+                },
+                Arc::new(move |event| recorded.lock().unwrap().push(event)),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.root(), OrchestratorError::Registry(_)));
+        let context = err.context().unwrap();
+        assert!(context.run_id.is_some());
+        assert_eq!(context.phase, Some(ErrorPhase::Setup));
+        assert_eq!(context.agent_key, None);
+        assert!(events.lock().unwrap().is_empty());
+        assert!(!tmp.path().join(".hydra").exists());
+    }
+
+    #[tokio::test]
+    async fn unsafe_race_requires_live_consent() {
+        let tmp = TempDir::new().unwrap();
+        let orchestrator = Orchestrator::new(tmp.path(), HydraConfig::default());
+        let opts = RaceOptions {
+            agents: vec!["claude".to_string()],
+            prompt: "task".to_string(),
+            prompt_source: None,
+            tags: Vec::new(),
+            task: None,
+            variants: Vec::new(),
+            base_ref: "HEAD".to_string(),
+            unsafe_mode: true,
+            unsafe_consent: None,
+            allow_experimental_adapters: false,
+            run_id: None,
+            deterministic: false,
+            lock: LockMode::Fail,
+            stop_on_first_success: false,
+            skip_baseline: false,
+            cancel: CancelToken::new(),
+            approvals: ApprovalGate::new(),
+            event_sinks: EventSinks::new(),
+        };
+
+        let err = orchestrator
+            .race(opts.clone(), Arc::new(|_: RaceEvent| {}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            OrchestratorError::UnsafeConsent(ConsentError::NotGranted)
+        ));
+
+        ConsentStore::new(&tmp.path().join(".hydra"))
+            .grant(chrono::Duration::minutes(10))
+            .unwrap();
+        let err = orchestrator
+            .race(
+                RaceOptions {
+                    unsafe_consent: Some("unsafe-other".to_string()),
+                    ..opts
+                },
+                Arc::new(|_: RaceEvent| {}),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            OrchestratorError::UnsafeConsent(ConsentError::TokenMismatch)
+        ));
+        assert!(!tmp.path().join(".hydra/runs").exists());
+    }
+
+    #[tokio::test]
+    async fn waiting_race_queues_until_the_repository_lock_is_released() {
+        let tmp = TempDir::new().unwrap();
+        let held = RepoLock::try_acquire(tmp.path(), Uuid::new_v4()).unwrap();
+        let holder = held.holder().clone();
+        let events: Arc<std::sync::Mutex<Vec<RaceEvent>>> = Arc::default();
+        let recorded = Arc::clone(&events);
+        let sink = move |event| recorded.lock().unwrap().push(event);
+        let opts = RaceOptions {
+            agents: Vec::new(),
+            prompt: "task".to_string(),
+            prompt_source: None,
+            tags: Vec::new(),
+            task: None,
+            variants: Vec::new(),
+            base_ref: "HEAD".to_string(),
+            unsafe_mode: false,
+            unsafe_consent: None,
+            allow_experimental_adapters: false,
+            run_id: None,
+            deterministic: false,
+            lock: LockMode::Fail,
+            stop_on_first_success: false,
+            skip_baseline: false,
+            cancel: CancelToken::new(),
+            approvals: ApprovalGate::new(),
+            event_sinks: EventSinks::new(),
+        };
+
+        let err = acquire_repo_lock(tmp.path(), Uuid::new_v4(), &opts, &sink)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            OrchestratorError::Artifact(ArtifactError::Locked { .. })
+        ));
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(400)).await;
+            drop(held);
+        });
+        let run_id = Uuid::new_v4();
+        let wait = RaceOptions {
+            lock: LockMode::Wait,
+            ..opts
+        };
+        let lock = acquire_repo_lock(tmp.path(), run_id, &wait, &sink)
+            .await
+            .unwrap();
+        release.await.unwrap();
+        assert_eq!(lock.holder().run_id, run_id);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], RaceEvent::Queued { holder: h } if *h == holder));
+    }
+
+    #[tokio::test]
+    async fn finished_agent_is_scored_on_its_own_for_early_stop() {
+        let tmp = TempDir::new().unwrap();
+        let layout = RunLayout::new(tmp.path(), Uuid::new_v4());
+        std::fs::create_dir_all(layout.agent_dir("claude")).unwrap();
+        let config = HydraConfig::default();
+        let baseline = BaselineResult {
+            build: None,
+            test: None,
+            lint: None,
+        };
+        let outcome = AgentRunResult {
+            status: RunStatus::Completed,
+            error: None,
+            usage: UsageAccumulator::default().to_estimate(),
+            priced_model: None,
+            usage_status: UsageStatus::Unavailable,
+            path_violations: Vec::new(),
+        };
+
+        for (build, mergeable) in [("true", true), ("false", false)] {
+            let commands = ResolvedCommands {
+                build: Some(build.to_string()),
+                test: None,
+                lint: None,
+            };
+            let ctx = ScoreRunCtx {
+                layout: &layout,
+                base_ref: "HEAD",
+                config: &config,
+                baseline: &baseline,
+                commands: &commands,
+                redactor: &SecretRedactor::from_config(&config.security.redaction),
+            };
+            let (dimensions, score) = score_finished_agent(
+                &ctx,
+                "claude",
+                Some(tmp.path()),
+                &outcome,
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+            assert_eq!(score.mergeable, mergeable, "build `{build}`");
+            // Speed is added again when the run is ranked as a whole.
+            assert!(dimensions.iter().all(|d| d.name != "speed"));
+            let speed = score.dimensions.iter().find(|d| d.name == "speed").unwrap();
+            assert_eq!(speed.score, 100.0);
+        }
+    }
+
+    #[test]
+    fn variants_race_one_adapter_under_suffixed_keys() {
+        let registry = AdapterRegistry::from_config(&Default::default());
+        let agents = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let claude = registry.resolve_many(&agents(&["claude"]), false).unwrap();
+        let variant = |name: &str, prompt: &str| PromptVariant {
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+        };
+
+        let slots = agent_slots(&claude, &[]).unwrap();
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].key, "claude");
+        assert!(slots[0].variant.is_none());
+
+        let variants = [
+            variant("terse", "Fix it."),
+            variant("detailed", "Fix the bug in X."),
+        ];
+        let slots = agent_slots(&claude, &variants).unwrap();
+        let keys: Vec<&str> = slots.iter().map(|slot| slot.key.as_str()).collect();
+        assert_eq!(keys, ["claude-terse", "claude-detailed"]);
+        assert_eq!(
+            slots[1].variant.as_ref().unwrap().prompt,
+            "Fix the bug in X."
+        );
+
+        let both = registry
+            .resolve_many(&agents(&["claude", "codex"]), false)
+            .unwrap();
+        for (adapters, variants) in [
+            (&both, vec![variant("a", "x")]),
+            (&claude, vec![variant("a", "x"), variant("a", "y")]),
+            (&claude, vec![variant("a", " ")]),
+            (&claude, vec![variant("bad name", "x")]),
+        ] {
+            assert!(matches!(
+                agent_slots(adapters, &variants),
+                Err(OrchestratorError::InvalidVariants { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn timed_out_agents_are_scored_only_when_configured() {
+        let tmp = TempDir::new().unwrap();
+        let layout = RunLayout::new(tmp.path(), Uuid::new_v4());
+        layout.create_dirs(&["claude", "codex"]).unwrap();
+        let config = HydraConfig::default();
+        let baseline = BaselineResult {
+            build: None,
+            test: None,
+            lint: None,
+        };
+        let commands = ResolvedCommands {
+            build: None,
+            test: None,
+            lint: None,
+        };
+        let ctx = ScoreRunCtx {
+            layout: &layout,
+            base_ref: "HEAD",
+            config: &config,
+            baseline: &baseline,
+            commands: &commands,
+            redactor: &SecretRedactor::from_config(&config.security.redaction),
+        };
+        let worktrees: Vec<WorktreeInfo> = ["claude", "codex"]
+            .into_iter()
+            .map(|key| WorktreeInfo {
+                path: tmp.path().join(key),
+                branch: format!("hydra/test/agent/{key}"),
+                run_id: layout.run_id(),
+                agent_key: key.to_string(),
+            })
+            .collect();
+        let result = |status| AgentRunResult {
+            status,
+            error: None,
+            usage: UsageAccumulator::default().to_estimate(),
+            priced_model: None,
+            usage_status: UsageStatus::Unavailable,
+            path_violations: Vec::new(),
+        };
+        let results = vec![
+            (
+                "claude".to_string(),
+                Ok(result(RunStatus::Completed)),
+                Duration::from_secs(5),
+            ),
+            (
+                "codex".to_string(),
+                Ok(result(RunStatus::TimedOut)),
+                Duration::from_secs(9),
+            ),
+        ];
+
+        let (candidates, _) =
+            scoring_candidates(&worktrees, &results, config.scoring.score_timed_out);
+        let keys: Vec<&str> = candidates.iter().map(|wt| wt.agent_key.as_str()).collect();
+        assert_eq!(keys, vec!["claude"]);
+
+        let (candidates, timed_out) = scoring_candidates(&worktrees, &results, true);
+        assert_eq!(candidates.len(), 2);
+        let build = DimensionScore {
+            name: "build".to_string(),
+            score: 100.0,
+            evidence: serde_json::json!({}),
+        };
+        let evaluated = HashMap::from([
+            ("claude".to_string(), vec![build.clone()]),
+            ("codex".to_string(), vec![build]),
+        ]);
+        let ranked = score_agents(
+            &candidates,
+            &ctx,
+            &HashMap::new(),
+            &HashMap::new(),
+            evaluated,
+            &timed_out,
+        )
+        .await
+        .unwrap();
+        let codex = ranked.iter().find(|s| s.agent_key == "codex").unwrap();
+        assert!(codex.partial);
+    }
+
+    #[tokio::test]
+    async fn timed_out_candidates_are_flagged_partial() {
+        let tmp = TempDir::new().unwrap();
+        let layout = RunLayout::new(tmp.path(), Uuid::new_v4());
+        layout.create_dirs(&["claude", "codex"]).unwrap();
+        let config = HydraConfig::default();
+        let baseline = BaselineResult {
+            build: None,
+            test: None,
+            lint: None,
+        };
+        let commands = ResolvedCommands {
+            build: None,
+            test: None,
+            lint: None,
+        };
+        let ctx = ScoreRunCtx {
+            layout: &layout,
+            base_ref: "HEAD",
+            config: &config,
+            baseline: &baseline,
+            commands: &commands,
+            redactor: &SecretRedactor::from_config(&config.security.redaction),
+        };
+        let worktrees: Vec<WorktreeInfo> = ["claude", "codex"]
+            .into_iter()
+            .map(|key| WorktreeInfo {
+                path: tmp.path().join(key),
+                branch: format!("hydra/test/agent/{key}"),
+                run_id: layout.run_id(),
+                agent_key: key.to_string(),
+            })
+            .collect();
+        let build = DimensionScore {
+            name: "build".to_string(),
+            score: 100.0,
+            evidence: serde_json::json!({}),
+        };
+        let evaluated = HashMap::from([
+            ("claude".to_string(), vec![build.clone()]),
+            ("codex".to_string(), vec![build]),
+        ]);
+
+        let ranked = score_agents(
+            &worktrees.iter().collect::<Vec<_>>(),
+            &ctx,
+            &HashMap::new(),
+            &HashMap::new(),
+            evaluated,
+            &HashSet::from(["codex"]),
+        )
+        .await
+        .unwrap();
+
+        let partial: Vec<(&str, bool)> = ranked
+            .iter()
+            .map(|s| (s.agent_key.as_str(), s.partial))
+            .collect();
+        assert!(partial.contains(&("claude", false)));
+        assert!(partial.contains(&("codex", true)));
+        let stored: AgentScore =
+            serde_json::from_str(&std::fs::read_to_string(layout.agent_score("codex")).unwrap())
+                .unwrap();
+        assert!(stored.partial);
+    }
+
+    #[test]
+    fn collect_notes_moves_notes_out_of_the_worktree() {
+        let tmp = TempDir::new().unwrap();
+        let dest = tmp.path().join("notes.md");
+        let worktree = tmp.path().join("wt");
+        std::fs::create_dir(&worktree).unwrap();
+        assert!(collect_notes(&worktree, &dest, &SecretRedactor::new()).is_none());
+
+        std::fs::write(
+            worktree.join(NOTES_FILE),
+            "\n# Approach\nCached the lookup.\n",
+        )
+        .unwrap();
+        let notes = collect_notes(&worktree, &dest, &SecretRedactor::new()).unwrap();
+        assert_eq!(notes, "# Approach\nCached the lookup.");
+        assert!(!worktree.join(NOTES_FILE).exists());
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), notes);
+    }
+
+    #[tokio::test]
+    async fn generate_diff_patch_includes_uncommitted_new_file_changes() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+
+        fn git(repo: &Path, args: &[&str]) {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "git {:?} failed: {}",
+                args,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        git(repo, &["init"]);
+        git(repo, &["config", "user.email", "test@example.com"]);
+        git(repo, &["config", "user.name", "Test User"]);
+
+        std::fs::write(repo.join("README.md"), "base\n").unwrap();
+        git(repo, &["add", "README.md"]);
+        git(repo, &["commit", "-m", "init"]);
+
+        std::fs::write(repo.join("snake.py"), "print('snake')\n").unwrap();
+
+        let patch = generate_diff_patch(repo, "HEAD").await.unwrap();
+        assert!(patch.contains("diff --git a/snake.py b/snake.py"));
+        assert!(patch.contains("+print('snake')"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn repeated_deterministic_race_is_refused_before_setup() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&repo)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(&["init", "--quiet"]);
+        std::fs::write(repo.join("README.md"), "base\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "init"]);
+        let head = git(&["rev-parse", "HEAD"]);
+
+        // Passes detection and exits without output when run as an agent.
+        let claude = tmp.path().join("claude");
+        std::fs::write(
+            &claude,
+            "#!/bin/sh\ncase \"$1\" in\n  --help) echo \"-p --output-format --permission-mode\" ;;\n  --version) echo 1.0.0 ;;\nesac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&claude, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut config = HydraConfig::default();
+        config.adapters.claude = Some(claude.display().to_string());
+
+        let orchestrator = Orchestrator::new(&repo, config.clone());
+        let race = || {
+            orchestrator.race(
+                RaceOptions {
+                    agents: vec!["claude".to_string()],
+                    prompt: "task".to_string(),
+                    prompt_source: None,
+                    tags: Vec::new(),
+                    task: None,
+                    variants: Vec::new(),
+                    base_ref: "HEAD".to_string(),
+                    unsafe_mode: false,
+                    unsafe_consent: None,
+                    allow_experimental_adapters: false,
+                    run_id: None,
+                    deterministic: true,
+                    lock: LockMode::Fail,
+                    stop_on_first_success: false,
+                    skip_baseline: false,
+                    cancel: CancelToken::new(),
+                    approvals: ApprovalGate::new(),
+                    event_sinks: EventSinks::new(),
+                },
+                Arc::new(|_: RaceEvent| {}),
+            )
+        };
+        let run_id = deterministic_run_id("task", &head, &["claude".to_string()], &config);
+        let layout = RunLayout::new(&repo.join(".hydra"), run_id);
+
+        // A setup that fails part way leaves no run directory behind.
+        let blocker = repo
+            .join(&config.worktree.base_dir)
+            .join(run_id.to_string())
+            .join("claude");
+        std::fs::create_dir_all(&blocker).unwrap();
+        let err = race().await.unwrap_err();
+        assert!(matches!(err.root(), OrchestratorError::Worktree { .. }));
+        assert!(!layout.base_dir().exists());
+        std::fs::remove_dir(&blocker).unwrap();
+
+        let outcome = race().await.unwrap();
+        assert_eq!(outcome.run_id, run_id);
+
+        let err = race().await.unwrap_err();
+        assert!(matches!(
+            err.root(),
+            OrchestratorError::RunExists { run_id: existing } if *existing == run_id
+        ));
+        assert!(err.to_string().contains(&format!("hydra run rm {run_id}")));
+        assert!(layout.manifest_path().exists());
+        assert!(!repo.join(".hydra").join("lock").exists());
+    }
+}
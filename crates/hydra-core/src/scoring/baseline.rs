@@ -6,6 +6,7 @@ use thiserror::Error;
 use tokio::io::AsyncReadExt;
 
 use crate::config::{ScoringConfig, ScoringProfile};
+use crate::security::SecretRedactor;
 
 #[derive(Debug, Error)]
 pub enum BaselineError {
@@ -52,6 +53,24 @@ pub struct BaselineResult {
     pub lint: Option<LintResult>,
 }
 
+impl BaselineResult {
+    /// Whether this baseline was captured with exactly `commands`, so it can
+    /// stand in for a fresh capture on the same commit.
+    pub fn ran_commands(&self, commands: &ResolvedCommands) -> bool {
+        self.build.as_ref().map(|b| b.command.as_str()) == commands.build.as_deref()
+            && self
+                .test
+                .as_ref()
+                .map(|t| t.command_result.command.as_str())
+                == commands.test.as_deref()
+            && self
+                .lint
+                .as_ref()
+                .map(|l| l.command_result.command.as_str())
+                == commands.lint.as_deref()
+    }
+}
+
 /// Resolve commands from profile + explicit overrides.
 pub fn resolve_commands(config: &ScoringConfig) -> ResolvedCommands {
     let profile_cmds = config.profile.map(profile_defaults);
@@ -73,7 +92,7 @@ pub fn resolve_commands(config: &ScoringConfig) -> ResolvedCommands {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ResolvedCommands {
     pub build: Option<String>,
     pub test: Option<String>,
@@ -330,12 +349,19 @@ pub async fn capture_baseline(
     Ok(BaselineResult { build, test, lint })
 }
 
-/// Persist baseline results as a JSON artifact.
-pub fn persist_baseline(result: &BaselineResult, path: &Path) -> Result<(), std::io::Error> {
+/// Persist baseline results as a JSON artifact, redacting secrets in the
+/// captured command output.
+pub fn persist_baseline(
+    result: &BaselineResult,
+    path: &Path,
+    redactor: &SecretRedactor,
+) -> Result<(), std::io::Error> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    let json = serde_json::to_string_pretty(result).map_err(std::io::Error::other)?;
+    let json = redactor
+        .redact_json(result)
+        .map_err(std::io::Error::other)?;
     std::fs::write(path, json)
 }
 
@@ -548,9 +574,37 @@ mod tests {
         };
         let tmp = tempfile::TempDir::new().unwrap();
         let path = tmp.path().join("baseline.json");
-        persist_baseline(&result, &path).unwrap();
+        persist_baseline(&result, &path, &SecretRedactor::new()).unwrap();
         let data = std::fs::read_to_string(&path).unwrap();
         let loaded: BaselineResult = serde_json::from_str(&data).unwrap();
         assert!(loaded.build.unwrap().success);
     }
+
+    #[test]
+    fn ran_commands_requires_the_same_commands() {
+        let result = BaselineResult {
+            build: Some(CommandResult {
+                command: "cargo build".to_string(),
+                success: true,
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                duration_ms: 10,
+            }),
+            test: None,
+            lint: None,
+        };
+        let mut commands = ResolvedCommands {
+            build: Some("cargo build".to_string()),
+            test: None,
+            lint: None,
+        };
+        assert!(result.ran_commands(&commands));
+
+        commands.test = Some("cargo test".to_string());
+        assert!(!result.ran_commands(&commands));
+        commands.test = None;
+        commands.build = Some("make".to_string());
+        assert!(!result.ran_commands(&commands));
+    }
 }
@@ -0,0 +1,338 @@
+//! Build cache seeding: after the baseline has been built in the first
+//! worktree, its build outputs (`target/`, `node_modules`, `.venv`) are
+//! cloned into each candidate worktree so scoring starts warm.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::{BuildCacheConfig, BuildCacheMode};
+use crate::git_exec::run_git;
+
+/// Files this small that embed the source worktree's path are rewritten
+/// instead of cloned: virtualenv scripts, `.pth` files and `pyvenv.cfg`.
+const MAX_REWRITE_BYTES: u64 = 64 * 1024;
+
+/// What was carried into one candidate worktree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BuildCacheSeed {
+    /// Directories seeded, relative to the worktree root.
+    pub dirs: Vec<String>,
+    pub files: u64,
+    pub bytes: u64,
+    /// Files shared as copy-on-write clones.
+    pub cloned: u64,
+    /// Files shared as hard links.
+    pub linked: u64,
+    /// Files copied byte for byte.
+    pub copied: u64,
+    /// Files copied with the source worktree's path replaced.
+    pub rewritten: u64,
+}
+
+/// Seed `dest`'s build outputs from `source` under `config`. A directory is
+/// seeded only when it exists in `source`, is missing in `dest`, and git
+/// ignores it in `dest`. A directory that fails part way is removed again so
+/// the candidate builds cold rather than from half a cache.
+pub async fn seed_build_cache(
+    source: &Path,
+    dest: &Path,
+    config: &BuildCacheConfig,
+) -> io::Result<BuildCacheSeed> {
+    let mut seeder = Seeder::new(config.mode, source, dest);
+    if config.mode == BuildCacheMode::Off {
+        return Ok(seeder.report);
+    }
+    for dir in &config.dirs {
+        let from = source.join(dir);
+        let to = dest.join(dir);
+        if !fs::symlink_metadata(&from).is_ok_and(|m| m.is_dir())
+            || fs::symlink_metadata(&to).is_ok()
+        {
+            continue;
+        }
+        if run_git(&["check-ignore", "-q", dir], dest).await.is_err() {
+            tracing::debug!(dir, "not seeding a directory git does not ignore");
+            continue;
+        }
+        seeder = tokio::task::spawn_blocking(move || {
+            let result = seeder.seed_dir(&from, &to);
+            (seeder, result)
+        })
+        .await
+        .map_err(io::Error::other)
+        .and_then(|(mut seeder, result)| match result {
+            Ok(()) => {
+                seeder.report.dirs.push(dir.clone());
+                Ok(seeder)
+            }
+            Err(e) => {
+                let _ = fs::remove_dir_all(dest.join(dir));
+                Err(e)
+            }
+        })?;
+    }
+    Ok(seeder.report)
+}
+
+struct Seeder {
+    mode: BuildCacheMode,
+    source_root: PathBuf,
+    dest_root: PathBuf,
+    /// Cleared after the first failed clone or link; the rest are copied.
+    share: bool,
+    report: BuildCacheSeed,
+}
+
+impl Seeder {
+    fn new(mode: BuildCacheMode, source_root: &Path, dest_root: &Path) -> Self {
+        Self {
+            mode,
+            source_root: source_root.to_path_buf(),
+            dest_root: dest_root.to_path_buf(),
+            share: true,
+            report: BuildCacheSeed::default(),
+        }
+    }
+
+    fn seed_dir(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let (from, to) = (entry.path(), to.join(entry.file_name()));
+            if file_type.is_dir() {
+                self.seed_dir(&from, &to)?;
+            } else if file_type.is_symlink() {
+                self.seed_symlink(&from, &to)?;
+            } else if file_type.is_file() {
+                self.seed_file(&from, &to)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Recreate a link, pointing absolute targets inside the source worktree
+    /// at the same place in the destination.
+    #[cfg(unix)]
+    fn seed_symlink(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        let target = fs::read_link(from)?;
+        let target = match target.strip_prefix(&self.source_root) {
+            Ok(rest) => self.dest_root.join(rest),
+            Err(_) => target,
+        };
+        std::os::unix::fs::symlink(target, to)
+    }
+
+    #[cfg(not(unix))]
+    fn seed_symlink(&mut self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn seed_file(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        let len = fs::metadata(from)?.len();
+        self.report.files += 1;
+        self.report.bytes += len;
+
+        if len <= MAX_REWRITE_BYTES && may_embed_paths(from) && self.rewrite(from, to)? {
+            self.report.rewritten += 1;
+            return Ok(());
+        }
+        if self.share {
+            let shared = match self.mode {
+                BuildCacheMode::Clone => clone_file(from, to),
+                BuildCacheMode::Hardlink => fs::hard_link(from, to),
+                BuildCacheMode::Off => Err(io::ErrorKind::Unsupported.into()),
+            };
+            match shared {
+                Ok(()) if self.mode == BuildCacheMode::Hardlink => {
+                    self.report.linked += 1;
+                    return Ok(());
+                }
+                Ok(()) => {
+                    self.report.cloned += 1;
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::debug!(error = %e, "falling back to copying build outputs");
+                    self.share = false;
+                    let _ = fs::remove_file(to);
+                }
+            }
+        }
+        fs::copy(from, to)?;
+        self.report.copied += 1;
+        Ok(())
+    }
+
+    /// Copy `from` with the source worktree's path replaced by the
+    /// destination's. `false` when the file does not mention it.
+    fn rewrite(&self, from: &Path, to: &Path) -> io::Result<bool> {
+        let Ok(text) = fs::read_to_string(from) else {
+            return Ok(false);
+        };
+        let source_root = self.source_root.to_string_lossy();
+        if !text.contains(source_root.as_ref()) {
+            return Ok(false);
+        }
+        fs::write(
+            to,
+            text.replace(source_root.as_ref(), &self.dest_root.to_string_lossy()),
+        )?;
+        fs::set_permissions(to, fs::metadata(from)?.permissions())?;
+        Ok(true)
+    }
+}
+
+/// Files tools write absolute paths into: virtualenv launchers and
+/// configuration, and Python path files.
+fn may_embed_paths(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let parent = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    name == "pyvenv.cfg"
+        || name.ends_with(".pth")
+        || name.ends_with(".egg-link")
+        || parent == "bin"
+        || parent == "Scripts"
+}
+
+/// Copy-on-write clone of `from` at `to`, which must not exist.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let src = fs::File::open(from)?;
+    let dst = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)?;
+    if unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE, src.as_raw_fd()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    dst.set_permissions(src.metadata()?.permissions())
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+    if unsafe { libc::clonefile(from.as_ptr(), to.as_ptr(), 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+fn clone_file(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn repo(path: &Path) {
+        fs::create_dir_all(path).unwrap();
+        run_git(&["init", "-q"], path).await.unwrap();
+        fs::write(path.join(".gitignore"), "/target\n/.venv\n").unwrap();
+    }
+
+    fn config(mode: BuildCacheMode) -> BuildCacheConfig {
+        BuildCacheConfig {
+            mode,
+            dirs: vec!["target".into(), ".venv".into(), "src".into()],
+        }
+    }
+
+    #[tokio::test]
+    async fn seeds_ignored_build_outputs_only() {
+        let tmp = TempDir::new().unwrap();
+        let (source, dest) = (tmp.path().join("a"), tmp.path().join("b"));
+        repo(&source).await;
+        repo(&dest).await;
+        fs::create_dir_all(source.join("target/debug/deps")).unwrap();
+        fs::write(source.join("target/debug/deps/libfoo.rlib"), b"rlib").unwrap();
+        fs::create_dir_all(source.join("src")).unwrap();
+        fs::write(source.join("src/lib.rs"), "").unwrap();
+        fs::create_dir_all(source.join(".venv/bin")).unwrap();
+        let launcher = format!("#!{}/.venv/bin/python\n", source.display());
+        fs::write(source.join(".venv/bin/pytest"), launcher).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(
+            source.join(".venv/bin/pytest"),
+            source.join(".venv/bin/py.test"),
+        )
+        .unwrap();
+
+        let seed = seed_build_cache(&source, &dest, &config(BuildCacheMode::Clone))
+            .await
+            .unwrap();
+        assert_eq!(seed.dirs, vec!["target", ".venv"]);
+        assert_eq!(seed.files, 2);
+        assert_eq!(seed.rewritten, 1);
+        assert_eq!(seed.cloned + seed.copied, 1);
+        assert_eq!(
+            fs::read(dest.join("target/debug/deps/libfoo.rlib")).unwrap(),
+            b"rlib"
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join(".venv/bin/pytest")).unwrap(),
+            format!("#!{}/.venv/bin/python\n", dest.display())
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(dest.join(".venv/bin/py.test")).unwrap(),
+            dest.join(".venv/bin/pytest")
+        );
+        assert!(!dest.join("src").exists());
+
+        // Existing directories are left alone.
+        let again = seed_build_cache(&source, &dest, &config(BuildCacheMode::Clone))
+            .await
+            .unwrap();
+        assert_eq!(again, BuildCacheSeed::default());
+    }
+
+    #[tokio::test]
+    async fn hardlink_mode_shares_files() {
+        let tmp = TempDir::new().unwrap();
+        let (source, dest) = (tmp.path().join("a"), tmp.path().join("b"));
+        repo(&source).await;
+        repo(&dest).await;
+        fs::create_dir_all(source.join("target")).unwrap();
+        fs::write(source.join("target/out"), b"x").unwrap();
+
+        let seed = seed_build_cache(&source, &dest, &config(BuildCacheMode::Hardlink))
+            .await
+            .unwrap();
+        assert_eq!((seed.linked, seed.copied), (1, 0));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(
+                fs::metadata(source.join("target/out")).unwrap().ino(),
+                fs::metadata(dest.join("target/out")).unwrap().ino()
+            );
+        }
+
+        let off = seed_build_cache(&source, &tmp.path().join("c"), &config(BuildCacheMode::Off))
+            .await
+            .unwrap();
+        assert_eq!(off, BuildCacheSeed::default());
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::adapter::AgentEvent;
+use crate::config::ModelPrice;
 
 /// Accumulates token usage from agent event streams.
 #[derive(Debug, Clone, Default)]
@@ -9,7 +10,11 @@ pub struct UsageAccumulator {
     pub output_tokens: u64,
     pub usage_events: u64,
     pub reported_cost_usd: f64,
+    /// Cost of usage events that carried no `cost_usd`, from the price table.
+    pub priced_cost_usd: f64,
+    price: Option<ModelPrice>,
     has_reported_cost: bool,
+    has_priced_cost: bool,
 }
 
 impl UsageAccumulator {
@@ -17,22 +22,41 @@ impl UsageAccumulator {
         Self::default()
     }
 
-    /// Process an agent event, accumulating usage if present.
-    pub fn process_event(&mut self, event: &AgentEvent) {
-        if let AgentEvent::Usage {
+    /// Price usage events that report tokens but not cost at `price`.
+    pub fn with_price(price: ModelPrice) -> Self {
+        Self {
+            price: Some(price),
+            ..Self::default()
+        }
+    }
+
+    /// Process an agent event, accumulating usage if present. Returns the
+    /// event's cost: the one it reports, else its priced cost.
+    pub fn process_event(&mut self, event: &AgentEvent) -> Option<f64> {
+        let AgentEvent::Usage {
             input_tokens,
             output_tokens,
             extra,
         } = event
-        {
-            self.input_tokens += input_tokens;
-            self.output_tokens += output_tokens;
-            self.usage_events += 1;
-            if let Some(cost) = extra.get("cost_usd").and_then(|v| v.as_f64()) {
-                self.reported_cost_usd += cost;
-                self.has_reported_cost = true;
-            }
+        else {
+            return None;
+        };
+        self.input_tokens += input_tokens;
+        self.output_tokens += output_tokens;
+        self.usage_events += 1;
+        if let Some(cost) = extra.get("cost_usd").and_then(|v| v.as_f64()) {
+            self.reported_cost_usd += cost;
+            self.has_reported_cost = true;
+            return Some(cost);
         }
+        let cached = extra
+            .get("cached_input_tokens")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let cost = self.price?.cost_usd(*input_tokens, *output_tokens, cached);
+        self.priced_cost_usd += cost;
+        self.has_priced_cost = true;
+        Some(cost)
     }
 
     pub fn total_tokens(&self) -> u64 {
@@ -48,7 +72,8 @@ impl UsageAccumulator {
             input_tokens: self.input_tokens,
             output_tokens: self.output_tokens,
             total_tokens: self.total_tokens(),
-            estimated_cost_usd: self.has_reported_cost.then_some(self.reported_cost_usd),
+            estimated_cost_usd: (self.has_reported_cost || self.has_priced_cost)
+                .then_some(self.reported_cost_usd + self.priced_cost_usd),
         }
     }
 }
@@ -143,6 +168,7 @@ mod tests {
             usage_events: 1,
             reported_cost_usd: 0.0,
             has_reported_cost: false,
+            ..UsageAccumulator::default()
         };
         assert_eq!(check_budget(&acc, Some(1000), None), BudgetAction::Continue);
     }
@@ -155,6 +181,7 @@ mod tests {
             usage_events: 1,
             reported_cost_usd: 0.0,
             has_reported_cost: false,
+            ..UsageAccumulator::default()
         };
         let action = check_budget(&acc, Some(1000), None);
         assert!(matches!(action, BudgetAction::Stop { .. }));
@@ -168,6 +195,7 @@ mod tests {
             usage_events: 2,
             reported_cost_usd: 0.0,
             has_reported_cost: false,
+            ..UsageAccumulator::default()
         };
         assert_eq!(check_budget(&acc, None, None), BudgetAction::Continue);
     }
@@ -180,6 +208,7 @@ mod tests {
             usage_events: 1,
             reported_cost_usd: 0.0,
             has_reported_cost: false,
+            ..UsageAccumulator::default()
         };
         let est = acc.to_estimate();
         assert_eq!(est.total_tokens, 150);
@@ -200,4 +229,36 @@ mod tests {
         let action = check_budget(&acc, None, Some(0.5));
         assert!(matches!(action, BudgetAction::Stop { .. }));
     }
+
+    #[test]
+    fn priced_cost_fills_in_when_usage_reports_no_cost() {
+        let mut acc = UsageAccumulator::with_price(ModelPrice {
+            input_per_mtok: 1.0,
+            output_per_mtok: 4.0,
+            cached_input_per_mtok: Some(0.1),
+        });
+        let mut cached = HashMap::new();
+        cached.insert(
+            "cached_input_tokens".to_string(),
+            serde_json::json!(500_000),
+        );
+        let priced = acc.process_event(&AgentEvent::Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 250_000,
+            extra: cached,
+        });
+        assert!((priced.unwrap() - 1.55).abs() < 1e-9);
+
+        let mut reported = HashMap::new();
+        reported.insert("cost_usd".to_string(), serde_json::json!(0.25));
+        let reported_cost = acc.process_event(&AgentEvent::Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 0,
+            extra: reported,
+        });
+        assert_eq!(reported_cost, Some(0.25));
+
+        let est = acc.to_estimate().estimated_cost_usd.unwrap();
+        assert!((est - 1.8).abs() < 1e-9);
+    }
 }
@@ -42,6 +42,7 @@ pub fn parse_numstat(output: &str) -> DiffStats {
 /// - Modest churn scores highest
 /// - Broad unrelated edits penalized
 /// - Out-of-scope path edits trigger hard penalty (cap at 30)
+/// - Edits outside `relevant_paths`, when set, scale the score down to half
 pub fn score_diff_scope(stats: &DiffStats, config: &DiffScopeConfig) -> DimensionScore {
     let churn = stats.total_churn() as f64;
     let files = stats.files_changed as f64;
@@ -72,7 +73,23 @@ pub fn score_diff_scope(stats: &DiffStats, config: &DiffScopeConfig) -> Dimensio
             .iter()
             .any(|p| config.protected_paths.iter().any(|pp| p.starts_with(pp)));
 
-    let raw_score = (churn_score * 0.5 + files_score * 0.5).min(100.0);
+    // Relevance check: the share of changed files inside relevant_paths
+    let out_of_scope_files = if config.relevant_paths.is_empty() {
+        0
+    } else {
+        stats
+            .paths
+            .iter()
+            .filter(|p| !config.relevant_paths.iter().any(|rp| p.starts_with(rp)))
+            .count()
+    };
+    let relevance = if stats.paths.is_empty() {
+        1.0
+    } else {
+        1.0 - out_of_scope_files as f64 / stats.paths.len() as f64
+    };
+
+    let raw_score = (churn_score * 0.5 + files_score * 0.5).min(100.0) * (0.5 + 0.5 * relevance);
 
     let score = if protected_violation {
         raw_score.min(30.0)
@@ -91,10 +108,52 @@ pub fn score_diff_scope(stats: &DiffStats, config: &DiffScopeConfig) -> Dimensio
             "churn_score": churn_score,
             "files_score": files_score,
             "protected_violation": protected_violation,
+            "out_of_scope_files": out_of_scope_files,
         }),
     }
 }
 
+/// Diff-scope signals available while an agent is still running, from the
+/// files it reports editing rather than the final diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopePrecheck {
+    pub files: u32,
+    pub churn: u32,
+    pub over_max_files: bool,
+    pub over_max_churn: bool,
+    /// Edited paths under `protected_paths`; any caps the score at 30.
+    pub protected: Vec<String>,
+    /// Edited paths outside `relevant_paths`, when those are set.
+    pub out_of_scope: Vec<String>,
+}
+
+/// Check the paths edited so far, and their churn, against `config` the way
+/// [`score_diff_scope`] will check the final diff.
+pub fn precheck_diff_scope<'a>(
+    paths: impl IntoIterator<Item = &'a str>,
+    churn: u32,
+    config: &DiffScopeConfig,
+) -> ScopePrecheck {
+    let mut check = ScopePrecheck {
+        churn,
+        over_max_churn: config.max_churn_soft > 0 && churn > config.max_churn_soft,
+        ..ScopePrecheck::default()
+    };
+    for path in paths {
+        check.files += 1;
+        if config.protected_paths.iter().any(|pp| path.starts_with(pp)) {
+            check.protected.push(path.to_string());
+        }
+        if !config.relevant_paths.is_empty()
+            && !config.relevant_paths.iter().any(|rp| path.starts_with(rp))
+        {
+            check.out_of_scope.push(path.to_string());
+        }
+    }
+    check.over_max_files = config.max_files_soft > 0 && check.files > config.max_files_soft;
+    check
+}
+
 /// Compute diff stats by running git in the given worktree.
 pub async fn compute_diff_stats(
     worktree_path: &std::path::Path,
@@ -148,7 +207,7 @@ pub async fn compute_diff_stats(
     Ok(stats)
 }
 
-async fn list_untracked_files(
+pub(crate) async fn list_untracked_files(
     worktree_path: &std::path::Path,
 ) -> Result<Vec<String>, std::io::Error> {
     let output = tokio::process::Command::new("git")
@@ -191,6 +250,7 @@ mod tests {
             max_files_soft: 20,
             max_churn_soft: 800,
             protected_paths: vec![],
+            relevant_paths: vec![],
         }
     }
 
@@ -204,6 +264,21 @@ mod tests {
         assert_eq!(stats.paths, vec!["src/main.rs", "src/lib.rs"]);
     }
 
+    #[test]
+    fn precheck_flags_protected_and_out_of_scope_paths() {
+        let config = DiffScopeConfig {
+            max_files_soft: 2,
+            protected_paths: vec!["infra/".into()],
+            relevant_paths: vec!["src/".into()],
+            ..default_config()
+        };
+        let check = precheck_diff_scope(["src/a.rs", "infra/main.tf", "README.md"], 900, &config);
+        assert_eq!(check.files, 3);
+        assert!(check.over_max_files && check.over_max_churn);
+        assert_eq!(check.protected, vec!["infra/main.tf"]);
+        assert_eq!(check.out_of_scope, vec!["infra/main.tf", "README.md"]);
+    }
+
     #[test]
     fn parse_numstat_empty() {
         let stats = parse_numstat("");
@@ -277,6 +352,28 @@ mod tests {
         assert!((score.score - 100.0).abs() < 0.01);
     }
 
+    #[test]
+    fn edits_outside_relevant_paths_lower_score() {
+        let mut config = default_config();
+        config.relevant_paths = vec!["src/".to_string()];
+        let stats = DiffStats {
+            files_changed: 2,
+            lines_added: 10,
+            lines_removed: 5,
+            paths: vec!["src/main.rs".into(), "docs/notes.md".into()],
+        };
+        let score = score_diff_scope(&stats, &config);
+        assert!((score.score - 75.0).abs() < 0.01);
+        assert_eq!(score.evidence["out_of_scope_files"], 1);
+
+        let stats = DiffStats {
+            paths: vec!["src/main.rs".into(), "src/lib.rs".into()],
+            ..stats
+        };
+        let score = score_diff_scope(&stats, &config);
+        assert!((score.score - 100.0).abs() < 0.01);
+    }
+
     #[test]
     fn empty_diff_scores_100() {
         let config = default_config();
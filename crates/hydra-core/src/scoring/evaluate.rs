@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use tracing::Instrument;
+
+use crate::config::HydraConfig;
+use crate::security::{PathRules, SecretRedactor};
+
+use super::baseline::{
+    parse_lint_output, parse_test_output, run_command, BaselineResult, CommandResult,
+    ResolvedCommands,
+};
+use super::build::score_build;
+use super::diff_scope::{compute_diff_stats, score_diff_scope};
+use super::lint::score_lint;
+use super::tests::score_tests;
+use super::write_policy::{enforce_write_policy, score_write_policy};
+use super::DimensionScore;
+
+/// Score a candidate checkout against the baseline, writing command logs to
+/// `log_dir`.
+///
+/// Command failures become zero-score dimensions; only failing to write a
+/// log is an error.
+pub async fn evaluate_agent_dimensions(
+    candidate_path: &Path,
+    log_dir: &Path,
+    base_ref: &str,
+    config: &HydraConfig,
+    baseline: &BaselineResult,
+    commands: &ResolvedCommands,
+) -> Result<Vec<DimensionScore>, std::io::Error> {
+    let mut dimensions = Vec::new();
+    let timeout = config.scoring.timeout_per_check_seconds;
+    let redactor = SecretRedactor::from_config(&config.security.redaction);
+
+    // Runs first so reverted files are not built, tested or counted as churn.
+    let write_policy = &config.security.write_policy;
+    let rules = PathRules::from_config(write_policy);
+    if !rules.is_empty() {
+        match enforce_write_policy(
+            candidate_path,
+            base_ref,
+            &rules,
+            write_policy.revert_violations,
+        )
+        .instrument(tracing::info_span!("scoring.check", check = "write_policy"))
+        .await
+        {
+            Ok(report) => dimensions.push(score_write_policy(&report)),
+            Err(err) => dimensions.push(failed_dimension("write_policy", None, &err.to_string())),
+        }
+    }
+
+    if let Some(build_cmd) = commands.build.as_deref() {
+        match run_command(build_cmd, candidate_path, timeout)
+            .instrument(check_span("build", build_cmd))
+            .await
+        {
+            Ok(build_result) => {
+                let build_log = log_dir.join("build.log");
+                write_command_artifact(&build_log, &build_result, &redactor)?;
+                let mut dim = score_build(baseline.build.as_ref(), &build_result);
+                dim.evidence["artifact"] =
+                    serde_json::Value::String(build_log.display().to_string());
+                dimensions.push(dim);
+            }
+            Err(err) => {
+                dimensions.push(failed_dimension("build", Some(build_cmd), &err.to_string()))
+            }
+        }
+    }
+
+    if let Some(test_cmd) = commands.test.as_deref() {
+        match run_command(test_cmd, candidate_path, timeout)
+            .instrument(check_span("tests", test_cmd))
+            .await
+        {
+            Ok(test_result_raw) => {
+                let test_log = log_dir.join("test.log");
+                write_command_artifact(&test_log, &test_result_raw, &redactor)?;
+                let test_result = parse_test_output(&test_result_raw);
+                let mut dim = score_tests(baseline.test.as_ref(), &test_result);
+                dim.evidence["artifact"] =
+                    serde_json::Value::String(test_log.display().to_string());
+                dimensions.push(dim);
+            }
+            Err(err) => {
+                dimensions.push(failed_dimension("tests", Some(test_cmd), &err.to_string()))
+            }
+        }
+    }
+
+    if let Some(lint_cmd) = commands.lint.as_deref() {
+        match run_command(lint_cmd, candidate_path, timeout)
+            .instrument(check_span("lint", lint_cmd))
+            .await
+        {
+            Ok(lint_result_raw) => {
+                let lint_log = log_dir.join("lint.log");
+                write_command_artifact(&lint_log, &lint_result_raw, &redactor)?;
+                let lint_result = parse_lint_output(&lint_result_raw);
+                let mut dim = score_lint(baseline.lint.as_ref(), &lint_result);
+                dim.evidence["artifact"] =
+                    serde_json::Value::String(lint_log.display().to_string());
+                dimensions.push(dim);
+            }
+            Err(err) => dimensions.push(failed_dimension("lint", Some(lint_cmd), &err.to_string())),
+        }
+    }
+
+    match compute_diff_stats(candidate_path, base_ref)
+        .instrument(tracing::info_span!("scoring.check", check = "diff_scope"))
+        .await
+    {
+        Ok(stats) => dimensions.push(score_diff_scope(&stats, &config.scoring.diff_scope)),
+        Err(err) => dimensions.push(failed_dimension("diff_scope", None, &err.to_string())),
+    }
+
+    Ok(dimensions)
+}
+
+/// Write a command's exit status and output as a plain-text log, with
+/// secrets in the output redacted.
+pub fn write_command_artifact(
+    path: &Path,
+    result: &CommandResult,
+    redactor: &SecretRedactor,
+) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = format!(
+        "command: {}\nexit_code: {}\nsuccess: {}\nduration_ms: {}\n\n[stdout]\n{}\n\n[stderr]\n{}\n",
+        result.command,
+        result.exit_code,
+        result.success,
+        result.duration_ms,
+        result.stdout,
+        result.stderr
+    );
+    std::fs::write(path, redactor.redact(&content).value)
+}
+
+fn check_span(check: &str, command: &str) -> tracing::Span {
+    tracing::info_span!("scoring.check", check, command)
+}
+
+fn failed_dimension(name: &str, command: Option<&str>, error: &str) -> DimensionScore {
+    DimensionScore {
+        name: name.to_string(),
+        score: 0.0,
+        evidence: serde_json::json!({
+            "command": command,
+            "error": error,
+        }),
+    }
+}
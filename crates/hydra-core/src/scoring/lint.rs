@@ -34,6 +34,7 @@ pub fn score_lint(baseline: Option<&LintResult>, agent_result: &LintResult) -> D
             "new_errors": new_errors,
             "new_warnings": new_warnings,
             "resolved": resolved,
+            "duration_ms": agent_result.command_result.duration_ms,
         }),
     }
 }
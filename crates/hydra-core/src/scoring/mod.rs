@@ -1,10 +1,15 @@
 pub mod baseline;
 pub mod build;
+pub mod build_cache;
 pub mod cost;
 pub mod diff_scope;
+pub mod evaluate;
 pub mod lint;
+pub mod pricing;
 pub mod ranking;
 pub mod tests;
+pub mod toolchain;
+pub mod write_policy;
 
 use serde::{Deserialize, Serialize};
 
@@ -0,0 +1,154 @@
+//! Token prices for agents whose output reports token counts but no cost.
+//!
+//! The built-in table covers the models the tier-1 adapters run by default;
+//! `[pricing]` in `hydra.toml` overrides entries, adds models, and picks the
+//! model each agent is billed as.
+
+use crate::config::{ModelPrice, PricingConfig};
+
+/// Published list prices in USD per million tokens:
+/// `(model, input, output, cached input)`.
+const BUILTIN_PRICES: &[(&str, f64, f64, f64)] = &[
+    ("claude-opus-4", 15.0, 75.0, 1.5),
+    ("claude-sonnet-4", 3.0, 15.0, 0.3),
+    ("claude-haiku-4", 1.0, 5.0, 0.1),
+    ("claude-3-5-haiku", 0.8, 4.0, 0.08),
+    ("gpt-5", 1.25, 10.0, 0.125),
+    ("gpt-5-codex", 1.25, 10.0, 0.125),
+    ("gpt-5-mini", 0.25, 2.0, 0.025),
+    ("o4-mini", 1.1, 4.4, 0.275),
+];
+
+/// Model each built-in adapter uses when the CLI is not told otherwise.
+const DEFAULT_AGENT_MODELS: &[(&str, &str)] =
+    &[("claude", "claude-sonnet-4"), ("codex", "gpt-5-codex")];
+
+/// The model an agent is billed as and its price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentPrice {
+    pub model: String,
+    pub price: ModelPrice,
+}
+
+impl ModelPrice {
+    /// Cost in USD of one usage report. `cached_input_tokens` are part of
+    /// `input_tokens`, as the CLIs report them.
+    pub fn cost_usd(&self, input_tokens: u64, output_tokens: u64, cached_input_tokens: u64) -> f64 {
+        let cached = cached_input_tokens.min(input_tokens);
+        let uncached = input_tokens - cached;
+        let cached_rate = self.cached_input_per_mtok.unwrap_or(self.input_per_mtok);
+        (uncached as f64 * self.input_per_mtok
+            + cached as f64 * cached_rate
+            + output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// Price of `model` from the built-in table. Dated snapshots such as
+/// `claude-sonnet-4-20250514` match the longest listed prefix.
+pub fn builtin_price(model: &str) -> Option<ModelPrice> {
+    BUILTIN_PRICES
+        .iter()
+        .filter(|(name, ..)| model == *name || model.starts_with(&format!("{name}-")))
+        .max_by_key(|(name, ..)| name.len())
+        .map(|&(_, input, output, cached)| ModelPrice {
+            input_per_mtok: input,
+            output_per_mtok: output,
+            cached_input_per_mtok: Some(cached),
+        })
+}
+
+/// Price of `model`, preferring `[pricing.models]` over the built-in table.
+pub fn model_price(config: &PricingConfig, model: &str) -> Option<ModelPrice> {
+    config
+        .models
+        .get(model)
+        .copied()
+        .or_else(|| builtin_price(model))
+}
+
+/// Model `agent_key` is billed as, from `[pricing.agents]` or the adapter's
+/// default.
+pub fn agent_model(config: &PricingConfig, agent_key: &str) -> Option<String> {
+    config.agents.get(agent_key).cloned().or_else(|| {
+        DEFAULT_AGENT_MODELS
+            .iter()
+            .find(|(agent, _)| *agent == agent_key)
+            .map(|(_, model)| model.to_string())
+    })
+}
+
+/// Model and price for `agent_key`, or `None` when either is unknown.
+pub fn agent_price(config: &PricingConfig, agent_key: &str) -> Option<AgentPrice> {
+    let model = agent_model(config, agent_key)?;
+    let price = model_price(config, &model)?;
+    Some(AgentPrice { model, price })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cost_bills_cached_input_at_the_cached_rate() {
+        let price = ModelPrice {
+            input_per_mtok: 2.0,
+            output_per_mtok: 10.0,
+            cached_input_per_mtok: Some(0.5),
+        };
+        // 600k uncached + 400k cached input, 100k output.
+        let cost = price.cost_usd(1_000_000, 100_000, 400_000);
+        assert!((cost - (1.2 + 0.2 + 1.0)).abs() < 1e-9);
+
+        let uncached_only = ModelPrice {
+            cached_input_per_mtok: None,
+            ..price
+        };
+        assert!((uncached_only.cost_usd(1_000_000, 0, 400_000) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn builtin_lookup_matches_dated_snapshots_by_longest_prefix() {
+        let mini = builtin_price("gpt-5-mini-2025-08-07").expect("mini is listed");
+        assert_eq!(mini.input_per_mtok, 0.25);
+        assert_eq!(builtin_price("gpt-5").unwrap().input_per_mtok, 1.25);
+        assert!(builtin_price("gpt-50").is_none());
+        assert!(builtin_price("unknown-model").is_none());
+    }
+
+    #[test]
+    fn config_overrides_agent_models_and_prices() {
+        let mut config = PricingConfig::default();
+        assert_eq!(
+            agent_price(&config, "claude").unwrap().model,
+            "claude-sonnet-4"
+        );
+        assert!(agent_price(&config, "cursor-agent").is_none());
+
+        config
+            .agents
+            .insert("claude".to_string(), "claude-opus-4".to_string());
+        config
+            .agents
+            .insert("cursor-agent".to_string(), "house-model".to_string());
+        config.models.insert(
+            "house-model".to_string(),
+            ModelPrice {
+                input_per_mtok: 1.0,
+                output_per_mtok: 1.0,
+                cached_input_per_mtok: None,
+            },
+        );
+
+        let claude = agent_price(&config, "claude").unwrap();
+        assert_eq!(claude.model, "claude-opus-4");
+        assert_eq!(claude.price.output_per_mtok, 75.0);
+        assert_eq!(
+            agent_price(&config, "cursor-agent")
+                .unwrap()
+                .price
+                .input_per_mtok,
+            1.0
+        );
+    }
+}
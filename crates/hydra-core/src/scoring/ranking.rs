@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use super::write_policy::parse_violations;
 use super::DimensionScore;
 use crate::config::{GatesConfig, WeightsConfig};
 
@@ -14,6 +15,10 @@ pub struct AgentScore {
     pub composite: f64,
     pub mergeable: bool,
     pub gate_failures: Vec<String>,
+    /// Scored from an agent stopped by a timeout; see
+    /// `scoring.score_timed_out`.
+    #[serde(default)]
+    pub partial: bool,
 }
 
 /// Rank agents by weighted composite score with mergeability gates.
@@ -58,6 +63,7 @@ pub fn rank_agents(
                 composite,
                 mergeable,
                 gate_failures,
+                partial: false,
             }
         })
         .collect();
@@ -145,6 +151,16 @@ fn check_gates(dimensions: &[DimensionScore], gates: &GatesConfig) -> (bool, Vec
         }
     }
 
+    if let Some(write_policy) = dimensions.iter().find(|d| d.name == "write_policy") {
+        if write_policy.score < 100.0 {
+            let violations = parse_violations(&write_policy.evidence["violations"]);
+            if violations.is_empty() {
+                failures.push("write policy check failed".to_string());
+            }
+            failures.extend(violations.iter().map(|v| format!("write policy: {v}")));
+        }
+    }
+
     let mergeable = failures.is_empty();
     (mergeable, failures)
 }
@@ -206,6 +222,31 @@ mod tests {
         assert!(failures.iter().any(|f| f.contains("build failed")));
     }
 
+    #[test]
+    fn write_policy_violations_fail_the_gate() {
+        let dims = vec![
+            make_dim("build", 100.0),
+            DimensionScore {
+                name: "write_policy".to_string(),
+                score: 0.0,
+                evidence: serde_json::json!({
+                    "violations": [{
+                        "path": "migrations/001.sql",
+                        "rule": "read_only",
+                        "pattern": "migrations/**",
+                    }],
+                    "reverted": [],
+                }),
+            },
+        ];
+        let (mergeable, failures) = check_gates(&dims, &default_gates());
+        assert!(!mergeable);
+        assert_eq!(
+            failures,
+            vec!["write policy: read-only path migrations/001.sql (matches migrations/**)"]
+        );
+    }
+
     #[test]
     fn build_gate_passes_when_build_100() {
         let dims = vec![make_dim("build", 100.0), make_dim("tests", 100.0)];
@@ -60,6 +60,7 @@ pub fn score_tests(baseline: Option<&TestResult>, agent_result: &TestResult) ->
             "reg_penalty": reg_penalty,
             "new_test_bonus": new_test_bonus,
             "test_drop_detected": test_drop,
+            "duration_ms": agent_result.command_result.duration_ms,
         }),
     }
 }
@@ -0,0 +1,147 @@
+//! Locating the tools behind scoring commands.
+//!
+//! A scoring command whose program is missing fails in every candidate and
+//! scores zero across the board, which reads like the agents did badly. The
+//! doctor resolves each command's programs up front so that shows up as a
+//! missing tool instead.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::adapter::parse_version_string;
+
+/// A program a scoring command needs, and how to ask it for its version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredTool {
+    /// Display name, e.g. `node` or `cargo-clippy`.
+    pub name: String,
+    /// Argv that prints the version; `argv[0]` is looked up on PATH.
+    pub version_argv: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStatus {
+    pub name: String,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+}
+
+impl ToolStatus {
+    pub fn found(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+/// Shell words that are not programs to look up.
+const BUILTINS: &[&str] = &[
+    ":", "true", "false", "cd", "echo", "exit", "export", "set", "test", "[", "exec", "env",
+];
+
+/// Package managers that run scripts through node.
+const NODE_RUNNERS: &[&str] = &["npm", "npx", "pnpm", "yarn"];
+
+/// Programs `command` runs, in order of first use. Handles `&&`, `||`, `;`
+/// and `|` chains and leading `VAR=value` assignments; anything more exotic
+/// is taken at its first word.
+pub fn required_tools(command: &str) -> Vec<RequiredTool> {
+    let mut tools: Vec<RequiredTool> = Vec::new();
+    let mut push = |tool: RequiredTool| {
+        if !tools.iter().any(|t| t.name == tool.name) {
+            tools.push(tool);
+        }
+    };
+
+    for segment in command.split(['&', '|', ';']) {
+        let mut words = segment
+            .split_whitespace()
+            .skip_while(|w| w.contains('=') && !w.starts_with('='));
+        let Some(program) = words.next() else {
+            continue;
+        };
+        if BUILTINS.contains(&program) {
+            continue;
+        }
+
+        if NODE_RUNNERS.contains(&program) {
+            push(tool(program, &[program, "--version"]));
+            push(tool("node", &["node", "--version"]));
+            continue;
+        }
+        if program == "cargo" {
+            push(tool("cargo", &["cargo", "--version"]));
+            if let Some(sub @ ("clippy" | "fmt")) = words.next() {
+                push(tool(&format!("cargo-{sub}"), &["cargo", sub, "--version"]));
+            }
+            continue;
+        }
+        push(tool(program, &[program, "--version"]));
+    }
+    tools
+}
+
+fn tool(name: &str, argv: &[&str]) -> RequiredTool {
+    RequiredTool {
+        name: name.to_string(),
+        version_argv: argv.iter().map(|a| a.to_string()).collect(),
+    }
+}
+
+/// Look `tool` up on PATH (relative paths against `cwd`) and ask for its
+/// version.
+pub fn probe_tool(tool: &RequiredTool, cwd: &Path) -> ToolStatus {
+    let program = &tool.version_argv[0];
+    let path = if program.contains('/') {
+        Some(cwd.join(program)).filter(|p| p.exists())
+    } else {
+        which::which(program).ok()
+    };
+
+    let version = path.as_ref().and_then(|path| {
+        let output = Command::new(path)
+            .args(&tool.version_argv[1..])
+            .current_dir(cwd)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        parse_version_string(&String::from_utf8_lossy(&output.stdout))
+    });
+
+    ToolStatus {
+        name: tool.name.clone(),
+        path,
+        version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(command: &str) -> Vec<String> {
+        required_tools(command)
+            .into_iter()
+            .map(|t| t.name)
+            .collect()
+    }
+
+    #[test]
+    fn required_tools_follow_chains_and_profiles() {
+        assert_eq!(names("cargo build --all-targets"), vec!["cargo"]);
+        assert_eq!(
+            names("cargo clippy --all-targets -- -D warnings"),
+            vec!["cargo", "cargo-clippy"]
+        );
+        assert_eq!(names("npm run build"), vec!["npm", "node"]);
+        assert_eq!(
+            names("CI=1 pnpm install && pnpm test | tee out.log"),
+            vec!["pnpm", "node", "tee"]
+        );
+        assert_eq!(names("pytest -q; ruff check ."), vec!["pytest", "ruff"]);
+        assert!(names("true").is_empty());
+
+        let clippy = &required_tools("cargo clippy")[1];
+        assert_eq!(clippy.version_argv, vec!["cargo", "clippy", "--version"]);
+    }
+}
@@ -0,0 +1,260 @@
+//! Write policy check on a candidate's final diff (`security.write_policy`).
+//!
+//! Every file that differs from the base ref is matched against the
+//! read-only and forbidden globs. With `revert_violations` the offending
+//! files are restored to their base content (or removed, if the agent
+//! created them) before the candidate is built and tested; otherwise each
+//! violation fails the candidate's merge gate.
+
+use std::path::Path;
+
+use super::diff_scope::list_untracked_files;
+use super::DimensionScore;
+use crate::security::{PathRules, PathViolation};
+
+/// Outcome of checking one candidate's diff.
+#[derive(Debug, Clone, Default)]
+pub struct WritePolicyReport {
+    /// Violations still present in the candidate.
+    pub violations: Vec<PathViolation>,
+    /// Violations undone by restoring the file from the base ref.
+    pub reverted: Vec<PathViolation>,
+}
+
+/// Check the files changed since `base_ref`, reverting them when `revert`
+/// is set.
+pub async fn enforce_write_policy(
+    candidate_path: &Path,
+    base_ref: &str,
+    rules: &PathRules,
+    revert: bool,
+) -> Result<WritePolicyReport, std::io::Error> {
+    let mut report = WritePolicyReport::default();
+    for path in changed_paths(candidate_path, base_ref).await? {
+        let Some(violation) = rules.check_write(&path) else {
+            continue;
+        };
+        if revert {
+            restore_from_base(candidate_path, base_ref, &path).await?;
+            report.reverted.push(violation);
+        } else {
+            report.violations.push(violation);
+        }
+    }
+    Ok(report)
+}
+
+/// The `write_policy` dimension: 100 when nothing is left in violation, 0
+/// otherwise. It carries no ranking weight; the gate is what matters.
+pub fn score_write_policy(report: &WritePolicyReport) -> DimensionScore {
+    DimensionScore {
+        name: "write_policy".to_string(),
+        score: if report.violations.is_empty() {
+            100.0
+        } else {
+            0.0
+        },
+        evidence: serde_json::json!({
+            "violations": report.violations,
+            "reverted": report.reverted,
+        }),
+    }
+}
+
+/// Add violations seen in the agent's tool calls (such as reads of
+/// forbidden files, which leave no trace in the diff) to its `write_policy`
+/// dimension. Paths that were reverted before scoring are not counted
+/// again.
+pub fn record_runtime_violations(dimensions: &mut Vec<DimensionScore>, runtime: &[PathViolation]) {
+    if runtime.is_empty() {
+        return;
+    }
+    let index = match dimensions.iter().position(|d| d.name == "write_policy") {
+        Some(index) => index,
+        None => {
+            dimensions.push(score_write_policy(&WritePolicyReport::default()));
+            dimensions.len() - 1
+        }
+    };
+    let dim = &mut dimensions[index];
+    let mut report = WritePolicyReport {
+        violations: parse_violations(&dim.evidence["violations"]),
+        reverted: parse_violations(&dim.evidence["reverted"]),
+    };
+    for violation in runtime {
+        let reverted = report.reverted.iter().any(|r| r.path == violation.path);
+        if !reverted && !report.violations.contains(violation) {
+            report.violations.push(violation.clone());
+        }
+    }
+    *dim = score_write_policy(&report);
+}
+
+/// Violations recorded in a `write_policy` dimension's evidence.
+pub fn parse_violations(evidence: &serde_json::Value) -> Vec<PathViolation> {
+    serde_json::from_value(evidence.clone()).unwrap_or_default()
+}
+
+async fn changed_paths(
+    worktree_path: &Path,
+    base_ref: &str,
+) -> Result<Vec<String>, std::io::Error> {
+    let output = tokio::process::Command::new("git")
+        .args(["diff", "--name-only", "--no-renames", base_ref])
+        .current_dir(worktree_path)
+        .output()
+        .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(std::io::Error::other(format!("git diff failed: {stderr}")));
+    }
+
+    let mut paths: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    paths.extend(list_untracked_files(worktree_path).await?);
+    Ok(paths)
+}
+
+async fn restore_from_base(
+    worktree_path: &Path,
+    base_ref: &str,
+    rel_path: &str,
+) -> Result<(), std::io::Error> {
+    let in_base = tokio::process::Command::new("git")
+        .args(["cat-file", "-e", &format!("{base_ref}:{rel_path}")])
+        .current_dir(worktree_path)
+        .output()
+        .await?
+        .status
+        .success();
+    if !in_base {
+        return match std::fs::remove_file(worktree_path.join(rel_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    let output = tokio::process::Command::new("git")
+        .args(["checkout", base_ref, "--", rel_path])
+        .current_dir(worktree_path)
+        .output()
+        .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(std::io::Error::other(format!(
+            "git checkout {rel_path} failed: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WritePolicyConfig;
+    use crate::security::PathRule;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn repo_with_violations() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        git(repo, &["init"]);
+        git(repo, &["config", "user.email", "test@example.com"]);
+        git(repo, &["config", "user.name", "Test User"]);
+        std::fs::create_dir_all(repo.join("migrations")).unwrap();
+        std::fs::write(repo.join("migrations/001.sql"), "create table a;\n").unwrap();
+        std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "-m", "init"]);
+
+        std::fs::write(repo.join("migrations/001.sql"), "drop table a;\n").unwrap();
+        std::fs::write(repo.join("main.rs"), "fn main() { run() }\n").unwrap();
+        std::fs::write(repo.join("dev.pem"), "key\n").unwrap();
+        tmp
+    }
+
+    fn rules() -> PathRules {
+        PathRules::from_config(&WritePolicyConfig {
+            read_only: vec!["migrations/**".to_string()],
+            forbidden: vec!["**/*.pem".to_string()],
+            revert_violations: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn violations_in_diff_fail_the_dimension() {
+        let tmp = repo_with_violations();
+        let report = enforce_write_policy(tmp.path(), "HEAD", &rules(), false)
+            .await
+            .unwrap();
+
+        let rules: Vec<(&str, PathRule)> = report
+            .violations
+            .iter()
+            .map(|v| (v.path.as_str(), v.rule))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("migrations/001.sql", PathRule::ReadOnly),
+                ("dev.pem", PathRule::Forbidden)
+            ]
+        );
+        assert_eq!(score_write_policy(&report).score, 0.0);
+        assert!(tmp.path().join("dev.pem").exists());
+    }
+
+    #[tokio::test]
+    async fn revert_restores_offending_files_only() {
+        let tmp = repo_with_violations();
+        let report = enforce_write_policy(tmp.path(), "HEAD", &rules(), true)
+            .await
+            .unwrap();
+
+        assert!(report.violations.is_empty());
+        assert_eq!(report.reverted.len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("migrations/001.sql")).unwrap(),
+            "create table a;\n"
+        );
+        assert!(!tmp.path().join("dev.pem").exists());
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("main.rs")).unwrap(),
+            "fn main() { run() }\n"
+        );
+
+        let mut dimensions = vec![score_write_policy(&report)];
+        record_runtime_violations(&mut dimensions, &report.reverted);
+        assert_eq!(dimensions[0].score, 100.0);
+        let read = PathViolation {
+            path: "secrets/ci.pem".to_string(),
+            rule: PathRule::Forbidden,
+            pattern: "**/*.pem".to_string(),
+        };
+        record_runtime_violations(&mut dimensions, std::slice::from_ref(&read));
+        assert_eq!(dimensions[0].score, 0.0);
+        assert_eq!(
+            parse_violations(&dimensions[0].evidence["violations"]),
+            vec![read]
+        );
+    }
+}
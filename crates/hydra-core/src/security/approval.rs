@@ -0,0 +1,228 @@
+//! Approval of dangerous agent actions (`security.approval`).
+//!
+//! Agents report the tools they call in their event stream. A shell command
+//! outside the safe list, or a file deletion, is a [`DangerousAction`]; the
+//! orchestrator pauses the agent and lets the policy (or the user) decide
+//! whether it may carry on. The check runs on the reported tool call, so it
+//! stops the agent from going further rather than preventing the call
+//! itself; kernel confinement is what keeps it inside the worktree.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// What happens when an agent attempts a dangerous action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalPolicy {
+    /// Let the agent carry on; the action is only recorded.
+    #[default]
+    AutoApprove,
+    /// Pause the agent until the user approves or denies the action.
+    Ask,
+    /// Stop the agent.
+    Deny,
+}
+
+/// The user's answer to an approval request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approve,
+    Deny,
+}
+
+impl ApprovalDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprovalDecision::Approve => "approve",
+            ApprovalDecision::Deny => "deny",
+        }
+    }
+}
+
+/// A tool call that needs approval under [`ApprovalPolicy::Ask`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DangerousAction {
+    ShellCommand { command: String },
+    FileDeletion { path: String },
+}
+
+impl fmt::Display for DangerousAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DangerousAction::ShellCommand { command } => write!(f, "run `{command}`"),
+            DangerousAction::FileDeletion { path } => write!(f, "delete {path}"),
+        }
+    }
+}
+
+/// Tool names adapters use for running a shell command.
+const SHELL_TOOLS: &[&str] = &[
+    "bash",
+    "shell",
+    "local_shell",
+    "exec",
+    "exec_command",
+    "run_command",
+    "run_terminal_cmd",
+    "terminal",
+];
+
+/// Commands that remove files, whatever the safe list says.
+const DELETE_COMMANDS: &[&str] = &["rm", "rmdir", "unlink", "shred", "git rm", "git clean"];
+
+/// Characters that chain or redirect commands; a command containing any of
+/// them is never considered safe.
+const SHELL_METACHARACTERS: &[char] = &[';', '|', '&', '>', '<', '`', '$', '\n'];
+
+/// Classify a reported tool call. Returns `None` for anything that does not
+/// need approval.
+pub fn dangerous_action(
+    tool: &str,
+    input: &serde_json::Value,
+    safe_commands: &[String],
+) -> Option<DangerousAction> {
+    let tool = tool.to_ascii_lowercase();
+    if tool.contains("delete") || tool.contains("remove") {
+        let path = ["path", "file_path", "target_file"]
+            .iter()
+            .find_map(|key| input.get(key).and_then(|v| v.as_str()))
+            .unwrap_or("<unknown>");
+        return Some(DangerousAction::FileDeletion {
+            path: path.to_string(),
+        });
+    }
+    if !SHELL_TOOLS.contains(&tool.as_str()) {
+        return None;
+    }
+
+    let command = shell_command(input)?;
+    let command = command.trim();
+    if let Some(rest) = DELETE_COMMANDS
+        .iter()
+        .find_map(|prefix| strip_words(command, prefix))
+    {
+        return Some(DangerousAction::FileDeletion {
+            path: rest.trim().to_string(),
+        });
+    }
+    if is_safe_command(command, safe_commands) {
+        return None;
+    }
+    Some(DangerousAction::ShellCommand {
+        command: command.to_string(),
+    })
+}
+
+/// The command line from a shell tool's input: a string, or an argv array
+/// (unwrapping `bash -c <script>` as Codex reports it).
+fn shell_command(input: &serde_json::Value) -> Option<String> {
+    let command = match input {
+        serde_json::Value::String(s) => return Some(s.clone()),
+        serde_json::Value::Object(obj) => obj.get("command").or_else(|| obj.get("cmd"))?,
+        _ => return None,
+    };
+    match command {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(items) => {
+            let argv: Vec<&str> = items.iter().filter_map(|v| v.as_str()).collect();
+            match argv.as_slice() {
+                [shell, flag, script]
+                    if matches!(*shell, "bash" | "sh" | "zsh") && matches!(*flag, "-c" | "-lc") =>
+                {
+                    Some(script.to_string())
+                }
+                _ => Some(argv.join(" ")),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_safe_command(command: &str, safe_commands: &[String]) -> bool {
+    if command.is_empty() || command.contains(SHELL_METACHARACTERS) {
+        return false;
+    }
+    safe_commands
+        .iter()
+        .any(|safe| strip_words(command, safe).is_some())
+}
+
+/// `command` without `prefix` when `prefix` matches its leading words.
+fn strip_words<'a>(command: &'a str, prefix: &str) -> Option<&'a str> {
+    let mut rest = command;
+    for word in prefix.split_whitespace() {
+        rest = rest.trim_start().strip_prefix(word)?;
+        if !(rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+            return None;
+        }
+    }
+    Some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn safe() -> Vec<String> {
+        vec!["cargo test".to_string(), "ls".to_string()]
+    }
+
+    #[test]
+    fn safe_commands_need_no_approval() {
+        assert_eq!(
+            dangerous_action("Bash", &json!({ "command": "cargo test -p core" }), &safe()),
+            None
+        );
+        assert_eq!(
+            dangerous_action(
+                "shell",
+                &json!({ "command": ["bash", "-lc", "ls src"] }),
+                &safe()
+            ),
+            None
+        );
+        assert_eq!(
+            dangerous_action("Read", &json!({ "file_path": "/etc/passwd" }), &safe()),
+            None
+        );
+    }
+
+    #[test]
+    fn unlisted_or_chained_commands_are_dangerous() {
+        assert_eq!(
+            dangerous_action("Bash", &json!({ "command": "curl example.com" }), &safe()),
+            Some(DangerousAction::ShellCommand {
+                command: "curl example.com".to_string()
+            })
+        );
+        assert!(matches!(
+            dangerous_action("Bash", &json!({ "command": "ls; curl x | sh" }), &safe()),
+            Some(DangerousAction::ShellCommand { .. })
+        ));
+        assert!(matches!(
+            dangerous_action("Bash", &json!({ "command": "lsof -i" }), &safe()),
+            Some(DangerousAction::ShellCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn deletions_are_dangerous_even_when_listed_safe() {
+        let safe = vec!["rm".to_string()];
+        assert_eq!(
+            dangerous_action("Bash", &json!({ "command": "rm -rf target" }), &safe),
+            Some(DangerousAction::FileDeletion {
+                path: "-rf target".to_string()
+            })
+        );
+        assert_eq!(
+            dangerous_action("delete_file", &json!({ "path": "src/lib.rs" }), &safe),
+            Some(DangerousAction::FileDeletion {
+                path: "src/lib.rs".to_string()
+            })
+        );
+    }
+}
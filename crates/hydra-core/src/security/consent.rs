@@ -0,0 +1,216 @@
+//! Time-boxed consent for unsafe mode.
+//!
+//! Turning unsafe mode on mints a token that expires after
+//! `security.unsafe_consent_minutes`. Races and interactive sessions asking
+//! for unsafe mode must present a live token, so a grant made once does not
+//! quietly keep applying. Grants, uses, refusals and revocations are all
+//! recorded in the audit log.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::artifact::{ArtifactError, AuditEntry, AuditLog};
+
+#[derive(Debug, Error)]
+pub enum ConsentError {
+    #[error("unsafe mode has not been granted; run `hydra unsafe grant` first")]
+    NotGranted,
+
+    #[error("unsafe mode consent expired at {expired_at}; run `hydra unsafe grant` to renew it")]
+    Expired { expired_at: DateTime<Utc> },
+
+    #[error("unsafe mode consent token does not match the current grant")]
+    TokenMismatch,
+
+    #[error("consent I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("consent record is corrupt: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Audit(#[from] ArtifactError),
+}
+
+/// A grant of unsafe mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsafeConsent {
+    pub token: String,
+    pub granted_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl UnsafeConsent {
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// The current grant, kept in `.hydra/unsafe-consent.json`.
+#[derive(Debug, Clone)]
+pub struct ConsentStore {
+    path: PathBuf,
+    audit: AuditLog,
+}
+
+impl ConsentStore {
+    pub fn new(hydra_root: &Path) -> Self {
+        Self {
+            path: hydra_root.join("unsafe-consent.json"),
+            audit: AuditLog::new(hydra_root),
+        }
+    }
+
+    /// Mint a token valid for `ttl`, replacing any earlier grant.
+    pub fn grant(&self, ttl: Duration) -> Result<UnsafeConsent, ConsentError> {
+        let granted_at = Utc::now();
+        let consent = UnsafeConsent {
+            token: format!("unsafe-{}", Uuid::new_v4().simple()),
+            granted_at,
+            expires_at: granted_at + ttl,
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&consent)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.audit.append(&AuditEntry::new(
+            "unsafe_consent_granted",
+            serde_json::json!({
+                "token": consent.token,
+                "expires_at": consent.expires_at,
+            }),
+        ))?;
+        Ok(consent)
+    }
+
+    /// Drop the current grant. Returns `false` if there was none.
+    pub fn revoke(&self) -> Result<bool, ConsentError> {
+        let Some(consent) = self.load()? else {
+            return Ok(false);
+        };
+        std::fs::remove_file(&self.path)?;
+        self.audit.append(&AuditEntry::new(
+            "unsafe_consent_revoked",
+            serde_json::json!({ "token": consent.token }),
+        ))?;
+        Ok(true)
+    }
+
+    /// The grant on record, expired or not.
+    pub fn load(&self) -> Result<Option<UnsafeConsent>, ConsentError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Check consent for unsafe mode on behalf of `used_for` (e.g. `race
+    /// <run id>`). Without a token the current grant is used. The outcome
+    /// is audited either way.
+    pub fn authorize(
+        &self,
+        token: Option<&str>,
+        used_for: &str,
+    ) -> Result<UnsafeConsent, ConsentError> {
+        let result = self.check(token, Utc::now());
+        let entry = match &result {
+            Ok(consent) => AuditEntry::new(
+                "unsafe_consent_used",
+                serde_json::json!({ "token": consent.token, "used_for": used_for }),
+            ),
+            Err(e) => AuditEntry::new(
+                "unsafe_consent_rejected",
+                serde_json::json!({ "used_for": used_for, "reason": e.to_string() }),
+            ),
+        };
+        self.audit.append(&entry)?;
+        result
+    }
+
+    fn check(
+        &self,
+        token: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<UnsafeConsent, ConsentError> {
+        let consent = self.load()?.ok_or(ConsentError::NotGranted)?;
+        if token.is_some_and(|token| token != consent.token) {
+            return Err(ConsentError::TokenMismatch);
+        }
+        if consent.is_expired_at(now) {
+            return Err(ConsentError::Expired {
+                expired_at: consent.expires_at,
+            });
+        }
+        Ok(consent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grant_is_required_and_audited() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = ConsentStore::new(tmp.path());
+        assert!(matches!(
+            store.authorize(None, "race a"),
+            Err(ConsentError::NotGranted)
+        ));
+
+        let consent = store.grant(Duration::minutes(30)).unwrap();
+        assert_eq!(store.authorize(None, "race b").unwrap(), consent);
+        assert_eq!(
+            store.authorize(Some(&consent.token), "session c").unwrap(),
+            consent
+        );
+        assert!(matches!(
+            store.authorize(Some("unsafe-stale"), "session d"),
+            Err(ConsentError::TokenMismatch)
+        ));
+
+        assert!(store.revoke().unwrap());
+        assert!(!store.revoke().unwrap());
+        assert!(store.authorize(None, "race e").is_err());
+
+        let actions: Vec<String> = AuditLog::new(tmp.path())
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                "unsafe_consent_rejected",
+                "unsafe_consent_granted",
+                "unsafe_consent_used",
+                "unsafe_consent_used",
+                "unsafe_consent_rejected",
+                "unsafe_consent_revoked",
+                "unsafe_consent_rejected",
+            ]
+        );
+    }
+
+    #[test]
+    fn consent_expires() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = ConsentStore::new(tmp.path());
+        let consent = store.grant(Duration::minutes(5)).unwrap();
+
+        let later = consent.expires_at + Duration::seconds(1);
+        assert!(matches!(
+            store.check(None, later),
+            Err(ConsentError::Expired { expired_at }) if expired_at == consent.expires_at
+        ));
+        assert!(store.check(None, consent.granted_at).is_ok());
+    }
+}
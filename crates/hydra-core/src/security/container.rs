@@ -0,0 +1,217 @@
+//! Container-backed execution (`security.isolation = "container"`).
+//!
+//! The agent's built command runs inside a Docker or Podman container with
+//! only the worktree (and its git directories) bind-mounted at the same
+//! paths, so nothing else on the host filesystem is visible to it. This
+//! works on every platform the runtime supports, at the cost of providing
+//! an image that contains the agent CLI.
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::adapter::BuiltCommand;
+use crate::config::ContainerConfig;
+
+/// Runtimes looked up on `$PATH` when `security.container.runtime` is unset.
+const RUNTIME_CANDIDATES: &[&str] = &["docker", "podman"];
+
+#[derive(Debug, Error)]
+pub enum ContainerError {
+    #[error("no container runtime found (tried {tried}); install Docker or Podman or set security.container.runtime")]
+    RuntimeNotFound { tried: String },
+
+    #[error("security.container.image must be set when isolation is \"container\"")]
+    MissingImage,
+}
+
+/// How to wrap one agent's command in a container.
+#[derive(Debug, Clone)]
+pub struct ContainerSpec {
+    runtime: PathBuf,
+    image: String,
+    name: String,
+    network: String,
+    mounts: Vec<PathBuf>,
+    workdir: PathBuf,
+    env: Vec<String>,
+    extra_args: Vec<String>,
+}
+
+impl ContainerSpec {
+    /// Resolve the runtime and collect the mounts for an agent in
+    /// `worktree`. `name` must be unique among running containers.
+    pub fn for_worktree(
+        cfg: &ContainerConfig,
+        worktree: &Path,
+        name: impl Into<String>,
+    ) -> Result<Self, ContainerError> {
+        let image = cfg.image.clone().ok_or(ContainerError::MissingImage)?;
+        let runtime = resolve_runtime(cfg.runtime.as_deref())?;
+        let mut mounts = vec![worktree.to_path_buf()];
+        if let Some(dirs) = super::os_sandbox::GitDirs::find(worktree) {
+            mounts.push(dirs.private);
+            mounts.extend(dirs.common);
+        }
+        Ok(Self {
+            runtime,
+            image,
+            name: name.into(),
+            network: cfg.network.clone(),
+            mounts,
+            workdir: worktree.to_path_buf(),
+            env: cfg.env.clone(),
+            extra_args: cfg.extra_args.clone(),
+        })
+    }
+
+    /// Cut the container off from all networks (`security.allow_network =
+    /// false`), whatever `security.container.network` says.
+    pub fn without_network(mut self) -> Self {
+        self.network = "none".to_string();
+        self
+    }
+
+    pub fn runtime(&self) -> &Path {
+        &self.runtime
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `<runtime> run ...` invocation that executes `cmd` in the
+    /// container. Environment variables are forwarded by name so their
+    /// values never appear on the runtime's command line. Chain steps are
+    /// not carried over; the supervisor wraps each one itself.
+    pub fn wrap(&self, cmd: &BuiltCommand) -> BuiltCommand {
+        let mut args: Vec<String> = vec![
+            "run".into(),
+            "--rm".into(),
+            "--interactive".into(),
+            "--init".into(),
+            "--name".into(),
+            self.name.clone(),
+            "--network".into(),
+            self.network.clone(),
+        ];
+        #[cfg(unix)]
+        {
+            // Files the agent creates stay owned by the invoking user.
+            let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+            args.push("--user".into());
+            args.push(format!("{uid}:{gid}"));
+        }
+        for mount in &self.mounts {
+            let path = mount.display();
+            args.push("--volume".into());
+            args.push(format!("{path}:{path}"));
+        }
+        args.push("--workdir".into());
+        args.push(self.workdir.display().to_string());
+        for key in cmd.env.iter().map(|(k, _)| k).chain(&self.env) {
+            args.push("--env".into());
+            args.push(key.clone());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args.push(self.image.clone());
+        args.push(cmd.program.clone());
+        args.extend(cmd.args.iter().cloned());
+
+        BuiltCommand {
+            program: self.runtime.display().to_string(),
+            args,
+            env: cmd.env.clone(),
+            cwd: cmd.cwd.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// `<runtime> <action> <name>` for `rm` (forced), `pause` or `unpause`.
+    /// Signals to the runtime client do not reliably reach the container,
+    /// so the supervisor removes it this way after a timeout or
+    /// cancellation and pauses it for approvals.
+    pub fn command(&self, action: &str) -> (PathBuf, Vec<String>) {
+        let mut args = vec![action.to_string()];
+        if action == "rm" {
+            args.push("--force".into());
+        }
+        args.push(self.name.clone());
+        (self.runtime.clone(), args)
+    }
+}
+
+fn resolve_runtime(configured: Option<&str>) -> Result<PathBuf, ContainerError> {
+    let candidates = match configured {
+        Some(runtime) => vec![runtime],
+        None => RUNTIME_CANDIDATES.to_vec(),
+    };
+    candidates
+        .iter()
+        .find_map(|name| which::which(name).ok())
+        .ok_or_else(|| ContainerError::RuntimeNotFound {
+            tried: candidates.join(", "),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(worktree: &Path) -> ContainerSpec {
+        ContainerSpec {
+            runtime: PathBuf::from("/usr/bin/docker"),
+            image: "hydra-agents:latest".to_string(),
+            name: "hydra-run-claude".to_string(),
+            network: "bridge".to_string(),
+            mounts: vec![worktree.to_path_buf()],
+            workdir: worktree.to_path_buf(),
+            env: vec!["ANTHROPIC_API_KEY".to_string()],
+            extra_args: vec!["--memory=4g".to_string()],
+        }
+    }
+
+    #[test]
+    fn wrap_runs_command_in_image_with_worktree_mounted() {
+        let worktree = Path::new("/repo/.hydra/worktrees/run/claude");
+        let cmd = BuiltCommand {
+            program: "claude".to_string(),
+            args: vec!["-p".to_string(), "fix it".to_string()],
+            env: vec![("CLAUDE_TOKEN".to_string(), "secret".to_string())],
+            cwd: worktree.to_path_buf(),
+            ..Default::default()
+        };
+
+        let wrapped = spec(worktree).wrap(&cmd);
+        assert_eq!(wrapped.program, "/usr/bin/docker");
+        assert_eq!(wrapped.env, cmd.env);
+        let args = wrapped.args.join(" ");
+        assert!(args.starts_with("run --rm --interactive --init --name hydra-run-claude"));
+        assert!(args.contains("--network bridge"));
+        assert!(args.contains(&format!(
+            "--volume {0}:{0} --workdir {0}",
+            worktree.display()
+        )));
+        assert!(args.contains("--env CLAUDE_TOKEN --env ANTHROPIC_API_KEY"));
+        assert!(args.ends_with("--memory=4g hydra-agents:latest claude -p fix it"));
+        assert!(!args.contains("secret"));
+    }
+
+    #[test]
+    fn missing_image_or_runtime_is_an_error() {
+        let worktree = Path::new("/tmp");
+        let cfg = ContainerConfig::default();
+        assert!(matches!(
+            ContainerSpec::for_worktree(&cfg, worktree, "x"),
+            Err(ContainerError::MissingImage)
+        ));
+
+        let cfg = ContainerConfig {
+            image: Some("img".to_string()),
+            runtime: Some("hydra-no-such-runtime".to_string()),
+            ..Default::default()
+        };
+        let err = ContainerSpec::for_worktree(&cfg, worktree, "x").unwrap_err();
+        assert!(err.to_string().contains("hydra-no-such-runtime"));
+    }
+}
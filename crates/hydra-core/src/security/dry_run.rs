@@ -0,0 +1,487 @@
+//! Dry run of the current security policy against a finished run.
+//!
+//! Policies are easier to tune against real agent behaviour than in the
+//! abstract. [`dry_run_policy`] replays a run's recorded events and final
+//! diffs through the write policy, approval policy, network allowlist and
+//! redaction rules as they are configured now, and reports what would have
+//! been blocked, paused or redacted. Nothing in the run is modified.
+//!
+//! Only what the run recorded can be replayed: tool calls, output lines,
+//! refused egress and the saved diffs. Connections that were allowed at the
+//! time left no trace and cannot be re-checked.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dangerous_action, host_allowed, tool_file_access, ApprovalPolicy, NetworkAccess, PathRules,
+    SandboxPolicy, SecretRedactor,
+};
+use crate::adapter::AgentEvent;
+use crate::artifact::{ArtifactError, EventKind, EventReader, RunEvent, RunLayout, RunManifest};
+use crate::config::SecurityConfig;
+
+/// Longest excerpt of a redacted line kept in a finding.
+const MAX_EXCERPT_CHARS: usize = 160;
+
+/// Which part of the policy a finding comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyArea {
+    WritePolicy,
+    Approval,
+    Network,
+    Redaction,
+}
+
+impl fmt::Display for PolicyArea {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyArea::WritePolicy => write!(f, "write policy"),
+            PolicyArea::Approval => write!(f, "approval"),
+            PolicyArea::Network => write!(f, "network"),
+            PolicyArea::Redaction => write!(f, "redaction"),
+        }
+    }
+}
+
+/// What the current policy would have done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyOutcome {
+    /// The agent would have been stopped, refused, or failed the merge gate.
+    Blocked,
+    /// The agent would have been paused for approval.
+    NeedsApproval,
+    /// The file would have been restored from the base ref.
+    Reverted,
+    /// The text would have been redacted from artifacts.
+    Redacted,
+}
+
+impl fmt::Display for PolicyOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyOutcome::Blocked => write!(f, "blocked"),
+            PolicyOutcome::NeedsApproval => write!(f, "needs approval"),
+            PolicyOutcome::Reverted => write!(f, "reverted"),
+            PolicyOutcome::Redacted => write!(f, "redacted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunFinding {
+    pub agent_key: Option<String>,
+    pub area: PolicyArea,
+    pub outcome: PolicyOutcome,
+    /// Where in the run it was found, e.g. `agents/claude/events.jsonl#12`.
+    pub source: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DryRunReport {
+    pub findings: Vec<DryRunFinding>,
+}
+
+impl DryRunReport {
+    pub fn count(&self, outcome: PolicyOutcome) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.outcome == outcome)
+            .count()
+    }
+}
+
+/// The parts of `[security]` that can be replayed.
+pub struct PolicyDryRun {
+    rules: PathRules,
+    revert_violations: bool,
+    approval: ApprovalPolicy,
+    safe_commands: Vec<String>,
+    allow_network: NetworkAccess,
+    network_allowlist: Vec<String>,
+    redactor: SecretRedactor,
+}
+
+impl PolicyDryRun {
+    pub fn from_config(cfg: &SecurityConfig) -> Self {
+        Self {
+            rules: PathRules::from_config(&cfg.write_policy),
+            revert_violations: cfg.write_policy.revert_violations,
+            approval: cfg.approval.policy,
+            safe_commands: cfg.approval.safe_commands.clone(),
+            allow_network: cfg.allow_network,
+            network_allowlist: cfg.network_allowlist.clone(),
+            redactor: SecretRedactor::from_config(&cfg.redaction),
+        }
+    }
+
+    /// Check recorded events. `worktree` is the agent's worktree, used to
+    /// make absolute tool-call paths relative.
+    pub fn check_events(
+        &self,
+        source: &str,
+        events: &[RunEvent],
+        worktree: Option<&Path>,
+    ) -> Vec<DryRunFinding> {
+        let mut findings = Vec::new();
+        for (index, event) in events.iter().enumerate() {
+            let source = format!("{source}#{}", index + 1);
+            let mut finding = |area, outcome, detail: String| {
+                findings.push(DryRunFinding {
+                    agent_key: event.agent_key.clone(),
+                    area,
+                    outcome,
+                    source: source.clone(),
+                    detail,
+                });
+            };
+
+            if let Ok(AgentEvent::ToolCall { tool, input }) =
+                serde_json::from_value::<AgentEvent>(event.data.clone())
+            {
+                if let Some(violation) =
+                    tool_file_access(&tool, &input).and_then(|(access, path)| {
+                        let root = worktree.map(Path::to_path_buf).unwrap_or_default();
+                        SandboxPolicy::strict(root)
+                            .with_path_rules(self.rules.clone())
+                            .check_access(Path::new(&path), access)
+                    })
+                {
+                    finding(
+                        PolicyArea::WritePolicy,
+                        PolicyOutcome::Blocked,
+                        format!("{tool}: {violation}"),
+                    );
+                }
+                if let Some(action) = dangerous_action(&tool, &input, &self.safe_commands) {
+                    match self.approval {
+                        ApprovalPolicy::AutoApprove => {}
+                        ApprovalPolicy::Ask => finding(
+                            PolicyArea::Approval,
+                            PolicyOutcome::NeedsApproval,
+                            action.to_string(),
+                        ),
+                        ApprovalPolicy::Deny => finding(
+                            PolicyArea::Approval,
+                            PolicyOutcome::Blocked,
+                            action.to_string(),
+                        ),
+                    }
+                }
+            }
+
+            if event.kind == EventKind::SecurityViolation && event.data["kind"] == "network_egress"
+            {
+                let host = event.data["host"].as_str().unwrap_or_default();
+                let allowed = match self.allow_network {
+                    NetworkAccess::Allow => true,
+                    NetworkAccess::Deny => false,
+                    NetworkAccess::Allowlist => host_allowed(&self.network_allowlist, host),
+                };
+                if !allowed {
+                    finding(
+                        PolicyArea::Network,
+                        PolicyOutcome::Blocked,
+                        format!("connect to {host}:{}", event.data["port"]),
+                    );
+                }
+            }
+
+            if let Some(detail) = self.redaction(&event.data.to_string()) {
+                finding(PolicyArea::Redaction, PolicyOutcome::Redacted, detail);
+            }
+        }
+        findings
+    }
+
+    /// Check an agent's saved diff: the files it touched against the write
+    /// policy, and the lines it added against the redaction rules.
+    pub fn check_diff(&self, agent_key: &str, source: &str, patch: &str) -> Vec<DryRunFinding> {
+        let mut findings = Vec::new();
+        let mut finding = |area, outcome, source: String, detail: String| {
+            findings.push(DryRunFinding {
+                agent_key: Some(agent_key.to_string()),
+                area,
+                outcome,
+                source,
+                detail,
+            });
+        };
+
+        for path in patch_paths(patch) {
+            if let Some(violation) = self.rules.check_write(&path) {
+                let outcome = if self.revert_violations {
+                    PolicyOutcome::Reverted
+                } else {
+                    PolicyOutcome::Blocked
+                };
+                finding(
+                    PolicyArea::WritePolicy,
+                    outcome,
+                    source.to_string(),
+                    violation.to_string(),
+                );
+            }
+        }
+        for (index, line) in patch.lines().enumerate() {
+            if !line.starts_with('+') || line.starts_with("+++") {
+                continue;
+            }
+            if let Some(detail) = self.redaction(line) {
+                finding(
+                    PolicyArea::Redaction,
+                    PolicyOutcome::Redacted,
+                    format!("{source}:{}", index + 1),
+                    detail,
+                );
+            }
+        }
+        findings
+    }
+
+    /// The labels the redactor would add to `text`, with the redacted text.
+    fn redaction(&self, text: &str) -> Option<String> {
+        let redacted = self.redactor.redact_line(text);
+        if redacted == text {
+            return None;
+        }
+        let label_re = Regex::new(r"\[REDACTED:([A-Za-z0-9_]+)\]").expect("valid regex");
+        let mut labels: Vec<&str> = label_re
+            .captures_iter(&redacted)
+            .filter_map(|c| c.get(1))
+            .map(|m| m.as_str())
+            .filter(|label| !text.contains(&format!("[REDACTED:{label}]")))
+            .collect();
+        labels.sort_unstable();
+        labels.dedup();
+        let mut excerpt: String = redacted.chars().take(MAX_EXCERPT_CHARS).collect();
+        if redacted.chars().count() > MAX_EXCERPT_CHARS {
+            excerpt.push('…');
+        }
+        Some(format!("{}: {excerpt}", labels.join(", ")))
+    }
+}
+
+/// Replay every recorded event and saved diff of a run through `cfg`.
+pub fn dry_run_policy(
+    layout: &RunLayout,
+    cfg: &SecurityConfig,
+) -> Result<DryRunReport, ArtifactError> {
+    let manifest = RunManifest::read_from(&layout.manifest_path())?;
+    let dry_run = PolicyDryRun::from_config(cfg);
+    let relative = |path: &Path| {
+        path.strip_prefix(layout.base_dir())
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+
+    let mut report = DryRunReport::default();
+    let run_events = read_events(&layout.events_path())?;
+    report.findings.extend(dry_run.check_events(
+        &relative(&layout.events_path()),
+        &run_events,
+        None,
+    ));
+
+    for agent in &manifest.agents {
+        let worktree = agent.worktree_path.as_ref().map(PathBuf::from);
+        let events_path = layout.agent_events(&agent.agent_key);
+        let events = read_events(&events_path)?;
+        report.findings.extend(dry_run.check_events(
+            &relative(&events_path),
+            &events,
+            worktree.as_deref(),
+        ));
+
+        let diff_path = layout.agent_diff(&agent.agent_key);
+        if let Ok(patch) = std::fs::read_to_string(&diff_path) {
+            report.findings.extend(dry_run.check_diff(
+                &agent.agent_key,
+                &relative(&diff_path),
+                &patch,
+            ));
+        }
+    }
+    Ok(report)
+}
+
+fn read_events(path: &Path) -> Result<Vec<RunEvent>, ArtifactError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    EventReader::read_all(path)
+}
+
+/// Paths named in a unified diff's `diff --git` headers, both sides.
+fn patch_paths(patch: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for line in patch.lines() {
+        let Some(rest) = line.strip_prefix("diff --git a/") else {
+            continue;
+        };
+        let Some((old, new)) = rest.split_once(" b/") else {
+            continue;
+        };
+        for path in [old, new] {
+            if !paths.iter().any(|p| p == path) {
+                paths.push(path.to_string());
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ApprovalConfig, RedactionConfig, RedactionPattern, WritePolicyConfig};
+
+    fn config() -> SecurityConfig {
+        SecurityConfig {
+            write_policy: WritePolicyConfig {
+                read_only: vec!["migrations/**".to_string()],
+                forbidden: vec!["**/*.pem".to_string()],
+                revert_violations: false,
+            },
+            approval: ApprovalConfig {
+                policy: ApprovalPolicy::Ask,
+                ..ApprovalConfig::default()
+            },
+            allow_network: NetworkAccess::Allowlist,
+            network_allowlist: vec!["api.anthropic.com".to_string()],
+            redaction: RedactionConfig {
+                patterns: vec![RedactionPattern {
+                    name: "INTERNAL_HOST".to_string(),
+                    regex: r"corp\.internal".to_string(),
+                }],
+                ..RedactionConfig::default()
+            },
+            ..SecurityConfig::default()
+        }
+    }
+
+    fn tool_call(tool: &str, input: serde_json::Value) -> RunEvent {
+        RunEvent::new(
+            EventKind::AgentStdout,
+            Some("claude".to_string()),
+            serde_json::to_value(AgentEvent::ToolCall {
+                tool: tool.to_string(),
+                input,
+            })
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn events_are_replayed_through_each_policy() {
+        let dry_run = PolicyDryRun::from_config(&config());
+        let events = vec![
+            tool_call(
+                "Read",
+                serde_json::json!({ "file_path": "/wt/claude/keys/dev.pem" }),
+            ),
+            tool_call("Bash", serde_json::json!({ "command": "cargo test" })),
+            tool_call(
+                "Bash",
+                serde_json::json!({ "command": "curl https://x.io" }),
+            ),
+            RunEvent::new(
+                EventKind::SecurityViolation,
+                Some("claude".to_string()),
+                serde_json::json!({ "kind": "network_egress", "host": "evil.io", "port": 443 }),
+            ),
+            RunEvent::new(
+                EventKind::SecurityViolation,
+                Some("claude".to_string()),
+                serde_json::json!({ "kind": "network_egress", "host": "api.anthropic.com", "port": 443 }),
+            ),
+            RunEvent::new(
+                EventKind::AgentStdout,
+                Some("claude".to_string()),
+                serde_json::json!({ "line": "pushing to git.corp.internal" }),
+            ),
+        ];
+
+        let findings = dry_run.check_events("events.jsonl", &events, Some(Path::new("/wt/claude")));
+        let summary: Vec<(PolicyArea, PolicyOutcome, &str)> = findings
+            .iter()
+            .map(|f| (f.area, f.outcome, f.source.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    PolicyArea::WritePolicy,
+                    PolicyOutcome::Blocked,
+                    "events.jsonl#1"
+                ),
+                (
+                    PolicyArea::Approval,
+                    PolicyOutcome::NeedsApproval,
+                    "events.jsonl#3"
+                ),
+                (
+                    PolicyArea::Network,
+                    PolicyOutcome::Blocked,
+                    "events.jsonl#4"
+                ),
+                (
+                    PolicyArea::Redaction,
+                    PolicyOutcome::Redacted,
+                    "events.jsonl#6"
+                ),
+            ]
+        );
+        assert!(findings[0].detail.contains("keys/dev.pem"));
+        assert!(findings[3].detail.starts_with("INTERNAL_HOST: "));
+        assert!(!findings[3].detail.contains("corp.internal"));
+    }
+
+    #[test]
+    fn diffs_are_checked_for_paths_and_added_secrets() {
+        let mut cfg = config();
+        cfg.write_policy.revert_violations = true;
+        let dry_run = PolicyDryRun::from_config(&cfg);
+        let patch = "\
+diff --git a/migrations/001.sql b/migrations/001.sql
+--- a/migrations/001.sql
++++ b/migrations/001.sql
+@@ -1 +1 @@
+-create table a;
++drop table a;
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1 +1,2 @@
+ fn main() {}
++// see https://wiki.corp.internal/x
+";
+        let findings = dry_run.check_diff("claude", "agents/claude/diff.patch", patch);
+        let summary: Vec<(PolicyArea, PolicyOutcome, &str)> = findings
+            .iter()
+            .map(|f| (f.area, f.outcome, f.source.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    PolicyArea::WritePolicy,
+                    PolicyOutcome::Reverted,
+                    "agents/claude/diff.patch"
+                ),
+                (
+                    PolicyArea::Redaction,
+                    PolicyOutcome::Redacted,
+                    "agents/claude/diff.patch:12"
+                ),
+            ]
+        );
+    }
+}
@@ -1,5 +1,32 @@
+mod approval;
+mod consent;
+mod container;
+mod dry_run;
+mod injection;
+mod network;
+mod os_sandbox;
 mod redact;
 mod sandbox;
+mod trust;
 
+pub use approval::{dangerous_action, ApprovalDecision, ApprovalPolicy, DangerousAction};
+pub use consent::{ConsentError, ConsentStore, UnsafeConsent};
+pub use container::{ContainerError, ContainerSpec};
+pub use dry_run::{
+    dry_run_policy, DryRunFinding, DryRunReport, PolicyArea, PolicyDryRun, PolicyOutcome,
+};
+pub use injection::{
+    referenced_files, GuardedPrompt, InjectionFinding, PromptGuard, PROMPT_SOURCE,
+};
+#[cfg(target_os = "linux")]
+pub(crate) use network::PrivateNetns;
+pub use network::{
+    host_allowed, private_netns_supported, EgressProxy, NetworkAccess, NetworkViolation,
+};
+pub use os_sandbox::{OsSandbox, OsSandboxMode};
 pub use redact::{RedactedString, SecretRedactor};
-pub use sandbox::{SandboxMode, SandboxPolicy, SandboxResult};
+pub use sandbox::{
+    tool_file_access, FileAccess, PathRule, PathRules, PathViolation, SandboxMode, SandboxPolicy,
+    SandboxResult,
+};
+pub use trust::{TrustError, TrustStore, TrustedRepo, TRUST_FILE_ENV};
@@ -0,0 +1,420 @@
+//! Network egress policy for agent processes (`security.allow_network`).
+//!
+//! `false` cuts an agent off entirely: on Linux it runs in a private
+//! network namespace with no interfaces up; elsewhere (or where
+//! unprivileged user namespaces are disabled) it is pointed at a local
+//! proxy that refuses every request. `"allowlist"` always uses the proxy,
+//! which tunnels connections to allowed hosts and reports the rest as
+//! [`NetworkViolation`]s. The proxy only sees clients that honour the
+//! standard `HTTPS_PROXY`/`HTTP_PROXY` variables, which every supported
+//! agent CLI does.
+
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Largest request head the proxy reads before giving up on a client.
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// `security.allow_network`: `true`, `false` or `"allowlist"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "NetworkAccessRepr", into = "NetworkAccessRepr")]
+pub enum NetworkAccess {
+    #[default]
+    Allow,
+    Deny,
+    /// Only hosts in `security.network_allowlist` are reachable.
+    Allowlist,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum NetworkAccessRepr {
+    Bool(bool),
+    Name(String),
+}
+
+impl TryFrom<NetworkAccessRepr> for NetworkAccess {
+    type Error = String;
+
+    fn try_from(repr: NetworkAccessRepr) -> Result<Self, Self::Error> {
+        match repr {
+            NetworkAccessRepr::Bool(true) => Ok(Self::Allow),
+            NetworkAccessRepr::Bool(false) => Ok(Self::Deny),
+            NetworkAccessRepr::Name(name) if name == "allowlist" => Ok(Self::Allowlist),
+            NetworkAccessRepr::Name(name) => Err(format!(
+                "invalid allow_network value '{name}': expected true, false or \"allowlist\""
+            )),
+        }
+    }
+}
+
+impl From<NetworkAccess> for NetworkAccessRepr {
+    fn from(access: NetworkAccess) -> Self {
+        match access {
+            NetworkAccess::Allow => Self::Bool(true),
+            NetworkAccess::Deny => Self::Bool(false),
+            NetworkAccess::Allowlist => Self::Name("allowlist".to_string()),
+        }
+    }
+}
+
+/// A connection the egress proxy refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkViolation {
+    pub host: String,
+    pub port: u16,
+}
+
+/// Whether `host` matches an allowlist entry. An entry matches itself and
+/// its subdomains; a leading `*.` or `.` is accepted and ignored.
+pub fn host_allowed(allowlist: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowlist.iter().any(|entry| {
+        let entry = entry
+            .trim_start_matches("*.")
+            .trim_start_matches('.')
+            .to_ascii_lowercase();
+        !entry.is_empty()
+            && (host == entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    })
+}
+
+/// Local HTTP proxy that enforces an allowlist for one agent. Stops when
+/// dropped.
+pub struct EgressProxy {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl EgressProxy {
+    /// Listen on an ephemeral loopback port. Refused connections are sent
+    /// on the returned channel.
+    pub async fn start(
+        allowlist: Vec<String>,
+    ) -> std::io::Result<(Self, mpsc::Receiver<NetworkViolation>)> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let (violation_tx, violation_rx) = mpsc::channel(64);
+        let allowlist = std::sync::Arc::new(allowlist);
+
+        let task = tokio::spawn(async move {
+            while let Ok((client, _)) = listener.accept().await {
+                let allowlist = std::sync::Arc::clone(&allowlist);
+                let violation_tx = violation_tx.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve(client, &allowlist, &violation_tx).await {
+                        tracing::debug!(error = %e, "egress proxy connection ended");
+                    }
+                });
+            }
+        });
+
+        Ok((Self { addr, task }, violation_rx))
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Proxy variables to add to the agent's environment.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let url = format!("http://{}", self.addr);
+        let mut env = Vec::new();
+        for key in ["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY"] {
+            env.push((key.to_string(), url.clone()));
+            env.push((key.to_ascii_lowercase(), url.clone()));
+        }
+        // An inherited NO_PROXY would let clients bypass the proxy.
+        env.push(("NO_PROXY".to_string(), String::new()));
+        env.push(("no_proxy".to_string(), String::new()));
+        env
+    }
+}
+
+impl Drop for EgressProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(
+    mut client: TcpStream,
+    allowlist: &[String],
+    violation_tx: &mpsc::Sender<NetworkViolation>,
+) -> std::io::Result<()> {
+    let head = read_request_head(&mut client).await?;
+    let Some((method, host, port)) = parse_target(&head) else {
+        client
+            .write_all(
+                b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .await?;
+        return Ok(());
+    };
+
+    if !host_allowed(allowlist, &host) {
+        let _ = violation_tx.try_send(NetworkViolation { host, port });
+        client
+            .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let mut upstream = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            client
+                .write_all(
+                    b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await?;
+            return Err(e);
+        }
+    };
+    if method.eq_ignore_ascii_case("CONNECT") {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        // Clients may start the TLS handshake without waiting for the reply.
+        let head_end = head.windows(4).position(|w| w == b"\r\n\r\n").unwrap_or(0) + 4;
+        upstream.write_all(&head[head_end..]).await?;
+    } else {
+        upstream.write_all(&head).await?;
+    }
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Read up to and including the blank line ending the request head. Bytes
+/// past it (a request body) are left in the returned buffer.
+async fn read_request_head(client: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 4096];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            return Err(std::io::Error::other("request head too large"));
+        }
+        let n = client.read(&mut buf).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(head)
+}
+
+/// Method, host and port from a proxy request line: `CONNECT host:443`
+/// or an absolute-form `GET http://host[:port]/path`.
+fn parse_target(head: &[u8]) -> Option<(String, String, u16)> {
+    let line = head.split(|&b| b == b'\r').next()?;
+    let line = std::str::from_utf8(line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+
+    let (authority, default_port) = if method.eq_ignore_ascii_case("CONNECT") {
+        (target, 443)
+    } else {
+        let rest = target.strip_prefix("http://")?;
+        (rest.split('/').next()?, 80)
+    };
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']')?;
+        match rest.strip_prefix(':') {
+            Some(port) => (host, port.parse().ok()?),
+            None => (host, default_port),
+        }
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, default_port),
+        }
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some((method, host.to_string(), port))
+}
+
+/// Whether agents can be put in a private network namespace, i.e. the
+/// kernel allows unprivileged user namespaces. Probed once by spawning
+/// `true` in one.
+#[cfg(target_os = "linux")]
+pub fn private_netns_supported() -> bool {
+    use std::os::unix::process::CommandExt;
+    use std::sync::OnceLock;
+
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let mut netns = PrivateNetns::prepare();
+        let mut probe = std::process::Command::new("true");
+        probe
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        unsafe {
+            probe.pre_exec(move || netns.enter());
+        }
+        probe.status().is_ok_and(|status| status.success())
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn private_netns_supported() -> bool {
+    false
+}
+
+/// New user and network namespaces for the child, mapping the caller's
+/// uid and gid to themselves so file ownership in the worktree is
+/// unchanged. The map contents are formatted in the parent; [`enter`]
+/// runs between `fork` and `exec`.
+///
+/// [`enter`]: Self::enter
+#[cfg(target_os = "linux")]
+pub(crate) struct PrivateNetns {
+    uid_map: Vec<u8>,
+    gid_map: Vec<u8>,
+}
+
+#[cfg(target_os = "linux")]
+impl PrivateNetns {
+    pub(crate) fn prepare() -> Self {
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        Self {
+            uid_map: format!("{uid} {uid} 1\n").into_bytes(),
+            gid_map: format!("{gid} {gid} 1\n").into_bytes(),
+        }
+    }
+
+    pub(crate) fn enter(&mut self) -> std::io::Result<()> {
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        write_proc(c"/proc/self/setgroups", b"deny")?;
+        write_proc(c"/proc/self/uid_map", &self.uid_map)?;
+        write_proc(c"/proc/self/gid_map", &self.gid_map)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn write_proc(path: &std::ffi::CStr, contents: &[u8]) -> std::io::Result<()> {
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+        let err = std::io::Error::last_os_error();
+        libc::close(fd);
+        if written != contents.len() as isize {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_matches_hosts_and_subdomains() {
+        let allowlist = vec!["anthropic.com".to_string(), "*.openai.com".to_string()];
+        assert!(host_allowed(&allowlist, "anthropic.com"));
+        assert!(host_allowed(&allowlist, "API.Anthropic.com"));
+        assert!(host_allowed(&allowlist, "api.openai.com"));
+        assert!(host_allowed(&allowlist, "openai.com"));
+        assert!(!host_allowed(&allowlist, "evilanthropic.com"));
+        assert!(!host_allowed(&allowlist, "anthropic.com.evil.net"));
+        assert!(!host_allowed(&[], "anthropic.com"));
+    }
+
+    #[test]
+    fn parses_connect_and_absolute_form_targets() {
+        let parse = |line: &str| parse_target(format!("{line}\r\nHost: x\r\n\r\n").as_bytes());
+        assert_eq!(
+            parse("CONNECT api.anthropic.com:443 HTTP/1.1"),
+            Some(("CONNECT".into(), "api.anthropic.com".into(), 443))
+        );
+        assert_eq!(
+            parse("GET http://example.com/path HTTP/1.1"),
+            Some(("GET".into(), "example.com".into(), 80))
+        );
+        assert_eq!(
+            parse("CONNECT [::1]:8443 HTTP/1.1"),
+            Some(("CONNECT".into(), "::1".into(), 8443))
+        );
+        assert_eq!(parse("GET /relative HTTP/1.1"), None);
+    }
+
+    #[test]
+    fn allow_network_accepts_bool_or_allowlist() {
+        #[derive(Deserialize, Serialize)]
+        struct Cfg {
+            allow_network: NetworkAccess,
+        }
+        let parse = |s: &str| toml::from_str::<Cfg>(s).map(|c| c.allow_network);
+        assert_eq!(parse("allow_network = true").unwrap(), NetworkAccess::Allow);
+        assert_eq!(parse("allow_network = false").unwrap(), NetworkAccess::Deny);
+        assert_eq!(
+            parse("allow_network = \"allowlist\"").unwrap(),
+            NetworkAccess::Allowlist
+        );
+        assert!(parse("allow_network = \"some\"").is_err());
+
+        let rendered = toml::to_string(&Cfg {
+            allow_network: NetworkAccess::Allowlist,
+        })
+        .unwrap();
+        assert_eq!(rendered.trim(), "allow_network = \"allowlist\"");
+    }
+
+    #[tokio::test]
+    async fn proxy_refuses_and_reports_unlisted_hosts() {
+        let upstream = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let upstream_port = upstream.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut conn, _) = upstream.accept().await.unwrap();
+            conn.write_all(b"pong").await.unwrap();
+        });
+
+        let (proxy, mut violations) = EgressProxy::start(vec!["127.0.0.1".to_string()])
+            .await
+            .unwrap();
+
+        let mut client = TcpStream::connect(proxy.addr()).await.unwrap();
+        client
+            .write_all(format!("CONNECT 127.0.0.1:{upstream_port} HTTP/1.1\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).await.unwrap();
+        let reply = String::from_utf8(reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200"));
+        assert!(reply.ends_with("pong"));
+
+        let mut client = TcpStream::connect(proxy.addr()).await.unwrap();
+        client
+            .write_all(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).await.unwrap();
+        assert!(reply.starts_with("HTTP/1.1 403"));
+        assert_eq!(
+            violations.recv().await,
+            Some(NetworkViolation {
+                host: "example.com".to_string(),
+                port: 443,
+            })
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Kernel-enforced confinement for agent processes.
+//!
+//! [`SandboxPolicy`](super::SandboxPolicy) only checks where a command
+//! starts; an agent CLI that ignores its flags could still write anywhere.
+//! On Linux this applies a Landlock ruleset (whole filesystem read-only,
+//! writes allowed only beneath the worktree and a short list of state
+//! directories) and a seccomp filter that refuses host-level syscalls. Both
+//! are built in the parent and installed in the child between `fork` and
+//! `exec`. Other platforms get no enforcement.
+//!
+//! Confinement is opt-in: toolchains that fetch dependencies into `$HOME`
+//! (`~/.cargo`, `~/.npm`, `~/.local`) fail under it unless those directories
+//! are added through `supervisor.sandbox_writable_paths`.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// How hard to insist on kernel enforcement (`supervisor.os_sandbox`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsSandboxMode {
+    /// No kernel enforcement; only the cwd check applies.
+    #[default]
+    Off,
+    /// Enforce what the running kernel supports and carry on otherwise.
+    BestEffort,
+    /// Refuse to start an agent unless Landlock is fully enforced.
+    Required,
+}
+
+/// Per-agent confinement: the writable roots for one worktree.
+#[derive(Debug, Clone)]
+pub struct OsSandbox {
+    mode: OsSandboxMode,
+    writable: Vec<PathBuf>,
+}
+
+impl OsSandbox {
+    /// Writable roots for an agent in `worktree`: the worktree, the parts
+    /// of its git directories a commit on its branch touches (see
+    /// [`GitDirs::writable`]), the temp dir, `/dev`, and the agent CLIs' own
+    /// state under `$HOME`.
+    pub fn for_worktree(worktree: &Path, mode: OsSandboxMode) -> Self {
+        let mut writable = vec![worktree.to_path_buf()];
+        writable.extend(
+            GitDirs::find(worktree)
+                .map(|dirs| dirs.writable())
+                .unwrap_or_default(),
+        );
+        writable.push(std::env::temp_dir());
+        writable.push(PathBuf::from("/dev"));
+        if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+            for entry in AGENT_STATE_PATHS {
+                writable.push(home.join(entry));
+            }
+        }
+        Self { mode, writable }
+    }
+
+    /// Add another writable root (from `supervisor.sandbox_writable_paths`).
+    pub fn allow_write(mut self, path: impl Into<PathBuf>) -> Self {
+        self.writable.push(path.into());
+        self
+    }
+
+    pub fn mode(&self) -> OsSandboxMode {
+        self.mode
+    }
+
+    pub fn writable_paths(&self) -> &[PathBuf] {
+        &self.writable
+    }
+
+    /// Build the kernel objects to install in the child. Paths that do not
+    /// exist are skipped.
+    pub(crate) fn prepare(&self) -> std::io::Result<PreparedOsSandbox> {
+        if self.mode == OsSandboxMode::Off {
+            return Ok(PreparedOsSandbox::default());
+        }
+        imp::prepare(self)
+    }
+}
+
+/// Paths under `$HOME` that agent CLIs write session and auth state to.
+const AGENT_STATE_PATHS: &[&str] = &[".claude", ".claude.json", ".codex", ".cursor", ".cache"];
+
+/// Entries of the common git dir a commit on the agent's branch writes to.
+/// `hooks/` and `config` stay read-only: git on the host runs whatever they
+/// name, outside any sandbox.
+const WRITABLE_COMMON_ENTRIES: &[&str] = &["objects", "refs", "logs"];
+
+/// A linked worktree's git directories, resolved from the `gitdir:` file git
+/// leaves in it.
+pub(super) struct GitDirs {
+    /// `<common>/worktrees/<name>`: the worktree's `HEAD`, index and reflog.
+    pub private: PathBuf,
+    /// The repository's shared git dir.
+    pub common: Option<PathBuf>,
+}
+
+impl GitDirs {
+    pub(super) fn find(worktree: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(worktree.join(".git")).ok()?;
+        let gitdir = contents.strip_prefix("gitdir:")?.trim();
+        let private = worktree.join(gitdir);
+        let common = std::fs::read_to_string(private.join("commondir"))
+            .map(|rel| private.join(rel.trim()))
+            .ok();
+        Some(Self { private, common })
+    }
+
+    /// The private git dir and the common dir's objects, refs and reflogs.
+    pub(super) fn writable(&self) -> Vec<PathBuf> {
+        let common = self.common.iter().flat_map(|common| {
+            WRITABLE_COMMON_ENTRIES
+                .iter()
+                .map(move |entry| common.join(entry))
+        });
+        std::iter::once(self.private.clone())
+            .chain(common)
+            .collect()
+    }
+}
+
+/// Landlock ruleset and seccomp program ready to install. Consumed by the
+/// first [`enforce`](Self::enforce) call.
+#[derive(Default)]
+pub(crate) struct PreparedOsSandbox {
+    #[cfg(target_os = "linux")]
+    inner: Option<imp::Prepared>,
+}
+
+impl PreparedOsSandbox {
+    /// Install the sandbox in the current process. Runs in the forked child
+    /// before `exec`, so it only makes syscalls on pre-built state.
+    pub(crate) fn enforce(&mut self) -> std::io::Result<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(prepared) = self.inner.take() {
+            return imp::enforce(prepared);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::collections::BTreeMap;
+
+    use landlock::{
+        Access, AccessFs, CompatLevel, Compatible, PathBeneath, PathFd, RulesetAttr,
+        RulesetCreated, RulesetCreatedAttr, RulesetStatus, ABI,
+    };
+    use seccompiler::{BpfProgram, SeccompAction, SeccompFilter, TargetArch};
+
+    use super::{OsSandbox, OsSandboxMode, PreparedOsSandbox};
+
+    const LANDLOCK_ABI: ABI = ABI::V3;
+
+    /// Syscalls an agent never needs and that reach beyond its worktree:
+    /// mounting, module loading, rebooting, tracing other processes and
+    /// the kernel keyring.
+    const DENIED_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_reboot,
+        libc::SYS_kexec_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_bpf,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        libc::SYS_keyctl,
+        libc::SYS_open_by_handle_at,
+    ];
+
+    pub(super) struct Prepared {
+        mode: OsSandboxMode,
+        ruleset: RulesetCreated,
+        seccomp: Option<BpfProgram>,
+    }
+
+    pub(super) fn prepare(sandbox: &OsSandbox) -> std::io::Result<PreparedOsSandbox> {
+        let level = match sandbox.mode {
+            OsSandboxMode::Required => CompatLevel::HardRequirement,
+            _ => CompatLevel::BestEffort,
+        };
+        let read = AccessFs::from_read(LANDLOCK_ABI);
+        let all = AccessFs::from_all(LANDLOCK_ABI);
+
+        let mut ruleset = landlock::Ruleset::default()
+            .set_compatibility(level)
+            .handle_access(all)
+            .and_then(|r| r.create())
+            .map_err(std::io::Error::other)?
+            .add_rule(PathBeneath::new(
+                PathFd::new("/").map_err(std::io::Error::other)?,
+                read,
+            ))
+            .map_err(std::io::Error::other)?;
+        for path in &sandbox.writable {
+            let Ok(fd) = PathFd::new(path) else {
+                continue;
+            };
+            // Rules on a regular file may only carry file rights.
+            let access = if path.is_dir() {
+                all
+            } else {
+                all & AccessFs::from_file(LANDLOCK_ABI)
+            };
+            ruleset = ruleset
+                .add_rule(PathBeneath::new(fd, access))
+                .map_err(std::io::Error::other)?;
+        }
+
+        Ok(PreparedOsSandbox {
+            inner: Some(Prepared {
+                mode: sandbox.mode,
+                ruleset,
+                seccomp: seccomp_program(),
+            }),
+        })
+    }
+
+    /// `None` on architectures seccompiler cannot target; Landlock still
+    /// applies there.
+    fn seccomp_program() -> Option<BpfProgram> {
+        let arch = TargetArch::try_from(std::env::consts::ARCH).ok()?;
+        let rules: BTreeMap<i64, Vec<seccompiler::SeccompRule>> =
+            DENIED_SYSCALLS.iter().map(|&nr| (nr, Vec::new())).collect();
+        let filter = SeccompFilter::new(
+            rules,
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::EPERM as u32),
+            arch,
+        )
+        .ok()?;
+        filter.try_into().ok()
+    }
+
+    pub(super) fn enforce(prepared: Prepared) -> std::io::Result<()> {
+        let status = prepared
+            .ruleset
+            .restrict_self()
+            .map_err(std::io::Error::other)?;
+        if prepared.mode == OsSandboxMode::Required
+            && status.ruleset != RulesetStatus::FullyEnforced
+        {
+            return Err(std::io::Error::other(
+                "Landlock is not fully supported by this kernel",
+            ));
+        }
+        if let Some(program) = prepared.seccomp {
+            // Landlock sets no_new_privs, but not when the kernel lacks it.
+            if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            seccompiler::apply_filter(&program).map_err(std::io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::{OsSandbox, PreparedOsSandbox};
+
+    pub(super) fn prepare(sandbox: &OsSandbox) -> std::io::Result<PreparedOsSandbox> {
+        if sandbox.mode == super::OsSandboxMode::Required {
+            return Err(std::io::Error::other(
+                "OS sandboxing is only available on Linux",
+            ));
+        }
+        Ok(PreparedOsSandbox::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn writable_roots_include_worktree_and_git_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let common = tmp.path().join("repo/.git");
+        let private = common.join("worktrees/wt");
+        std::fs::create_dir_all(&private).unwrap();
+        std::fs::write(private.join("commondir"), "../..\n").unwrap();
+        let worktree = tmp.path().join("wt");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", private.display()),
+        )
+        .unwrap();
+
+        let sandbox =
+            OsSandbox::for_worktree(&worktree, OsSandboxMode::BestEffort).allow_write("/opt/cache");
+        let paths = sandbox.writable_paths();
+        let common = private.join("../..");
+        assert_eq!(paths[0], worktree);
+        assert_eq!(paths[1], private);
+        assert_eq!(
+            paths[2..5],
+            [
+                common.join("objects"),
+                common.join("refs"),
+                common.join("logs")
+            ]
+        );
+        assert!(!paths.contains(&common));
+        assert!(paths.contains(&std::env::temp_dir()));
+        assert_eq!(paths.last().unwrap(), Path::new("/opt/cache"));
+    }
+
+    #[test]
+    fn off_mode_prepares_nothing() {
+        let sandbox = OsSandbox::for_worktree(Path::new("/nonexistent"), OsSandboxMode::Off);
+        let mut prepared = sandbox.prepare().unwrap();
+        prepared.enforce().unwrap();
+    }
+
+    /// Landlock ABI version of the running kernel, 0 if unsupported.
+    #[cfg(target_os = "linux")]
+    fn landlock_abi() -> i64 {
+        const LANDLOCK_CREATE_RULESET_VERSION: libc::c_long = 1;
+        let version = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<u8>(),
+                0usize,
+                LANDLOCK_CREATE_RULESET_VERSION,
+            )
+        };
+        version.max(0)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn child_cannot_write_outside_writable_roots() {
+        use std::os::unix::process::CommandExt;
+
+        let tmp = TempDir::new().unwrap();
+        let inside = tmp.path().join("inside");
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir_all(&inside).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+
+        let sandbox = OsSandbox {
+            mode: OsSandboxMode::BestEffort,
+            writable: vec![inside.clone(), PathBuf::from("/dev")],
+        };
+        let mut prepared = sandbox.prepare().unwrap();
+        let status = unsafe {
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg("echo ok > inside/a; echo no > outside/b")
+                .current_dir(tmp.path())
+                .stderr(std::process::Stdio::null())
+                .pre_exec(move || prepared.enforce())
+                .status()
+                .unwrap()
+        };
+
+        assert!(inside.join("a").exists());
+        if landlock_abi() > 0 {
+            assert!(!status.success());
+            assert!(!outside.join("b").exists());
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn child_cannot_write_git_hooks_or_config() {
+        use std::os::unix::process::CommandExt;
+
+        let tmp = TempDir::new().unwrap();
+        let common = tmp.path().join("repo/.git");
+        let private = common.join("worktrees/wt");
+        for dir in ["hooks", "objects", "refs", "logs"] {
+            std::fs::create_dir_all(common.join(dir)).unwrap();
+        }
+        std::fs::create_dir_all(&private).unwrap();
+        std::fs::write(private.join("commondir"), "../..\n").unwrap();
+        let worktree = tmp.path().join("wt");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", private.display()),
+        )
+        .unwrap();
+
+        // The git dirs `for_worktree` allows, without the temp dir this
+        // test lives in.
+        let mut writable = GitDirs::find(&worktree).unwrap().writable();
+        writable.push(PathBuf::from("/dev"));
+        let sandbox = OsSandbox {
+            mode: OsSandboxMode::BestEffort,
+            writable,
+        };
+        let mut prepared = sandbox.prepare().unwrap();
+        let status = unsafe {
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg("echo x > objects/ab; echo x > hooks/pre-commit; echo x >> config")
+                .current_dir(&common)
+                .stderr(std::process::Stdio::null())
+                .pre_exec(move || prepared.enforce())
+                .status()
+                .unwrap()
+        };
+
+        assert!(common.join("objects/ab").exists());
+        if landlock_abi() > 0 {
+            assert!(!status.success());
+            assert!(!common.join("hooks/pre-commit").exists());
+            assert!(!common.join("config").exists());
+        }
+    }
+}
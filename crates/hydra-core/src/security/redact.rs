@@ -1,5 +1,10 @@
 use std::borrow::Cow;
 
+use regex::Regex;
+use serde::Serialize;
+
+use crate::config::RedactionConfig;
+
 /// Secret patterns that should be redacted from logs and artifacts.
 ///
 /// Patterns are applied in order; first match wins per token.
@@ -63,16 +68,49 @@ pub struct RedactedString {
     pub redaction_count: usize,
 }
 
+/// Label used for strings caught by entropy detection.
+const HIGH_ENTROPY_LABEL: &str = "HIGH_ENTROPY";
+
 /// Redacts known secret patterns from text.
+#[derive(Debug, Clone)]
 pub struct SecretRedactor {
     custom_patterns: Vec<(String, String)>,
+    regex_patterns: Vec<(Regex, String)>,
+    entropy: Option<EntropyRule>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EntropyRule {
+    threshold: f64,
+    min_length: usize,
 }
 
 impl SecretRedactor {
     pub fn new() -> Self {
         Self {
             custom_patterns: Vec::new(),
+            regex_patterns: Vec::new(),
+            entropy: None,
+        }
+    }
+
+    /// Built-in patterns plus the user patterns and entropy detection from
+    /// `security.redaction`. Patterns that fail to compile are skipped;
+    /// config validation rejects them before a run starts.
+    pub fn from_config(cfg: &RedactionConfig) -> Self {
+        let mut redactor = Self::new();
+        for pattern in &cfg.patterns {
+            match Regex::new(&pattern.regex) {
+                Ok(regex) => redactor.add_regex(regex, pattern.name.clone()),
+                Err(e) => {
+                    tracing::warn!(name = %pattern.name, error = %e, "skipping invalid redaction pattern")
+                }
+            }
+        }
+        if cfg.entropy_detection {
+            redactor.detect_high_entropy(cfg.entropy_threshold, cfg.entropy_min_length);
         }
+        redactor
     }
 
     /// Add a custom pattern: any occurrence of `pattern` is replaced with `label`.
@@ -80,6 +118,22 @@ impl SecretRedactor {
         self.custom_patterns.push((pattern, label));
     }
 
+    /// Add a regex pattern: every match is replaced with `label`.
+    pub fn add_regex(&mut self, regex: Regex, label: String) {
+        self.regex_patterns.push((regex, label));
+    }
+
+    /// Also redact token-like strings of at least `min_length` characters
+    /// whose Shannon entropy is at least `threshold` bits per character.
+    /// Only strings mixing upper case, lower case and digits qualify, so
+    /// hex digests (at most 4 bits per character) and identifiers are kept.
+    pub fn detect_high_entropy(&mut self, threshold: f64, min_length: usize) {
+        self.entropy = Some(EntropyRule {
+            threshold,
+            min_length,
+        });
+    }
+
     /// Redact secrets from a single line of text.
     pub fn redact_line<'a>(&self, input: &'a str) -> Cow<'a, str> {
         let mut output = input.to_string();
@@ -106,6 +160,23 @@ impl SecretRedactor {
             }
         }
 
+        for (regex, label) in &self.regex_patterns {
+            if regex.is_match(&output) {
+                let replacement = format!("[REDACTED:{label}]");
+                output = regex
+                    .replace_all(&output, regex::NoExpand(&replacement))
+                    .into_owned();
+                changed = true;
+            }
+        }
+
+        if let Some(rule) = self.entropy {
+            if let Some(redacted) = redact_high_entropy(&output, rule) {
+                output = redacted;
+                changed = true;
+            }
+        }
+
         if changed {
             Cow::Owned(output)
         } else {
@@ -113,25 +184,31 @@ impl SecretRedactor {
         }
     }
 
-    /// Redact all lines in a multi-line string.
+    /// Redact all lines in a multi-line string. Line endings are kept, so
+    /// patches and logs stay byte-identical apart from the redactions.
     pub fn redact(&self, input: &str) -> RedactedString {
         let mut count = 0;
-        let output: Vec<String> = input
-            .lines()
-            .map(|line| {
-                let redacted = self.redact_line(line);
-                if let Cow::Owned(_) = &redacted {
-                    count += 1;
-                }
-                redacted.into_owned()
-            })
-            .collect();
+        let mut value = String::with_capacity(input.len());
+        for line in input.split_inclusive('\n') {
+            let redacted = self.redact_line(line);
+            if let Cow::Owned(_) = &redacted {
+                count += 1;
+            }
+            value.push_str(&redacted);
+        }
 
         RedactedString {
-            value: output.join("\n"),
+            value,
             redaction_count: count,
         }
     }
+
+    /// Serialize `value` as pretty JSON with secrets redacted, for JSON
+    /// artifacts that embed command output.
+    pub fn redact_json<T: Serialize + ?Sized>(&self, value: &T) -> serde_json::Result<String> {
+        let json = serde_json::to_string_pretty(value)?;
+        Ok(self.redact(&json).value)
+    }
 }
 
 impl Default for SecretRedactor {
@@ -157,6 +234,70 @@ fn find_token_end(s: &str, start: usize) -> usize {
         .unwrap_or(s.len())
 }
 
+/// Characters that make up a candidate token for entropy detection
+/// (base64, base64url and common token alphabets). `=` is left out so
+/// `key=value` pairs split; base64 padding does not affect the score.
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '_' | '-')
+}
+
+fn redact_high_entropy(input: &str, rule: EntropyRule) -> Option<String> {
+    let replacement = format!("[REDACTED:{HIGH_ENTROPY_LABEL}]");
+    let mut output = String::new();
+    let mut copied = 0;
+    let mut start = None;
+
+    for (i, c) in input
+        .char_indices()
+        .chain(std::iter::once((input.len(), ' ')))
+    {
+        match (start, is_token_char(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                let token = &input[s..i];
+                if token.len() >= rule.min_length && looks_random(token, rule.threshold) {
+                    output.push_str(&input[copied..s]);
+                    output.push_str(&replacement);
+                    copied = i;
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if copied == 0 {
+        return None;
+    }
+    output.push_str(&input[copied..]);
+    Some(output)
+}
+
+fn looks_random(token: &str, threshold: f64) -> bool {
+    let has = |pred: fn(&u8) -> bool| token.as_bytes().iter().any(pred);
+    has(u8::is_ascii_uppercase)
+        && has(u8::is_ascii_lowercase)
+        && has(u8::is_ascii_digit)
+        && shannon_entropy(token) >= threshold
+}
+
+/// Shannon entropy of `token` in bits per byte.
+fn shannon_entropy(token: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for &b in token.as_bytes() {
+        counts[b as usize] += 1;
+    }
+    let len = token.len() as f64;
+    counts
+        .iter()
+        .filter(|&&n| n > 0)
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,4 +430,55 @@ mod tests {
         assert_eq!(output.matches("[REDACTED:CUSTOM]").count(), 2);
         assert!(!output.contains("abc123"));
     }
+
+    #[test]
+    fn regex_pattern_redaction() {
+        let mut r = SecretRedactor::new();
+        r.add_regex(
+            Regex::new(r"itk_[A-Za-z0-9]{8}").unwrap(),
+            "INTERNAL_TOKEN".to_string(),
+        );
+        let output = r.redact_line("a=itk_abcd1234 b=itk_short");
+        assert_eq!(output, "a=[REDACTED:INTERNAL_TOKEN] b=itk_short");
+    }
+
+    #[test]
+    fn entropy_detection_redacts_random_tokens_only() {
+        let mut r = SecretRedactor::new();
+        r.detect_high_entropy(4.5, 24);
+        let secret = "Zq8vR2mT9xLp4KwN7bYc3HdF6sJg";
+        let line = format!("token={secret} ok");
+        let output = r.redact_line(&line);
+        assert_eq!(output, "token=[REDACTED:HIGH_ENTROPY] ok");
+
+        for kept in [
+            "commit 3f2a9c1d8e7b6a5f4e3d2c1b0a9f8e7d6c5b4a39",
+            "id 550e8400-e29b-41d4-a716-446655440000",
+            "fn compute_weighted_score_for_agent_ranking()",
+            "short Ab1Cd2Ef3",
+        ] {
+            assert!(matches!(r.redact_line(kept), Cow::Borrowed(_)), "{kept}");
+        }
+    }
+
+    #[test]
+    fn redact_keeps_line_endings() {
+        let r = SecretRedactor::new();
+        let result = r.redact("+key = sk-ant-abc\n context\n");
+        assert_eq!(result.value, "+key = [REDACTED:ANTHROPIC_KEY]\n context\n");
+    }
+
+    #[test]
+    fn from_config_applies_patterns_and_entropy() {
+        let cfg = RedactionConfig {
+            patterns: vec![crate::config::RedactionPattern {
+                name: "ACME".to_string(),
+                regex: "acme-[0-9]+".to_string(),
+            }],
+            ..Default::default()
+        };
+        let r = SecretRedactor::from_config(&cfg);
+        let output = r.redact_line("acme-42 Zq8vR2mT9xLp4KwN7bYc3HdF6sJg");
+        assert_eq!(output, "[REDACTED:ACME] [REDACTED:HIGH_ENTROPY]");
+    }
 }
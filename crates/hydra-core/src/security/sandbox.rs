@@ -1,5 +1,10 @@
 use std::path::{Path, PathBuf};
 
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::WritePolicyConfig;
+
 /// Sandbox enforcement mode for agent processes.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SandboxMode {
@@ -13,6 +18,7 @@ pub enum SandboxMode {
 pub struct SandboxPolicy {
     mode: SandboxMode,
     allowed_root: PathBuf,
+    path_rules: PathRules,
 }
 
 impl SandboxPolicy {
@@ -20,6 +26,7 @@ impl SandboxPolicy {
         Self {
             mode: SandboxMode::Strict,
             allowed_root: worktree_root,
+            path_rules: PathRules::default(),
         }
     }
 
@@ -31,6 +38,37 @@ impl SandboxPolicy {
         Self {
             mode: SandboxMode::Unsafe,
             allowed_root: worktree_root,
+            path_rules: PathRules::default(),
+        }
+    }
+
+    /// Add read-only and forbidden globs inside the worktree. They apply in
+    /// both modes.
+    pub fn with_path_rules(mut self, rules: PathRules) -> Self {
+        self.path_rules = rules;
+        self
+    }
+
+    pub fn path_rules(&self) -> &PathRules {
+        &self.path_rules
+    }
+
+    /// Check an agent's file access against the path rules. `target` may be
+    /// absolute or relative to the worktree; paths outside it are left to
+    /// [`check_path`](Self::check_path).
+    pub fn check_access(&self, target: &Path, access: FileAccess) -> Option<PathViolation> {
+        let relative = if target.is_absolute() {
+            normalize_abs_path(target)
+                .strip_prefix(normalize_abs_path(&self.allowed_root))
+                .ok()?
+                .to_path_buf()
+        } else {
+            target.to_path_buf()
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        match access {
+            FileAccess::Read => self.path_rules.check_read(&relative),
+            FileAccess::Write => self.path_rules.check_write(&relative),
         }
     }
 
@@ -95,6 +133,142 @@ fn normalize_abs_path(path: &Path) -> PathBuf {
     normalized
 }
 
+/// Glob rules for paths inside a worktree (`security.write_policy`).
+///
+/// `*` and `?` stay within one path component and `**` spans any number of
+/// them, so `**/*.pem` matches at any depth while `*.pem` only matches at
+/// the root.
+#[derive(Debug, Clone, Default)]
+pub struct PathRules {
+    read_only: Vec<(String, Regex)>,
+    forbidden: Vec<(String, Regex)>,
+}
+
+/// Which rule a path broke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathRule {
+    /// Matched `read_only` and was modified.
+    ReadOnly,
+    /// Matched `forbidden` and was read or modified.
+    Forbidden,
+}
+
+impl std::fmt::Display for PathRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathRule::ReadOnly => write!(f, "read-only"),
+            PathRule::Forbidden => write!(f, "forbidden"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathViolation {
+    /// Relative to the worktree root.
+    pub path: String,
+    pub rule: PathRule,
+    /// The glob that matched.
+    pub pattern: String,
+}
+
+impl std::fmt::Display for PathViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} path {} (matches {})",
+            self.rule, self.path, self.pattern
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAccess {
+    Read,
+    Write,
+}
+
+impl PathRules {
+    pub fn from_config(cfg: &WritePolicyConfig) -> Self {
+        let compile = |globs: &[String]| {
+            globs
+                .iter()
+                .map(|glob| (glob.clone(), glob_regex(glob)))
+                .collect()
+        };
+        Self {
+            read_only: compile(&cfg.read_only),
+            forbidden: compile(&cfg.forbidden),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read_only.is_empty() && self.forbidden.is_empty()
+    }
+
+    /// A write to (or deletion of) `path`, relative to the worktree root.
+    pub fn check_write(&self, path: &str) -> Option<PathViolation> {
+        self.check_read(path)
+            .or_else(|| matching(&self.read_only, path, PathRule::ReadOnly))
+    }
+
+    /// A read of `path`; only `forbidden` paths may not be read.
+    pub fn check_read(&self, path: &str) -> Option<PathViolation> {
+        matching(&self.forbidden, path, PathRule::Forbidden)
+    }
+}
+
+fn matching(rules: &[(String, Regex)], path: &str, rule: PathRule) -> Option<PathViolation> {
+    let path = path.trim_start_matches("./");
+    rules
+        .iter()
+        .find(|(_, re)| re.is_match(path))
+        .map(|(glob, _)| PathViolation {
+            path: path.to_string(),
+            rule,
+            pattern: glob.clone(),
+        })
+}
+
+fn glob_regex(glob: &str) -> Regex {
+    let mut re = String::from("^");
+    let mut chars = glob.trim_start_matches("./").chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    re.push('$');
+    Regex::new(&re).expect("escaped glob is a valid regex")
+}
+
+/// The file a reported tool call reads or writes, for tools that name one.
+pub fn tool_file_access(tool: &str, input: &serde_json::Value) -> Option<(FileAccess, String)> {
+    let access = match tool.to_ascii_lowercase().as_str() {
+        "read" | "read_file" | "view" | "cat" => FileAccess::Read,
+        "edit" | "multiedit" | "write" | "write_file" | "edit_file" | "create_file"
+        | "str_replace" | "str_replace_editor" | "notebookedit" | "delete_file" => {
+            FileAccess::Write
+        }
+        _ => return None,
+    };
+    let path = ["file_path", "path", "target_file", "notebook_path"]
+        .iter()
+        .find_map(|key| input.get(key).and_then(|v| v.as_str()))?;
+    Some((access, path.to_string()))
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum SandboxResult {
     Allowed,
@@ -204,6 +378,54 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn path_rules_match_globs() {
+        let rules = PathRules::from_config(&WritePolicyConfig {
+            read_only: vec!["migrations/**".to_string()],
+            forbidden: vec!["**/*.pem".to_string(), ".env".to_string()],
+            revert_violations: false,
+        });
+
+        let violation = rules.check_write("migrations/001_init.sql").unwrap();
+        assert_eq!(violation.rule, PathRule::ReadOnly);
+        assert_eq!(violation.pattern, "migrations/**");
+        assert!(rules.check_read("migrations/001_init.sql").is_none());
+
+        assert_eq!(
+            rules.check_read("certs/dev/key.pem").unwrap().rule,
+            PathRule::Forbidden
+        );
+        assert!(rules.check_write("key.pem").is_some());
+        assert!(rules.check_write(".env").is_some());
+        assert!(rules.check_write("config/.env").is_none());
+        assert!(rules.check_write("src/migrations.rs").is_none());
+    }
+
+    #[test]
+    fn check_access_resolves_paths_against_the_worktree() {
+        let root = PathBuf::from("/repo/.hydra/worktrees/run/claude");
+        let policy = SandboxPolicy::strict(root.clone()).with_path_rules(PathRules::from_config(
+            &WritePolicyConfig {
+                read_only: vec!["Cargo.lock".to_string()],
+                ..Default::default()
+            },
+        ));
+
+        assert!(policy
+            .check_access(&root.join("Cargo.lock"), FileAccess::Write)
+            .is_some());
+        assert!(policy
+            .check_access(Path::new("Cargo.lock"), FileAccess::Read)
+            .is_none());
+        assert!(policy
+            .check_access(Path::new("/elsewhere/Cargo.lock"), FileAccess::Write)
+            .is_none());
+        assert_eq!(
+            tool_file_access("Edit", &serde_json::json!({ "file_path": "Cargo.lock" })),
+            Some((FileAccess::Write, "Cargo.lock".to_string()))
+        );
+    }
+
     #[test]
     fn path_traversal_with_dotdot_is_blocked() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,181 @@
+//! Workspace trust.
+//!
+//! Racing agents or scoring a candidate runs the repository's own build and
+//! test commands, so pointing hydra at a freshly cloned, hostile repository
+//! would execute whatever it ships. Front ends therefore refuse to spawn
+//! agents or run scoring in a repository until the user has trusted it.
+//! The trusted list lives in the user's config directory rather than in the
+//! repository, so a repository cannot vouch for itself.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Overrides the location of the trusted-repos list.
+pub const TRUST_FILE_ENV: &str = "HYDRA_TRUST_FILE";
+
+#[derive(Debug, Error)]
+pub enum TrustError {
+    #[error(
+        "repository {} is not trusted; run `hydra trust add` there to allow agents and scoring",
+        repo_root.display()
+    )]
+    Untrusted { repo_root: PathBuf },
+
+    #[error("cannot locate the trusted repositories list: set HOME or {TRUST_FILE_ENV}")]
+    NoTrustFile,
+
+    #[error("trust list I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("trust list is corrupt: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedRepo {
+    pub path: PathBuf,
+    pub trusted_at: DateTime<Utc>,
+}
+
+/// The user's list of trusted repositories.
+#[derive(Debug, Clone)]
+pub struct TrustStore {
+    path: PathBuf,
+}
+
+impl TrustStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `$HYDRA_TRUST_FILE`, else `trusted-repos.json` under
+    /// `$XDG_CONFIG_HOME/hydra` or `~/.config/hydra`.
+    pub fn user_default() -> Result<Self, TrustError> {
+        if let Some(path) = std::env::var_os(TRUST_FILE_ENV).filter(|p| !p.is_empty()) {
+            return Ok(Self::new(path));
+        }
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .or_else(|| std::env::var_os("USERPROFILE"))
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })
+            .ok_or(TrustError::NoTrustFile)?;
+        Ok(Self::new(
+            config_dir.join("hydra").join("trusted-repos.json"),
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn list(&self) -> Result<Vec<TrustedRepo>, TrustError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(data) => Ok(serde_json::from_str(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The entry for `repo_root`, if it is trusted.
+    pub fn entry(&self, repo_root: &Path) -> Result<Option<TrustedRepo>, TrustError> {
+        let key = repo_key(repo_root);
+        Ok(self.list()?.into_iter().find(|repo| repo.path == key))
+    }
+
+    pub fn is_trusted(&self, repo_root: &Path) -> Result<bool, TrustError> {
+        Ok(self.entry(repo_root)?.is_some())
+    }
+
+    pub fn require_trusted(&self, repo_root: &Path) -> Result<(), TrustError> {
+        if self.is_trusted(repo_root)? {
+            Ok(())
+        } else {
+            Err(TrustError::Untrusted {
+                repo_root: repo_key(repo_root),
+            })
+        }
+    }
+
+    /// Trust `repo_root`. Trusting it again keeps the original entry.
+    pub fn trust(&self, repo_root: &Path) -> Result<TrustedRepo, TrustError> {
+        let key = repo_key(repo_root);
+        let mut repos = self.list()?;
+        if let Some(existing) = repos.iter().find(|repo| repo.path == key) {
+            return Ok(existing.clone());
+        }
+        let entry = TrustedRepo {
+            path: key,
+            trusted_at: Utc::now(),
+        };
+        repos.push(entry.clone());
+        self.save(&repos)?;
+        Ok(entry)
+    }
+
+    /// Stop trusting `repo_root`. Returns `false` if it was not trusted.
+    pub fn revoke(&self, repo_root: &Path) -> Result<bool, TrustError> {
+        let key = repo_key(repo_root);
+        let mut repos = self.list()?;
+        let before = repos.len();
+        repos.retain(|repo| repo.path != key);
+        if repos.len() == before {
+            return Ok(false);
+        }
+        self.save(&repos)?;
+        Ok(true)
+    }
+
+    fn save(&self, repos: &[TrustedRepo]) -> Result<(), TrustError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(repos)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Repositories are identified by their canonical path, so symlinked or
+/// relative spellings of the same checkout share one entry.
+fn repo_key(repo_root: &Path) -> PathBuf {
+    repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn repos_must_be_trusted_explicitly() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let store = TrustStore::new(tmp.path().join("config/trusted-repos.json"));
+
+        assert!(matches!(
+            store.require_trusted(&repo),
+            Err(TrustError::Untrusted { .. })
+        ));
+
+        let entry = store.trust(&repo).unwrap();
+        assert_eq!(store.trust(&repo.join("src/..")).unwrap(), entry);
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.require_trusted(&repo).is_ok());
+        assert!(!store.is_trusted(&repo.join("src")).unwrap());
+
+        assert!(store.revoke(&repo).unwrap());
+        assert!(!store.revoke(&repo).unwrap());
+        assert!(!store.is_trusted(&repo).unwrap());
+    }
+}
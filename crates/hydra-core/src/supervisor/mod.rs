@@ -1,14 +1,18 @@
 pub mod pty;
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
 use thiserror::Error;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
 use tokio::process::Command;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Notify};
 
-use crate::adapter::{AgentEvent, BuiltCommand};
+use crate::adapter::{AgentEvent, BuiltCommand, CommandStep};
+use crate::security::{ContainerError, ContainerSpec, OsSandbox};
 
 #[derive(Debug, Error)]
 pub enum SupervisorError {
@@ -26,6 +30,12 @@ pub enum SupervisorError {
 
     #[error("I/O error during supervision: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("failed to set up OS sandbox: {0}")]
+    SandboxSetup(std::io::Error),
+
+    #[error(transparent)]
+    Container(#[from] ContainerError),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -52,6 +62,17 @@ pub enum SupervisorEvent {
     Stdout(String),
     Stderr(String),
     AgentEvent(AgentEvent),
+    /// A line printed by a chain step, kept apart from the agent's output.
+    StepOutput {
+        label: String,
+        line: String,
+        stderr: bool,
+    },
+    /// A chain step exited successfully.
+    StepCompleted {
+        label: String,
+        duration: Duration,
+    },
     Completed {
         exit_code: i32,
         duration: Duration,
@@ -74,6 +95,15 @@ pub struct SupervisorPolicy {
     pub hard_timeout: Duration,
     pub idle_timeout: Duration,
     pub output_buffer_bytes: usize,
+    /// Kernel confinement installed in the child before `exec`.
+    pub sandbox: Option<OsSandbox>,
+    /// Run the command inside this container instead of on the host.
+    pub container: Option<ContainerSpec>,
+    /// Start the child in a private network namespace with no interfaces
+    /// up (Linux only; see [`private_netns_supported`]).
+    ///
+    /// [`private_netns_supported`]: crate::security::private_netns_supported
+    pub isolate_network: bool,
 }
 
 impl Default for SupervisorPolicy {
@@ -82,6 +112,9 @@ impl Default for SupervisorPolicy {
             hard_timeout: Duration::from_secs(1800),
             idle_timeout: Duration::from_secs(300),
             output_buffer_bytes: 10 * 1024 * 1024,
+            sandbox: None,
+            container: None,
+            isolate_network: false,
         }
     }
 }
@@ -92,13 +125,30 @@ impl SupervisorPolicy {
             hard_timeout: Duration::from_secs(cfg.hard_timeout_seconds),
             idle_timeout: Duration::from_secs(cfg.idle_timeout_seconds),
             output_buffer_bytes: cfg.output_buffer_bytes,
+            sandbox: None,
+            container: None,
+            isolate_network: false,
         }
     }
 }
 
-/// Handle to a running supervised process, used for cancellation.
+/// Handle to a running supervised process, used for cancellation and for
+/// pausing it while an action awaits approval.
 pub struct SupervisorHandle {
     cancel_tx: mpsc::Sender<()>,
+    /// Set once the agent itself is running, after any setup steps.
+    pid: Arc<OnceLock<u32>>,
+    container: Option<ContainerSpec>,
+    pause: Arc<PauseState>,
+}
+
+/// Shared between a [`SupervisorHandle`] and the idle timeout.
+#[derive(Default)]
+struct PauseState {
+    paused: AtomicBool,
+    /// Signalled on resume so the idle timer starts over rather than
+    /// counting the time spent paused.
+    resumed: Notify,
 }
 
 impl SupervisorHandle {
@@ -106,14 +156,64 @@ impl SupervisorHandle {
     pub async fn cancel(&self) {
         let _ = self.cancel_tx.send(()).await;
     }
+
+    /// Stop the process group (or pause its container). The idle timeout
+    /// does not run while paused; the hard timeout does.
+    pub async fn pause(&self) {
+        self.pause.paused.store(true, Ordering::SeqCst);
+        match &self.container {
+            Some(container) => run_container_command(container, "pause").await,
+            None => self.signal_group(SIGNAL_STOP),
+        }
+    }
+
+    /// Continue a process paused with [`pause`](Self::pause).
+    pub async fn resume(&self) {
+        match &self.container {
+            Some(container) => run_container_command(container, "unpause").await,
+            None => self.signal_group(SIGNAL_CONT),
+        }
+        self.pause.paused.store(false, Ordering::SeqCst);
+        self.pause.resumed.notify_one();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause.paused.load(Ordering::SeqCst)
+    }
+
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    fn signal_group(&self, signal: i32) {
+        #[cfg(unix)]
+        if let Some(&pid) = self.pid.get() {
+            if unsafe { libc::kill(-(pid as i32), signal) } != 0 {
+                tracing::debug!(
+                    pid,
+                    signal,
+                    error = %std::io::Error::last_os_error(),
+                    "failed to signal process group"
+                );
+            }
+        }
+    }
 }
 
+#[cfg(unix)]
+const SIGNAL_STOP: i32 = libc::SIGSTOP;
+#[cfg(unix)]
+const SIGNAL_CONT: i32 = libc::SIGCONT;
+#[cfg(not(unix))]
+const SIGNAL_STOP: i32 = 0;
+#[cfg(not(unix))]
+const SIGNAL_CONT: i32 = 0;
+
 /// Spawn and supervise a single agent process.
 ///
 /// Returns a handle for cancellation and streams events via the provided channel.
 /// The line_parser closure converts raw stdout lines into optional `AgentEvent`s.
+/// The command's `pre` steps run first and its `post` steps after a successful
+/// exit, all within the hard timeout.
 pub async fn supervise<F>(
-    cmd: BuiltCommand,
+    mut cmd: BuiltCommand,
     policy: SupervisorPolicy,
     event_tx: mpsc::Sender<SupervisorEvent>,
     line_parser: F,
@@ -121,31 +221,78 @@ pub async fn supervise<F>(
 where
     F: Fn(&str) -> Option<AgentEvent> + Send + 'static,
 {
-    let mut child = build_process(&cmd)?;
-
-    let pid = child.id().unwrap_or(0);
-    let _ = event_tx.send(SupervisorEvent::Started { pid }).await;
-
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or_else(|| SupervisorError::Io(std::io::Error::other("stdout not captured")))?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| SupervisorError::Io(std::io::Error::other("stderr not captured")))?;
+    let pre = std::mem::take(&mut cmd.pre);
+    let post = std::mem::take(&mut cmd.post);
+    // Without setup steps the agent starts right away, so a failed spawn is
+    // returned to the caller rather than reported as an event.
+    let child = if pre.is_empty() {
+        Some(spawn_agent(&cmd, &policy)?)
+    } else {
+        None
+    };
 
     let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+    let pause = Arc::new(PauseState::default());
+    let pid = Arc::new(OnceLock::new());
+    if let Some(child) = &child {
+        started(child, &pid, &event_tx).await;
+    }
     let handle = SupervisorHandle {
         cancel_tx: cancel_tx.clone(),
+        pid: Arc::clone(&pid),
+        container: policy.container.clone(),
+        pause: Arc::clone(&pause),
     };
 
-    let hard_timeout = policy.hard_timeout;
-    let idle_timeout = policy.idle_timeout;
-    let max_buffer = policy.output_buffer_bytes;
-
+    let start = Instant::now();
+    let deadline = tokio::time::Instant::now() + policy.hard_timeout;
     tokio::spawn(async move {
-        let start = Instant::now();
+        let mut chain = Chain {
+            cwd: cmd.cwd.clone(),
+            env: cmd.env.clone(),
+            policy: &policy,
+            event_tx: &event_tx,
+            start,
+            deadline,
+        };
+        let mut child = match child {
+            Some(child) => child,
+            None => {
+                if !chain.run(&pre, &mut cancel_rx).await {
+                    return;
+                }
+                cmd.env = chain.env.clone();
+                match spawn_agent(&cmd, &policy) {
+                    Ok(child) => {
+                        started(&child, &pid, &event_tx).await;
+                        child
+                    }
+                    Err(e) => {
+                        let _ = event_tx
+                            .send(SupervisorEvent::Failed {
+                                error: e.to_string(),
+                                duration: start.elapsed(),
+                            })
+                            .await;
+                        return;
+                    }
+                }
+            }
+        };
+        let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            terminate_process(&mut child, policy.container.as_ref()).await;
+            let _ = event_tx
+                .send(SupervisorEvent::Failed {
+                    error: "output not captured".to_string(),
+                    duration: start.elapsed(),
+                })
+                .await;
+            return;
+        };
+
+        let idle_timeout = policy.idle_timeout;
+        let max_buffer = policy.output_buffer_bytes;
+        let container = policy.container.clone();
 
         let stdout_tx = event_tx.clone();
         let stderr_tx = event_tx.clone();
@@ -207,16 +354,17 @@ where
                 match status {
                     Ok(s) => {
                         let code = s.code().unwrap_or(-1);
-                        let duration = start.elapsed();
                         if code == 0 {
-                            let _ = event_tx.send(SupervisorEvent::Completed {
-                                exit_code: code,
-                                duration,
-                            }).await;
+                            if chain.run(&post, &mut cancel_rx).await {
+                                let _ = event_tx.send(SupervisorEvent::Completed {
+                                    exit_code: code,
+                                    duration: start.elapsed(),
+                                }).await;
+                            }
                         } else {
                             let _ = event_tx.send(SupervisorEvent::Failed {
                                 error: format!("exited with code {code}"),
-                                duration,
+                                duration: start.elapsed(),
                             }).await;
                         }
                     }
@@ -228,22 +376,22 @@ where
                     }
                 }
             }
-            _ = tokio::time::sleep(hard_timeout) => {
-                terminate_process(&mut child).await;
+            _ = tokio::time::sleep_until(deadline) => {
+                terminate_process(&mut child, container.as_ref()).await;
                 let _ = event_tx.send(SupervisorEvent::TimedOut {
                     kind: TimeoutKind::Hard,
                     duration: start.elapsed(),
                 }).await;
             }
-            _ = idle_timeout_watch(idle_timeout, &mut idle_reset_rx) => {
-                terminate_process(&mut child).await;
+            _ = idle_timeout_watch(idle_timeout, &mut idle_reset_rx, &pause) => {
+                terminate_process(&mut child, container.as_ref()).await;
                 let _ = event_tx.send(SupervisorEvent::TimedOut {
                     kind: TimeoutKind::Idle,
                     duration: start.elapsed(),
                 }).await;
             }
             _ = cancel_rx.recv() => {
-                terminate_process(&mut child).await;
+                terminate_process(&mut child, container.as_ref()).await;
                 let _ = event_tx.send(SupervisorEvent::Failed {
                     error: "cancelled".to_string(),
                     duration: start.elapsed(),
@@ -258,11 +406,21 @@ where
     Ok(handle)
 }
 
-async fn idle_timeout_watch(timeout: Duration, reset_rx: &mut mpsc::Receiver<()>) {
+async fn idle_timeout_watch(
+    timeout: Duration,
+    reset_rx: &mut mpsc::Receiver<()>,
+    pause: &PauseState,
+) {
     loop {
         tokio::select! {
             _ = tokio::time::sleep(timeout) => {
-                return;
+                // A paused process is silent by design.
+                if !pause.paused.load(Ordering::SeqCst) {
+                    return;
+                }
+            }
+            _ = pause.resumed.notified() => {
+                // Resumed, restart the idle timer
             }
             msg = reset_rx.recv() => {
                 match msg {
@@ -300,7 +458,36 @@ fn classify_kill_error(errno: Option<i32>) -> KillErrorKind {
     }
 }
 
-async fn terminate_process(child: &mut tokio::process::Child) {
+async fn terminate_process(child: &mut tokio::process::Child, container: Option<&ContainerSpec>) {
+    kill_process_group(child).await;
+    if let Some(container) = container {
+        remove_container(container).await;
+    }
+}
+
+/// Stopping the runtime client does not reliably stop its container.
+async fn remove_container(container: &ContainerSpec) {
+    run_container_command(container, "rm").await;
+}
+
+/// Run `<runtime> <action>` against the agent's container. Signals sent to
+/// the runtime client do not reach the container, so it is managed through
+/// the runtime instead.
+async fn run_container_command(container: &ContainerSpec, action: &str) {
+    let (program, args) = container.command(action);
+    let result = Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await;
+    if let Err(e) = result {
+        tracing::warn!(container = container.name(), action, error = %e, "container command failed");
+    }
+}
+
+async fn kill_process_group(child: &mut tokio::process::Child) {
     #[cfg(unix)]
     {
         let Some(pid_u32) = child.id() else {
@@ -383,7 +570,212 @@ async fn terminate_process(child: &mut tokio::process::Child) {
     }
 }
 
-fn build_process(cmd: &BuiltCommand) -> Result<tokio::process::Child, SupervisorError> {
+/// Record the agent's pid for the handle and announce it.
+async fn started(
+    child: &tokio::process::Child,
+    pid: &OnceLock<u32>,
+    event_tx: &mpsc::Sender<SupervisorEvent>,
+) {
+    if let Some(id) = child.id() {
+        let _ = pid.set(id);
+    }
+    let _ = event_tx
+        .send(SupervisorEvent::Started {
+            pid: child.id().unwrap_or(0),
+        })
+        .await;
+}
+
+/// Start the agent itself, inside its container if it has one.
+fn spawn_agent(
+    cmd: &BuiltCommand,
+    policy: &SupervisorPolicy,
+) -> Result<tokio::process::Child, SupervisorError> {
+    let cmd = match &policy.container {
+        Some(container) => container.wrap(cmd),
+        None => cmd.clone(),
+    };
+    build_process(&cmd, policy.sandbox.as_ref(), policy.isolate_network)
+}
+
+/// Sources the script given as `$1` with its output sent to stderr, then
+/// prints the resulting environment NUL-separated. The path is an argument,
+/// never part of the script text.
+const SOURCE_SCRIPT: &str = r#". "$1" >&2 && exec env -0"#;
+
+/// Variables the shell itself changes, which are not the script's doing.
+const SHELL_VARS: &[&str] = &["_", "SHLVL", "PWD", "OLDPWD"];
+
+/// Runs chain steps with the agent's working directory and sandbox, carrying
+/// the environment `Source` steps build up from one step to the next.
+struct Chain<'a> {
+    cwd: PathBuf,
+    env: Vec<(String, String)>,
+    policy: &'a SupervisorPolicy,
+    event_tx: &'a mpsc::Sender<SupervisorEvent>,
+    start: Instant,
+    deadline: tokio::time::Instant,
+}
+
+impl Chain<'_> {
+    /// Run `steps` in order. `false` after one fails, times out or is
+    /// cancelled, once that has been reported.
+    async fn run(&mut self, steps: &[CommandStep], cancel_rx: &mut mpsc::Receiver<()>) -> bool {
+        for step in steps {
+            let started = Instant::now();
+            let event = match self.run_step(step, cancel_rx).await {
+                Ok(()) => SupervisorEvent::StepCompleted {
+                    label: step.label().to_string(),
+                    duration: started.elapsed(),
+                },
+                Err(event) => {
+                    let _ = self.event_tx.send(event).await;
+                    return false;
+                }
+            };
+            let _ = self.event_tx.send(event).await;
+        }
+        true
+    }
+
+    async fn run_step(
+        &mut self,
+        step: &CommandStep,
+        cancel_rx: &mut mpsc::Receiver<()>,
+    ) -> Result<(), SupervisorEvent> {
+        let label = step.label();
+        let (program, args, capture) = match step {
+            CommandStep::Run { program, args, .. } => (program.clone(), args.clone(), false),
+            CommandStep::Source { script, .. } => (
+                "sh".to_string(),
+                vec![
+                    "-c".to_string(),
+                    SOURCE_SCRIPT.to_string(),
+                    "hydra-source".to_string(),
+                    script.display().to_string(),
+                ],
+                true,
+            ),
+        };
+        let cmd = BuiltCommand {
+            program,
+            args,
+            env: self.env.clone(),
+            cwd: self.cwd.clone(),
+            ..Default::default()
+        };
+        let container = self.policy.container.as_ref();
+        let failed = |error: String| SupervisorEvent::Failed {
+            error: format!("step `{label}` {error}"),
+            duration: self.start.elapsed(),
+        };
+
+        let mut child = spawn_agent(&cmd, self.policy).map_err(|e| failed(e.to_string()))?;
+        let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            terminate_process(&mut child, container).await;
+            return Err(failed("output not captured".to_string()));
+        };
+        let stdout = tokio::spawn(read_step_output(
+            stdout,
+            label.to_string(),
+            false,
+            capture,
+            self.event_tx.clone(),
+        ));
+        let stderr = tokio::spawn(read_step_output(
+            stderr,
+            label.to_string(),
+            true,
+            false,
+            self.event_tx.clone(),
+        ));
+
+        let status = tokio::select! {
+            status = child.wait() => status,
+            _ = tokio::time::sleep_until(self.deadline) => {
+                terminate_process(&mut child, container).await;
+                return Err(SupervisorEvent::TimedOut {
+                    kind: TimeoutKind::Hard,
+                    duration: self.start.elapsed(),
+                });
+            }
+            _ = cancel_rx.recv() => {
+                terminate_process(&mut child, container).await;
+                return Err(SupervisorEvent::Failed {
+                    error: "cancelled".to_string(),
+                    duration: self.start.elapsed(),
+                });
+            }
+        };
+        let captured = stdout.await.unwrap_or_default();
+        let _ = stderr.await;
+        match status.map(|s| s.code().unwrap_or(-1)) {
+            Ok(0) => {}
+            Ok(code) => return Err(failed(format!("exited with code {code}"))),
+            Err(e) => return Err(failed(format!("failed: {e}"))),
+        }
+        if capture {
+            for (key, value) in changed_vars(&captured, &self.env) {
+                self.env.retain(|(k, _)| *k != key);
+                self.env.push((key, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Forward a step's output line by line, or with `capture` collect it
+/// instead.
+async fn read_step_output(
+    stream: impl AsyncRead + Unpin,
+    label: String,
+    stderr: bool,
+    capture: bool,
+    event_tx: mpsc::Sender<SupervisorEvent>,
+) -> Vec<u8> {
+    let mut reader = BufReader::new(stream);
+    let mut captured = Vec::new();
+    if capture {
+        let _ = reader.read_to_end(&mut captured).await;
+        return captured;
+    }
+    let mut line = String::new();
+    while matches!(reader.read_line(&mut line).await, Ok(n) if n > 0) {
+        let _ = event_tx
+            .send(SupervisorEvent::StepOutput {
+                label: label.clone(),
+                line: line.trim_end().to_string(),
+                stderr,
+            })
+            .await;
+        line.clear();
+    }
+    captured
+}
+
+/// Variables in the NUL-separated `env -0` output that differ from what the
+/// step was started with: the supervisor's own environment overlaid with
+/// `env`.
+fn changed_vars(output: &[u8], env: &[(String, String)]) -> Vec<(String, String)> {
+    let mut before: HashMap<String, String> = std::env::vars().collect();
+    before.extend(env.iter().cloned());
+    String::from_utf8_lossy(output)
+        .split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(key, value)| {
+            !key.is_empty()
+                && !SHELL_VARS.contains(key)
+                && before.get(*key).map(String::as_str) != Some(*value)
+        })
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn build_process(
+    cmd: &BuiltCommand,
+    sandbox: Option<&OsSandbox>,
+    isolate_network: bool,
+) -> Result<tokio::process::Child, SupervisorError> {
     let mut command = Command::new(&cmd.program);
     command
         .args(&cmd.args)
@@ -398,15 +790,37 @@ fn build_process(cmd: &BuiltCommand) -> Result<tokio::process::Child, Supervisor
 
     #[cfg(unix)]
     {
+        let mut sandbox = sandbox
+            .map(OsSandbox::prepare)
+            .transpose()
+            .map_err(SupervisorError::SandboxSetup)?;
+        #[cfg(target_os = "linux")]
+        let mut netns = isolate_network.then(crate::security::PrivateNetns::prepare);
+        #[cfg(not(target_os = "linux"))]
+        let _ = isolate_network;
         unsafe {
-            command.pre_exec(|| {
+            command.pre_exec(move || {
                 if libc::setsid() == -1 {
                     return Err(std::io::Error::last_os_error());
                 }
+                // Before Landlock, which would block writing the id maps.
+                #[cfg(target_os = "linux")]
+                if let Some(netns) = netns.as_mut() {
+                    netns.enter()?;
+                }
+                if let Some(sandbox) = sandbox.as_mut() {
+                    sandbox.enforce()?;
+                }
                 Ok(())
             });
         }
     }
+    #[cfg(not(unix))]
+    let _ = isolate_network;
+    #[cfg(not(unix))]
+    if let Some(sandbox) = sandbox {
+        sandbox.prepare().map_err(SupervisorError::SandboxSetup)?;
+    }
 
     command.spawn().map_err(SupervisorError::SpawnFailed)
 }
@@ -444,6 +858,7 @@ mod tests {
             args,
             env: vec![],
             cwd: test_cwd(),
+            ..Default::default()
         }
     }
 
@@ -465,6 +880,7 @@ mod tests {
             args,
             env: vec![],
             cwd: test_cwd(),
+            ..Default::default()
         }
     }
 
@@ -485,6 +901,7 @@ mod tests {
             args,
             env: vec![],
             cwd: test_cwd(),
+            ..Default::default()
         }
     }
 
@@ -511,6 +928,7 @@ mod tests {
             args,
             env: vec![],
             cwd: test_cwd(),
+            ..Default::default()
         }
     }
 
@@ -524,6 +942,7 @@ mod tests {
             ],
             env: vec![],
             cwd: test_cwd(),
+            ..Default::default()
         }
     }
 
@@ -549,6 +968,9 @@ mod tests {
             hard_timeout: Duration::from_secs(10),
             idle_timeout: Duration::from_secs(5),
             output_buffer_bytes: 1024,
+            sandbox: None,
+            container: None,
+            isolate_network: false,
         };
 
         let _handle = supervise(cmd, config, tx, |_| None).await.unwrap();
@@ -713,6 +1135,7 @@ mod tests {
             args: vec![],
             env: vec![],
             cwd: test_cwd(),
+            ..Default::default()
         };
         let config = SupervisorPolicy::default();
 
@@ -780,4 +1203,208 @@ mod tests {
         );
         assert_eq!(classify_kill_error(None), KillErrorKind::Unknown);
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn container_policy_runs_command_through_runtime() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let runtime = tmp.path().join("fake-runtime");
+        std::fs::write(&runtime, "#!/bin/sh\necho \"$@\"\n").unwrap();
+        std::fs::set_permissions(&runtime, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let cfg = crate::config::ContainerConfig {
+            runtime: Some(runtime.display().to_string()),
+            image: Some("agents:latest".to_string()),
+            ..Default::default()
+        };
+        let container = ContainerSpec::for_worktree(&cfg, tmp.path(), "hydra-test").unwrap();
+
+        let (tx, mut rx) = mpsc::channel(64);
+        let policy = SupervisorPolicy {
+            container: Some(container),
+            ..Default::default()
+        };
+        let _handle = supervise(echo_command("hi"), policy, tx, |_| None)
+            .await
+            .unwrap();
+
+        let mut invocation = None;
+        while let Some(evt) = rx.recv().await {
+            match evt {
+                SupervisorEvent::Stdout(line) => invocation = Some(line),
+                SupervisorEvent::Completed { .. } => break,
+                other => assert!(matches!(other, SupervisorEvent::Started { .. })),
+            }
+        }
+        let invocation = invocation.expect("runtime should print its arguments");
+        assert!(invocation.starts_with("run --rm --interactive --init --name hydra-test"));
+        assert!(invocation.ends_with("agents:latest echo hi"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn isolate_network_runs_child_in_private_namespace() {
+        if !crate::security::private_netns_supported() {
+            return;
+        }
+        let host_ns = std::fs::read_link("/proc/self/ns/net").unwrap();
+        let cmd = BuiltCommand {
+            program: "readlink".to_string(),
+            args: vec!["/proc/self/ns/net".to_string()],
+            env: vec![],
+            cwd: test_cwd(),
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::channel(64);
+        let policy = SupervisorPolicy {
+            isolate_network: true,
+            ..Default::default()
+        };
+        let _handle = supervise(cmd, policy, tx, |_| None).await.unwrap();
+
+        let mut child_ns = None;
+        while let Some(evt) = rx.recv().await {
+            match evt {
+                SupervisorEvent::Stdout(line) => child_ns = Some(line),
+                SupervisorEvent::Completed { .. } => break,
+                SupervisorEvent::Failed { error, .. } => panic!("child failed: {error}"),
+                _ => {}
+            }
+        }
+        let child_ns = child_ns.expect("readlink should print the namespace");
+        assert!(child_ns.starts_with("net:"));
+        assert_ne!(Path::new(&child_ns), host_ns);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn paused_process_waits_without_idle_timeout() {
+        let (tx, mut rx) = mpsc::channel(64);
+        let cmd = BuiltCommand {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), "sleep 0.2; echo done".to_string()],
+            env: vec![],
+            cwd: test_cwd(),
+            ..Default::default()
+        };
+        let policy = SupervisorPolicy {
+            hard_timeout: Duration::from_secs(10),
+            idle_timeout: Duration::from_millis(300),
+            ..Default::default()
+        };
+
+        let handle = supervise(cmd, policy, tx, |_| None).await.unwrap();
+        handle.pause().await;
+        assert!(handle.is_paused());
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+        while let Ok(evt) = rx.try_recv() {
+            assert!(
+                matches!(evt, SupervisorEvent::Started { .. }),
+                "paused process produced {evt:?}"
+            );
+        }
+
+        handle.resume().await;
+        let mut completed = false;
+        while let Some(evt) = rx.recv().await {
+            match evt {
+                SupervisorEvent::Completed { .. } => {
+                    completed = true;
+                    break;
+                }
+                SupervisorEvent::TimedOut { .. } | SupervisorEvent::Failed { .. } => {
+                    panic!("unexpected {evt:?}")
+                }
+                _ => {}
+            }
+        }
+        assert!(completed);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn chain_steps_run_around_the_agent_with_sourced_env() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("activate"),
+            "echo activating\nexport HYDRA_CHAIN_VAR=venv\n",
+        )
+        .unwrap();
+        let (tx, mut rx) = mpsc::channel(64);
+        let cmd = BuiltCommand {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo agent:$HYDRA_CHAIN_VAR".to_string()],
+            cwd: tmp.path().to_path_buf(),
+            pre: vec![CommandStep::Source {
+                label: "venv".to_string(),
+                script: tmp.path().join("activate"),
+            }],
+            post: vec![CommandStep::Run {
+                label: "report".to_string(),
+                program: "sh".to_string(),
+                args: vec!["-c".to_string(), "echo post:$HYDRA_CHAIN_VAR".to_string()],
+            }],
+            ..Default::default()
+        };
+
+        let _handle = supervise(cmd, SupervisorPolicy::default(), tx, |_| None)
+            .await
+            .unwrap();
+        let mut seen = Vec::new();
+        while let Some(evt) = rx.recv().await {
+            match evt {
+                SupervisorEvent::StepOutput { label, line, .. } => {
+                    seen.push(format!("{label}> {line}"))
+                }
+                SupervisorEvent::StepCompleted { label, .. } => seen.push(format!("{label} ok")),
+                SupervisorEvent::Stdout(line) => seen.push(line),
+                SupervisorEvent::Completed { .. } => break,
+                SupervisorEvent::Failed { error, .. } => panic!("chain failed: {error}"),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            seen,
+            vec![
+                "venv> activating",
+                "venv ok",
+                "agent:venv",
+                "report> post:venv",
+                "report ok",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failing_setup_step_stops_the_chain() {
+        let (tx, mut rx) = mpsc::channel(64);
+        let cmd = BuiltCommand {
+            pre: vec![CommandStep::Run {
+                label: "setup".to_string(),
+                program: "sh".to_string(),
+                args: vec!["-c".to_string(), "exit 3".to_string()],
+            }],
+            ..echo_command("never")
+        };
+
+        let _handle = supervise(cmd, SupervisorPolicy::default(), tx, |_| None)
+            .await
+            .unwrap();
+        let mut events = Vec::new();
+        while let Some(evt) = rx.recv().await {
+            events.push(evt);
+        }
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, SupervisorEvent::Started { .. })),
+            "agent started after a failed step: {events:?}"
+        );
+        assert!(matches!(
+            events.last(),
+            Some(SupervisorEvent::Failed { error, .. }) if error == "step `setup` exited with code 3"
+        ));
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -84,6 +85,34 @@ struct PtyInner {
 const CHILD_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
 const CHILD_EXIT_POLL_ATTEMPTS: usize = 20;
 
+/// Bounded buffer of the most recent PTY output, replayed to clients that
+/// attach after the session started.
+#[derive(Debug, Clone)]
+pub struct Scrollback {
+    buf: VecDeque<u8>,
+    cap: usize,
+}
+
+impl Scrollback {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            buf: VecDeque::with_capacity(cap),
+            cap,
+        }
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        let data = &data[data.len().saturating_sub(self.cap)..];
+        let overflow = (self.buf.len() + data.len()).saturating_sub(self.cap);
+        self.buf.drain(..overflow);
+        self.buf.extend(data);
+    }
+
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.buf.iter().copied().collect()
+    }
+}
+
 pub struct PtySession {
     inner: Arc<Mutex<Option<PtyInner>>>,
     status: Arc<Mutex<PtySessionStatus>>,
@@ -368,6 +397,16 @@ fn spawn_reader_thread(
 mod tests {
     use super::*;
 
+    #[test]
+    fn scrollback_keeps_most_recent_bytes() {
+        let mut scrollback = Scrollback::new(4);
+        scrollback.push(b"abc");
+        scrollback.push(b"def");
+        assert_eq!(scrollback.snapshot(), b"cdef");
+        scrollback.push(b"0123456789");
+        assert_eq!(scrollback.snapshot(), b"6789");
+    }
+
     fn test_echo_config(msg: &str) -> PtySessionConfig {
         PtySessionConfig {
             #[cfg(unix)]
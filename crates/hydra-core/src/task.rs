@@ -0,0 +1,142 @@
+//! Structured task input for `hydra race --task`: what to build, how to
+//! tell it is done, and where the agents should and should not work.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::HydraConfig;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+    /// Repository-relative paths the work is expected to stay within.
+    /// Changes elsewhere lower the diff-scope score.
+    #[serde(default)]
+    pub relevant_paths: Vec<String>,
+    /// Globs the agents must not modify, enforced as
+    /// `security.write_policy.read_only`.
+    #[serde(default)]
+    pub forbidden_paths: Vec<String>,
+    /// Check out only `relevant_paths`, plus top-level files, in the agent
+    /// worktrees.
+    #[serde(default)]
+    pub sparse: bool,
+}
+
+impl TaskSpec {
+    /// The task as a Markdown prompt, for agents without a preferred format.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title.trim());
+        if !self.description.trim().is_empty() {
+            out.push_str(&format!("\n{}\n", self.description.trim()));
+        }
+        for (heading, items) in self.sections() {
+            out.push_str(&format!("\n## {heading}\n\n"));
+            for item in items {
+                out.push_str(&format!("- {item}\n"));
+            }
+        }
+        out
+    }
+
+    /// The task with each part in its own XML tag.
+    pub fn to_xml(&self) -> String {
+        let mut out = format!("<task>\n<title>{}</title>\n", self.title.trim());
+        if !self.description.trim().is_empty() {
+            out.push_str(&format!(
+                "<description>\n{}\n</description>\n",
+                self.description.trim()
+            ));
+        }
+        for (heading, items) in self.sections() {
+            let tag = heading.to_lowercase().replace(' ', "_");
+            out.push_str(&format!("<{tag}>\n"));
+            for item in items {
+                out.push_str(&format!("- {item}\n"));
+            }
+            out.push_str(&format!("</{tag}>\n"));
+        }
+        out.push_str("</task>\n");
+        out
+    }
+
+    /// `config` with the spec's paths applied to scoring and write policy.
+    pub fn apply_to(&self, config: &HydraConfig) -> HydraConfig {
+        let mut config = config.clone();
+        config
+            .scoring
+            .diff_scope
+            .relevant_paths
+            .extend(self.relevant_paths.iter().cloned());
+        config
+            .security
+            .write_policy
+            .read_only
+            .extend(self.forbidden_paths.iter().cloned());
+        config
+    }
+
+    fn sections(&self) -> Vec<(&'static str, &[String])> {
+        [
+            ("Acceptance criteria", self.acceptance_criteria.as_slice()),
+            ("Relevant paths", self.relevant_paths.as_slice()),
+            ("Do not modify", self.forbidden_paths.as_slice()),
+        ]
+        .into_iter()
+        .filter(|(_, items)| !items.is_empty())
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> TaskSpec {
+        TaskSpec {
+            title: "Add retries".to_string(),
+            description: "Retry failed uploads.\n".to_string(),
+            acceptance_criteria: vec!["uploads retry 3 times".to_string()],
+            relevant_paths: vec!["src/upload".to_string()],
+            forbidden_paths: vec!["migrations/**".to_string()],
+            sparse: false,
+        }
+    }
+
+    #[test]
+    fn renders_markdown_and_xml() {
+        assert_eq!(
+            spec().to_markdown(),
+            "# Add retries\n\nRetry failed uploads.\n\n\
+             ## Acceptance criteria\n\n- uploads retry 3 times\n\n\
+             ## Relevant paths\n\n- src/upload\n\n\
+             ## Do not modify\n\n- migrations/**\n"
+        );
+        let xml = spec().to_xml();
+        assert!(xml.starts_with("<task>\n<title>Add retries</title>\n"));
+        assert!(
+            xml.contains("<acceptance_criteria>\n- uploads retry 3 times\n</acceptance_criteria>")
+        );
+        assert!(xml.contains("<do_not_modify>\n- migrations/**\n</do_not_modify>"));
+
+        let bare = TaskSpec {
+            title: "Fix it".to_string(),
+            ..TaskSpec::default()
+        };
+        assert_eq!(bare.to_markdown(), "# Fix it\n");
+    }
+
+    #[test]
+    fn apply_to_extends_scope_and_write_policy() {
+        let config = spec().apply_to(&HydraConfig::default());
+        assert_eq!(config.scoring.diff_scope.relevant_paths, vec!["src/upload"]);
+        assert_eq!(
+            config.security.write_policy.read_only,
+            vec!["migrations/**"]
+        );
+    }
+}
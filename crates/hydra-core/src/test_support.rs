@@ -0,0 +1,39 @@
+//! Fixtures for tests that need a run on disk, shared with the CLI's and the
+//! app's tests through the `test-support` feature.
+
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::artifact::{AgentEntry, RunLayout, RunManifest};
+
+/// A manifest for `run_id` with one `tier1` entry per agent on the run's
+/// usual branch, in `/repo` at `HEAD`.
+pub fn run_manifest(run_id: Uuid, agents: &[&str]) -> RunManifest {
+    let entries = agents
+        .iter()
+        .map(|key| AgentEntry {
+            agent_key: key.to_string(),
+            tier: "tier1".to_string(),
+            branch: format!("hydra/{run_id}/agent/{key}"),
+            worktree_path: None,
+        })
+        .collect();
+    RunManifest::new(
+        run_id,
+        "/repo".to_string(),
+        "HEAD".to_string(),
+        "hash".to_string(),
+        entries,
+    )
+}
+
+/// A fresh run directory under `hydra_root` with one directory per agent,
+/// and its [`run_manifest`], not written yet so tests can adjust it before
+/// calling [`RunManifest::write_to`].
+pub fn new_run(hydra_root: &Path, agents: &[&str]) -> (RunLayout, RunManifest) {
+    let layout = RunLayout::new(hydra_root, Uuid::new_v4());
+    layout.create_dirs(agents).unwrap();
+    let manifest = run_manifest(layout.run_id(), agents);
+    (layout, manifest)
+}
@@ -7,6 +7,10 @@ use uuid::Uuid;
 use crate::git_exec::{run_git_program_with_timeout, GitCommandOutput, GitExecError};
 use crate::git_ref::{validate_agent_key, validate_branch_name};
 
+mod registry;
+
+pub use registry::{cleanup_live_runs, cleanup_run, finish_run, live_runs, track_run, LiveRun};
+
 #[derive(Debug, Error)]
 pub enum WorktreeError {
     #[error("git command failed: {detail}")]
@@ -48,6 +52,23 @@ pub struct WorktreeEntry {
     pub head_commit: String,
 }
 
+impl WorktreeEntry {
+    /// Recover the run and agent for a worktree on a Hydra agent branch
+    /// (`hydra/<run_id>/agent/<agent_key>`). Returns `None` for other worktrees.
+    pub fn agent_info(&self) -> Option<WorktreeInfo> {
+        let rest = self.branch.strip_prefix("hydra/")?;
+        let (run_id, agent_key) = rest.split_once("/agent/")?;
+        let run_id = Uuid::parse_str(run_id).ok()?;
+        validate_agent_key(agent_key).ok()?;
+        Some(WorktreeInfo {
+            path: self.path.clone(),
+            branch: self.branch.clone(),
+            run_id,
+            agent_key: agent_key.to_string(),
+        })
+    }
+}
+
 /// Service for managing git worktrees used for agent isolation.
 ///
 /// Each agent in a run gets its own worktree branched from the base ref.
@@ -141,12 +162,36 @@ impl WorktreeService {
             "created worktree"
         );
 
-        Ok(WorktreeInfo {
+        let info = WorktreeInfo {
             path: wt_path,
             branch,
             run_id,
             agent_key: agent_key.to_string(),
-        })
+        };
+        registry::track_worktree(&self.repo_root, &self.base_dir, &info);
+        Ok(info)
+    }
+
+    /// Limit the checkout of a worktree to `paths`, plus the files at the
+    /// repository root. Paths are repository-relative.
+    pub async fn set_sparse_paths(
+        &self,
+        wt_path: &Path,
+        paths: &[String],
+    ) -> Result<(), WorktreeError> {
+        let mut patterns = vec!["/*".to_string(), "!/*/".to_string()];
+        patterns.extend(paths.iter().map(|p| format!("/{}", p.trim_matches('/'))));
+
+        let mut args = vec!["sparse-checkout", "set", "--no-cone"];
+        args.extend(patterns.iter().map(String::as_str));
+        run_git_program_with_timeout(&self.git_program, &args, wt_path, self.git_timeout)
+            .await
+            .map_err(|e| WorktreeError::GitFailed {
+                detail: e.to_string(),
+            })?;
+
+        tracing::info!(path = %wt_path.display(), ?paths, "set sparse checkout");
+        Ok(())
     }
 
     /// List all worktrees known to git in this repo.
@@ -171,6 +216,7 @@ impl WorktreeService {
         args.push(&wt_str);
 
         self.run_git(&args).await?;
+        registry::untrack_worktree(wt_path);
 
         tracing::info!(path = %wt_path.display(), "removed worktree");
         Ok(())
@@ -225,6 +271,7 @@ impl WorktreeService {
             });
         }
 
+        registry::untrack_worktree(&info.path);
         tracing::info!(
             branch = %info.branch,
             path = %info.path.display(),
@@ -276,7 +323,7 @@ mod tests {
     use std::time::Duration;
     use tempfile::TempDir;
 
-    fn init_test_repo(dir: &Path) {
+    pub(super) fn init_test_repo(dir: &Path) {
         use std::process::Command as StdCommand;
         StdCommand::new("git")
             .args(["init"])
@@ -333,6 +380,28 @@ branch refs/heads/hydra/run1/agent/claude
         assert_eq!(entries[1].head_commit, "def789abc012");
     }
 
+    #[test]
+    fn agent_info_parses_hydra_branches_only() {
+        let run_id = Uuid::new_v4();
+        let entry = WorktreeEntry {
+            path: PathBuf::from("/repo/.hydra/worktrees/x/claude"),
+            branch: format!("hydra/{run_id}/agent/claude"),
+            head_commit: "abc".to_string(),
+        };
+        let info = entry.agent_info().unwrap();
+        assert_eq!(info.run_id, run_id);
+        assert_eq!(info.agent_key, "claude");
+        assert_eq!(info.path, entry.path);
+
+        for branch in ["main", "hydra/run1/agent/claude", "hydra/{run_id}/agent/"] {
+            let entry = WorktreeEntry {
+                branch: branch.replace("{run_id}", &run_id.to_string()),
+                ..entry.clone()
+            };
+            assert!(entry.agent_info().is_none(), "{branch} should not parse");
+        }
+    }
+
     #[test]
     fn parse_porcelain_with_bare_worktree() {
         let output = "\
@@ -373,6 +442,40 @@ bare
         assert!(!info.path.exists());
     }
 
+    #[tokio::test]
+    async fn sparse_paths_limit_checkout() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        init_test_repo(&repo);
+        for dir in ["src", "docs"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+            std::fs::write(repo.join(dir).join("a.txt"), "a\n").unwrap();
+        }
+        std::process::Command::new("git")
+            .args(["add", "."])
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+        std::process::Command::new("git")
+            .args(["commit", "-m", "dirs"])
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+
+        let svc = WorktreeService::new(repo.clone(), tmp.path().join("worktrees"));
+        let info = svc.create(Uuid::new_v4(), "claude", "HEAD").await.unwrap();
+        svc.set_sparse_paths(&info.path, &["src/".to_string()])
+            .await
+            .unwrap();
+
+        assert!(info.path.join("README.md").exists());
+        assert!(info.path.join("src/a.txt").exists());
+        assert!(!info.path.join("docs").exists());
+
+        svc.force_cleanup(&info).await.unwrap();
+    }
+
     #[tokio::test]
     async fn create_duplicate_worktree_fails() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,210 @@
+//! Process-wide record of the worktrees and runs this process has live, so
+//! an interrupt can clean up exactly what it created.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use uuid::Uuid;
+
+use super::{WorktreeInfo, WorktreeService};
+use crate::artifact::{RunManifest, RunStatus};
+
+#[derive(Debug, Clone)]
+struct LiveWorktree {
+    repo_root: PathBuf,
+    base_dir: PathBuf,
+    info: WorktreeInfo,
+}
+
+struct Registry {
+    worktrees: Vec<LiveWorktree>,
+    /// Manifests of runs that have not finished, by run ID.
+    runs: Vec<(Uuid, PathBuf)>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    worktrees: Vec::new(),
+    runs: Vec::new(),
+});
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub(super) fn track_worktree(repo_root: &Path, base_dir: &Path, info: &WorktreeInfo) {
+    registry().worktrees.push(LiveWorktree {
+        repo_root: repo_root.to_path_buf(),
+        base_dir: base_dir.to_path_buf(),
+        info: info.clone(),
+    });
+}
+
+pub(super) fn untrack_worktree(path: &Path) {
+    registry().worktrees.retain(|wt| wt.info.path != path);
+}
+
+/// Record a run whose manifest should be marked interrupted if the process
+/// is interrupted while the returned guard is alive. Dropping the guard
+/// calls [`finish_run`], so a run that fails part way is forgotten too.
+#[must_use = "the run is forgotten as soon as the guard is dropped"]
+pub fn track_run(run_id: Uuid, manifest_path: PathBuf) -> LiveRun {
+    registry().runs.push((run_id, manifest_path));
+    LiveRun { run_id }
+}
+
+/// A run registered by [`track_run`]; forgets it when dropped.
+#[derive(Debug)]
+pub struct LiveRun {
+    run_id: Uuid,
+}
+
+impl Drop for LiveRun {
+    fn drop(&mut self) {
+        finish_run(self.run_id);
+    }
+}
+
+/// Forget a run and any of its worktrees still registered, e.g. ones kept
+/// by `worktree.retain`.
+pub fn finish_run(run_id: Uuid) {
+    let mut registry = registry();
+    registry.runs.retain(|(id, _)| *id != run_id);
+    registry.worktrees.retain(|wt| wt.info.run_id != run_id);
+}
+
+/// IDs of runs with a registered manifest or worktree.
+pub fn live_runs() -> Vec<Uuid> {
+    let registry = registry();
+    let mut ids: Vec<Uuid> = registry
+        .runs
+        .iter()
+        .map(|(id, _)| *id)
+        .chain(registry.worktrees.iter().map(|wt| wt.info.run_id))
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Remove a run's registered worktrees and branches and mark its manifest
+/// interrupted. Failures are logged; cleanup carries on with the rest.
+pub async fn cleanup_run(run_id: Uuid) {
+    let (worktrees, manifest) = {
+        let registry = registry();
+        let worktrees: Vec<LiveWorktree> = registry
+            .worktrees
+            .iter()
+            .filter(|wt| wt.info.run_id == run_id)
+            .cloned()
+            .collect();
+        let manifest = registry
+            .runs
+            .iter()
+            .find(|(id, _)| *id == run_id)
+            .map(|(_, path)| path.clone());
+        (worktrees, manifest)
+    };
+
+    for wt in &worktrees {
+        let service = WorktreeService::new(wt.repo_root.clone(), wt.base_dir.clone());
+        if let Err(e) = service.force_cleanup(&wt.info).await {
+            tracing::warn!(
+                branch = %wt.info.branch,
+                path = %wt.info.path.display(),
+                error = %e,
+                "interrupt cleanup: failed to remove worktree"
+            );
+        }
+    }
+
+    if let Some(path) = manifest {
+        if let Err(e) = mark_interrupted(&path) {
+            tracing::warn!(path = %path.display(), error = %e, "interrupt cleanup: failed to update manifest");
+        }
+    }
+    finish_run(run_id);
+}
+
+fn mark_interrupted(path: &Path) -> Result<(), crate::artifact::ArtifactError> {
+    let mut manifest = RunManifest::read_from(path)?;
+    if manifest.status == RunStatus::Running {
+        manifest.mark_completed(RunStatus::Interrupted);
+        manifest.write_to(path)?;
+    }
+    Ok(())
+}
+
+/// [`cleanup_run`] for every live run, e.g. once the process has been
+/// interrupted. Reporting the interrupt and exiting are up to the caller.
+pub async fn cleanup_live_runs() {
+    for run_id in live_runs() {
+        cleanup_run(run_id).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::{AgentEntry, RunLayout};
+
+    #[tokio::test]
+    async fn cleanup_run_removes_only_that_runs_worktrees() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        super::super::tests::init_test_repo(&repo);
+        let svc = WorktreeService::new(repo.clone(), tmp.path().join("worktrees"));
+
+        let run_id = Uuid::new_v4();
+        let other_run = Uuid::new_v4();
+        let info = svc.create(run_id, "claude", "HEAD").await.unwrap();
+        let other = svc.create(other_run, "claude", "HEAD").await.unwrap();
+
+        let layout = RunLayout::new(&repo.join(".hydra"), run_id);
+        layout.create_dirs(&["claude"]).unwrap();
+        let entry = AgentEntry {
+            agent_key: "claude".to_string(),
+            tier: "tier1".to_string(),
+            branch: info.branch.clone(),
+            worktree_path: None,
+        };
+        RunManifest::new(
+            run_id,
+            repo.display().to_string(),
+            "HEAD".to_string(),
+            "hash".to_string(),
+            vec![entry],
+        )
+        .write_to(&layout.manifest_path())
+        .unwrap();
+        let _live = track_run(run_id, layout.manifest_path());
+        assert!(live_runs().contains(&run_id));
+
+        cleanup_run(run_id).await;
+
+        assert!(!info.path.exists());
+        assert!(other.path.exists());
+        assert!(!live_runs().contains(&run_id));
+        assert!(live_runs().contains(&other_run));
+        let manifest = RunManifest::read_from(&layout.manifest_path()).unwrap();
+        assert_eq!(manifest.status, RunStatus::Interrupted);
+        assert!(manifest.completed_at.is_some());
+
+        svc.force_cleanup(&other).await.unwrap();
+        assert!(!live_runs().contains(&other_run));
+    }
+
+    #[test]
+    fn runs_that_fail_after_tracking_are_forgotten() {
+        fn race(run_id: Uuid) -> Result<(), std::io::Error> {
+            let _live = track_run(run_id, PathBuf::from("manifest.json"));
+            assert!(live_runs().contains(&run_id));
+            std::fs::read("/nonexistent/hydra/scoring")?;
+            Ok(())
+        }
+
+        let run_id = Uuid::new_v4();
+        assert!(race(run_id).is_err());
+        assert!(!live_runs().contains(&run_id));
+    }
+}
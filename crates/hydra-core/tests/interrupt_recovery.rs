@@ -61,6 +61,7 @@ fn long_running_command(cwd: PathBuf) -> BuiltCommand {
         args,
         env: vec![],
         cwd,
+        ..Default::default()
     }
 }
 
@@ -81,6 +82,7 @@ fn crash_command(cwd: PathBuf) -> BuiltCommand {
         args,
         env: vec![],
         cwd,
+        ..Default::default()
     }
 }
 
@@ -108,6 +110,7 @@ fn partial_output_then_sleep_command(cwd: PathBuf) -> BuiltCommand {
         args,
         env: vec![],
         cwd,
+        ..Default::default()
     }
 }
 
@@ -389,6 +392,7 @@ async fn simulated_ctrlc_full_cleanup() {
         ],
         env: vec![],
         cwd: wt_info.path.clone(),
+        ..Default::default()
     };
 
     let (tx, mut rx) = mpsc::channel(64);
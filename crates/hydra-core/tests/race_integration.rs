@@ -16,6 +16,7 @@ use hydra_core::scoring::baseline::{
 use hydra_core::scoring::build::score_build;
 use hydra_core::scoring::ranking::{rank_agents, AgentScore};
 use hydra_core::scoring::tests::score_tests;
+use hydra_core::security::SecretRedactor;
 use hydra_core::supervisor::{supervise, SupervisorEvent, SupervisorPolicy};
 use hydra_core::worktree::WorktreeService;
 use tempfile::TempDir;
@@ -75,18 +76,23 @@ async fn two_agents_run_concurrently_both_complete() {
         args: vec!["agent-a done".to_string()],
         env: vec![],
         cwd: wt1.path.clone(),
+        ..Default::default()
     };
     let cmd2 = hydra_core::adapter::BuiltCommand {
         program: "echo".to_string(),
         args: vec!["agent-b done".to_string()],
         env: vec![],
         cwd: wt2.path.clone(),
+        ..Default::default()
     };
 
     let policy = SupervisorPolicy {
         hard_timeout: Duration::from_secs(10),
         idle_timeout: Duration::from_secs(5),
         output_buffer_bytes: 1024,
+        sandbox: None,
+        container: None,
+        isolate_network: false,
     };
 
     let (tx1, mut rx1) = mpsc::channel(64);
@@ -139,18 +145,23 @@ async fn one_agent_failure_does_not_kill_other() {
         args: vec!["-c".to_string(), "sleep 0.1 && echo success".to_string()],
         env: vec![],
         cwd: wt_good.path.clone(),
+        ..Default::default()
     };
     let cmd_bad = hydra_core::adapter::BuiltCommand {
         program: "sh".to_string(),
         args: vec!["-c".to_string(), "exit 1".to_string()],
         env: vec![],
         cwd: wt_bad.path.clone(),
+        ..Default::default()
     };
 
     let policy = SupervisorPolicy {
         hard_timeout: Duration::from_secs(10),
         idle_timeout: Duration::from_secs(5),
         output_buffer_bytes: 1024,
+        sandbox: None,
+        container: None,
+        isolate_network: false,
     };
 
     let (tx_good, mut rx_good) = mpsc::channel(64);
@@ -313,7 +324,7 @@ async fn baseline_capture_and_persist_roundtrip() {
     assert_eq!(result.test.as_ref().unwrap().passed, 10);
 
     let baseline_path = tmp.path().join("baseline.json");
-    persist_baseline(&result, &baseline_path).unwrap();
+    persist_baseline(&result, &baseline_path, &SecretRedactor::new()).unwrap();
 
     let data = std::fs::read_to_string(&baseline_path).unwrap();
     let loaded: BaselineResult = serde_json::from_str(&data).unwrap();